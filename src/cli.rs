@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -59,13 +59,18 @@ pub enum Commands {
     /// Convert Arazzo workflow to test script
     Convert {
         /// Path to Arazzo file
-        #[arg(short, long)]
-        arazzo: PathBuf,
+        #[arg(short, long, required_unless_present = "list")]
+        arazzo: Option<PathBuf>,
 
-        /// Target format (k6)
+        /// Target format (k6, postman, hurl, curl, rust, jmeter, or an external
+        /// `hornet2-convert-<name>` converter on PATH)
         #[arg(short, long, default_value = "k6")]
         to: String,
 
+        /// List the available converters and exit
+        #[arg(long)]
+        list: bool,
+
         /// Output file (stdout if not specified)
         #[arg(short = 'O', long)]
         output: Option<PathBuf>,
@@ -89,15 +94,19 @@ pub enum Commands {
         /// Number of iterations (mutually exclusive with duration)
         #[arg(long)]
         iterations: Option<u32>,
+
+        /// Workflow mix file (JSON/YAML; overrides `x-load-profile` of the Arazzo file)
+        #[arg(long)]
+        profile: Option<PathBuf>,
     },
 
-    /// Run tests using an external engine
+    /// Run tests using an external engine (k6) or the built-in native engine
     Run {
         /// Path to Arazzo file
         #[arg(short, long)]
         arazzo: PathBuf,
 
-        /// Test engine to use (k6)
+        /// Test engine to use (k6, native)
         #[arg(short, long, default_value = "k6")]
         engine: String,
 
@@ -120,6 +129,275 @@ pub enum Commands {
         /// Number of iterations (mutually exclusive with duration)
         #[arg(long)]
         iterations: Option<u32>,
+
+        /// Workflow mix file (JSON/YAML; overrides `x-load-profile` of the
+        /// Arazzo file; k6 engine, all workflows unless --workflow is given)
+        #[arg(long)]
+        profile: Option<PathBuf>,
+
+        /// Workflow inputs as a JSON object, or @file with JSON/YAML
+        /// (native engine; merged over the input defaults)
+        #[arg(long)]
+        inputs: Option<String>,
+
+        /// Also send requests breaking one OpenAPI constraint each and expect
+        /// a declared 4xx (native engine)
+        #[arg(long)]
+        negative: bool,
+
+        /// Write the test cases to a report file (JUnit XML for .xml, JSON
+        /// otherwise; native engine)
+        #[arg(long)]
+        report: Option<PathBuf>,
+
+        /// Record every request/response exchange to a cassette (YAML, or
+        /// JSON for .json; native engine)
+        #[arg(long, conflicts_with = "replay")]
+        record: Option<PathBuf>,
+
+        /// Answer requests from a recorded cassette instead of the network
+        /// (native engine)
+        #[arg(long)]
+        replay: Option<PathBuf>,
+
+        /// Body matching on replay: none, exact, or comma-separated JSON
+        /// Pointers compared between the recorded and the actual body
+        #[arg(long, requires = "replay")]
+        match_body: Option<String>,
+
+        /// Additional header, query parameter or property name to redact
+        /// when recording (repeatable)
+        #[arg(long, requires = "record")]
+        redact: Vec<String>,
+
+        #[command(flatten)]
+        baseline: BaselineFlags,
+
+        /// Add the run to the project's history under .hornet/runs, shown by
+        /// the web UI (native engine)
+        #[arg(long)]
+        history: bool,
+
+        /// Environment name stored with the run in the history
+        #[arg(long, requires = "history")]
+        environment: Option<String>,
+    },
+
+    /// Load test a workflow with the native engine (closed VUs or open arrival rates)
+    Load {
+        /// Path to Arazzo file
+        #[arg(short, long)]
+        arazzo: PathBuf,
+
+        /// Specific workflow ID to run (first workflow if not specified)
+        #[arg(short, long)]
+        workflow: Option<String>,
+
+        /// Base URL override for API requests
+        #[arg(long)]
+        base_url: Option<String>,
+
+        /// Number of VUs looping over the workflow (closed model)
+        #[arg(long)]
+        vus: Option<u32>,
+
+        /// Duration of the test (e.g., "30s", "5m")
+        #[arg(long)]
+        duration: Option<String>,
+
+        /// Iterations shared by all VUs (closed model)
+        #[arg(long)]
+        iterations: Option<u64>,
+
+        /// Iterations started per second (open model; start rate with --stages)
+        #[arg(long)]
+        rate: Option<f64>,
+
+        /// Ramp the arrival rate through stages (e.g., "30s:10,1m:50,10s:0")
+        #[arg(long)]
+        stages: Option<String>,
+
+        /// Maximum concurrent iterations of the open model
+        #[arg(long)]
+        max_vus: Option<u32>,
+
+        /// Workflow mix file (JSON/YAML; overrides `x-load-profile` of the Arazzo file)
+        #[arg(long)]
+        profile: Option<PathBuf>,
+
+        /// Search the highest rate meeting the steps' `x-slo` (step, binary);
+        /// --rate is the first rate and --duration the time spent at each rate
+        #[arg(long)]
+        search: Option<String>,
+
+        /// Highest rate tried by --search
+        #[arg(long)]
+        max_rate: Option<f64>,
+
+        /// Rate increment of --search step, resolution of --search binary
+        #[arg(long)]
+        rate_step: Option<f64>,
+
+        /// Spread the load over this many `hornet2 agent` processes
+        #[arg(long)]
+        agents: Option<usize>,
+
+        /// Address the coordinator listens on for agents (with --agents)
+        #[arg(long, default_value = "0.0.0.0:7420")]
+        listen: String,
+
+        /// Timeout of a single request (e.g., "10s")
+        #[arg(long)]
+        timeout: Option<String>,
+
+        /// Write the summary as JSON
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        #[command(flatten)]
+        baseline: BaselineFlags,
+    },
+
+    /// Generate load for a coordinator (`hornet2 load --agents`)
+    Agent {
+        /// Coordinator address (host:port)
+        #[arg(short, long)]
+        coordinator: String,
+
+        /// Name reported to the coordinator (host name if not specified)
+        #[arg(long)]
+        name: Option<String>,
+    },
+
+    /// Import a Postman collection or HAR file as Arazzo workflows
+    Import {
+        /// Path to the Postman collection or HAR file
+        #[arg(short, long)]
+        input: PathBuf,
+
+        /// Input format (postman, har)
+        #[arg(short, long)]
+        from: String,
+
+        /// OpenAPI file to map requests onto (repeatable; added to sourceDescriptions)
+        #[arg(long)]
+        openapi: Vec<PathBuf>,
+
+        /// Arazzo file to write (workflows are appended if it exists)
+        #[arg(short = 'O', long)]
+        output: PathBuf,
+    },
+
+    /// Record traffic through a local proxy and write it as an Arazzo workflow on Ctrl-C
+    Record {
+        /// OpenAPI file to map requests onto (repeatable; added to sourceDescriptions)
+        #[arg(long)]
+        openapi: Vec<PathBuf>,
+
+        /// Arazzo file to write (the workflow is appended if it exists)
+        #[arg(short = 'O', long, default_value = "arazzo.yaml")]
+        output: PathBuf,
+
+        /// Upstream URL to forward to as a reverse proxy (forward proxy if omitted)
+        #[arg(long)]
+        target: Option<String>,
+
+        /// Port number
+        #[arg(short, long, default_value = "8888")]
+        port: u16,
+
+        /// Name of the recorded workflow
+        #[arg(long, default_value = "recorded")]
+        name: String,
+    },
+
+    /// Generate Arazzo workflows (CRUD lifecycles, OpenAPI links) from OpenAPI documents
+    Generate {
+        /// OpenAPI file to generate from (repeatable; added to sourceDescriptions).
+        /// The sourceDescriptions of an existing output file are always used
+        #[arg(long)]
+        openapi: Vec<PathBuf>,
+
+        /// Arazzo file to write (generated workflows are merged into it if it exists)
+        #[arg(short = 'O', long, default_value = "arazzo.yaml")]
+        output: PathBuf,
+
+        /// Replace the output file instead of merging into it
+        #[arg(long)]
+        overwrite: bool,
+
+        /// Print the resulting Arazzo document instead of writing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Synthesize a sample request for an operation from its schemas
+    Sample {
+        /// OpenAPI file containing the operation (repeatable)
+        #[arg(long, required = true)]
+        openapi: Vec<PathBuf>,
+
+        /// operationId of the operation
+        #[arg(long)]
+        operation: String,
+
+        /// Which instance to build
+        #[arg(long, default_value = "minimal")]
+        mode: SampleModeArg,
+
+        /// Seed of the generator (same seed, same sample)
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+
+    /// Serve mock responses for every operation of OpenAPI documents
+    Mock {
+        /// OpenAPI file to mock (repeatable)
+        #[arg(long)]
+        openapi: Vec<PathBuf>,
+
+        /// Arazzo file whose sourceDescriptions are mocked
+        #[arg(short, long, required_unless_present = "openapi")]
+        arazzo: Option<PathBuf>,
+
+        /// Port number
+        #[arg(short, long, default_value = "4010")]
+        port: u16,
+
+        /// Keep resources created through CRUD operations in memory
+        #[arg(long)]
+        stateful: bool,
+    },
+
+    /// Fuzz the operation steps of workflows with schema-based request variants
+    Fuzz {
+        /// Path to Arazzo file
+        #[arg(short, long)]
+        arazzo: PathBuf,
+
+        /// Specific workflow ID to fuzz (all if not specified)
+        #[arg(short, long)]
+        workflow: Option<String>,
+
+        /// Base URL override for API requests
+        #[arg(long)]
+        base_url: Option<String>,
+
+        /// Number of request variants per step
+        #[arg(long, default_value_t = 20)]
+        variants: usize,
+
+        /// Seed of the generator (same seed, same variants)
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+
+        /// Timeout of a single request (e.g., "10s", "500ms")
+        #[arg(long, default_value = "10s")]
+        timeout: String,
+
+        /// Arazzo file the reproducers of the findings are written to
+        #[arg(short = 'O', long, default_value = "fuzz-findings.arazzo.yaml")]
+        output: PathBuf,
     },
 
     /// Export Hornet2 API specification in OpenAPI format
@@ -145,6 +423,28 @@ pub enum Commands {
     },
 }
 
+/// Saving and comparing baselines (`run` and `load`)
+#[derive(Args)]
+pub struct BaselineFlags {
+    /// Compare the run against metrics saved with --save-baseline and fail
+    /// on regressions
+    #[arg(long)]
+    pub baseline: Option<PathBuf>,
+
+    /// Save the metrics of the run as a baseline
+    #[arg(long)]
+    pub save_baseline: Option<PathBuf>,
+
+    /// Allowed change per step (e.g., "p95=10%,throughput=10%,errorRate=1%";
+    /// "off" disables a metric)
+    #[arg(long, requires = "baseline")]
+    pub tolerance: Option<String>,
+
+    /// Write the comparison (HTML for .html, JSON otherwise)
+    #[arg(long, requires = "baseline")]
+    pub diff_output: Option<PathBuf>,
+}
+
 #[derive(Clone, ValueEnum)]
 pub enum OutputFormat {
     /// GraphViz DOT format
@@ -162,3 +462,13 @@ pub enum ExportFormat {
     /// JSON format
     Json,
 }
+
+#[derive(Clone, ValueEnum)]
+pub enum SampleModeArg {
+    /// Required properties only, lower bounds
+    Minimal,
+    /// Every property, upper bounds
+    Maximal,
+    /// Random instance within the schema constraints
+    Random,
+}
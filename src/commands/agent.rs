@@ -0,0 +1,53 @@
+//! 負荷試験 agent コマンドの実装
+//!
+//! コーディネーター（`hornet2 load --agents`）に TCP で接続し、配られた
+//! ワークフローと負荷の割り当てを native エンジンで実行して、1秒ごとの
+//! メトリクスの差分を送り返す。
+
+use super::load::describe;
+use crate::error::Result;
+use crate::runner::load::run_agent;
+use colored::Colorize;
+
+/// `execute_agent` に渡す引数をまとめた構造体
+pub struct AgentCommandArgs<'a> {
+    /// コーディネーターのアドレス（`host:port`）
+    pub coordinator: &'a str,
+    /// コーディネーターに名乗る名前（なければホスト名）
+    pub name: Option<&'a str>,
+}
+
+/// agent コマンドを実行する
+pub async fn execute_agent(args: AgentCommandArgs<'_>) -> Result<()> {
+    let name = args.name.map(str::to_string).unwrap_or_else(default_name);
+    println!(
+        "{} Agent '{}' connecting to {}",
+        "→".blue(),
+        name,
+        args.coordinator
+    );
+
+    // ペイロードのファイル参照はカレントディレクトリから解決する
+    let summary = run_agent(args.coordinator, &name, std::env::current_dir()?, |model| {
+        println!("{} Running {}", "→".blue(), describe(model));
+    })
+    .await?;
+
+    println!(
+        "{} {} iteration(s), {} request(s), {:.2}% errors, p95 {:.2}ms",
+        "✓".green(),
+        summary.iterations,
+        summary.requests,
+        summary.error_rate * 100.0,
+        summary.latency.p95
+    );
+    Ok(())
+}
+
+/// ホスト名、取れなければプロセス ID から名前を作る
+fn default_name() -> String {
+    std::env::var("HOSTNAME")
+        .ok()
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| format!("agent-{}", std::process::id()))
+}
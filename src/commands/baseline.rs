@@ -0,0 +1,247 @@
+//! ベースラインとの比較（`run` と `load` で共通）
+//!
+//! `--save-baseline` で実行のメトリクスを保存し、`--baseline` で保存済みの
+//! メトリクスとステップごとに比べる。p95、スループット、エラー率のいずれかが
+//! 許容範囲を超えて悪化したらエラーを返し、コマンドを非ゼロで終了させる。
+//! 1回だけ実行する native の `run` では、スループットは比べない。
+
+use crate::error::{HornetError, Result};
+use crate::runner::RunMetrics;
+use crate::runner::baseline::{BaselineDiff, DiffStatus, Format, Tolerance, describe_tolerance};
+use colored::Colorize;
+use std::path::Path;
+
+/// ベースラインに関する指定
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BaselineArgs<'a> {
+    /// 比べる相手のメトリクス
+    pub baseline_path: Option<&'a Path>,
+    /// 今回のメトリクスの保存先
+    pub save_path: Option<&'a Path>,
+    /// 許容範囲（`p95=10%,throughput=10%,errorRate=1%`）
+    pub tolerance: Option<&'a str>,
+    /// 比較結果の出力先（`.html` なら HTML、それ以外は JSON）
+    pub diff_path: Option<&'a Path>,
+}
+
+impl BaselineArgs<'_> {
+    /// 実行前に指定を確かめ、許容範囲を読む
+    pub(super) fn tolerance(&self) -> Result<Tolerance> {
+        if self.baseline_path.is_none() && (self.tolerance.is_some() || self.diff_path.is_some()) {
+            return Err(HornetError::ValidationError(
+                "--tolerance and --diff-output require --baseline".to_string(),
+            ));
+        }
+        match self.tolerance {
+            Some(tolerance) => tolerance.parse(),
+            None => Ok(Tolerance::default()),
+        }
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.baseline_path.is_none() && self.save_path.is_none()
+    }
+
+    /// メトリクスをベースラインと比べ、保存する
+    ///
+    /// 悪化していればエラーを返す（`--save-baseline` の保存は先に済ませる）。
+    pub(super) fn apply(&self, metrics: &RunMetrics) -> Result<()> {
+        self.apply_with(metrics, self.tolerance()?)
+    }
+
+    /// 1回だけの機能テストのメトリクスをベースラインと比べ、保存する
+    ///
+    /// 1回の実行の req/s はレイテンシの裏返しでしかないので、スループットは
+    /// `--tolerance` の指定にかかわらず比べない。
+    pub(super) fn apply_functional(&self, metrics: &RunMetrics) -> Result<()> {
+        let tolerance = Tolerance {
+            throughput: None,
+            ..self.tolerance()?
+        };
+        self.apply_with(metrics, tolerance)
+    }
+
+    fn apply_with(&self, metrics: &RunMetrics, tolerance: Tolerance) -> Result<()> {
+        let diff = match self.baseline_path {
+            Some(path) => {
+                let baseline = RunMetrics::load(path).map_err(|e| {
+                    HornetError::ValidationError(format!(
+                        "Failed to read baseline {}: {}",
+                        path.display(),
+                        e
+                    ))
+                })?;
+                let diff = BaselineDiff::compare(&baseline, metrics, tolerance);
+                println!(
+                    "\n{} {} ({})",
+                    "Baseline:".bold(),
+                    path.display(),
+                    describe_tolerance(&tolerance)
+                );
+                print_diff(&diff);
+                if let Some(path) = self.diff_path {
+                    diff.write(path)?;
+                    println!("{} Diff written to {}", "✓".green(), path.display());
+                }
+                Some(diff)
+            }
+            None => None,
+        };
+
+        if let Some(path) = self.save_path {
+            metrics.save(path)?;
+            println!("{} Baseline saved to {}", "✓".green(), path.display());
+        }
+
+        match diff {
+            Some(diff) if diff.regressed() => {
+                let names: Vec<String> = diff.regressions().map(|r| r.name()).collect();
+                Err(HornetError::ValidationError(format!(
+                    "Regressed against the baseline: {}",
+                    names.join(", ")
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// 比較結果の表を表示する
+fn print_diff(diff: &BaselineDiff) {
+    println!(
+        "{}",
+        format!(
+            "  {:<32} {:>9} {:>9} {:>8} {:>9} {:>9} {:>8} {:>8} {:>8} {:>9}  {}",
+            "step",
+            "p95 base",
+            "p95 now",
+            "Δ",
+            "rps base",
+            "rps now",
+            "Δ",
+            "err base",
+            "err now",
+            "Δ",
+            "status"
+        )
+        .dimmed()
+    );
+    for row in &diff.rows {
+        let mut line = format!("  {:<32}", row.name());
+        let cells = [
+            (row.p95_ms, Format::Millis, 9, 8),
+            (row.throughput, Format::Rate, 9, 8),
+            (row.error_rate, Format::Percent, 8, 9),
+        ];
+        for (metric, format, width, delta_width) in cells {
+            match metric {
+                Some(metric) => {
+                    let delta = format!("{:>delta_width$}", format.delta(&metric));
+                    line.push_str(&format!(
+                        " {:>width$} {:>width$} {}",
+                        format.value(metric.baseline),
+                        format.value(metric.current),
+                        if metric.regressed {
+                            delta.red().to_string()
+                        } else {
+                            delta
+                        }
+                    ));
+                }
+                None => line.push_str(&format!(
+                    " {:>width$} {:>width$} {:>delta_width$}",
+                    "-", "-", "-"
+                )),
+            }
+        }
+        let status = match row.status {
+            DiffStatus::Ok => "ok".green(),
+            DiffStatus::Regressed => "regressed".red().bold(),
+            DiffStatus::New => "new".dimmed(),
+            DiffStatus::Missing => "missing".yellow(),
+        };
+        println!("{}  {}", line, status);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{LatencySummary, StepRunMetrics};
+
+    fn metrics(p95: f64) -> RunMetrics {
+        RunMetrics {
+            latency: LatencySummary {
+                p95,
+                ..Default::default()
+            },
+            rps: 10.0,
+            steps: vec![StepRunMetrics {
+                workflow_id: "flow".to_string(),
+                step_id: "get".to_string(),
+                requests: 10,
+                errors: 0,
+                rps: 10.0,
+                error_rate: 0.0,
+                latency: LatencySummary {
+                    p95,
+                    ..Default::default()
+                },
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_apply() {
+        let dir = tempfile::tempdir().unwrap();
+        let baseline = dir.path().join("baseline.json");
+        let diff = dir.path().join("diff.json");
+
+        // 保存したメトリクスと同じなら通る
+        BaselineArgs {
+            save_path: Some(&baseline),
+            ..Default::default()
+        }
+        .apply(&metrics(100.0))
+        .unwrap();
+        let args = BaselineArgs {
+            baseline_path: Some(&baseline),
+            diff_path: Some(&diff),
+            ..Default::default()
+        };
+        args.apply(&metrics(105.0)).unwrap();
+        let written: BaselineDiff =
+            serde_json::from_str(&std::fs::read_to_string(&diff).unwrap()).unwrap();
+        assert!(!written.regressed());
+
+        // 異常系: p95 が許容範囲を超えて悪化
+        let error = args.apply(&metrics(150.0)).unwrap_err();
+        assert!(error.to_string().contains("flow/get, (total)"), "{error}");
+        // 許容範囲を広げれば通る
+        BaselineArgs {
+            tolerance: Some("p95=60%"),
+            ..args
+        }
+        .apply(&metrics(150.0))
+        .unwrap();
+
+        // 異常系: ベースラインなしの許容範囲、読めないベースライン
+        assert!(
+            BaselineArgs {
+                tolerance: Some("p95=5%"),
+                ..Default::default()
+            }
+            .tolerance()
+            .is_err()
+        );
+        assert!(
+            BaselineArgs {
+                baseline_path: Some(&dir.path().join("missing.json")),
+                ..Default::default()
+            }
+            .apply(&metrics(100.0))
+            .is_err()
+        );
+    }
+}
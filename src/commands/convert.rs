@@ -2,12 +2,25 @@
 //!
 //! Arazzoワークフローを各種テストスクリプト形式へ変換する。
 
-use crate::converters::{ConvertOptions, Converter, K6Converter};
-use crate::error::Result;
-use crate::loader::{SourceDescriptionResolver, arazzo::load_arazzo};
+use super::baseline::BaselineArgs;
+use crate::converters::common::{find_workflow, resolve_base_url};
+use crate::converters::registry::ConverterSource;
+use crate::converters::{ConvertInput, ConvertOptions, Converter, ConverterRegistry, K6Converter};
+use crate::error::{HornetError, Result};
+use crate::fuzz::{FuzzOptions, Fuzzer};
+use crate::loader::{OpenApiResolver, SourceDescriptionResolver, arazzo::load_arazzo};
+use crate::models::arazzo::Workflow;
+use crate::models::profile::LoadProfile;
+use crate::runner::cassette::{BodyMatcher, Cassette, CassetteMode, Recorder, Redactor, Replayer};
+use crate::runner::history::{RunHistory, RunRecord, git_commit};
+use crate::runner::native::{NativeExecutor, NativeOptions, WorkflowPlan};
+use crate::runner::report::{CaseStatus, RunReport, TestCase};
 use colored::Colorize;
+use serde_json::Value;
 use std::fs;
-use std::path::Path;
+use std::path::{Component, Path};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 
 /// `execute_convert` に渡す引数をまとめた構造体
 pub struct ConvertCommandArgs<'a> {
@@ -19,6 +32,8 @@ pub struct ConvertCommandArgs<'a> {
     pub vus: Option<u32>,
     pub duration: Option<&'a str>,
     pub iterations: Option<u32>,
+    /// ワークフローの配分を定めるプロファイル（`x-load-profile` より優先）
+    pub profile_path: Option<&'a Path>,
 }
 
 /// `execute_run` に渡す引数をまとめた構造体
@@ -30,6 +45,28 @@ pub struct RunCommandArgs<'a> {
     pub vus: Option<u32>,
     pub duration: Option<&'a str>,
     pub iterations: Option<u32>,
+    /// ワークフローの配分を定めるプロファイル（`x-load-profile` より優先、k6 エンジンのみ）
+    pub profile_path: Option<&'a Path>,
+    /// ワークフローの入力（JSON か `@ファイル`、native エンジンのみ）
+    pub inputs: Option<&'a str>,
+    /// 制約違反のリクエストも送る（native エンジンのみ）
+    pub negative: bool,
+    /// テストケースのレポートの出力先（native エンジンのみ）
+    pub report_path: Option<&'a Path>,
+    /// 交換を記録するカセットの出力先（native エンジンのみ）
+    pub record_path: Option<&'a Path>,
+    /// 応答を再生するカセット（native エンジンのみ）
+    pub replay_path: Option<&'a Path>,
+    /// 再生時のボディの照合（`none`、`exact` またはカンマ区切りの JSON Pointer）
+    pub match_body: Option<&'a str>,
+    /// 記録時に既定に加えて秘匿するヘッダー、クエリ、プロパティの名前
+    pub redact: &'a [String],
+    /// ベースラインの保存と比較
+    pub baseline: BaselineArgs<'a>,
+    /// 実行をプロジェクトの履歴（`.hornet/runs`）に残す（native エンジンのみ）
+    pub history: bool,
+    /// 履歴に残す環境の名前
+    pub environment: Option<&'a str>,
 }
 
 /// 変換コマンドを実行する
@@ -43,6 +80,7 @@ pub fn execute_convert(args: ConvertCommandArgs<'_>) -> Result<()> {
         vus,
         duration,
         iterations,
+        profile_path,
     } = args;
 
     // Arazzoファイルを読み込む
@@ -83,58 +121,100 @@ pub fn execute_convert(args: ConvertCommandArgs<'_>) -> Result<()> {
         vus,
         duration: duration.map(|s| s.to_string()),
         iterations,
+        profile: profile_path.map(LoadProfile::load).transpose()?,
     };
 
-    // ターゲットに応じてスクリプトを生成する
-    let script = match target.to_lowercase().as_str() {
-        "k6" => {
-            let converter = K6Converter::new();
-
-            if let Some(wf_id) = workflow_id {
-                // 特定のワークフローのみ変換
-                let workflow = arazzo
-                    .workflows
-                    .iter()
-                    .find(|w| w.workflow_id == wf_id)
-                    .ok_or_else(|| {
-                        crate::error::HornetError::ValidationError(format!(
-                            "Workflow '{}' not found",
-                            wf_id
-                        ))
-                    })?;
+    // ターゲットに応じてスクリプトを生成する（組み込み + PATH 上の外部コンバータ）
+    let registry = ConverterRegistry::discover();
+    let converter = registry.get(target).ok_or_else(|| {
+        HornetError::ValidationError(format!(
+            "Unsupported target format: {}. Supported: {}",
+            target,
+            registry.names().join(", ")
+        ))
+    })?;
+    let files = converter.convert(&ConvertInput {
+        arazzo: &arazzo,
+        resolver: &source_result.resolver,
+        options: &options,
+        workflow_id,
+    })?;
 
-                converter.convert_workflow(workflow, &source_result.resolver, &options)?
-            } else {
-                // 全ワークフローを変換
-                converter.convert_spec(&arazzo, &source_result.resolver, &options)?
+    // 生成結果を出力する
+    match (output_path, files.as_slice()) {
+        (Some(path), [file]) => {
+            fs::write(path, &file.content)?;
+            println!(
+                "{} Generated {} script: {}",
+                "✓".green(),
+                target,
+                path.display()
+            );
+        }
+        (None, [file]) => println!("\n{}", file.content),
+        // 複数ファイルの場合は --output をディレクトリとして扱う
+        (Some(dir), files) => {
+            for file in files {
+                let path = dir.join(safe_relative_path(&file.path)?);
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&path, &file.content)?;
+                println!("{} Generated {}", "✓".green(), path.display());
             }
         }
-        _ => {
-            return Err(crate::error::HornetError::ValidationError(format!(
-                "Unsupported target format: {}. Supported: k6",
-                target
+        (None, files) => {
+            return Err(HornetError::ValidationError(format!(
+                "Converter '{}' generated {} files; use --output to specify a directory",
+                target,
+                files.len()
             )));
         }
-    };
+    }
 
-    // 生成結果を出力する
-    if let Some(path) = output_path {
-        fs::write(path, &script)?;
+    Ok(())
+}
+
+/// 変換器が返したパスを出力ディレクトリ配下の相対パスとして検証する
+fn safe_relative_path(path: &str) -> Result<&Path> {
+    let relative = Path::new(path);
+    let escapes = relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+    if path.is_empty() || escapes {
+        return Err(HornetError::InvalidPath(format!(
+            "Generated file path must be relative to the output directory: {}",
+            path
+        )));
+    }
+    Ok(relative)
+}
+
+/// 利用可能なコンバータを一覧表示する
+pub fn execute_list_converters() -> Result<()> {
+    let registry = ConverterRegistry::discover();
+    let width = registry.names().iter().map(|n| n.len()).max().unwrap_or(0);
+
+    println!("{}", "Available converters:".bold());
+    for info in registry.list() {
+        let source = match info.source {
+            ConverterSource::Builtin => "built-in".dimmed(),
+            ConverterSource::External(_) => "external".cyan(),
+        };
         println!(
-            "{} Generated {} script: {}",
-            "✓".green(),
-            target,
-            path.display()
+            "  {:width$}  {} [{}]",
+            info.name,
+            info.description,
+            source,
+            width = width
         );
-    } else {
-        println!("\n{}", script);
     }
 
     Ok(())
 }
 
 /// 変換と実行をまとめて行う `run` コマンドを実行する
-pub fn execute_run(args: RunCommandArgs<'_>) -> Result<()> {
+pub async fn execute_run(args: RunCommandArgs<'_>) -> Result<()> {
     let RunCommandArgs {
         arazzo_path,
         engine,
@@ -143,8 +223,52 @@ pub fn execute_run(args: RunCommandArgs<'_>) -> Result<()> {
         vus,
         duration,
         iterations,
+        profile_path,
+        inputs,
+        negative,
+        report_path,
+        record_path,
+        replay_path,
+        match_body,
+        redact,
+        baseline,
+        history,
+        environment,
     } = args;
 
+    let native = engine.eq_ignore_ascii_case("native");
+    if !native
+        && (negative
+            || inputs.is_some()
+            || report_path.is_some()
+            || record_path.is_some()
+            || replay_path.is_some()
+            || history)
+    {
+        return Err(HornetError::ValidationError(
+            "--negative, --inputs, --report, --record, --replay and --history require --engine native"
+                .to_string(),
+        ));
+    }
+    if native && profile_path.is_some() {
+        return Err(HornetError::ValidationError(
+            "--profile requires --engine k6 (use `hornet2 load --profile` for native load tests)"
+                .to_string(),
+        ));
+    }
+    if environment.is_some() && !history {
+        return Err(HornetError::ValidationError(
+            "--environment requires --history".to_string(),
+        ));
+    }
+    if record_path.is_some() && replay_path.is_some() {
+        return Err(HornetError::ValidationError(
+            "--record and --replay cannot be used together".to_string(),
+        ));
+    }
+    let matcher: BodyMatcher = match_body.unwrap_or("none").parse()?;
+    baseline.tolerance()?;
+
     use crate::runner::{K6Runner, Runner};
 
     // まずスクリプトを生成する
@@ -170,14 +294,106 @@ pub fn execute_run(args: RunCommandArgs<'_>) -> Result<()> {
         ));
     }
 
+    // 配分は k6 の起動前に確かめる
+    let profile = LoadProfile::resolve(profile_path, &arazzo)?;
     let options = ConvertOptions {
         base_url: base_url.map(|s| s.to_string()),
         vus,
         duration: duration.map(|s| s.to_string()),
         iterations,
+        profile,
     };
 
     match engine.to_lowercase().as_str() {
+        "native" => {
+            if vus.is_some() || duration.is_some() || iterations.is_some() {
+                eprintln!(
+                    "{} --vus/--duration/--iterations are ignored by the native engine",
+                    "⚠".yellow()
+                );
+            }
+            let workflow = match workflow_id {
+                Some(id) => find_workflow(&arazzo, id)?,
+                None => arazzo.workflows.first().ok_or_else(|| {
+                    HornetError::ValidationError("No workflow to run".to_string())
+                })?,
+            };
+            let inputs = parse_inputs(inputs)?;
+            check_required_inputs(workflow, &inputs)?;
+            let base_dir = arazzo_path
+                .parent()
+                .filter(|p| !p.as_os_str().is_empty())
+                .unwrap_or(Path::new("."));
+            let base_url = resolve_base_url(&source_result.resolver, &options);
+            let mut executor = NativeExecutor::new(NativeOptions {
+                base_url: base_url.clone(),
+                timeout: NATIVE_TIMEOUT,
+                base_dir: base_dir.to_path_buf(),
+            })?;
+
+            // カセットへの記録、またはカセットからの再生
+            let recorder =
+                record_path.map(|_| Arc::new(Recorder::new(Redactor::with_names(redact))));
+            if let Some(ref recorder) = recorder {
+                executor = executor.with_cassette(CassetteMode::Record(recorder.clone()));
+            }
+            if let Some(path) = replay_path {
+                let cassette = Cassette::load(path)?;
+                println!(
+                    "{} Replaying {} interaction(s) from {}",
+                    "→".blue(),
+                    cassette.interactions.len(),
+                    path.display()
+                );
+                executor = executor.with_cassette(CassetteMode::Replay(Arc::new(Replayer::new(
+                    cassette, matcher,
+                ))));
+            }
+
+            println!("{} Running tests with the native engine...\n", "→".blue());
+            let report = run_native(
+                &executor,
+                &source_result.resolver,
+                workflow,
+                &inputs,
+                negative,
+            )
+            .await;
+            // 失敗したランの交換も残す
+            if let (Some(recorder), Some(path)) = (recorder, record_path) {
+                let cassette = recorder.cassette();
+                cassette.save(path)?;
+                println!(
+                    "{} Recorded {} interaction(s) to {}",
+                    "✓".green(),
+                    cassette.interactions.len(),
+                    path.display()
+                );
+            }
+            let (report, mut record) = report?;
+            print_report(&report);
+
+            // 成否にかかわらず履歴には残す
+            if history {
+                record.environment = environment.map(str::to_string);
+                record.base_url = Some(base_url);
+                record.git_commit = git_commit(base_dir);
+                RunHistory::new(base_dir).save(&record)?;
+                println!("{} Run {} added to the history", "✓".green(), record.id);
+            }
+
+            if let Some(path) = report_path {
+                report.write(path)?;
+                println!("{} Report written to {}", "✓".green(), path.display());
+            }
+            if !report.success() {
+                return Err(HornetError::ValidationError(format!(
+                    "{} test case(s) failed",
+                    report.count(CaseStatus::Failed)
+                )));
+            }
+            baseline.apply_functional(&record.metrics)?;
+        }
         "k6" => {
             let converter = K6Converter::new();
             let runner = K6Runner::new();
@@ -204,6 +420,9 @@ pub fn execute_run(args: RunCommandArgs<'_>) -> Result<()> {
                         ))
                     })?;
                 converter.convert_workflow(workflow, &source_result.resolver, &options)?
+            } else if options.profile.is_some() {
+                // 配分があれば全ワークフローを混ぜる
+                converter.convert_spec(&arazzo, &source_result.resolver, &options)?
             } else {
                 // ワークフロー未指定時は先頭を使用
                 converter.convert_workflow(
@@ -237,11 +456,12 @@ pub fn execute_run(args: RunCommandArgs<'_>) -> Result<()> {
                 );
             }
 
-            if let Some(metrics) = result.metrics {
+            if let Some(ref metrics) = result.metrics {
                 println!("\n{}", "Metrics Summary:".bold());
                 println!("  HTTP Requests: {}", metrics.http_reqs);
                 println!("  Iterations: {}", metrics.iterations);
                 println!("  Avg Response Time: {:.2}ms", metrics.avg_response_time_ms);
+                println!("  p95 Response Time: {:.2}ms", metrics.latency.p95);
                 println!(
                     "  Checks: {} passed, {} failed",
                     metrics.checks_passed, metrics.checks_failed
@@ -254,10 +474,20 @@ pub fn execute_run(args: RunCommandArgs<'_>) -> Result<()> {
                     result.exit_code
                 )));
             }
+            // k6 の出力からは全体のメトリクスしか取れないので、比較は合計の行だけになる
+            if !baseline.is_empty() {
+                let metrics = result.metrics.ok_or_else(|| {
+                    HornetError::ValidationError(
+                        "No metrics found in the k6 output to compare with the baseline"
+                            .to_string(),
+                    )
+                })?;
+                baseline.apply(&metrics)?;
+            }
         }
         _ => {
             return Err(crate::error::HornetError::ValidationError(format!(
-                "Unsupported engine: {}. Supported: k6",
+                "Unsupported engine: {}. Supported: k6, native",
                 engine
             )));
         }
@@ -265,3 +495,139 @@ pub fn execute_run(args: RunCommandArgs<'_>) -> Result<()> {
 
     Ok(())
 }
+
+/// native エンジンで1リクエストに許す時間
+const NATIVE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// `--inputs` の値（JSON のオブジェクトか、`@` に続けた JSON/YAML ファイル）
+fn parse_inputs(arg: Option<&str>) -> Result<Value> {
+    let Some(arg) = arg else {
+        return Ok(Value::Null);
+    };
+    let parsed = match arg.strip_prefix('@') {
+        Some(path) => {
+            let content = fs::read_to_string(path)?;
+            serde_yaml::from_str::<Value>(&content).map_err(|e| e.to_string())
+        }
+        None => serde_json::from_str::<Value>(arg).map_err(|e| e.to_string()),
+    };
+    match parsed {
+        Ok(inputs @ Value::Object(_)) => Ok(inputs),
+        Ok(_) => Err(HornetError::ValidationError(
+            "--inputs must be a JSON object".to_string(),
+        )),
+        Err(e) => Err(HornetError::ValidationError(format!(
+            "Invalid --inputs: {}",
+            e
+        ))),
+    }
+}
+
+/// 既定値のない必須の入力が渡されているか確かめる
+fn check_required_inputs(workflow: &Workflow, inputs: &Value) -> Result<()> {
+    let Some(schema) = workflow.inputs.as_ref() else {
+        return Ok(());
+    };
+    let missing: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .filter(|name| {
+            inputs.get(name).is_none()
+                && schema
+                    .pointer(&format!("/properties/{}/default", name))
+                    .is_none()
+        })
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    Err(HornetError::ValidationError(format!(
+        "Workflow '{}' requires input(s) {}; pass them with --inputs",
+        workflow.workflow_id,
+        missing.join(", ")
+    )))
+}
+
+/// ワークフローを実行し、ステップごと（と否定テストごと）のテストケースにまとめる
+///
+/// 履歴の記録とメトリクスはワークフローの1回の実行（否定テストを除く）から作る。
+async fn run_native(
+    executor: &NativeExecutor,
+    resolver: &OpenApiResolver,
+    workflow: &Workflow,
+    inputs: &Value,
+    negative: bool,
+) -> Result<(RunReport, RunRecord)> {
+    let plan = WorkflowPlan::new(workflow, resolver)?;
+    let started_at = SystemTime::now();
+    let started = Instant::now();
+    let run = executor.run(&plan, inputs).await;
+    let record = RunRecord::new(&run, started_at, started.elapsed());
+
+    let mut report = RunReport::default();
+    for step in &run.steps {
+        let elapsed = step
+            .response
+            .as_ref()
+            .map(|r| r.elapsed)
+            .unwrap_or_default();
+        let case = if step.passed() {
+            TestCase::passed(&run.workflow_id, &step.step_id, elapsed)
+        } else {
+            let mut reasons: Vec<String> = step.error.iter().map(|e| e.message.clone()).collect();
+            reasons.extend(step.failures.iter().cloned());
+            TestCase::failed(&run.workflow_id, &step.step_id, elapsed, reasons.join("; "))
+        };
+        report.cases.push(case);
+    }
+    // ステップ以外の理由（goto 先がないなど）で失敗した場合
+    if let Some(ref failure) = run.failure
+        && run.steps.iter().all(|s| s.passed())
+    {
+        report.cases.push(TestCase::failed(
+            &run.workflow_id,
+            "workflow",
+            Duration::ZERO,
+            failure,
+        ));
+    }
+
+    if negative {
+        let fuzzer = Fuzzer::new(executor, resolver, FuzzOptions::default());
+        report
+            .cases
+            .extend(fuzzer.negative_workflow(workflow).await?);
+    }
+
+    Ok((report, record))
+}
+
+/// テストケースの結果を表示する
+fn print_report(report: &RunReport) {
+    for case in &report.cases {
+        match case.status {
+            CaseStatus::Passed => println!("  {} {}", "✓".green(), case.name),
+            CaseStatus::Failed => println!(
+                "  {} {}: {}",
+                "✗".red(),
+                case.name,
+                case.message.as_deref().unwrap_or_default()
+            ),
+            CaseStatus::Skipped => println!(
+                "  {} {} (skipped: {})",
+                "-".dimmed(),
+                case.name,
+                case.message.as_deref().unwrap_or_default()
+            ),
+        }
+    }
+    println!(
+        "\n{} passed, {} failed, {} skipped",
+        report.count(CaseStatus::Passed),
+        report.count(CaseStatus::Failed),
+        report.count(CaseStatus::Skipped)
+    );
+}
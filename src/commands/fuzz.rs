@@ -0,0 +1,157 @@
+//! ファズコマンドの実装
+//!
+//! ワークフローの各操作ステップに、スキーマから合成したリクエストを送り、
+//! 5xx・スキーマ違反・タイムアウトを検出する。検出したリクエストは最小化し、
+//! 再現用のワークフローとして Arazzo ファイルに保存する。
+
+use super::import::{load_resolver, relative_url};
+use crate::converters::ConvertOptions;
+use crate::converters::common::{find_workflow, resolve_base_url};
+use crate::error::{HornetError, Result};
+use crate::fuzz::{FuzzOptions, Fuzzer};
+use crate::loader::{load_arazzo, save_arazzo};
+use crate::models::arazzo::{ArazzoSpec, Info, SourceDescription};
+use crate::runner::native::{NativeExecutor, NativeOptions};
+use colored::Colorize;
+use std::path::Path;
+use std::time::Duration;
+
+/// `execute_fuzz` に渡す引数をまとめた構造体
+pub struct FuzzCommandArgs<'a> {
+    pub arazzo_path: &'a Path,
+    pub workflow_id: Option<&'a str>,
+    pub base_url: Option<&'a str>,
+    pub variants: usize,
+    pub seed: u64,
+    pub timeout: Duration,
+    pub output_path: &'a Path,
+}
+
+/// ファズコマンドを実行する
+pub async fn execute_fuzz(args: FuzzCommandArgs<'_>) -> Result<()> {
+    let FuzzCommandArgs {
+        arazzo_path,
+        workflow_id,
+        base_url,
+        variants,
+        seed,
+        timeout,
+        output_path,
+    } = args;
+
+    let arazzo = load_arazzo(arazzo_path)?;
+    let resolver = load_resolver(&arazzo, arazzo_path)?;
+
+    let base_dir = arazzo_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let executor = NativeExecutor::new(NativeOptions {
+        base_url: resolve_base_url(
+            &resolver,
+            &ConvertOptions {
+                base_url: base_url.map(|s| s.to_string()),
+                ..Default::default()
+            },
+        ),
+        timeout,
+        base_dir: base_dir.to_path_buf(),
+    })?;
+    let fuzzer = Fuzzer::new(
+        &executor,
+        &resolver,
+        FuzzOptions {
+            seed,
+            variants,
+            ..Default::default()
+        },
+    );
+
+    let workflows = match workflow_id {
+        Some(id) => vec![find_workflow(&arazzo, id)?],
+        None => arazzo.workflows.iter().collect(),
+    };
+
+    let mut findings = Vec::new();
+    for workflow in workflows {
+        println!("{} Fuzzing workflow '{}'", "→".blue(), workflow.workflow_id);
+        for report in fuzzer.fuzz_workflow(workflow).await? {
+            if let Some(ref reason) = report.skipped {
+                println!("  {} {} skipped: {}", "-".dimmed(), report.step_id, reason);
+            } else if report.findings.is_empty() {
+                println!(
+                    "  {} {} ({} variants)",
+                    "✓".green(),
+                    report.step_id,
+                    report.variants
+                );
+            }
+            for finding in report.findings {
+                println!(
+                    "  {} {} [{}] {}",
+                    "✗".red(),
+                    report.step_id,
+                    finding.kind,
+                    finding.message
+                );
+                println!(
+                    "      variant {}, shrunk in {} step(s)",
+                    finding.variant, finding.shrink_steps
+                );
+                findings.push(finding);
+            }
+        }
+    }
+
+    if findings.is_empty() {
+        println!("{} No findings", "✓".green());
+        return Ok(());
+    }
+
+    // 再現用ワークフローを保存する（sourceDescriptions は出力先からの相対パスに直す）
+    let output_dir = output_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let source_descriptions = arazzo
+        .source_descriptions
+        .iter()
+        .map(|source| SourceDescription {
+            url: if source.url.contains("://") {
+                source.url.clone()
+            } else {
+                relative_url(&base_dir.join(&source.url), output_dir)
+            },
+            ..source.clone()
+        })
+        .collect();
+    let spec = ArazzoSpec {
+        arazzo: arazzo.arazzo.clone(),
+        info: Info {
+            title: format!("Fuzz findings for {}", arazzo.info.title),
+            summary: None,
+            description: Some(format!(
+                "Minimal reproducers found by `hornet2 fuzz` (seed {}) in {}",
+                seed,
+                arazzo_path.display()
+            )),
+            version: arazzo.info.version.clone(),
+        },
+        source_descriptions,
+        workflows: findings.iter().map(|f| f.reproducer.clone()).collect(),
+        components: arazzo.components.clone(),
+        extensions: Default::default(),
+    };
+    save_arazzo(output_path, &spec)?;
+    println!(
+        "{} Saved {} reproducer(s) to {}",
+        "✓".green(),
+        findings.len(),
+        output_path.display()
+    );
+
+    Err(HornetError::ValidationError(format!(
+        "Fuzzing found {} issue(s)",
+        findings.len()
+    )))
+}
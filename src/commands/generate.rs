@@ -0,0 +1,108 @@
+//! 生成コマンドの実装
+//!
+//! OpenAPIドキュメントからArazzoワークフロー（CRUDライフサイクル、リンクの連鎖）を生成する。
+
+use super::import::{add_source_description, load_resolver};
+use crate::error::{HornetError, Result};
+use crate::generator;
+use crate::loader::{load_arazzo, save_arazzo};
+use crate::models::arazzo::{ArazzoSpec, Info};
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+
+/// `execute_generate` に渡す引数をまとめた構造体
+pub struct GenerateCommandArgs<'a> {
+    pub openapi_paths: &'a [PathBuf],
+    pub output_path: &'a Path,
+    /// 既存ファイルにマージせず置き換える
+    pub overwrite: bool,
+    /// ファイルに書き込まず標準出力に表示する
+    pub dry_run: bool,
+}
+
+/// 生成コマンドを実行する
+pub fn execute_generate(args: GenerateCommandArgs<'_>) -> Result<()> {
+    let GenerateCommandArgs {
+        openapi_paths,
+        output_path,
+        overwrite,
+        dry_run,
+    } = args;
+
+    // 既存のArazzoファイルがあればマージ先として読み込む
+    let mut spec = if output_path.exists() && !overwrite {
+        load_arazzo(output_path)?
+    } else {
+        if openapi_paths.is_empty() {
+            return Err(HornetError::ValidationError(
+                "No OpenAPI document given (use --openapi)".to_string(),
+            ));
+        }
+        ArazzoSpec {
+            arazzo: "1.0.0".to_string(),
+            info: Info {
+                title: "Generated workflows".to_string(),
+                summary: None,
+                description: Some("Generated from OpenAPI by hornet2".to_string()),
+                version: "1.0.0".to_string(),
+            },
+            source_descriptions: Vec::new(),
+            workflows: Vec::new(),
+            components: None,
+            extensions: Default::default(),
+        }
+    };
+
+    let base_dir = output_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    for openapi_path in openapi_paths {
+        add_source_description(&mut spec, openapi_path, base_dir);
+    }
+
+    let resolver = load_resolver(&spec, output_path)?;
+    eprintln!(
+        "{} Loaded {} OpenAPI source(s)",
+        "✓".green(),
+        resolver.get_all_specs().len()
+    );
+
+    let result = generator::generate_workflows(&resolver);
+    for warning in &result.warnings {
+        eprintln!("  {} {}", "⚠".yellow(), warning);
+    }
+    if result.workflows.is_empty() {
+        return Err(HornetError::ValidationError(
+            "No CRUD resource or OpenAPI link found to generate workflows from".to_string(),
+        ));
+    }
+
+    let summary = generator::merge_workflows(&mut spec, result.workflows);
+    for id in &summary.added {
+        eprintln!("{} Workflow '{}'", "✓".green(), id);
+    }
+    for id in &summary.skipped {
+        eprintln!(
+            "{} Workflow '{}' already exists, kept as is",
+            "→".blue(),
+            id
+        );
+    }
+
+    if dry_run {
+        spec.validate()?;
+        println!("{}", serde_yaml::to_string(&spec)?);
+        return Ok(());
+    }
+
+    save_arazzo(output_path, &spec)?;
+    println!(
+        "{} Generated {} workflow(s) into {}",
+        "✓".green(),
+        summary.added.len(),
+        output_path.display()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,220 @@
+//! インポートコマンドの実装
+//!
+//! Postmanコレクション・HARファイルからArazzoワークフローを生成する。
+
+use crate::error::{HornetError, Result};
+use crate::importers::{self, RecordedFlow};
+use crate::loader::{OpenApiResolver, SourceDescriptionResolver, load_arazzo, save_arazzo};
+use crate::models::arazzo::{ArazzoSpec, Info, SourceDescription};
+use colored::Colorize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// `execute_import` に渡す引数をまとめた構造体
+pub struct ImportCommandArgs<'a> {
+    pub input_path: &'a Path,
+    pub from: &'a str,
+    pub openapi_paths: &'a [PathBuf],
+    pub output_path: &'a Path,
+}
+
+/// インポートコマンドを実行する
+pub fn execute_import(args: ImportCommandArgs<'_>) -> Result<()> {
+    let ImportCommandArgs {
+        input_path,
+        from,
+        openapi_paths,
+        output_path,
+    } = args;
+
+    // 入力ファイルを読み込む
+    let content = fs::read_to_string(input_path)?;
+    let document: serde_json::Value = serde_json::from_str(&content)?;
+
+    let flows = match from.to_lowercase().as_str() {
+        "postman" => importers::postman::parse_collection(&document)?,
+        "har" => {
+            let default_name = input_path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("har");
+            vec![importers::har::parse_har(&document, default_name)?]
+        }
+        _ => {
+            return Err(HornetError::ValidationError(format!(
+                "Unsupported import format: {}. Supported: postman, har",
+                from
+            )));
+        }
+    };
+    eprintln!(
+        "{} Loaded {} flow(s) from {}",
+        "✓".green(),
+        flows.len(),
+        input_path.display()
+    );
+
+    // 既存のArazzoファイルがあればワークフローを追記する
+    let title = flows
+        .first()
+        .map(|f| f.name.clone())
+        .unwrap_or_else(|| "Imported workflows".to_string());
+    let mut spec = load_or_new(
+        output_path,
+        &title,
+        &format!("Imported from {}", input_path.display()),
+    )?;
+
+    let base_dir = output_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    for openapi_path in openapi_paths {
+        add_source_description(&mut spec, openapi_path, base_dir);
+    }
+
+    let resolver = load_resolver(&spec, output_path)?;
+    let imported = import_flows(&flows, &resolver, &mut spec);
+
+    if imported == 0 {
+        return Err(HornetError::ImportError(
+            "No request matched an OpenAPI operation".to_string(),
+        ));
+    }
+
+    save_arazzo(output_path, &spec)?;
+    println!(
+        "{} Imported {} workflow(s) into {}",
+        "✓".green(),
+        imported,
+        output_path.display()
+    );
+
+    Ok(())
+}
+
+/// 既存の Arazzo を読み込む（なければ空の Arazzo を作る）
+pub(crate) fn load_or_new(
+    output_path: &Path,
+    title: &str,
+    description: &str,
+) -> Result<ArazzoSpec> {
+    if output_path.exists() {
+        return load_arazzo(output_path);
+    }
+    Ok(ArazzoSpec {
+        arazzo: "1.0.0".to_string(),
+        info: Info {
+            title: title.to_string(),
+            summary: None,
+            description: Some(description.to_string()),
+            version: "1.0.0".to_string(),
+        },
+        source_descriptions: Vec::new(),
+        workflows: Vec::new(),
+        components: None,
+        extensions: Default::default(),
+    })
+}
+
+/// フローをワークフローへ変換して追加し、追加した数を返す
+pub(crate) fn import_flows(
+    flows: &[RecordedFlow],
+    resolver: &OpenApiResolver,
+    spec: &mut ArazzoSpec,
+) -> usize {
+    let mut workflow_ids: HashSet<String> = spec
+        .workflows
+        .iter()
+        .map(|w| w.workflow_id.clone())
+        .collect();
+    let mut imported = 0;
+
+    for flow in flows {
+        let result = importers::build_workflow(flow, resolver);
+        for warning in &result.warnings {
+            eprintln!("  {} {}", "⚠".yellow(), warning);
+        }
+
+        let mut workflow = result.workflow;
+        if workflow.steps.is_empty() {
+            eprintln!(
+                "{} Flow '{}' has no importable requests, skipped",
+                "⚠️ ".yellow(),
+                flow.name
+            );
+            continue;
+        }
+
+        workflow.workflow_id = importers::unique_id(&workflow.workflow_id, &mut workflow_ids);
+        eprintln!(
+            "{} Workflow '{}' ({} step(s))",
+            "✓".green(),
+            workflow.workflow_id,
+            workflow.steps.len()
+        );
+        spec.workflows.push(workflow);
+        imported += 1;
+    }
+
+    imported
+}
+
+/// `--openapi` で指定されたファイルを sourceDescriptions に追加する
+pub(crate) fn add_source_description(spec: &mut ArazzoSpec, openapi_path: &Path, base_dir: &Path) {
+    let url = relative_url(openapi_path, base_dir);
+    if spec.source_descriptions.iter().any(|s| s.url == url) {
+        return;
+    }
+
+    let stem = openapi_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("api");
+    let mut names: HashSet<String> = spec
+        .source_descriptions
+        .iter()
+        .map(|s| s.name.clone())
+        .collect();
+    let name = importers::unique_id(&importers::sanitize_id(stem), &mut names);
+
+    spec.source_descriptions.push(SourceDescription {
+        name,
+        url,
+        source_type: Some("openapi".to_string()),
+    });
+}
+
+/// 出力先ディレクトリからの相対パスを求める（求められない場合は絶対パス）
+pub(crate) fn relative_url(path: &Path, base_dir: &Path) -> String {
+    let absolute = |p: &Path| fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
+    let path = absolute(path);
+    let base = absolute(base_dir);
+
+    match path.strip_prefix(&base) {
+        Ok(relative) => format!("./{}", relative.display()),
+        Err(_) => path.display().to_string(),
+    }
+}
+
+/// sourceDescriptions からOpenAPIを読み込む
+pub(crate) fn load_resolver(spec: &ArazzoSpec, output_path: &Path) -> Result<OpenApiResolver> {
+    let source_result =
+        SourceDescriptionResolver::new(output_path)?.load_sources(&spec.source_descriptions);
+
+    if !source_result.errors.is_empty() {
+        eprintln!("{}", "⚠️  Some OpenAPI sources failed to load:".yellow());
+        for err in &source_result.errors {
+            eprintln!("  - Source '{}': {}", err.name, err.message);
+        }
+    }
+
+    if source_result.resolver.get_all_specs().is_empty() {
+        return Err(HornetError::ValidationError(
+            "No OpenAPI sources loaded successfully (use --openapi to add one)".to_string(),
+        ));
+    }
+
+    Ok(source_result.resolver)
+}
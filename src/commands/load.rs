@@ -0,0 +1,786 @@
+//! 負荷試験コマンドの実装
+//!
+//! ワークフローを native エンジンで繰り返し実行して負荷をかける。VU 数を固定する
+//! クローズドモデルと、到着レート（一定またはステージで変化）を指定するオープン
+//! モデルに対応し、実行中は1秒ごとに進捗を、終了時にステップとワークフローごとの
+//! レイテンシのパーセンタイル、RPS、エラー率を表示する。負荷プロファイル
+//! （`x-load-profile` または `--profile`）があれば、重みに従って複数の
+//! ワークフローを混ぜて実行する。`--search` を指定すると到着レートを段階的に
+//! （または二分探索で）変えて、ステップの `x-slo` を満たす最大のレートを探す。
+//! `--agents` を指定するとコーディネーターとして agent の接続を待ち、負荷を
+//! 分けて実行させた結果をまとめる。`--baseline` を指定すると保存済みの
+//! メトリクスとステップごとに比べ、悪化していれば非ゼロで終了する。
+
+use super::baseline::BaselineArgs;
+use super::import::load_resolver;
+use crate::converters::ConvertOptions;
+use crate::converters::common::{find_workflow, parse_duration, resolve_base_url};
+use crate::error::{HornetError, Result};
+use crate::loader::load_arazzo;
+use crate::models::profile::LoadProfile;
+use crate::models::slo::step_objectives;
+use crate::runner::RunMetrics;
+use crate::runner::load::{
+    AgentStatus, AgentUpdate, Bundle, CapacityReport, CapacitySearch, Coordinator, LatencySummary,
+    LoadModel, LoadSummary, LoadTest, Probe, Progress, Scenario, SearchStrategy, parse_stages,
+};
+use crate::runner::native::{NativeOptions, WorkflowPlan};
+use colored::Colorize;
+use std::io::{IsTerminal, Write};
+use std::path::Path;
+use std::time::Duration;
+
+/// オープンモデルで同時に実行できるイテレーションの既定の上限
+const DEFAULT_MAX_VUS: u32 = 100;
+
+/// 負荷試験で1リクエストに許す既定の時間
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// コーディネーターが agent の接続を待つ時間
+const AGENT_WAIT: Duration = Duration::from_secs(60);
+
+/// `execute_load` に渡す引数をまとめた構造体
+pub struct LoadCommandArgs<'a> {
+    pub arazzo_path: &'a Path,
+    pub workflow_id: Option<&'a str>,
+    pub base_url: Option<&'a str>,
+    /// クローズドモデルの VU 数
+    pub vus: Option<u32>,
+    pub duration: Option<&'a str>,
+    /// クローズドモデルで全 VU が合わせて実行するイテレーション数
+    pub iterations: Option<u64>,
+    /// オープンモデルの到着レート（イテレーション/秒、ステージの開始値）
+    pub rate: Option<f64>,
+    /// オープンモデルのステージ（`30s:10,1m:50`）
+    pub stages: Option<&'a str>,
+    /// オープンモデルで同時に実行できるイテレーションの上限
+    pub max_vus: Option<u32>,
+    /// 1リクエストに許す時間
+    pub timeout: Option<&'a str>,
+    /// サマリーを JSON で書き出すファイル
+    pub output_path: Option<&'a Path>,
+    /// 負荷プロファイルのファイル（なければ Arazzo の `x-load-profile`）
+    pub profile_path: Option<&'a Path>,
+    /// 容量探索の方法（`step` または `binary`）
+    pub search: Option<&'a str>,
+    /// 容量探索で試す最大のレート
+    pub max_rate: Option<f64>,
+    /// 容量探索でレートを上げる幅（二分探索では打ち切る幅）
+    pub rate_step: Option<f64>,
+    /// 負荷を分ける agent の数（指定するとコーディネーターになる）
+    pub agents: Option<usize>,
+    /// agent の接続を待つアドレス
+    pub listen: &'a str,
+    /// ベースラインの保存と比較
+    pub baseline: BaselineArgs<'a>,
+}
+
+/// 負荷試験コマンドを実行する
+pub async fn execute_load(args: LoadCommandArgs<'_>) -> Result<()> {
+    let search = capacity_search(&args)?;
+    args.baseline.tolerance()?;
+    if search.is_some() && !args.baseline.is_empty() {
+        return Err(HornetError::ValidationError(
+            "--search cannot be combined with --baseline or --save-baseline".to_string(),
+        ));
+    }
+    let model = match search {
+        Some(ref search) => {
+            LoadModel::constant_rate(search.start_rate, search.duration, search.max_vus)
+        }
+        None => load_model(&args)?,
+    };
+    let timeout = match args.timeout {
+        Some(timeout) => parse_duration(timeout)
+            .ok_or_else(|| HornetError::ValidationError(format!("Invalid timeout: {}", timeout)))?,
+        None => DEFAULT_TIMEOUT,
+    };
+
+    let arazzo = load_arazzo(args.arazzo_path)?;
+    let resolver = load_resolver(&arazzo, args.arazzo_path)?;
+    let profile = LoadProfile::resolve(args.profile_path, &arazzo)?;
+
+    // ワークフローの指定がなければプロファイルの配分で混ぜる
+    let scenarios = match (args.workflow_id, &profile) {
+        (None, Some(profile)) => Scenario::from_profile(profile, &arazzo, &resolver)?,
+        (workflow_id, _) => {
+            let workflow = match workflow_id {
+                Some(id) => find_workflow(&arazzo, id)?,
+                None => arazzo.workflows.first().ok_or_else(|| {
+                    HornetError::ValidationError("No workflow to run".to_string())
+                })?,
+            };
+            let id = workflow.workflow_id.as_str();
+            vec![Scenario {
+                think_time: profile.as_ref().and_then(|p| p.think_time(id)),
+                pacing: profile.as_ref().and_then(|p| p.pacing(id)),
+                ..Scenario::new(WorkflowPlan::new(workflow, &resolver)?)
+            }]
+        }
+    };
+    let total_weight: f64 = scenarios.iter().map(|s| s.weight).sum();
+    let mix: Vec<String> = scenarios
+        .iter()
+        .map(|s| {
+            format!(
+                "{} {:.0}%",
+                s.plan.workflow_id,
+                s.weight / total_weight * 100.0
+            )
+        })
+        .collect();
+    let workflow_ids: Vec<String> = scenarios
+        .iter()
+        .map(|s| s.plan.workflow_id.clone())
+        .collect();
+
+    let options = ConvertOptions {
+        base_url: args.base_url.map(|s| s.to_string()),
+        ..Default::default()
+    };
+    let base_dir = args
+        .arazzo_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let base_url = resolve_base_url(&resolver, &options);
+
+    if let Some(agents) = args.agents {
+        if search.is_some() {
+            return Err(HornetError::ValidationError(
+                "--search cannot be combined with --agents".to_string(),
+            ));
+        }
+        let coordinator = Coordinator::bind(args.listen).await?;
+        println!(
+            "{} Waiting for {} agent(s) on {} to load test {} ({})",
+            "→".blue(),
+            agents,
+            coordinator.local_addr()?,
+            mix.join(", "),
+            describe(&model)
+        );
+        let live = std::io::stderr().is_terminal();
+        let summary = coordinator
+            .run(
+                Bundle {
+                    base_url,
+                    timeout,
+                    scenarios,
+                },
+                &model,
+                agents,
+                AGENT_WAIT,
+                |update| match update {
+                    AgentUpdate::Joined { name, address } => {
+                        println!("{} Agent '{}' joined from {}", "✓".green(), name, address)
+                    }
+                    AgentUpdate::Lost {
+                        name,
+                        address,
+                        reason,
+                    } => println!(
+                        "{} Agent '{}' ({}) lost: {}",
+                        "⚠".yellow(),
+                        name,
+                        address,
+                        reason
+                    ),
+                },
+                |progress| {
+                    let line = progress_line(progress);
+                    if live {
+                        eprint!("\r\x1b[2K{}", line);
+                        let _ = std::io::stderr().flush();
+                    } else {
+                        eprintln!("{}", line);
+                    }
+                },
+            )
+            .await?;
+        if live {
+            eprintln!();
+        }
+
+        print_summary(&summary);
+        if let Some(path) = args.output_path {
+            std::fs::write(path, serde_json::to_string_pretty(&summary)?)?;
+            println!("{} Summary written to {}", "✓".green(), path.display());
+        }
+        // 途中で失われた agent があっても、1つでも完了すれば結果は出す
+        if summary
+            .agents
+            .iter()
+            .all(|a| a.status != AgentStatus::Finished)
+        {
+            return Err(HornetError::ValidationError(
+                "Every agent was lost before finishing".to_string(),
+            ));
+        }
+        return args.baseline.apply(&RunMetrics::from(&summary));
+    }
+
+    let test = LoadTest::new(
+        NativeOptions {
+            base_url,
+            timeout,
+            base_dir: base_dir.to_path_buf(),
+        },
+        scenarios,
+        model.clone(),
+    )?;
+
+    if let Some(search) = search {
+        // 実行するワークフローの SLO だけを見る
+        let mut objectives = step_objectives(&arazzo)?;
+        objectives.retain(|o| workflow_ids.contains(&o.workflow_id));
+        if objectives.is_empty() {
+            return Err(HornetError::ValidationError(
+                "Capacity search needs x-slo on at least one step of the tested workflows"
+                    .to_string(),
+            ));
+        }
+        println!(
+            "{} Searching capacity of {} ({:?} search from {}/s up to {}/s, {:?} per rate)",
+            "→".blue(),
+            mix.join(", "),
+            search.strategy,
+            search.start_rate,
+            search.max_rate,
+            search.duration
+        );
+        let live = std::io::stderr().is_terminal();
+        let report = test
+            .search_capacity(
+                &search,
+                &objectives,
+                |rate, progress| {
+                    let line = format!("{:>8.1}/s {}", rate, progress_line(progress));
+                    if live {
+                        eprint!("\r\x1b[2K{}", line);
+                        let _ = std::io::stderr().flush();
+                    } else {
+                        eprintln!("{}", line);
+                    }
+                },
+                |probe| {
+                    if live {
+                        eprint!("\r\x1b[2K");
+                    }
+                    print_probe(probe);
+                },
+            )
+            .await?;
+
+        print_capacity(&report);
+        if let Some(path) = args.output_path {
+            std::fs::write(path, serde_json::to_string_pretty(&report)?)?;
+            println!("{} Report written to {}", "✓".green(), path.display());
+        }
+        return Ok(());
+    }
+
+    println!(
+        "{} Load testing {} ({})",
+        "→".blue(),
+        mix.join(", "),
+        describe(&model)
+    );
+    // 端末なら同じ行を書き換え、そうでなければ1行ずつ出す
+    let live = std::io::stderr().is_terminal();
+    let summary = test
+        .run(|progress| {
+            let line = progress_line(progress);
+            if live {
+                eprint!("\r\x1b[2K{}", line);
+                let _ = std::io::stderr().flush();
+            } else {
+                eprintln!("{}", line);
+            }
+        })
+        .await;
+    if live {
+        eprintln!();
+    }
+
+    print_summary(&summary);
+    if let Some(path) = args.output_path {
+        std::fs::write(path, serde_json::to_string_pretty(&summary)?)?;
+        println!("{} Summary written to {}", "✓".green(), path.display());
+    }
+    args.baseline.apply(&RunMetrics::from(&summary))
+}
+
+/// 容量探索の指定を読む（`--search` がなければ `None`）
+fn capacity_search(args: &LoadCommandArgs<'_>) -> Result<Option<CapacitySearch>> {
+    let Some(strategy) = args.search else {
+        if args.max_rate.is_some() || args.rate_step.is_some() {
+            return Err(HornetError::ValidationError(
+                "--max-rate and --rate-step require --search".to_string(),
+            ));
+        }
+        return Ok(None);
+    };
+    let strategy: SearchStrategy = strategy.parse()?;
+    if args.vus.is_some() || args.iterations.is_some() || args.stages.is_some() {
+        return Err(HornetError::ValidationError(
+            "--search cannot be combined with --vus, --iterations or --stages".to_string(),
+        ));
+    }
+    let (Some(start_rate), Some(max_rate), Some(duration)) =
+        (args.rate, args.max_rate, args.duration)
+    else {
+        return Err(HornetError::ValidationError(
+            "--search requires --rate, --max-rate and --duration (per rate)".to_string(),
+        ));
+    };
+    let duration = parse_duration(duration)
+        .ok_or_else(|| HornetError::ValidationError(format!("Invalid duration: {}", duration)))?;
+    // 既定の幅: 段階的なら開始レートずつ、二分探索なら上限の 1%
+    let rate_step = args.rate_step.unwrap_or(match strategy {
+        SearchStrategy::Step => start_rate,
+        SearchStrategy::Binary => max_rate / 100.0,
+    });
+    let search = CapacitySearch {
+        strategy,
+        start_rate,
+        max_rate,
+        rate_step,
+        duration,
+        max_vus: args.max_vus.unwrap_or(DEFAULT_MAX_VUS),
+    };
+    search.validate()?;
+    Ok(Some(search))
+}
+
+/// 引数から負荷のモデルを決める
+fn load_model(args: &LoadCommandArgs<'_>) -> Result<LoadModel> {
+    let duration = args
+        .duration
+        .map(|d| {
+            parse_duration(d)
+                .ok_or_else(|| HornetError::ValidationError(format!("Invalid duration: {}", d)))
+        })
+        .transpose()?;
+
+    if args.rate.is_none() && args.stages.is_none() {
+        if args.max_vus.is_some() {
+            return Err(HornetError::ValidationError(
+                "--max-vus requires --rate or --stages".to_string(),
+            ));
+        }
+        let vus = args.vus.unwrap_or(1);
+        // 期間も回数もなければ各 VU が1回ずつ実行する
+        let iterations = match (duration, args.iterations) {
+            (None, None) => Some(u64::from(vus)),
+            (_, iterations) => iterations,
+        };
+        return Ok(LoadModel::Vus {
+            vus,
+            duration,
+            iterations,
+        });
+    }
+
+    if args.vus.is_some() || args.iterations.is_some() {
+        return Err(HornetError::ValidationError(
+            "--vus and --iterations cannot be combined with --rate or --stages (use --max-vus)"
+                .to_string(),
+        ));
+    }
+    let max_vus = args.max_vus.unwrap_or(DEFAULT_MAX_VUS);
+    match (args.stages, duration) {
+        (Some(_), Some(_)) => Err(HornetError::ValidationError(
+            "--duration cannot be combined with --stages".to_string(),
+        )),
+        (Some(stages), None) => Ok(LoadModel::ArrivalRate {
+            start_rate: args.rate.unwrap_or(0.0),
+            stages: parse_stages(stages)?,
+            max_vus,
+        }),
+        (None, Some(duration)) => Ok(LoadModel::constant_rate(
+            args.rate.unwrap_or_default(),
+            duration,
+            max_vus,
+        )),
+        (None, None) => Err(HornetError::ValidationError(
+            "--rate requires --duration".to_string(),
+        )),
+    }
+}
+
+pub(super) fn describe(model: &LoadModel) -> String {
+    match model {
+        LoadModel::Vus {
+            vus,
+            duration,
+            iterations,
+        } => {
+            let mut parts = vec![format!("{} VU(s)", vus)];
+            if let Some(duration) = duration {
+                parts.push(format!("for {:?}", duration));
+            }
+            if let Some(iterations) = iterations {
+                parts.push(format!("{} iteration(s)", iterations));
+            }
+            parts.join(", ")
+        }
+        LoadModel::ArrivalRate {
+            start_rate,
+            stages,
+            max_vus,
+        } => {
+            let targets: Vec<String> = stages
+                .iter()
+                .map(|s| format!("{:?}→{}/s", s.duration, s.target))
+                .collect();
+            format!(
+                "from {}/s: {}, up to {} VU(s)",
+                start_rate,
+                targets.join(", "),
+                max_vus
+            )
+        }
+    }
+}
+
+fn progress_line(progress: &Progress) -> String {
+    let mut line = format!(
+        "{:>5}s  vus {:>4}  iterations {:>7}  requests {:>8}  errors {:>6}  rps {:>8.1}  p95 {:>8.2}ms  p99 {:>8.2}ms",
+        progress.elapsed.as_secs(),
+        progress.active_vus,
+        progress.iterations,
+        progress.requests,
+        progress.errors,
+        progress.rps,
+        progress.latency.p95,
+        progress.latency.p99
+    );
+    if progress.dropped_iterations > 0 {
+        line.push_str(&format!("  dropped {}", progress.dropped_iterations));
+    }
+    line
+}
+
+/// ステップとワークフローごとの結果を表示する
+fn print_summary(summary: &LoadSummary) {
+    let header = format!(
+        "  {:<24} {:>9} {:>9} {:>7} {:>9} {:>9} {:>9} {:>9} {:>9} {:>9}",
+        "step", "requests", "rps", "errors", "p50", "p90", "p95", "p99", "p99.9", "max"
+    );
+    for workflow in &summary.workflows {
+        println!(
+            "\n{} {}: {} iteration(s), {} failed, {:.1}/s",
+            "Workflow".bold(),
+            workflow.workflow_id,
+            workflow.iterations,
+            workflow.failed,
+            workflow.iteration_rate
+        );
+        println!("{}", header.dimmed());
+        for step in &workflow.steps {
+            print_row(
+                &step.step_id,
+                step.requests,
+                step.rps,
+                step.error_rate,
+                &step.latency,
+            );
+        }
+        print_row(
+            "(iteration)",
+            workflow.iterations,
+            workflow.iteration_rate,
+            if workflow.iterations == 0 {
+                0.0
+            } else {
+                workflow.failed as f64 / workflow.iterations as f64
+            },
+            &workflow.duration,
+        );
+    }
+
+    println!(
+        "\n{} {} request(s) in {:.1}s, {:.1} req/s, {:.2}% errors, p95 {:.2}ms, p99 {:.2}ms",
+        "Total:".bold(),
+        summary.requests,
+        summary.duration_ms / 1000.0,
+        summary.rps,
+        summary.error_rate * 100.0,
+        summary.latency.p95,
+        summary.latency.p99
+    );
+    if summary.dropped_iterations > 0 {
+        println!(
+            "{} {} iteration(s) dropped because every VU was busy (raise --max-vus)",
+            "⚠".yellow(),
+            summary.dropped_iterations
+        );
+    }
+    for agent in &summary.agents {
+        match agent.status {
+            AgentStatus::Finished => println!(
+                "  {} agent {} ({}): {} iteration(s), {} request(s)",
+                "✓".green(),
+                agent.name,
+                agent.address,
+                agent.iterations,
+                agent.requests
+            ),
+            AgentStatus::Lost { ref reason } => println!(
+                "  {} agent {} ({}) lost ({}): {} iteration(s), {} request(s) reported",
+                "⚠".yellow(),
+                agent.name,
+                agent.address,
+                reason,
+                agent.iterations,
+                agent.requests
+            ),
+        }
+    }
+}
+
+/// 1つのレートの結果と SLO 違反を表示する
+fn print_probe(probe: &Probe) {
+    let result = if probe.passed {
+        "pass".green()
+    } else {
+        "fail".red()
+    };
+    println!(
+        "{:>8.1}/s  {}  {:.1} it/s, p95 {:.2}ms, p99 {:.2}ms, {:.2}% errors",
+        probe.rate,
+        result,
+        achieved_rate(&probe.summary),
+        probe.summary.latency.p95,
+        probe.summary.latency.p99,
+        probe.summary.error_rate * 100.0
+    );
+    for violation in &probe.violations {
+        let (limit, actual) = if violation.metric == "errorRate" {
+            (
+                format!("{:.2}%", violation.limit * 100.0),
+                format!("{:.2}%", violation.actual * 100.0),
+            )
+        } else {
+            (
+                format!("{:.2}ms", violation.limit),
+                format!("{:.2}ms", violation.actual),
+            )
+        };
+        println!(
+            "            {}/{} {} {} > {}",
+            violation.workflow_id, violation.step_id, violation.metric, actual, limit
+        );
+    }
+    if probe.summary.dropped_iterations > 0 {
+        println!(
+            "            {} iteration(s) dropped (raise --max-vus)",
+            probe.summary.dropped_iterations
+        );
+    }
+}
+
+/// レートごとのレイテンシの推移と、SLO を満たした最大のレートを表示する
+fn print_capacity(report: &CapacityReport) {
+    println!(
+        "\n{}",
+        format!(
+            "  {:>10} {:>9} {:>9} {:>7} {:>9} {:>9} {:>9}  {}",
+            "rate", "it/s", "rps", "errors", "p50", "p95", "p99", "result"
+        )
+        .dimmed()
+    );
+    for probe in report.curve() {
+        let summary = &probe.summary;
+        println!(
+            "  {:>8.1}/s {:>9.1} {:>9.1} {:>6.2}% {:>9.2} {:>9.2} {:>9.2}  {}",
+            probe.rate,
+            achieved_rate(summary),
+            summary.rps,
+            summary.error_rate * 100.0,
+            summary.latency.p50,
+            summary.latency.p95,
+            summary.latency.p99,
+            if probe.passed {
+                "pass".green()
+            } else {
+                "fail".red()
+            }
+        );
+    }
+    match report.max_passing_rate {
+        Some(rate) => println!(
+            "\n{} Highest rate meeting the SLOs: {:.1} iteration(s)/s",
+            "✓".green(),
+            rate
+        ),
+        None => println!("\n{} No probed rate met the SLOs", "✗".red()),
+    }
+}
+
+fn achieved_rate(summary: &LoadSummary) -> f64 {
+    summary.iterations as f64 / (summary.duration_ms / 1000.0).max(f64::EPSILON)
+}
+
+fn print_row(name: &str, count: u64, rate: f64, error_rate: f64, latency: &LatencySummary) {
+    let errors = format!("{:.2}%", error_rate * 100.0);
+    let errors = if error_rate > 0.0 {
+        errors.red().to_string()
+    } else {
+        errors
+    };
+    println!(
+        "  {:<24} {:>9} {:>9.1} {:>7} {:>9.2} {:>9.2} {:>9.2} {:>9.2} {:>9.2} {:>9.2}",
+        name,
+        count,
+        rate,
+        errors,
+        latency.p50,
+        latency.p90,
+        latency.p95,
+        latency.p99,
+        latency.p999,
+        latency.max
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args<'a>() -> LoadCommandArgs<'a> {
+        LoadCommandArgs {
+            arazzo_path: Path::new("arazzo.yaml"),
+            workflow_id: None,
+            base_url: None,
+            vus: None,
+            duration: None,
+            iterations: None,
+            rate: None,
+            stages: None,
+            max_vus: None,
+            timeout: None,
+            output_path: None,
+            profile_path: None,
+            search: None,
+            max_rate: None,
+            rate_step: None,
+            agents: None,
+            listen: "127.0.0.1:0",
+            baseline: BaselineArgs::default(),
+        }
+    }
+
+    #[test]
+    fn test_load_model() {
+        // 何も指定しなければ 1 VU で1回
+        assert_eq!(
+            load_model(&args()).unwrap(),
+            LoadModel::Vus {
+                vus: 1,
+                duration: None,
+                iterations: Some(1)
+            }
+        );
+        assert_eq!(
+            load_model(&LoadCommandArgs {
+                vus: Some(5),
+                duration: Some("10s"),
+                ..args()
+            })
+            .unwrap(),
+            LoadModel::Vus {
+                vus: 5,
+                duration: Some(Duration::from_secs(10)),
+                iterations: None
+            }
+        );
+        assert_eq!(
+            load_model(&LoadCommandArgs {
+                rate: Some(20.0),
+                duration: Some("1m"),
+                ..args()
+            })
+            .unwrap(),
+            LoadModel::constant_rate(20.0, Duration::from_secs(60), DEFAULT_MAX_VUS)
+        );
+
+        // 異常系: モデルの混在や期間のないレート
+        for invalid in [
+            LoadCommandArgs {
+                rate: Some(20.0),
+                ..args()
+            },
+            LoadCommandArgs {
+                rate: Some(20.0),
+                vus: Some(3),
+                duration: Some("1m"),
+                ..args()
+            },
+            LoadCommandArgs {
+                stages: Some("10s:5"),
+                duration: Some("1m"),
+                ..args()
+            },
+            LoadCommandArgs {
+                max_vus: Some(5),
+                ..args()
+            },
+        ] {
+            assert!(load_model(&invalid).is_err());
+        }
+    }
+
+    #[test]
+    fn test_capacity_search() {
+        assert_eq!(capacity_search(&args()).unwrap(), None);
+
+        let search = capacity_search(&LoadCommandArgs {
+            search: Some("binary"),
+            rate: Some(10.0),
+            max_rate: Some(200.0),
+            duration: Some("30s"),
+            ..args()
+        })
+        .unwrap()
+        .unwrap();
+        assert_eq!(search.strategy, SearchStrategy::Binary);
+        assert_eq!(search.rate_step, 2.0);
+        assert_eq!(search.duration, Duration::from_secs(30));
+        assert_eq!(search.max_vus, DEFAULT_MAX_VUS);
+
+        // 異常系: 不足した指定、クローズドモデルとの混在、未知の方法
+        let valid = || LoadCommandArgs {
+            search: Some("step"),
+            rate: Some(10.0),
+            max_rate: Some(200.0),
+            duration: Some("30s"),
+            ..args()
+        };
+        for invalid in [
+            LoadCommandArgs {
+                max_rate: None,
+                ..valid()
+            },
+            LoadCommandArgs {
+                vus: Some(3),
+                ..valid()
+            },
+            LoadCommandArgs {
+                search: Some("linear"),
+                ..valid()
+            },
+            LoadCommandArgs {
+                max_rate: Some(5.0),
+                ..valid()
+            },
+            LoadCommandArgs {
+                max_rate: Some(5.0),
+                ..args()
+            },
+        ] {
+            assert!(capacity_search(&invalid).is_err());
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! モックコマンドの実装
+//!
+//! OpenAPI に宣言された操作に、例またはスキーマから合成した値で応答する
+//! モックサーバーを起動する。Arazzo を与えるとステップの successCriteria を
+//! 満たすように応答し、`--stateful` では作成されたリソースをメモリに保持する。
+
+use super::import::load_resolver;
+use crate::error::{HornetError, Result};
+use crate::loader::{OpenApiResolver, load_arazzo};
+use crate::server::mock::{Mock, start_mock_server};
+use colored::Colorize;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+/// `execute_mock` に渡す引数をまとめた構造体
+pub struct MockCommandArgs<'a> {
+    pub openapi_paths: &'a [PathBuf],
+    pub arazzo_path: Option<&'a Path>,
+    pub port: u16,
+    pub stateful: bool,
+}
+
+/// モックコマンドを実行する
+pub async fn execute_mock(args: MockCommandArgs<'_>) -> Result<()> {
+    let MockCommandArgs {
+        openapi_paths,
+        arazzo_path,
+        port,
+        stateful,
+    } = args;
+
+    // Arazzo の sourceDescriptions と --openapi の両方を読み込む
+    let arazzo = arazzo_path.map(load_arazzo).transpose()?;
+    let mut resolver = match (&arazzo, arazzo_path) {
+        (Some(spec), Some(path)) => load_resolver(spec, path)?,
+        _ => OpenApiResolver::new("."),
+    };
+    for path in openapi_paths {
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("openapi");
+        resolver.load_spec(name, path)?;
+    }
+    if resolver.get_all_specs().is_empty() {
+        return Err(HornetError::ValidationError(
+            "No OpenAPI document to mock (use --openapi or --arazzo)".to_string(),
+        ));
+    }
+
+    println!("{}", "Starting mock server...".bright_blue());
+    for (name, spec) in resolver.get_all_specs() {
+        let operations: usize = spec
+            .paths
+            .iter()
+            .flatten()
+            .map(|(_, item)| item.methods().into_iter().count())
+            .sum();
+        println!("  {} {} ({} operation(s))", "✓".green(), name, operations);
+    }
+
+    let mut mock = Mock::new(resolver);
+    if let Some(ref spec) = arazzo {
+        mock = mock.with_workflows(spec);
+        println!(
+            "  {} successCriteria of {} step(s)",
+            "✓".green(),
+            mock.expectations().len()
+        );
+    }
+    if stateful {
+        mock = mock.stateful();
+        for resource in mock.resources() {
+            println!(
+                "  {} resource {} ({})",
+                "✓".green(),
+                resource.collection,
+                resource.item
+            );
+        }
+    }
+
+    let addr: SocketAddr = ([127, 0, 0, 1], port).into();
+    println!("  Listening on http://{}", addr);
+    println!("  Use `Prefer: code=404` to select a declared response");
+    println!();
+
+    start_mock_server(addr, mock).await
+}
@@ -1,15 +1,35 @@
+pub mod agent;
+pub mod baseline;
 pub mod convert;
 pub mod export_arazzo;
 pub mod export_openapi;
+pub mod fuzz;
+pub mod generate;
+pub mod import;
 pub mod list;
+pub mod load;
+pub mod mock;
+pub mod record;
+pub mod sample;
 pub mod serve;
 pub mod validate;
 pub mod visualize;
 
-pub use convert::{ConvertCommandArgs, RunCommandArgs, execute_convert, execute_run};
+pub use agent::{AgentCommandArgs, execute_agent};
+pub use baseline::BaselineArgs;
+pub use convert::{
+    ConvertCommandArgs, RunCommandArgs, execute_convert, execute_list_converters, execute_run,
+};
 pub use export_arazzo::execute_export_arazzo;
 pub use export_openapi::execute_export_openapi;
+pub use fuzz::{FuzzCommandArgs, execute_fuzz};
+pub use generate::{GenerateCommandArgs, execute_generate};
+pub use import::{ImportCommandArgs, execute_import};
 pub use list::execute_list;
+pub use load::{LoadCommandArgs, execute_load};
+pub use mock::{MockCommandArgs, execute_mock};
+pub use record::{RecordCommandArgs, execute_record};
+pub use sample::{SampleCommandArgs, execute_sample};
 pub use serve::execute_serve;
 pub use validate::execute_validate;
 pub use visualize::execute_visualize;
@@ -0,0 +1,99 @@
+//! 記録コマンドの実装
+//!
+//! 通信を記録するプロキシを起動し、Ctrl-C で止めたときに記録した通信を
+//! OpenAPI の operation に対応付けたワークフローとして Arazzo に書き出す。
+
+use super::import::{add_source_description, import_flows, load_or_new, load_resolver};
+use crate::error::{HornetError, Result};
+use crate::importers::{RecordedExchange, RecordedFlow};
+use crate::loader::{OpenApiResolver, save_arazzo};
+use crate::models::arazzo::ArazzoSpec;
+use crate::server::proxy::{RecordingProxy, start_recording_proxy};
+use colored::Colorize;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// `execute_record` に渡す引数をまとめた構造体
+pub struct RecordCommandArgs<'a> {
+    pub openapi_paths: &'a [PathBuf],
+    pub output_path: &'a Path,
+    /// リバースプロキシの転送先（なければフォワードプロキシ）
+    pub target: Option<&'a str>,
+    pub port: u16,
+    /// 書き出すワークフローの名前（ID の元になる）
+    pub name: &'a str,
+}
+
+/// 記録コマンドを実行する
+pub async fn execute_record(args: RecordCommandArgs<'_>) -> Result<()> {
+    // OpenAPI を読み込めることを先に確かめる
+    prepare(&args)?;
+
+    let proxy = Arc::new(RecordingProxy::new(args.target)?);
+    let addr: SocketAddr = ([127, 0, 0, 1], args.port).into();
+    println!("{}", "Recording traffic...".bright_blue());
+    match args.target {
+        Some(target) => println!("  Forwarding http://{} to {}", addr, target),
+        None => println!(
+            "  Set http_proxy=http://{} to record plain HTTP traffic",
+            addr
+        ),
+    }
+    println!("  Press Ctrl-C to stop and write the workflow");
+    println!();
+
+    start_recording_proxy(addr, proxy.clone()).await?;
+
+    println!();
+    save_recording(&args, proxy.exchanges())
+}
+
+/// 記録した通信をワークフローとして Arazzo に追記する
+pub fn save_recording(
+    args: &RecordCommandArgs<'_>,
+    exchanges: Vec<RecordedExchange>,
+) -> Result<()> {
+    if exchanges.is_empty() {
+        return Err(HornetError::ImportError(
+            "No traffic was recorded".to_string(),
+        ));
+    }
+    eprintln!("{} Recorded {} request(s)", "✓".green(), exchanges.len());
+
+    let (mut spec, resolver) = prepare(args)?;
+    let flow = RecordedFlow {
+        name: args.name.to_string(),
+        description: Some("Recorded with hornet2 record".to_string()),
+        exchanges,
+        variables: Vec::new(),
+    };
+    if import_flows(std::slice::from_ref(&flow), &resolver, &mut spec) == 0 {
+        return Err(HornetError::ImportError(
+            "No request matched an OpenAPI operation".to_string(),
+        ));
+    }
+
+    save_arazzo(args.output_path, &spec)?;
+    println!(
+        "{} Workflow written to {}",
+        "✓".green(),
+        args.output_path.display()
+    );
+    Ok(())
+}
+
+/// 出力先の Arazzo と、その sourceDescriptions の OpenAPI を読み込む
+fn prepare(args: &RecordCommandArgs<'_>) -> Result<(ArazzoSpec, OpenApiResolver)> {
+    let mut spec = load_or_new(args.output_path, args.name, "Recorded with hornet2 record")?;
+    let base_dir = args
+        .output_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    for openapi_path in args.openapi_paths {
+        add_source_description(&mut spec, openapi_path, base_dir);
+    }
+    let resolver = load_resolver(&spec, args.output_path)?;
+    Ok((spec, resolver))
+}
@@ -0,0 +1,46 @@
+//! サンプルコマンドの実装
+//!
+//! 操作のスキーマからリクエスト（パラメータとボディ）を合成し、JSONで表示する。
+
+use crate::error::Result;
+use crate::generator::sample::{SampleMode, SampleOptions, sample_operation};
+use crate::loader::OpenApiResolver;
+use std::path::PathBuf;
+
+/// `execute_sample` に渡す引数をまとめた構造体
+pub struct SampleCommandArgs<'a> {
+    pub openapi_paths: &'a [PathBuf],
+    pub operation_id: &'a str,
+    pub mode: SampleMode,
+    pub seed: u64,
+}
+
+/// サンプルコマンドを実行する
+pub fn execute_sample(args: SampleCommandArgs<'_>) -> Result<()> {
+    let SampleCommandArgs {
+        openapi_paths,
+        operation_id,
+        mode,
+        seed,
+    } = args;
+
+    // ソース名はファイル名（拡張子なし）にする
+    let mut resolver = OpenApiResolver::new(".");
+    for path in openapi_paths {
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("openapi");
+        resolver.load_spec(name, path)?;
+    }
+
+    let options = SampleOptions {
+        mode,
+        seed,
+        ..Default::default()
+    };
+    let sample = sample_operation(&resolver, operation_id, &options)?;
+    println!("{}", serde_json::to_string_pretty(&sample)?);
+
+    Ok(())
+}
@@ -0,0 +1,162 @@
+//! Helpers shared by the converters
+
+use super::ConvertOptions;
+use crate::error::{HornetError, Result};
+use crate::loader::OpenApiResolver;
+use crate::models::arazzo::{ArazzoSpec, Step, Workflow};
+
+/// Default base URL when neither the options nor the OpenAPI servers provide one
+pub const DEFAULT_BASE_URL: &str = "http://localhost:8080";
+
+/// Resolve the base URL: `--base-url`, then the first OpenAPI server
+pub fn resolve_base_url(resolver: &OpenApiResolver, options: &ConvertOptions) -> String {
+    options.base_url.clone().unwrap_or_else(|| {
+        resolver
+            .get_all_specs()
+            .values()
+            .next()
+            .and_then(|spec| spec.servers.first())
+            .map(|server| server.url.clone())
+            .unwrap_or_else(|| DEFAULT_BASE_URL.to_string())
+    })
+}
+
+/// Resolve the (path, METHOD) of the operation a step calls
+///
+/// `operationPath` may be given either as `"METHOD /path"` or as an Arazzo
+/// JSON Pointer (`{$sourceDescriptions.api.url}#/paths/~1users/get`).
+pub fn resolve_operation(step: &Step, resolver: &OpenApiResolver) -> Result<(String, String)> {
+    if let Some(ref op_id) = step.operation_id {
+        resolver
+            .find_operation(op_id)
+            .map(|op_ref| (op_ref.path, op_ref.method))
+            .ok_or_else(|| {
+                HornetError::OperationNotFound(format!("Operation '{}' not found", op_id))
+            })
+    } else if let Some(ref op_path) = step.operation_path {
+        parse_operation_path(op_path).ok_or_else(|| {
+            HornetError::ValidationError(format!("Invalid operationPath format: {}", op_path))
+        })
+    } else {
+        Err(HornetError::ValidationError(format!(
+            "Step '{}' has no operationId or operationPath",
+            step.step_id
+        )))
+    }
+}
+
+/// Parse an `operationPath` (or OpenAPI `operationRef`) into (path, METHOD)
+pub fn parse_operation_path(op_path: &str) -> Option<(String, String)> {
+    if let Some((_, pointer)) = op_path.split_once("#/paths/") {
+        let (path, method) = pointer.rsplit_once('/')?;
+        let path = path.replace("~1", "/").replace("~0", "~");
+        return Some((path, method.to_uppercase()));
+    }
+
+    let (method, path) = op_path.split_once(' ')?;
+    Some((path.trim().to_string(), method.to_uppercase()))
+}
+
+/// Find a workflow by id
+pub fn find_workflow<'a>(arazzo: &'a ArazzoSpec, workflow_id: &str) -> Result<&'a Workflow> {
+    arazzo
+        .workflows
+        .iter()
+        .find(|w| w.workflow_id == workflow_id)
+        .ok_or_else(|| {
+            HornetError::ValidationError(format!("Workflow '{}' not found", workflow_id))
+        })
+}
+
+/// Default values of the workflow inputs (`inputs.properties.*.default`)
+///
+/// Inputs without a default get an empty value of their declared type.
+pub fn input_defaults(workflow: &Workflow) -> Vec<(String, serde_json::Value)> {
+    let Some(serde_json::Value::Object(props)) =
+        workflow.inputs.as_ref().and_then(|i| i.get("properties"))
+    else {
+        return Vec::new();
+    };
+
+    props
+        .iter()
+        .map(|(name, schema)| {
+            let value = schema.get("default").cloned().unwrap_or_else(|| {
+                match schema.get("type").and_then(|t| t.as_str()) {
+                    Some("string") => serde_json::json!(""),
+                    Some("number") | Some("integer") => serde_json::json!(0),
+                    Some("boolean") => serde_json::json!(false),
+                    _ => serde_json::Value::Null,
+                }
+            });
+            (name.clone(), value)
+        })
+        .collect()
+}
+
+/// Parse a duration such as `30s`, `5m`, `1h30m` or `500ms`
+///
+/// A bare number is taken as seconds.
+pub fn parse_duration(s: &str) -> Option<std::time::Duration> {
+    let s = s.trim();
+    if let Ok(secs) = s.parse::<u64>() {
+        return Some(std::time::Duration::from_secs(secs));
+    }
+
+    let mut total = std::time::Duration::ZERO;
+    let mut rest = s;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let value: u64 = rest[..digits].parse().ok()?;
+        rest = &rest[digits..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let unit = match &rest[..unit_len] {
+            "ms" => std::time::Duration::from_millis(value),
+            "s" => std::time::Duration::from_secs(value),
+            "m" => std::time::Duration::from_secs(value * 60),
+            "h" => std::time::Duration::from_secs(value * 3600),
+            _ => return None,
+        };
+        total += unit;
+        rest = &rest[unit_len..];
+    }
+
+    (!s.is_empty()).then_some(total)
+}
+
+/// Target step of a `goto` action, if any
+pub fn goto_target(config: &indexmap::IndexMap<String, serde_json::Value>) -> Option<&str> {
+    config.get("stepId").and_then(|v| v.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_operation_path() {
+        assert_eq!(
+            parse_operation_path("post /users"),
+            Some(("/users".to_string(), "POST".to_string()))
+        );
+        assert_eq!(
+            parse_operation_path("{$sourceDescriptions.api.url}#/paths/~1users~1{id}/get"),
+            Some(("/users/{id}".to_string(), "GET".to_string()))
+        );
+        assert_eq!(parse_operation_path("users"), None);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        use std::time::Duration;
+
+        assert_eq!(parse_duration("30s"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_duration("1h30m"), Some(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_duration("45"), Some(Duration::from_secs(45)));
+        assert_eq!(parse_duration("5x"), None);
+        assert_eq!(parse_duration(""), None);
+    }
+}
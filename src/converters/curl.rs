@@ -0,0 +1,924 @@
+//! curl / bash script converter
+//!
+//! Generates a self-contained bash script that calls each step with `curl`
+//! and uses `jq` to build JSON bodies, extract outputs and evaluate
+//! successCriteria. Each workflow becomes a shell function running its steps
+//! as a small state machine, so `goto` and `end` actions behave as in the
+//! Arazzo document.
+//!
+//! Variables:
+//! - `BASE_URL` - base URL of the API
+//! - `INPUTS_<NAME>` - workflow inputs (defaults from the input schema)
+//! - `STEPS_<STEP_ID>_<OUTPUT>` - step outputs, stored as JSON
+
+use super::{ConvertOptions, Converter, common};
+use crate::error::Result;
+use crate::loader::OpenApiResolver;
+use crate::models::arazzo::{ArazzoSpec, RequestBody, Step, SuccessCriteria, Workflow};
+use crate::models::expression::{
+    ExpressionSource, RuntimeExpression, TemplateSegment, as_single_expression, parse_template,
+};
+use crate::payload::multipart::{self, PartContent};
+use crate::payload::{self, BodyEncoding, form, text_value, xml};
+use indexmap::IndexMap;
+use std::collections::HashSet;
+
+/// Helper functions shared by every generated script
+const SCRIPT_HELPERS: &str = r#"command -v jq >/dev/null || { echo "jq is required" >&2; exit 1; }
+
+WORKDIR=$(mktemp -d)
+trap 'rm -rf "$WORKDIR"' EXIT
+
+# JSON string of a text
+str() { jq -cn --arg v "$1" '$v'; }
+
+# Text of a JSON value (strings unquoted), optionally at a jq path
+text() { jq -r "${2:-.} | if type == \"string\" then . else tojson end" <<<"$1"; }
+
+# Percent-encode a path or query value
+urlencode() { jq -rn --arg v "$1" '$v | @uri'; }
+
+# Value at a jq path of the last response body, as JSON
+body() { jq -c "${1:-.}" "$WORKDIR/body" 2>/dev/null || echo null; }
+
+# Header of the last response, as JSON
+header() {
+    local value
+    value=$(grep -i "^$1:" "$WORKDIR/headers" | tail -n 1 | cut -d: -f2- | sed -e 's/^ *//' -e 's/\r$//' || true)
+    str "$value"
+}
+
+# Compare two JSON values: compare <left> <operator> <right>
+compare() { jq -en --argjson l "$1" --argjson r "$3" "\$l $2 \$r" >/dev/null; }
+
+# Test the text of a JSON value against a regular expression
+matches() { jq -en --argjson v "$1" --arg re "$2" '($v | if type == "string" then . else tojson end) | test($re)' >/dev/null; }
+
+# Run a check and report it: check <description> <command...>
+check() {
+    local description=$1
+    shift
+    if "$@"; then
+        echo "  ✓ $description" >&2
+    else
+        echo "  ✗ $description" >&2
+        return 1
+    fi
+}
+
+# Send a request: request <method> <url> [curl args...]
+request() {
+    local method=$1
+    url=$2
+    shift 2
+    echo "→ $method $url" >&2
+    local method_args=(-X "$method")
+    if [ "$method" = HEAD ]; then
+        method_args=(--head)
+    fi
+    status=$(curl -sS -o "$WORKDIR/body" -D "$WORKDIR/headers" -w '%{http_code}' "${method_args[@]}" "$url" "$@")
+}
+
+# Abort after a failed step
+fail() {
+    echo "✗ Step $1 failed (HTTP $status)" >&2
+    exit 1
+}"#;
+
+/// Converter for generating curl/bash scripts
+#[derive(Debug, Clone, Default)]
+pub struct CurlConverter;
+
+/// Per-workflow state used while generating steps
+struct WorkflowContext {
+    /// Inputs whose values are JSON rather than plain text
+    non_string_inputs: HashSet<String>,
+    /// Method of the step being generated (for `$method`)
+    method: String,
+}
+
+/// Arguments collected while building a jq program
+#[derive(Default)]
+struct JqArgs {
+    args: Vec<String>,
+}
+
+impl JqArgs {
+    /// Register a `--arg`/`--argjson` value and return its jq variable
+    fn push(&mut self, flag: &str, word: String) -> String {
+        let var = format!("v{}", self.args.len());
+        self.args.push(format!("{} {} {}", flag, var, word));
+        format!("${}", var)
+    }
+}
+
+impl CurlConverter {
+    /// Create a new CurlConverter
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Upper-case shell variable name from name parts
+    fn shell_var(parts: &[&str]) -> String {
+        parts
+            .iter()
+            .map(|part| {
+                part.chars()
+                    .map(|c| {
+                        if c.is_ascii_alphanumeric() {
+                            c.to_ascii_uppercase()
+                        } else {
+                            '_'
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("_")
+    }
+
+    fn input_var(name: &str) -> String {
+        Self::shell_var(&["inputs", name])
+    }
+
+    fn output_var(step_id: &str, output: &str) -> String {
+        Self::shell_var(&["steps", step_id, output])
+    }
+
+    /// Shell function running a workflow
+    fn workflow_fn(workflow_id: &str) -> String {
+        Self::shell_var(&["workflow", workflow_id]).to_lowercase()
+    }
+
+    /// Quote a string with single quotes
+    fn single_quote(s: &str) -> String {
+        format!("'{}'", s.replace('\'', r"'\''"))
+    }
+
+    /// Escape text for use inside double quotes
+    fn escape_dq(s: &str) -> String {
+        let mut out = String::with_capacity(s.len());
+        for c in s.chars() {
+            if matches!(c, '\\' | '"' | '$' | '`') {
+                out.push('\\');
+            }
+            out.push(c);
+        }
+        out
+    }
+
+    /// Render a property path as a jq path (`.a[0]["b-c"]`)
+    fn jq_path(path: &[String]) -> String {
+        let mut out = String::new();
+        for segment in path {
+            if segment.parse::<usize>().is_ok() {
+                if out.is_empty() {
+                    out.push('.');
+                }
+                out.push_str(&format!("[{}]", segment));
+            } else if !segment.is_empty()
+                && !segment.starts_with(|c: char| c.is_ascii_digit())
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            {
+                out.push('.');
+                out.push_str(segment);
+            } else {
+                if out.is_empty() {
+                    out.push('.');
+                }
+                out.push_str(&format!("[{}]", serde_json::Value::from(segment.as_str())));
+            }
+        }
+        if out.is_empty() { ".".to_string() } else { out }
+    }
+
+    /// Shell code (for use inside double quotes) producing the JSON value of
+    /// an expression
+    fn json_fragment(expr: &RuntimeExpression, context: &WorkflowContext) -> String {
+        match expr {
+            RuntimeExpression::StatusCode => "${status}".to_string(),
+            RuntimeExpression::Url => "$(str \"${url}\")".to_string(),
+            RuntimeExpression::Method => format!("$(str {})", context.method),
+            RuntimeExpression::Response(ExpressionSource::Body(path)) => {
+                format!("$(body {})", Self::single_quote(&Self::jq_path(path)))
+            }
+            RuntimeExpression::Response(ExpressionSource::Header(name)) => {
+                format!("$(header {})", Self::single_quote(name))
+            }
+            RuntimeExpression::Inputs(path) if !path.is_empty() => {
+                let var = Self::input_var(&path[0]);
+                if path.len() > 1 {
+                    format!(
+                        "$(jq -c {} <<<\"${{{}}}\")",
+                        Self::single_quote(&Self::jq_path(&path[1..])),
+                        var
+                    )
+                } else if context.non_string_inputs.contains(&path[0]) {
+                    format!("${{{}}}", var)
+                } else {
+                    format!("$(str \"${{{}}}\")", var)
+                }
+            }
+            RuntimeExpression::StepOutput {
+                step_id,
+                output,
+                path,
+            } => {
+                let var = Self::output_var(step_id, output);
+                if path.is_empty() {
+                    format!("${{{}}}", var)
+                } else {
+                    format!(
+                        "$(jq -c {} <<<\"${{{}}}\")",
+                        Self::single_quote(&Self::jq_path(path)),
+                        var
+                    )
+                }
+            }
+            other => Self::escape_dq(&serde_json::Value::from(other.to_string()).to_string()),
+        }
+    }
+
+    /// Shell code (for use inside double quotes) producing the text of an
+    /// expression
+    fn text_fragment(expr: &RuntimeExpression, context: &WorkflowContext) -> String {
+        match expr {
+            RuntimeExpression::StatusCode => "${status}".to_string(),
+            RuntimeExpression::Url => "${url}".to_string(),
+            RuntimeExpression::Method => context.method.clone(),
+            RuntimeExpression::Inputs(path) if path.len() == 1 => {
+                format!("${{{}}}", Self::input_var(&path[0]))
+            }
+            RuntimeExpression::StepOutput {
+                step_id,
+                output,
+                path,
+            } if path.is_empty() => {
+                format!("$(text \"${{{}}}\")", Self::output_var(step_id, output))
+            }
+            RuntimeExpression::Other(_) | RuntimeExpression::Outputs(_) => {
+                Self::escape_dq(&expr.to_string())
+            }
+            _ => format!("$(text \"{}\")", Self::json_fragment(expr, context)),
+        }
+    }
+
+    /// Double-quoted shell word for a string that may embed expressions
+    ///
+    /// With `encode`, literal text is kept as is and expression values are
+    /// percent-encoded (for URLs).
+    fn text_word(text: &str, context: &WorkflowContext, encode: bool) -> String {
+        let inner: String = parse_template(text)
+            .into_iter()
+            .map(|segment| match segment {
+                TemplateSegment::Literal(s) => Self::escape_dq(&s),
+                TemplateSegment::Expression(expr) if encode => {
+                    format!("$(urlencode \"{}\")", Self::text_fragment(&expr, context))
+                }
+                TemplateSegment::Expression(expr) => Self::text_fragment(&expr, context),
+            })
+            .collect();
+        format!("\"{}\"", inner)
+    }
+
+    fn value_word(value: &serde_json::Value, context: &WorkflowContext) -> String {
+        Self::text_word(&text_value(value), context, false)
+    }
+
+    /// Render a JSON payload as a jq program, collecting the values of
+    /// embedded expressions as jq arguments
+    fn jq_program(
+        value: &serde_json::Value,
+        context: &WorkflowContext,
+        args: &mut JqArgs,
+    ) -> String {
+        match value {
+            serde_json::Value::String(s) => {
+                if let Some(expr) = as_single_expression(value) {
+                    return args.push(
+                        "--argjson",
+                        format!("\"{}\"", Self::json_fragment(&expr, context)),
+                    );
+                }
+                let segments = parse_template(s);
+                if segments
+                    .iter()
+                    .all(|s| matches!(s, TemplateSegment::Literal(_)))
+                {
+                    return value.to_string();
+                }
+
+                let mut out = String::from("\"");
+                for segment in segments {
+                    match segment {
+                        TemplateSegment::Literal(text) => {
+                            let quoted = serde_json::Value::from(text).to_string();
+                            out.push_str(&quoted[1..quoted.len() - 1]);
+                        }
+                        TemplateSegment::Expression(expr) => {
+                            let var = args.push(
+                                "--arg",
+                                format!("\"{}\"", Self::text_fragment(&expr, context)),
+                            );
+                            out.push_str(&format!("\\({})", var));
+                        }
+                    }
+                }
+                out.push('"');
+                out
+            }
+            serde_json::Value::Array(items) => {
+                let items: Vec<String> = items
+                    .iter()
+                    .map(|v| Self::jq_program(v, context, args))
+                    .collect();
+                format!("[{}]", items.join(", "))
+            }
+            serde_json::Value::Object(obj) => {
+                let fields: Vec<String> = obj
+                    .iter()
+                    .map(|(k, v)| {
+                        format!(
+                            "{}: {}",
+                            serde_json::Value::from(k.as_str()),
+                            Self::jq_program(v, context, args)
+                        )
+                    })
+                    .collect();
+                format!("{{{}}}", fields.join(", "))
+            }
+            other => other.to_string(),
+        }
+    }
+
+    /// Generate the lines preparing the body and the curl arguments sending it
+    fn generate_body(
+        body: &RequestBody,
+        context: &WorkflowContext,
+    ) -> Result<(Vec<String>, Vec<String>)> {
+        let payload = payload::apply_replacements(body)?;
+        let encoding = BodyEncoding::from_content_type(body.content_type.as_deref());
+        let content_type = body
+            .content_type
+            .clone()
+            .unwrap_or_else(|| encoding.default_content_type().to_string());
+        let header = format!(
+            "-H {}",
+            Self::single_quote(&format!("Content-Type: {}", content_type))
+        );
+
+        let raw = |text: &str| {
+            (
+                vec![format!("body={}", Self::text_word(text, context, false))],
+                vec![header.clone(), "--data-binary \"$body\"".to_string()],
+            )
+        };
+
+        if let serde_json::Value::String(text) = &payload
+            && encoding != BodyEncoding::Json
+        {
+            return Ok(raw(text));
+        }
+
+        let result = match encoding {
+            BodyEncoding::Json => {
+                let mut args = JqArgs::default();
+                let program = Self::jq_program(&payload, context, &mut args);
+                let mut command = vec!["jq -n".to_string()];
+                command.extend(args.args);
+                command.push(Self::single_quote(&program));
+                (
+                    vec![format!("body=$({})", command.join(" "))],
+                    vec![header, "--data-binary \"$body\"".to_string()],
+                )
+            }
+            BodyEncoding::FormUrlEncoded => {
+                let args = form::flatten(&payload)?
+                    .iter()
+                    .map(|(key, value)| {
+                        format!(
+                            "--data-urlencode {}",
+                            Self::text_word(
+                                &format!("{}={}", key, text_value(value)),
+                                context,
+                                false
+                            )
+                        )
+                    })
+                    .collect();
+                (Vec::new(), args)
+            }
+            // curl generates the multipart boundary itself
+            BodyEncoding::Multipart => {
+                let args = multipart::collect_parts(&payload)?
+                    .into_iter()
+                    .map(|part| {
+                        let name = Self::escape_dq(&part.name);
+                        match part.content {
+                            PartContent::Field(value) => format!(
+                                "--form-string \"{}=\"{}",
+                                name,
+                                Self::value_word(&value, context)
+                            ),
+                            PartContent::Json(value) => {
+                                let mut args = JqArgs::default();
+                                let program = Self::jq_program(&value, context, &mut args);
+                                let mut command = vec!["jq -c -n".to_string()];
+                                command.extend(args.args);
+                                command.push(Self::single_quote(&program));
+                                format!(
+                                    "-F \"{}=$({});type=application/json\"",
+                                    name,
+                                    command.join(" ")
+                                )
+                            }
+                            PartContent::File(file) => format!(
+                                "-F {}",
+                                Self::single_quote(&format!(
+                                    "{}=@{};filename={};type={}",
+                                    part.name,
+                                    file.path,
+                                    file.file_name(),
+                                    file.mime_type()
+                                ))
+                            ),
+                        }
+                    })
+                    .collect();
+                (Vec::new(), args)
+            }
+            BodyEncoding::Xml => raw(&xml::to_xml(&payload)?),
+            BodyEncoding::Raw => raw(&text_value(&payload)),
+        };
+
+        Ok(result)
+    }
+
+    /// Shell command evaluating a criterion, or `None` if it cannot be evaluated
+    fn criterion_command(crit: &SuccessCriteria, context: &WorkflowContext) -> Option<String> {
+        if crit.criteria_type.as_deref() == Some("jsonpath") {
+            return None;
+        }
+
+        let left = match RuntimeExpression::parse(&crit.context) {
+            Some(expr) => format!("\"{}\"", Self::json_fragment(&expr, context)),
+            None => Self::single_quote(&serde_json::Value::from(crit.context.as_str()).to_string()),
+        };
+
+        if crit.criteria_type.as_deref() == Some("regex") {
+            return Some(format!(
+                "matches {} {}",
+                left,
+                Self::single_quote(&crit.condition)
+            ));
+        }
+
+        let operator = match crit.condition.as_str() {
+            "==" | "$eq" => "==",
+            "!=" | "$ne" => "!=",
+            ">" | "$gt" => ">",
+            ">=" | "$gte" => ">=",
+            "<" | "$lt" => "<",
+            "<=" | "$lte" => "<=",
+            _ => "==",
+        };
+
+        let right = match crit.value {
+            Some(ref value) => match as_single_expression(value) {
+                Some(expr) => format!("\"{}\"", Self::json_fragment(&expr, context)),
+                None => Self::single_quote(&value.to_string()),
+            },
+            None => "'true'".to_string(),
+        };
+
+        Some(format!(
+            "compare {} {} {}",
+            left,
+            Self::single_quote(operator),
+            right
+        ))
+    }
+
+    fn criterion_description(crit: &SuccessCriteria) -> String {
+        match crit.value {
+            Some(ref value) => format!("{} {} {}", crit.context, crit.condition, text_value(value)),
+            None => format!("{} {}", crit.context, crit.condition),
+        }
+    }
+
+    /// Generate the `check` lines for successCriteria
+    fn generate_checks(criteria: &[SuccessCriteria], context: &WorkflowContext) -> Vec<String> {
+        criteria
+            .iter()
+            .map(|crit| {
+                let description = Self::single_quote(&Self::criterion_description(crit));
+                match Self::criterion_command(crit, context) {
+                    Some(command) => {
+                        format!("check {} {} || passed=false", description, command)
+                    }
+                    None => format!("echo \"  - skipped \"{} >&2", description),
+                }
+            })
+            .collect()
+    }
+
+    /// Generate the assignments storing step outputs
+    fn generate_outputs(step: &Step, context: &WorkflowContext) -> Vec<String> {
+        let Some(serde_json::Value::Object(outputs)) = step.outputs.as_ref() else {
+            return Vec::new();
+        };
+
+        outputs
+            .iter()
+            .map(|(name, expr)| {
+                let value = match as_single_expression(expr) {
+                    Some(expr) => format!("\"{}\"", Self::json_fragment(&expr, context)),
+                    None => Self::single_quote(&expr.to_string()),
+                };
+                format!("{}={}", Self::output_var(&step.step_id, name), value)
+            })
+            .collect()
+    }
+
+    /// Generate the lines running onSuccess/onFailure actions
+    fn generate_actions<'a>(
+        actions: impl Iterator<Item = (&'a str, &'a IndexMap<String, serde_json::Value>)>,
+        context: &WorkflowContext,
+    ) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        for (action_type, config) in actions {
+            let jump = match action_type {
+                "goto" => match common::goto_target(config) {
+                    Some(target) => format!("next={}; continue", Self::single_quote(target)),
+                    None => {
+                        lines.push("# goto to another workflow is not supported".to_string());
+                        continue;
+                    }
+                },
+                "end" => "next=''; continue".to_string(),
+                other => {
+                    lines.push(format!("# {} actions are not supported", other));
+                    continue;
+                }
+            };
+
+            let criteria: Vec<SuccessCriteria> = config
+                .get("criteria")
+                .and_then(|c| serde_json::from_value(c.clone()).ok())
+                .unwrap_or_default();
+            let conditions: Vec<String> = criteria
+                .iter()
+                .filter_map(|crit| Self::criterion_command(crit, context))
+                .collect();
+
+            if conditions.is_empty() {
+                lines.push(jump);
+            } else {
+                lines.push(format!("if {}; then", conditions.join(" && ")));
+                lines.push(format!("    {}", jump));
+                lines.push("fi".to_string());
+            }
+        }
+
+        lines
+    }
+
+    /// Generate the `case` branch of a step
+    fn generate_step(
+        &self,
+        step: &Step,
+        next_step: Option<&str>,
+        resolver: &OpenApiResolver,
+        context: &mut WorkflowContext,
+    ) -> Result<Vec<String>> {
+        let (path, method) = common::resolve_operation(step, resolver)?;
+        context.method = method.clone();
+
+        let mut path = path;
+        let mut query = Vec::new();
+        let mut curl_args = Vec::new();
+        let mut cookies = Vec::new();
+
+        for param in &step.parameters {
+            let text = text_value(&param.value);
+            match param.location.as_str() {
+                "path" => {
+                    let word = Self::text_word(&text, context, true);
+                    path = path.replace(&format!("{{{}}}", param.name), &word[1..word.len() - 1]);
+                }
+                "query" => {
+                    let word = Self::text_word(&text, context, true);
+                    query.push(format!(
+                        "{}={}",
+                        Self::escape_dq(&form::escape(&param.name)),
+                        &word[1..word.len() - 1]
+                    ));
+                }
+                "header" => curl_args.push(format!(
+                    "-H {}",
+                    Self::text_word(&format!("{}: {}", param.name, text), context, false)
+                )),
+                "cookie" => cookies.push(format!("{}={}", param.name, text)),
+                _ => {}
+            }
+        }
+        if !cookies.is_empty() {
+            curl_args.push(format!(
+                "-b {}",
+                Self::text_word(&cookies.join("; "), context, false)
+            ));
+        }
+
+        let mut lines = Vec::new();
+        if let Some(ref description) = step.description {
+            lines.push(format!(
+                "# {}",
+                description.lines().next().unwrap_or_default()
+            ));
+        }
+
+        if let Some(ref request_body) = step.request_body {
+            let (prepare, args) = Self::generate_body(request_body, context)?;
+            lines.extend(prepare);
+            curl_args.extend(args);
+        }
+
+        let mut url = format!("\"${{BASE_URL}}{}", path);
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(&query.join("&"));
+        }
+        url.push('"');
+
+        let mut request = vec!["request".to_string(), method, url];
+        request.extend(curl_args);
+        lines.push(request.join(" "));
+
+        lines.push("passed=true".to_string());
+        lines.extend(Self::generate_checks(
+            step.success_criteria.as_deref().unwrap_or(&[]),
+            context,
+        ));
+
+        let next = format!("next={}", Self::single_quote(next_step.unwrap_or_default()));
+        lines.push("if $passed; then".to_string());
+        let mut on_success = Self::generate_outputs(step, context);
+        on_success.extend(Self::generate_actions(
+            step.on_success
+                .iter()
+                .flatten()
+                .map(|a| (a.action_type.as_str(), &a.config)),
+            context,
+        ));
+        on_success.push(next);
+        lines.extend(on_success.into_iter().map(|l| format!("    {}", l)));
+
+        lines.push("else".to_string());
+        let mut on_failure = Self::generate_actions(
+            step.on_failure
+                .iter()
+                .flatten()
+                .map(|a| (a.action_type.as_str(), &a.config)),
+            context,
+        );
+        on_failure.push(format!("fail {}", Self::single_quote(&step.step_id)));
+        lines.extend(on_failure.into_iter().map(|l| format!("    {}", l)));
+        lines.push("fi".to_string());
+
+        Ok(lines)
+    }
+
+    /// Generate the shell function running a workflow
+    fn generate_workflow(&self, workflow: &Workflow, resolver: &OpenApiResolver) -> Result<String> {
+        let defaults = common::input_defaults(workflow);
+        let mut context = WorkflowContext {
+            non_string_inputs: defaults
+                .iter()
+                .filter(|(_, value)| !value.is_string() && !value.is_null())
+                .map(|(name, _)| name.clone())
+                .collect(),
+            method: String::new(),
+        };
+
+        let mut lines = Vec::new();
+        if let Some(ref summary) = workflow.summary {
+            lines.push(format!("# {}", summary));
+        }
+        lines.push(format!("{}() {{", Self::workflow_fn(&workflow.workflow_id)));
+        lines.push(format!(
+            "    echo {} >&2",
+            Self::single_quote(&format!("▶ Workflow {}", workflow.workflow_id))
+        ));
+        for (name, value) in &defaults {
+            let var = Self::input_var(name);
+            lines.push(format!(
+                "    local {}=\"${{{}:-{}}}\"",
+                var,
+                var,
+                Self::escape_dq(&text_value(value))
+            ));
+        }
+
+        let first = workflow
+            .steps
+            .first()
+            .map(|s| s.step_id.as_str())
+            .unwrap_or_default();
+        lines.push(format!("    local next={}", Self::single_quote(first)));
+        lines.push("    while [ -n \"$next\" ]; do".to_string());
+        lines.push("        case \"$next\" in".to_string());
+
+        for (i, step) in workflow.steps.iter().enumerate() {
+            let next_step = workflow.steps.get(i + 1).map(|s| s.step_id.as_str());
+            lines.push(format!(
+                "            {})",
+                Self::single_quote(&step.step_id)
+            ));
+            for line in self.generate_step(step, next_step, resolver, &mut context)? {
+                lines.push(format!("                {}", line));
+            }
+            lines.push("                ;;".to_string());
+        }
+
+        lines.push("            *)".to_string());
+        lines.push("                echo \"Unknown step: $next\" >&2".to_string());
+        lines.push("                exit 1".to_string());
+        lines.push("                ;;".to_string());
+        lines.push("        esac".to_string());
+        lines.push("    done".to_string());
+        lines.push("}".to_string());
+
+        Ok(lines.join("\n"))
+    }
+
+    /// Assemble the script from the workflow functions
+    fn render(
+        &self,
+        title: &str,
+        workflows: &[&Workflow],
+        resolver: &OpenApiResolver,
+        options: &ConvertOptions,
+    ) -> Result<String> {
+        let mut sections = vec![
+            format!("#!/usr/bin/env bash\n# {}\n\nset -euo pipefail", title),
+            format!(
+                "BASE_URL=\"${{BASE_URL:-{}}}\"",
+                Self::escape_dq(&common::resolve_base_url(resolver, options))
+            ),
+            SCRIPT_HELPERS.to_string(),
+        ];
+
+        for workflow in workflows {
+            sections.push(self.generate_workflow(workflow, resolver)?);
+        }
+
+        let mut main: Vec<String> = workflows
+            .iter()
+            .map(|w| Self::workflow_fn(&w.workflow_id))
+            .collect();
+        main.push("echo \"✓ All workflows passed\" >&2".to_string());
+        sections.push(main.join("\n"));
+
+        let mut script = sections.join("\n\n");
+        script.push('\n');
+        Ok(script)
+    }
+}
+
+impl Converter for CurlConverter {
+    type Output = String;
+
+    fn convert_spec(
+        &self,
+        arazzo: &ArazzoSpec,
+        resolver: &OpenApiResolver,
+        options: &ConvertOptions,
+    ) -> Result<Self::Output> {
+        let workflows: Vec<&Workflow> = arazzo.workflows.iter().collect();
+        self.render(&arazzo.info.title, &workflows, resolver, options)
+    }
+
+    fn convert_workflow(
+        &self,
+        workflow: &Workflow,
+        resolver: &OpenApiResolver,
+        options: &ConvertOptions,
+    ) -> Result<Self::Output> {
+        let title = workflow
+            .summary
+            .clone()
+            .unwrap_or_else(|| workflow.workflow_id.clone());
+        self.render(&title, &[workflow], resolver, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loader::arazzo::load_arazzo;
+    use serde_json::json;
+    use std::path::Path;
+
+    fn context() -> WorkflowContext {
+        WorkflowContext {
+            non_string_inputs: HashSet::from(["age".to_string()]),
+            method: "POST".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_text_word() {
+        let context = context();
+        assert_eq!(
+            CurlConverter::text_word("Bearer $steps.login.outputs.token", &context, false),
+            "\"Bearer $(text \"${STEPS_LOGIN_TOKEN}\")\""
+        );
+        assert_eq!(
+            CurlConverter::text_word("$inputs.name costs $5", &context, true),
+            "\"$(urlencode \"${INPUTS_NAME}\") costs \\$5\""
+        );
+        assert_eq!(
+            CurlConverter::jq_path(&["items".to_string(), "0".to_string(), "a-b".to_string()]),
+            ".items[0][\"a-b\"]"
+        );
+    }
+
+    #[test]
+    fn test_json_body_uses_jq_arguments() {
+        let body = RequestBody {
+            content_type: Some("application/json".to_string()),
+            payload: json!({
+                "name": "$inputs.name",
+                "age": "$inputs.age",
+                "greeting": "Hello $inputs.name"
+            }),
+            replacements: Vec::new(),
+        };
+        let (prepare, args) = CurlConverter::generate_body(&body, &context()).unwrap();
+
+        assert_eq!(
+            prepare,
+            vec![
+                "body=$(jq -n --argjson v0 \"${INPUTS_AGE}\" --arg v1 \"${INPUTS_NAME}\" --argjson v2 \"$(str \"${INPUTS_NAME}\")\" '{\"age\": $v0, \"greeting\": \"Hello \\($v1)\", \"name\": $v2}')"
+            ]
+        );
+        assert_eq!(args[0], "-H 'Content-Type: application/json'");
+    }
+
+    #[test]
+    fn test_generate_actions() {
+        let mut config = IndexMap::new();
+        config.insert("stepId".to_string(), json!("login"));
+        config.insert(
+            "criteria".to_string(),
+            json!([{"context": "$statusCode", "condition": "==", "value": 401}]),
+        );
+        let end = IndexMap::new();
+
+        let lines = CurlConverter::generate_actions(
+            [("goto", &config), ("end", &end)].into_iter(),
+            &context(),
+        );
+        assert_eq!(
+            lines,
+            vec![
+                "if compare \"${status}\" '==' '401'; then",
+                "    next='login'; continue",
+                "fi",
+                "next=''; continue",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convert_workflow_integration() {
+        let arazzo = load_arazzo("tests/fixtures/arazzo.yaml").unwrap();
+
+        let mut resolver = OpenApiResolver::new(Path::new("tests/fixtures"));
+        resolver
+            .load_spec("userAPI", Path::new("tests/fixtures/openapi.yaml"))
+            .unwrap();
+
+        let options = ConvertOptions {
+            base_url: Some("https://api.test".to_string()),
+            ..Default::default()
+        };
+        let script = CurlConverter::new()
+            .convert_workflow(&arazzo.workflows[0], &resolver, &options)
+            .unwrap();
+
+        assert!(script.starts_with("#!/usr/bin/env bash"));
+        assert!(script.contains("BASE_URL=\"${BASE_URL:-https://api.test}\""));
+        assert!(script.contains("local INPUTS_USERNAME=\"${INPUTS_USERNAME:-testuser}\""));
+        assert!(script.contains("STEPS_REGISTER_USERID=\"$(body '.id')\""));
+        assert!(script.contains("check '$statusCode == 201' compare \"${status}\" '==' '201'"));
+        assert!(
+            script
+                .trim_end()
+                .ends_with("echo \"✓ All workflows passed\" >&2")
+        );
+    }
+}
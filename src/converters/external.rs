@@ -0,0 +1,258 @@
+//! External converters (`hornet2-convert-<name>` executables)
+//!
+//! An external converter is run with no arguments. It receives a JSON
+//! document on stdin:
+//!
+//! ```json
+//! {
+//!   "protocolVersion": 1,
+//!   "target": "<name>",
+//!   "workflowId": null,
+//!   "options": { "baseUrl": "http://localhost:8080", "vus": null, "duration": null, "iterations": null },
+//!   "arazzo": { "...": "the Arazzo document" },
+//!   "operations": {
+//!     "<workflowId>": {
+//!       "<stepId>": { "source": "api", "method": "POST", "path": "/users", "operation": { "...": "OpenAPI operation" } }
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! `options.baseUrl` is already resolved (`--base-url`, then the first OpenAPI
+//! server) and `operations` holds the resolved operation of every step that
+//! calls one. The converter writes `{"files": [{"path": "...", "content": "..."}]}`
+//! to stdout and exits with status 0; on failure it exits non-zero and
+//! explains why on stderr.
+
+use super::common;
+use super::registry::{ConvertInput, DynConverter, OutputFile};
+use crate::error::{HornetError, Result};
+use crate::loader::OpenApiResolver;
+use crate::models::arazzo::Step;
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Prefix of external converter executables
+pub const EXTERNAL_PREFIX: &str = "hornet2-convert-";
+
+/// Version of the stdin/stdout protocol
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Converter delegating to an external executable
+#[derive(Debug, Clone)]
+pub struct ExternalConverter {
+    name: String,
+    path: PathBuf,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExternalResponse {
+    files: Vec<OutputFile>,
+}
+
+impl ExternalConverter {
+    /// Create a converter running the executable at `path`
+    pub fn new(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            name: name.into(),
+            path: path.into(),
+        }
+    }
+
+    /// Build the document sent to the converter on stdin
+    pub fn build_document(&self, input: &ConvertInput<'_>) -> Result<Value> {
+        let mut operations = Map::new();
+        for workflow in &input.arazzo.workflows {
+            if input
+                .workflow_id
+                .is_some_and(|id| id != workflow.workflow_id)
+            {
+                continue;
+            }
+
+            let steps: Map<String, Value> = workflow
+                .steps
+                .iter()
+                .filter_map(|step| {
+                    resolve_step(step, input.resolver).map(|op| (step.step_id.clone(), op))
+                })
+                .collect();
+            operations.insert(workflow.workflow_id.clone(), Value::Object(steps));
+        }
+
+        Ok(json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "target": self.name,
+            "workflowId": input.workflow_id,
+            "options": {
+                "baseUrl": common::resolve_base_url(input.resolver, input.options),
+                "vus": input.options.vus,
+                "duration": input.options.duration,
+                "iterations": input.options.iterations,
+            },
+            "arazzo": serde_json::to_value(input.arazzo)?,
+            "operations": operations,
+        }))
+    }
+}
+
+/// Resolved operation of a step (`None` for steps calling a workflow)
+fn resolve_step(step: &Step, resolver: &OpenApiResolver) -> Option<Value> {
+    let (path, method) = common::resolve_operation(step, resolver).ok()?;
+
+    let details = match step.operation_id {
+        Some(ref op_id) => resolver.find_operation_with_details(op_id),
+        None => resolver.find_operation_by_path_with_details(&path, &method),
+    };
+
+    let mut op = json!({ "method": method, "path": path });
+    if let Some((op_ref, operation)) = details {
+        op["source"] = json!(op_ref.source_name);
+        op["operation"] = serde_json::to_value(operation).unwrap_or(Value::Null);
+    }
+    Some(op)
+}
+
+impl DynConverter for ExternalConverter {
+    fn convert(&self, input: &ConvertInput<'_>) -> Result<Vec<OutputFile>> {
+        let document = serde_json::to_vec(&self.build_document(input)?)?;
+
+        let mut child = Command::new(&self.path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| {
+                HornetError::ConverterError(format!(
+                    "Failed to start {}: {}",
+                    self.path.display(),
+                    e
+                ))
+            })?;
+
+        // 大きな出力でデッドロックしないよう、書き込みは別スレッドで行う
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let writer = std::thread::spawn(move || stdin.write_all(&document));
+        let output = child.wait_with_output()?;
+        // 変換器が入力を読まずに終了した場合の BrokenPipe は無視する
+        let _ = writer.join();
+
+        if !output.status.success() {
+            return Err(HornetError::ConverterError(format!(
+                "Converter '{}' failed ({}): {}",
+                self.name,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let response: ExternalResponse = serde_json::from_slice(&output.stdout).map_err(|e| {
+            HornetError::ConverterError(format!(
+                "Converter '{}' returned an invalid response: {}",
+                self.name, e
+            ))
+        })?;
+        if response.files.is_empty() {
+            return Err(HornetError::ConverterError(format!(
+                "Converter '{}' returned no files",
+                self.name
+            )));
+        }
+
+        Ok(response.files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converters::ConvertOptions;
+    use crate::loader::arazzo::load_arazzo;
+    use crate::models::arazzo::ArazzoSpec;
+    use std::path::Path;
+
+    fn load_fixture() -> (ArazzoSpec, OpenApiResolver) {
+        let arazzo = load_arazzo("tests/fixtures/arazzo.yaml").unwrap();
+        let mut resolver = OpenApiResolver::new(Path::new("tests/fixtures"));
+        resolver
+            .load_spec("userAPI", Path::new("tests/fixtures/openapi.yaml"))
+            .unwrap();
+        (arazzo, resolver)
+    }
+
+    #[test]
+    fn test_build_document() {
+        let (arazzo, resolver) = load_fixture();
+        let converter = ExternalConverter::new("custom", "/bin/false");
+        let doc = converter
+            .build_document(&ConvertInput {
+                arazzo: &arazzo,
+                resolver: &resolver,
+                options: &ConvertOptions {
+                    base_url: Some("http://api.test".to_string()),
+                    ..Default::default()
+                },
+                workflow_id: None,
+            })
+            .unwrap();
+
+        assert_eq!(doc["protocolVersion"], 1);
+        assert_eq!(doc["options"]["baseUrl"], "http://api.test");
+        assert_eq!(doc["arazzo"]["info"]["title"], json!(arazzo.info.title));
+
+        let workflow = &arazzo.workflows[0];
+        let step = &workflow.steps[0];
+        let op = &doc["operations"][&workflow.workflow_id][&step.step_id];
+        assert_eq!(op["source"], "userAPI");
+        assert!(op["method"].is_string());
+        assert_eq!(op["operation"]["operationId"], json!(step.operation_id));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_external_converter_round_trip() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("hornet2-convert-echo");
+        std::fs::write(
+            &script,
+            r#"#!/bin/sh
+input=$(cat)
+case "$input" in
+  *'"protocolVersion":1'*) printf '{"files":[{"path":"a/out.txt","content":"ok"}]}' ;;
+  *) echo "bad input" >&2; exit 3 ;;
+esac
+"#,
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let (arazzo, resolver) = load_fixture();
+        let input = ConvertInput {
+            arazzo: &arazzo,
+            resolver: &resolver,
+            options: &ConvertOptions::default(),
+            workflow_id: None,
+        };
+
+        let files = ExternalConverter::new("echo", &script)
+            .convert(&input)
+            .unwrap();
+        assert_eq!(
+            files,
+            vec![OutputFile {
+                path: "a/out.txt".to_string(),
+                content: "ok".to_string()
+            }]
+        );
+
+        let err = ExternalConverter::new("false", "/bin/false")
+            .convert(&input)
+            .unwrap_err();
+        assert!(err.to_string().contains("Converter 'false' failed"));
+    }
+}
@@ -0,0 +1,768 @@
+//! Hurl converter
+//!
+//! Each step becomes a Hurl entry. Step outputs are captured into variables
+//! named `<stepId>_<output>`, workflow inputs are variables named
+//! `inputs_<name>` whose defaults are set in the `[Options]` section of the
+//! workflow's first entry, and successCriteria become `[Asserts]`.
+//!
+//! Hurl runs entries strictly in order, so `goto` and `end` actions are only
+//! kept as comments.
+
+use super::{ConvertOptions, Converter, common};
+use crate::error::Result;
+use crate::loader::OpenApiResolver;
+use crate::models::arazzo::{ArazzoSpec, RequestBody, Step, SuccessCriteria, Workflow};
+use crate::models::expression::{
+    ExpressionSource, RuntimeExpression, TemplateSegment, as_single_expression, parse_template,
+};
+use crate::payload::multipart::{self, PartContent};
+use crate::payload::{self, BodyEncoding, form, text_value, xml};
+use indexmap::IndexMap;
+use std::collections::HashSet;
+
+/// Converter for generating Hurl files
+#[derive(Debug, Clone, Default)]
+pub struct HurlConverter;
+
+/// Per-workflow state used while generating entries
+struct WorkflowContext {
+    /// Inputs whose values are not strings (rendered unquoted in JSON bodies)
+    non_string_inputs: HashSet<String>,
+    /// Nested paths of step outputs referenced later, by step id
+    nested_refs: IndexMap<String, Vec<(String, Vec<String>)>>,
+}
+
+impl HurlConverter {
+    /// Create a new HurlConverter
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Join name parts into a valid Hurl variable name
+    fn var_name(parts: &[&str]) -> String {
+        parts
+            .iter()
+            .map(|part| {
+                part.chars()
+                    .map(|c| {
+                        if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                            c
+                        } else {
+                            '_'
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("_")
+    }
+
+    /// Variable holding a workflow input (nested paths must be provided separately)
+    fn input_var(path: &[String]) -> String {
+        let mut parts = vec!["inputs"];
+        parts.extend(path.iter().map(String::as_str));
+        Self::var_name(&parts)
+    }
+
+    /// Variable holding a step output, or a nested value of it
+    fn output_var(step_id: &str, output: &str, path: &[String]) -> String {
+        let mut parts = vec![step_id, output];
+        parts.extend(path.iter().map(String::as_str));
+        Self::var_name(&parts)
+    }
+
+    /// Quote a string for Hurl (`"..."`)
+    fn quote(s: &str) -> String {
+        let mut out = String::with_capacity(s.len() + 2);
+        out.push('"');
+        for c in s.chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                _ => out.push(c),
+            }
+        }
+        out.push('"');
+        out
+    }
+
+    /// Escape an unquoted value of a header or section line
+    fn escape_value(s: &str) -> String {
+        s.replace('\\', "\\\\").replace('#', "\\#")
+    }
+
+    /// Render a property path as a JSONPath query (`$.a[0]['b-c']`)
+    fn jsonpath(path: &[String]) -> String {
+        let mut out = "$".to_string();
+        for segment in path {
+            if segment.parse::<usize>().is_ok() {
+                out.push_str(&format!("[{}]", segment));
+            } else if !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            {
+                out.push('.');
+                out.push_str(segment);
+            } else {
+                out.push_str(&format!("['{}']", segment.replace('\'', "\\'")));
+            }
+        }
+        out
+    }
+
+    /// Hurl query reading the value an expression refers to in the response
+    fn response_query(expr: &RuntimeExpression) -> Option<String> {
+        match expr {
+            RuntimeExpression::StatusCode => Some("status".to_string()),
+            RuntimeExpression::Url => Some("url".to_string()),
+            RuntimeExpression::Response(ExpressionSource::Body(path)) if path.is_empty() => {
+                Some("body".to_string())
+            }
+            RuntimeExpression::Response(ExpressionSource::Body(path)) => {
+                Some(format!("jsonpath {}", Self::quote(&Self::jsonpath(path))))
+            }
+            RuntimeExpression::Response(ExpressionSource::Header(name)) => {
+                Some(format!("header {}", Self::quote(name)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Variable an expression is available as, if any
+    fn expr_variable(expr: &RuntimeExpression) -> Option<String> {
+        match expr {
+            RuntimeExpression::Inputs(path) if !path.is_empty() => Some(Self::input_var(path)),
+            RuntimeExpression::StepOutput {
+                step_id,
+                output,
+                path,
+            } => Some(Self::output_var(step_id, output, path)),
+            _ => None,
+        }
+    }
+
+    /// Convert a string that may embed runtime expressions into Hurl
+    /// `{{variable}}` syntax
+    fn template(text: &str) -> String {
+        parse_template(text)
+            .into_iter()
+            .map(|segment| match segment {
+                TemplateSegment::Literal(s) => s,
+                TemplateSegment::Expression(expr) => match Self::expr_variable(&expr) {
+                    Some(var) => format!("{{{{{}}}}}", var),
+                    None => expr.to_string(),
+                },
+            })
+            .collect()
+    }
+
+    fn value_template(value: &serde_json::Value) -> String {
+        Self::template(&text_value(value))
+    }
+
+    /// Render a JSON payload with `{{variable}}` placeholders
+    ///
+    /// Non-string inputs are rendered unquoted so that they keep their type;
+    /// everything else is rendered inside a JSON string.
+    fn templated_json(value: &serde_json::Value, context: &WorkflowContext) -> Result<String> {
+        let mut unquoted = Vec::new();
+        let templated = Self::mark_json(value, context, &mut unquoted);
+
+        let mut json = serde_json::to_string_pretty(&templated)?;
+        for (i, var) in unquoted.iter().enumerate() {
+            json = json.replace(
+                &format!("\"__hornet_var_{}__\"", i),
+                &format!("{{{{{}}}}}", var),
+            );
+        }
+        Ok(json)
+    }
+
+    fn mark_json(
+        value: &serde_json::Value,
+        context: &WorkflowContext,
+        unquoted: &mut Vec<String>,
+    ) -> serde_json::Value {
+        match value {
+            serde_json::Value::String(s) => {
+                if let Some(RuntimeExpression::Inputs(path)) = as_single_expression(value)
+                    && path.len() == 1
+                    && context.non_string_inputs.contains(&path[0])
+                {
+                    unquoted.push(Self::input_var(&path));
+                    return serde_json::json!(format!("__hornet_var_{}__", unquoted.len() - 1));
+                }
+                serde_json::Value::String(Self::template(s))
+            }
+            serde_json::Value::Array(items) => serde_json::Value::Array(
+                items
+                    .iter()
+                    .map(|v| Self::mark_json(v, context, unquoted))
+                    .collect(),
+            ),
+            serde_json::Value::Object(obj) => serde_json::Value::Object(
+                obj.iter()
+                    .map(|(k, v)| (k.clone(), Self::mark_json(v, context, unquoted)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    /// Render text as a Hurl multiline string body
+    fn multiline_body(text: &str) -> Vec<String> {
+        let mut lines = vec!["```".to_string()];
+        lines.extend(text.lines().map(|l| l.to_string()));
+        lines.push("```".to_string());
+        lines
+    }
+
+    /// Generate the sections and body lines of a request body
+    ///
+    /// Returns `(content_type_header, sections, body)`.
+    fn generate_body(
+        body: &RequestBody,
+        context: &WorkflowContext,
+    ) -> Result<(Option<String>, Vec<String>, Vec<String>)> {
+        let payload = payload::apply_replacements(body)?;
+        let encoding = BodyEncoding::from_content_type(body.content_type.as_deref());
+        let content_type = body
+            .content_type
+            .clone()
+            .unwrap_or_else(|| encoding.default_content_type().to_string());
+
+        if let serde_json::Value::String(text) = &payload
+            && encoding != BodyEncoding::Json
+        {
+            return Ok((
+                Some(content_type),
+                Vec::new(),
+                Self::multiline_body(&Self::template(text)),
+            ));
+        }
+
+        let result = match encoding {
+            BodyEncoding::Json => {
+                let json = Self::templated_json(&payload, context)?;
+                (
+                    Some(content_type),
+                    Vec::new(),
+                    json.lines().map(|l| l.to_string()).collect(),
+                )
+            }
+            // Hurl sets the Content-Type of form bodies itself
+            BodyEncoding::FormUrlEncoded => {
+                let mut sections = vec!["[FormParams]".to_string()];
+                for (key, value) in form::flatten(&payload)? {
+                    sections.push(format!(
+                        "{}: {}",
+                        key,
+                        Self::escape_value(&Self::value_template(&value))
+                    ));
+                }
+                (None, sections, Vec::new())
+            }
+            BodyEncoding::Multipart => {
+                let mut sections = vec!["[MultipartFormData]".to_string()];
+                for part in multipart::collect_parts(&payload)? {
+                    let value = match part.content {
+                        PartContent::Field(value) => {
+                            Self::escape_value(&Self::value_template(&value))
+                        }
+                        PartContent::Json(value) => {
+                            Self::escape_value(&Self::template(&value.to_string()))
+                        }
+                        PartContent::File(file) => {
+                            format!("file,{}; {}", file.path, file.mime_type())
+                        }
+                    };
+                    sections.push(format!("{}: {}", part.name, value));
+                }
+                (None, sections, Vec::new())
+            }
+            BodyEncoding::Xml => (
+                Some(content_type),
+                Vec::new(),
+                Self::multiline_body(&Self::template(&xml::to_xml(&payload)?)),
+            ),
+            BodyEncoding::Raw => (
+                Some(content_type),
+                Vec::new(),
+                Self::multiline_body(&Self::value_template(&payload)),
+            ),
+        };
+
+        Ok(result)
+    }
+
+    /// Convert a criterion into a Hurl assert, or a comment if Hurl cannot express it
+    fn generate_assert(crit: &SuccessCriteria) -> String {
+        let unsupported = || {
+            let value = crit
+                .value
+                .as_ref()
+                .map(|v| format!(" {}", text_value(v)))
+                .unwrap_or_default();
+            format!(
+                "# unsupported criterion: {} {}{}",
+                crit.context, crit.condition, value
+            )
+        };
+
+        if crit.criteria_type.as_deref() == Some("jsonpath") {
+            return unsupported();
+        }
+        let Some(query) = RuntimeExpression::parse(&crit.context)
+            .as_ref()
+            .and_then(Self::response_query)
+        else {
+            return unsupported();
+        };
+
+        if crit.criteria_type.as_deref() == Some("regex") {
+            return format!("{} matches {}", query, Self::quote(&crit.condition));
+        }
+
+        let operator = match crit.condition.as_str() {
+            "==" | "$eq" => "==",
+            "!=" | "$ne" => "!=",
+            ">" | "$gt" => ">",
+            ">=" | "$gte" => ">=",
+            "<" | "$lt" => "<",
+            "<=" | "$lte" => "<=",
+            _ => return unsupported(),
+        };
+
+        let value = match crit.value {
+            Some(ref value) => match as_single_expression(value) {
+                Some(expr) => match Self::expr_variable(&expr) {
+                    Some(var) => format!("{{{{{}}}}}", var),
+                    None => return unsupported(),
+                },
+                None => match value {
+                    serde_json::Value::String(s) => Self::quote(&Self::template(s)),
+                    serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
+                        return unsupported();
+                    }
+                    other => other.to_string(),
+                },
+            },
+            None => "true".to_string(),
+        };
+
+        format!("{} {} {}", query, operator, value)
+    }
+
+    /// Generate `[Captures]` lines for step outputs and nested values used later
+    fn generate_captures(step: &Step, context: &WorkflowContext) -> Vec<String> {
+        let mut lines = Vec::new();
+        let outputs = match step.outputs.as_ref() {
+            Some(serde_json::Value::Object(outputs)) => outputs.clone(),
+            _ => serde_json::Map::new(),
+        };
+
+        for (name, expr) in &outputs {
+            let var = Self::output_var(&step.step_id, name, &[]);
+            match as_single_expression(expr)
+                .as_ref()
+                .and_then(Self::response_query)
+            {
+                Some(query) => lines.push(format!("{}: {}", var, query)),
+                None => lines.push(format!("# unsupported output: {} = {}", var, expr)),
+            }
+        }
+
+        // Values nested in an output are captured into their own variable
+        for (output, path) in context.nested_refs.get(&step.step_id).into_iter().flatten() {
+            let var = Self::output_var(&step.step_id, output, path);
+            let expr = outputs.get(output).and_then(as_single_expression);
+            match expr {
+                Some(RuntimeExpression::Response(ExpressionSource::Body(base))) => {
+                    let mut full = base.clone();
+                    full.extend(path.iter().cloned());
+                    lines.push(format!(
+                        "{}: jsonpath {}",
+                        var,
+                        Self::quote(&Self::jsonpath(&full))
+                    ));
+                }
+                _ => lines.push(format!(
+                    "# unsupported nested output: {}.{}",
+                    output,
+                    path.join(".")
+                )),
+            }
+        }
+
+        lines
+    }
+
+    /// Describe onSuccess/onFailure actions as comments
+    fn generate_control_flow_comments(step: &Step) -> Vec<String> {
+        let describe = |kind: &str,
+                        action_type: &str,
+                        config: &IndexMap<String, serde_json::Value>|
+         -> String {
+            let target = match (action_type, common::goto_target(config)) {
+                ("goto", Some(target)) => format!(" {}", target),
+                _ => String::new(),
+            };
+            let criteria: Vec<SuccessCriteria> = config
+                .get("criteria")
+                .and_then(|c| serde_json::from_value(c.clone()).ok())
+                .unwrap_or_default();
+            let condition = if criteria.is_empty() {
+                String::new()
+            } else {
+                let parts: Vec<String> = criteria
+                    .iter()
+                    .map(|c| match c.value {
+                        Some(ref v) => format!("{} {} {}", c.context, c.condition, text_value(v)),
+                        None => format!("{} {}", c.context, c.condition),
+                    })
+                    .collect();
+                format!(" when {}", parts.join(" && "))
+            };
+            format!(
+                "# {}: {}{}{} (not supported by Hurl)",
+                kind, action_type, target, condition
+            )
+        };
+
+        let mut lines = Vec::new();
+        for action in step.on_success.iter().flatten() {
+            lines.push(describe("onSuccess", &action.action_type, &action.config));
+        }
+        for action in step.on_failure.iter().flatten() {
+            lines.push(describe("onFailure", &action.action_type, &action.config));
+        }
+        lines
+    }
+
+    /// Generate the entry for a step
+    fn generate_entry(
+        &self,
+        step: &Step,
+        resolver: &OpenApiResolver,
+        base_url: &str,
+        context: &WorkflowContext,
+        input_defaults: &[(String, serde_json::Value)],
+    ) -> Result<Vec<String>> {
+        let (path, method) = common::resolve_operation(step, resolver)?;
+
+        let mut path = path;
+        let mut headers = Vec::new();
+        let mut query = Vec::new();
+        let mut cookies = Vec::new();
+
+        for param in &step.parameters {
+            let value = Self::value_template(&param.value);
+            match param.location.as_str() {
+                "path" => path = path.replace(&format!("{{{}}}", param.name), &value),
+                "query" => query.push(format!("{}: {}", param.name, Self::escape_value(&value))),
+                "header" => headers.push(format!("{}: {}", param.name, Self::escape_value(&value))),
+                "cookie" => cookies.push(format!("{}: {}", param.name, Self::escape_value(&value))),
+                _ => {}
+            }
+        }
+
+        let (content_type, body_sections, body) = match step.request_body {
+            Some(ref request_body) => Self::generate_body(request_body, context)?,
+            None => (None, Vec::new(), Vec::new()),
+        };
+        if let Some(content_type) = content_type {
+            headers.push(format!("Content-Type: {}", content_type));
+        }
+
+        let mut lines = Vec::new();
+        match step.description {
+            Some(ref description) => lines.push(format!(
+                "# {}: {}",
+                step.step_id,
+                description.lines().next().unwrap_or_default()
+            )),
+            None => lines.push(format!("# {}", step.step_id)),
+        }
+        lines.push(format!(
+            "{} {}{}",
+            method,
+            base_url.trim_end_matches('/'),
+            path
+        ));
+        lines.extend(headers);
+
+        if !query.is_empty() {
+            lines.push("[QueryStringParams]".to_string());
+            lines.extend(query);
+        }
+        lines.extend(body_sections);
+        if !cookies.is_empty() {
+            lines.push("[Cookies]".to_string());
+            lines.extend(cookies);
+        }
+        if !input_defaults.is_empty() {
+            lines.push("[Options]".to_string());
+            for (name, value) in input_defaults {
+                lines.push(format!(
+                    "variable: {}={}",
+                    Self::input_var(std::slice::from_ref(name)),
+                    text_value(value)
+                ));
+            }
+        }
+        lines.extend(body);
+
+        lines.push("HTTP *".to_string());
+        let captures = Self::generate_captures(step, context);
+        if !captures.is_empty() {
+            lines.push("[Captures]".to_string());
+            lines.extend(captures);
+        }
+        let criteria = step.success_criteria.as_deref().unwrap_or(&[]);
+        if !criteria.is_empty() {
+            lines.push("[Asserts]".to_string());
+            lines.extend(criteria.iter().map(Self::generate_assert));
+        }
+        lines.extend(Self::generate_control_flow_comments(step));
+
+        Ok(lines)
+    }
+
+    /// Step outputs referenced with a nested path anywhere in the workflow
+    fn collect_nested_refs(workflow: &Workflow) -> IndexMap<String, Vec<(String, Vec<String>)>> {
+        fn visit(
+            value: &serde_json::Value,
+            refs: &mut IndexMap<String, Vec<(String, Vec<String>)>>,
+        ) {
+            match value {
+                serde_json::Value::String(s) => {
+                    for segment in parse_template(s) {
+                        if let TemplateSegment::Expression(RuntimeExpression::StepOutput {
+                            step_id,
+                            output,
+                            path,
+                        }) = segment
+                            && !path.is_empty()
+                        {
+                            let entry = refs.entry(step_id).or_default();
+                            if !entry.contains(&(output.clone(), path.clone())) {
+                                entry.push((output, path));
+                            }
+                        }
+                    }
+                }
+                serde_json::Value::Array(items) => items.iter().for_each(|v| visit(v, refs)),
+                serde_json::Value::Object(obj) => obj.values().for_each(|v| visit(v, refs)),
+                _ => {}
+            }
+        }
+
+        let mut refs = IndexMap::new();
+        for step in &workflow.steps {
+            for param in &step.parameters {
+                visit(&param.value, &mut refs);
+            }
+            if let Some(ref body) = step.request_body {
+                visit(&body.payload, &mut refs);
+                for replacement in &body.replacements {
+                    visit(&replacement.value, &mut refs);
+                }
+            }
+            for crit in step.success_criteria.iter().flatten() {
+                if let Some(ref value) = crit.value {
+                    visit(value, &mut refs);
+                }
+            }
+        }
+        refs
+    }
+
+    /// Generate all entries of a workflow
+    fn generate_workflow(
+        &self,
+        workflow: &Workflow,
+        resolver: &OpenApiResolver,
+        base_url: &str,
+    ) -> Result<String> {
+        let defaults = common::input_defaults(workflow);
+        let context = WorkflowContext {
+            non_string_inputs: defaults
+                .iter()
+                .filter(|(_, value)| !value.is_string() && !value.is_null())
+                .map(|(name, _)| name.clone())
+                .collect(),
+            nested_refs: Self::collect_nested_refs(workflow),
+        };
+
+        let mut lines = vec![format!("# Workflow: {}", workflow.workflow_id)];
+        if let Some(ref summary) = workflow.summary {
+            lines.push(format!("# {}", summary));
+        }
+        if !defaults.is_empty() {
+            lines
+                .push("# Inputs are set in the [Options] section of the first request".to_string());
+        }
+
+        for (i, step) in workflow.steps.iter().enumerate() {
+            lines.push(String::new());
+            let input_defaults: &[(String, serde_json::Value)] =
+                if i == 0 { &defaults } else { &[] };
+            lines.extend(self.generate_entry(
+                step,
+                resolver,
+                base_url,
+                &context,
+                input_defaults,
+            )?);
+        }
+
+        let mut hurl = lines.join("\n");
+        hurl.push('\n');
+        Ok(hurl)
+    }
+}
+
+impl Converter for HurlConverter {
+    type Output = String;
+
+    fn convert_spec(
+        &self,
+        arazzo: &ArazzoSpec,
+        resolver: &OpenApiResolver,
+        options: &ConvertOptions,
+    ) -> Result<Self::Output> {
+        let base_url = common::resolve_base_url(resolver, options);
+
+        let mut sections = vec![format!("# {}\n", arazzo.info.title)];
+        for workflow in &arazzo.workflows {
+            sections.push(self.generate_workflow(workflow, resolver, &base_url)?);
+        }
+        Ok(sections.join("\n"))
+    }
+
+    fn convert_workflow(
+        &self,
+        workflow: &Workflow,
+        resolver: &OpenApiResolver,
+        options: &ConvertOptions,
+    ) -> Result<Self::Output> {
+        let base_url = common::resolve_base_url(resolver, options);
+        self.generate_workflow(workflow, resolver, &base_url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loader::arazzo::load_arazzo;
+    use serde_json::json;
+    use std::path::Path;
+
+    fn criterion(context: &str, condition: &str, value: serde_json::Value) -> SuccessCriteria {
+        SuccessCriteria {
+            context: context.to_string(),
+            condition: condition.to_string(),
+            value: Some(value),
+            criteria_type: None,
+        }
+    }
+
+    fn empty_context() -> WorkflowContext {
+        WorkflowContext {
+            non_string_inputs: HashSet::new(),
+            nested_refs: IndexMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_template_and_jsonpath() {
+        assert_eq!(
+            HurlConverter::template("Bearer $steps.login.outputs.token"),
+            "Bearer {{login_token}}"
+        );
+        assert_eq!(
+            HurlConverter::template("$steps.get-user.outputs.profile.email"),
+            "{{get-user_profile_email}}"
+        );
+        assert_eq!(
+            HurlConverter::jsonpath(&["items".to_string(), "0".to_string(), "a-b".to_string()]),
+            "$.items[0]['a-b']"
+        );
+    }
+
+    #[test]
+    fn test_generate_assert() {
+        assert_eq!(
+            HurlConverter::generate_assert(&criterion("$statusCode", "==", json!(201))),
+            "status == 201"
+        );
+        assert_eq!(
+            HurlConverter::generate_assert(&criterion(
+                "$response.body.email",
+                "==",
+                json!("$inputs.email")
+            )),
+            "jsonpath \"$.email\" == {{inputs_email}}"
+        );
+        assert_eq!(
+            HurlConverter::generate_assert(&SuccessCriteria {
+                context: "$response.header.Location".to_string(),
+                condition: "^/users/".to_string(),
+                value: None,
+                criteria_type: Some("regex".to_string()),
+            }),
+            "header \"Location\" matches \"^/users/\""
+        );
+        assert!(
+            HurlConverter::generate_assert(&criterion("$request.body.x", "==", json!(1)))
+                .starts_with("# unsupported criterion")
+        );
+    }
+
+    #[test]
+    fn test_json_body_keeps_non_string_inputs_unquoted() {
+        let mut context = empty_context();
+        context.non_string_inputs.insert("age".to_string());
+
+        let body = RequestBody {
+            content_type: Some("application/json".to_string()),
+            payload: json!({"name": "$inputs.name", "age": "$inputs.age"}),
+            replacements: Vec::new(),
+        };
+        let (content_type, sections, body) = HurlConverter::generate_body(&body, &context).unwrap();
+        let body = body.join("\n");
+
+        assert_eq!(content_type.as_deref(), Some("application/json"));
+        assert!(sections.is_empty());
+        assert!(body.contains("\"age\": {{inputs_age}}"));
+        assert!(body.contains("\"name\": \"{{inputs_name}}\""));
+    }
+
+    #[test]
+    fn test_convert_workflow_integration() {
+        let arazzo = load_arazzo("tests/fixtures/arazzo.yaml").unwrap();
+
+        let mut resolver = OpenApiResolver::new(Path::new("tests/fixtures"));
+        resolver
+            .load_spec("userAPI", Path::new("tests/fixtures/openapi.yaml"))
+            .unwrap();
+
+        let options = ConvertOptions {
+            base_url: Some("https://api.test".to_string()),
+            ..Default::default()
+        };
+        let hurl = HurlConverter::new()
+            .convert_workflow(&arazzo.workflows[0], &resolver, &options)
+            .unwrap();
+
+        assert!(hurl.contains("POST https://api.test/"));
+        assert!(hurl.contains("variable: inputs_username=testuser"));
+        assert!(hurl.contains("register_userId: jsonpath \"$.id\""));
+        assert!(hurl.contains("status == 201"));
+        assert!(hurl.contains("Bearer {{login_token}}"));
+    }
+}
@@ -0,0 +1,1429 @@
+//! JMeter test plan (JMX) converter
+//!
+//! Each workflow becomes a Thread Group and each step an HTTP sampler.
+//! Step outputs are extracted into JMeter variables named like the Postman
+//! ones (`steps.<stepId>.outputs.<name>`, `inputs.<name>`), successCriteria
+//! become Response/JSONPath assertions (JSR223 assertions when no native
+//! assertion can express them) and the load options map to the Thread Group
+//! settings.
+//!
+//! Workflows using `goto`/`end` actions run their steps inside a While
+//! Controller with one If Controller per step; a JSR223 assertion after each
+//! sampler picks the next step.
+//!
+//! The target server and the inputs can be overridden with JMeter properties
+//! (`-Jprotocol=`, `-Jhost=`, `-Jport=`, `-JbasePath=`, `-Jinputs.<name>=`).
+
+use super::{ConvertOptions, Converter, common};
+use crate::error::{HornetError, Result};
+use crate::loader::OpenApiResolver;
+use crate::models::arazzo::{ArazzoSpec, RequestBody, Step, SuccessCriteria, Workflow};
+use crate::models::expression::{
+    ExpressionSource, RuntimeExpression, TemplateSegment, as_single_expression, parse_template,
+};
+use crate::payload::multipart::{self, PartContent};
+use crate::payload::xml::{escape_attr, escape_text};
+use crate::payload::{self, BodyEncoding, form, text_value, xml};
+use indexmap::IndexMap;
+use std::collections::HashSet;
+
+/// JMeter version the generated plans target
+const JMETER_VERSION: &str = "5.6.3";
+
+/// Converter for generating JMeter test plans
+#[derive(Debug, Clone, Default)]
+pub struct JMeterConverter;
+
+/// A property of a test element
+#[derive(Debug, Clone)]
+enum Prop {
+    String(String, String),
+    Bool(String, bool),
+    Int(String, i64),
+    Element {
+        name: String,
+        element_type: &'static str,
+        props: Vec<Prop>,
+    },
+    Collection(String, Vec<Prop>),
+}
+
+impl Prop {
+    fn string(name: &str, value: impl Into<String>) -> Self {
+        Self::String(name.to_string(), value.into())
+    }
+
+    fn bool(name: &str, value: bool) -> Self {
+        Self::Bool(name.to_string(), value)
+    }
+
+    fn int(name: &str, value: i64) -> Self {
+        Self::Int(name.to_string(), value)
+    }
+
+    fn element(name: &str, element_type: &'static str, props: Vec<Prop>) -> Self {
+        Self::Element {
+            name: name.to_string(),
+            element_type,
+            props,
+        }
+    }
+
+    fn collection(name: &str, items: Vec<Prop>) -> Self {
+        Self::Collection(name.to_string(), items)
+    }
+
+    /// Empty `Arguments` element used by samplers and defaults
+    fn arguments(name: &str, items: Vec<Prop>) -> Self {
+        Self::element(
+            name,
+            "Arguments",
+            vec![Self::collection("Arguments.arguments", items)],
+        )
+    }
+
+    fn write(&self, out: &mut String, depth: usize) {
+        let pad = "  ".repeat(depth);
+        match self {
+            Prop::String(name, value) if value.is_empty() => {
+                out.push_str(&format!(
+                    "{}<stringProp name=\"{}\"></stringProp>\n",
+                    pad,
+                    escape_attr(name)
+                ));
+            }
+            Prop::String(name, value) => out.push_str(&format!(
+                "{}<stringProp name=\"{}\">{}</stringProp>\n",
+                pad,
+                escape_attr(name),
+                escape_text(value)
+            )),
+            Prop::Bool(name, value) => out.push_str(&format!(
+                "{}<boolProp name=\"{}\">{}</boolProp>\n",
+                pad,
+                escape_attr(name),
+                value
+            )),
+            Prop::Int(name, value) => out.push_str(&format!(
+                "{}<intProp name=\"{}\">{}</intProp>\n",
+                pad,
+                escape_attr(name),
+                value
+            )),
+            Prop::Element {
+                name,
+                element_type,
+                props,
+            } => {
+                out.push_str(&format!(
+                    "{}<elementProp name=\"{}\" elementType=\"{}\">\n",
+                    pad,
+                    escape_attr(name),
+                    element_type
+                ));
+                for prop in props {
+                    prop.write(out, depth + 1);
+                }
+                out.push_str(&format!("{}</elementProp>\n", pad));
+            }
+            Prop::Collection(name, items) if items.is_empty() => out.push_str(&format!(
+                "{}<collectionProp name=\"{}\"/>\n",
+                pad,
+                escape_attr(name)
+            )),
+            Prop::Collection(name, items) => {
+                out.push_str(&format!(
+                    "{}<collectionProp name=\"{}\">\n",
+                    pad,
+                    escape_attr(name)
+                ));
+                for item in items {
+                    item.write(out, depth + 1);
+                }
+                out.push_str(&format!("{}</collectionProp>\n", pad));
+            }
+        }
+    }
+}
+
+/// A JMeter test element with the elements nested under it
+#[derive(Debug, Clone)]
+struct TestElement {
+    tag: &'static str,
+    guiclass: &'static str,
+    name: String,
+    props: Vec<Prop>,
+    children: Vec<TestElement>,
+}
+
+impl TestElement {
+    fn new(tag: &'static str, guiclass: &'static str, name: impl Into<String>) -> Self {
+        Self {
+            tag,
+            guiclass,
+            name: name.into(),
+            props: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    fn prop(mut self, prop: Prop) -> Self {
+        self.props.push(prop);
+        self
+    }
+
+    fn child(mut self, child: TestElement) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// JSR223 element running a Groovy script
+    fn jsr223(tag: &'static str, name: impl Into<String>, script: String) -> Self {
+        Self::new(tag, "TestBeanGUI", name)
+            .prop(Prop::string("scriptLanguage", "groovy"))
+            .prop(Prop::string("parameters", ""))
+            .prop(Prop::string("filename", ""))
+            .prop(Prop::string("cacheKey", "true"))
+            .prop(Prop::string("script", script))
+    }
+
+    /// Write the element followed by its `hashTree`
+    fn write(&self, out: &mut String, depth: usize) {
+        let pad = "  ".repeat(depth);
+        out.push_str(&format!(
+            "{}<{} guiclass=\"{}\" testclass=\"{}\" testname=\"{}\" enabled=\"true\">\n",
+            pad,
+            self.tag,
+            self.guiclass,
+            self.tag,
+            escape_attr(&self.name)
+        ));
+        for prop in &self.props {
+            prop.write(out, depth + 1);
+        }
+        out.push_str(&format!("{}</{}>\n", pad, self.tag));
+
+        if self.children.is_empty() {
+            out.push_str(&format!("{}<hashTree/>\n", pad));
+        } else {
+            out.push_str(&format!("{}<hashTree>\n", pad));
+            for child in &self.children {
+                child.write(out, depth + 1);
+            }
+            out.push_str(&format!("{}</hashTree>\n", pad));
+        }
+    }
+}
+
+/// Thread Group settings derived from the convert options
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ThreadSettings {
+    threads: u32,
+    /// Loops per thread (`-1` loops until the duration is over)
+    loops: i64,
+    duration_secs: Option<u64>,
+}
+
+impl ThreadSettings {
+    /// `iterations` is the total across all threads, as in k6
+    fn from_options(options: &ConvertOptions) -> Result<Self> {
+        let threads = options.vus.unwrap_or(1).max(1);
+
+        if let Some(ref duration) = options.duration {
+            let duration = common::parse_duration(duration).ok_or_else(|| {
+                HornetError::ValidationError(format!("Invalid duration: {}", duration))
+            })?;
+            return Ok(Self {
+                threads,
+                loops: -1,
+                duration_secs: Some(duration.as_secs().max(1)),
+            });
+        }
+
+        let iterations = options.iterations.unwrap_or(1).max(1);
+        Ok(Self {
+            threads,
+            loops: i64::from(iterations.div_ceil(threads)),
+            duration_secs: None,
+        })
+    }
+}
+
+/// Per-workflow state used while generating samplers
+struct WorkflowContext {
+    /// Inputs whose values are not strings (rendered unquoted in JSON bodies)
+    non_string_inputs: HashSet<String>,
+    /// Whether steps run as a state machine (workflow uses goto/end)
+    branching: bool,
+}
+
+impl JMeterConverter {
+    /// Create a new JMeterConverter
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn input_var(name: &str) -> String {
+        format!("inputs.{}", name)
+    }
+
+    fn output_var(step_id: &str, output: &str) -> String {
+        format!("steps.{}.outputs.{}", step_id, output)
+    }
+
+    /// Quote a string as a Groovy single-quoted literal
+    fn groovy_string(s: &str) -> String {
+        format!(
+            "'{}'",
+            s.replace('\\', "\\\\")
+                .replace('\'', "\\'")
+                .replace('\n', "\\n")
+                .replace('\r', "\\r")
+        )
+    }
+
+    /// Groovy literal for a JSON value
+    fn groovy_literal(value: &serde_json::Value) -> String {
+        match value {
+            serde_json::Value::String(s) => Self::groovy_string(s),
+            serde_json::Value::Null => "null".to_string(),
+            serde_json::Value::Bool(_) | serde_json::Value::Number(_) => value.to_string(),
+            other => format!(
+                "new groovy.json.JsonSlurper().parseText({})",
+                Self::groovy_string(&other.to_string())
+            ),
+        }
+    }
+
+    /// Render a property path as Groovy safe navigation (`?.a?.getAt(0)`)
+    fn groovy_path(path: &[String]) -> String {
+        path.iter()
+            .map(|segment| {
+                if let Ok(index) = segment.parse::<usize>() {
+                    format!("?.getAt({})", index)
+                } else if !segment.is_empty()
+                    && !segment.starts_with(|c: char| c.is_ascii_digit())
+                    && segment
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_')
+                {
+                    format!("?.{}", segment)
+                } else {
+                    format!("?.getAt({})", Self::groovy_string(segment))
+                }
+            })
+            .collect()
+    }
+
+    /// Render a property path as a JSONPath query (`$.a[0]['b-c']`)
+    fn jsonpath(path: &[String]) -> String {
+        let mut out = "$".to_string();
+        for segment in path {
+            if segment.parse::<usize>().is_ok() {
+                out.push_str(&format!("[{}]", segment));
+            } else if !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            {
+                out.push('.');
+                out.push_str(segment);
+            } else {
+                out.push_str(&format!("['{}']", segment.replace('\'', "\\'")));
+            }
+        }
+        out
+    }
+
+    /// Groovy expression for the value of a runtime expression
+    ///
+    /// Response values are read from `result` (a `SampleResult`).
+    fn groovy_expr(expr: &RuntimeExpression, result: &str) -> String {
+        match expr {
+            RuntimeExpression::StatusCode => format!("({}.getResponseCode() as Integer)", result),
+            RuntimeExpression::Url => format!("{}.getURL()?.toString()", result),
+            RuntimeExpression::Response(ExpressionSource::Body(path)) => {
+                format!("json({}){}", result, Self::groovy_path(path))
+            }
+            RuntimeExpression::Response(ExpressionSource::Header(name)) => {
+                format!("header({}, {})", result, Self::groovy_string(name))
+            }
+            RuntimeExpression::Inputs(path) if !path.is_empty() => {
+                Self::groovy_var(&Self::input_var(&path[0]), &path[1..])
+            }
+            RuntimeExpression::StepOutput {
+                step_id,
+                output,
+                path,
+            } => Self::groovy_var(&Self::output_var(step_id, output), path),
+            other => Self::groovy_string(&other.to_string()),
+        }
+    }
+
+    /// Groovy expression reading a variable, parsed as JSON when a path follows
+    fn groovy_var(var: &str, path: &[String]) -> String {
+        let value = format!("vars.get({})", Self::groovy_string(var));
+        if path.is_empty() {
+            value
+        } else {
+            format!(
+                "new groovy.json.JsonSlurper().parseText({}){}",
+                value,
+                Self::groovy_path(path)
+            )
+        }
+    }
+
+    /// Escape a JMeter function argument
+    fn function_arg(s: &str) -> String {
+        s.replace('\\', "\\\\").replace(',', "\\,")
+    }
+
+    /// JMeter reference to an expression (`${var}` or a `__groovy` call)
+    fn expr_to_jmeter(expr: &RuntimeExpression) -> String {
+        match expr {
+            RuntimeExpression::Inputs(path) if path.len() == 1 => {
+                format!("${{{}}}", Self::input_var(&path[0]))
+            }
+            RuntimeExpression::StepOutput {
+                step_id,
+                output,
+                path,
+            } if path.is_empty() => format!("${{{}}}", Self::output_var(step_id, output)),
+            RuntimeExpression::Inputs(_) | RuntimeExpression::StepOutput { .. } => format!(
+                "${{__groovy({})}}",
+                Self::function_arg(&Self::groovy_expr(expr, "prev"))
+            ),
+            other => other.to_string(),
+        }
+    }
+
+    /// Convert a string that may embed runtime expressions into JMeter syntax
+    fn template(text: &str) -> String {
+        parse_template(text)
+            .into_iter()
+            .map(|segment| match segment {
+                TemplateSegment::Literal(s) => s,
+                TemplateSegment::Expression(expr) => Self::expr_to_jmeter(&expr),
+            })
+            .collect()
+    }
+
+    fn value_template(value: &serde_json::Value) -> String {
+        Self::template(&text_value(value))
+    }
+
+    /// Render a JSON payload with JMeter variable references
+    ///
+    /// Non-string inputs are rendered unquoted so that they keep their type.
+    fn templated_json(value: &serde_json::Value, context: &WorkflowContext) -> Result<String> {
+        let mut unquoted = Vec::new();
+        let templated = Self::mark_json(value, context, &mut unquoted);
+
+        let mut json = serde_json::to_string_pretty(&templated)?;
+        for (i, reference) in unquoted.iter().enumerate() {
+            json = json.replace(&format!("\"__hornet_var_{}__\"", i), reference);
+        }
+        Ok(json)
+    }
+
+    fn mark_json(
+        value: &serde_json::Value,
+        context: &WorkflowContext,
+        unquoted: &mut Vec<String>,
+    ) -> serde_json::Value {
+        match value {
+            serde_json::Value::String(s) => {
+                if let Some(RuntimeExpression::Inputs(path)) = as_single_expression(value)
+                    && path.len() == 1
+                    && context.non_string_inputs.contains(&path[0])
+                {
+                    unquoted.push(format!("${{{}}}", Self::input_var(&path[0])));
+                    return serde_json::json!(format!("__hornet_var_{}__", unquoted.len() - 1));
+                }
+                serde_json::Value::String(Self::template(s))
+            }
+            serde_json::Value::Array(items) => serde_json::Value::Array(
+                items
+                    .iter()
+                    .map(|v| Self::mark_json(v, context, unquoted))
+                    .collect(),
+            ),
+            serde_json::Value::Object(obj) => serde_json::Value::Object(
+                obj.iter()
+                    .map(|(k, v)| (k.clone(), Self::mark_json(v, context, unquoted)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    fn http_argument(name: &str, value: String, encode: bool) -> Prop {
+        Prop::element(
+            name,
+            "HTTPArgument",
+            vec![
+                Prop::bool("HTTPArgument.always_encode", encode),
+                Prop::string("Argument.name", name),
+                Prop::string("Argument.value", value),
+                Prop::string("Argument.metadata", "="),
+                Prop::bool("HTTPArgument.use_equals", true),
+            ],
+        )
+    }
+
+    /// Generate the sampler properties sending a request body
+    ///
+    /// Returns the properties and the Content-Type header to send, if any.
+    fn generate_body(
+        body: &RequestBody,
+        context: &WorkflowContext,
+    ) -> Result<(Vec<Prop>, Option<String>)> {
+        let payload = payload::apply_replacements(body)?;
+        let encoding = BodyEncoding::from_content_type(body.content_type.as_deref());
+        let content_type = body
+            .content_type
+            .clone()
+            .unwrap_or_else(|| encoding.default_content_type().to_string());
+
+        let raw = |text: String| {
+            vec![
+                Prop::bool("HTTPSampler.postBodyRaw", true),
+                Prop::arguments(
+                    "HTTPsampler.Arguments",
+                    vec![Self::http_argument("", text, false)],
+                ),
+            ]
+        };
+
+        if let serde_json::Value::String(text) = &payload
+            && encoding != BodyEncoding::Json
+        {
+            return Ok((raw(Self::template(text)), Some(content_type)));
+        }
+
+        let result = match encoding {
+            BodyEncoding::Json => (
+                raw(Self::templated_json(&payload, context)?),
+                Some(content_type),
+            ),
+            // JMeter sets the Content-Type of form bodies itself
+            BodyEncoding::FormUrlEncoded => {
+                let args = form::flatten(&payload)?
+                    .iter()
+                    .map(|(key, value)| Self::http_argument(key, Self::value_template(value), true))
+                    .collect();
+                (vec![Prop::arguments("HTTPsampler.Arguments", args)], None)
+            }
+            BodyEncoding::Multipart => {
+                let mut args = Vec::new();
+                let mut files = Vec::new();
+                for part in multipart::collect_parts(&payload)? {
+                    match part.content {
+                        PartContent::Field(value) => args.push(Self::http_argument(
+                            &part.name,
+                            Self::value_template(&value),
+                            false,
+                        )),
+                        PartContent::Json(value) => args.push(Self::http_argument(
+                            &part.name,
+                            Self::templated_json(&value, context)?,
+                            false,
+                        )),
+                        PartContent::File(file) => files.push(Prop::element(
+                            &file.path,
+                            "HTTPFileArg",
+                            vec![
+                                Prop::string("File.path", file.path.clone()),
+                                Prop::string("File.paramname", part.name.clone()),
+                                Prop::string("File.mimetype", file.mime_type()),
+                            ],
+                        )),
+                    }
+                }
+                (
+                    vec![
+                        Prop::bool("HTTPSampler.DO_MULTIPART_POST", true),
+                        Prop::arguments("HTTPsampler.Arguments", args),
+                        Prop::element(
+                            "HTTPsampler.Files",
+                            "HTTPFileArgs",
+                            vec![Prop::collection("HTTPFileArgs.files", files)],
+                        ),
+                    ],
+                    None,
+                )
+            }
+            BodyEncoding::Xml => (
+                raw(Self::template(&xml::to_xml(&payload)?)),
+                Some(content_type),
+            ),
+            BodyEncoding::Raw => (raw(Self::value_template(&payload)), Some(content_type)),
+        };
+
+        Ok(result)
+    }
+
+    fn criterion_description(crit: &SuccessCriteria) -> String {
+        match crit.value {
+            Some(ref value) => format!("{} {} {}", crit.context, crit.condition, text_value(value)),
+            None => format!("{} {}", crit.context, crit.condition),
+        }
+    }
+
+    fn criterion_operator(crit: &SuccessCriteria) -> Option<&'static str> {
+        match crit.condition.as_str() {
+            "==" | "$eq" => Some("=="),
+            "!=" | "$ne" => Some("!="),
+            ">" | "$gt" => Some(">"),
+            ">=" | "$gte" => Some(">="),
+            "<" | "$lt" => Some("<"),
+            "<=" | "$lte" => Some("<="),
+            _ => None,
+        }
+    }
+
+    /// Value of a criterion as JMeter text, if it is a literal or a variable
+    fn criterion_text(crit: &SuccessCriteria) -> Option<String> {
+        match crit.value {
+            Some(ref value) => match as_single_expression(value) {
+                Some(
+                    expr @ (RuntimeExpression::Inputs(_) | RuntimeExpression::StepOutput { .. }),
+                ) => Some(Self::expr_to_jmeter(&expr)),
+                Some(_) => None,
+                None => match value {
+                    serde_json::Value::Array(_) | serde_json::Value::Object(_) => None,
+                    other => Some(text_value(other)),
+                },
+            },
+            None => Some("true".to_string()),
+        }
+    }
+
+    /// Native assertion for a criterion, if JMeter has one
+    ///
+    /// `assume_success` makes the assertion ignore the HTTP status (JMeter
+    /// otherwise fails every 4xx/5xx response).
+    fn native_assertion(crit: &SuccessCriteria, assume_success: bool) -> Option<TestElement> {
+        let context = RuntimeExpression::parse(&crit.context)?;
+        let name = Self::criterion_description(crit);
+        let is_regex = crit.criteria_type.as_deref() == Some("regex");
+
+        match context {
+            RuntimeExpression::StatusCode if !is_regex && crit.criteria_type.is_none() => {
+                let test_type = match Self::criterion_operator(crit)? {
+                    "==" => 8,
+                    "!=" => 8 | 4,
+                    _ => return None,
+                };
+                Some(
+                    TestElement::new("ResponseAssertion", "AssertionGui", name)
+                        .prop(Prop::collection(
+                            "Asserion.test_strings",
+                            vec![Prop::string("0", Self::criterion_text(crit)?)],
+                        ))
+                        .prop(Prop::string("Assertion.custom_message", ""))
+                        .prop(Prop::string(
+                            "Assertion.test_field",
+                            "Assertion.response_code",
+                        ))
+                        .prop(Prop::bool("Assertion.assume_success", assume_success))
+                        .prop(Prop::int("Assertion.test_type", test_type)),
+                )
+            }
+            RuntimeExpression::Response(ExpressionSource::Body(path)) if !path.is_empty() => {
+                let (expected, invert, regex) = if is_regex {
+                    (crit.condition.clone(), false, true)
+                } else if crit.criteria_type.is_none() {
+                    let invert = match Self::criterion_operator(crit)? {
+                        "==" => false,
+                        "!=" => true,
+                        _ => return None,
+                    };
+                    (Self::criterion_text(crit)?, invert, false)
+                } else {
+                    return None;
+                };
+                Some(
+                    TestElement::new("JSONPathAssertion", "JSONPathAssertionGui", name)
+                        .prop(Prop::string("JSON_PATH", Self::jsonpath(&path)))
+                        .prop(Prop::string("EXPECTED_VALUE", expected))
+                        .prop(Prop::bool("JSONVALIDATION", true))
+                        .prop(Prop::bool("EXPECT_NULL", false))
+                        .prop(Prop::bool("INVERT", invert))
+                        .prop(Prop::bool("ISREGEX", regex)),
+                )
+            }
+            _ => None,
+        }
+    }
+
+    /// Groovy helpers for reading response values in scripts
+    fn groovy_helpers() -> Vec<String> {
+        vec![
+            "def json = { r -> try { new groovy.json.JsonSlurper().parseText(r.getResponseDataAsString()) } catch (e) { null } }".to_string(),
+            "def header = { r, String name -> r.getResponseHeaders().readLines().find { it.toLowerCase().startsWith(name.toLowerCase() + ':') }?.split(':', 2)?.getAt(1)?.trim() }".to_string(),
+            "def compare = { a, String op, b ->".to_string(),
+            "    if (a instanceof Number || b instanceof Number) {".to_string(),
+            "        try { a = a as BigDecimal; b = b as BigDecimal } catch (e) { }".to_string(),
+            "    }".to_string(),
+            "    switch (op) {".to_string(),
+            "        case '==': return a == b || String.valueOf(a) == String.valueOf(b)".to_string(),
+            "        case '!=': return !(a == b || String.valueOf(a) == String.valueOf(b))".to_string(),
+            "        case '>': return a > b".to_string(),
+            "        case '>=': return a >= b".to_string(),
+            "        case '<': return a < b".to_string(),
+            "        case '<=': return a <= b".to_string(),
+            "    }".to_string(),
+            "    false".to_string(),
+            "}".to_string(),
+        ]
+    }
+
+    /// Groovy condition evaluating a criterion against `result`
+    fn groovy_condition(crit: &SuccessCriteria, result: &str) -> Option<String> {
+        if crit.criteria_type.as_deref() == Some("jsonpath") {
+            return None;
+        }
+
+        let left = match RuntimeExpression::parse(&crit.context) {
+            Some(expr) => Self::groovy_expr(&expr, result),
+            None => Self::groovy_string(&crit.context),
+        };
+
+        if crit.criteria_type.as_deref() == Some("regex") {
+            return Some(format!(
+                "(String.valueOf({}) =~ {})",
+                left,
+                Self::groovy_string(&crit.condition)
+            ));
+        }
+
+        let right = match crit.value {
+            Some(ref value) => match as_single_expression(value) {
+                Some(expr) => Self::groovy_expr(&expr, result),
+                None => Self::groovy_literal(value),
+            },
+            None => "true".to_string(),
+        };
+
+        Some(format!(
+            "compare({}, {}, {})",
+            left,
+            Self::groovy_string(Self::criterion_operator(crit).unwrap_or("==")),
+            right
+        ))
+    }
+
+    /// JSR223 assertion for criteria that no native assertion can express
+    fn script_assertion(criteria: &[&SuccessCriteria]) -> Option<TestElement> {
+        let mut lines = Self::groovy_helpers();
+        lines.push("def failures = []".to_string());
+
+        let mut checked = 0;
+        for crit in criteria {
+            let Some(condition) = Self::groovy_condition(crit, "SampleResult") else {
+                continue;
+            };
+            checked += 1;
+            lines.push(format!(
+                "if (!{}) failures << {}",
+                condition,
+                Self::groovy_string(&Self::criterion_description(crit))
+            ));
+        }
+        if checked == 0 {
+            return None;
+        }
+
+        lines.push("if (failures) {".to_string());
+        lines.push("    AssertionResult.setFailure(true)".to_string());
+        lines.push(
+            "    AssertionResult.setFailureMessage('Failed: ' + failures.join('; '))".to_string(),
+        );
+        lines.push("}".to_string());
+
+        Some(TestElement::jsr223(
+            "JSR223Assertion",
+            "successCriteria",
+            lines.join("\n"),
+        ))
+    }
+
+    /// Generate extractors storing step outputs
+    fn generate_extractors(step: &Step) -> Vec<TestElement> {
+        let Some(serde_json::Value::Object(outputs)) = step.outputs.as_ref() else {
+            return Vec::new();
+        };
+
+        let mut names = Vec::new();
+        let mut paths = Vec::new();
+        let mut script = Vec::new();
+
+        for (name, expr) in outputs {
+            let var = Self::output_var(&step.step_id, name);
+            match as_single_expression(expr) {
+                Some(RuntimeExpression::Response(ExpressionSource::Body(path))) => {
+                    names.push(var);
+                    paths.push(Self::jsonpath(&path));
+                }
+                Some(expr) => script.push(format!(
+                    "vars.put({}, String.valueOf({}))",
+                    Self::groovy_string(&var),
+                    Self::groovy_expr(&expr, "prev")
+                )),
+                None => script.push(format!(
+                    "vars.put({}, {})",
+                    Self::groovy_string(&var),
+                    Self::groovy_string(&text_value(expr))
+                )),
+            }
+        }
+
+        let mut extractors = Vec::new();
+        if !names.is_empty() {
+            let repeat = |value: &str| vec![value; names.len()].join(";");
+            extractors.push(
+                TestElement::new("JSONPostProcessor", "JSONPostProcessorGui", "outputs")
+                    .prop(Prop::string(
+                        "JSONPostProcessor.referenceNames",
+                        names.join(";"),
+                    ))
+                    .prop(Prop::string(
+                        "JSONPostProcessor.jsonPathExprs",
+                        paths.join(";"),
+                    ))
+                    .prop(Prop::string("JSONPostProcessor.match_numbers", repeat("1")))
+                    .prop(Prop::string(
+                        "JSONPostProcessor.defaultValues",
+                        repeat("NOT_FOUND"),
+                    ))
+                    .prop(Prop::bool("JSONPostProcessor.compute_concat", false)),
+            );
+        }
+        if !script.is_empty() {
+            let mut lines = Self::groovy_helpers();
+            lines.extend(script);
+            extractors.push(TestElement::jsr223(
+                "JSR223PostProcessor",
+                "outputs (script)",
+                lines.join("\n"),
+            ));
+        }
+        extractors
+    }
+
+    /// Generate the Groovy lines running onSuccess/onFailure actions
+    fn generate_actions<'a>(
+        actions: impl Iterator<Item = (&'a str, &'a IndexMap<String, serde_json::Value>)>,
+    ) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        for (action_type, config) in actions {
+            let jump = match action_type {
+                "goto" => match common::goto_target(config) {
+                    Some(target) => {
+                        format!("vars.put('next', {}); return", Self::groovy_string(target))
+                    }
+                    None => {
+                        lines.push("// goto to another workflow is not supported".to_string());
+                        continue;
+                    }
+                },
+                "end" => "vars.put('next', ''); return".to_string(),
+                other => {
+                    lines.push(format!("// {} actions are not supported", other));
+                    continue;
+                }
+            };
+
+            let criteria: Vec<SuccessCriteria> = config
+                .get("criteria")
+                .and_then(|c| serde_json::from_value(c.clone()).ok())
+                .unwrap_or_default();
+            let conditions: Vec<String> = criteria
+                .iter()
+                .filter_map(|crit| Self::groovy_condition(crit, "SampleResult"))
+                .collect();
+
+            if conditions.is_empty() {
+                lines.push(jump);
+            } else {
+                lines.push(format!("if ({}) {{ {} }}", conditions.join(" && "), jump));
+            }
+        }
+
+        lines
+    }
+
+    /// JSR223 assertion choosing the next step (runs after the other assertions)
+    fn control_flow_assertion(step: &Step, next_step: Option<&str>) -> TestElement {
+        let mut lines = Self::groovy_helpers();
+        lines.push("if (SampleResult.isSuccessful()) {".to_string());
+        let mut on_success = Self::generate_actions(
+            step.on_success
+                .iter()
+                .flatten()
+                .map(|a| (a.action_type.as_str(), &a.config)),
+        );
+        on_success.push(format!(
+            "vars.put('next', {})",
+            Self::groovy_string(next_step.unwrap_or_default())
+        ));
+        lines.extend(on_success.into_iter().map(|l| format!("    {}", l)));
+        lines.push("} else {".to_string());
+        let mut on_failure = Self::generate_actions(
+            step.on_failure
+                .iter()
+                .flatten()
+                .map(|a| (a.action_type.as_str(), &a.config)),
+        );
+        // 失敗したステップはワークフローを終了する
+        on_failure.push("vars.put('next', '')".to_string());
+        lines.extend(on_failure.into_iter().map(|l| format!("    {}", l)));
+        lines.push("}".to_string());
+
+        TestElement::jsr223("JSR223Assertion", "control flow", lines.join("\n"))
+    }
+
+    /// Generate the HTTP sampler for a step
+    fn generate_sampler(
+        &self,
+        step: &Step,
+        next_step: Option<&str>,
+        resolver: &OpenApiResolver,
+        context: &WorkflowContext,
+    ) -> Result<TestElement> {
+        let (path, method) = common::resolve_operation(step, resolver)?;
+
+        let mut path = path;
+        let mut query = Vec::new();
+        let mut headers = Vec::new();
+        let mut cookies = Vec::new();
+
+        for param in &step.parameters {
+            let value = Self::value_template(&param.value);
+            match param.location.as_str() {
+                "path" => path = path.replace(&format!("{{{}}}", param.name), &value),
+                "query" => query.push(format!(
+                    "{}={}",
+                    form::escape(&param.name),
+                    if value.contains("${") {
+                        format!("${{__urlencode({})}}", Self::function_arg(&value))
+                    } else {
+                        form::escape(&value)
+                    }
+                )),
+                "header" => headers.push((param.name.clone(), value)),
+                "cookie" => cookies.push(format!("{}={}", param.name, value)),
+                _ => {}
+            }
+        }
+        if !cookies.is_empty() {
+            headers.push(("Cookie".to_string(), cookies.join("; ")));
+        }
+
+        let mut sampler_path = format!("${{__P(basePath,)}}{}", path);
+        if !query.is_empty() {
+            sampler_path = format!("{}?{}", sampler_path, query.join("&"));
+        }
+
+        let mut body_props = Vec::new();
+        if let Some(ref request_body) = step.request_body {
+            let (props, content_type) = Self::generate_body(request_body, context)?;
+            body_props = props;
+            if let Some(content_type) = content_type {
+                headers.push(("Content-Type".to_string(), content_type));
+            }
+        }
+        if !body_props
+            .iter()
+            .any(|p| matches!(p, Prop::Element { name, .. } if name == "HTTPsampler.Arguments"))
+        {
+            body_props.push(Prop::arguments("HTTPsampler.Arguments", Vec::new()));
+        }
+
+        let mut sampler = TestElement::new("HTTPSamplerProxy", "HttpTestSampleGui", &step.step_id)
+            .prop(Prop::string("HTTPSampler.path", sampler_path))
+            .prop(Prop::string("HTTPSampler.method", method))
+            .prop(Prop::string("HTTPSampler.contentEncoding", "UTF-8"))
+            .prop(Prop::bool("HTTPSampler.follow_redirects", true))
+            .prop(Prop::bool("HTTPSampler.use_keepalive", true));
+        for prop in body_props {
+            sampler = sampler.prop(prop);
+        }
+        if let Some(ref description) = step.description {
+            sampler = sampler.prop(Prop::string("TestPlan.comments", description.trim()));
+        }
+
+        if !headers.is_empty() {
+            let items = headers
+                .into_iter()
+                .map(|(name, value)| {
+                    Prop::element(
+                        "",
+                        "Header",
+                        vec![
+                            Prop::string("Header.name", name),
+                            Prop::string("Header.value", value),
+                        ],
+                    )
+                })
+                .collect();
+            sampler = sampler.child(
+                TestElement::new("HeaderManager", "HeaderPanel", "HTTP Header Manager")
+                    .prop(Prop::collection("HeaderManager.headers", items)),
+            );
+        }
+
+        for extractor in Self::generate_extractors(step) {
+            sampler = sampler.child(extractor);
+        }
+
+        // Status code assertions first: only the first one may ignore the status
+        let criteria = step.success_criteria.as_deref().unwrap_or(&[]);
+        let mut ordered: Vec<&SuccessCriteria> = criteria
+            .iter()
+            .filter(|c| c.context.trim() == "$statusCode")
+            .collect();
+        ordered.extend(
+            criteria
+                .iter()
+                .filter(|c| c.context.trim() != "$statusCode"),
+        );
+
+        let mut scripted = Vec::new();
+        let mut assume_success = true;
+        for crit in ordered {
+            match Self::native_assertion(crit, assume_success) {
+                Some(assertion) => {
+                    if assertion.tag == "ResponseAssertion" {
+                        assume_success = false;
+                    }
+                    sampler = sampler.child(assertion);
+                }
+                None => scripted.push(crit),
+            }
+        }
+        if let Some(assertion) = Self::script_assertion(&scripted) {
+            sampler = sampler.child(assertion);
+        }
+
+        if context.branching {
+            sampler = sampler.child(Self::control_flow_assertion(step, next_step));
+        }
+
+        Ok(sampler)
+    }
+
+    /// Sampler that sets the inputs (and the first step) at each iteration
+    fn init_sampler(workflow: &Workflow, context: &WorkflowContext) -> TestElement {
+        let mut lines = Vec::new();
+        for (name, value) in common::input_defaults(workflow) {
+            let var = Self::input_var(&name);
+            lines.push(format!(
+                "vars.put({}, props.getProperty({}, {}))",
+                Self::groovy_string(&var),
+                Self::groovy_string(&var),
+                Self::groovy_string(&text_value(&value))
+            ));
+        }
+        if context.branching {
+            let first = workflow
+                .steps
+                .first()
+                .map(|s| s.step_id.as_str())
+                .unwrap_or_default();
+            lines.push(format!("vars.put('next', {})", Self::groovy_string(first)));
+        }
+
+        TestElement::new("TestAction", "TestActionGui", "init")
+            .prop(Prop::int("ActionProcessor.action", 1))
+            .prop(Prop::int("ActionProcessor.target", 0))
+            .prop(Prop::string("ActionProcessor.duration", "0"))
+            .child(TestElement::jsr223(
+                "JSR223PreProcessor",
+                "inputs",
+                lines.join("\n"),
+            ))
+    }
+
+    fn uses_branching(workflow: &Workflow) -> bool {
+        workflow.steps.iter().any(|step| {
+            step.on_success
+                .iter()
+                .flatten()
+                .map(|a| a.action_type.as_str())
+                .chain(
+                    step.on_failure
+                        .iter()
+                        .flatten()
+                        .map(|a| a.action_type.as_str()),
+                )
+                .any(|t| t == "goto" || t == "end")
+        })
+    }
+
+    /// Generate the Thread Group of a workflow
+    fn generate_thread_group(
+        &self,
+        workflow: &Workflow,
+        resolver: &OpenApiResolver,
+        settings: &ThreadSettings,
+    ) -> Result<TestElement> {
+        let context = WorkflowContext {
+            non_string_inputs: common::input_defaults(workflow)
+                .into_iter()
+                .filter(|(_, value)| !value.is_string() && !value.is_null())
+                .map(|(name, _)| name)
+                .collect(),
+            branching: Self::uses_branching(workflow),
+        };
+
+        let mut samplers = Vec::new();
+        for (i, step) in workflow.steps.iter().enumerate() {
+            let next_step = workflow.steps.get(i + 1).map(|s| s.step_id.as_str());
+            samplers.push(self.generate_sampler(step, next_step, resolver, &context)?);
+        }
+
+        let on_error = if context.branching {
+            // 分岐はcontrol flowアサーションで制御する
+            "continue"
+        } else {
+            "startnextloop"
+        };
+
+        let mut group = TestElement::new("ThreadGroup", "ThreadGroupGui", &workflow.workflow_id)
+            .prop(Prop::string("ThreadGroup.on_sample_error", on_error))
+            .prop(Prop::element(
+                "ThreadGroup.main_controller",
+                "LoopController",
+                vec![
+                    Prop::bool("LoopController.continue_forever", false),
+                    Prop::string("LoopController.loops", settings.loops.to_string()),
+                ],
+            ))
+            .prop(Prop::string(
+                "ThreadGroup.num_threads",
+                settings.threads.to_string(),
+            ))
+            .prop(Prop::string("ThreadGroup.ramp_time", "0"))
+            .prop(Prop::bool(
+                "ThreadGroup.scheduler",
+                settings.duration_secs.is_some(),
+            ))
+            .prop(Prop::string(
+                "ThreadGroup.duration",
+                settings
+                    .duration_secs
+                    .map(|d| d.to_string())
+                    .unwrap_or_default(),
+            ))
+            .prop(Prop::string("ThreadGroup.delay", ""))
+            .prop(Prop::bool("ThreadGroup.same_user_on_next_iteration", true));
+        if let Some(description) = workflow.description.as_ref().or(workflow.summary.as_ref()) {
+            group = group.prop(Prop::string("TestPlan.comments", description.trim()));
+        }
+
+        group = group.child(Self::init_sampler(workflow, &context));
+
+        if context.branching {
+            let mut loop_controller =
+                TestElement::new("WhileController", "WhileControllerGui", "steps").prop(
+                    Prop::string(
+                        "WhileController.condition",
+                        "${__groovy(vars.get('next') != '')}",
+                    ),
+                );
+            for sampler in samplers {
+                let condition = format!(
+                    "${{__groovy({})}}",
+                    Self::function_arg(&format!(
+                        "vars.get('next') == {}",
+                        Self::groovy_string(&sampler.name)
+                    ))
+                );
+                loop_controller = loop_controller.child(
+                    TestElement::new("IfController", "IfControllerPanel", sampler.name.clone())
+                        .prop(Prop::string("IfController.condition", condition))
+                        .prop(Prop::bool("IfController.evaluateAll", false))
+                        .prop(Prop::bool("IfController.useExpression", true))
+                        .child(sampler),
+                );
+            }
+            group = group.child(loop_controller);
+        } else {
+            for sampler in samplers {
+                group = group.child(sampler);
+            }
+        }
+
+        Ok(group)
+    }
+
+    /// HTTP Request Defaults pointing at the base URL (overridable with properties)
+    fn http_defaults(base_url: &str) -> Result<TestElement> {
+        let url = reqwest::Url::parse(base_url).map_err(|e| {
+            HornetError::ValidationError(format!("Invalid base URL '{}': {}", base_url, e))
+        })?;
+        let property = |name: &str, default: &str| {
+            format!("${{__P({},{})}}", name, Self::function_arg(default))
+        };
+
+        Ok(TestElement::new(
+            "ConfigTestElement",
+            "HttpDefaultsGui",
+            "HTTP Request Defaults",
+        )
+        .prop(Prop::arguments("HTTPsampler.Arguments", Vec::new()))
+        .prop(Prop::string(
+            "HTTPSampler.protocol",
+            property("protocol", url.scheme()),
+        ))
+        .prop(Prop::string(
+            "HTTPSampler.domain",
+            property("host", url.host_str().unwrap_or("localhost")),
+        ))
+        .prop(Prop::string(
+            "HTTPSampler.port",
+            property(
+                "port",
+                &url.port().map(|p| p.to_string()).unwrap_or_default(),
+            ),
+        ))
+        .prop(Prop::string("HTTPSampler.path", ""))
+        .prop(Prop::string("HTTPSampler.contentEncoding", "UTF-8")))
+    }
+
+    /// Assemble the test plan
+    fn render(
+        &self,
+        title: &str,
+        workflows: &[&Workflow],
+        resolver: &OpenApiResolver,
+        options: &ConvertOptions,
+    ) -> Result<String> {
+        let settings = ThreadSettings::from_options(options)?;
+        let base_url = common::resolve_base_url(resolver, options);
+        let base_path = reqwest::Url::parse(&base_url)
+            .map(|u| u.path().trim_end_matches('/').to_string())
+            .unwrap_or_default();
+
+        let mut plan = TestElement::new("TestPlan", "TestPlanGui", title)
+            .prop(Prop::element(
+                "TestPlan.user_defined_variables",
+                "Arguments",
+                vec![Prop::collection(
+                    "Arguments.arguments",
+                    vec![Prop::element(
+                        "basePath",
+                        "Argument",
+                        vec![
+                            Prop::string("Argument.name", "basePath"),
+                            Prop::string("Argument.value", base_path.clone()),
+                            Prop::string("Argument.metadata", "="),
+                        ],
+                    )],
+                )],
+            ))
+            .prop(Prop::bool("TestPlan.functional_mode", false))
+            .prop(Prop::bool("TestPlan.serialize_threadgroups", true))
+            .prop(Prop::string(
+                "TestPlan.comments",
+                "Generated by hornet2. Override the target with -Jprotocol, -Jhost, -Jport and -JbasePath, and inputs with -Jinputs.<name>.",
+            ))
+            .child(Self::http_defaults(&base_url)?);
+
+        for workflow in workflows {
+            plan = plan.child(self.generate_thread_group(workflow, resolver, &settings)?);
+        }
+
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str(&format!(
+            "<jmeterTestPlan version=\"1.2\" properties=\"5.0\" jmeter=\"{}\">\n",
+            JMETER_VERSION
+        ));
+        out.push_str("  <hashTree>\n");
+        plan.write(&mut out, 2);
+        out.push_str("  </hashTree>\n");
+        out.push_str("</jmeterTestPlan>\n");
+
+        // basePath プロパティが指定されていればそちらを優先する
+        Ok(out.replace(
+            "${__P(basePath,)}",
+            &format!("${{__P(basePath,{})}}", Self::function_arg(&base_path)),
+        ))
+    }
+}
+
+impl Converter for JMeterConverter {
+    type Output = String;
+
+    fn convert_spec(
+        &self,
+        arazzo: &ArazzoSpec,
+        resolver: &OpenApiResolver,
+        options: &ConvertOptions,
+    ) -> Result<Self::Output> {
+        let workflows: Vec<&Workflow> = arazzo.workflows.iter().collect();
+        self.render(&arazzo.info.title, &workflows, resolver, options)
+    }
+
+    fn convert_workflow(
+        &self,
+        workflow: &Workflow,
+        resolver: &OpenApiResolver,
+        options: &ConvertOptions,
+    ) -> Result<Self::Output> {
+        let title = workflow
+            .summary
+            .clone()
+            .unwrap_or_else(|| workflow.workflow_id.clone());
+        self.render(&title, &[workflow], resolver, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loader::arazzo::load_arazzo;
+    use serde_json::json;
+    use std::path::Path;
+
+    fn criterion(context: &str, condition: &str, value: serde_json::Value) -> SuccessCriteria {
+        SuccessCriteria {
+            context: context.to_string(),
+            condition: condition.to_string(),
+            value: Some(value),
+            criteria_type: None,
+        }
+    }
+
+    fn load_fixture() -> (ArazzoSpec, OpenApiResolver) {
+        let arazzo = load_arazzo("tests/fixtures/arazzo.yaml").unwrap();
+        let mut resolver = OpenApiResolver::new(Path::new("tests/fixtures"));
+        resolver
+            .load_spec("userAPI", Path::new("tests/fixtures/openapi.yaml"))
+            .unwrap();
+        (arazzo, resolver)
+    }
+
+    #[test]
+    fn test_thread_settings() {
+        let settings = ThreadSettings::from_options(&ConvertOptions {
+            vus: Some(4),
+            iterations: Some(10),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(
+            settings,
+            ThreadSettings {
+                threads: 4,
+                loops: 3,
+                duration_secs: None
+            }
+        );
+
+        let settings = ThreadSettings::from_options(&ConvertOptions {
+            duration: Some("2m".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(settings.loops, -1);
+        assert_eq!(settings.duration_secs, Some(120));
+
+        assert!(
+            ThreadSettings::from_options(&ConvertOptions {
+                duration: Some("soon".to_string()),
+                ..Default::default()
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_native_and_script_assertions() {
+        let status =
+            JMeterConverter::native_assertion(&criterion("$statusCode", "==", json!(201)), true)
+                .unwrap();
+        assert_eq!(status.tag, "ResponseAssertion");
+
+        let body = JMeterConverter::native_assertion(
+            &criterion("$response.body.email", "!=", json!("$inputs.email")),
+            false,
+        )
+        .unwrap();
+        let mut xml = String::new();
+        body.write(&mut xml, 0);
+        assert!(xml.contains("<stringProp name=\"EXPECTED_VALUE\">${inputs.email}</stringProp>"));
+        assert!(xml.contains("<boolProp name=\"INVERT\">true</boolProp>"));
+
+        let greater = criterion("$response.body.count", ">", json!(0));
+        assert!(JMeterConverter::native_assertion(&greater, false).is_none());
+        let script = JMeterConverter::script_assertion(&[&greater]).unwrap();
+        let mut xml = String::new();
+        script.write(&mut xml, 0);
+        assert!(xml.contains("if (!compare(json(SampleResult)?.count, '&gt;', 0))"));
+    }
+
+    #[test]
+    fn test_convert_spec_integration() {
+        let (arazzo, resolver) = load_fixture();
+
+        let jmx = JMeterConverter::new()
+            .convert_spec(
+                &arazzo,
+                &resolver,
+                &ConvertOptions {
+                    base_url: Some("https://api.test:8443/v1".to_string()),
+                    vus: Some(5),
+                    duration: Some("30s".to_string()),
+                    iterations: None,
+                    profile: None,
+                },
+            )
+            .unwrap();
+
+        assert!(jmx.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+        assert_eq!(jmx.matches("<ThreadGroup ").count(), arazzo.workflows.len());
+        assert!(jmx.contains("<stringProp name=\"ThreadGroup.num_threads\">5</stringProp>"));
+        assert!(jmx.contains("<stringProp name=\"ThreadGroup.duration\">30</stringProp>"));
+        assert!(jmx.contains("${__P(host,api.test)}"));
+        assert!(jmx.contains(
+            "<stringProp name=\"HTTPSampler.path\">${__P(basePath,/v1)}/register</stringProp>"
+        ));
+        assert!(jmx.contains("steps.register.outputs.userId;steps.register.outputs.username"));
+        assert!(jmx.contains("Bearer ${steps.login.outputs.token}"));
+        // 分岐のないワークフローは While/If コントローラを使わない
+        assert!(!jmx.contains("WhileController"));
+    }
+
+    #[test]
+    fn test_goto_uses_while_and_if_controllers() {
+        let (arazzo, resolver) = load_fixture();
+        let mut workflow = arazzo.workflows[1].clone();
+        workflow.steps[0].on_failure = Some(vec![
+            serde_json::from_value(json!({
+                "name": "retry",
+                "type": "goto",
+                "stepId": "login",
+                "criteria": [{"context": "$statusCode", "condition": "==", "value": 503}]
+            }))
+            .unwrap(),
+        ]);
+
+        let jmx = JMeterConverter::new()
+            .convert_workflow(&workflow, &resolver, &ConvertOptions::default())
+            .unwrap();
+
+        assert!(jmx.contains("<WhileController "));
+        assert!(jmx.contains("<IfController "));
+        assert!(jmx.contains(
+            "if (compare((SampleResult.getResponseCode() as Integer), '==', 503)) { vars.put('next', 'login'); return }"
+        ));
+        assert!(
+            jmx.contains("<stringProp name=\"ThreadGroup.on_sample_error\">continue</stringProp>")
+        );
+    }
+}
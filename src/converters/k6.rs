@@ -2,13 +2,41 @@
 //!
 //! This module generates k6 JavaScript test scripts from Arazzo workflows.
 
-use crate::error::{HornetError, Result};
+use crate::error::Result;
 use crate::loader::OpenApiResolver;
 use crate::models::arazzo::{ArazzoSpec, RequestBody, Step, SuccessCriteria, Workflow};
+use crate::models::profile::{Distribution, LoadProfile};
+use crate::payload::multipart::{self, PartContent};
+use crate::payload::{self, BodyEncoding, FileRef, form, xml};
 
-use super::{ConvertOptions, Converter};
+use super::{ConvertOptions, Converter, common};
 use regex::Regex;
 use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Draws a number of seconds from a distribution generated by `distribution_to_js`
+const SAMPLE_SECONDS_JS: &str = r#"function sampleSeconds(d) {
+  switch (d.distribution) {
+    case 'constant':
+      return d.value;
+    case 'uniform':
+      return d.min + (d.max - d.min) * Math.random();
+    case 'normal': {
+      const radius = Math.sqrt(-2 * Math.log(1 - Math.random()));
+      return Math.max(0, d.mean + d.stddev * radius * Math.cos(2 * Math.PI * Math.random()));
+    }
+    case 'exponential':
+      return -d.mean * Math.log(1 - Math.random());
+  }
+  return 0;
+}
+"#;
+
+/// Runtime expressions embedded in free text (`$steps.login.outputs.token`)
+fn runtime_expr_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\$[a-zA-Z][a-zA-Z0-9_.]*[a-zA-Z0-9_]").unwrap())
+}
 
 /// Converter for generating k6 test scripts
 #[derive(Debug, Clone, Default)]
@@ -20,24 +48,6 @@ impl K6Converter {
         Self
     }
 
-    /// Get the base URL from first OpenAPI spec in resolver
-    fn get_base_url(resolver: &OpenApiResolver) -> String {
-        resolver
-            .get_all_specs()
-            .values()
-            .next()
-            .and_then(|spec| spec.servers.first())
-            .map(|server| server.url.clone())
-            .unwrap_or_else(|| "http://localhost:8080".to_string())
-    }
-
-    /// Find operation info (path and method) by operationId using resolver
-    fn find_operation(resolver: &OpenApiResolver, operation_id: &str) -> Option<(String, String)> {
-        resolver
-            .find_operation(operation_id)
-            .map(|op_ref| (op_ref.path, op_ref.method))
-    }
-
     /// Convert a JSON value to JavaScript code
     fn json_to_js(value: &serde_json::Value, indent: usize) -> String {
         let indent_str = "  ".repeat(indent);
@@ -186,13 +196,13 @@ impl K6Converter {
         lines.push(self.generate_step_comment(step));
 
         // Get operation info
-        let (path, method) = self.get_operation_info(step, resolver)?;
+        let (path, method) = common::resolve_operation(step, resolver)?;
 
         // Build URL
         let final_url = self.build_url(base_url, &path, &step.parameters);
 
         // Generate request body and headers
-        let (body_code, headers_code) = self.generate_body_and_headers(step);
+        let (body_code, headers_code) = self.generate_body_and_headers(step)?;
 
         // Generate the HTTP call
         let response_var = format!("{}_response", step.step_id);
@@ -241,33 +251,6 @@ impl K6Converter {
         }
     }
 
-    fn get_operation_info(
-        &self,
-        step: &Step,
-        resolver: &OpenApiResolver,
-    ) -> Result<(String, String)> {
-        if let Some(ref op_id) = step.operation_id {
-            Self::find_operation(resolver, op_id).ok_or_else(|| {
-                HornetError::OperationNotFound(format!("Operation '{}' not found", op_id))
-            })
-        } else if let Some(ref op_path) = step.operation_path {
-            let parts: Vec<&str> = op_path.splitn(2, ' ').collect();
-            if parts.len() == 2 {
-                Ok((parts[1].to_string(), parts[0].to_uppercase()))
-            } else {
-                Err(HornetError::ValidationError(format!(
-                    "Invalid operationPath format: {}",
-                    op_path
-                )))
-            }
-        } else {
-            Err(HornetError::ValidationError(format!(
-                "Step '{}' has no operationId or operationPath",
-                step.step_id
-            )))
-        }
-    }
-
     fn build_url(
         &self,
         base_url: &str,
@@ -309,7 +292,7 @@ impl K6Converter {
         final_url
     }
 
-    fn generate_body_and_headers(&self, step: &Step) -> (Option<String>, Option<String>) {
+    fn generate_body_and_headers(&self, step: &Step) -> Result<(Option<String>, Option<String>)> {
         let mut headers: HashMap<String, String> = HashMap::new();
 
         // Process header parameters
@@ -322,17 +305,25 @@ impl K6Converter {
             }
         }
 
-        let body_code = step.request_body.as_ref().map(Self::generate_request_body);
-
-        if step.request_body.is_some() {
-            let content_type = step
-                .request_body
-                .as_ref()
-                .and_then(|b| b.content_type.clone())
-                .unwrap_or_else(|| "application/json".to_string());
-            headers
-                .entry("Content-Type".to_string())
-                .or_insert_with(|| format!("\"{}\"", content_type));
+        let body_code = step
+            .request_body
+            .as_ref()
+            .map(Self::generate_request_body)
+            .transpose()?;
+
+        if let Some(ref body) = step.request_body {
+            let encoding = BodyEncoding::from_content_type(body.content_type.as_deref());
+
+            // k6 sets the multipart Content-Type itself (it includes the boundary)
+            if encoding != BodyEncoding::Multipart {
+                let content_type = body
+                    .content_type
+                    .clone()
+                    .unwrap_or_else(|| encoding.default_content_type().to_string());
+                headers
+                    .entry("Content-Type".to_string())
+                    .or_insert_with(|| format!("\"{}\"", content_type));
+            }
         }
 
         let headers_code = if headers.is_empty() {
@@ -348,7 +339,7 @@ impl K6Converter {
             ))
         };
 
-        (body_code, headers_code)
+        Ok((body_code, headers_code))
     }
 
     fn generate_http_call(
@@ -415,13 +406,145 @@ impl K6Converter {
         }
     }
 
-    /// Generate request body code
-    fn generate_request_body(body: &RequestBody) -> String {
-        // Check if payload contains runtime expressions
-        let payload_str = Self::json_to_js(&body.payload, 0);
+    /// Generate request body code according to the body's content type
+    fn generate_request_body(body: &RequestBody) -> Result<String> {
+        let payload = payload::apply_replacements(body)?;
+
+        let code = match BodyEncoding::from_content_type(body.content_type.as_deref()) {
+            BodyEncoding::Json => format!("JSON.stringify({})", Self::json_to_js(&payload, 0)),
+            BodyEncoding::FormUrlEncoded => match payload {
+                serde_json::Value::String(ref s) => Self::text_to_js(s),
+                _ => {
+                    // Encode at runtime so repeated keys and expressions are preserved
+                    let pairs: Vec<String> = form::flatten(&payload)?
+                        .iter()
+                        .map(|(k, v)| {
+                            format!(
+                                "    [\"{}\", {}]",
+                                k.replace('"', "\\\""),
+                                Self::convert_value_with_expr(v)
+                            )
+                        })
+                        .collect();
+                    format!(
+                        "[\n{}\n  ].map(([k, v]) => `${{encodeURIComponent(k)}}=${{encodeURIComponent(v)}}`).join('&')",
+                        pairs.join(",\n")
+                    )
+                }
+            },
+            BodyEncoding::Multipart => Self::generate_multipart_body(&payload)?,
+            BodyEncoding::Xml => match payload {
+                serde_json::Value::String(ref s) => Self::text_to_js(s),
+                _ => Self::text_to_js(&xml::to_xml(&payload)?),
+            },
+            BodyEncoding::Raw => match payload {
+                serde_json::Value::String(ref s) => Self::text_to_js(s),
+                _ => format!("JSON.stringify({})", Self::json_to_js(&payload, 0)),
+            },
+        };
 
-        // Wrap in JSON.stringify
-        format!("JSON.stringify({})", payload_str)
+        Ok(code)
+    }
+
+    /// Generate a k6 multipart object (file parts use `http.file`)
+    fn generate_multipart_body(payload: &serde_json::Value) -> Result<String> {
+        let mut grouped: Vec<(String, Vec<String>)> = Vec::new();
+
+        for part in multipart::collect_parts(payload)? {
+            let value = match &part.content {
+                PartContent::Field(v) => Self::convert_value_with_expr(v),
+                PartContent::Json(v) => format!("JSON.stringify({})", Self::json_to_js(v, 2)),
+                PartContent::File(file) => format!(
+                    "http.file({}, \"{}\", \"{}\")",
+                    Self::file_var_name(file),
+                    file.file_name(),
+                    file.mime_type()
+                ),
+            };
+
+            match grouped.iter_mut().find(|(name, _)| *name == part.name) {
+                Some((_, values)) => values.push(value),
+                None => grouped.push((part.name.clone(), vec![value])),
+            }
+        }
+
+        let fields: Vec<String> = grouped
+            .into_iter()
+            .map(|(name, mut values)| {
+                let value = if values.len() == 1 {
+                    values.remove(0)
+                } else {
+                    format!("[{}]", values.join(", "))
+                };
+                format!("    \"{}\": {}", name, value)
+            })
+            .collect();
+
+        Ok(format!("{{\n{}\n  }}", fields.join(",\n")))
+    }
+
+    /// Convert free text (XML documents, plain text) to a JavaScript string literal
+    fn text_to_js(text: &str) -> String {
+        if !Self::contains_runtime_expr(text) {
+            return serde_json::to_string(text).unwrap_or_else(|_| "\"\"".to_string());
+        }
+
+        let escaped = text
+            .replace('\\', "\\\\")
+            .replace('`', "\\`")
+            .replace("${", "\\${");
+        let result = runtime_expr_regex().replace_all(&escaped, |caps: &regex::Captures| {
+            format!("${{{}}}", Self::convert_runtime_expr(&caps[0]))
+        });
+        format!("`{}`", result)
+    }
+
+    /// Name of the init-context variable holding a file's contents
+    fn file_var_name(file: &FileRef) -> String {
+        let sanitized: String = file
+            .path
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        format!("file_{}", sanitized.trim_matches('_'))
+    }
+
+    /// Generate `open()` calls for files used by multipart bodies
+    ///
+    /// k6 only allows `open()` in the init context, so files are loaded once
+    /// at the top of the script.
+    fn generate_file_declarations<'a>(steps: impl Iterator<Item = &'a Step>) -> Result<String> {
+        let mut declarations: Vec<String> = Vec::new();
+
+        for step in steps {
+            let Some(body) = step.request_body.as_ref() else {
+                continue;
+            };
+            if BodyEncoding::from_content_type(body.content_type.as_deref())
+                != BodyEncoding::Multipart
+            {
+                continue;
+            }
+
+            for part in multipart::collect_parts(&payload::apply_replacements(body)?)? {
+                if let PartContent::File(file) = part.content {
+                    let line = format!(
+                        "const {} = open('{}', 'b');",
+                        Self::file_var_name(&file),
+                        file.path.replace('\'', "\\'")
+                    );
+                    if !declarations.contains(&line) {
+                        declarations.push(line);
+                    }
+                }
+            }
+        }
+
+        if declarations.is_empty() {
+            Ok(String::new())
+        } else {
+            Ok(format!("{}\n", declarations.join("\n")))
+        }
     }
 
     /// Generate check assertions
@@ -952,27 +1075,225 @@ impl K6Converter {
 
     /// Generate workflow inputs
     fn generate_inputs(workflow: &Workflow) -> String {
-        if let Some(serde_json::Value::Object(props_map)) =
-            workflow.inputs.as_ref().and_then(|i| i.get("properties"))
-        {
-            let mut input_lines = Vec::new();
-            for (name, schema) in props_map {
-                let default_val = schema.get("default");
-                let value = if let Some(def) = default_val {
-                    Self::json_to_js(def, 0)
-                } else {
-                    match schema.get("type").and_then(|t| t.as_str()) {
-                        Some("string") => "\"\"".to_string(),
-                        Some("number") | Some("integer") => "0".to_string(),
-                        Some("boolean") => "false".to_string(),
-                        _ => "null".to_string(),
-                    }
-                };
-                input_lines.push(format!("    {}: {},", name, value));
+        let defaults = common::input_defaults(workflow);
+        if defaults.is_empty() {
+            return "  let inputs = {};\n".to_string();
+        }
+        let input_lines: Vec<String> = defaults
+            .iter()
+            .map(|(name, value)| format!("    {}: {},", name, Self::json_to_js(value, 0)))
+            .collect();
+        format!("  let inputs = {{\n{}\n  }};\n", input_lines.join("\n"))
+    }
+
+    /// Generate a workflow as a function named after its ID
+    ///
+    /// With a think time, every step but the first one starts with a pause.
+    fn generate_workflow_function(
+        &self,
+        workflow: &Workflow,
+        resolver: &OpenApiResolver,
+        base_url: &str,
+        think_time: Option<&Distribution>,
+    ) -> Result<Vec<String>> {
+        let mut lines = Vec::new();
+        let pause =
+            think_time.map(|d| format!("  sleep(sampleSeconds({}));", Self::distribution_to_js(d)));
+        let func_name = workflow.workflow_id.replace('-', "_");
+        lines.push(format!("// Workflow: {}", workflow.workflow_id));
+        if let Some(ref summary) = workflow.summary {
+            lines.push(format!("// {}", summary));
+        }
+
+        // Check if workflow has conditional branching
+        let has_branching = workflow
+            .steps
+            .iter()
+            .any(|s| s.on_success.is_some() || s.on_failure.is_some());
+
+        if has_branching {
+            // Generate step functions for branching workflow
+            let step_map: HashMap<String, &Step> = workflow
+                .steps
+                .iter()
+                .map(|s| (s.step_id.clone(), s))
+                .collect();
+
+            // Generate each step as a separate function (with inputs parameter)
+            for (index, step) in workflow.steps.iter().enumerate() {
+                let step_func_name = format!("{}__step_{}", func_name, step.step_id);
+                lines.push(format!("function {}(inputs) {{", step_func_name));
+                if let Some(pause) = pause.as_ref().filter(|_| index > 0) {
+                    lines.push(pause.clone());
+                }
+
+                // Generate step code
+                let step_code = self.generate_step(step, resolver, base_url)?;
+                lines.push(step_code);
+
+                // Return response variable for conditional logic
+                let response_var = format!("{}_response", step.step_id);
+                lines.push(format!("  return {};", response_var));
+                lines.push("}".to_string());
+                lines.push(String::new());
+            }
+
+            // Generate main workflow function with control flow
+            lines.push(format!("function {}() {{", func_name));
+
+            // Add inputs
+            let inputs = Self::generate_inputs(workflow);
+            lines.push(inputs);
+
+            // Generate control flow
+            let control_flow =
+                self.generate_control_flow_for_multi(&workflow.steps, &step_map, &func_name, "  ")?;
+            lines.push(control_flow);
+
+            lines.push("}".to_string());
+            lines.push(String::new());
+        } else {
+            // Simple sequential workflow
+            lines.push(format!("function {}() {{", func_name));
+
+            // Add inputs
+            let inputs = Self::generate_inputs(workflow);
+            lines.push(inputs);
+
+            // Generate steps
+            for (index, step) in workflow.steps.iter().enumerate() {
+                if let Some(pause) = pause.as_ref().filter(|_| index > 0) {
+                    lines.push(pause.clone());
+                }
+                let step_code = self.generate_step(step, resolver, base_url)?;
+                lines.push(step_code);
             }
-            return format!("  let inputs = {{\n{}\n  }};\n", input_lines.join("\n"));
+
+            lines.push("}".to_string());
+            lines.push(String::new());
         }
-        "  let inputs = {};\n".to_string()
+
+        Ok(lines)
+    }
+
+    /// Generate a distribution as an object read by `sampleSeconds`
+    fn distribution_to_js(distribution: &Distribution) -> String {
+        match *distribution {
+            Distribution::Constant { value } => format!(
+                "{{ distribution: 'constant', value: {} }}",
+                value.as_secs_f64()
+            ),
+            Distribution::Uniform { min, max } => format!(
+                "{{ distribution: 'uniform', min: {}, max: {} }}",
+                min.as_secs_f64(),
+                max.as_secs_f64()
+            ),
+            Distribution::Normal { mean, stddev } => format!(
+                "{{ distribution: 'normal', mean: {}, stddev: {} }}",
+                mean.as_secs_f64(),
+                stddev.as_secs_f64()
+            ),
+            Distribution::Exponential { mean } => format!(
+                "{{ distribution: 'exponential', mean: {} }}",
+                mean.as_secs_f64()
+            ),
+        }
+    }
+
+    /// Generate a script picking one workflow of the profile per iteration
+    ///
+    /// Each workflow runs inside a `group` so that k6 reports its metrics
+    /// separately; pacing sleeps for the rest of the iteration's target time.
+    fn convert_mix(
+        &self,
+        arazzo: &ArazzoSpec,
+        resolver: &OpenApiResolver,
+        options: &ConvertOptions,
+        profile: &LoadProfile,
+    ) -> Result<String> {
+        let base_url = common::resolve_base_url(resolver, options);
+        let shares: Vec<(&Workflow, f64)> = profile
+            .shares()
+            .into_iter()
+            .filter(|(_, share)| *share > 0.0)
+            .map(|(id, share)| Ok((common::find_workflow(arazzo, id)?, share)))
+            .collect::<Result<_>>()?;
+
+        let mut lines = Vec::new();
+
+        // Add header comment
+        lines.push("// k6 script generated from Arazzo specification".to_string());
+        let mix: Vec<String> = shares
+            .iter()
+            .map(|(w, share)| format!("{} {:.0}%", w.workflow_id, share * 100.0))
+            .collect();
+        lines.push(format!("// Workflow mix: {}", mix.join(", ")));
+        lines.push(String::new());
+
+        // Add imports
+        lines.push("import http from 'k6/http';".to_string());
+        lines.push("import { check, group, sleep } from 'k6';".to_string());
+        lines.push(String::new());
+
+        // Load files used by multipart bodies (init context)
+        let files = Self::generate_file_declarations(shares.iter().flat_map(|(w, _)| &w.steps))?;
+        if !files.is_empty() {
+            lines.push(files);
+        }
+
+        // Add options
+        lines.push(Self::generate_options(options));
+        lines.push(SAMPLE_SECONDS_JS.to_string());
+
+        for (workflow, _) in &shares {
+            let think_time = profile.think_time(&workflow.workflow_id);
+            lines.extend(self.generate_workflow_function(
+                workflow,
+                resolver,
+                &base_url,
+                think_time.as_ref(),
+            )?);
+        }
+
+        // Cumulative shares: an iteration runs the first entry above a random number
+        lines.push("const mix = [".to_string());
+        let mut until = 0.0;
+        for (workflow, share) in &shares {
+            until += share;
+            let pacing = profile
+                .pacing(&workflow.workflow_id)
+                .map(|d| Self::distribution_to_js(&d))
+                .unwrap_or_else(|| "null".to_string());
+            lines.push(format!(
+                "  {{ name: '{}', run: {}, until: {}, pacing: {} }},",
+                workflow.workflow_id,
+                workflow.workflow_id.replace('-', "_"),
+                until,
+                pacing
+            ));
+        }
+        lines.push("];".to_string());
+        lines.push(String::new());
+
+        lines.push("export default function () {".to_string());
+        lines.push("  const started = Date.now();".to_string());
+        lines.push("  const r = Math.random();".to_string());
+        lines.push(
+            "  const entry = mix.find((e) => r < e.until) || mix[mix.length - 1];".to_string(),
+        );
+        lines.push("  group(entry.name, entry.run);".to_string());
+        lines.push("  if (entry.pacing) {".to_string());
+        lines.push(
+            "    const rest = sampleSeconds(entry.pacing) - (Date.now() - started) / 1000;"
+                .to_string(),
+        );
+        lines.push("    if (rest > 0) {".to_string());
+        lines.push("      sleep(rest);".to_string());
+        lines.push("    }".to_string());
+        lines.push("  }".to_string());
+        lines.push("}".to_string());
+
+        Ok(lines.join("\n"))
     }
 }
 
@@ -989,16 +1310,23 @@ impl Converter for K6Converter {
             return Ok(String::new());
         }
 
+        // A load profile picks one workflow per iteration
+        let profile = match options.profile {
+            Some(ref profile) => Some(profile.clone()),
+            None => LoadProfile::from_arazzo(arazzo)?,
+        };
+        if let Some(ref profile) = profile {
+            profile.validate(arazzo)?;
+            return self.convert_mix(arazzo, resolver, options, profile);
+        }
+
         // If only one workflow, generate it directly
         if arazzo.workflows.len() == 1 {
             return self.convert_workflow(&arazzo.workflows[0], resolver, options);
         }
 
         // For multiple workflows, combine them into one script with separate functions
-        let base_url = options
-            .base_url
-            .clone()
-            .unwrap_or_else(|| Self::get_base_url(resolver));
+        let base_url = common::resolve_base_url(resolver, options);
 
         let mut lines = Vec::new();
 
@@ -1012,82 +1340,19 @@ impl Converter for K6Converter {
         lines.push("import { check, sleep } from 'k6';".to_string());
         lines.push(String::new());
 
+        // Load files used by multipart bodies (init context)
+        let files =
+            Self::generate_file_declarations(arazzo.workflows.iter().flat_map(|w| &w.steps))?;
+        if !files.is_empty() {
+            lines.push(files);
+        }
+
         // Add options
         lines.push(Self::generate_options(options));
 
         // Generate each workflow as a separate function
         for workflow in &arazzo.workflows {
-            let func_name = workflow.workflow_id.replace('-', "_");
-            lines.push(format!("// Workflow: {}", workflow.workflow_id));
-            if let Some(ref summary) = workflow.summary {
-                lines.push(format!("// {}", summary));
-            }
-
-            // Check if workflow has conditional branching
-            let has_branching = workflow
-                .steps
-                .iter()
-                .any(|s| s.on_success.is_some() || s.on_failure.is_some());
-
-            if has_branching {
-                // Generate step functions for branching workflow
-                let step_map: HashMap<String, &Step> = workflow
-                    .steps
-                    .iter()
-                    .map(|s| (s.step_id.clone(), s))
-                    .collect();
-
-                // Generate each step as a separate function (with inputs parameter)
-                for step in &workflow.steps {
-                    let step_func_name = format!("{}__step_{}", func_name, step.step_id);
-                    lines.push(format!("function {}(inputs) {{", step_func_name));
-
-                    // Generate step code
-                    let step_code = self.generate_step(step, resolver, &base_url)?;
-                    lines.push(step_code);
-
-                    // Return response variable for conditional logic
-                    let response_var = format!("{}_response", step.step_id);
-                    lines.push(format!("  return {};", response_var));
-                    lines.push("}".to_string());
-                    lines.push(String::new());
-                }
-
-                // Generate main workflow function with control flow
-                lines.push(format!("function {}() {{", func_name));
-
-                // Add inputs
-                let inputs = Self::generate_inputs(workflow);
-                lines.push(inputs);
-
-                // Generate control flow
-                let control_flow = self.generate_control_flow_for_multi(
-                    &workflow.steps,
-                    &step_map,
-                    &func_name,
-                    "  ",
-                )?;
-                lines.push(control_flow);
-
-                lines.push("}".to_string());
-                lines.push(String::new());
-            } else {
-                // Simple sequential workflow
-                lines.push(format!("function {}() {{", func_name));
-
-                // Add inputs
-                let inputs = Self::generate_inputs(workflow);
-                lines.push(inputs);
-
-                // Generate steps
-                for step in &workflow.steps {
-                    let step_code = self.generate_step(step, resolver, &base_url)?;
-                    lines.push(step_code);
-                }
-
-                lines.push("}".to_string());
-                lines.push(String::new());
-            }
+            lines.extend(self.generate_workflow_function(workflow, resolver, &base_url, None)?);
         }
 
         // Add default function that calls all workflows
@@ -1108,10 +1373,7 @@ impl Converter for K6Converter {
         resolver: &OpenApiResolver,
         options: &ConvertOptions,
     ) -> Result<Self::Output> {
-        let base_url = options
-            .base_url
-            .clone()
-            .unwrap_or_else(|| Self::get_base_url(resolver));
+        let base_url = common::resolve_base_url(resolver, options);
 
         let mut lines = Vec::new();
 
@@ -1130,6 +1392,12 @@ impl Converter for K6Converter {
         lines.push("import { check, sleep } from 'k6';".to_string());
         lines.push(String::new());
 
+        // Load files used by multipart bodies (init context)
+        let files = Self::generate_file_declarations(workflow.steps.iter())?;
+        if !files.is_empty() {
+            lines.push(files);
+        }
+
         // Add options
         lines.push(Self::generate_options(options));
 
@@ -1242,6 +1510,72 @@ mod tests {
         assert!(code.contains("headers: {'X-Custom': 'value'}"));
     }
 
+    #[test]
+    fn test_generate_request_body_by_content_type() {
+        let form = RequestBody {
+            content_type: Some("application/x-www-form-urlencoded".to_string()),
+            payload: serde_json::json!({"username": "$inputs.username", "remember": true}),
+            replacements: Vec::new(),
+        };
+        let code = K6Converter::generate_request_body(&form).unwrap();
+        assert!(code.contains("[\"username\", inputs.username]"));
+        assert!(code.contains("encodeURIComponent"));
+        assert!(!code.contains("JSON.stringify"));
+
+        let xml = RequestBody {
+            content_type: Some("application/xml".to_string()),
+            payload: serde_json::json!("<user><name>placeholder</name></user>"),
+            replacements: vec![crate::models::arazzo::PayloadReplacement {
+                target: "/user/name".to_string(),
+                value: serde_json::json!("$inputs.name"),
+            }],
+        };
+        let code = K6Converter::generate_request_body(&xml).unwrap();
+        assert_eq!(code, "`<user><name>${inputs.name}</name></user>`");
+
+        let text = RequestBody {
+            content_type: Some("text/plain".to_string()),
+            payload: serde_json::json!("line one\nline two"),
+            replacements: Vec::new(),
+        };
+        let code = K6Converter::generate_request_body(&text).unwrap();
+        assert_eq!(code, "\"line one\\nline two\"");
+    }
+
+    #[test]
+    fn test_multipart_body_and_file_declarations() {
+        let step: Step = serde_yaml::from_str(
+            r#"
+stepId: upload
+operationPath: POST /avatar
+requestBody:
+  contentType: multipart/form-data
+  payload:
+    userId: $steps.login.outputs.userId
+    avatar:
+      $file: ./fixtures/avatar.png
+      contentType: image/png
+"#,
+        )
+        .unwrap();
+
+        let converter = K6Converter::new();
+        let (body, headers) = converter.generate_body_and_headers(&step).unwrap();
+        let body = body.unwrap();
+        assert!(body.contains("\"userId\": login_userId"));
+        assert!(body.contains(
+            "\"avatar\": http.file(file_fixtures_avatar_png, \"avatar.png\", \"image/png\")"
+        ));
+        // k6 computes the multipart Content-Type (with boundary) itself
+        assert!(headers.is_none());
+
+        let files = K6Converter::generate_file_declarations(std::iter::once(&step)).unwrap();
+        assert_eq!(
+            files,
+            "const file_fixtures_avatar_png = open('./fixtures/avatar.png', 'b');\n"
+        );
+    }
+
     #[test]
     fn test_convert_workflow_integration() {
         use crate::loader::OpenApiResolver;
@@ -1267,4 +1601,71 @@ mod tests {
         assert!(!script.contains("registerUser")); // Should use actual paths
         assert!(script.contains("/register") || script.contains("/login"));
     }
+
+    #[test]
+    fn test_convert_spec_with_profile() {
+        use crate::loader::OpenApiResolver;
+        use std::path::Path;
+
+        let arazzo = load_arazzo("tests/fixtures/arazzo.yaml").unwrap();
+        let mut resolver = OpenApiResolver::new(Path::new("tests/fixtures"));
+        resolver
+            .load_spec("userAPI", Path::new("tests/fixtures/openapi.yaml"))
+            .unwrap();
+
+        let profile: LoadProfile = serde_yaml::from_str(
+            r#"
+workflows:
+  - workflowId: user-onboarding-flow
+    weight: 3
+    pacing: { distribution: constant, value: 5s }
+  - workflowId: simple-login-flow
+    weight: 1
+thinkTime: { distribution: uniform, min: 500ms, max: 2s }
+"#,
+        )
+        .unwrap();
+        let options = ConvertOptions {
+            profile: Some(profile),
+            ..Default::default()
+        };
+
+        let script = K6Converter::new()
+            .convert_spec(&arazzo, &resolver, &options)
+            .unwrap();
+        assert!(
+            script.contains("// Workflow mix: user-onboarding-flow 75%, simple-login-flow 25%")
+        );
+        assert!(script.contains("import { check, group, sleep } from 'k6';"));
+        assert!(script.contains("function sampleSeconds(d)"));
+        assert!(script.contains(
+            "{ name: 'user-onboarding-flow', run: user_onboarding_flow, until: 0.75, pacing: { distribution: 'constant', value: 5 } },"
+        ));
+        assert!(script.contains(
+            "{ name: 'simple-login-flow', run: simple_login_flow, until: 1, pacing: null },"
+        ));
+        assert!(script.contains("group(entry.name, entry.run);"));
+        assert!(
+            script.contains("sleep(sampleSeconds({ distribution: 'uniform', min: 0.5, max: 2 }));")
+        );
+
+        // 異常系: 存在しないワークフロー
+        let options = ConvertOptions {
+            profile: Some(LoadProfile {
+                workflows: vec![crate::models::profile::WorkflowWeight {
+                    workflow_id: "missing".to_string(),
+                    weight: 1.0,
+                    think_time: None,
+                    pacing: None,
+                }],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(
+            K6Converter::new()
+                .convert_spec(&arazzo, &resolver, &options)
+                .is_err()
+        );
+    }
 }
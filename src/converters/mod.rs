@@ -3,13 +3,28 @@
 //! This module provides traits and implementations for converting
 //! Arazzo workflows to various test script formats.
 
+pub mod common;
+pub mod curl;
+pub mod external;
+pub mod hurl;
+pub mod jmeter;
 pub mod k6;
+pub mod postman;
+pub mod registry;
+pub mod rust;
 
+pub use curl::CurlConverter;
+pub use hurl::HurlConverter;
+pub use jmeter::JMeterConverter;
 pub use k6::K6Converter;
+pub use postman::PostmanConverter;
+pub use registry::{ConvertInput, ConverterRegistry, DynConverter, OutputFile};
+pub use rust::RustConverter;
 
 use crate::error::Result;
 use crate::loader::OpenApiResolver;
 use crate::models::arazzo::{ArazzoSpec, Workflow};
+use crate::models::profile::LoadProfile;
 
 /// Configuration options for converters
 #[derive(Debug, Clone, Default)]
@@ -22,6 +37,8 @@ pub struct ConvertOptions {
     pub duration: Option<String>,
     /// Number of iterations (mutually exclusive with duration)
     pub iterations: Option<u32>,
+    /// Workflow mix overriding the `x-load-profile` extension of the spec
+    pub profile: Option<LoadProfile>,
 }
 
 /// Trait for converting Arazzo workflows to test scripts
@@ -0,0 +1,1024 @@
+//! Postman Collection v2.1 converter
+//!
+//! Each workflow becomes a folder and each step a request. Step outputs are
+//! stored as collection variables (`<workflowId>.steps.<stepId>.outputs.<name>`,
+//! so workflows sharing a stepId do not overwrite each other) from the
+//! request's test script, workflow inputs are exposed as `inputs.<name>` and
+//! successCriteria become `pm.test` assertions.
+
+use super::{ConvertOptions, Converter, common};
+use crate::error::Result;
+use crate::loader::OpenApiResolver;
+use crate::models::arazzo::{ArazzoSpec, RequestBody, Step, SuccessCriteria, Workflow};
+use crate::models::expression::{
+    ExpressionSource, RuntimeExpression, TemplateSegment, as_single_expression, parse_template,
+};
+use crate::payload::multipart::{self, PartContent};
+use crate::payload::{self, BodyEncoding, form, text_value, xml};
+use serde::Serialize;
+
+/// Postman Collection v2.1 schema URL
+pub const POSTMAN_SCHEMA: &str =
+    "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+/// Converter for generating Postman collections
+#[derive(Debug, Clone, Default)]
+pub struct PostmanConverter;
+
+#[derive(Debug, Serialize)]
+struct Collection {
+    info: CollectionInfo,
+    item: Vec<Folder>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    variable: Vec<Variable>,
+}
+
+#[derive(Debug, Serialize)]
+struct CollectionInfo {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    schema: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct Folder {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    item: Vec<Item>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    event: Vec<Event>,
+}
+
+#[derive(Debug, Serialize)]
+struct Item {
+    id: String,
+    name: String,
+    request: Request,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    event: Vec<Event>,
+}
+
+#[derive(Debug, Serialize)]
+struct Request {
+    method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    header: Vec<KeyValue>,
+    url: Url,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<Body>,
+}
+
+#[derive(Debug, Serialize)]
+struct Url {
+    raw: String,
+    host: Vec<String>,
+    path: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    query: Vec<KeyValue>,
+}
+
+#[derive(Debug, Serialize)]
+struct KeyValue {
+    key: String,
+    value: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Variable {
+    key: String,
+    value: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "mode", rename_all = "lowercase")]
+enum Body {
+    Raw { raw: String, options: RawOptions },
+    Urlencoded { urlencoded: Vec<KeyValue> },
+    Formdata { formdata: Vec<FormField> },
+}
+
+#[derive(Debug, Serialize)]
+struct RawOptions {
+    raw: RawLanguage,
+}
+
+#[derive(Debug, Serialize)]
+struct RawLanguage {
+    language: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum FormField {
+    Text {
+        key: String,
+        value: String,
+        #[serde(rename = "contentType", skip_serializing_if = "Option::is_none")]
+        content_type: Option<String>,
+    },
+    File {
+        key: String,
+        src: String,
+    },
+}
+
+#[derive(Debug, Serialize)]
+struct Event {
+    listen: &'static str,
+    script: Script,
+}
+
+#[derive(Debug, Serialize)]
+struct Script {
+    #[serde(rename = "type")]
+    script_type: &'static str,
+    exec: Vec<String>,
+}
+
+impl Event {
+    fn new(listen: &'static str, exec: Vec<String>) -> Self {
+        Self {
+            listen,
+            script: Script {
+                script_type: "text/javascript",
+                exec,
+            },
+        }
+    }
+}
+
+impl PostmanConverter {
+    /// Create a new PostmanConverter
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Collection variable holding a workflow input
+    fn input_var(name: &str) -> String {
+        format!("inputs.{}", name)
+    }
+
+    /// Collection variable holding a step output, scoped to its workflow
+    fn output_var(workflow_id: &str, step_id: &str, output: &str) -> String {
+        format!("{}.steps.{}.outputs.{}", workflow_id, step_id, output)
+    }
+
+    /// Item id used as `postman.setNextRequest` target
+    fn item_id(workflow_id: &str, step_id: &str) -> String {
+        format!("{}/{}", workflow_id, step_id)
+    }
+
+    /// Render a property path as a JavaScript accessor (`.a[0]["b-c"]`)
+    fn js_accessor(path: &[String]) -> String {
+        path.iter()
+            .map(|segment| {
+                if segment.parse::<usize>().is_ok() {
+                    format!("[{}]", segment)
+                } else if !segment.is_empty()
+                    && !segment.starts_with(|c: char| c.is_ascii_digit())
+                    && segment
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_')
+                {
+                    format!(".{}", segment)
+                } else {
+                    format!("[{}]", serde_json::Value::from(segment.as_str()))
+                }
+            })
+            .collect()
+    }
+
+    /// Convert a runtime expression into a script expression
+    fn expr_to_js(workflow_id: &str, expr: &RuntimeExpression) -> String {
+        let quote = |s: &str| serde_json::Value::from(s).to_string();
+        match expr {
+            RuntimeExpression::StatusCode => "pm.response.code".to_string(),
+            RuntimeExpression::Url => "pm.request.url.toString()".to_string(),
+            RuntimeExpression::Method => "pm.request.method".to_string(),
+            RuntimeExpression::Response(ExpressionSource::Body(path)) => {
+                format!("pm.response.json(){}", Self::js_accessor(path))
+            }
+            RuntimeExpression::Response(ExpressionSource::Header(name)) => {
+                format!("pm.response.headers.get({})", quote(name))
+            }
+            RuntimeExpression::Request(ExpressionSource::Header(name)) => {
+                format!("pm.request.headers.get({})", quote(name))
+            }
+            RuntimeExpression::Request(ExpressionSource::Query(name)) => {
+                format!("pm.request.url.query.get({})", quote(name))
+            }
+            RuntimeExpression::Inputs(path) if !path.is_empty() => {
+                let value = format!("pm.variables.get({})", quote(&Self::input_var(&path[0])));
+                if path.len() == 1 {
+                    value
+                } else {
+                    format!("JSON.parse({}){}", value, Self::js_accessor(&path[1..]))
+                }
+            }
+            RuntimeExpression::StepOutput {
+                step_id,
+                output,
+                path,
+            } => {
+                let value = format!(
+                    "pm.collectionVariables.get({})",
+                    quote(&Self::output_var(workflow_id, step_id, output))
+                );
+                if path.is_empty() {
+                    value
+                } else {
+                    format!("JSON.parse({}){}", value, Self::js_accessor(path))
+                }
+            }
+            other => quote(&other.to_string()),
+        }
+    }
+
+    /// Convert a string that may embed runtime expressions into Postman
+    /// `{{variable}}` syntax
+    ///
+    /// Expressions that cannot be referenced directly (nested paths) are
+    /// computed into local variables by lines appended to `pre_request`.
+    fn template_to_postman(workflow_id: &str, text: &str, pre_request: &mut Vec<String>) -> String {
+        parse_template(text)
+            .into_iter()
+            .map(|segment| match segment {
+                TemplateSegment::Literal(s) => s,
+                TemplateSegment::Expression(expr) => {
+                    Self::expr_to_variable(workflow_id, &expr, pre_request)
+                }
+            })
+            .collect()
+    }
+
+    fn expr_to_variable(
+        workflow_id: &str,
+        expr: &RuntimeExpression,
+        pre_request: &mut Vec<String>,
+    ) -> String {
+        match expr {
+            RuntimeExpression::Inputs(path) if path.len() == 1 => {
+                format!("{{{{{}}}}}", Self::input_var(&path[0]))
+            }
+            RuntimeExpression::StepOutput {
+                step_id,
+                output,
+                path,
+            } if path.is_empty() => {
+                format!("{{{{{}}}}}", Self::output_var(workflow_id, step_id, output))
+            }
+            RuntimeExpression::Inputs(_) | RuntimeExpression::StepOutput { .. } => {
+                let name = expr.to_string().trim_start_matches('$').to_string();
+                let line = format!(
+                    "pm.variables.set({}, {});",
+                    serde_json::Value::from(name.as_str()),
+                    Self::expr_to_js(workflow_id, expr)
+                );
+                if !pre_request.contains(&line) {
+                    pre_request.push(line);
+                }
+                format!("{{{{{}}}}}", name)
+            }
+            other => other.to_string(),
+        }
+    }
+
+    /// Convert a parameter or payload value into Postman text
+    fn value_to_postman(
+        workflow_id: &str,
+        value: &serde_json::Value,
+        pre_request: &mut Vec<String>,
+    ) -> String {
+        Self::template_to_postman(workflow_id, &text_value(value), pre_request)
+    }
+
+    /// Replace runtime expressions in every string of a JSON payload
+    fn templated_json(
+        workflow_id: &str,
+        value: &serde_json::Value,
+        pre_request: &mut Vec<String>,
+    ) -> serde_json::Value {
+        match value {
+            serde_json::Value::String(s) => {
+                serde_json::Value::String(Self::template_to_postman(workflow_id, s, pre_request))
+            }
+            serde_json::Value::Array(items) => serde_json::Value::Array(
+                items
+                    .iter()
+                    .map(|v| Self::templated_json(workflow_id, v, pre_request))
+                    .collect(),
+            ),
+            serde_json::Value::Object(obj) => serde_json::Value::Object(
+                obj.iter()
+                    .map(|(k, v)| (k.clone(), Self::templated_json(workflow_id, v, pre_request)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    fn raw_body(raw: String, language: &'static str) -> Body {
+        Body::Raw {
+            raw,
+            options: RawOptions {
+                raw: RawLanguage { language },
+            },
+        }
+    }
+
+    /// Generate the request body according to its content type
+    fn generate_body(
+        workflow_id: &str,
+        body: &RequestBody,
+        pre_request: &mut Vec<String>,
+    ) -> Result<Body> {
+        let payload = payload::apply_replacements(body)?;
+        let encoding = BodyEncoding::from_content_type(body.content_type.as_deref());
+
+        if let serde_json::Value::String(text) = &payload
+            && encoding != BodyEncoding::Json
+        {
+            let language = if encoding == BodyEncoding::Xml {
+                "xml"
+            } else {
+                "text"
+            };
+            return Ok(Self::raw_body(
+                Self::template_to_postman(workflow_id, text, pre_request),
+                language,
+            ));
+        }
+
+        let body = match encoding {
+            BodyEncoding::Json => {
+                let templated = Self::templated_json(workflow_id, &payload, pre_request);
+                Self::raw_body(serde_json::to_string_pretty(&templated)?, "json")
+            }
+            BodyEncoding::FormUrlEncoded => Body::Urlencoded {
+                urlencoded: form::flatten(&payload)?
+                    .iter()
+                    .map(|(key, value)| KeyValue {
+                        key: key.clone(),
+                        value: Self::value_to_postman(workflow_id, value, pre_request),
+                    })
+                    .collect(),
+            },
+            BodyEncoding::Multipart => Body::Formdata {
+                formdata: multipart::collect_parts(&payload)?
+                    .into_iter()
+                    .map(|part| match part.content {
+                        PartContent::Field(value) => FormField::Text {
+                            key: part.name,
+                            value: Self::value_to_postman(workflow_id, &value, pre_request),
+                            content_type: None,
+                        },
+                        PartContent::Json(value) => FormField::Text {
+                            key: part.name,
+                            value: Self::templated_json(workflow_id, &value, pre_request)
+                                .to_string(),
+                            content_type: Some("application/json".to_string()),
+                        },
+                        PartContent::File(file) => FormField::File {
+                            key: part.name,
+                            src: file.path,
+                        },
+                    })
+                    .collect(),
+            },
+            BodyEncoding::Xml => Self::raw_body(
+                Self::template_to_postman(workflow_id, &xml::to_xml(&payload)?, pre_request),
+                "xml",
+            ),
+            BodyEncoding::Raw => Self::raw_body(
+                Self::template_to_postman(workflow_id, &text_value(&payload), pre_request),
+                "text",
+            ),
+        };
+
+        Ok(body)
+    }
+
+    /// Generate the request (URL, headers and body) for a step
+    fn generate_request(
+        &self,
+        workflow_id: &str,
+        step: &Step,
+        resolver: &OpenApiResolver,
+        pre_request: &mut Vec<String>,
+    ) -> Result<Request> {
+        let (path, method) = common::resolve_operation(step, resolver)?;
+
+        let mut path = path;
+        let mut query = Vec::new();
+        let mut header = Vec::new();
+        let mut cookies = Vec::new();
+
+        for param in &step.parameters {
+            let value = Self::value_to_postman(workflow_id, &param.value, pre_request);
+            match param.location.as_str() {
+                "path" => path = path.replace(&format!("{{{}}}", param.name), &value),
+                "query" => query.push(KeyValue {
+                    key: param.name.clone(),
+                    value,
+                }),
+                "header" => header.push(KeyValue {
+                    key: param.name.clone(),
+                    value,
+                }),
+                "cookie" => cookies.push(format!("{}={}", param.name, value)),
+                _ => {}
+            }
+        }
+
+        if !cookies.is_empty() {
+            header.push(KeyValue {
+                key: "Cookie".to_string(),
+                value: cookies.join("; "),
+            });
+        }
+
+        let body = match step.request_body {
+            Some(ref request_body) => {
+                let encoding =
+                    BodyEncoding::from_content_type(request_body.content_type.as_deref());
+                // Postman generates the multipart boundary itself
+                if encoding != BodyEncoding::Multipart {
+                    header.push(KeyValue {
+                        key: "Content-Type".to_string(),
+                        value: request_body
+                            .content_type
+                            .clone()
+                            .unwrap_or_else(|| encoding.default_content_type().to_string()),
+                    });
+                }
+                Some(Self::generate_body(workflow_id, request_body, pre_request)?)
+            }
+            None => None,
+        };
+
+        let mut raw = format!("{{{{baseUrl}}}}{}", path);
+        if !query.is_empty() {
+            let pairs: Vec<String> = query
+                .iter()
+                .map(|kv| format!("{}={}", kv.key, kv.value))
+                .collect();
+            raw = format!("{}?{}", raw, pairs.join("&"));
+        }
+
+        Ok(Request {
+            method,
+            description: step.description.clone(),
+            header,
+            url: Url {
+                raw,
+                host: vec!["{{baseUrl}}".to_string()],
+                path: path
+                    .split('/')
+                    .filter(|s| !s.is_empty())
+                    .map(|s| s.to_string())
+                    .collect(),
+                query,
+            },
+            body,
+        })
+    }
+
+    /// Convert a criterion into a script condition
+    fn criterion_condition(workflow_id: &str, crit: &SuccessCriteria) -> String {
+        let left = RuntimeExpression::parse(&crit.context)
+            .map(|expr| Self::expr_to_js(workflow_id, &expr))
+            .unwrap_or_else(|| serde_json::Value::from(crit.context.as_str()).to_string());
+
+        if crit.criteria_type.as_deref() == Some("regex") {
+            return format!(
+                "new RegExp({}).test(String({}))",
+                serde_json::Value::from(crit.condition.as_str()),
+                left
+            );
+        }
+
+        let right = Self::criterion_value(workflow_id, crit);
+        let operator = match crit.condition.as_str() {
+            "==" | "$eq" => "==",
+            "!=" | "$ne" => "!=",
+            ">" | "$gt" => ">",
+            ">=" | "$gte" => ">=",
+            "<" | "$lt" => "<",
+            "<=" | "$lte" => "<=",
+            _ => "==",
+        };
+        format!("{} {} {}", left, operator, right)
+    }
+
+    fn criterion_value(workflow_id: &str, crit: &SuccessCriteria) -> String {
+        match crit.value {
+            Some(ref value) => match as_single_expression(value) {
+                Some(expr) => Self::expr_to_js(workflow_id, &expr),
+                None => value.to_string(),
+            },
+            None => "true".to_string(),
+        }
+    }
+
+    /// Generate `pm.test` assertions for successCriteria
+    fn generate_tests(workflow_id: &str, criteria: &[SuccessCriteria]) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        for crit in criteria {
+            let name = match crit.value {
+                Some(ref value) => {
+                    format!("{} {} {}", crit.context, crit.condition, text_value(value))
+                }
+                None => format!("{} {}", crit.context, crit.condition),
+            };
+            let name = serde_json::Value::from(name).to_string();
+
+            if crit.criteria_type.as_deref() == Some("jsonpath") {
+                lines.push(format!("pm.test.skip({}, function () {{}});", name));
+                continue;
+            }
+
+            let left = RuntimeExpression::parse(&crit.context)
+                .map(|expr| Self::expr_to_js(workflow_id, &expr))
+                .unwrap_or_else(|| serde_json::Value::from(crit.context.as_str()).to_string());
+
+            let assertion = if crit.criteria_type.as_deref() == Some("regex") {
+                format!(
+                    "pm.expect(String({})).to.match(new RegExp({}));",
+                    left,
+                    serde_json::Value::from(crit.condition.as_str())
+                )
+            } else {
+                let right = Self::criterion_value(workflow_id, crit);
+                let chain = match crit.condition.as_str() {
+                    "!=" | "$ne" => "to.not.eql",
+                    ">" | "$gt" => "to.be.above",
+                    ">=" | "$gte" => "to.be.at.least",
+                    "<" | "$lt" => "to.be.below",
+                    "<=" | "$lte" => "to.be.at.most",
+                    _ => "to.eql",
+                };
+                format!("pm.expect({}).{}({});", left, chain, right)
+            };
+
+            lines.push(format!("pm.test({}, function () {{", name));
+            lines.push(format!("    {}", assertion));
+            lines.push("});".to_string());
+        }
+
+        lines
+    }
+
+    /// Generate the script storing step outputs as collection variables
+    fn generate_output_extraction(workflow_id: &str, step: &Step) -> Vec<String> {
+        let Some(serde_json::Value::Object(outputs)) = step.outputs.as_ref() else {
+            return Vec::new();
+        };
+
+        let mut lines = vec![
+            "function setOutput(name, extract) {".to_string(),
+            "    try {".to_string(),
+            "        const value = extract();".to_string(),
+            "        pm.collectionVariables.set(name, typeof value === \"object\" ? JSON.stringify(value) : value);".to_string(),
+            "    } catch (e) {".to_string(),
+            "        console.warn(\"Could not extract \" + name + \": \" + e);".to_string(),
+            "    }".to_string(),
+            "}".to_string(),
+        ];
+
+        for (name, expr) in outputs {
+            let extraction = match as_single_expression(expr) {
+                Some(expr) => Self::expr_to_js(workflow_id, &expr),
+                None => expr.to_string(),
+            };
+            lines.push(format!(
+                "setOutput({}, () => {});",
+                serde_json::Value::from(Self::output_var(workflow_id, &step.step_id, name)),
+                extraction
+            ));
+        }
+
+        lines
+    }
+
+    /// Generate `postman.setNextRequest` calls for onSuccess/onFailure
+    ///
+    /// `end` continues with the first request of the next workflow so that
+    /// ending one workflow does not stop the rest of the collection run.
+    fn generate_control_flow(
+        step: &Step,
+        workflow_id: &str,
+        next_workflow: Option<&str>,
+    ) -> Vec<String> {
+        if step.on_success.is_none() && step.on_failure.is_none() {
+            return Vec::new();
+        }
+
+        let end_target = next_workflow
+            .map(|id| serde_json::Value::from(id).to_string())
+            .unwrap_or_else(|| "null".to_string());
+
+        let action_lines = |action_type: &str,
+                            config: &indexmap::IndexMap<String, serde_json::Value>|
+         -> Option<String> {
+            let next = match action_type {
+                "goto" => serde_json::Value::from(Self::item_id(
+                    workflow_id,
+                    common::goto_target(config)?,
+                ))
+                .to_string(),
+                "end" => end_target.clone(),
+                _ => return None,
+            };
+            let call = format!("postman.setNextRequest({});", next);
+
+            let criteria: Vec<SuccessCriteria> = config
+                .get("criteria")
+                .and_then(|c| serde_json::from_value(c.clone()).ok())
+                .unwrap_or_default();
+            if criteria.is_empty() {
+                Some(format!("    {}", call))
+            } else {
+                let condition: Vec<String> = criteria
+                    .iter()
+                    .map(|c| Self::criterion_condition(workflow_id, c))
+                    .collect();
+                Some(format!("    if ({}) {}", condition.join(" && "), call))
+            }
+        };
+
+        let success_condition = match step.success_criteria {
+            Some(ref criteria) if !criteria.is_empty() => criteria
+                .iter()
+                .map(|c| Self::criterion_condition(workflow_id, c))
+                .collect::<Vec<_>>()
+                .join(" && "),
+            _ => "pm.response.code >= 200 && pm.response.code < 300".to_string(),
+        };
+
+        let mut lines = vec![format!("if ({}) {{", success_condition)];
+        for action in step.on_success.iter().flatten() {
+            lines.extend(action_lines(&action.action_type, &action.config));
+        }
+        lines.push("} else {".to_string());
+        for action in step.on_failure.iter().flatten() {
+            lines.extend(action_lines(&action.action_type, &action.config));
+        }
+        lines.push("}".to_string());
+        lines
+    }
+
+    /// Folder-level pre-request script exposing input defaults as `inputs.*`
+    ///
+    /// Environment variables with the same name take precedence.
+    fn generate_input_defaults(workflow: &Workflow) -> Vec<String> {
+        let defaults = common::input_defaults(workflow);
+        if defaults.is_empty() {
+            return Vec::new();
+        }
+
+        let mut lines = vec!["const defaults = {".to_string()];
+        for (name, value) in &defaults {
+            lines.push(format!(
+                "    {}: {},",
+                serde_json::Value::from(Self::input_var(name)),
+                value
+            ));
+        }
+        lines.push("};".to_string());
+        lines.push("Object.entries(defaults).forEach(([name, value]) => {".to_string());
+        lines.push("    if (!pm.environment.has(name)) {".to_string());
+        lines.push(
+            "        pm.variables.set(name, typeof value === \"object\" ? JSON.stringify(value) : value);"
+                .to_string(),
+        );
+        lines.push("    }".to_string());
+        lines.push("});".to_string());
+        lines
+    }
+
+    /// Generate a folder for a workflow
+    fn generate_folder(
+        &self,
+        workflow: &Workflow,
+        resolver: &OpenApiResolver,
+        next_workflow: Option<&str>,
+    ) -> Result<Folder> {
+        let mut items = Vec::new();
+
+        for step in &workflow.steps {
+            let mut pre_request = Vec::new();
+            let request =
+                self.generate_request(&workflow.workflow_id, step, resolver, &mut pre_request)?;
+
+            let mut tests = Self::generate_tests(
+                &workflow.workflow_id,
+                step.success_criteria.as_deref().unwrap_or(&[]),
+            );
+            tests.extend(Self::generate_output_extraction(
+                &workflow.workflow_id,
+                step,
+            ));
+            tests.extend(Self::generate_control_flow(
+                step,
+                &workflow.workflow_id,
+                next_workflow,
+            ));
+
+            let mut event = Vec::new();
+            if !pre_request.is_empty() {
+                event.push(Event::new("prerequest", pre_request));
+            }
+            if !tests.is_empty() {
+                event.push(Event::new("test", tests));
+            }
+
+            items.push(Item {
+                id: Self::item_id(&workflow.workflow_id, &step.step_id),
+                name: step.step_id.clone(),
+                request,
+                event,
+            });
+        }
+
+        let input_defaults = Self::generate_input_defaults(workflow);
+        let event = if input_defaults.is_empty() {
+            Vec::new()
+        } else {
+            vec![Event::new("prerequest", input_defaults)]
+        };
+
+        Ok(Folder {
+            name: workflow.workflow_id.clone(),
+            description: workflow
+                .description
+                .clone()
+                .or_else(|| workflow.summary.clone()),
+            item: items,
+            event,
+        })
+    }
+
+    /// Collection variables: the base URL and one entry per step output
+    fn generate_variables<'a>(
+        workflows: impl Iterator<Item = &'a Workflow>,
+        base_url: String,
+    ) -> Vec<Variable> {
+        let mut variables = vec![Variable {
+            key: "baseUrl".to_string(),
+            value: base_url,
+        }];
+
+        for workflow in workflows {
+            for step in &workflow.steps {
+                if let Some(serde_json::Value::Object(outputs)) = step.outputs.as_ref() {
+                    for name in outputs.keys() {
+                        let key = Self::output_var(&workflow.workflow_id, &step.step_id, name);
+                        if !variables.iter().any(|v| v.key == key) {
+                            variables.push(Variable {
+                                key,
+                                value: String::new(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        variables
+    }
+
+    fn render(collection: &Collection) -> Result<String> {
+        let mut json = serde_json::to_string_pretty(collection)?;
+        json.push('\n');
+        Ok(json)
+    }
+}
+
+impl Converter for PostmanConverter {
+    type Output = String;
+
+    fn convert_spec(
+        &self,
+        arazzo: &ArazzoSpec,
+        resolver: &OpenApiResolver,
+        options: &ConvertOptions,
+    ) -> Result<Self::Output> {
+        let mut folders = Vec::new();
+        for (i, workflow) in arazzo.workflows.iter().enumerate() {
+            let next_workflow = arazzo.workflows.get(i + 1).and_then(|next| {
+                next.steps
+                    .first()
+                    .map(|s| Self::item_id(&next.workflow_id, &s.step_id))
+            });
+            folders.push(self.generate_folder(workflow, resolver, next_workflow.as_deref())?);
+        }
+
+        let collection = Collection {
+            info: CollectionInfo {
+                name: arazzo.info.title.clone(),
+                description: arazzo
+                    .info
+                    .description
+                    .clone()
+                    .or_else(|| arazzo.info.summary.clone()),
+                schema: POSTMAN_SCHEMA,
+            },
+            item: folders,
+            variable: Self::generate_variables(
+                arazzo.workflows.iter(),
+                common::resolve_base_url(resolver, options),
+            ),
+        };
+
+        Self::render(&collection)
+    }
+
+    fn convert_workflow(
+        &self,
+        workflow: &Workflow,
+        resolver: &OpenApiResolver,
+        options: &ConvertOptions,
+    ) -> Result<Self::Output> {
+        let collection = Collection {
+            info: CollectionInfo {
+                name: workflow
+                    .summary
+                    .clone()
+                    .unwrap_or_else(|| workflow.workflow_id.clone()),
+                description: workflow.description.clone(),
+                schema: POSTMAN_SCHEMA,
+            },
+            item: vec![self.generate_folder(workflow, resolver, None)?],
+            variable: Self::generate_variables(
+                std::iter::once(workflow),
+                common::resolve_base_url(resolver, options),
+            ),
+        };
+
+        Self::render(&collection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loader::arazzo::load_arazzo;
+    use serde_json::json;
+    use std::path::Path;
+
+    fn criterion(context: &str, condition: &str, value: serde_json::Value) -> SuccessCriteria {
+        SuccessCriteria {
+            context: context.to_string(),
+            condition: condition.to_string(),
+            value: Some(value),
+            criteria_type: None,
+        }
+    }
+
+    #[test]
+    fn test_template_to_postman() {
+        let mut pre_request = Vec::new();
+        let text = PostmanConverter::template_to_postman(
+            "flow",
+            "Bearer $steps.login.outputs.token",
+            &mut pre_request,
+        );
+        assert_eq!(text, "Bearer {{flow.steps.login.outputs.token}}");
+        assert!(pre_request.is_empty());
+
+        let text = PostmanConverter::template_to_postman(
+            "flow",
+            "$steps.update.outputs.profile.email",
+            &mut pre_request,
+        );
+        assert_eq!(text, "{{steps.update.outputs.profile.email}}");
+        assert_eq!(
+            pre_request,
+            vec![
+                "pm.variables.set(\"steps.update.outputs.profile.email\", JSON.parse(pm.collectionVariables.get(\"flow.steps.update.outputs.profile\")).email);"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generate_tests() {
+        let lines = PostmanConverter::generate_tests(
+            "flow",
+            &[
+                criterion("$statusCode", "==", json!(201)),
+                criterion("$response.body.email", "==", json!("$inputs.email")),
+            ],
+        );
+
+        assert_eq!(lines[0], "pm.test(\"$statusCode == 201\", function () {");
+        assert_eq!(lines[1], "    pm.expect(pm.response.code).to.eql(201);");
+        assert_eq!(
+            lines[4],
+            "    pm.expect(pm.response.json().email).to.eql(pm.variables.get(\"inputs.email\"));"
+        );
+    }
+
+    #[test]
+    fn test_form_body() {
+        let body = RequestBody {
+            content_type: Some("application/x-www-form-urlencoded".to_string()),
+            payload: json!({"user": "$inputs.username"}),
+            replacements: Vec::new(),
+        };
+        let body = PostmanConverter::generate_body("flow", &body, &mut Vec::new()).unwrap();
+        let value = serde_json::to_value(&body).unwrap();
+        assert_eq!(value["mode"], "urlencoded");
+        assert_eq!(value["urlencoded"][0]["value"], "{{inputs.username}}");
+    }
+
+    #[test]
+    fn test_convert_spec_integration() {
+        let arazzo = load_arazzo("tests/fixtures/arazzo.yaml").unwrap();
+
+        let mut resolver = OpenApiResolver::new(Path::new("tests/fixtures"));
+        resolver
+            .load_spec("userAPI", Path::new("tests/fixtures/openapi.yaml"))
+            .unwrap();
+
+        let output = PostmanConverter::new()
+            .convert_spec(&arazzo, &resolver, &ConvertOptions::default())
+            .unwrap();
+        let collection: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(collection["info"]["schema"], POSTMAN_SCHEMA);
+        assert_eq!(collection["variable"][0]["key"], "baseUrl");
+        assert_eq!(
+            collection["item"].as_array().unwrap().len(),
+            arazzo.workflows.len()
+        );
+
+        let first = &collection["item"][0]["item"][0];
+        assert_eq!(first["name"], arazzo.workflows[0].steps[0].step_id);
+        assert!(
+            first["request"]["url"]["raw"]
+                .as_str()
+                .unwrap()
+                .starts_with("{{baseUrl}}/")
+        );
+    }
+
+    #[test]
+    fn test_outputs_scoped_per_workflow() {
+        let workflow = |id: &str| -> Workflow {
+            serde_json::from_value(json!({
+                "workflowId": id,
+                "steps": [
+                    {
+                        "stepId": "login",
+                        "operationId": "loginUser",
+                        "outputs": {"token": "$response.body#/token"}
+                    },
+                    {
+                        "stepId": "profile",
+                        "operationId": "getUser",
+                        "parameters": [
+                            {"name": "Authorization", "in": "header", "value": "Bearer $steps.login.outputs.token"}
+                        ]
+                    }
+                ]
+            }))
+            .unwrap()
+        };
+        let workflows = [workflow("admin-flow"), workflow("user-flow")];
+
+        // Both workflows have a `login` step; each keeps its own variable
+        let variables = PostmanConverter::generate_variables(workflows.iter(), String::new());
+        let keys: Vec<&str> = variables.iter().map(|v| v.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "baseUrl",
+                "admin-flow.steps.login.outputs.token",
+                "user-flow.steps.login.outputs.token"
+            ]
+        );
+
+        for workflow in &workflows {
+            let id = &workflow.workflow_id;
+            let extraction = PostmanConverter::generate_output_extraction(id, &workflow.steps[0]);
+            assert!(
+                extraction
+                    .iter()
+                    .any(|line| line.contains(&format!("\"{}.steps.login.outputs.token\"", id)))
+            );
+            let header = PostmanConverter::value_to_postman(
+                id,
+                &workflow.steps[1].parameters[0].value,
+                &mut Vec::new(),
+            );
+            assert_eq!(
+                header,
+                format!("Bearer {{{{{}.steps.login.outputs.token}}}}", id)
+            );
+        }
+    }
+}
@@ -0,0 +1,345 @@
+//! Registry of the converters available to `hornet2 convert`
+//!
+//! Built-in converters register by name; external converters are discovered
+//! on `PATH` (see [`super::external`]). Both are driven through the
+//! object-safe [`DynConverter`] trait and produce a list of files.
+
+use super::external::{EXTERNAL_PREFIX, ExternalConverter};
+use super::{
+    ConvertOptions, Converter, CurlConverter, HurlConverter, JMeterConverter, K6Converter,
+    PostmanConverter, RustConverter, common,
+};
+use crate::error::Result;
+use crate::loader::OpenApiResolver;
+use crate::models::arazzo::ArazzoSpec;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Everything a converter needs to produce its output
+#[derive(Debug, Clone, Copy)]
+pub struct ConvertInput<'a> {
+    pub arazzo: &'a ArazzoSpec,
+    pub resolver: &'a OpenApiResolver,
+    pub options: &'a ConvertOptions,
+    /// Convert only this workflow (all workflows if `None`)
+    pub workflow_id: Option<&'a str>,
+}
+
+/// A file produced by a converter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputFile {
+    /// Path relative to the output directory
+    pub path: String,
+    pub content: String,
+}
+
+/// Object-safe converter interface used by the registry
+pub trait DynConverter: Send + Sync {
+    /// Convert the input into one or more files
+    fn convert(&self, input: &ConvertInput<'_>) -> Result<Vec<OutputFile>>;
+}
+
+/// Adapter exposing a [`Converter`] producing a single script as a [`DynConverter`]
+pub struct BuiltinConverter<C> {
+    converter: C,
+    file_name: &'static str,
+}
+
+impl<C> BuiltinConverter<C> {
+    /// Wrap a converter; `file_name` is the default name of the generated file
+    pub fn new(converter: C, file_name: &'static str) -> Self {
+        Self {
+            converter,
+            file_name,
+        }
+    }
+}
+
+impl<C> DynConverter for BuiltinConverter<C>
+where
+    C: Converter<Output = String> + Send + Sync,
+{
+    fn convert(&self, input: &ConvertInput<'_>) -> Result<Vec<OutputFile>> {
+        let content = match input.workflow_id {
+            Some(wf_id) => {
+                let workflow = common::find_workflow(input.arazzo, wf_id)?;
+                self.converter
+                    .convert_workflow(workflow, input.resolver, input.options)?
+            }
+            None => self
+                .converter
+                .convert_spec(input.arazzo, input.resolver, input.options)?,
+        };
+
+        Ok(vec![OutputFile {
+            path: self.file_name.to_string(),
+            content,
+        }])
+    }
+}
+
+/// Where a registered converter comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConverterSource {
+    Builtin,
+    /// External executable found on `PATH`
+    External(PathBuf),
+}
+
+/// Description of a registered converter (as shown by `convert --list`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConverterInfo {
+    pub name: String,
+    pub description: String,
+    pub source: ConverterSource,
+}
+
+struct Entry {
+    info: ConverterInfo,
+    converter: Box<dyn DynConverter>,
+}
+
+/// Converters available by name
+#[derive(Default)]
+pub struct ConverterRegistry {
+    entries: IndexMap<String, Entry>,
+}
+
+impl ConverterRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry holding the built-in converters
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        registry.register(
+            "k6",
+            "k6 load test script (JavaScript)",
+            BuiltinConverter::new(K6Converter::new(), "script.js"),
+        );
+        registry.register(
+            "postman",
+            "Postman Collection v2.1",
+            BuiltinConverter::new(PostmanConverter::new(), "collection.json"),
+        );
+        registry.register(
+            "hurl",
+            "Hurl file",
+            BuiltinConverter::new(HurlConverter::new(), "workflow.hurl"),
+        );
+        registry.register(
+            "curl",
+            "bash script using curl and jq",
+            BuiltinConverter::new(CurlConverter::new(), "workflow.sh"),
+        );
+        registry.register(
+            "rust",
+            "reqwest/tokio integration tests",
+            BuiltinConverter::new(RustConverter::new(), "workflows.rs"),
+        );
+        registry.register(
+            "jmeter",
+            "JMeter test plan (JMX)",
+            BuiltinConverter::new(JMeterConverter::new(), "plan.jmx"),
+        );
+        registry
+    }
+
+    /// Create a registry holding the built-in converters and the external
+    /// converters found on `PATH`
+    pub fn discover() -> Self {
+        let mut registry = Self::with_builtins();
+        if let Some(path) = std::env::var_os("PATH") {
+            registry.discover_external(std::env::split_paths(&path));
+        }
+        registry
+    }
+
+    /// Register a built-in converter (replaces any converter with the same name)
+    pub fn register(
+        &mut self,
+        name: &str,
+        description: &str,
+        converter: impl DynConverter + 'static,
+    ) {
+        self.entries.insert(
+            name.to_string(),
+            Entry {
+                info: ConverterInfo {
+                    name: name.to_string(),
+                    description: description.to_string(),
+                    source: ConverterSource::Builtin,
+                },
+                converter: Box::new(converter),
+            },
+        );
+    }
+
+    /// Register the `hornet2-convert-<name>` executables found in `dirs`
+    ///
+    /// Registered names take precedence, and earlier directories take
+    /// precedence over later ones (as with `PATH` lookup).
+    pub fn discover_external(&mut self, dirs: impl IntoIterator<Item = PathBuf>) {
+        for dir in dirs {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+
+            let mut found: Vec<(String, PathBuf)> = entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| is_executable(path))
+                .filter_map(|path| {
+                    let name = external_name(&path)?;
+                    Some((name, path))
+                })
+                .collect();
+            found.sort();
+
+            for (name, path) in found {
+                if self.entries.contains_key(&name) {
+                    continue;
+                }
+                self.entries.insert(
+                    name.clone(),
+                    Entry {
+                        info: ConverterInfo {
+                            name: name.clone(),
+                            description: format!("external converter ({})", path.display()),
+                            source: ConverterSource::External(path.clone()),
+                        },
+                        converter: Box::new(ExternalConverter::new(name, path)),
+                    },
+                );
+            }
+        }
+    }
+
+    /// Look up a converter by name (case-insensitive)
+    pub fn get(&self, name: &str) -> Option<&dyn DynConverter> {
+        self.entries
+            .get(name)
+            .or_else(|| self.entries.get(&name.to_lowercase()))
+            .map(|entry| entry.converter.as_ref())
+    }
+
+    /// Registered converters in registration order
+    pub fn list(&self) -> impl Iterator<Item = &ConverterInfo> {
+        self.entries.values().map(|entry| &entry.info)
+    }
+
+    /// Names of the registered converters
+    pub fn names(&self) -> Vec<&str> {
+        self.entries.keys().map(|name| name.as_str()).collect()
+    }
+}
+
+/// Converter name of an external executable (`hornet2-convert-<name>`)
+fn external_name(path: &Path) -> Option<String> {
+    let file_name = path.file_name()?.to_str()?;
+    let file_name = if cfg!(windows) {
+        file_name.strip_suffix(".exe").unwrap_or(file_name)
+    } else {
+        file_name
+    };
+    let name = file_name.strip_prefix(EXTERNAL_PREFIX)?;
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed;
+
+    impl DynConverter for Fixed {
+        fn convert(&self, _input: &ConvertInput<'_>) -> Result<Vec<OutputFile>> {
+            Ok(vec![OutputFile {
+                path: "fixed.txt".to_string(),
+                content: "fixed".to_string(),
+            }])
+        }
+    }
+
+    #[test]
+    fn test_builtins_are_registered() {
+        let registry = ConverterRegistry::with_builtins();
+        assert_eq!(
+            registry.names(),
+            vec!["k6", "postman", "hurl", "curl", "rust", "jmeter"]
+        );
+        assert!(registry.get("K6").is_some());
+        assert!(registry.get("unknown").is_none());
+        assert!(
+            registry
+                .list()
+                .all(|info| info.source == ConverterSource::Builtin)
+        );
+    }
+
+    #[test]
+    fn test_register_custom_converter() {
+        let mut registry = ConverterRegistry::new();
+        registry.register("fixed", "Fixed output", Fixed);
+
+        let arazzo: ArazzoSpec = serde_yaml::from_str(
+            "arazzo: 1.0.0\ninfo: {title: t, version: '1'}\nsourceDescriptions: []\nworkflows: []\n",
+        )
+        .unwrap();
+        let resolver = OpenApiResolver::new(".");
+        let files = registry
+            .get("fixed")
+            .unwrap()
+            .convert(&ConvertInput {
+                arazzo: &arazzo,
+                resolver: &resolver,
+                options: &ConvertOptions::default(),
+                workflow_id: None,
+            })
+            .unwrap();
+        assert_eq!(files[0].content, "fixed");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_discover_external() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, mode: u32| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, "#!/bin/sh\n").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+        };
+        write("hornet2-convert-gatling", 0o755);
+        write("hornet2-convert-k6", 0o755);
+        write("hornet2-convert-noexec", 0o644);
+        write("other-tool", 0o755);
+
+        let mut registry = ConverterRegistry::with_builtins();
+        registry.discover_external([dir.path().to_path_buf()]);
+
+        assert!(registry.get("gatling").is_some());
+        assert!(registry.get("noexec").is_none());
+        assert_eq!(registry.names().len(), 7);
+        // 組み込みの k6 は外部コンバータで上書きされない
+        let k6 = registry.list().find(|info| info.name == "k6").unwrap();
+        assert_eq!(k6.source, ConverterSource::Builtin);
+    }
+}
@@ -0,0 +1,932 @@
+//! Rust integration test converter
+//!
+//! Generates a self-contained `tests/*.rs` file using reqwest and tokio.
+//! Each workflow becomes a `#[tokio::test]` that runs its steps as a small
+//! state machine (so `goto` and `end` actions are honoured), reads inputs
+//! from `INPUTS_<NAME>` environment variables and turns successCriteria into
+//! assertions that report the actual and expected values.
+
+use super::{ConvertOptions, Converter, common};
+use crate::error::Result;
+use crate::loader::OpenApiResolver;
+use crate::models::arazzo::{ArazzoSpec, RequestBody, Step, SuccessCriteria, Workflow};
+use crate::models::expression::{
+    ExpressionSource, RuntimeExpression, TemplateSegment, as_single_expression, parse_template,
+};
+use crate::payload::multipart::{self, PartContent};
+use crate::payload::{self, BodyEncoding, form, text_value, xml};
+use indexmap::IndexMap;
+
+/// Helper functions shared by every generated file
+const TEST_HELPERS: &str = r#"/// Read a workflow input from the environment, falling back to its default
+fn input(name: &str, default: Value) -> Value {
+    match std::env::var(name) {
+        Ok(raw) if default.is_string() || default.is_null() => Value::String(raw),
+        Ok(raw) => serde_json::from_str(&raw).unwrap_or(Value::String(raw)),
+        Err(_) => default,
+    }
+}
+
+/// Value at a JSON pointer, or null
+fn at(value: &Value, pointer: &str) -> Value {
+    value.pointer(pointer).cloned().unwrap_or(Value::Null)
+}
+
+/// Text of a value (strings are not quoted)
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Percent-encode a path segment or form component
+fn encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Encode fields as `application/x-www-form-urlencoded`
+fn form(fields: &[(&str, Value)]) -> String {
+    fields
+        .iter()
+        .map(|(k, v)| format!("{}={}", encode(k), encode(&text(v))))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+struct Response {
+    status: u16,
+    url: String,
+    headers: reqwest::header::HeaderMap,
+    body: Value,
+}
+
+impl Response {
+    fn header(&self, name: &str) -> Value {
+        self.headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| Value::String(v.to_string()))
+            .unwrap_or(Value::Null)
+    }
+}
+
+/// Send a request and read the body (as JSON when possible)
+async fn send(request: reqwest::RequestBuilder) -> Response {
+    let response = request.send().await.expect("request failed");
+    let status = response.status().as_u16();
+    let url = response.url().to_string();
+    let headers = response.headers().clone();
+    let text = response.text().await.expect("failed to read response body");
+    let body = serde_json::from_str(&text).unwrap_or(Value::String(text));
+    Response {
+        status,
+        url,
+        headers,
+        body,
+    }
+}
+
+/// Compare two values with an Arazzo operator
+fn compare(left: &Value, operator: &str, right: &Value) -> bool {
+    use std::cmp::Ordering;
+
+    let ordering = match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64().partial_cmp(&b.as_f64()),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    };
+    let equal = left == right || ordering == Some(Ordering::Equal);
+    match operator {
+        "==" => equal,
+        "!=" => !equal,
+        ">" => ordering == Some(Ordering::Greater),
+        ">=" => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+        "<" => ordering == Some(Ordering::Less),
+        "<=" => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        _ => false,
+    }
+}
+
+/// Record a failed criterion
+fn expect(failures: &mut Vec<String>, criterion: &str, actual: Value, operator: &str, expected: Value) {
+    if !compare(&actual, operator, &expected) {
+        failures.push(format!(
+            "{} (actual: {}, expected: {})",
+            criterion, actual, expected
+        ));
+    }
+}"#;
+
+/// Helper emitted only when a workflow uses regex criteria
+const REGEX_HELPER: &str = r#"/// Record a failed regex criterion
+fn expect_match(failures: &mut Vec<String>, criterion: &str, actual: Value, pattern: &str) {
+    let regex = regex::Regex::new(pattern).expect("invalid pattern");
+    if !regex.is_match(&text(&actual)) {
+        failures.push(format!("{} (actual: {})", criterion, actual));
+    }
+}"#;
+
+/// Converter for generating Rust integration tests
+#[derive(Debug, Clone, Default)]
+pub struct RustConverter;
+
+/// A `format!` call being built from literal text and arguments
+#[derive(Default)]
+struct FormatBuilder {
+    template: String,
+    args: Vec<String>,
+}
+
+impl FormatBuilder {
+    fn literal(&mut self, s: &str) {
+        self.template
+            .push_str(&s.replace('{', "{{").replace('}', "}}"));
+    }
+
+    fn arg(&mut self, expr: String) {
+        self.template.push_str("{}");
+        self.args.push(expr);
+    }
+
+    /// Render as an expression of type `String`
+    fn build(self) -> String {
+        if self.args.is_empty() {
+            return format!(
+                "{:?}.to_string()",
+                self.template.replace("{{", "{").replace("}}", "}")
+            );
+        }
+        format!("format!({:?}, {})", self.template, self.args.join(", "))
+    }
+}
+
+impl RustConverter {
+    /// Create a new RustConverter
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Environment variable holding a workflow input
+    fn input_env(name: &str) -> String {
+        let name: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        format!("INPUTS_{}", name)
+    }
+
+    /// Test function name for a workflow
+    fn test_name(workflow_id: &str) -> String {
+        let mut name = String::new();
+        for (i, c) in workflow_id.chars().enumerate() {
+            if c.is_ascii_uppercase() {
+                if i > 0 && !name.ends_with('_') {
+                    name.push('_');
+                }
+                name.push(c.to_ascii_lowercase());
+            } else if c.is_ascii_alphanumeric() {
+                name.push(c);
+            } else if !name.ends_with('_') {
+                name.push('_');
+            }
+        }
+        let name = name.trim_matches('_').to_string();
+        if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+            format!("workflow_{}", name)
+        } else {
+            name
+        }
+    }
+
+    /// Render a property path as a JSON Pointer
+    fn pointer<'a>(path: impl IntoIterator<Item = &'a String>) -> String {
+        path.into_iter()
+            .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+            .collect()
+    }
+
+    /// Rust expression (of type `Value`) for a runtime expression
+    fn value_expr(expr: &RuntimeExpression, method: &str) -> String {
+        match expr {
+            RuntimeExpression::StatusCode => "json!(response.status)".to_string(),
+            RuntimeExpression::Url => "json!(response.url)".to_string(),
+            RuntimeExpression::Method => format!("json!({:?})", method),
+            RuntimeExpression::Response(ExpressionSource::Body(path)) => {
+                format!("at(&response.body, {:?})", Self::pointer(path))
+            }
+            RuntimeExpression::Response(ExpressionSource::Header(name)) => {
+                format!("response.header({:?})", name)
+            }
+            RuntimeExpression::Inputs(path) => {
+                format!("at(&inputs, {:?})", Self::pointer(path))
+            }
+            RuntimeExpression::StepOutput {
+                step_id,
+                output,
+                path,
+            } => {
+                let full = [step_id, output].into_iter().chain(path);
+                format!("at(&steps, {:?})", Self::pointer(full))
+            }
+            other => format!("json!({:?})", other.to_string()),
+        }
+    }
+
+    /// Rust expression (of type `String`) for a string that may embed expressions
+    ///
+    /// With `encode`, expression values are percent-encoded (for paths).
+    fn text_expr(text: &str, method: &str, encode: bool) -> String {
+        let segments = parse_template(text);
+        if let [TemplateSegment::Expression(expr)] = segments.as_slice()
+            && !encode
+        {
+            return format!("text(&{})", Self::value_expr(expr, method));
+        }
+
+        let mut builder = FormatBuilder::default();
+        for segment in segments {
+            match segment {
+                TemplateSegment::Literal(s) => builder.literal(&s),
+                TemplateSegment::Expression(expr) if encode => builder.arg(format!(
+                    "encode(&text(&{}))",
+                    Self::value_expr(&expr, method)
+                )),
+                TemplateSegment::Expression(expr) => {
+                    builder.arg(format!("text(&{})", Self::value_expr(&expr, method)))
+                }
+            }
+        }
+        builder.build()
+    }
+
+    /// Rust expression (of type `Value`) for a parameter or payload value
+    fn json_expr(value: &serde_json::Value, method: &str, indent: usize) -> String {
+        let pad = "    ".repeat(indent + 1);
+        let close = "    ".repeat(indent);
+
+        match value {
+            serde_json::Value::String(s) => {
+                if let Some(expr) = as_single_expression(value) {
+                    return Self::value_expr(&expr, method);
+                }
+                if parse_template(s)
+                    .iter()
+                    .all(|seg| matches!(seg, TemplateSegment::Literal(_)))
+                {
+                    return format!("json!({:?})", s);
+                }
+                format!("json!({})", Self::text_expr(s, method, false))
+            }
+            serde_json::Value::Array(items) if !items.is_empty() => {
+                let items: Vec<String> = items
+                    .iter()
+                    .map(|v| format!("{}{}", pad, Self::json_expr(v, method, indent + 1)))
+                    .collect();
+                format!("json!([\n{}\n{}])", items.join(",\n"), close)
+            }
+            serde_json::Value::Object(obj) if !obj.is_empty() => {
+                let fields: Vec<String> = obj
+                    .iter()
+                    .map(|(k, v)| {
+                        format!("{}{:?}: {}", pad, k, Self::json_expr(v, method, indent + 1))
+                    })
+                    .collect();
+                format!("json!({{\n{}\n{}}})", fields.join(",\n"), close)
+            }
+            other => format!("json!({})", other),
+        }
+    }
+
+    /// Generate the builder calls sending a request body
+    fn generate_body(body: &RequestBody, method: &str, indent: usize) -> Result<Vec<String>> {
+        let payload = payload::apply_replacements(body)?;
+        let encoding = BodyEncoding::from_content_type(body.content_type.as_deref());
+        let content_type = body
+            .content_type
+            .clone()
+            .unwrap_or_else(|| encoding.default_content_type().to_string());
+        let header = format!(".header(\"Content-Type\", {:?})", content_type);
+
+        if let serde_json::Value::String(text) = &payload
+            && encoding != BodyEncoding::Json
+        {
+            return Ok(vec![
+                header,
+                format!(".body({})", Self::text_expr(text, method, false)),
+            ]);
+        }
+
+        let calls = match encoding {
+            BodyEncoding::Json => vec![
+                header,
+                format!(
+                    ".body({}.to_string())",
+                    Self::json_expr(&payload, method, indent)
+                ),
+            ],
+            BodyEncoding::FormUrlEncoded => {
+                let pad = "    ".repeat(indent + 1);
+                let fields: Vec<String> = form::flatten(&payload)?
+                    .iter()
+                    .map(|(key, value)| {
+                        format!(
+                            "{}({:?}, {})",
+                            pad,
+                            key,
+                            Self::json_expr(value, method, indent + 1)
+                        )
+                    })
+                    .collect();
+                vec![
+                    header,
+                    format!(
+                        ".body(form(&[\n{}\n{}]))",
+                        fields.join(",\n"),
+                        "    ".repeat(indent)
+                    ),
+                ]
+            }
+            // reqwest generates the multipart boundary itself
+            BodyEncoding::Multipart => {
+                let pad = "    ".repeat(indent + 1);
+                let mut lines = vec![".multipart(".to_string()];
+                lines.push(format!("{}reqwest::multipart::Form::new()", pad));
+                for part in multipart::collect_parts(&payload)? {
+                    let call = match part.content {
+                        PartContent::Field(value) => format!(
+                            ".text({:?}, {})",
+                            part.name,
+                            Self::text_expr(&text_value(&value), method, false)
+                        ),
+                        PartContent::Json(value) => format!(
+                            ".part({:?}, reqwest::multipart::Part::text({}.to_string()).mime_str(\"application/json\").unwrap())",
+                            part.name,
+                            Self::json_expr(&value, method, indent + 1)
+                        ),
+                        PartContent::File(file) => format!(
+                            ".part({:?}, reqwest::multipart::Part::bytes(std::fs::read({:?}).expect(\"failed to read {}\")).file_name({:?}).mime_str({:?}).unwrap())",
+                            part.name,
+                            file.path,
+                            file.path.replace('"', "'"),
+                            file.file_name(),
+                            file.mime_type()
+                        ),
+                    };
+                    lines.push(format!("{}    {}", pad, call));
+                }
+                lines.push(format!("{})", "    ".repeat(indent)));
+                vec![lines.join("\n")]
+            }
+            BodyEncoding::Xml => vec![
+                header,
+                format!(
+                    ".body({})",
+                    Self::text_expr(&xml::to_xml(&payload)?, method, false)
+                ),
+            ],
+            BodyEncoding::Raw => vec![
+                header,
+                format!(
+                    ".body({})",
+                    Self::text_expr(&text_value(&payload), method, false)
+                ),
+            ],
+        };
+
+        Ok(calls)
+    }
+
+    fn criterion_description(crit: &SuccessCriteria) -> String {
+        match crit.value {
+            Some(ref value) => format!("{} {} {}", crit.context, crit.condition, text_value(value)),
+            None => format!("{} {}", crit.context, crit.condition),
+        }
+    }
+
+    fn criterion_operator(crit: &SuccessCriteria) -> &'static str {
+        match crit.condition.as_str() {
+            "!=" | "$ne" => "!=",
+            ">" | "$gt" => ">",
+            ">=" | "$gte" => ">=",
+            "<" | "$lt" => "<",
+            "<=" | "$lte" => "<=",
+            _ => "==",
+        }
+    }
+
+    fn criterion_operands(crit: &SuccessCriteria, method: &str) -> (String, String) {
+        let left = match RuntimeExpression::parse(&crit.context) {
+            Some(expr) => Self::value_expr(&expr, method),
+            None => format!("json!({:?})", crit.context),
+        };
+        let right = match crit.value {
+            Some(ref value) => Self::json_expr(value, method, 0),
+            None => "json!(true)".to_string(),
+        };
+        (left, right)
+    }
+
+    /// Rust condition for a criterion used by an action
+    fn criterion_condition(crit: &SuccessCriteria, method: &str) -> Option<String> {
+        let (left, right) = Self::criterion_operands(crit, method);
+        match crit.criteria_type.as_deref() {
+            Some("jsonpath") => None,
+            Some("regex") => Some(format!(
+                "regex::Regex::new({:?}).unwrap().is_match(&text(&{}))",
+                crit.condition, left
+            )),
+            _ => Some(format!(
+                "compare(&{}, {:?}, &{})",
+                left,
+                Self::criterion_operator(crit),
+                right
+            )),
+        }
+    }
+
+    /// Generate the `expect` calls for successCriteria
+    fn generate_checks(criteria: &[SuccessCriteria], method: &str) -> Vec<String> {
+        criteria
+            .iter()
+            .map(|crit| {
+                let description = Self::criterion_description(crit);
+                let (left, right) = Self::criterion_operands(crit, method);
+                match crit.criteria_type.as_deref() {
+                    Some("jsonpath") => {
+                        format!("// jsonpath criterion not supported: {}", description)
+                    }
+                    Some("regex") => format!(
+                        "expect_match(&mut failures, {:?}, {}, {:?});",
+                        description, left, crit.condition
+                    ),
+                    _ => format!(
+                        "expect(&mut failures, {:?}, {}, {:?}, {});",
+                        description,
+                        left,
+                        Self::criterion_operator(crit),
+                        right
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    /// Generate the assignments storing step outputs
+    fn generate_outputs(step: &Step, method: &str) -> Vec<String> {
+        let Some(serde_json::Value::Object(outputs)) = step.outputs.as_ref() else {
+            return Vec::new();
+        };
+
+        outputs
+            .iter()
+            .map(|(name, expr)| {
+                format!(
+                    "steps[{:?}][{:?}] = {};",
+                    step.step_id,
+                    name,
+                    Self::json_expr(expr, method, 0)
+                )
+            })
+            .collect()
+    }
+
+    /// Generate the statements running onSuccess/onFailure actions
+    fn generate_actions<'a>(
+        actions: impl Iterator<Item = (&'a str, &'a IndexMap<String, serde_json::Value>)>,
+        method: &str,
+    ) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        for (action_type, config) in actions {
+            let jump = match action_type {
+                "goto" => match common::goto_target(config) {
+                    Some(target) => format!("next = Some({:?});", target),
+                    None => {
+                        lines.push("// goto to another workflow is not supported".to_string());
+                        continue;
+                    }
+                },
+                "end" => "next = None;".to_string(),
+                other => {
+                    lines.push(format!("// {} actions are not supported", other));
+                    continue;
+                }
+            };
+
+            let criteria: Vec<SuccessCriteria> = config
+                .get("criteria")
+                .and_then(|c| serde_json::from_value(c.clone()).ok())
+                .unwrap_or_default();
+            let conditions: Vec<String> = criteria
+                .iter()
+                .filter_map(|crit| Self::criterion_condition(crit, method))
+                .collect();
+
+            if conditions.is_empty() {
+                lines.push(jump);
+                lines.push("continue;".to_string());
+            } else {
+                lines.push(format!("if {} {{", conditions.join(" && ")));
+                lines.push(format!("    {}", jump));
+                lines.push("    continue;".to_string());
+                lines.push("}".to_string());
+            }
+        }
+
+        lines
+    }
+
+    /// Generate the `match` arm of a step
+    fn generate_step(
+        &self,
+        step: &Step,
+        next_step: Option<&str>,
+        resolver: &OpenApiResolver,
+    ) -> Result<Vec<String>> {
+        let (path, method) = common::resolve_operation(step, resolver)?;
+
+        let mut url = FormatBuilder::default();
+        url.arg("base_url".to_string());
+        let mut calls = Vec::new();
+        let mut cookies = Vec::new();
+
+        let mut path_params = IndexMap::new();
+        for param in &step.parameters {
+            let text = text_value(&param.value);
+            match param.location.as_str() {
+                "path" => {
+                    path_params.insert(param.name.clone(), text);
+                }
+                "query" => calls.push(format!(
+                    ".query(&[({:?}, {})])",
+                    param.name,
+                    Self::text_expr(&text, &method, false)
+                )),
+                "header" => calls.push(format!(
+                    ".header({:?}, {})",
+                    param.name,
+                    Self::text_expr(&text, &method, false)
+                )),
+                "cookie" => cookies.push(format!("{}={}", param.name, text)),
+                _ => {}
+            }
+        }
+        if !cookies.is_empty() {
+            calls.push(format!(
+                ".header(\"Cookie\", {})",
+                Self::text_expr(&cookies.join("; "), &method, false)
+            ));
+        }
+
+        let mut rest = path.as_str();
+        while let Some(start) = rest.find('{') {
+            let Some(end) = rest[start..].find('}').map(|e| start + e) else {
+                break;
+            };
+            url.literal(&rest[..start]);
+            let name = &rest[start + 1..end];
+            match path_params.get(name) {
+                Some(value) => {
+                    for segment in parse_template(value) {
+                        match segment {
+                            TemplateSegment::Literal(s) => url.literal(&s),
+                            TemplateSegment::Expression(expr) => url.arg(format!(
+                                "encode(&text(&{}))",
+                                Self::value_expr(&expr, &method)
+                            )),
+                        }
+                    }
+                }
+                None => url.literal(&rest[start..=end]),
+            }
+            rest = &rest[end + 1..];
+        }
+        url.literal(rest);
+
+        if let Some(ref request_body) = step.request_body {
+            calls.extend(Self::generate_body(request_body, &method, 1)?);
+        }
+
+        let method_expr = match method.as_str() {
+            "GET" | "POST" | "PUT" | "DELETE" | "HEAD" | "OPTIONS" | "CONNECT" | "PATCH"
+            | "TRACE" => format!("reqwest::Method::{}", method),
+            other => format!("reqwest::Method::from_bytes(b{:?}).unwrap()", other),
+        };
+
+        let mut lines = Vec::new();
+        if let Some(ref description) = step.description {
+            lines.push(format!(
+                "// {}",
+                description.lines().next().unwrap_or_default()
+            ));
+        }
+        lines.push("let request = client".to_string());
+        lines.push(format!("    .request({}, {})", method_expr, url.build()));
+        for call in calls {
+            lines.push(format!("    {}", call));
+        }
+        if let Some(last) = lines.last_mut() {
+            last.push(';');
+        }
+        lines.push("let response = send(request).await;".to_string());
+
+        lines.push("let mut failures: Vec<String> = Vec::new();".to_string());
+        lines.extend(Self::generate_checks(
+            step.success_criteria.as_deref().unwrap_or(&[]),
+            &method,
+        ));
+
+        lines.push("if failures.is_empty() {".to_string());
+        let mut on_success = Self::generate_outputs(step, &method);
+        on_success.extend(Self::generate_actions(
+            step.on_success
+                .iter()
+                .flatten()
+                .map(|a| (a.action_type.as_str(), &a.config)),
+            &method,
+        ));
+        on_success.push(match next_step {
+            Some(next) => format!("next = Some({:?});", next),
+            None => "next = None;".to_string(),
+        });
+        lines.extend(on_success.into_iter().map(|l| format!("    {}", l)));
+
+        lines.push("} else {".to_string());
+        let mut on_failure = Self::generate_actions(
+            step.on_failure
+                .iter()
+                .flatten()
+                .map(|a| (a.action_type.as_str(), &a.config)),
+            &method,
+        );
+        on_failure.push(format!(
+            "panic!(\"step `{}` failed:\\n  {{}}\", failures.join(\"\\n  \"));",
+            step.step_id
+        ));
+        lines.extend(on_failure.into_iter().map(|l| format!("    {}", l)));
+        lines.push("}".to_string());
+
+        Ok(lines)
+    }
+
+    /// Generate the test function of a workflow
+    fn generate_test(&self, workflow: &Workflow, resolver: &OpenApiResolver) -> Result<String> {
+        let mut lines = Vec::new();
+        let doc = workflow
+            .summary
+            .clone()
+            .unwrap_or_else(|| format!("Workflow `{}`", workflow.workflow_id));
+        lines.push(format!("/// {}", doc));
+        lines.push("#[tokio::test]".to_string());
+        lines.push(format!(
+            "async fn {}() {{",
+            Self::test_name(&workflow.workflow_id)
+        ));
+        lines.push("    let client = reqwest::Client::new();".to_string());
+        lines.push("    let base_url = base_url();".to_string());
+
+        let defaults = common::input_defaults(workflow);
+        if defaults.is_empty() {
+            lines.push("    let inputs = json!({});".to_string());
+        } else {
+            lines.push("    let inputs = json!({".to_string());
+            for (name, value) in &defaults {
+                lines.push(format!(
+                    "        {:?}: input({:?}, json!({})),",
+                    name,
+                    Self::input_env(name),
+                    value
+                ));
+            }
+            lines.push("    });".to_string());
+        }
+        lines.push("    let mut steps = json!({});".to_string());
+
+        let first = workflow
+            .steps
+            .first()
+            .map(|s| format!("Some({:?})", s.step_id))
+            .unwrap_or_else(|| "None".to_string());
+        lines.push(format!("    let mut next: Option<&str> = {};", first));
+        lines.push("    while let Some(step) = next.take() {".to_string());
+        lines.push("        match step {".to_string());
+
+        for (i, step) in workflow.steps.iter().enumerate() {
+            let next_step = workflow.steps.get(i + 1).map(|s| s.step_id.as_str());
+            lines.push(format!("            {:?} => {{", step.step_id));
+            for line in self.generate_step(step, next_step, resolver)? {
+                for line in line.lines() {
+                    lines.push(format!("                {}", line));
+                }
+            }
+            lines.push("            }".to_string());
+        }
+
+        lines.push("            other => unreachable!(\"unknown step {}\", other),".to_string());
+        lines.push("        }".to_string());
+        lines.push("    }".to_string());
+        lines.push("}".to_string());
+
+        Ok(lines.join("\n"))
+    }
+
+    /// Assemble the test file
+    fn render(
+        &self,
+        title: &str,
+        workflows: &[&Workflow],
+        resolver: &OpenApiResolver,
+        options: &ConvertOptions,
+    ) -> Result<String> {
+        let base_url = common::resolve_base_url(resolver, options);
+        let uses_multipart = workflows.iter().flat_map(|w| &w.steps).any(|s| {
+            s.request_body.as_ref().is_some_and(|b| {
+                BodyEncoding::from_content_type(b.content_type.as_deref())
+                    == BodyEncoding::Multipart
+            })
+        });
+        let uses_regex = workflows.iter().flat_map(|w| &w.steps).any(|s| {
+            let action_criteria = s
+                .on_success
+                .iter()
+                .flatten()
+                .map(|a| &a.config)
+                .chain(s.on_failure.iter().flatten().map(|a| &a.config))
+                .filter_map(|c| c.get("criteria"))
+                .filter_map(|c| serde_json::from_value::<Vec<SuccessCriteria>>(c.clone()).ok())
+                .flatten()
+                .collect::<Vec<_>>();
+            s.success_criteria
+                .iter()
+                .flatten()
+                .chain(action_criteria.iter())
+                .any(|c| c.criteria_type.as_deref() == Some("regex"))
+        });
+
+        let mut dependencies = vec![
+            "`reqwest`".to_string(),
+            "`serde_json`".to_string(),
+            "`tokio` (`macros` and `rt-multi-thread` features)".to_string(),
+        ];
+        if uses_multipart {
+            dependencies[0] = "`reqwest` (`multipart` feature)".to_string();
+        }
+        if uses_regex {
+            dependencies.push("`regex`".to_string());
+        }
+
+        let header = [
+            format!(
+                "//! Integration tests generated by hornet2 from \"{}\"",
+                title
+            ),
+            "//!".to_string(),
+            "//! Environment variables:".to_string(),
+            format!(
+                "//! - `BASE_URL` - base URL of the API (default: `{}`)",
+                base_url
+            ),
+            "//! - `INPUTS_<NAME>` - workflow inputs (JSON for non-string inputs)".to_string(),
+            "//!".to_string(),
+            format!("//! Dev-dependencies: {}", dependencies.join(", ")),
+            String::new(),
+            "#![allow(dead_code, unused_mut, unused_variables)]".to_string(),
+            String::new(),
+            "use serde_json::{Value, json};".to_string(),
+        ]
+        .join("\n");
+
+        let mut sections = vec![
+            header,
+            format!(
+                "/// Base URL of the API\nfn base_url() -> String {{\n    std::env::var(\"BASE_URL\").unwrap_or_else(|_| {:?}.to_string())\n}}",
+                base_url.trim_end_matches('/')
+            ),
+            TEST_HELPERS.to_string(),
+        ];
+        if uses_regex {
+            sections.push(REGEX_HELPER.to_string());
+        }
+        for workflow in workflows {
+            sections.push(self.generate_test(workflow, resolver)?);
+        }
+
+        let mut code = sections.join("\n\n");
+        code.push('\n');
+        Ok(code)
+    }
+}
+
+impl Converter for RustConverter {
+    type Output = String;
+
+    fn convert_spec(
+        &self,
+        arazzo: &ArazzoSpec,
+        resolver: &OpenApiResolver,
+        options: &ConvertOptions,
+    ) -> Result<Self::Output> {
+        let workflows: Vec<&Workflow> = arazzo.workflows.iter().collect();
+        self.render(&arazzo.info.title, &workflows, resolver, options)
+    }
+
+    fn convert_workflow(
+        &self,
+        workflow: &Workflow,
+        resolver: &OpenApiResolver,
+        options: &ConvertOptions,
+    ) -> Result<Self::Output> {
+        let title = workflow
+            .summary
+            .clone()
+            .unwrap_or_else(|| workflow.workflow_id.clone());
+        self.render(&title, &[workflow], resolver, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loader::arazzo::load_arazzo;
+    use serde_json::json;
+    use std::path::Path;
+
+    #[test]
+    fn test_text_expr() {
+        assert_eq!(
+            RustConverter::text_expr("Bearer $steps.login.outputs.token", "GET", false),
+            "format!(\"Bearer {}\", text(&at(&steps, \"/login/token\")))"
+        );
+        assert_eq!(
+            RustConverter::text_expr("$inputs.user.name", "GET", false),
+            "text(&at(&inputs, \"/user/name\"))"
+        );
+        assert_eq!(
+            RustConverter::text_expr("{literal}", "GET", false),
+            "\"{literal}\".to_string()"
+        );
+    }
+
+    #[test]
+    fn test_test_name() {
+        assert_eq!(
+            RustConverter::test_name("user-onboarding-flow"),
+            "user_onboarding_flow"
+        );
+        assert_eq!(RustConverter::test_name("createPet"), "create_pet");
+        assert_eq!(RustConverter::test_name("1st"), "workflow_1st");
+    }
+
+    #[test]
+    fn test_generate_checks() {
+        let lines = RustConverter::generate_checks(
+            &[SuccessCriteria {
+                context: "$response.body.email".to_string(),
+                condition: "==".to_string(),
+                value: Some(json!("$inputs.email")),
+                criteria_type: None,
+            }],
+            "GET",
+        );
+        assert_eq!(
+            lines,
+            vec![
+                "expect(&mut failures, \"$response.body.email == $inputs.email\", at(&response.body, \"/email\"), \"==\", at(&inputs, \"/email\"));"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convert_workflow_integration() {
+        let arazzo = load_arazzo("tests/fixtures/arazzo.yaml").unwrap();
+
+        let mut resolver = OpenApiResolver::new(Path::new("tests/fixtures"));
+        resolver
+            .load_spec("userAPI", Path::new("tests/fixtures/openapi.yaml"))
+            .unwrap();
+
+        let code = RustConverter::new()
+            .convert_spec(&arazzo, &resolver, &ConvertOptions::default())
+            .unwrap();
+
+        assert!(code.contains("#[tokio::test]\nasync fn user_onboarding_flow() {"));
+        assert!(code.contains("async fn simple_login_flow() {"));
+        assert!(code.contains("\"username\": input(\"INPUTS_USERNAME\", json!(\"testuser\")),"));
+        assert!(code.contains("steps[\"register\"][\"userId\"] = at(&response.body, \"/id\");"));
+        assert!(!code.contains("expect_match"));
+    }
+}
@@ -37,6 +37,15 @@ pub enum HornetError {
 
     #[error("Invalid path: {0}")]
     InvalidPath(String),
+
+    #[error("Payload encoding error: {0}")]
+    PayloadError(String),
+
+    #[error("Import error: {0}")]
+    ImportError(String),
+
+    #[error("Converter error: {0}")]
+    ConverterError(String),
 }
 
 pub type Result<T> = std::result::Result<T, HornetError>;
@@ -0,0 +1,578 @@
+//! Property-based fuzzing of workflow operation steps
+//!
+//! Every operation step of a workflow is replayed with request variants
+//! synthesized from its OpenAPI schemas (minimal, maximal and random
+//! instances). Values bound to runtime expressions are kept, so the steps
+//! before the fuzzed one run unchanged and feed it real data. Responses are
+//! flagged when the server answers with a 5xx status, when the response
+//! does not match the declared responses, or when the request times out.
+//! Failing variants are shrunk to a minimal request and saved as a
+//! reproducer workflow.
+//!
+//! The same step-by-step replay drives negative tests ([`negative`]):
+//! requests breaking one constraint each, expected to get a declared 4xx.
+
+pub mod negative;
+mod variants;
+
+pub use variants::{Variant, generate, shrink_candidates};
+
+use crate::Result;
+use crate::generator::schema::{OperationSchemas, validate_instance};
+use crate::loader::OpenApiResolver;
+use crate::models::arazzo::{Step, SuccessCriteria, Workflow};
+use crate::runner::native::{
+    Context, ErrorKind, NativeExecutor, PlannedStep, StepResult, WorkflowPlan,
+};
+use crate::runner::report::TestCase;
+use serde::Serialize;
+use serde_json::json;
+use std::fmt;
+
+/// Options of a fuzzing session
+#[derive(Debug, Clone, Copy)]
+pub struct FuzzOptions {
+    /// Seed of the first variant (variant `i` uses `seed + i`)
+    pub seed: u64,
+    /// Number of variants per step
+    pub variants: usize,
+    /// Upper bound of requests sent while shrinking one finding
+    pub max_shrink_attempts: usize,
+}
+
+impl Default for FuzzOptions {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            variants: 20,
+            max_shrink_attempts: 64,
+        }
+    }
+}
+
+/// Kind of a finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FindingKind {
+    /// The server answered with a 5xx status
+    ServerError,
+    /// The status or body does not match the declared responses
+    SchemaViolation,
+    /// No response within the timeout
+    Timeout,
+    /// The connection failed
+    TransportError,
+}
+
+impl fmt::Display for FindingKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FindingKind::ServerError => "server-error",
+            FindingKind::SchemaViolation => "schema-violation",
+            FindingKind::Timeout => "timeout",
+            FindingKind::TransportError => "transport-error",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A request variant that made the server misbehave
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Finding {
+    pub workflow_id: String,
+    pub step_id: String,
+    pub kind: FindingKind,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    /// Label of the variant that first triggered the finding
+    pub variant: String,
+    /// Number of successful shrinking steps
+    pub shrink_steps: usize,
+    /// Workflow reproducing the finding with the shrunk request
+    #[serde(skip)]
+    pub reproducer: Workflow,
+}
+
+/// Fuzzing result of one step
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepReport {
+    pub workflow_id: String,
+    pub step_id: String,
+    /// Number of variants sent
+    pub variants: usize,
+    pub findings: Vec<Finding>,
+    /// Why the step was not fuzzed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<String>,
+}
+
+/// Classification of one response
+struct Flagged {
+    kind: FindingKind,
+    status: Option<u16>,
+    message: String,
+}
+
+/// Fuzzes the steps of workflows against a running server
+pub struct Fuzzer<'a> {
+    executor: &'a NativeExecutor,
+    resolver: &'a OpenApiResolver,
+    options: FuzzOptions,
+}
+
+impl<'a> Fuzzer<'a> {
+    pub fn new(
+        executor: &'a NativeExecutor,
+        resolver: &'a OpenApiResolver,
+        options: FuzzOptions,
+    ) -> Self {
+        Self {
+            executor,
+            resolver,
+            options,
+        }
+    }
+
+    /// Fuzz every step of a workflow, in order
+    ///
+    /// Step `i` is fuzzed with the outputs of steps `0..i`. When the
+    /// original step `i` fails, the later steps are skipped because their
+    /// prerequisites are missing.
+    pub async fn fuzz_workflow(&self, workflow: &Workflow) -> Result<Vec<StepReport>> {
+        let plan = WorkflowPlan::new(workflow, self.resolver)?;
+        let mut context = Context::new(plan.inputs.clone());
+        let mut reports = Vec::new();
+        let mut broken: Option<String> = None;
+
+        for (index, planned) in plan.steps.iter().enumerate() {
+            let mut report = StepReport {
+                workflow_id: plan.workflow_id.clone(),
+                step_id: planned.step.step_id.clone(),
+                variants: 0,
+                findings: Vec::new(),
+                skipped: None,
+            };
+            if let Some(ref prerequisite) = broken {
+                report.skipped = Some(format!("prerequisite step '{prerequisite}' failed"));
+                reports.push(report);
+                continue;
+            }
+
+            match OperationSchemas::by_path(self.resolver, &planned.method, &planned.path) {
+                Some(schemas) => {
+                    self.fuzz_step(workflow, &plan, index, &schemas, &context, &mut report)
+                        .await;
+                }
+                None => {
+                    report.skipped = Some(format!(
+                        "operation {} {} not found",
+                        planned.method, planned.path
+                    ));
+                }
+            }
+
+            // 後続ステップのために元のステップを実行して出力を得る
+            if !self.executor.run_step(planned, &mut context).await.passed() {
+                broken = Some(planned.step.step_id.clone());
+            }
+            reports.push(report);
+        }
+
+        Ok(reports)
+    }
+
+    /// Send the negative cases of every step of a workflow, in order
+    ///
+    /// Each case becomes a test case named `<step> [negative] <case>`,
+    /// passing when the server answers with a 4xx declared in the
+    /// operation's responses.
+    pub async fn negative_workflow(&self, workflow: &Workflow) -> Result<Vec<TestCase>> {
+        let plan = WorkflowPlan::new(workflow, self.resolver)?;
+        let mut context = Context::new(plan.inputs.clone());
+        let mut cases = Vec::new();
+        let mut broken: Option<String> = None;
+
+        for planned in &plan.steps {
+            let name = |label: &str| format!("{} [negative] {}", planned.step.step_id, label);
+            let Some(schemas) =
+                OperationSchemas::by_path(self.resolver, &planned.method, &planned.path)
+            else {
+                cases.push(TestCase::skipped(
+                    &plan.workflow_id,
+                    format!("{} [negative]", planned.step.step_id),
+                    format!("operation {} {} not found", planned.method, planned.path),
+                ));
+                continue;
+            };
+            let variants = negative::derive_cases(&planned.step, &schemas);
+
+            if let Some(ref prerequisite) = broken {
+                for variant in &variants {
+                    cases.push(TestCase::skipped(
+                        &plan.workflow_id,
+                        name(&variant.label),
+                        format!("prerequisite step '{prerequisite}' failed"),
+                    ));
+                }
+                continue;
+            }
+
+            for variant in &variants {
+                let result = self.send(planned, variant, &context).await;
+                let elapsed = result
+                    .response
+                    .as_ref()
+                    .map(|r| r.elapsed)
+                    .unwrap_or_default();
+                let case = match (&result.response, &result.error) {
+                    (Some(response), _)
+                        if negative::is_declared_client_error(&schemas, response.status) =>
+                    {
+                        TestCase::passed(&plan.workflow_id, name(&variant.label), elapsed)
+                    }
+                    (Some(response), _) => TestCase::failed(
+                        &plan.workflow_id,
+                        name(&variant.label),
+                        elapsed,
+                        format!(
+                            "expected a 4xx declared in responses ({}), got {}",
+                            schemas
+                                .responses
+                                .iter()
+                                .map(|r| r.status.as_str())
+                                .filter(|s| s.starts_with('4'))
+                                .collect::<Vec<_>>()
+                                .join(", "),
+                            response.status
+                        ),
+                    ),
+                    (None, Some(error)) if error.kind == ErrorKind::Request => TestCase::skipped(
+                        &plan.workflow_id,
+                        name(&variant.label),
+                        error.message.clone(),
+                    ),
+                    (None, error) => TestCase::failed(
+                        &plan.workflow_id,
+                        name(&variant.label),
+                        elapsed,
+                        error
+                            .as_ref()
+                            .map(|e| e.message.clone())
+                            .unwrap_or_default(),
+                    ),
+                };
+                cases.push(case);
+            }
+
+            if !self.executor.run_step(planned, &mut context).await.passed() {
+                broken = Some(planned.step.step_id.clone());
+            }
+        }
+
+        Ok(cases)
+    }
+
+    async fn fuzz_step(
+        &self,
+        workflow: &Workflow,
+        plan: &WorkflowPlan,
+        index: usize,
+        schemas: &OperationSchemas,
+        context: &Context,
+        report: &mut StepReport,
+    ) {
+        let planned = &plan.steps[index];
+        for variant in generate(&planned.step, schemas, &self.options) {
+            report.variants += 1;
+            let result = self.send(planned, &variant, context).await;
+            let Some(flagged) = classify(&result, schemas) else {
+                continue;
+            };
+            if report
+                .findings
+                .iter()
+                .any(|f| f.kind == flagged.kind && f.status == flagged.status)
+            {
+                continue;
+            }
+
+            let (shrunk, shrink_steps) = self
+                .shrink(planned, schemas, variant.clone(), &flagged, context)
+                .await;
+            report.findings.push(Finding {
+                workflow_id: plan.workflow_id.clone(),
+                step_id: planned.step.step_id.clone(),
+                kind: flagged.kind,
+                status: flagged.status,
+                reproducer: self.reproducer(workflow, plan, index, &shrunk, &flagged),
+                message: flagged.message,
+                variant: variant.label,
+                shrink_steps,
+            });
+        }
+    }
+
+    /// Send a variant of a step without touching the shared context
+    async fn send(
+        &self,
+        planned: &PlannedStep,
+        variant: &Variant,
+        context: &Context,
+    ) -> StepResult {
+        let fuzzed = PlannedStep {
+            step: variant.apply(&planned.step),
+            method: planned.method.clone(),
+            path: planned.path.clone(),
+        };
+        let mut context = context.clone();
+        self.executor.run_step(&fuzzed, &mut context).await
+    }
+
+    /// Greedily replace the variant by smaller ones failing the same way
+    async fn shrink(
+        &self,
+        planned: &PlannedStep,
+        schemas: &OperationSchemas,
+        mut current: Variant,
+        flagged: &Flagged,
+        context: &Context,
+    ) -> (Variant, usize) {
+        let mut attempts = 0;
+        let mut steps = 0;
+
+        'shrinking: loop {
+            for candidate in shrink_candidates(&current, schemas) {
+                if attempts >= self.options.max_shrink_attempts {
+                    break 'shrinking;
+                }
+                attempts += 1;
+                let result = self.send(planned, &candidate, context).await;
+                if classify(&result, schemas)
+                    .is_some_and(|f| f.kind == flagged.kind && f.status == flagged.status)
+                {
+                    current = candidate;
+                    steps += 1;
+                    continue 'shrinking;
+                }
+            }
+            break;
+        }
+
+        (current, steps)
+    }
+
+    /// Workflow running the prerequisite steps and then the shrunk request
+    fn reproducer(
+        &self,
+        workflow: &Workflow,
+        plan: &WorkflowPlan,
+        index: usize,
+        variant: &Variant,
+        flagged: &Flagged,
+    ) -> Workflow {
+        let planned = &plan.steps[index];
+        let mut steps: Vec<Step> = plan.steps[..index]
+            .iter()
+            .map(|p| Step {
+                on_success: None,
+                on_failure: None,
+                ..p.step.clone()
+            })
+            .collect();
+
+        let mut step = variant.apply(&planned.step);
+        step.on_success = None;
+        step.on_failure = None;
+        if flagged.kind == FindingKind::ServerError || step.success_criteria.is_none() {
+            step.success_criteria = Some(vec![SuccessCriteria {
+                context: "$statusCode".to_string(),
+                condition: "<".to_string(),
+                value: Some(json!(500)),
+                criteria_type: None,
+            }]);
+        }
+        steps.push(step);
+
+        let mut id = format!(
+            "fuzz-{}-{}-{}",
+            plan.workflow_id, planned.step.step_id, flagged.kind
+        );
+        if let Some(status) = flagged.status {
+            id.push_str(&format!("-{status}"));
+        }
+
+        let mut extensions = indexmap::IndexMap::new();
+        extensions.insert(
+            "x-fuzz".to_string(),
+            json!({
+                "kind": flagged.kind.to_string(),
+                "message": flagged.message,
+                "variant": variant.label,
+                "seed": self.options.seed,
+            }),
+        );
+
+        Workflow {
+            workflow_id: id,
+            summary: Some(format!(
+                "Reproduces a {} in step '{}' of '{}'",
+                flagged.kind, planned.step.step_id, plan.workflow_id
+            )),
+            description: Some(flagged.message.clone()),
+            inputs: workflow.inputs.clone(),
+            steps,
+            success_criteria: None,
+            outputs: None,
+            extensions,
+        }
+    }
+}
+
+/// Flag a step result (`None` when the response is acceptable)
+fn classify(result: &StepResult, schemas: &OperationSchemas) -> Option<Flagged> {
+    if let Some(ref error) = result.error {
+        let kind = match error.kind {
+            ErrorKind::Timeout => FindingKind::Timeout,
+            ErrorKind::Transport => FindingKind::TransportError,
+            // リクエストを組み立てられなかった場合はサーバーの問題ではない
+            ErrorKind::Request => return None,
+        };
+        return Some(Flagged {
+            kind,
+            status: None,
+            message: error.message.clone(),
+        });
+    }
+
+    let response = result.response.as_ref()?;
+    let status = response.status;
+    if status >= 500 {
+        return Some(Flagged {
+            kind: FindingKind::ServerError,
+            status: Some(status),
+            message: format!("{} {} returned {}", schemas.method, schemas.path, status),
+        });
+    }
+
+    let Some(declared) = schemas.response(status) else {
+        return Some(Flagged {
+            kind: FindingKind::SchemaViolation,
+            status: Some(status),
+            message: format!(
+                "{} {} returned undeclared status {}",
+                schemas.method, schemas.path, status
+            ),
+        });
+    };
+
+    let schema = declared.body.as_ref().and_then(|b| b.schema.as_ref())?;
+    let is_json = response
+        .header("content-type")
+        .is_none_or(|ct| ct.contains("json"));
+    if !is_json || response.body.is_null() {
+        return None;
+    }
+    let errors = validate_instance(schema, &response.body);
+    if errors.is_empty() {
+        return None;
+    }
+    Some(Flagged {
+        kind: FindingKind::SchemaViolation,
+        status: Some(status),
+        message: format!(
+            "{} {} returned a {} body not matching its schema: {}",
+            schemas.method,
+            schemas.path,
+            status,
+            errors.join("; ")
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::schema::{BodySchema, ResponseSchema};
+    use crate::runner::native::{ExecutionError, StepResponse};
+    use serde_json::Value;
+    use std::time::Duration;
+
+    fn schemas() -> OperationSchemas {
+        OperationSchemas {
+            operation_id: Some("getItem".to_string()),
+            method: "GET".to_string(),
+            path: "/items/{id}".to_string(),
+            parameters: Vec::new(),
+            request_body: None,
+            responses: vec![
+                ResponseSchema {
+                    status: "200".to_string(),
+                    body: Some(BodySchema {
+                        content_type: "application/json".to_string(),
+                        required: false,
+                        schema: Some(json!({
+                            "type": "object",
+                            "required": ["id"],
+                            "properties": {"id": {"type": "integer"}}
+                        })),
+                        media: Default::default(),
+                    }),
+                },
+                ResponseSchema {
+                    status: "404".to_string(),
+                    body: None,
+                },
+            ],
+        }
+    }
+
+    fn result(status: u16, body: Value) -> StepResult {
+        StepResult {
+            step_id: "get".to_string(),
+            request: None,
+            response: Some(StepResponse {
+                status,
+                headers: Default::default(),
+                body,
+                elapsed: Duration::ZERO,
+            }),
+            error: None,
+            failures: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_classify() {
+        let schemas = schemas();
+        assert!(classify(&result(200, json!({"id": 1})), &schemas).is_none());
+        assert!(classify(&result(404, Value::Null), &schemas).is_none());
+
+        let flagged = classify(&result(503, Value::Null), &schemas).unwrap();
+        assert_eq!(flagged.kind, FindingKind::ServerError);
+        assert_eq!(flagged.status, Some(503));
+
+        let flagged = classify(&result(200, json!({"id": "1"})), &schemas).unwrap();
+        assert_eq!(flagged.kind, FindingKind::SchemaViolation);
+        assert!(flagged.message.contains("#/id"));
+
+        // 宣言されていないステータス
+        let flagged = classify(&result(409, Value::Null), &schemas).unwrap();
+        assert_eq!(flagged.kind, FindingKind::SchemaViolation);
+
+        let timeout = StepResult {
+            response: None,
+            error: Some(ExecutionError {
+                kind: ErrorKind::Timeout,
+                message: "timed out".to_string(),
+            }),
+            ..result(0, Value::Null)
+        };
+        assert_eq!(
+            classify(&timeout, &schemas).unwrap().kind,
+            FindingKind::Timeout
+        );
+    }
+}
@@ -0,0 +1,366 @@
+//! Negative requests derived from OpenAPI constraints
+//!
+//! Each case breaks exactly one constraint of the operation (a missing
+//! required parameter or property, a wrong type, an out-of-range value, an
+//! oversized string, an unknown enum value) and keeps the rest of the
+//! step's request as is. The server is expected to reject it with a 4xx
+//! declared in the operation's responses.
+
+use super::Variant;
+use crate::generator::sample::{bounds, merge_all_of, schema_type};
+use crate::generator::schema::OperationSchemas;
+use crate::models::arazzo::{Parameter, Step};
+use crate::payload::{BodyEncoding, set_pointer};
+use serde_json::{Map, Value, json};
+
+/// Value used for unknown enum values
+const UNKNOWN_ENUM: &str = "hornet2-unknown-value";
+
+/// Derive the negative cases of a step (the variant label describes the case)
+pub fn derive_cases(step: &Step, schemas: &OperationSchemas) -> Vec<Variant> {
+    let mut cases = Vec::new();
+    let base = Variant {
+        label: String::new(),
+        parameters: step.parameters.clone(),
+        request_body: step.request_body.clone(),
+    };
+
+    for declared in &schemas.parameters {
+        let position = base
+            .parameters
+            .iter()
+            .position(|p| p.name == declared.name && p.location == declared.location);
+        let subject = format!("{} parameter '{}'", declared.location, declared.name);
+
+        // パスパラメータを外すと別のパスになるので対象外
+        if declared.required
+            && declared.location != "path"
+            && let Some(i) = position
+        {
+            let mut case = base.clone();
+            case.label = format!("missing required {subject}");
+            case.parameters.remove(i);
+            cases.push(case);
+        }
+
+        for (problem, value) in invalid_values(&declared.schema, false) {
+            let mut case = base.clone();
+            case.label = format!("{subject}: {problem}");
+            let parameter = Parameter {
+                name: declared.name.clone(),
+                location: declared.location.to_string(),
+                value,
+            };
+            match position {
+                Some(i) => case.parameters[i] = parameter,
+                None => case.parameters.push(parameter),
+            }
+            cases.push(case);
+        }
+    }
+
+    if let (Some(declared), Some(body)) = (&schemas.request_body, &step.request_body) {
+        if declared.required {
+            let mut case = base.clone();
+            case.label = "missing required request body".to_string();
+            case.request_body = None;
+            cases.push(case);
+        }
+
+        let encoding = BodyEncoding::from_content_type(
+            body.content_type
+                .as_deref()
+                .or(Some(&declared.content_type)),
+        );
+        if encoding == BodyEncoding::Json
+            && let Some(ref schema) = declared.schema
+        {
+            let mut edits = Vec::new();
+            body_cases(&body.payload, schema, "", &mut edits);
+            for (label, pointer, value) in edits {
+                let mut payload = body.payload.clone();
+                let applied = match value {
+                    Some(value) if pointer.is_empty() => {
+                        payload = value;
+                        true
+                    }
+                    Some(value) => set_pointer(&mut payload, &pointer, value).is_ok(),
+                    None => remove_pointer(&mut payload, &pointer),
+                };
+                if applied {
+                    let mut case = base.clone();
+                    case.label = label;
+                    if let Some(ref mut body) = case.request_body {
+                        body.payload = payload;
+                    }
+                    cases.push(case);
+                }
+            }
+        }
+    }
+
+    cases
+}
+
+/// Whether a status is a client error declared in the operation's responses
+pub fn is_declared_client_error(schemas: &OperationSchemas, status: u16) -> bool {
+    (400..500).contains(&status)
+        && schemas.responses.iter().any(|r| {
+            r.status == status.to_string()
+                || r.status
+                    .eq_ignore_ascii_case(&format!("{}XX", status / 100))
+        })
+}
+
+/// Body cases: `(label, pointer, Some(value))` to replace or `None` to remove
+fn body_cases(
+    value: &Value,
+    schema: &Value,
+    pointer: &str,
+    out: &mut Vec<(String, String, Option<Value>)>,
+) {
+    let schema = merge_all_of(schema);
+    let location = if pointer.is_empty() { "#" } else { pointer };
+
+    if !pointer.is_empty() {
+        for (problem, invalid) in invalid_values(&schema, true) {
+            out.push((
+                format!("body property '{location}': {problem}"),
+                pointer.to_string(),
+                Some(invalid),
+            ));
+        }
+    } else if let Some((problem, invalid)) = invalid_values(&schema, true)
+        .into_iter()
+        .find(|(problem, _)| problem.starts_with("wrong type"))
+    {
+        out.push((format!("body: {problem}"), String::new(), Some(invalid)));
+    }
+
+    let Value::Object(map) = value else {
+        return;
+    };
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(|r| r.as_array())
+        .map(|r| r.iter().filter_map(|n| n.as_str()).collect())
+        .unwrap_or_default();
+    let properties = schema
+        .get("properties")
+        .and_then(|p| p.as_object())
+        .cloned()
+        .unwrap_or_default();
+
+    for (key, child_schema) in &properties {
+        if child_schema.get("readOnly").and_then(|v| v.as_bool()) == Some(true) {
+            continue;
+        }
+        let child_pointer = format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"));
+        if required.contains(&key.as_str()) && map.contains_key(key) {
+            out.push((
+                format!("missing required body property '{child_pointer}'"),
+                child_pointer.clone(),
+                None,
+            ));
+        }
+        // 値がなければ null から不正な値を作る
+        let child = map.get(key).unwrap_or(&Value::Null);
+        body_cases(child, child_schema, &child_pointer, out);
+    }
+}
+
+/// Values violating one constraint of a schema, with a description
+///
+/// Parameters are sent as text, so a string parameter has no wrong type.
+fn invalid_values(schema: &Value, in_body: bool) -> Vec<(String, Value)> {
+    let schema = merge_all_of(schema);
+    let Some(obj) = schema.as_object() else {
+        return Vec::new();
+    };
+    let mut values = Vec::new();
+    let ty = schema_type(obj);
+
+    let wrong = match ty.as_deref() {
+        Some("integer") | Some("number") => Some(json!("not-a-number")),
+        Some("boolean") => Some(json!("not-a-boolean")),
+        Some("string") if in_body => Some(json!(12345)),
+        Some("object") if in_body => Some(json!("not-an-object")),
+        Some("array") if in_body => Some(json!("not-an-array")),
+        _ => None,
+    };
+    if let (Some(ty), Some(value)) = (ty.as_deref(), wrong) {
+        values.push((format!("wrong type (expected {ty})"), value));
+    }
+
+    if matches!(ty.as_deref(), Some("integer") | Some("number")) {
+        let integer = ty.as_deref() == Some("integer");
+        let number = |v: f64| {
+            if integer { json!(v as i64) } else { json!(v) }
+        };
+        let (lower, upper) = bounds(obj);
+        if let Some((min, exclusive)) = lower {
+            let value = if exclusive { min } else { min - 1.0 };
+            values.push((format!("below minimum ({})", number(min)), number(value)));
+        }
+        if let Some((max, exclusive)) = upper {
+            let value = if exclusive { max } else { max + 1.0 };
+            values.push((format!("above maximum ({})", number(max)), number(value)));
+        }
+    }
+
+    if ty.as_deref() == Some("string") {
+        if let Some(max) = uint(obj, "maxLength") {
+            values.push((
+                format!("longer than maxLength ({max})"),
+                json!("x".repeat(max as usize + 1)),
+            ));
+        }
+        if let Some(min) = uint(obj, "minLength").filter(|min| *min > 0) {
+            values.push((
+                format!("shorter than minLength ({min})"),
+                json!("x".repeat(min as usize - 1)),
+            ));
+        }
+        if let Some(options) = obj.get("enum").and_then(|e| e.as_array())
+            && !options.contains(&json!(UNKNOWN_ENUM))
+        {
+            values.push(("unknown enum value".to_string(), json!(UNKNOWN_ENUM)));
+        }
+    }
+
+    values
+}
+
+fn uint(obj: &Map<String, Value>, key: &str) -> Option<u64> {
+    obj.get(key).and_then(|v| v.as_u64())
+}
+
+/// Remove the value at a JSON Pointer
+fn remove_pointer(root: &mut Value, pointer: &str) -> bool {
+    let Some((parent, key)) = pointer.rsplit_once('/') else {
+        return false;
+    };
+    let key = key.replace("~1", "/").replace("~0", "~");
+    match root.pointer_mut(parent) {
+        Some(Value::Object(map)) => map.remove(&key).is_some(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::schema::{BodySchema, ParameterSchema, ResponseSchema};
+
+    fn schemas() -> OperationSchemas {
+        OperationSchemas {
+            operation_id: Some("createItem".to_string()),
+            method: "POST".to_string(),
+            path: "/owners/{ownerId}/items".to_string(),
+            parameters: vec![
+                ParameterSchema {
+                    name: "ownerId".to_string(),
+                    location: "path",
+                    required: true,
+                    schema: json!({"type": "integer", "minimum": 1}),
+                },
+                ParameterSchema {
+                    name: "mode".to_string(),
+                    location: "query",
+                    required: true,
+                    schema: json!({"type": "string", "enum": ["fast", "safe"]}),
+                },
+            ],
+            request_body: Some(BodySchema {
+                content_type: "application/json".to_string(),
+                required: true,
+                schema: Some(json!({
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": {
+                        "id": {"type": "integer", "readOnly": true},
+                        "name": {"type": "string", "maxLength": 4},
+                        "qty": {"type": "integer", "maximum": 10}
+                    }
+                })),
+                media: Default::default(),
+            }),
+            responses: vec![
+                ResponseSchema {
+                    status: "201".to_string(),
+                    body: None,
+                },
+                ResponseSchema {
+                    status: "4XX".to_string(),
+                    body: None,
+                },
+            ],
+        }
+    }
+
+    fn step() -> Step {
+        serde_json::from_value(json!({
+            "stepId": "create",
+            "operationId": "createItem",
+            "parameters": [
+                {"name": "ownerId", "in": "path", "value": "$steps.owner.outputs.id"},
+                {"name": "mode", "in": "query", "value": "fast"}
+            ],
+            "requestBody": {"payload": {"name": "box"}}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_derive_cases() {
+        let cases = derive_cases(&step(), &schemas());
+        let labels: Vec<&str> = cases.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            vec![
+                "path parameter 'ownerId': wrong type (expected integer)",
+                "path parameter 'ownerId': below minimum (1)",
+                "missing required query parameter 'mode'",
+                "query parameter 'mode': unknown enum value",
+                "missing required request body",
+                "body: wrong type (expected object)",
+                "missing required body property '/name'",
+                "body property '/name': wrong type (expected string)",
+                "body property '/name': longer than maxLength (4)",
+                "body property '/qty': wrong type (expected integer)",
+                "body property '/qty': above maximum (10)",
+            ]
+        );
+
+        // 1つの制約だけを破り、残りは元のリクエストのまま
+        let below = &cases[1];
+        assert_eq!(below.parameters[0].value, json!(0));
+        assert_eq!(below.parameters[1].value, json!("fast"));
+        let missing = &cases[2];
+        assert_eq!(missing.parameters.len(), 1);
+        let above = &cases[10];
+        assert_eq!(
+            above.request_body.as_ref().unwrap().payload,
+            json!({"name": "box", "qty": 11})
+        );
+        let oversized = &cases[8];
+        assert_eq!(
+            oversized.request_body.as_ref().unwrap().payload,
+            json!({"name": "xxxxx"})
+        );
+    }
+
+    #[test]
+    fn test_is_declared_client_error() {
+        let schemas = schemas();
+        assert!(is_declared_client_error(&schemas, 400));
+        assert!(is_declared_client_error(&schemas, 422));
+        assert!(!is_declared_client_error(&schemas, 201));
+        assert!(!is_declared_client_error(&schemas, 500));
+
+        let mut strict = schemas.clone();
+        strict.responses[1].status = "404".to_string();
+        assert!(!is_declared_client_error(&strict, 400));
+        assert!(is_declared_client_error(&strict, 404));
+    }
+}
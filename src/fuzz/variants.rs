@@ -0,0 +1,493 @@
+//! Request variants of a step and their shrinking candidates
+
+use super::FuzzOptions;
+use crate::generator::sample::{SampleMode, SampleOptions, merge_all_of, synthesize};
+use crate::generator::schema::{OperationSchemas, validate_instance};
+use crate::models::arazzo::{Parameter, RequestBody, Step};
+use crate::models::expression::{TemplateSegment, parse_template};
+use crate::payload::{BodyEncoding, set_pointer};
+use serde_json::Value;
+
+/// Parameters and request body of one fuzzed request
+#[derive(Debug, Clone)]
+pub struct Variant {
+    /// How the variant was built (`minimal`, `maximal`, `random#3`)
+    pub label: String,
+    pub parameters: Vec<Parameter>,
+    pub request_body: Option<RequestBody>,
+}
+
+impl Variant {
+    /// The step with its parameters and request body replaced
+    pub fn apply(&self, step: &Step) -> Step {
+        let mut step = step.clone();
+        step.parameters = self.parameters.clone();
+        step.request_body = self.request_body.clone();
+        step
+    }
+
+    /// Size of the serialized request data (shrinking must decrease it)
+    pub fn size(&self) -> usize {
+        let params: usize = self
+            .parameters
+            .iter()
+            .map(|p| p.name.len() + p.value.to_string().len())
+            .sum();
+        params
+            + self
+                .request_body
+                .as_ref()
+                .map(|b| b.payload.to_string().len())
+                .unwrap_or(0)
+    }
+}
+
+/// Build `options.variants` variants: minimal, maximal, then random instances
+pub fn generate(step: &Step, schemas: &OperationSchemas, options: &FuzzOptions) -> Vec<Variant> {
+    (0..options.variants)
+        .map(|i| {
+            let (mode, label) = match i {
+                0 => (SampleMode::Minimal, "minimal".to_string()),
+                1 => (SampleMode::Maximal, "maximal".to_string()),
+                _ => (SampleMode::Random, format!("random#{}", i - 1)),
+            };
+            build(
+                step,
+                schemas,
+                mode,
+                options.seed.wrapping_add(i as u64),
+                label,
+            )
+        })
+        .collect()
+}
+
+fn build(
+    step: &Step,
+    schemas: &OperationSchemas,
+    mode: SampleMode,
+    seed: u64,
+    label: String,
+) -> Variant {
+    let options = |field: u64| SampleOptions {
+        mode,
+        seed: seed.wrapping_mul(0x100_0000_01B3).wrapping_add(field),
+        ..Default::default()
+    };
+
+    let mut parameters = Vec::new();
+    for (k, param) in schemas.parameters.iter().enumerate() {
+        let original = step
+            .parameters
+            .iter()
+            .find(|p| p.name == param.name && p.location == param.location);
+        // 前のステップから流れてくる値はそのまま使う
+        if let Some(original) = original
+            && is_bound(&original.value)
+        {
+            parameters.push(original.clone());
+            continue;
+        }
+        let include = param.required
+            || match mode {
+                SampleMode::Minimal => false,
+                SampleMode::Maximal => true,
+                SampleMode::Random => (seed >> (k % 64)) & 1 == 0,
+            };
+        if include {
+            parameters.push(Parameter {
+                name: param.name.clone(),
+                location: param.location.to_string(),
+                value: synthesize(&param.schema, &options(k as u64)),
+            });
+        }
+    }
+    // OpenAPI に宣言されていないパラメータ（認証ヘッダーなど）は残す
+    for param in &step.parameters {
+        if !schemas
+            .parameters
+            .iter()
+            .any(|p| p.name == param.name && p.location == param.location)
+        {
+            parameters.push(param.clone());
+        }
+    }
+
+    let request_body = match (&schemas.request_body, step.request_body.as_ref()) {
+        (Some(body), original) if original.is_some() || body.required => {
+            let encoding = BodyEncoding::from_content_type(
+                original
+                    .and_then(|o| o.content_type.as_deref())
+                    .or(Some(&body.content_type)),
+            );
+            match body.schema {
+                // ファイルを含むマルチパートは元のボディを使う
+                Some(ref schema) if encoding != BodyEncoding::Multipart => {
+                    let mut payload = synthesize(schema, &options(u64::MAX));
+                    if let Some(original) = original {
+                        overlay_bound(&original.payload, "", &mut payload);
+                    }
+                    Some(RequestBody {
+                        content_type: original
+                            .and_then(|o| o.content_type.clone())
+                            .or_else(|| Some(body.content_type.clone())),
+                        payload,
+                        replacements: original.map(|o| o.replacements.clone()).unwrap_or_default(),
+                    })
+                }
+                _ => original.cloned(),
+            }
+        }
+        _ => step.request_body.clone(),
+    };
+
+    Variant {
+        label,
+        parameters,
+        request_body,
+    }
+}
+
+/// Smaller variants to try when shrinking a failing one
+///
+/// Candidates stay schema-valid: optional parameters and properties are
+/// dropped, values are replaced by the minimal instance of their schema,
+/// arrays and strings are cut down to their minimum size. Values bound to
+/// runtime expressions are never touched. Every candidate is smaller than
+/// `variant`.
+pub fn shrink_candidates(variant: &Variant, schemas: &OperationSchemas) -> Vec<Variant> {
+    let mut candidates = Vec::new();
+
+    for (i, param) in variant.parameters.iter().enumerate() {
+        if is_bound(&param.value) {
+            continue;
+        }
+        let Some(declared) = schemas
+            .parameters
+            .iter()
+            .find(|p| p.name == param.name && p.location == param.location)
+        else {
+            continue;
+        };
+
+        if !declared.required {
+            let mut candidate = variant.clone();
+            candidate.parameters.remove(i);
+            candidates.push(candidate);
+        }
+        let minimal = synthesize(&declared.schema, &SampleOptions::default());
+        if minimal != param.value {
+            let mut candidate = variant.clone();
+            candidate.parameters[i].value = minimal;
+            candidates.push(candidate);
+        }
+    }
+
+    if let (Some(body), Some(schema)) = (
+        &variant.request_body,
+        schemas
+            .request_body
+            .as_ref()
+            .and_then(|b| b.schema.as_ref()),
+    ) {
+        let mut edits = Vec::new();
+        body_edits(&body.payload, schema, "", &mut edits);
+        for (pointer, replacement) in edits {
+            let mut payload = body.payload.clone();
+            if edit(&mut payload, &pointer, replacement) {
+                let mut candidate = variant.clone();
+                if let Some(ref mut body) = candidate.request_body {
+                    body.payload = payload;
+                }
+                candidates.push(candidate);
+            }
+        }
+    }
+
+    let size = variant.size();
+    candidates.retain(|c| c.size() < size);
+    for candidate in &mut candidates {
+        candidate.label = format!("{} (shrunk)", variant.label.trim_end_matches(" (shrunk)"));
+    }
+    candidates
+}
+
+/// Edits of a body value: `(pointer, Some(new value))` or `(pointer, None)` to remove
+fn body_edits(
+    value: &Value,
+    schema: &Value,
+    pointer: &str,
+    out: &mut Vec<(String, Option<Value>)>,
+) {
+    if is_bound(value) {
+        return;
+    }
+    let schema = merge_all_of(schema);
+    if !contains_bound(value) && schema.as_object().is_some_and(|s| !s.is_empty()) {
+        let minimal = synthesize(&schema, &SampleOptions::default());
+        if &minimal != value && minimal.to_string().len() < value.to_string().len() {
+            out.push((pointer.to_string(), Some(minimal)));
+        }
+    }
+
+    match value {
+        Value::Object(map) => {
+            let required: Vec<&str> = schema
+                .get("required")
+                .and_then(|r| r.as_array())
+                .map(|r| r.iter().filter_map(|n| n.as_str()).collect())
+                .unwrap_or_default();
+            for (key, child) in map {
+                let child_pointer =
+                    format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"));
+                if !required.contains(&key.as_str()) && !contains_bound(child) {
+                    out.push((child_pointer.clone(), None));
+                }
+                let child_schema = schema
+                    .get("properties")
+                    .and_then(|p| p.get(key))
+                    .cloned()
+                    .unwrap_or(Value::Object(Default::default()));
+                body_edits(child, &child_schema, &child_pointer, out);
+            }
+        }
+        Value::Array(items) => {
+            let min_items = schema.get("minItems").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+            if items.len() > min_items && !items.iter().any(contains_bound) {
+                let keep = min_items.max(items.len() / 2);
+                out.push((
+                    pointer.to_string(),
+                    Some(Value::Array(items[..keep].to_vec())),
+                ));
+            }
+            let item_schema = schema
+                .get("items")
+                .cloned()
+                .unwrap_or(Value::Object(Default::default()));
+            for (i, item) in items.iter().enumerate() {
+                body_edits(item, &item_schema, &format!("{pointer}/{i}"), out);
+            }
+        }
+        Value::String(s) => {
+            let min_length = schema
+                .get("minLength")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0) as usize;
+            let len = s.chars().count();
+            if len > min_length {
+                let cut: String = s.chars().take(min_length.max(len / 2)).collect();
+                let candidate = Value::String(cut);
+                if validate_instance(&schema, &candidate).is_empty() {
+                    out.push((pointer.to_string(), Some(candidate)));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Replace (`Some`) or remove (`None`) the value at a JSON Pointer
+fn edit(root: &mut Value, pointer: &str, replacement: Option<Value>) -> bool {
+    match replacement {
+        Some(value) => {
+            if pointer.is_empty() {
+                *root = value;
+                true
+            } else {
+                set_pointer(root, pointer, value).is_ok()
+            }
+        }
+        None => {
+            let Some((parent, key)) = pointer.rsplit_once('/') else {
+                return false;
+            };
+            let key = key.replace("~1", "/").replace("~0", "~");
+            match root.pointer_mut(parent) {
+                Some(Value::Object(map)) => map.remove(&key).is_some(),
+                _ => false,
+            }
+        }
+    }
+}
+
+/// Copy values bound to runtime expressions from `original` into `target`
+fn overlay_bound(original: &Value, pointer: &str, target: &mut Value) {
+    match original {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let child_pointer =
+                    format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"));
+                overlay_bound(child, &child_pointer, target);
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                overlay_bound(item, &format!("{pointer}/{i}"), target);
+            }
+        }
+        value if is_bound(value) => {
+            if pointer.is_empty() {
+                *target = value.clone();
+            } else {
+                // 合成された配列が短い場合などは設定できないので諦める
+                let _ = set_pointer(target, pointer, value.clone());
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Whether a value is a string embedding a runtime expression
+fn is_bound(value: &Value) -> bool {
+    value.as_str().is_some_and(|s| {
+        parse_template(s)
+            .iter()
+            .any(|segment| matches!(segment, TemplateSegment::Expression(_)))
+    })
+}
+
+fn contains_bound(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => map.values().any(contains_bound),
+        Value::Array(items) => items.iter().any(contains_bound),
+        other => is_bound(other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::schema::{BodySchema, ParameterSchema};
+    use serde_json::json;
+
+    fn schemas() -> OperationSchemas {
+        OperationSchemas {
+            operation_id: Some("createItem".to_string()),
+            method: "POST".to_string(),
+            path: "/owners/{ownerId}/items".to_string(),
+            parameters: vec![
+                ParameterSchema {
+                    name: "ownerId".to_string(),
+                    location: "path",
+                    required: true,
+                    schema: json!({"type": "integer"}),
+                },
+                ParameterSchema {
+                    name: "dryRun".to_string(),
+                    location: "query",
+                    required: false,
+                    schema: json!({"type": "boolean"}),
+                },
+            ],
+            request_body: Some(BodySchema {
+                content_type: "application/json".to_string(),
+                required: true,
+                schema: Some(json!({
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": {
+                        "name": {"type": "string", "minLength": 2, "maxLength": 20},
+                        "ownerRef": {"type": "integer"},
+                        "tags": {"type": "array", "items": {"type": "string"}}
+                    }
+                })),
+                media: Default::default(),
+            }),
+            responses: Vec::new(),
+        }
+    }
+
+    fn step() -> Step {
+        serde_json::from_value(json!({
+            "stepId": "create",
+            "operationId": "createItem",
+            "parameters": [
+                {"name": "ownerId", "in": "path", "value": "$steps.owner.outputs.id"},
+                {"name": "X-Api-Key", "in": "header", "value": "secret"}
+            ],
+            "requestBody": {"payload": {"name": "box", "ownerRef": "$steps.owner.outputs.id"}}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_generate_keeps_bound_values() {
+        let options = FuzzOptions {
+            variants: 4,
+            ..Default::default()
+        };
+        let variants = generate(&step(), &schemas(), &options);
+        assert_eq!(variants.len(), 4);
+        assert_eq!(variants[0].label, "minimal");
+        assert_eq!(variants[1].label, "maximal");
+
+        for variant in &variants {
+            assert_eq!(
+                variant.parameters[0].value,
+                json!("$steps.owner.outputs.id")
+            );
+            // 宣言されていないヘッダーはそのまま
+            assert!(
+                variant
+                    .parameters
+                    .iter()
+                    .any(|p| p.name == "X-Api-Key" && p.value == json!("secret"))
+            );
+            let payload = &variant.request_body.as_ref().unwrap().payload;
+            assert_eq!(payload["ownerRef"], json!("$steps.owner.outputs.id"));
+            assert!(payload["name"].is_string());
+        }
+        // maximal はすべての任意パラメータを含む
+        assert!(variants[1].parameters.iter().any(|p| p.name == "dryRun"));
+        assert!(!variants[0].parameters.iter().any(|p| p.name == "dryRun"));
+    }
+
+    #[test]
+    fn test_shrink_candidates_are_smaller() {
+        let variant = Variant {
+            label: "maximal".to_string(),
+            parameters: vec![
+                Parameter {
+                    name: "ownerId".to_string(),
+                    location: "path".to_string(),
+                    value: json!("$steps.owner.outputs.id"),
+                },
+                Parameter {
+                    name: "dryRun".to_string(),
+                    location: "query".to_string(),
+                    value: json!(true),
+                },
+            ],
+            request_body: Some(RequestBody {
+                content_type: None,
+                payload: json!({
+                    "name": "abcdefghij",
+                    "ownerRef": "$steps.owner.outputs.id",
+                    "tags": ["x", "y", "z"]
+                }),
+                replacements: Vec::new(),
+            }),
+        };
+
+        let candidates = shrink_candidates(&variant, &schemas());
+        assert!(!candidates.is_empty());
+        for candidate in &candidates {
+            assert!(candidate.size() < variant.size());
+            assert_eq!(
+                candidate.parameters[0].value,
+                json!("$steps.owner.outputs.id")
+            );
+            let payload = &candidate.request_body.as_ref().unwrap().payload;
+            assert_eq!(payload["ownerRef"], json!("$steps.owner.outputs.id"));
+            // 必須プロパティは残り、minLength も守られる
+            assert!(payload["name"].as_str().unwrap().len() >= 2);
+        }
+        let payloads: Vec<&Value> = candidates
+            .iter()
+            .map(|c| &c.request_body.as_ref().unwrap().payload)
+            .collect();
+        assert!(payloads.iter().any(|p| p.get("tags").is_none()));
+        assert!(payloads.iter().any(|p| p["name"] == json!("abcde")));
+        assert!(candidates.iter().any(|c| c.parameters.len() == 1));
+    }
+}
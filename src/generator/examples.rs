@@ -0,0 +1,175 @@
+//! Example values declared in OpenAPI documents
+//!
+//! Examples are looked up in the order `example`, `examples`, `x-examples`,
+//! then in the schema (`example`, `examples`, `default`, `const`, `enum`).
+//! Object schemas without an example are assembled from the examples of
+//! their properties.
+
+use super::schema::resolve_schema;
+use oas3::spec::{MediaType, MediaTypeExamples, ObjectOrReference, Parameter, Spec};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Example of a media type (request or response body)
+pub fn media_example(media: &MediaType, spec: &Spec) -> Option<Value> {
+    let declared = match media.examples {
+        Some(MediaTypeExamples::Example { ref example }) => Some(example.clone()),
+        Some(MediaTypeExamples::Examples { ref examples }) => first_example(examples, spec),
+        None => None,
+    };
+
+    declared
+        .or_else(|| extension_example(&media.extensions))
+        .or_else(|| {
+            media
+                .schema
+                .as_ref()
+                .and_then(|s| schema_example(&resolve_schema(s, spec)))
+        })
+}
+
+/// Example of a parameter
+pub fn parameter_example(param: &Parameter, spec: &Spec) -> Option<Value> {
+    param
+        .example
+        .clone()
+        .or_else(|| first_example(&param.examples, spec))
+        .or_else(|| extension_example(&param.extensions))
+        .or_else(|| {
+            param
+                .schema
+                .as_ref()
+                .and_then(|s| schema_example(&resolve_schema(s, spec)))
+        })
+        .or_else(|| {
+            param
+                .content
+                .as_ref()
+                .and_then(|content| content.values().next())
+                .and_then(|media| media_example(media, spec))
+        })
+}
+
+/// Example of a (ref-resolved) JSON schema
+pub fn schema_example(schema: &Value) -> Option<Value> {
+    if let Some(example) = schema.get("example") {
+        return Some(example.clone());
+    }
+    if let Some(example) = schema
+        .get("examples")
+        .and_then(|e| e.as_array())
+        .and_then(|e| e.first())
+    {
+        return Some(example.clone());
+    }
+    for key in ["default", "const"] {
+        if let Some(value) = schema.get(key) {
+            return Some(value.clone());
+        }
+    }
+    if let Some(value) = schema
+        .get("enum")
+        .and_then(|e| e.as_array())
+        .and_then(|e| e.first())
+    {
+        return Some(value.clone());
+    }
+
+    // allOf はメンバーの例をマージする
+    if let Some(members) = schema.get("allOf").and_then(|a| a.as_array()) {
+        let mut merged = serde_json::Map::new();
+        for member in members {
+            if let Some(Value::Object(obj)) = schema_example(member) {
+                merged.extend(obj);
+            }
+        }
+        if !merged.is_empty() {
+            return Some(Value::Object(merged));
+        }
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(example) = schema
+            .get(key)
+            .and_then(|a| a.as_array())
+            .and_then(|members| members.iter().find_map(schema_example))
+        {
+            return Some(example);
+        }
+    }
+
+    if let Some(props) = schema.get("properties").and_then(|p| p.as_object()) {
+        let obj: serde_json::Map<String, Value> = props
+            .iter()
+            .filter_map(|(name, prop)| schema_example(prop).map(|v| (name.clone(), v)))
+            .collect();
+        return (!obj.is_empty()).then_some(Value::Object(obj));
+    }
+    if let Some(items) = schema.get("items") {
+        return schema_example(items).map(|item| Value::Array(vec![item]));
+    }
+
+    None
+}
+
+fn first_example(
+    examples: &BTreeMap<String, ObjectOrReference<oas3::spec::Example>>,
+    spec: &Spec,
+) -> Option<Value> {
+    examples
+        .values()
+        .filter_map(|example| example.resolve(spec).ok())
+        .find_map(|example| example.value)
+}
+
+/// `x-examples` (a list of values or a map of named examples)
+fn extension_example(extensions: &BTreeMap<String, Value>) -> Option<Value> {
+    let first = match extensions.get("examples")? {
+        Value::Array(items) => items.first()?.clone(),
+        Value::Object(named) => named.values().next()?.clone(),
+        _ => return None,
+    };
+
+    // 名前付きの例は Example Object 形式（value キー）の場合がある
+    match first {
+        Value::Object(ref obj) if obj.contains_key("value") => obj.get("value").cloned(),
+        other => Some(other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_schema_example() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string", "example": "doggie"},
+                "status": {"type": "string", "enum": ["available", "sold"]},
+                "tags": {"type": "array", "items": {"type": "string", "default": "new"}},
+                "notes": {"type": "string"}
+            }
+        });
+
+        assert_eq!(
+            schema_example(&schema),
+            Some(json!({"name": "doggie", "status": "available", "tags": ["new"]}))
+        );
+        assert_eq!(schema_example(&json!({"type": "string"})), None);
+    }
+
+    #[test]
+    fn test_extension_example() {
+        let mut extensions = BTreeMap::new();
+        extensions.insert(
+            "examples".to_string(),
+            json!({"basic": {"summary": "Basic", "value": {"name": "rex"}}}),
+        );
+        assert_eq!(extension_example(&extensions), Some(json!({"name": "rex"})));
+
+        extensions.insert("examples".to_string(), json!([{"name": "tom"}]));
+        assert_eq!(extension_example(&extensions), Some(json!({"name": "tom"})));
+    }
+}
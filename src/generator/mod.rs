@@ -0,0 +1,807 @@
+//! Generate Arazzo workflows from OpenAPI documents
+//!
+//! Two kinds of workflows are proposed:
+//!
+//! - CRUD lifecycles: every collection path with a `POST` and an item path
+//!   (`/pets` and `/pets/{petId}`) becomes create → get → update → list →
+//!   delete, with the id returned by the create step wired into the item
+//!   requests;
+//! - link chains: an operation whose responses declare OpenAPI `links` is
+//!   followed by the linked operations, with the link parameters wired from
+//!   the first step's outputs.
+//!
+//! Parameters and request bodies are filled from the examples declared in the
+//! OpenAPI documents (see [`examples`]). Required parameters without an
+//! example become workflow inputs.
+
+pub mod examples;
+pub mod sample;
+pub mod schema;
+
+use crate::converters::common::parse_operation_path;
+use crate::importers::{sanitize_id, unique_id};
+use crate::loader::OpenApiResolver;
+use crate::models::arazzo::{
+    ArazzoSpec, Parameter as StepParameter, RequestBody, Step, SuccessCriteria, Workflow,
+};
+use crate::models::expression::RuntimeExpression;
+use crate::payload::BodyEncoding;
+use indexmap::IndexMap;
+use oas3::spec::{Link, ObjectOrReference, Operation, Parameter, ParameterIn, Spec};
+use serde_json::{Value, json};
+use std::collections::{HashMap, HashSet};
+
+/// Workflows proposed for a set of OpenAPI documents
+#[derive(Debug, Clone, Default)]
+pub struct GenerateResult {
+    pub workflows: Vec<Workflow>,
+    /// Things the user should review (missing examples, unresolved links, ...)
+    pub warnings: Vec<String>,
+}
+
+/// Outcome of merging generated workflows into an existing document
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeSummary {
+    /// Ids of the workflows that were added
+    pub added: Vec<String>,
+    /// Ids of the workflows that already existed and were left untouched
+    pub skipped: Vec<String>,
+}
+
+/// An operation of one of the loaded OpenAPI documents
+struct OperationInfo<'a> {
+    spec: &'a Spec,
+    method: &'static str,
+    path: &'a str,
+    operation: &'a Operation,
+    /// Parameters declared on the path item and on the operation
+    parameters: Vec<Parameter>,
+}
+
+impl OperationInfo<'_> {
+    fn label(&self) -> String {
+        format!("{} {}", self.method, self.path)
+    }
+
+    fn base_step_id(&self) -> String {
+        let name = self
+            .operation
+            .operation_id
+            .clone()
+            .unwrap_or_else(|| format!("{} {}", self.method.to_lowercase(), self.path));
+        sanitize_id(&name)
+    }
+
+    /// Lowest 2xx status code declared for the operation
+    fn expected_status(&self) -> Option<u16> {
+        self.operation
+            .responses
+            .iter()
+            .flatten()
+            .filter_map(|(code, _)| code.parse::<u16>().ok())
+            .filter(|code| (200..300).contains(code))
+            .min()
+    }
+
+    /// Resolved schema of the first successful JSON response
+    fn response_schema(&self) -> Option<Value> {
+        self.success_responses().into_iter().find_map(|response| {
+            response
+                .content
+                .iter()
+                .find(|(content_type, _)| {
+                    BodyEncoding::from_content_type(Some(content_type)) == BodyEncoding::Json
+                })
+                .and_then(|(_, media)| media.schema.as_ref())
+                .map(|s| schema::resolve_schema(s, self.spec))
+        })
+    }
+
+    /// Links declared on the successful responses, sorted by name
+    fn links(&self) -> Vec<(String, Link)> {
+        let mut links: Vec<(String, Link)> = self
+            .success_responses()
+            .into_iter()
+            .flat_map(|response| {
+                response
+                    .links
+                    .iter()
+                    .filter_map(|(name, link)| {
+                        resolve_link(link, self.spec).map(|link| (name.clone(), link))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        links.sort_by(|a, b| a.0.cmp(&b.0));
+        links
+    }
+
+    fn success_responses(&self) -> Vec<oas3::spec::Response> {
+        let mut responses: Vec<(String, oas3::spec::Response)> = self
+            .operation
+            .responses(self.spec)
+            .into_iter()
+            .filter(|(code, _)| code.starts_with('2'))
+            .collect();
+        responses.sort_by(|a, b| a.0.cmp(&b.0));
+        responses.into_iter().map(|(_, r)| r).collect()
+    }
+}
+
+/// Resolve a link, following `#/components/links/*` references
+fn resolve_link(link: &ObjectOrReference<Link>, spec: &Spec) -> Option<Link> {
+    match link {
+        ObjectOrReference::Object(link) => Some(link.clone()),
+        ObjectOrReference::Ref { ref_path, .. } => {
+            let name = ref_path.strip_prefix("#/components/links/")?;
+            match spec.components.as_ref()?.links.get(name)? {
+                ObjectOrReference::Object(link) => Some(link.clone()),
+                ObjectOrReference::Ref { .. } => None,
+            }
+        }
+    }
+}
+
+/// A resource exposing a collection path and an item path
+struct CrudResource<'a, 'b> {
+    collection: &'a str,
+    item_param: String,
+    create: &'b OperationInfo<'a>,
+    get: Option<&'b OperationInfo<'a>>,
+    update: Option<&'b OperationInfo<'a>>,
+    list: Option<&'b OperationInfo<'a>>,
+    delete: Option<&'b OperationInfo<'a>>,
+}
+
+impl CrudResource<'_, '_> {
+    fn item_operations(&self) -> impl Iterator<Item = &OperationInfo<'_>> {
+        [self.get, self.update, self.delete].into_iter().flatten()
+    }
+
+    fn contains(&self, op: &OperationInfo<'_>) -> bool {
+        [
+            Some(self.create),
+            self.get,
+            self.update,
+            self.list,
+            self.delete,
+        ]
+        .into_iter()
+        .flatten()
+        .any(|o| o.method == op.method && o.path == op.path)
+    }
+}
+
+/// Steps and inputs of a workflow being generated
+#[derive(Default)]
+struct WorkflowBuilder {
+    steps: Vec<Step>,
+    step_ids: HashSet<String>,
+    inputs: IndexMap<String, Value>,
+}
+
+impl WorkflowBuilder {
+    /// Add a step calling `op`; `bindings` gives values for parameters by name
+    fn add_step(
+        &mut self,
+        op: &OperationInfo<'_>,
+        bindings: &HashMap<String, Value>,
+        warnings: &mut Vec<String>,
+    ) -> String {
+        let step_id = unique_id(&op.base_step_id(), &mut self.step_ids);
+
+        let mut parameters = Vec::new();
+        for param in &op.parameters {
+            let location = location_name(param.location);
+
+            let value = if let Some(value) = bindings.get(&param.name) {
+                value.clone()
+            } else if param.location != ParameterIn::Path && param.required != Some(true) {
+                // 任意パラメータは最小限のリクエストには含めない
+                continue;
+            } else if let Some(example) = examples::parameter_example(param, op.spec) {
+                example
+            } else {
+                self.add_input(param, op.spec);
+                json!(format!("$inputs.{}", param.name))
+            };
+
+            parameters.push(StepParameter {
+                name: param.name.clone(),
+                location: location.to_string(),
+                value,
+            });
+        }
+
+        let request_body = match op.operation.request_body(op.spec) {
+            Ok(Some(body)) => {
+                let content = body
+                    .content
+                    .iter()
+                    .find(|(content_type, _)| {
+                        BodyEncoding::from_content_type(Some(content_type)) == BodyEncoding::Json
+                    })
+                    .or_else(|| body.content.iter().next());
+                match content {
+                    Some((content_type, media)) => match examples::media_example(media, op.spec) {
+                        Some(payload) => Some(RequestBody {
+                            content_type: Some(content_type.clone()),
+                            payload,
+                            replacements: Vec::new(),
+                        }),
+                        None if body.required == Some(true) => {
+                            // 例がなければスキーマから最小のインスタンスを合成する
+                            let payload = match media.schema {
+                                Some(ref s) => {
+                                    warnings.push(format!(
+                                        "{}: no example for the request body, synthesized one from the schema in step '{}'",
+                                        op.label(),
+                                        step_id
+                                    ));
+                                    sample::synthesize(
+                                        &schema::resolve_schema(s, op.spec),
+                                        &sample::SampleOptions::default(),
+                                    )
+                                }
+                                None => {
+                                    warnings.push(format!(
+                                        "{}: no example for the request body, fill in step '{}'",
+                                        op.label(),
+                                        step_id
+                                    ));
+                                    json!({})
+                                }
+                            };
+                            Some(RequestBody {
+                                content_type: Some(content_type.clone()),
+                                payload,
+                                replacements: Vec::new(),
+                            })
+                        }
+                        None => None,
+                    },
+                    None => None,
+                }
+            }
+            _ => None,
+        };
+
+        let success_criteria = op.expected_status().map(|status| {
+            vec![SuccessCriteria {
+                context: "$statusCode".to_string(),
+                condition: "==".to_string(),
+                value: Some(json!(status)),
+                criteria_type: None,
+            }]
+        });
+
+        let (operation_id, operation_path) = match op.operation.operation_id.clone() {
+            Some(op_id) => (Some(op_id), None),
+            None => (None, Some(op.label())),
+        };
+
+        self.steps.push(Step {
+            step_id: step_id.clone(),
+            description: op.operation.summary.clone(),
+            operation_id,
+            operation_path,
+            workflow_id: None,
+            parameters,
+            request_body,
+            success_criteria,
+            on_success: None,
+            on_failure: None,
+            outputs: None,
+            extensions: Default::default(),
+        });
+
+        step_id
+    }
+
+    fn add_input(&mut self, param: &Parameter, spec: &Spec) {
+        let schema_type = param
+            .schema
+            .as_ref()
+            .map(|s| schema::resolve_schema(s, spec))
+            .and_then(|s| s.get("type").and_then(|t| t.as_str()).map(str::to_string))
+            .unwrap_or_else(|| "string".to_string());
+        self.inputs
+            .entry(param.name.clone())
+            .or_insert_with(|| json!({ "type": schema_type }));
+    }
+
+    fn set_outputs(&mut self, step_id: &str, outputs: Value) {
+        if let Some(step) = self.steps.iter_mut().find(|s| s.step_id == step_id) {
+            step.outputs = Some(outputs);
+        }
+    }
+
+    fn finish(self, workflow_id: String, summary: String) -> Workflow {
+        let inputs = (!self.inputs.is_empty()).then(|| {
+            json!({
+                "type": "object",
+                "properties": self.inputs,
+                "required": self.inputs.keys().collect::<Vec<_>>(),
+            })
+        });
+
+        Workflow {
+            workflow_id,
+            summary: Some(summary),
+            description: None,
+            inputs,
+            steps: self.steps,
+            success_criteria: None,
+            outputs: None,
+            extensions: IndexMap::new(),
+        }
+    }
+}
+
+/// Propose workflows for all the OpenAPI documents loaded in `resolver`
+pub fn generate_workflows(resolver: &OpenApiResolver) -> GenerateResult {
+    let operations = collect_operations(resolver);
+    let resources = crud_resources(&operations);
+
+    let mut result = GenerateResult::default();
+    let mut workflow_ids = HashSet::new();
+
+    for resource in &resources {
+        let workflow = crud_workflow(resource, &mut result.warnings);
+        let id = unique_id(&workflow.workflow_id, &mut workflow_ids);
+        result.workflows.push(Workflow {
+            workflow_id: id,
+            ..workflow
+        });
+    }
+
+    for op in &operations {
+        if let Some(workflow) = link_workflow(op, &operations, &resources, &mut result.warnings) {
+            let id = unique_id(&workflow.workflow_id, &mut workflow_ids);
+            result.workflows.push(Workflow {
+                workflow_id: id,
+                ..workflow
+            });
+        }
+    }
+
+    result
+}
+
+/// Add the workflows whose id is not used yet to `spec`
+///
+/// Existing workflows are never replaced, so hand-written workflows survive
+/// regenerating.
+pub fn merge_workflows(spec: &mut ArazzoSpec, workflows: Vec<Workflow>) -> MergeSummary {
+    let mut summary = MergeSummary::default();
+    for workflow in workflows {
+        if spec
+            .workflows
+            .iter()
+            .any(|w| w.workflow_id == workflow.workflow_id)
+        {
+            summary.skipped.push(workflow.workflow_id);
+        } else {
+            summary.added.push(workflow.workflow_id.clone());
+            spec.workflows.push(workflow);
+        }
+    }
+    summary
+}
+
+fn collect_operations(resolver: &OpenApiResolver) -> Vec<OperationInfo<'_>> {
+    let mut sources: Vec<(&String, &Spec)> = resolver.get_all_specs().iter().collect();
+    sources.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut operations = Vec::new();
+    for (_, spec) in sources {
+        for (path, item) in spec.paths.iter().flatten() {
+            let methods = [
+                ("POST", &item.post),
+                ("GET", &item.get),
+                ("PUT", &item.put),
+                ("PATCH", &item.patch),
+                ("DELETE", &item.delete),
+                ("HEAD", &item.head),
+                ("OPTIONS", &item.options),
+                ("TRACE", &item.trace),
+            ];
+            for (method, operation) in methods {
+                let Some(operation) = operation else {
+                    continue;
+                };
+
+                // 操作レベルの定義がパスレベルの同名パラメータを上書きする
+                let mut parameters: Vec<Parameter> = Vec::new();
+                for param in item.parameters.iter().chain(&operation.parameters) {
+                    let Ok(param) = param.resolve(spec) else {
+                        continue;
+                    };
+                    parameters.retain(|p| !(p.name == param.name && p.location == param.location));
+                    parameters.push(param);
+                }
+
+                operations.push(OperationInfo {
+                    spec,
+                    method,
+                    path,
+                    operation,
+                    parameters,
+                });
+            }
+        }
+    }
+    operations
+}
+
+/// Name of a `{param}` path segment
+/// Name of a parameter location as written in Arazzo and OpenAPI
+pub(crate) fn location_name(location: ParameterIn) -> &'static str {
+    match location {
+        ParameterIn::Path => "path",
+        ParameterIn::Query => "query",
+        ParameterIn::Header => "header",
+        ParameterIn::Cookie => "cookie",
+    }
+}
+
+fn path_param(segment: &str) -> Option<&str> {
+    segment.strip_prefix('{')?.strip_suffix('}')
+}
+
+fn crud_resources<'a, 'b>(operations: &'b [OperationInfo<'a>]) -> Vec<CrudResource<'a, 'b>> {
+    let find = |method: &str, path: &str| {
+        operations
+            .iter()
+            .find(|op| op.method == method && op.path == path)
+    };
+
+    let mut resources = Vec::new();
+    for create in operations.iter().filter(|op| op.method == "POST") {
+        let collection = create.path.trim_end_matches('/');
+        if collection
+            .rsplit('/')
+            .next()
+            .is_none_or(|last| path_param(last).is_some() || last.is_empty())
+        {
+            continue;
+        }
+
+        let item = operations.iter().find_map(|op| {
+            let rest = op.path.strip_prefix(collection)?.strip_prefix('/')?;
+            path_param(rest)
+                .filter(|_| !rest.contains('/'))
+                .map(|name| (op.path, name.to_string()))
+        });
+        let Some((item_path, item_param)) = item else {
+            continue;
+        };
+
+        let resource = CrudResource {
+            collection,
+            item_param,
+            create,
+            get: find("GET", item_path),
+            update: find("PUT", item_path).or_else(|| find("PATCH", item_path)),
+            list: find("GET", create.path),
+            delete: find("DELETE", item_path),
+        };
+        if resource.item_operations().next().is_some() {
+            resources.push(resource);
+        }
+    }
+    resources
+}
+
+/// Expression giving the id of the created resource
+fn created_id_expression(resource: &CrudResource<'_, '_>) -> String {
+    // リンクで宣言されていればそれを優先する
+    for (_, link) in resource.create.links() {
+        if !links_to_item(&link, resource) {
+            continue;
+        }
+        for (key, expr) in link_parameters(&link) {
+            if key == resource.item_param
+                && expr.starts_with("$response.")
+                && RuntimeExpression::parse(expr).is_some()
+            {
+                return expr.to_string();
+            }
+        }
+    }
+
+    let properties = resource
+        .create
+        .response_schema()
+        .map(|s| schema::property_names(&s))
+        .unwrap_or_default();
+    let field = [resource.item_param.as_str(), "id"]
+        .into_iter()
+        .find(|candidate| properties.iter().any(|p| p == candidate))
+        .unwrap_or("id");
+    format!("$response.body.{}", field)
+}
+
+fn links_to_item(link: &Link, resource: &CrudResource<'_, '_>) -> bool {
+    resource.item_operations().any(|op| link_matches(link, op))
+}
+
+fn crud_workflow(resource: &CrudResource<'_, '_>, warnings: &mut Vec<String>) -> Workflow {
+    let mut builder = WorkflowBuilder::default();
+
+    let create_id = builder.add_step(resource.create, &HashMap::new(), warnings);
+    builder.set_outputs(&create_id, json!({ "id": created_id_expression(resource) }));
+
+    let mut bindings = HashMap::new();
+    bindings.insert(
+        resource.item_param.clone(),
+        json!(format!("$steps.{}.outputs.id", create_id)),
+    );
+
+    for op in [
+        resource.get,
+        resource.update,
+        resource.list,
+        resource.delete,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        builder.add_step(op, &bindings, warnings);
+    }
+
+    let name = resource
+        .collection
+        .split('/')
+        .filter(|s| !s.is_empty() && path_param(s).is_none())
+        .collect::<Vec<_>>()
+        .join("-");
+    builder.finish(
+        format!("{}-crud", sanitize_id(&name)),
+        format!("CRUD lifecycle of {}", resource.collection),
+    )
+}
+
+fn link_parameters(link: &Link) -> Vec<(&str, &str)> {
+    let parameters = match link {
+        Link::Ref { parameters, .. } | Link::Id { parameters, .. } => parameters,
+    };
+    parameters
+        .iter()
+        .map(|(key, value)| {
+            // `path.id` のようにパラメータの場所で修飾されている場合がある
+            let name = ["path.", "query.", "header.", "cookie."]
+                .iter()
+                .find_map(|prefix| key.strip_prefix(prefix))
+                .unwrap_or(key);
+            (name, value.as_str())
+        })
+        .collect()
+}
+
+fn link_matches(link: &Link, op: &OperationInfo<'_>) -> bool {
+    match link {
+        Link::Id { operation_id, .. } => op.operation.operation_id.as_ref() == Some(operation_id),
+        Link::Ref { operation_ref, .. } => parse_operation_path(operation_ref)
+            .is_some_and(|(path, method)| path == op.path && method == op.method),
+    }
+}
+
+fn link_target<'r, 'a>(
+    link: &Link,
+    operations: &'r [OperationInfo<'a>],
+) -> Option<&'r OperationInfo<'a>> {
+    operations.iter().find(|op| link_matches(link, op))
+}
+
+/// Workflow calling `op` and then every operation its links point to
+fn link_workflow(
+    op: &OperationInfo<'_>,
+    operations: &[OperationInfo<'_>],
+    resources: &[CrudResource<'_, '_>],
+    warnings: &mut Vec<String>,
+) -> Option<Workflow> {
+    let covering = resources
+        .iter()
+        .find(|r| r.create.method == op.method && r.create.path == op.path);
+
+    let mut targets = Vec::new();
+    for (name, link) in op.links() {
+        match link_target(&link, operations) {
+            // CRUD ワークフローで既にカバーされているリンクは除く
+            Some(target) if covering.is_some_and(|r| r.contains(target)) => {}
+            Some(target) => targets.push((link, target)),
+            None => warnings.push(format!(
+                "{}: link '{}' points to an unknown operation",
+                op.label(),
+                name
+            )),
+        }
+    }
+    if targets.is_empty() {
+        return None;
+    }
+
+    let mut builder = WorkflowBuilder::default();
+    let source_id = builder.add_step(op, &HashMap::new(), warnings);
+    let source_params: HashMap<(String, String), Value> = builder.steps[0]
+        .parameters
+        .iter()
+        .map(|p| ((p.location.clone(), p.name.clone()), p.value.clone()))
+        .collect();
+
+    let mut outputs = serde_json::Map::new();
+    for (link, target) in targets {
+        let mut bindings = HashMap::new();
+        for (param, expr) in link_parameters(&link) {
+            let value = match RuntimeExpression::parse(expr) {
+                // `$request.path.id` などは元のステップの値をそのまま渡す
+                Some(RuntimeExpression::Request(source)) => {
+                    let key = match source {
+                        crate::models::expression::ExpressionSource::Path(name) => {
+                            ("path".to_string(), name)
+                        }
+                        crate::models::expression::ExpressionSource::Query(name) => {
+                            ("query".to_string(), name)
+                        }
+                        crate::models::expression::ExpressionSource::Header(name) => {
+                            ("header".to_string(), name)
+                        }
+                        crate::models::expression::ExpressionSource::Body(_) => {
+                            warnings.push(format!(
+                                "{}: link parameter '{}' uses the request body, not supported",
+                                op.label(),
+                                param
+                            ));
+                            continue;
+                        }
+                    };
+                    match source_params.get(&key) {
+                        Some(value) => value.clone(),
+                        None => continue,
+                    }
+                }
+                Some(_) => {
+                    let mut output = param.to_string();
+                    let mut n = 2;
+                    while outputs
+                        .get(&output)
+                        .is_some_and(|existing| existing.as_str() != Some(expr))
+                    {
+                        output = format!("{}{}", param, n);
+                        n += 1;
+                    }
+                    outputs.insert(output.clone(), json!(expr));
+                    json!(format!("$steps.{}.outputs.{}", source_id, output))
+                }
+                // 定数はそのまま渡す
+                None => json!(expr),
+            };
+            bindings.insert(param.to_string(), value);
+        }
+        builder.add_step(target, &bindings, warnings);
+    }
+
+    if !outputs.is_empty() {
+        builder.set_outputs(&source_id, Value::Object(outputs));
+    }
+
+    Some(builder.finish(
+        format!("{}-links", source_id),
+        format!("Follow the links of {}", op.label()),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn load_fixture() -> OpenApiResolver {
+        let mut resolver = OpenApiResolver::new("tests/fixtures/generate");
+        resolver
+            .load_spec(
+                "petstore",
+                Path::new("tests/fixtures/generate/openapi.yaml"),
+            )
+            .unwrap();
+        resolver
+    }
+
+    fn workflow<'a>(result: &'a GenerateResult, id: &str) -> &'a Workflow {
+        result
+            .workflows
+            .iter()
+            .find(|w| w.workflow_id == id)
+            .unwrap_or_else(|| panic!("workflow '{}' not generated", id))
+    }
+
+    #[test]
+    fn test_crud_lifecycle() {
+        let result = generate_workflows(&load_fixture());
+        let crud = workflow(&result, "pets-crud");
+
+        let step_ids: Vec<&str> = crud.steps.iter().map(|s| s.step_id.as_str()).collect();
+        assert_eq!(
+            step_ids,
+            vec!["createPet", "getPet", "updatePet", "listPets", "deletePet"]
+        );
+
+        let create = &crud.steps[0];
+        assert_eq!(create.outputs, Some(json!({"id": "$response.body.id"})));
+        assert_eq!(
+            create.request_body.as_ref().unwrap().payload,
+            json!({"name": "doggie", "status": "available"})
+        );
+        assert_eq!(
+            create.success_criteria.as_ref().unwrap()[0].value,
+            Some(json!(201))
+        );
+
+        // アイテムのパスパラメータは create の出力につながる
+        assert_eq!(
+            crud.steps[1].parameters[0].value,
+            json!("$steps.createPet.outputs.id")
+        );
+        // x-examples から更新ペイロードを取る
+        assert_eq!(
+            crud.steps[2].request_body.as_ref().unwrap().payload,
+            json!({"name": "rex", "status": "sold"})
+        );
+    }
+
+    #[test]
+    fn test_nested_resource_uses_inputs() {
+        let result = generate_workflows(&load_fixture());
+        let crud = workflow(&result, "owners-pets-crud");
+
+        assert_eq!(
+            crud.inputs.as_ref().unwrap()["properties"]["ownerId"],
+            json!({"type": "string"})
+        );
+        assert_eq!(crud.steps[0].parameters[0].value, json!("$inputs.ownerId"));
+        assert_eq!(
+            crud.steps[0].outputs,
+            Some(json!({"id": "$response.body#/petId"}))
+        );
+    }
+
+    #[test]
+    fn test_links_are_followed() {
+        let result = generate_workflows(&load_fixture());
+        let links = workflow(&result, "login-links");
+
+        assert_eq!(links.steps.len(), 2);
+        assert_eq!(
+            links.steps[0].outputs,
+            Some(json!({"userId": "$response.body#/user/id"}))
+        );
+        assert_eq!(links.steps[1].step_id, "getUser");
+        assert_eq!(
+            links.steps[1].parameters[0].value,
+            json!("$steps.login.outputs.userId")
+        );
+        assert!(result.warnings.iter().any(|w| w.contains("link 'missing'")));
+    }
+
+    #[test]
+    fn test_merge_keeps_existing_workflows() {
+        let result = generate_workflows(&load_fixture());
+        let mut spec: ArazzoSpec = serde_yaml::from_str(
+            "arazzo: 1.0.0\ninfo: {title: t, version: '1'}\nsourceDescriptions: []\nworkflows: []\n",
+        )
+        .unwrap();
+        let mut existing = result.workflows[0].clone();
+        existing.summary = Some("hand-written".to_string());
+        spec.workflows.push(existing);
+
+        let summary = merge_workflows(&mut spec, result.workflows.clone());
+
+        assert_eq!(
+            summary.skipped,
+            vec![result.workflows[0].workflow_id.clone()]
+        );
+        assert_eq!(summary.added.len(), result.workflows.len() - 1);
+        assert_eq!(spec.workflows[0].summary.as_deref(), Some("hand-written"));
+    }
+}
@@ -0,0 +1,924 @@
+//! Synthesize sample values from JSON schemas
+//!
+//! When an operation declares no example, a valid instance is built from its
+//! (ref-resolved) schema instead. [`SampleMode::Minimal`] produces the
+//! smallest instance (required properties only, lower bounds), while
+//! [`SampleMode::Maximal`] fills every property up to the upper bounds, and
+//! [`SampleMode::Random`] draws sizes and values anywhere within the bounds.
+//!
+//! Formats, enums, patterns, numeric and length bounds, `allOf` (merged) and
+//! `oneOf`/`anyOf` (one branch picked) are honoured. Every choice is drawn
+//! from a generator seeded by [`SampleOptions::seed`], so the same seed
+//! always yields the same instance.
+
+use super::schema::OperationSchemas;
+use crate::error::{HornetError, Result};
+use crate::loader::OpenApiResolver;
+use regex_syntax::hir::{Class, Hir, HirKind};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+
+/// Nesting depth after which only required properties are generated
+const MAX_DEPTH: usize = 8;
+/// Number of items of an array without `maxItems` in maximal mode
+const DEFAULT_MAX_ITEMS: u64 = 3;
+/// Upper limit of generated array items
+const ITEMS_CAP: u64 = 16;
+/// Length of a string without `maxLength` in maximal mode
+const DEFAULT_MAX_LENGTH: u64 = 32;
+/// Upper limit of generated string lengths
+const LENGTH_CAP: u64 = 256;
+/// Extra repetitions of unbounded pattern quantifiers (`*`, `+`) in maximal mode
+const PATTERN_REPEAT: u32 = 3;
+/// Number of attempts to generate a pattern match within the length bounds
+const PATTERN_ATTEMPTS: usize = 16;
+
+/// Which instance to synthesize
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SampleMode {
+    /// Required properties only, lower bounds
+    #[default]
+    Minimal,
+    /// Every property, upper bounds
+    Maximal,
+    /// Random subset of the optional properties, values within the bounds
+    Random,
+}
+
+/// Whether the instance is sent or received (`readOnly` / `writeOnly`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SampleDirection {
+    /// Skip `readOnly` properties
+    #[default]
+    Request,
+    /// Skip `writeOnly` properties
+    Response,
+}
+
+/// Options for [`synthesize`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SampleOptions {
+    pub mode: SampleMode,
+    pub seed: u64,
+    pub direction: SampleDirection,
+}
+
+/// Synthesize an instance of a (ref-resolved) JSON schema
+pub fn synthesize(schema: &Value, options: &SampleOptions) -> Value {
+    Synthesizer {
+        options: *options,
+        rng: Rng::new(options.seed),
+    }
+    .value(schema, 0)
+}
+
+/// Sample request of an operation
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationSample {
+    pub operation_id: String,
+    pub method: String,
+    pub path: String,
+    pub mode: SampleMode,
+    pub seed: u64,
+    pub parameters: Vec<ParameterSample>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+/// Sample value of a parameter
+#[derive(Debug, Clone, Serialize)]
+pub struct ParameterSample {
+    pub name: String,
+    #[serde(rename = "in")]
+    pub location: String,
+    pub value: Value,
+}
+
+/// Synthesize a request for the operation `operation_id`
+///
+/// Parameters and the request body are always synthesized from their schemas,
+/// ignoring declared examples. In minimal mode only required parameters are
+/// included.
+pub fn sample_operation(
+    resolver: &OpenApiResolver,
+    operation_id: &str,
+    options: &SampleOptions,
+) -> Result<OperationSample> {
+    let schemas = OperationSchemas::by_id(resolver, operation_id)
+        .ok_or_else(|| HornetError::OperationNotFound(operation_id.to_string()))?;
+
+    let mut synthesizer = Synthesizer {
+        options: *options,
+        rng: Rng::new(options.seed),
+    };
+
+    let parameters = schemas
+        .parameters
+        .iter()
+        .filter(|param| options.mode != SampleMode::Minimal || param.required)
+        .map(|param| ParameterSample {
+            name: param.name.clone(),
+            location: param.location.to_string(),
+            value: synthesizer.value(&param.schema, 0),
+        })
+        .collect();
+
+    let (content_type, body) = match schemas.request_body {
+        Some(ref body) => (
+            Some(body.content_type.clone()),
+            body.schema.as_ref().map(|s| synthesizer.value(s, 0)),
+        ),
+        None => (None, None),
+    };
+
+    Ok(OperationSample {
+        operation_id: operation_id.to_string(),
+        method: schemas.method,
+        path: schemas.path,
+        mode: options.mode,
+        seed: options.seed,
+        parameters,
+        content_type,
+        body,
+    })
+}
+
+/// Merge the members of `allOf` into the schema itself
+///
+/// Properties and `required` are unioned, numeric and length bounds take the
+/// stricter value, and other keywords keep the first value seen.
+pub fn merge_all_of(schema: &Value) -> Value {
+    let Some(obj) = schema.as_object() else {
+        return schema.clone();
+    };
+    let Some(members) = obj.get("allOf").and_then(|a| a.as_array()) else {
+        return schema.clone();
+    };
+
+    let mut merged = obj.clone();
+    merged.remove("allOf");
+    for member in members {
+        if let Value::Object(member) = merge_all_of(member) {
+            merge_into(&mut merged, member);
+        }
+    }
+    Value::Object(merged)
+}
+
+fn merge_into(target: &mut Map<String, Value>, source: Map<String, Value>) {
+    for (key, value) in source {
+        let Some(existing) = target.get_mut(&key) else {
+            target.insert(key, value);
+            continue;
+        };
+        match key.as_str() {
+            "properties" => {
+                if let (Value::Object(existing), Value::Object(props)) = (existing, value) {
+                    for (name, prop) in props {
+                        match existing.get_mut(&name) {
+                            Some(current) => {
+                                *current = merge_all_of(&serde_json::json!({
+                                    "allOf": [current.clone(), prop]
+                                }));
+                            }
+                            None => {
+                                existing.insert(name, prop);
+                            }
+                        }
+                    }
+                }
+            }
+            "required" => {
+                if let (Value::Array(existing), Value::Array(names)) = (existing, value) {
+                    for name in names {
+                        if !existing.contains(&name) {
+                            existing.push(name);
+                        }
+                    }
+                }
+            }
+            "minimum" | "exclusiveMinimum" | "minLength" | "minItems" | "minProperties" => {
+                if let (Some(a), Some(b)) = (existing.as_f64(), value.as_f64())
+                    && b > a
+                {
+                    *existing = value;
+                }
+            }
+            "maximum" | "exclusiveMaximum" | "maxLength" | "maxItems" | "maxProperties" => {
+                if let (Some(a), Some(b)) = (existing.as_f64(), value.as_f64())
+                    && b < a
+                {
+                    *existing = value;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Deterministic pseudo random numbers (splitmix64)
+#[derive(Debug)]
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Seeded from the clock, for sampling that need not be reproducible
+    pub(crate) fn from_time() -> Self {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        Self(nanos)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `[0, 1)`
+    pub(crate) fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform value in `lo..=hi`
+    fn between(&mut self, lo: u64, hi: u64) -> u64 {
+        if hi <= lo {
+            return lo;
+        }
+        lo + self.below(hi - lo + 1)
+    }
+
+    /// Uniform value in `0..n` (`n` > 0)
+    fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n.max(1)
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+        items.get(self.below(items.len() as u64) as usize)
+    }
+}
+
+struct Synthesizer {
+    options: SampleOptions,
+    rng: Rng,
+}
+
+impl Synthesizer {
+    fn maximal(&self, depth: usize) -> bool {
+        self.options.mode == SampleMode::Maximal && depth < MAX_DEPTH
+    }
+
+    fn random(&self, depth: usize) -> bool {
+        self.options.mode == SampleMode::Random && depth < MAX_DEPTH
+    }
+
+    fn value(&mut self, schema: &Value, depth: usize) -> Value {
+        let Some(obj) = schema.as_object() else {
+            return Value::Null;
+        };
+
+        if let Some(value) = obj.get("const") {
+            return value.clone();
+        }
+        if let Some(values) = obj.get("enum").and_then(|e| e.as_array())
+            && let Some(value) = self.rng.pick(values)
+        {
+            return value.clone();
+        }
+
+        if obj.contains_key("allOf") {
+            return self.value(&merge_all_of(schema), depth);
+        }
+        for key in ["oneOf", "anyOf"] {
+            if let Some(branches) = obj.get(key).and_then(|b| b.as_array())
+                && let Some(branch) = self.rng.pick(branches)
+            {
+                // 分岐の外側に書かれたキー（type, properties など）も分岐に合成する
+                let mut base = obj.clone();
+                base.remove(key);
+                let combined = merge_all_of(&serde_json::json!({
+                    "allOf": [Value::Object(base), branch.clone()]
+                }));
+                return self.value(&combined, depth);
+            }
+        }
+
+        match schema_type(obj).as_deref() {
+            Some("object") => self.object(obj, depth),
+            Some("array") => self.array(obj, depth),
+            Some("string") => Value::String(self.string(obj)),
+            Some("integer") => self.integer(obj),
+            Some("number") => self.number(obj),
+            Some("boolean") => Value::Bool(match self.options.mode {
+                SampleMode::Minimal => false,
+                SampleMode::Maximal => true,
+                SampleMode::Random => self.rng.below(2) == 1,
+            }),
+            _ => Value::Null,
+        }
+    }
+
+    fn object(&mut self, obj: &Map<String, Value>, depth: usize) -> Value {
+        let empty = Map::new();
+        let props = obj
+            .get("properties")
+            .and_then(|p| p.as_object())
+            .unwrap_or(&empty);
+        let required: Vec<&str> = obj
+            .get("required")
+            .and_then(|r| r.as_array())
+            .map(|r| r.iter().filter_map(|n| n.as_str()).collect())
+            .unwrap_or_default();
+        let min_properties = uint(obj, "minProperties").unwrap_or(0) as usize;
+        let max_properties = uint(obj, "maxProperties").map(|n| n as usize);
+        let skipped = match self.options.direction {
+            SampleDirection::Request => "readOnly",
+            SampleDirection::Response => "writeOnly",
+        };
+
+        let mut result = Map::new();
+        for (name, prop) in props {
+            if prop.get(skipped).and_then(|v| v.as_bool()) == Some(true) {
+                continue;
+            }
+            if required.contains(&name.as_str()) {
+                result.insert(name.clone(), self.value(prop, depth + 1));
+            }
+        }
+        // 任意プロパティは maximal（random なら抽選）か minProperties に届かない場合に追加する
+        for (name, prop) in props {
+            if result.contains_key(name)
+                || required.contains(&name.as_str())
+                || prop.get(skipped).and_then(|v| v.as_bool()) == Some(true)
+            {
+                continue;
+            }
+            if max_properties.is_some_and(|max| result.len() >= max) {
+                break;
+            }
+            let wanted = self.maximal(depth)
+                || (self.random(depth) && self.rng.below(2) == 0)
+                || result.len() < min_properties;
+            if wanted {
+                result.insert(name.clone(), self.value(prop, depth + 1));
+            }
+        }
+
+        // additionalProperties で不足分を埋める
+        let additional = match obj.get("additionalProperties") {
+            Some(Value::Bool(false)) => None,
+            Some(Value::Object(schema)) => Some(Value::Object(schema.clone())),
+            _ => Some(Value::Object(Map::new())),
+        };
+        if let Some(additional) = additional {
+            let target = if self.maximal(depth) && props.is_empty() {
+                min_properties.max(1)
+            } else {
+                min_properties
+            };
+            let target = max_properties.map_or(target, |max| target.min(max));
+            let mut index = 1;
+            while result.len() < target {
+                let key = format!("key{index}");
+                index += 1;
+                if !result.contains_key(&key) {
+                    let value = self.value(&additional, depth + 1);
+                    result.insert(key, value);
+                }
+            }
+        }
+
+        Value::Object(result)
+    }
+
+    fn array(&mut self, obj: &Map<String, Value>, depth: usize) -> Value {
+        let min_items = uint(obj, "minItems").unwrap_or(0);
+        let upper = uint(obj, "maxItems")
+            .unwrap_or(DEFAULT_MAX_ITEMS)
+            .min(ITEMS_CAP)
+            .max(min_items);
+        let count = if self.maximal(depth) {
+            upper
+        } else if self.random(depth) {
+            self.rng.between(min_items, upper)
+        } else {
+            min_items
+        };
+        let unique = obj.get("uniqueItems").and_then(|u| u.as_bool()) == Some(true);
+        let items_schema = obj
+            .get("items")
+            .cloned()
+            .unwrap_or(Value::Object(Map::new()));
+
+        let mut items: Vec<Value> = Vec::new();
+        let mut attempts = 0;
+        while (items.len() as u64) < count && attempts < count * 4 {
+            attempts += 1;
+            let item = self.value(&items_schema, depth + 1);
+            if unique && items.contains(&item) {
+                continue;
+            }
+            items.push(item);
+        }
+        Value::Array(items)
+    }
+
+    fn string(&mut self, obj: &Map<String, Value>) -> String {
+        let min_length = uint(obj, "minLength").unwrap_or(0);
+        let max_length = uint(obj, "maxLength");
+        let fits = |s: &str| {
+            let len = s.chars().count() as u64;
+            len >= min_length && max_length.is_none_or(|max| len <= max)
+        };
+
+        if let Some(pattern) = obj.get("pattern").and_then(|p| p.as_str())
+            && let Some(value) = self.pattern(pattern, &fits)
+        {
+            return value;
+        }
+        if let Some(value) = obj
+            .get("format")
+            .and_then(|f| f.as_str())
+            .and_then(|format| self.format(format))
+            .filter(|value| fits(value))
+        {
+            return value;
+        }
+
+        let upper = max_length
+            .unwrap_or(DEFAULT_MAX_LENGTH)
+            .min(LENGTH_CAP)
+            .max(min_length);
+        let length = match self.options.mode {
+            SampleMode::Maximal => upper,
+            SampleMode::Random => self.rng.between(min_length, upper),
+            SampleMode::Minimal => min_length.max(1).min(max_length.unwrap_or(u64::MAX)),
+        };
+        (0..length)
+            .map(|_| (b'a' + self.rng.below(26) as u8) as char)
+            .collect()
+    }
+
+    /// A string matching `pattern`, preferring one that `fits` the length bounds
+    fn pattern(&mut self, pattern: &str, fits: &dyn Fn(&str) -> bool) -> Option<String> {
+        let hir = regex_syntax::Parser::new().parse(pattern).ok()?;
+        let regex = regex::Regex::new(pattern).ok()?;
+
+        let mut fallback = None;
+        for _ in 0..PATTERN_ATTEMPTS {
+            let mut out = String::new();
+            self.hir(&hir, &mut out);
+            if !regex.is_match(&out) {
+                continue;
+            }
+            if fits(&out) {
+                return Some(out);
+            }
+            fallback.get_or_insert(out);
+        }
+        fallback
+    }
+
+    fn hir(&mut self, hir: &Hir, out: &mut String) {
+        match hir.kind() {
+            HirKind::Empty | HirKind::Look(_) => {}
+            HirKind::Literal(literal) => out.push_str(&String::from_utf8_lossy(&literal.0)),
+            HirKind::Class(Class::Unicode(class)) => {
+                // 表示可能な ASCII を優先する
+                let ranges: Vec<(u32, u32)> = class
+                    .ranges()
+                    .iter()
+                    .map(|r| (r.start() as u32, r.end() as u32))
+                    .collect();
+                if let Some(c) = self.pick_char(&ranges) {
+                    out.push(c);
+                }
+            }
+            HirKind::Class(Class::Bytes(class)) => {
+                let ranges: Vec<(u32, u32)> = class
+                    .ranges()
+                    .iter()
+                    .map(|r| (r.start() as u32, r.end() as u32))
+                    .collect();
+                if let Some(c) = self.pick_char(&ranges) {
+                    out.push(c);
+                }
+            }
+            HirKind::Repetition(rep) => {
+                let upper = rep
+                    .max
+                    .unwrap_or(rep.min + PATTERN_REPEAT)
+                    .min(rep.min + PATTERN_REPEAT);
+                let count = match self.options.mode {
+                    SampleMode::Maximal => upper,
+                    SampleMode::Random => {
+                        self.rng.between(u64::from(rep.min), u64::from(upper)) as u32
+                    }
+                    SampleMode::Minimal => rep.min,
+                };
+                for _ in 0..count {
+                    self.hir(&rep.sub, out);
+                }
+            }
+            HirKind::Capture(capture) => self.hir(&capture.sub, out),
+            HirKind::Concat(items) => {
+                for item in items {
+                    self.hir(item, out);
+                }
+            }
+            HirKind::Alternation(branches) => {
+                if let Some(branch) = self.rng.pick(branches) {
+                    self.hir(branch, out);
+                }
+            }
+        }
+    }
+
+    fn pick_char(&mut self, ranges: &[(u32, u32)]) -> Option<char> {
+        let printable: Vec<(u32, u32)> = ranges
+            .iter()
+            .filter_map(|&(start, end)| {
+                let (start, end) = (start.max(0x21), end.min(0x7E));
+                (start <= end).then_some((start, end))
+            })
+            .collect();
+        let candidates = if printable.is_empty() {
+            ranges
+        } else {
+            &printable
+        };
+        let &(start, end) = self.rng.pick(candidates)?;
+        let code = start + self.rng.below(u64::from(end - start) + 1) as u32;
+        char::from_u32(code).or_else(|| char::from_u32(start))
+    }
+
+    fn format(&mut self, format: &str) -> Option<String> {
+        let n = self.rng.below(1000);
+        let value = match format {
+            "date-time" => format!("{}T{}Z", self.date(), self.time()),
+            "date" => self.date(),
+            "time" => format!("{}Z", self.time()),
+            "email" | "idn-email" => format!("user{n}@example.com"),
+            "uri" | "url" | "iri" => format!("https://example.com/items/{n}"),
+            "uri-reference" | "iri-reference" => format!("/items/{n}"),
+            "hostname" | "idn-hostname" => format!("host{n}.example.com"),
+            "ipv4" => format!("192.0.2.{}", n % 256),
+            "ipv6" => format!("2001:db8::{:x}", n),
+            "uuid" => {
+                let (a, b) = (self.rng.next_u64(), self.rng.next_u64());
+                // RFC 4122 バージョン4 / variant 10xx
+                let a = (a & 0xFFFF_FFFF_FFFF_0FFF) | 0x0000_0000_0000_4000;
+                let b = (b & 0x3FFF_FFFF_FFFF_FFFF) | 0x8000_0000_0000_0000;
+                format!(
+                    "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+                    a >> 32,
+                    (a >> 16) & 0xFFFF,
+                    a & 0xFFFF,
+                    b >> 48,
+                    b & 0xFFFF_FFFF_FFFF
+                )
+            }
+            "byte" => "c2FtcGxl".to_string(),
+            "binary" => "sample".to_string(),
+            "password" => format!("Secret{n}!"),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    fn date(&mut self) -> String {
+        format!(
+            "{}-{:02}-{:02}",
+            2020 + self.rng.below(6),
+            1 + self.rng.below(12),
+            1 + self.rng.below(28)
+        )
+    }
+
+    fn time(&mut self) -> String {
+        format!(
+            "{:02}:{:02}:{:02}",
+            self.rng.below(24),
+            self.rng.below(60),
+            self.rng.below(60)
+        )
+    }
+
+    fn integer(&mut self, obj: &Map<String, Value>) -> Value {
+        let (format_min, format_max) = match obj.get("format").and_then(|f| f.as_str()) {
+            Some("int32") => (f64::from(i32::MIN), f64::from(i32::MAX)),
+            _ => (i64::MIN as f64, i64::MAX as f64),
+        };
+        let (min, max) = bounds(obj);
+        let mut lo = min.map_or(format_min, |(v, exclusive)| {
+            if exclusive { v.floor() + 1.0 } else { v.ceil() }
+        });
+        let mut hi = max.map_or(format_max, |(v, exclusive)| {
+            if exclusive { v.ceil() - 1.0 } else { v.floor() }
+        });
+        lo = lo.max(format_min);
+        hi = hi.min(format_max);
+
+        let multiple = obj
+            .get("multipleOf")
+            .and_then(|m| m.as_f64())
+            .filter(|m| *m >= 1.0 && m.fract() == 0.0);
+        let value = self.pick_number(lo, hi, min.is_some(), max.is_some(), multiple);
+        Value::Number(Number::from(value as i64))
+    }
+
+    fn number(&mut self, obj: &Map<String, Value>) -> Value {
+        let (min, max) = bounds(obj);
+        let multiple = obj
+            .get("multipleOf")
+            .and_then(|m| m.as_f64())
+            .filter(|m| *m > 0.0);
+        // 排他的な境界はステップ（multipleOf か範囲の一部）だけ内側に寄せる
+        let span = match (min, max) {
+            (Some((a, _)), Some((b, _))) => (b - a).abs(),
+            _ => 2.0,
+        };
+        let step = multiple.unwrap_or((span / 4.0).min(0.5));
+        let lo = min.map_or(
+            f64::MIN,
+            |(v, exclusive)| if exclusive { v + step } else { v },
+        );
+        let hi = max.map_or(
+            f64::MAX,
+            |(v, exclusive)| if exclusive { v - step } else { v },
+        );
+
+        let value = self.pick_number(lo, hi, min.is_some(), max.is_some(), multiple);
+        Number::from_f64(value)
+            .map(Value::Number)
+            .unwrap_or(Value::Null)
+    }
+
+    /// Lower bound in minimal mode, upper bound in maximal mode, anywhere in
+    /// between in random mode, aligned to `multiple`
+    fn pick_number(
+        &mut self,
+        lo: f64,
+        hi: f64,
+        has_min: bool,
+        has_max: bool,
+        multiple: Option<f64>,
+    ) -> f64 {
+        let target = match self.options.mode {
+            SampleMode::Minimal if has_min => lo,
+            SampleMode::Maximal if has_max => hi,
+            // 境界がない側は 0 から少し離れた値にする
+            SampleMode::Minimal => 0f64.min(hi),
+            SampleMode::Maximal => lo.max(0.0) + 1.0 + self.rng.below(100) as f64,
+            SampleMode::Random => {
+                let fraction = self.rng.below(1 << 20) as f64 / (1 << 20) as f64;
+                match (has_min, has_max) {
+                    (true, true) => lo + (hi - lo) * fraction,
+                    (true, false) => lo + 1000.0 * fraction,
+                    (false, true) => hi - 1000.0 * fraction,
+                    (false, false) => 2000.0 * fraction - 1000.0,
+                }
+            }
+        };
+        let target = target.clamp(lo, hi.max(lo));
+
+        match multiple {
+            Some(m) => {
+                let (up, down) = ((target / m).ceil() * m, (target / m).floor() * m);
+                let candidates = if self.options.mode == SampleMode::Maximal {
+                    [down, up]
+                } else {
+                    [up, down]
+                };
+                candidates
+                    .into_iter()
+                    .find(|aligned| (lo..=hi).contains(aligned))
+                    .unwrap_or(target)
+            }
+            None => target,
+        }
+    }
+}
+
+/// Type of a schema; the first non-null entry of a `type` array, or inferred from keywords
+pub(crate) fn schema_type(obj: &Map<String, Value>) -> Option<String> {
+    match obj.get("type") {
+        Some(Value::String(t)) => return Some(t.clone()),
+        Some(Value::Array(types)) => {
+            return types
+                .iter()
+                .filter_map(|t| t.as_str())
+                .find(|t| *t != "null")
+                .or(Some("null"))
+                .map(str::to_string);
+        }
+        _ => {}
+    }
+
+    let inferred = if obj.contains_key("properties") || obj.contains_key("additionalProperties") {
+        "object"
+    } else if obj.contains_key("items") {
+        "array"
+    } else if ["pattern", "format", "minLength", "maxLength"]
+        .iter()
+        .any(|k| obj.contains_key(*k))
+    {
+        "string"
+    } else if ["minimum", "maximum", "multipleOf"]
+        .iter()
+        .any(|k| obj.contains_key(*k))
+    {
+        "number"
+    } else {
+        return None;
+    };
+    Some(inferred.to_string())
+}
+
+/// Lower and upper bounds with exclusiveness (OpenAPI 3.0 booleans or 3.1 numbers)
+pub(crate) type Bound = Option<(f64, bool)>;
+
+pub(crate) fn bounds(obj: &Map<String, Value>) -> (Bound, Bound) {
+    let bound = |inclusive: &str, exclusive: &str| match obj.get(exclusive) {
+        Some(Value::Number(n)) => n.as_f64().map(|v| (v, true)),
+        Some(Value::Bool(flag)) => obj
+            .get(inclusive)
+            .and_then(|v| v.as_f64())
+            .map(|v| (v, *flag)),
+        _ => obj
+            .get(inclusive)
+            .and_then(|v| v.as_f64())
+            .map(|v| (v, false)),
+    };
+    (
+        bound("minimum", "exclusiveMinimum"),
+        bound("maximum", "exclusiveMaximum"),
+    )
+}
+
+fn uint(obj: &Map<String, Value>, key: &str) -> Option<u64> {
+    obj.get(key).and_then(|v| v.as_u64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn options(mode: SampleMode, seed: u64) -> SampleOptions {
+        SampleOptions {
+            mode,
+            seed,
+            direction: SampleDirection::Request,
+        }
+    }
+
+    fn pet_schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["name", "tags"],
+            "properties": {
+                "id": {"type": "integer", "readOnly": true},
+                "name": {"type": "string", "minLength": 3, "maxLength": 8},
+                "status": {"type": "string", "enum": ["available", "pending", "sold"]},
+                "tags": {"type": "array", "minItems": 1, "maxItems": 2, "items": {"type": "string"}},
+                "age": {"type": "integer", "minimum": 1, "maximum": 30, "multipleOf": 4},
+                "weight": {"type": "number", "exclusiveMinimum": 0, "exclusiveMaximum": 10}
+            }
+        })
+    }
+
+    #[test]
+    fn test_minimal_and_maximal_objects() {
+        let minimal = synthesize(&pet_schema(), &options(SampleMode::Minimal, 1));
+        let obj = minimal.as_object().unwrap();
+        assert_eq!(obj.keys().collect::<Vec<_>>(), vec!["name", "tags"]);
+        assert_eq!(obj["name"].as_str().unwrap().len(), 3);
+        assert_eq!(obj["tags"].as_array().unwrap().len(), 1);
+
+        let maximal = synthesize(&pet_schema(), &options(SampleMode::Maximal, 1));
+        let obj = maximal.as_object().unwrap();
+        // readOnly のプロパティはリクエストに含めない
+        assert!(!obj.contains_key("id"));
+        assert_eq!(obj["name"].as_str().unwrap().len(), 8);
+        assert_eq!(obj["tags"].as_array().unwrap().len(), 2);
+        assert_eq!(obj["age"], json!(28));
+        let weight = obj["weight"].as_f64().unwrap();
+        assert!(weight > 0.0 && weight < 10.0, "weight = {weight}");
+        assert!(
+            ["available", "pending", "sold"].contains(&obj["status"].as_str().unwrap()),
+            "status = {}",
+            obj["status"]
+        );
+    }
+
+    #[test]
+    fn test_formats_and_patterns() {
+        let schema = json!({
+            "type": "object",
+            "required": ["id", "email", "createdAt", "code", "ip"],
+            "properties": {
+                "id": {"type": "string", "format": "uuid"},
+                "email": {"type": "string", "format": "email"},
+                "createdAt": {"type": "string", "format": "date-time"},
+                "code": {"type": "string", "pattern": "^[A-Z]{3}-\\d{2,4}$"},
+                "ip": {"type": "string", "format": "ipv4"}
+            }
+        });
+
+        let uuid = regex::Regex::new(
+            "^[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$",
+        )
+        .unwrap();
+        let date_time = regex::Regex::new(r"^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z$").unwrap();
+        let code = regex::Regex::new(r"^[A-Z]{3}-\d{2,4}$").unwrap();
+
+        for mode in [SampleMode::Minimal, SampleMode::Maximal] {
+            let value = synthesize(&schema, &options(mode, 7));
+            assert!(uuid.is_match(value["id"].as_str().unwrap()), "{value}");
+            assert!(value["email"].as_str().unwrap().contains('@'));
+            assert!(date_time.is_match(value["createdAt"].as_str().unwrap()));
+            assert!(code.is_match(value["code"].as_str().unwrap()), "{value}");
+            assert!(value["ip"].as_str().unwrap().starts_with("192.0.2."));
+        }
+    }
+
+    #[test]
+    fn test_composition() {
+        let schema = json!({
+            "allOf": [
+                {"type": "object", "required": ["kind"], "properties": {"kind": {"type": "string"}}},
+                {"required": ["size"], "properties": {"size": {"type": "integer", "minimum": 5}}}
+            ]
+        });
+        let value = synthesize(&schema, &options(SampleMode::Minimal, 0));
+        assert_eq!(value["size"], json!(5));
+        assert!(value["kind"].is_string());
+
+        let schema = json!({
+            "type": "object",
+            "required": ["pet"],
+            "properties": {
+                "pet": {"oneOf": [
+                    {"type": "object", "required": ["bark"], "properties": {"bark": {"type": "boolean"}}},
+                    {"type": "object", "required": ["meow"], "properties": {"meow": {"type": "boolean"}}}
+                ]}
+            }
+        });
+        let picked: Vec<Value> = (0..16)
+            .map(|seed| synthesize(&schema, &options(SampleMode::Minimal, seed)))
+            .collect();
+        assert!(picked.iter().all(|v| {
+            let pet = v["pet"].as_object().unwrap();
+            pet.len() == 1 && (pet.contains_key("bark") || pet.contains_key("meow"))
+        }));
+        assert!(picked.iter().any(|v| v["pet"].get("bark").is_some()));
+        assert!(picked.iter().any(|v| v["pet"].get("meow").is_some()));
+    }
+
+    #[test]
+    fn test_random_stays_within_bounds() {
+        for seed in 0..32 {
+            let value = synthesize(&pet_schema(), &options(SampleMode::Random, seed));
+            let obj = value.as_object().unwrap();
+            let name = obj["name"].as_str().unwrap().len();
+            assert!((3..=8).contains(&name), "{value}");
+            if let Some(age) = obj.get("age") {
+                let age = age.as_i64().unwrap();
+                assert!((1..=30).contains(&age) && age % 4 == 0, "{value}");
+            }
+            if let Some(weight) = obj.get("weight") {
+                let weight = weight.as_f64().unwrap();
+                assert!(weight > 0.0 && weight < 10.0, "{value}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_deterministic_with_seed() {
+        let schema = json!({
+            "type": "array",
+            "maxItems": 3,
+            "items": {"type": "string", "format": "uuid"}
+        });
+        let a = synthesize(&schema, &options(SampleMode::Maximal, 42));
+        let b = synthesize(&schema, &options(SampleMode::Maximal, 42));
+        let c = synthesize(&schema, &options(SampleMode::Maximal, 43));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+}
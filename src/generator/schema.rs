@@ -0,0 +1,585 @@
+//! JSON view of OpenAPI schemas with component references inlined
+
+use super::location_name;
+use crate::loader::{OpenApiResolver, OperationRef};
+use crate::payload::BodyEncoding;
+use oas3::spec::{MediaType, ObjectOrReference, ObjectSchema, Operation, Parameter, Spec};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+const SCHEMA_REF_PREFIX: &str = "#/components/schemas/";
+
+/// Resolve a schema into plain JSON with `#/components/schemas/*` references inlined
+///
+/// Recursive references are replaced by an empty schema (`{}`) so that the
+/// result is always finite.
+pub fn resolve_schema(schema: &ObjectOrReference<ObjectSchema>, spec: &Spec) -> Value {
+    let components = spec
+        .components
+        .as_ref()
+        .and_then(|c| serde_json::to_value(&c.schemas).ok())
+        .unwrap_or(Value::Null);
+    let schema = serde_json::to_value(schema).unwrap_or(Value::Null);
+
+    inline_refs(&schema, &components, &mut Vec::new())
+}
+
+fn inline_refs(value: &Value, components: &Value, visiting: &mut Vec<String>) -> Value {
+    match value {
+        Value::Object(obj) => {
+            if let Some(name) = obj
+                .get("$ref")
+                .and_then(|r| r.as_str())
+                .and_then(|r| r.strip_prefix(SCHEMA_REF_PREFIX))
+            {
+                if visiting.iter().any(|v| v == name) {
+                    return Value::Object(Default::default());
+                }
+                let Some(target) = components.get(name) else {
+                    return value.clone();
+                };
+
+                visiting.push(name.to_string());
+                let mut resolved = inline_refs(target, components, visiting);
+                visiting.pop();
+
+                // $ref の横に書かれたキー（description など）を残す
+                if let Value::Object(ref mut resolved_obj) = resolved {
+                    for (k, v) in obj.iter().filter(|(k, _)| k.as_str() != "$ref") {
+                        resolved_obj.insert(k.clone(), inline_refs(v, components, visiting));
+                    }
+                }
+                return resolved;
+            }
+
+            Value::Object(
+                obj.iter()
+                    .map(|(k, v)| (k.clone(), inline_refs(v, components, visiting)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|v| inline_refs(v, components, visiting))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Schemas of an operation's parameters, request body and responses
+#[derive(Debug, Clone)]
+pub struct OperationSchemas {
+    pub operation_id: Option<String>,
+    /// HTTP method (upper case)
+    pub method: String,
+    /// Path template
+    pub path: String,
+    /// Path item and operation parameters (operation parameters win)
+    pub parameters: Vec<ParameterSchema>,
+    pub request_body: Option<BodySchema>,
+    /// Responses in declaration order (`200`, `4XX`, `default`)
+    pub responses: Vec<ResponseSchema>,
+}
+
+/// Schema of a parameter
+#[derive(Debug, Clone)]
+pub struct ParameterSchema {
+    pub name: String,
+    /// `path`, `query`, `header` or `cookie`
+    pub location: &'static str,
+    pub required: bool,
+    /// Resolved schema (`{}` when none is declared)
+    pub schema: Value,
+}
+
+/// Schema of a request or response body (JSON media type preferred)
+#[derive(Debug, Clone)]
+pub struct BodySchema {
+    pub content_type: String,
+    pub required: bool,
+    pub schema: Option<Value>,
+    /// The declared media type (for examples)
+    pub media: MediaType,
+}
+
+/// A declared response
+#[derive(Debug, Clone)]
+pub struct ResponseSchema {
+    /// Status key as declared (`200`, `4XX`, `default`)
+    pub status: String,
+    pub body: Option<BodySchema>,
+}
+
+impl OperationSchemas {
+    /// Look up an operation by id
+    pub fn by_id(resolver: &OpenApiResolver, operation_id: &str) -> Option<Self> {
+        let (op_ref, operation) = resolver.find_operation_with_details(operation_id)?;
+        Self::from_ref(resolver, op_ref, &operation)
+    }
+
+    /// Look up an operation by method and path template
+    pub fn by_path(resolver: &OpenApiResolver, method: &str, path: &str) -> Option<Self> {
+        let (op_ref, operation) = resolver.find_operation_by_path_with_details(path, method)?;
+        Self::from_ref(resolver, op_ref, &operation)
+    }
+
+    fn from_ref(
+        resolver: &OpenApiResolver,
+        op_ref: OperationRef,
+        operation: &Operation,
+    ) -> Option<Self> {
+        let spec = resolver.get_spec(&op_ref.source_name)?;
+        Some(Self::new(spec, op_ref.method, op_ref.path, operation))
+    }
+
+    /// Collect the schemas of `operation` found at `path` in `spec`
+    pub fn new(spec: &Spec, method: String, path: String, operation: &Operation) -> Self {
+        let path_params = spec
+            .paths
+            .as_ref()
+            .and_then(|paths| paths.get(&path))
+            .map(|item| item.parameters.clone())
+            .unwrap_or_default();
+        let mut params: Vec<Parameter> = Vec::new();
+        for param in path_params.iter().chain(operation.parameters.iter()) {
+            let Ok(param) = param.resolve(spec) else {
+                continue;
+            };
+            params.retain(|p| !(p.name == param.name && p.location == param.location));
+            params.push(param);
+        }
+        let parameters = params
+            .iter()
+            .map(|param| ParameterSchema {
+                name: param.name.clone(),
+                location: location_name(param.location),
+                required: param.required == Some(true)
+                    || param.location == oas3::spec::ParameterIn::Path,
+                schema: param
+                    .schema
+                    .as_ref()
+                    .or_else(|| {
+                        param
+                            .content
+                            .as_ref()
+                            .and_then(|content| content.values().next())
+                            .and_then(|media| media.schema.as_ref())
+                    })
+                    .map(|schema| resolve_schema(schema, spec))
+                    .unwrap_or(Value::Object(Default::default())),
+            })
+            .collect();
+
+        let request_body = match operation.request_body(spec) {
+            Ok(Some(body)) => {
+                body_schema(&body.content, spec).map(|(content_type, media, schema)| BodySchema {
+                    content_type,
+                    required: body.required == Some(true),
+                    schema,
+                    media,
+                })
+            }
+            _ => None,
+        };
+
+        let responses = operation
+            .responses(spec)
+            .into_iter()
+            .map(|(status, response)| ResponseSchema {
+                status,
+                body: body_schema(&response.content, spec).map(|(content_type, media, schema)| {
+                    BodySchema {
+                        content_type,
+                        required: true,
+                        schema,
+                        media,
+                    }
+                }),
+            })
+            .collect();
+
+        Self {
+            operation_id: operation.operation_id.clone(),
+            method,
+            path,
+            parameters,
+            request_body,
+            responses,
+        }
+    }
+
+    /// The response declared for a status code: exact match, then `NXX`, then `default`
+    pub fn response(&self, status: u16) -> Option<&ResponseSchema> {
+        let exact = status.to_string();
+        let range = format!("{}XX", status / 100);
+        self.responses
+            .iter()
+            .find(|r| r.status == exact)
+            .or_else(|| {
+                self.responses
+                    .iter()
+                    .find(|r| r.status.eq_ignore_ascii_case(&range))
+            })
+            .or_else(|| self.responses.iter().find(|r| r.status == "default"))
+    }
+
+    /// Declared status codes (numeric keys only) in ascending order
+    pub fn status_codes(&self) -> Vec<u16> {
+        let mut codes: Vec<u16> = self
+            .responses
+            .iter()
+            .filter_map(|r| r.status.parse().ok())
+            .collect();
+        codes.sort_unstable();
+        codes
+    }
+}
+
+/// Pick the JSON media type of a content map (or the first one)
+fn body_schema(
+    content: &BTreeMap<String, MediaType>,
+    spec: &Spec,
+) -> Option<(String, MediaType, Option<Value>)> {
+    let (content_type, media) = content
+        .iter()
+        .find(|(content_type, _)| {
+            BodyEncoding::from_content_type(Some(content_type)) == BodyEncoding::Json
+        })
+        .or_else(|| content.iter().next())?;
+    let schema = media.schema.as_ref().map(|s| resolve_schema(s, spec));
+    Some((content_type.clone(), media.clone(), schema))
+}
+
+/// Names of the top-level properties of an object schema (including `allOf` members)
+pub fn property_names(schema: &Value) -> Vec<String> {
+    let mut names: Vec<String> = schema
+        .get("properties")
+        .and_then(|p| p.as_object())
+        .map(|p| p.keys().cloned().collect())
+        .unwrap_or_default();
+
+    for member in schema
+        .get("allOf")
+        .and_then(|a| a.as_array())
+        .into_iter()
+        .flatten()
+    {
+        for name in property_names(member) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+/// Check an instance against a (ref-resolved) JSON schema
+///
+/// Returns one message per violation, prefixed with the JSON Pointer of the
+/// offending value. `format` is treated as an annotation and not checked.
+/// Required properties marked `readOnly` or `writeOnly` may be absent, since
+/// they only appear in one direction.
+pub fn validate_instance(schema: &Value, instance: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_at(schema, instance, "", &mut errors);
+    errors
+}
+
+fn validate_at(schema: &Value, instance: &Value, path: &str, errors: &mut Vec<String>) {
+    let Some(obj) = schema.as_object() else {
+        if schema == &Value::Bool(false) {
+            errors.push(format!("#{path}: no value is allowed here"));
+        }
+        return;
+    };
+    let mut fail = |message: String| errors.push(format!("#{path}: {message}"));
+
+    if instance.is_null() && obj.get("nullable").and_then(|n| n.as_bool()) == Some(true) {
+        return;
+    }
+    let types: Vec<&str> = match obj.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(|t| t.as_str()).collect(),
+        _ => Vec::new(),
+    };
+    if !types.is_empty() && !types.iter().any(|t| has_type(instance, t)) {
+        fail(format!(
+            "expected {}, got {}",
+            types.join(" or "),
+            type_name(instance)
+        ));
+        return;
+    }
+
+    if let Some(expected) = obj.get("const")
+        && expected != instance
+    {
+        fail(format!("expected {expected}"));
+    }
+    if let Some(values) = obj.get("enum").and_then(|e| e.as_array())
+        && !values.contains(instance)
+    {
+        fail(format!("{instance} is not one of the allowed values"));
+    }
+
+    match instance {
+        Value::String(s) => {
+            let len = s.chars().count() as u64;
+            if let Some(min) = obj.get("minLength").and_then(|v| v.as_u64())
+                && len < min
+            {
+                fail(format!("length {len} is shorter than {min}"));
+            }
+            if let Some(max) = obj.get("maxLength").and_then(|v| v.as_u64())
+                && len > max
+            {
+                fail(format!("length {len} is longer than {max}"));
+            }
+            if let Some(pattern) = obj.get("pattern").and_then(|p| p.as_str())
+                && let Ok(re) = regex::Regex::new(pattern)
+                && !re.is_match(s)
+            {
+                fail(format!("does not match pattern {pattern}"));
+            }
+        }
+        Value::Number(n) => {
+            let value = n.as_f64().unwrap_or_default();
+            let exclusive = |key: &str| obj.get(key).and_then(|v| v.as_bool()) == Some(true);
+            if let Some(min) = obj.get("minimum").and_then(|v| v.as_f64()) {
+                if exclusive("exclusiveMinimum") && value <= min {
+                    fail(format!("{value} is not greater than {min}"));
+                } else if value < min {
+                    fail(format!("{value} is less than {min}"));
+                }
+            }
+            if let Some(min) = obj.get("exclusiveMinimum").and_then(|v| v.as_f64())
+                && value <= min
+            {
+                fail(format!("{value} is not greater than {min}"));
+            }
+            if let Some(max) = obj.get("maximum").and_then(|v| v.as_f64()) {
+                if exclusive("exclusiveMaximum") && value >= max {
+                    fail(format!("{value} is not less than {max}"));
+                } else if value > max {
+                    fail(format!("{value} is greater than {max}"));
+                }
+            }
+            if let Some(max) = obj.get("exclusiveMaximum").and_then(|v| v.as_f64())
+                && value >= max
+            {
+                fail(format!("{value} is not less than {max}"));
+            }
+            if let Some(multiple) = obj.get("multipleOf").and_then(|v| v.as_f64())
+                && multiple > 0.0
+            {
+                let quotient = value / multiple;
+                if (quotient - quotient.round()).abs() > 1e-9 {
+                    fail(format!("{value} is not a multiple of {multiple}"));
+                }
+            }
+        }
+        Value::Array(items) => {
+            let len = items.len() as u64;
+            if let Some(min) = obj.get("minItems").and_then(|v| v.as_u64())
+                && len < min
+            {
+                fail(format!("{len} items, expected at least {min}"));
+            }
+            if let Some(max) = obj.get("maxItems").and_then(|v| v.as_u64())
+                && len > max
+            {
+                fail(format!("{len} items, expected at most {max}"));
+            }
+            if obj.get("uniqueItems").and_then(|v| v.as_bool()) == Some(true)
+                && items
+                    .iter()
+                    .enumerate()
+                    .any(|(i, item)| items[..i].contains(item))
+            {
+                fail("items are not unique".to_string());
+            }
+            if let Some(item_schema) = obj.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    validate_at(item_schema, item, &format!("{path}/{i}"), errors);
+                }
+            }
+        }
+        Value::Object(map) => {
+            let props = obj.get("properties").and_then(|p| p.as_object());
+            for name in obj
+                .get("required")
+                .and_then(|r| r.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|n| n.as_str())
+            {
+                let one_way = props.and_then(|p| p.get(name)).is_some_and(|prop| {
+                    ["readOnly", "writeOnly"]
+                        .iter()
+                        .any(|k| prop.get(*k).and_then(|v| v.as_bool()) == Some(true))
+                });
+                if !map.contains_key(name) && !one_way {
+                    fail(format!("missing required property '{name}'"));
+                }
+            }
+            let count = map.len() as u64;
+            if let Some(min) = obj.get("minProperties").and_then(|v| v.as_u64())
+                && count < min
+            {
+                fail(format!("{count} properties, expected at least {min}"));
+            }
+            if let Some(max) = obj.get("maxProperties").and_then(|v| v.as_u64())
+                && count > max
+            {
+                fail(format!("{count} properties, expected at most {max}"));
+            }
+            for (name, value) in map {
+                let child = format!("{path}/{}", name.replace('~', "~0").replace('/', "~1"));
+                match props.and_then(|p| p.get(name)) {
+                    Some(prop) => validate_at(prop, value, &child, errors),
+                    None => match obj.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            errors.push(format!("#{path}: unexpected property '{name}'"))
+                        }
+                        Some(additional @ Value::Object(_)) => {
+                            validate_at(additional, value, &child, errors)
+                        }
+                        _ => {}
+                    },
+                }
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(members) = obj.get("allOf").and_then(|a| a.as_array()) {
+        for member in members {
+            validate_at(member, instance, path, errors);
+        }
+    }
+    if let Some(members) = obj.get("anyOf").and_then(|a| a.as_array())
+        && !members
+            .iter()
+            .any(|m| validate_instance(m, instance).is_empty())
+    {
+        errors.push(format!("#{path}: does not match any of anyOf"));
+    }
+    if let Some(members) = obj.get("oneOf").and_then(|a| a.as_array()) {
+        let matched = members
+            .iter()
+            .filter(|m| validate_instance(m, instance).is_empty())
+            .count();
+        if matched != 1 {
+            errors.push(format!(
+                "#{path}: matches {matched} schemas of oneOf, expected exactly 1"
+            ));
+        }
+    }
+    if let Some(not) = obj.get("not")
+        && validate_instance(not, instance).is_empty()
+    {
+        errors.push(format!("#{path}: must not match the `not` schema"));
+    }
+}
+
+fn has_type(instance: &Value, expected: &str) -> bool {
+    match expected {
+        "integer" => instance
+            .as_f64()
+            .is_some_and(|n| n.fract() == 0.0 && instance.is_number()),
+        "number" => instance.is_number(),
+        "string" => instance.is_string(),
+        "boolean" => instance.is_boolean(),
+        "array" => instance.is_array(),
+        "object" => instance.is_object(),
+        "null" => instance.is_null(),
+        _ => true,
+    }
+}
+
+fn type_name(instance: &Value) -> &'static str {
+    match instance {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_inline_refs() {
+        let components = json!({
+            "Pet": {
+                "type": "object",
+                "properties": {
+                    "id": {"type": "integer"},
+                    "owner": {"$ref": "#/components/schemas/Owner"},
+                    "parent": {"$ref": "#/components/schemas/Pet"}
+                }
+            },
+            "Owner": {"type": "object", "properties": {"name": {"type": "string"}}}
+        });
+
+        let resolved = inline_refs(
+            &json!({"$ref": "#/components/schemas/Pet", "description": "A pet"}),
+            &components,
+            &mut Vec::new(),
+        );
+
+        assert_eq!(resolved["description"], "A pet");
+        assert_eq!(
+            resolved["properties"]["owner"]["properties"]["name"]["type"],
+            "string"
+        );
+        // 再帰参照は空のスキーマになる
+        assert_eq!(resolved["properties"]["parent"], json!({}));
+        assert_eq!(property_names(&resolved), vec!["id", "owner", "parent"]);
+    }
+
+    #[test]
+    fn test_validate_instance() {
+        let schema = json!({
+            "type": "object",
+            "required": ["id", "name", "password"],
+            "additionalProperties": false,
+            "properties": {
+                "id": {"type": "integer", "minimum": 1},
+                "name": {"type": "string", "maxLength": 4, "pattern": "^[a-z]+$"},
+                "password": {"type": "string", "writeOnly": true},
+                "kind": {"oneOf": [{"type": "string", "enum": ["cat"]}, {"type": "integer"}]},
+                "nick": {"type": "string", "nullable": true}
+            }
+        });
+
+        assert!(
+            validate_instance(&schema, &json!({"id": 1, "name": "rex", "nick": null})).is_empty()
+        );
+        assert_eq!(
+            validate_instance(
+                &schema,
+                &json!({"id": 0, "name": "Rexxy", "kind": "dog", "extra": true})
+            ),
+            vec![
+                "#: unexpected property 'extra'",
+                "#/id: 0 is less than 1",
+                "#/kind: matches 0 schemas of oneOf, expected exactly 1",
+                "#/name: length 5 is longer than 4",
+                "#/name: does not match pattern ^[a-z]+$",
+            ]
+        );
+        assert_eq!(
+            validate_instance(&schema, &json!([])),
+            vec!["#: expected object, got array"]
+        );
+    }
+}
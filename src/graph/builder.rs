@@ -225,6 +225,7 @@ mod tests {
                     on_success: None,
                     on_failure: None,
                     outputs: None,
+                    extensions: Default::default(),
                 },
                 Step {
                     step_id: "step2".to_string(),
@@ -238,6 +239,7 @@ mod tests {
                     on_success: None,
                     on_failure: None,
                     outputs: None,
+                    extensions: Default::default(),
                 },
             ],
             success_criteria: None,
@@ -271,6 +273,7 @@ mod tests {
                     on_success: None,
                     on_failure: None,
                     outputs: Some(serde_json::json!({"token": "$response.body.token"})),
+                    extensions: Default::default(),
                 },
                 Step {
                     step_id: "getProfile".to_string(),
@@ -288,6 +291,7 @@ mod tests {
                     on_success: None,
                     on_failure: None,
                     outputs: None,
+                    extensions: Default::default(),
                 },
             ],
             success_criteria: None,
@@ -340,6 +344,7 @@ mod tests {
                         config: failure_config,
                     }]),
                     outputs: None,
+                    extensions: Default::default(),
                 },
                 Step {
                     step_id: "step2".to_string(),
@@ -353,6 +358,7 @@ mod tests {
                     on_success: None,
                     on_failure: None,
                     outputs: None,
+                    extensions: Default::default(),
                 },
                 Step {
                     step_id: "step3".to_string(),
@@ -366,6 +372,7 @@ mod tests {
                     on_success: None,
                     on_failure: None,
                     outputs: None,
+                    extensions: Default::default(),
                 },
             ],
             success_criteria: None,
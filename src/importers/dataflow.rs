@@ -0,0 +1,469 @@
+//! Detection of values flowing between recorded requests
+//!
+//! Two sources of data flow are recognised:
+//!
+//! - `{{variable}}` placeholders (Postman). A variable captured from an
+//!   earlier response becomes a step output, any other variable becomes a
+//!   workflow input.
+//! - Literal values that were returned by an earlier response (HAR, or
+//!   Postman collections with saved responses), e.g. an id returned by a
+//!   create call and used in the path of a later call.
+
+use super::RecordedExchange;
+use crate::models::arazzo::Step;
+use crate::models::expression::{ExpressionSource, RuntimeExpression};
+use indexmap::IndexMap;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Minimum length of a string value to be linked by exact match
+const MIN_EXACT_LEN: usize = 4;
+
+/// Minimum length of a string value to be linked inside a larger string
+/// (e.g. a token inside `Bearer <token>`)
+const MIN_EMBEDDED_LEN: usize = 16;
+
+/// A value found in an earlier response
+struct Producer {
+    step: usize,
+    expression: RuntimeExpression,
+    value: serde_json::Value,
+}
+
+/// State shared while rewriting a step
+struct Linker<'a> {
+    producers: Vec<Producer>,
+    captures: IndexMap<String, (usize, RuntimeExpression)>,
+    variables: &'a [(String, serde_json::Value)],
+    inputs: IndexMap<String, serde_json::Value>,
+}
+
+fn placeholder_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\{\{\s*([^{}\s]+)\s*\}\}").unwrap())
+}
+
+/// Rewrite data flow between `steps` as runtime expressions
+///
+/// `exchanges[i]` is the recorded exchange `steps[i]` was built from.
+/// Producing steps get the needed `outputs`; the returned map holds the
+/// workflow inputs with their default values.
+pub fn link_steps(
+    steps: &mut [Step],
+    exchanges: &[&RecordedExchange],
+    variables: &[(String, serde_json::Value)],
+) -> IndexMap<String, serde_json::Value> {
+    let mut linker = Linker {
+        producers: Vec::new(),
+        captures: IndexMap::new(),
+        variables,
+        inputs: IndexMap::new(),
+    };
+
+    for i in 0..steps.len() {
+        // パラメータとリクエストボディを書き換える
+        let mut parameters = std::mem::take(&mut steps[i].parameters);
+        for param in &mut parameters {
+            let is_path = param.location == "path";
+            let name = param.name.clone();
+            linker.rewrite(&mut param.value, &name, is_path, steps);
+        }
+        steps[i].parameters = parameters;
+
+        if let Some(mut body) = steps[i].request_body.take() {
+            linker.rewrite(&mut body.payload, "", false, steps);
+            steps[i].request_body = Some(body);
+        }
+
+        // このステップのレスポンスを以降のステップで参照できるようにする
+        let exchange = exchanges[i];
+        for capture in &exchange.captures {
+            linker
+                .captures
+                .insert(capture.variable.clone(), (i, capture.expression.clone()));
+        }
+        if let Some(body) = exchange.response.as_ref().and_then(|r| r.body.as_ref()) {
+            let mut leaves = Vec::new();
+            collect_leaves(body, &mut Vec::new(), &mut leaves);
+            for (path, value) in leaves {
+                linker.producers.push(Producer {
+                    step: i,
+                    expression: RuntimeExpression::Response(ExpressionSource::Body(path)),
+                    value,
+                });
+            }
+        }
+    }
+
+    linker.inputs
+}
+
+impl Linker<'_> {
+    /// Rewrite a request value in place
+    ///
+    /// `key` is the parameter or property name, used to decide whether
+    /// numbers look like identifiers.
+    fn rewrite(
+        &mut self,
+        value: &mut serde_json::Value,
+        key: &str,
+        is_path: bool,
+        steps: &mut [Step],
+    ) {
+        match value {
+            serde_json::Value::Object(obj) => {
+                for (k, v) in obj.iter_mut() {
+                    self.rewrite(v, k, false, steps);
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for item in items.iter_mut() {
+                    self.rewrite(item, key, false, steps);
+                }
+            }
+            serde_json::Value::String(s) => {
+                let text = s.clone();
+                if placeholder_regex().is_match(&text) {
+                    *value = serde_json::Value::String(self.replace_placeholders(&text, steps));
+                } else if let Some(expr) = self.find_exact(value, key, is_path, steps) {
+                    *value = serde_json::Value::String(expr);
+                } else if let Some(replaced) = self.replace_embedded(&text, steps) {
+                    *value = serde_json::Value::String(replaced);
+                }
+            }
+            serde_json::Value::Number(_) => {
+                if let Some(expr) = self.find_exact(value, key, is_path, steps) {
+                    *value = serde_json::Value::String(expr);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Replace `{{variable}}` placeholders with step outputs or inputs
+    fn replace_placeholders(&mut self, s: &str, steps: &mut [Step]) -> String {
+        let names: Vec<String> = placeholder_regex()
+            .captures_iter(s)
+            .map(|c| c[1].to_string())
+            .collect();
+
+        let mut result = s.to_string();
+        for name in names {
+            let expr = match self.captures.get(&name).cloned() {
+                Some((step, expression)) => {
+                    let output = register_output(steps, step, &expression, Some(&name));
+                    format!("$steps.{}.outputs.{}", steps[step].step_id, output)
+                }
+                None => {
+                    let input = output_name_from_variable(&name);
+                    let default = self
+                        .variables
+                        .iter()
+                        .find(|(k, _)| *k == name)
+                        .map(|(_, v)| v.clone())
+                        .unwrap_or(serde_json::Value::Null);
+                    self.inputs.entry(input.clone()).or_insert(default);
+                    format!("$inputs.{}", input)
+                }
+            };
+            let pattern = Regex::new(&format!(r"\{{\{{\s*{}\s*\}}\}}", regex::escape(&name)))
+                .expect("escaped placeholder pattern");
+            result = pattern
+                .replace_all(&result, regex::NoExpand(expr.as_str()))
+                .into_owned();
+        }
+        result
+    }
+
+    /// Find the most recent producer of exactly this value
+    fn find_exact(
+        &mut self,
+        value: &serde_json::Value,
+        key: &str,
+        is_path: bool,
+        steps: &mut [Step],
+    ) -> Option<String> {
+        let id_like = is_path || key.to_ascii_lowercase().ends_with("id");
+
+        let producer = self
+            .producers
+            .iter()
+            .rev()
+            .find(|p| match (&p.value, value) {
+                (serde_json::Value::String(a), serde_json::Value::String(b)) => {
+                    a == b && a.chars().count() >= MIN_EXACT_LEN
+                }
+                (serde_json::Value::Number(a), serde_json::Value::Number(b)) => id_like && a == b,
+                // パスパラメータは文字列として記録されている
+                (serde_json::Value::Number(a), serde_json::Value::String(b)) => {
+                    id_like && a.to_string() == *b
+                }
+                _ => false,
+            })?;
+
+        let (step, expression) = (producer.step, producer.expression.clone());
+        let output = register_output(steps, step, &expression, None);
+        Some(format!("$steps.{}.outputs.{}", steps[step].step_id, output))
+    }
+
+    /// Replace long producer values embedded in a larger string
+    fn replace_embedded(&mut self, s: &str, steps: &mut [Step]) -> Option<String> {
+        let producer = self.producers.iter().rev().find(|p| {
+            p.value
+                .as_str()
+                .is_some_and(|v| v.chars().count() >= MIN_EMBEDDED_LEN && s.contains(v))
+        })?;
+
+        let needle = producer.value.as_str()?.to_string();
+        let (step, expression) = (producer.step, producer.expression.clone());
+        let output = register_output(steps, step, &expression, None);
+        Some(s.replace(
+            &needle,
+            &format!("$steps.{}.outputs.{}", steps[step].step_id, output),
+        ))
+    }
+}
+
+/// Add an output for `expression` to `steps[step]`, returning its name
+///
+/// An existing output with the same expression is reused.
+fn register_output(
+    steps: &mut [Step],
+    step: usize,
+    expression: &RuntimeExpression,
+    preferred: Option<&str>,
+) -> String {
+    let expr = expression.to_string();
+    let outputs = steps[step]
+        .outputs
+        .get_or_insert_with(|| serde_json::Value::Object(Default::default()));
+    let Some(outputs) = outputs.as_object_mut() else {
+        return String::new();
+    };
+
+    if let Some((name, _)) = outputs.iter().find(|(_, v)| v.as_str() == Some(&expr)) {
+        return name.clone();
+    }
+
+    let base = match preferred {
+        Some(name) => output_name_from_variable(name),
+        None => output_name_from_expression(expression),
+    };
+    let mut name = base.clone();
+    let mut n = 2;
+    while outputs.contains_key(&name) {
+        name = format!("{}{}", base, n);
+        n += 1;
+    }
+
+    outputs.insert(name.clone(), serde_json::Value::String(expr));
+    name
+}
+
+/// Derive an output or input name from a variable name (`user-id` → `user_id`)
+pub(super) fn output_name_from_variable(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("v{}", name)
+    } else {
+        name
+    }
+}
+
+/// Derive an output name from a response expression
+///
+/// The last two named segments are joined in camelCase
+/// (`$response.body.user.id` → `userId`).
+fn output_name_from_expression(expression: &RuntimeExpression) -> String {
+    let segments: Vec<&str> = match expression {
+        RuntimeExpression::Response(ExpressionSource::Body(path)) => path
+            .iter()
+            .filter(|s| !s.chars().all(|c| c.is_ascii_digit()))
+            .map(|s| s.as_str())
+            .collect(),
+        RuntimeExpression::Response(ExpressionSource::Header(name)) => vec![name.as_str()],
+        _ => Vec::new(),
+    };
+
+    let tail = &segments[segments.len().saturating_sub(2)..];
+    let mut name = String::new();
+    for (i, segment) in tail.iter().enumerate() {
+        let segment = output_name_from_variable(segment);
+        if i == 0 {
+            name.push_str(&segment);
+        } else {
+            let mut chars = segment.chars();
+            if let Some(first) = chars.next() {
+                name.extend(first.to_uppercase());
+                name.push_str(chars.as_str());
+            }
+        }
+    }
+
+    if name.is_empty() {
+        "body".to_string()
+    } else {
+        name
+    }
+}
+
+/// Collect scalar leaves (strings and numbers) with their paths
+fn collect_leaves(
+    value: &serde_json::Value,
+    path: &mut Vec<String>,
+    out: &mut Vec<(Vec<String>, serde_json::Value)>,
+) {
+    match value {
+        serde_json::Value::Object(obj) => {
+            for (k, v) in obj {
+                path.push(k.clone());
+                collect_leaves(v, path, out);
+                path.pop();
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for (i, v) in items.iter().enumerate() {
+                path.push(i.to_string());
+                collect_leaves(v, path, out);
+                path.pop();
+            }
+        }
+        serde_json::Value::String(_) | serde_json::Value::Number(_) => {
+            out.push((path.clone(), value.clone()));
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importers::{Capture, RecordedResponse};
+    use crate::models::arazzo::{Parameter, RequestBody};
+    use serde_json::json;
+
+    fn step(id: &str, parameters: Vec<Parameter>, payload: Option<serde_json::Value>) -> Step {
+        Step {
+            step_id: id.to_string(),
+            description: None,
+            operation_id: Some(id.to_string()),
+            operation_path: None,
+            workflow_id: None,
+            parameters,
+            request_body: payload.map(|payload| RequestBody {
+                content_type: Some("application/json".to_string()),
+                payload,
+                replacements: Vec::new(),
+            }),
+            success_criteria: None,
+            on_success: None,
+            on_failure: None,
+            outputs: None,
+            extensions: Default::default(),
+        }
+    }
+
+    fn header(name: &str, value: &str) -> Parameter {
+        Parameter {
+            name: name.to_string(),
+            location: "header".to_string(),
+            value: json!(value),
+        }
+    }
+
+    fn exchange(body: Option<serde_json::Value>, captures: Vec<Capture>) -> RecordedExchange {
+        RecordedExchange {
+            response: body.map(|body| RecordedResponse {
+                status: 200,
+                headers: Vec::new(),
+                body: Some(body),
+            }),
+            captures,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_link_embedded_token_and_nested_id() {
+        let token = "eyJhbGciOiJIUzI1NiJ9.payload";
+        let mut steps = vec![
+            step("login", vec![], Some(json!({"username": "alice"}))),
+            step(
+                "createOrder",
+                vec![header("Authorization", &format!("Bearer {}", token))],
+                Some(json!({"userId": 7, "note": "alice"})),
+            ),
+        ];
+        let first = exchange(Some(json!({"token": token, "user": {"id": 7}})), vec![]);
+        let second = exchange(None, vec![]);
+
+        let inputs = link_steps(&mut steps, &[&first, &second], &[]);
+        assert!(inputs.is_empty());
+
+        assert_eq!(
+            steps[1].parameters[0].value,
+            json!("Bearer $steps.login.outputs.token")
+        );
+        let payload = &steps[1].request_body.as_ref().unwrap().payload;
+        assert_eq!(payload["userId"], json!("$steps.login.outputs.userId"));
+        // Short strings are not linked
+        assert_eq!(payload["note"], json!("alice"));
+
+        assert_eq!(
+            steps[0].outputs,
+            Some(json!({
+                "token": "$response.body.token",
+                "userId": "$response.body.user.id"
+            }))
+        );
+    }
+
+    #[test]
+    fn test_link_placeholders() {
+        let mut steps = vec![
+            step("login", vec![], Some(json!({"username": "{{username}}"}))),
+            step(
+                "me",
+                vec![header("Authorization", "Bearer {{authToken}}")],
+                None,
+            ),
+        ];
+        let first = exchange(
+            None,
+            vec![Capture::new(
+                "authToken",
+                RuntimeExpression::parse("$response.body.token").unwrap(),
+            )],
+        );
+        let second = exchange(None, vec![]);
+
+        let inputs = link_steps(
+            &mut steps,
+            &[&first, &second],
+            &[("username".to_string(), json!("alice"))],
+        );
+
+        assert_eq!(inputs.get("username"), Some(&json!("alice")));
+        assert_eq!(
+            steps[0].request_body.as_ref().unwrap().payload,
+            json!({"username": "$inputs.username"})
+        );
+        assert_eq!(
+            steps[1].parameters[0].value,
+            json!("Bearer $steps.login.outputs.authToken")
+        );
+        assert_eq!(
+            steps[0].outputs,
+            Some(json!({"authToken": "$response.body.token"}))
+        );
+    }
+}
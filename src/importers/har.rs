@@ -0,0 +1,240 @@
+//! HAR (HTTP Archive 1.2) importer
+//!
+//! All entries of the archive form a single flow, in recording order.
+
+use super::{
+    RecordedBody, RecordedExchange, RecordedFlow, RecordedResponse, fields_to_object, file_value,
+    parse_body, split_url,
+};
+use crate::error::{HornetError, Result};
+use crate::payload::{BodyEncoding, text_value};
+
+/// Parse a HAR document into a recorded flow named `default_name` unless the
+/// archive has a page title
+pub fn parse_har(har: &serde_json::Value, default_name: &str) -> Result<RecordedFlow> {
+    let log = har
+        .get("log")
+        .ok_or_else(|| HornetError::ImportError("Not a HAR file: missing 'log'".to_string()))?;
+    let entries = log
+        .get("entries")
+        .and_then(|e| e.as_array())
+        .ok_or_else(|| HornetError::ImportError("Not a HAR file: missing 'entries'".to_string()))?;
+
+    let name = log
+        .pointer("/pages/0/title")
+        .and_then(|t| t.as_str())
+        .filter(|t| !t.is_empty())
+        .unwrap_or(default_name)
+        .to_string();
+
+    let mut entries: Vec<&serde_json::Value> = entries.iter().collect();
+    // 記録順（startedDateTime）に並べる
+    entries.sort_by_key(|e| {
+        e.get("startedDateTime")
+            .and_then(|t| t.as_str())
+            .unwrap_or_default()
+            .to_string()
+    });
+
+    Ok(RecordedFlow {
+        name,
+        description: None,
+        exchanges: entries.into_iter().filter_map(parse_entry).collect(),
+        variables: Vec::new(),
+    })
+}
+
+fn parse_entry(entry: &serde_json::Value) -> Option<RecordedExchange> {
+    let request = entry.get("request")?;
+    let method = request.get("method")?.as_str()?.to_uppercase();
+    let (path, query_from_url) = split_url(request.get("url")?.as_str()?);
+
+    let query = match request.get("queryString").and_then(|q| q.as_array()) {
+        Some(items) if !items.is_empty() => name_values(Some(items)),
+        _ => query_from_url,
+    };
+
+    let headers = name_values(request.get("headers").and_then(|h| h.as_array()));
+    let body = request.get("postData").and_then(parse_post_data);
+    let response = entry.get("response").and_then(parse_response);
+
+    Some(RecordedExchange {
+        name: None,
+        method,
+        path,
+        query,
+        headers,
+        body,
+        response,
+        captures: Vec::new(),
+    })
+}
+
+fn name_values(items: Option<&Vec<serde_json::Value>>) -> Vec<(String, String)> {
+    items
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|i| {
+                    Some((
+                        i.get("name")?.as_str()?.to_string(),
+                        i.get("value")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or_default()
+                            .to_string(),
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_post_data(post_data: &serde_json::Value) -> Option<RecordedBody> {
+    let content_type = post_data
+        .get("mimeType")
+        .and_then(|m| m.as_str())
+        .filter(|m| !m.is_empty())
+        .map(String::from);
+    let encoding = BodyEncoding::from_content_type(content_type.as_deref());
+
+    // multipart/form 形式は params から組み立てる
+    if let Some(params) = post_data.get("params").and_then(|p| p.as_array())
+        && !params.is_empty()
+        && matches!(
+            encoding,
+            BodyEncoding::Multipart | BodyEncoding::FormUrlEncoded
+        )
+    {
+        let fields = params.iter().filter_map(|p| {
+            let name = p.get("name")?.as_str()?.to_string();
+            let value = match p.get("fileName").and_then(|f| f.as_str()) {
+                Some(file) => file_value(file),
+                None => {
+                    serde_json::json!(p.get("value").and_then(|v| v.as_str()).unwrap_or_default())
+                }
+            };
+            Some((name, value))
+        });
+        return Some(RecordedBody {
+            // ブラウザが付与した boundary は引き継がない
+            content_type: content_type
+                .map(|ct| ct.split(';').next().unwrap_or_default().trim().to_string()),
+            payload: fields_to_object(fields),
+        });
+    }
+
+    let text = post_data.get("text").and_then(|t| t.as_str())?;
+    if text.is_empty() {
+        return None;
+    }
+    let payload = parse_body(content_type.as_deref(), text);
+    if encoding == BodyEncoding::Multipart {
+        // 生のmultipart本文は再現できないため文字列として残す
+        return Some(RecordedBody {
+            content_type,
+            payload: serde_json::json!(text_value(&payload)),
+        });
+    }
+
+    Some(RecordedBody {
+        content_type,
+        payload,
+    })
+}
+
+fn parse_response(response: &serde_json::Value) -> Option<RecordedResponse> {
+    let status = response.get("status")?.as_u64()? as u16;
+    let content = response.get("content");
+    let mime_type = content
+        .and_then(|c| c.get("mimeType"))
+        .and_then(|m| m.as_str());
+    let is_base64 = content
+        .and_then(|c| c.get("encoding"))
+        .and_then(|e| e.as_str())
+        == Some("base64");
+
+    let body = content
+        .and_then(|c| c.get("text"))
+        .and_then(|t| t.as_str())
+        .filter(|t| !t.is_empty() && !is_base64)
+        .filter(|_| BodyEncoding::from_content_type(mime_type) == BodyEncoding::Json)
+        .map(|t| parse_body(mime_type, t));
+
+    Some(RecordedResponse {
+        status,
+        headers: name_values(response.get("headers").and_then(|h| h.as_array())),
+        body,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_har() {
+        let har = json!({
+            "log": {
+                "version": "1.2",
+                "entries": [
+                    {
+                        "startedDateTime": "2024-01-01T00:00:02Z",
+                        "request": {
+                            "method": "get",
+                            "url": "https://api.example.com/users/42?fields=name",
+                            "headers": [{"name": "Authorization", "value": "Bearer abc"}],
+                            "queryString": [{"name": "fields", "value": "name"}]
+                        },
+                        "response": {"status": 200, "headers": [], "content": {"mimeType": "application/json", "text": "{\"id\": 42}"}}
+                    },
+                    {
+                        "startedDateTime": "2024-01-01T00:00:01Z",
+                        "request": {
+                            "method": "POST",
+                            "url": "https://api.example.com/users",
+                            "headers": [],
+                            "postData": {"mimeType": "application/json", "text": "{\"name\": \"alice\"}"}
+                        },
+                        "response": {"status": 201, "headers": [], "content": {"mimeType": "text/html", "text": "<p>ok</p>"}}
+                    }
+                ]
+            }
+        });
+
+        let flow = parse_har(&har, "recording").unwrap();
+        assert_eq!(flow.name, "recording");
+        assert_eq!(flow.exchanges.len(), 2);
+
+        let create = &flow.exchanges[0];
+        assert_eq!(create.method, "POST");
+        assert_eq!(
+            create.body.as_ref().unwrap().payload,
+            json!({"name": "alice"})
+        );
+        assert!(create.response.as_ref().unwrap().body.is_none());
+
+        let get = &flow.exchanges[1];
+        assert_eq!(get.path, "/users/42");
+        assert_eq!(get.query, vec![("fields".to_string(), "name".to_string())]);
+        assert_eq!(get.response.as_ref().unwrap().body, Some(json!({"id": 42})));
+    }
+
+    #[test]
+    fn test_form_post_data_uses_params() {
+        let body = parse_post_data(&json!({
+            "mimeType": "multipart/form-data; boundary=----x",
+            "params": [
+                {"name": "title", "value": "hello"},
+                {"name": "file", "fileName": "a.png"}
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(body.content_type.as_deref(), Some("multipart/form-data"));
+        assert_eq!(
+            body.payload,
+            json!({"title": "hello", "file": {"$file": "a.png"}})
+        );
+    }
+}
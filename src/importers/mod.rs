@@ -0,0 +1,714 @@
+//! Importers for generating Arazzo workflows from recorded HTTP traffic
+//!
+//! Postman collections and HAR captures are first parsed into
+//! [`RecordedFlow`]s. [`build_workflow`] then maps every request onto an
+//! OpenAPI operation and rewrites values that flow from earlier responses
+//! into later requests as `$steps.*.outputs.*` expressions. Credentials
+//! that no earlier response produced become workflow inputs, so recorded
+//! tokens, cookies and API keys never end up in the generated file.
+
+pub mod dataflow;
+pub mod har;
+pub mod postman;
+
+use crate::loader::OpenApiResolver;
+use crate::models::arazzo::{Parameter, RequestBody, Step, SuccessCriteria, Workflow};
+use crate::models::expression::RuntimeExpression;
+use crate::payload::{BodyEncoding, FILE_KEY};
+use indexmap::IndexMap;
+use oas3::spec::{ObjectOrReference, ParameterIn, SecurityScheme};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+/// A sequence of requests that becomes one workflow
+#[derive(Debug, Clone, Default)]
+pub struct RecordedFlow {
+    /// Flow name (used to derive the workflow id)
+    pub name: String,
+    /// Description carried over to the workflow
+    pub description: Option<String>,
+    /// Requests in execution order
+    pub exchanges: Vec<RecordedExchange>,
+    /// Known variable values (`{{name}}` placeholders) used as input defaults
+    pub variables: Vec<(String, serde_json::Value)>,
+}
+
+/// A single recorded request with its response
+#[derive(Debug, Clone, Default)]
+pub struct RecordedExchange {
+    /// Human-readable request name
+    pub name: Option<String>,
+    /// HTTP method
+    pub method: String,
+    /// Request path without scheme, host or query string
+    pub path: String,
+    /// Query parameters
+    pub query: Vec<(String, String)>,
+    /// Request headers
+    pub headers: Vec<(String, String)>,
+    /// Request body
+    pub body: Option<RecordedBody>,
+    /// Recorded response, if any
+    pub response: Option<RecordedResponse>,
+    /// Variables set from this request's response
+    pub captures: Vec<Capture>,
+}
+
+/// A recorded request body
+#[derive(Debug, Clone)]
+pub struct RecordedBody {
+    pub content_type: Option<String>,
+    pub payload: serde_json::Value,
+}
+
+/// A recorded response
+#[derive(Debug, Clone)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    /// Parsed JSON body (other bodies are kept as strings)
+    pub body: Option<serde_json::Value>,
+}
+
+/// A variable assigned from a response, e.g. by a Postman test script
+#[derive(Debug, Clone, PartialEq)]
+pub struct Capture {
+    pub variable: String,
+    pub expression: RuntimeExpression,
+}
+
+impl Capture {
+    pub fn new(variable: impl Into<String>, expression: RuntimeExpression) -> Self {
+        Self {
+            variable: variable.into(),
+            expression,
+        }
+    }
+}
+
+/// A workflow generated from a recorded flow
+#[derive(Debug, Clone)]
+pub struct ImportResult {
+    pub workflow: Workflow,
+    /// Requests that could not be imported
+    pub warnings: Vec<String>,
+}
+
+/// Headers that are always carried over even when the operation does not
+/// declare them (security schemes are not listed as parameters)
+const PASSTHROUGH_HEADERS: &[&str] = &["authorization"];
+
+fn input_ref_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\$inputs\.([A-Za-z0-9_]+)").unwrap())
+}
+
+/// Build a workflow from a recorded flow
+///
+/// Requests that do not match any OpenAPI operation are skipped and
+/// reported in [`ImportResult::warnings`].
+pub fn build_workflow(flow: &RecordedFlow, resolver: &OpenApiResolver) -> ImportResult {
+    let mut warnings = Vec::new();
+    let mut steps = Vec::new();
+    let mut exchanges = Vec::new();
+    let mut step_ids = HashSet::new();
+    // ステップごとの、資格情報を運ぶパラメータの位置
+    let mut credentials: Vec<Vec<usize>> = Vec::new();
+    let api_keys = api_key_names(resolver);
+
+    for exchange in &flow.exchanges {
+        let Some(matched) = resolver.match_path(&exchange.method, &exchange.path) else {
+            warnings.push(format!(
+                "{} {}: no matching OpenAPI operation, skipped",
+                exchange.method.to_uppercase(),
+                exchange.path
+            ));
+            continue;
+        };
+
+        let op_ref = &matched.operation_ref;
+        let base_id = matched
+            .operation
+            .operation_id
+            .clone()
+            .unwrap_or_else(|| format!("{} {}", op_ref.method.to_lowercase(), op_ref.path));
+        let step_id = unique_id(&sanitize_id(&base_id), &mut step_ids);
+
+        let mut parameters: Vec<Parameter> = matched
+            .path_params
+            .iter()
+            .map(|(name, value)| Parameter {
+                name: name.clone(),
+                location: "path".to_string(),
+                value: serde_json::Value::String(percent_decode(value)),
+            })
+            .collect();
+
+        let mut secret = Vec::new();
+        for (name, value) in &exchange.query {
+            if api_keys.contains(&("query", name.to_ascii_lowercase())) {
+                secret.push(parameters.len());
+            }
+            parameters.push(Parameter {
+                name: name.clone(),
+                location: "query".to_string(),
+                value: serde_json::Value::String(value.clone()),
+            });
+        }
+
+        // 宣言されたヘッダー・Cookieパラメータのみを引き継ぐ
+        for param in matched.operation.parameters.iter() {
+            let ObjectOrReference::Object(param) = param else {
+                continue;
+            };
+            let value = match param.location {
+                ParameterIn::Header => header_value(&exchange.headers, &param.name),
+                ParameterIn::Cookie => cookie_value(&exchange.headers, &param.name),
+                _ => None,
+            };
+            if let Some(value) = value {
+                secret.push(parameters.len());
+                parameters.push(Parameter {
+                    name: param.name.clone(),
+                    location: format!("{:?}", param.location).to_lowercase(),
+                    value: serde_json::Value::String(value),
+                });
+            }
+        }
+        let header_keys = api_keys
+            .iter()
+            .filter(|(location, _)| *location == "header")
+            .map(|(_, name)| name.as_str());
+        for name in PASSTHROUGH_HEADERS.iter().copied().chain(header_keys) {
+            if parameters
+                .iter()
+                .any(|p| p.location == "header" && p.name.eq_ignore_ascii_case(name))
+            {
+                continue;
+            }
+            if let Some((header, value)) = exchange
+                .headers
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            {
+                secret.push(parameters.len());
+                parameters.push(Parameter {
+                    name: header.clone(),
+                    location: "header".to_string(),
+                    value: serde_json::Value::String(value.clone()),
+                });
+            }
+        }
+        credentials.push(secret);
+
+        let request_body = exchange.body.as_ref().map(|body| RequestBody {
+            content_type: body.content_type.clone(),
+            payload: body.payload.clone(),
+            replacements: Vec::new(),
+        });
+
+        let success_criteria = exchange.response.as_ref().map(|response| {
+            vec![SuccessCriteria {
+                context: "$statusCode".to_string(),
+                condition: "==".to_string(),
+                value: Some(serde_json::json!(response.status)),
+                criteria_type: None,
+            }]
+        });
+
+        let (operation_id, operation_path) = match matched.operation.operation_id.clone() {
+            Some(op_id) => (Some(op_id), None),
+            None => (None, Some(format!("{} {}", op_ref.method, op_ref.path))),
+        };
+
+        steps.push(Step {
+            step_id,
+            description: exchange.name.clone(),
+            operation_id,
+            operation_path,
+            workflow_id: None,
+            parameters,
+            request_body,
+            success_criteria,
+            on_success: None,
+            on_failure: None,
+            outputs: None,
+            extensions: Default::default(),
+        });
+        exchanges.push(exchange);
+    }
+
+    let mut inputs = dataflow::link_steps(&mut steps, &exchanges, &flow.variables);
+    extract_credentials(&mut steps, &credentials, &mut inputs);
+
+    let workflow = Workflow {
+        workflow_id: sanitize_id(&flow.name),
+        summary: Some(flow.name.clone()),
+        description: flow.description.clone(),
+        inputs: inputs_schema(&inputs),
+        steps,
+        success_criteria: None,
+        outputs: None,
+        extensions: IndexMap::new(),
+    };
+
+    ImportResult { workflow, warnings }
+}
+
+/// Lower-case names of the `apiKey` security schemes with their location
+fn api_key_names(resolver: &OpenApiResolver) -> HashSet<(&'static str, String)> {
+    let mut names = HashSet::new();
+    for spec in resolver.get_all_specs().values() {
+        let Some(components) = spec.components.as_ref() else {
+            continue;
+        };
+        for scheme in components.security_schemes.values() {
+            let ObjectOrReference::Object(SecurityScheme::ApiKey { name, location, .. }) = scheme
+            else {
+                continue;
+            };
+            let location = match location.as_str() {
+                "header" => "header",
+                "query" => "query",
+                "cookie" => "cookie",
+                _ => continue,
+            };
+            names.insert((location, name.to_ascii_lowercase()));
+        }
+    }
+    names
+}
+
+/// Move credentials that no earlier response produced into workflow inputs
+///
+/// Recorded values become `$inputs.<name>` without a default (the same value
+/// shares one input), and inputs referenced by credentials lose the default
+/// taken from collection variables.
+fn extract_credentials(
+    steps: &mut [Step],
+    credentials: &[Vec<usize>],
+    inputs: &mut IndexMap<String, serde_json::Value>,
+) {
+    let mut by_value: HashMap<String, String> = HashMap::new();
+    for (step, positions) in steps.iter_mut().zip(credentials) {
+        for &position in positions {
+            let param = &mut step.parameters[position];
+            let serde_json::Value::String(value) = &param.value else {
+                continue;
+            };
+            if value.contains("$steps.") || value.contains("$inputs.") {
+                for input in input_ref_regex().captures_iter(value) {
+                    if let Some(default) = inputs.get_mut(&input[1]) {
+                        *default = serde_json::Value::Null;
+                    }
+                }
+                continue;
+            }
+
+            let input = match by_value.get(value) {
+                Some(input) => input.clone(),
+                None => {
+                    let base =
+                        dataflow::output_name_from_variable(&param.name.to_ascii_lowercase());
+                    let mut input = base.clone();
+                    let mut n = 2;
+                    while inputs.contains_key(&input) {
+                        input = format!("{}_{}", base, n);
+                        n += 1;
+                    }
+                    inputs.insert(input.clone(), serde_json::Value::Null);
+                    by_value.insert(value.clone(), input.clone());
+                    input
+                }
+            };
+            param.value = serde_json::Value::String(format!("$inputs.{}", input));
+        }
+    }
+}
+
+/// Build the workflow `inputs` JSON Schema from input defaults
+fn inputs_schema(inputs: &IndexMap<String, serde_json::Value>) -> Option<serde_json::Value> {
+    if inputs.is_empty() {
+        return None;
+    }
+
+    let properties: serde_json::Map<String, serde_json::Value> = inputs
+        .iter()
+        .map(|(name, default)| {
+            let schema_type = match default {
+                serde_json::Value::Bool(_) => "boolean",
+                serde_json::Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+                serde_json::Value::Number(_) => "number",
+                serde_json::Value::Object(_) => "object",
+                serde_json::Value::Array(_) => "array",
+                _ => "string",
+            };
+            let mut schema = serde_json::json!({ "type": schema_type });
+            if !default.is_null() {
+                schema["default"] = default.clone();
+            }
+            (name.clone(), schema)
+        })
+        .collect();
+
+    Some(serde_json::json!({
+        "type": "object",
+        "properties": properties,
+    }))
+}
+
+/// Turn an arbitrary name into a valid Arazzo id (`[A-Za-z0-9_-]+`)
+pub fn sanitize_id(name: &str) -> String {
+    let mut id = String::new();
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+            id.push(c);
+        } else if !id.ends_with('-') {
+            id.push('-');
+        }
+    }
+    let id = id.trim_matches('-').to_string();
+    if id.is_empty() {
+        "imported".to_string()
+    } else {
+        id
+    }
+}
+
+/// Return `base`, or `base-2`, `base-3`, ... if already taken
+pub fn unique_id(base: &str, taken: &mut HashSet<String>) -> String {
+    let mut id = base.to_string();
+    let mut n = 2;
+    while !taken.insert(id.clone()) {
+        id = format!("{}-{}", base, n);
+        n += 1;
+    }
+    id
+}
+
+fn header_value(headers: &[(String, String)], name: &str) -> Option<String> {
+    headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.clone())
+}
+
+fn cookie_value(headers: &[(String, String)], name: &str) -> Option<String> {
+    header_value(headers, "cookie")?
+        .split(';')
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(k, _)| *k == name)
+        .map(|(_, v)| v.to_string())
+}
+
+/// Split a URL (absolute or path-only) into its path and query parameters
+pub fn split_url(url: &str) -> (String, Vec<(String, String)>) {
+    let url = url.split('#').next().unwrap_or_default();
+    let after_scheme = match url.split_once("://") {
+        Some((_, rest)) => rest.find('/').map(|i| &rest[i..]).unwrap_or("/"),
+        None => url,
+    };
+    let (path, query) = after_scheme.split_once('?').unwrap_or((after_scheme, ""));
+    let path = if path.is_empty() { "/" } else { path };
+    (path.to_string(), parse_query(query))
+}
+
+/// Parse a `a=1&b=2` query string
+pub fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(k), percent_decode(v))
+        })
+        .collect()
+}
+
+/// Decode `%XX` escapes and `+` (form encoding)
+pub fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(byte) => {
+                        out.push(byte);
+                        i += 2;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Parse a recorded body text according to its content type
+///
+/// JSON is parsed, form bodies become objects (repeated keys become arrays)
+/// and everything else is kept as a string.
+pub fn parse_body(content_type: Option<&str>, text: &str) -> serde_json::Value {
+    match BodyEncoding::from_content_type(content_type) {
+        BodyEncoding::Json => serde_json::from_str(text)
+            .unwrap_or_else(|_| serde_json::Value::String(text.to_string())),
+        BodyEncoding::FormUrlEncoded => fields_to_object(parse_query(text)),
+        _ => serde_json::Value::String(text.to_string()),
+    }
+}
+
+/// Collect form fields into an object, turning repeated keys into arrays
+pub fn fields_to_object(
+    fields: impl IntoIterator<Item = (String, impl Into<serde_json::Value>)>,
+) -> serde_json::Value {
+    let mut obj = serde_json::Map::new();
+    for (key, value) in fields {
+        let value = value.into();
+        match obj.get_mut(&key) {
+            Some(serde_json::Value::Array(items)) => items.push(value),
+            Some(existing) => *existing = serde_json::json!([existing.take(), value]),
+            None => {
+                obj.insert(key, value);
+            }
+        }
+    }
+    serde_json::Value::Object(obj)
+}
+
+/// Payload value referencing a file for multipart uploads
+pub fn file_value(path: &str) -> serde_json::Value {
+    serde_json::json!({ FILE_KEY: path })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_split_url() {
+        let (path, query) = split_url("https://api.example.com/v1/users?page=2&q=a+b#top");
+        assert_eq!(path, "/v1/users");
+        assert_eq!(
+            query,
+            vec![
+                ("page".to_string(), "2".to_string()),
+                ("q".to_string(), "a b".to_string())
+            ]
+        );
+
+        assert_eq!(split_url("http://localhost:8080").0, "/");
+        assert_eq!(split_url("/users/%7Bid%7D").0, "/users/%7Bid%7D");
+        assert_eq!(percent_decode("%7Bid%7D%"), "{id}%");
+    }
+
+    #[test]
+    fn test_sanitize_and_unique_ids() {
+        assert_eq!(sanitize_id("Get user / by id"), "Get-user-by-id");
+        assert_eq!(sanitize_id("???"), "imported");
+
+        let mut taken = HashSet::new();
+        assert_eq!(unique_id("login", &mut taken), "login");
+        assert_eq!(unique_id("login", &mut taken), "login-2");
+    }
+
+    #[test]
+    fn test_build_workflow_maps_operations() {
+        let mut resolver = OpenApiResolver::new("tests/fixtures/multi_project/project2");
+        resolver
+            .load_spec(
+                "userAPI",
+                Path::new("tests/fixtures/multi_project/project2/openapi.yaml"),
+            )
+            .unwrap();
+
+        let flow = RecordedFlow {
+            name: "User lifecycle".to_string(),
+            exchanges: vec![
+                RecordedExchange {
+                    method: "POST".to_string(),
+                    path: "/users".to_string(),
+                    body: Some(RecordedBody {
+                        content_type: Some("application/json".to_string()),
+                        payload: serde_json::json!({"name": "alice"}),
+                    }),
+                    response: Some(RecordedResponse {
+                        status: 201,
+                        headers: Vec::new(),
+                        body: Some(serde_json::json!({"id": 42, "name": "alice"})),
+                    }),
+                    ..Default::default()
+                },
+                RecordedExchange {
+                    method: "GET".to_string(),
+                    path: "/users/42".to_string(),
+                    ..Default::default()
+                },
+                RecordedExchange {
+                    method: "GET".to_string(),
+                    path: "/favicon.ico".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let result = build_workflow(&flow, &resolver);
+        let workflow = result.workflow;
+
+        assert_eq!(workflow.workflow_id, "User-lifecycle");
+        assert_eq!(workflow.steps.len(), 2);
+        assert_eq!(result.warnings.len(), 1);
+
+        assert_eq!(workflow.steps[0].step_id, "createUser");
+        assert_eq!(
+            workflow.steps[0].outputs,
+            Some(serde_json::json!({"id": "$response.body.id"}))
+        );
+        assert_eq!(
+            workflow.steps[1].operation_id.as_deref(),
+            Some("getUserById")
+        );
+        assert_eq!(
+            workflow.steps[1].parameters[0].value,
+            serde_json::json!("$steps.createUser.outputs.id")
+        );
+    }
+
+    #[test]
+    fn test_credentials_become_inputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let openapi = dir.path().join("openapi.yaml");
+        std::fs::write(
+            &openapi,
+            r#"
+openapi: 3.0.0
+info:
+  title: Shop
+  version: 1.0.0
+components:
+  securitySchemes:
+    key:
+      type: apiKey
+      in: header
+      name: X-API-Key
+paths:
+  /login:
+    post:
+      operationId: login
+      responses:
+        '200':
+          description: OK
+  /cart:
+    get:
+      operationId: getCart
+      parameters:
+        - name: session
+          in: cookie
+          schema:
+            type: string
+        - name: X-Tenant
+          in: header
+          schema:
+            type: string
+      responses:
+        '200':
+          description: OK
+"#,
+        )
+        .unwrap();
+        let mut resolver = OpenApiResolver::new(dir.path());
+        resolver.load_spec("shop", &openapi).unwrap();
+
+        let login_token = "eyJhbGciOiJIUzI1NiJ9.login-issued-token";
+        let recorded_token = "eyJhbGciOiJIUzI1NiJ9.recorded-user-token";
+        let headers = |token: &str| {
+            vec![
+                ("Authorization".to_string(), format!("Bearer {}", token)),
+                ("X-API-Key".to_string(), "sk_live_recorded_key".to_string()),
+                (
+                    "Cookie".to_string(),
+                    "session=s3cr3t-session-id".to_string(),
+                ),
+                ("X-Tenant".to_string(), "acme".to_string()),
+            ]
+        };
+        let flow = RecordedFlow {
+            name: "cart".to_string(),
+            exchanges: vec![
+                RecordedExchange {
+                    method: "POST".to_string(),
+                    path: "/login".to_string(),
+                    headers: headers(recorded_token),
+                    response: Some(RecordedResponse {
+                        status: 200,
+                        headers: Vec::new(),
+                        body: Some(serde_json::json!({"token": login_token})),
+                    }),
+                    ..Default::default()
+                },
+                RecordedExchange {
+                    method: "GET".to_string(),
+                    path: "/cart".to_string(),
+                    headers: headers(login_token),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let workflow = build_workflow(&flow, &resolver).workflow;
+        let yaml = serde_yaml::to_string(&workflow).unwrap();
+        for secret in [
+            recorded_token,
+            "sk_live_recorded_key",
+            "s3cr3t-session-id",
+            "acme",
+        ] {
+            assert!(!yaml.contains(secret), "{secret} leaked:\n{yaml}");
+        }
+
+        // 後のステップのトークンはログインの応答から取る
+        let value = |step: usize, name: &str| {
+            workflow.steps[step]
+                .parameters
+                .iter()
+                .find(|p| p.name == name)
+                .map(|p| p.value.clone())
+                .unwrap()
+        };
+        assert_eq!(
+            value(0, "Authorization"),
+            serde_json::json!("$inputs.authorization")
+        );
+        assert_eq!(
+            value(1, "Authorization"),
+            serde_json::json!("Bearer $steps.login.outputs.token")
+        );
+        // 同じ値は同じ入力を使う
+        assert_eq!(
+            value(0, "X-API-Key"),
+            serde_json::json!("$inputs.x_api_key")
+        );
+        assert_eq!(
+            value(1, "X-API-Key"),
+            serde_json::json!("$inputs.x_api_key")
+        );
+        assert_eq!(value(1, "session"), serde_json::json!("$inputs.session"));
+        assert_eq!(value(1, "X-Tenant"), serde_json::json!("$inputs.x_tenant"));
+
+        let properties = &workflow.inputs.as_ref().unwrap()["properties"];
+        assert_eq!(
+            properties["authorization"],
+            serde_json::json!({"type": "string"})
+        );
+        assert!(properties["session"].get("default").is_none());
+    }
+}
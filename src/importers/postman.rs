@@ -0,0 +1,615 @@
+//! Postman Collection (v2.0 / v2.1) importer
+//!
+//! Every folder holding requests becomes a flow; requests at the top level of
+//! the collection form a flow named after the collection. Variables assigned
+//! from responses in test scripts (`pm.environment.set("token",
+//! pm.response.json().token)`) are recorded as [`Capture`]s.
+
+use super::{
+    Capture, RecordedBody, RecordedExchange, RecordedFlow, RecordedResponse, fields_to_object,
+    file_value, parse_body, parse_query, split_url,
+};
+use crate::error::{HornetError, Result};
+use crate::models::expression::{ExpressionSource, RuntimeExpression};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Parse a Postman collection into recorded flows
+pub fn parse_collection(collection: &serde_json::Value) -> Result<Vec<RecordedFlow>> {
+    let info = collection
+        .get("info")
+        .ok_or_else(|| import_error("Not a Postman collection: missing 'info'"))?;
+    let items = collection
+        .get("item")
+        .and_then(|i| i.as_array())
+        .ok_or_else(|| import_error("Not a Postman collection: missing 'item'"))?;
+
+    let name = info
+        .get("name")
+        .and_then(|n| n.as_str())
+        .unwrap_or("postman")
+        .to_string();
+    let description = description_text(info.get("description"));
+    let variables = parse_variables(collection.get("variable"));
+
+    let mut flows = Vec::new();
+    let mut root = RecordedFlow {
+        name: name.clone(),
+        description,
+        variables: variables.clone(),
+        ..Default::default()
+    };
+    collect_items(items, &[], &variables, &mut root, &mut flows);
+
+    if !root.exchanges.is_empty() {
+        flows.insert(0, root);
+    }
+
+    Ok(flows)
+}
+
+fn collect_items(
+    items: &[serde_json::Value],
+    folder_path: &[String],
+    variables: &[(String, serde_json::Value)],
+    current: &mut RecordedFlow,
+    flows: &mut Vec<RecordedFlow>,
+) {
+    for item in items {
+        if let Some(children) = item.get("item").and_then(|i| i.as_array()) {
+            let folder = item
+                .get("name")
+                .and_then(|n| n.as_str())
+                .unwrap_or("folder")
+                .to_string();
+            let mut path = folder_path.to_vec();
+            path.push(folder);
+
+            let mut variables = variables.to_vec();
+            variables.extend(parse_variables(item.get("variable")));
+
+            let mut flow = RecordedFlow {
+                name: path.join(" "),
+                description: description_text(item.get("description")),
+                variables: variables.clone(),
+                ..Default::default()
+            };
+            collect_items(children, &path, &variables, &mut flow, flows);
+            if !flow.exchanges.is_empty() {
+                flows.push(flow);
+            }
+        } else if let Some(exchange) = parse_item(item) {
+            current.exchanges.push(exchange);
+        }
+    }
+}
+
+/// Parse a request item
+fn parse_item(item: &serde_json::Value) -> Option<RecordedExchange> {
+    let request = item.get("request")?;
+
+    // リクエストはURL文字列のみの場合もある
+    let (method, url) = match request {
+        serde_json::Value::String(url) => ("GET".to_string(), serde_json::json!(url)),
+        _ => (
+            request
+                .get("method")
+                .and_then(|m| m.as_str())
+                .unwrap_or("GET")
+                .to_uppercase(),
+            request.get("url")?.clone(),
+        ),
+    };
+
+    let (path, query) = parse_url(&url);
+    let headers = parse_key_values(request.get("header"));
+    let body = request
+        .get("body")
+        .and_then(|b| parse_request_body(b, &headers));
+
+    let response = item
+        .get("response")
+        .and_then(|r| r.as_array())
+        .and_then(|r| r.first())
+        .map(parse_saved_response);
+
+    let captures = item
+        .get("event")
+        .and_then(|e| e.as_array())
+        .map(|events| {
+            events
+                .iter()
+                .filter(|e| e.get("listen").and_then(|l| l.as_str()) == Some("test"))
+                .flat_map(|e| script_lines(e.get("script")))
+                .collect::<Vec<_>>()
+        })
+        .map(|lines| parse_captures(&lines))
+        .unwrap_or_default();
+
+    Some(RecordedExchange {
+        name: item.get("name").and_then(|n| n.as_str()).map(String::from),
+        method,
+        path,
+        query,
+        headers,
+        body,
+        response,
+        captures,
+    })
+}
+
+/// Extract the request path and query from a Postman URL (string or object)
+fn parse_url(url: &serde_json::Value) -> (String, Vec<(String, String)>) {
+    let raw = match url {
+        serde_json::Value::String(raw) => raw.clone(),
+        _ => url
+            .get("raw")
+            .and_then(|r| r.as_str())
+            .unwrap_or_default()
+            .to_string(),
+    };
+
+    let path_variables = parse_key_values(url.get("variable"));
+
+    let path = match url.get("path").and_then(|p| p.as_array()) {
+        Some(segments) => {
+            let segments: Vec<String> = segments
+                .iter()
+                .map(|s| match s {
+                    serde_json::Value::String(s) => s.clone(),
+                    other => other
+                        .get("value")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                })
+                .collect();
+            format!("/{}", segments.join("/"))
+        }
+        None => split_url(&strip_host_variable(&raw)).0,
+    };
+
+    // `:id` 形式のパス変数を値に置き換える
+    let path = path
+        .split('/')
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(name) => path_variables
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.clone())
+                .unwrap_or_else(|| format!("{{{{{}}}}}", name)),
+            None => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+
+    let query = match url.get("query").and_then(|q| q.as_array()) {
+        Some(_) => parse_key_values(url.get("query")),
+        None => raw
+            .split_once('?')
+            .map(|(_, q)| parse_query(q))
+            .unwrap_or_default(),
+    };
+
+    (path, query)
+}
+
+/// Remove a leading `{{baseUrl}}`-style host variable from a raw URL
+fn strip_host_variable(raw: &str) -> String {
+    if raw.starts_with("{{")
+        && let Some(end) = raw.find("}}")
+    {
+        let rest = &raw[end + 2..];
+        return if rest.starts_with('/') {
+            rest.to_string()
+        } else {
+            format!("/{}", rest)
+        };
+    }
+    raw.to_string()
+}
+
+/// Parse `[{key, value, disabled}]` (headers, query, url variables)
+fn parse_key_values(list: Option<&serde_json::Value>) -> Vec<(String, String)> {
+    list.and_then(|l| l.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter(|i| !is_disabled(i))
+                .filter_map(|i| {
+                    let key = i.get("key")?.as_str()?.to_string();
+                    let value = i
+                        .get("value")
+                        .map(|v| match v {
+                            serde_json::Value::String(s) => s.clone(),
+                            serde_json::Value::Null => String::new(),
+                            other => other.to_string(),
+                        })
+                        .unwrap_or_default();
+                    Some((key, value))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parse collection or folder variables
+fn parse_variables(list: Option<&serde_json::Value>) -> Vec<(String, serde_json::Value)> {
+    list.and_then(|l| l.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter(|i| !is_disabled(i))
+                .filter_map(|i| {
+                    let key = i.get("key")?.as_str()?.to_string();
+                    Some((key, i.get("value").cloned().unwrap_or_default()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn is_disabled(item: &serde_json::Value) -> bool {
+    item.get("disabled").and_then(|d| d.as_bool()) == Some(true)
+}
+
+fn description_text(description: Option<&serde_json::Value>) -> Option<String> {
+    match description? {
+        serde_json::Value::String(s) => Some(s.clone()),
+        other => other
+            .get("content")
+            .and_then(|c| c.as_str())
+            .map(String::from),
+    }
+}
+
+fn placeholder_value_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"([:\[,]\s*)(\{\{[^{}]+\}\})").unwrap())
+}
+
+/// Parse a Postman request body
+fn parse_request_body(
+    body: &serde_json::Value,
+    headers: &[(String, String)],
+) -> Option<RecordedBody> {
+    if is_disabled(body) {
+        return None;
+    }
+    let header_type = headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+        .map(|(_, v)| v.clone());
+
+    match body.get("mode")?.as_str()? {
+        "raw" => {
+            let raw = body.get("raw")?.as_str()?;
+            if raw.trim().is_empty() {
+                return None;
+            }
+            let language = body
+                .pointer("/options/raw/language")
+                .and_then(|l| l.as_str());
+            let content_type = header_type.or_else(|| {
+                match language {
+                    Some("json") => Some("application/json"),
+                    Some("xml") => Some("application/xml"),
+                    Some("text") => Some("text/plain"),
+                    _ => None,
+                }
+                .map(String::from)
+            });
+
+            let mut payload = parse_body(content_type.as_deref(), raw);
+            // 引用符なしの `{{var}}` を含むJSONは文字列として扱ってから再度パースする
+            if payload.is_string()
+                && content_type
+                    .as_deref()
+                    .is_some_and(|ct| ct.contains("json"))
+            {
+                let quoted = placeholder_value_regex().replace_all(raw, "$1\"$2\"");
+                if let Ok(value) = serde_json::from_str(&quoted) {
+                    payload = value;
+                }
+            }
+
+            Some(RecordedBody {
+                content_type,
+                payload,
+            })
+        }
+        "urlencoded" => Some(RecordedBody {
+            content_type: Some(
+                header_type.unwrap_or_else(|| "application/x-www-form-urlencoded".to_string()),
+            ),
+            payload: fields_to_object(parse_key_values(body.get("urlencoded"))),
+        }),
+        "formdata" => {
+            let fields = body
+                .get("formdata")
+                .and_then(|f| f.as_array())
+                .map(|items| {
+                    items
+                        .iter()
+                        .filter(|i| !is_disabled(i))
+                        .filter_map(|i| {
+                            let key = i.get("key")?.as_str()?.to_string();
+                            let value = if i.get("type").and_then(|t| t.as_str()) == Some("file") {
+                                let src = match i.get("src") {
+                                    Some(serde_json::Value::Array(srcs)) => {
+                                        srcs.first().and_then(|s| s.as_str())
+                                    }
+                                    Some(src) => src.as_str(),
+                                    None => None,
+                                };
+                                file_value(src.unwrap_or_default())
+                            } else {
+                                serde_json::json!(
+                                    i.get("value").and_then(|v| v.as_str()).unwrap_or_default()
+                                )
+                            };
+                            Some((key, value))
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+
+            Some(RecordedBody {
+                content_type: Some("multipart/form-data".to_string()),
+                payload: fields_to_object(fields),
+            })
+        }
+        "graphql" => {
+            let graphql = body.get("graphql")?;
+            let variables = graphql
+                .get("variables")
+                .and_then(|v| v.as_str())
+                .and_then(|v| serde_json::from_str::<serde_json::Value>(v).ok())
+                .unwrap_or_default();
+            Some(RecordedBody {
+                content_type: Some("application/json".to_string()),
+                payload: serde_json::json!({
+                    "query": graphql.get("query").cloned().unwrap_or_default(),
+                    "variables": variables,
+                }),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Parse a saved example response
+fn parse_saved_response(response: &serde_json::Value) -> RecordedResponse {
+    let headers = parse_key_values(response.get("header"));
+    let content_type = headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+        .map(|(_, v)| v.as_str());
+
+    RecordedResponse {
+        status: response.get("code").and_then(|c| c.as_u64()).unwrap_or(200) as u16,
+        body: response
+            .get("body")
+            .and_then(|b| b.as_str())
+            .filter(|b| !b.is_empty())
+            .map(|b| parse_body(content_type.or(Some("application/json")), b)),
+        headers,
+    }
+}
+
+fn script_lines(script: Option<&serde_json::Value>) -> Vec<String> {
+    match script.and_then(|s| s.get("exec")) {
+        Some(serde_json::Value::Array(lines)) => lines
+            .iter()
+            .filter_map(|l| l.as_str())
+            .flat_map(|l| l.lines().map(String::from).collect::<Vec<_>>())
+            .collect(),
+        Some(serde_json::Value::String(s)) => s.lines().map(String::from).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn alias_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?:var|let|const)\s+([A-Za-z_$][\w$]*)\s*=\s*(pm\.response\.json\(\)|JSON\.parse\(\s*responseBody\s*\))\s*;?",
+        )
+        .unwrap()
+    })
+}
+
+fn set_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r#"(?:pm\.(?:environment|collectionVariables|globals|variables)\.set|postman\.set(?:Environment|Global)Variable)\(\s*["']([^"']+)["']\s*,\s*(.+?)\s*\)\s*;?\s*$"#,
+        )
+        .unwrap()
+    })
+}
+
+/// Find variables assigned from the response in test script lines
+pub fn parse_captures(lines: &[String]) -> Vec<Capture> {
+    let mut aliases: Vec<String> = Vec::new();
+    let mut captures = Vec::new();
+
+    for line in lines {
+        let line = line.trim();
+        if let Some(caps) = alias_regex().captures(line) {
+            aliases.push(caps[1].to_string());
+        }
+        let Some(caps) = set_regex().captures(line) else {
+            continue;
+        };
+        if let Some(expression) = parse_response_access(&caps[2], &aliases) {
+            captures.push(Capture::new(&caps[1], expression));
+        }
+    }
+
+    captures
+}
+
+/// Interpret a script expression reading from the response
+fn parse_response_access(expr: &str, aliases: &[String]) -> Option<RuntimeExpression> {
+    let expr = expr.trim();
+
+    for prefix in ["pm.response.headers.get(", "postman.getResponseHeader("] {
+        if let Some(rest) = expr.strip_prefix(prefix) {
+            let name = rest
+                .strip_suffix(')')?
+                .trim()
+                .trim_matches(|c| c == '"' || c == '\'');
+            return Some(RuntimeExpression::Response(ExpressionSource::Header(
+                name.to_string(),
+            )));
+        }
+    }
+    if expr == "pm.response.code" || expr == "responseCode.code" {
+        return Some(RuntimeExpression::StatusCode);
+    }
+
+    let roots = ["pm.response.json()", "JSON.parse(responseBody)"];
+    let rest = roots
+        .iter()
+        .find_map(|root| expr.strip_prefix(root))
+        .or_else(|| {
+            aliases.iter().find_map(|alias| {
+                expr.strip_prefix(alias.as_str())
+                    .filter(|r| r.is_empty() || r.starts_with(['.', '[']))
+            })
+        })?;
+
+    parse_accessor(rest).map(|path| RuntimeExpression::Response(ExpressionSource::Body(path)))
+}
+
+/// Parse a property access chain (`.a[0]["b"]`) into path segments
+fn parse_accessor(mut rest: &str) -> Option<Vec<String>> {
+    let mut path = Vec::new();
+    while !rest.is_empty() {
+        if let Some(r) = rest.strip_prefix('.') {
+            let end = r
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(r.len());
+            if end == 0 {
+                return None;
+            }
+            path.push(r[..end].to_string());
+            rest = &r[end..];
+        } else if let Some(r) = rest.strip_prefix('[') {
+            let end = r.find(']')?;
+            path.push(
+                r[..end]
+                    .trim()
+                    .trim_matches(|c| c == '"' || c == '\'')
+                    .to_string(),
+            );
+            rest = &r[end + 1..];
+        } else {
+            return None;
+        }
+    }
+    Some(path)
+}
+
+fn import_error(message: &str) -> HornetError {
+    HornetError::ImportError(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_captures() {
+        let lines: Vec<String> = [
+            "var jsonData = pm.response.json();",
+            "pm.environment.set(\"token\", jsonData.token);",
+            "pm.collectionVariables.set('userId', pm.response.json().user[\"id\"]);",
+            "pm.globals.set(\"trace\", pm.response.headers.get(\"X-Trace-Id\"));",
+            "pm.environment.set(\"now\", Date.now());",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let captures = parse_captures(&lines);
+        assert_eq!(captures.len(), 3);
+        assert_eq!(captures[0].variable, "token");
+        assert_eq!(captures[0].expression.to_string(), "$response.body.token");
+        assert_eq!(captures[1].expression.to_string(), "$response.body.user.id");
+        assert_eq!(
+            captures[2].expression.to_string(),
+            "$response.header.X-Trace-Id"
+        );
+    }
+
+    #[test]
+    fn test_parse_collection() {
+        let collection = json!({
+            "info": {"name": "Users", "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"},
+            "variable": [{"key": "username", "value": "alice"}],
+            "item": [
+                {
+                    "name": "Lifecycle",
+                    "item": [
+                        {
+                            "name": "Create user",
+                            "request": {
+                                "method": "POST",
+                                "header": [{"key": "Content-Type", "value": "application/json"}],
+                                "url": {"raw": "{{baseUrl}}/users", "host": ["{{baseUrl}}"], "path": ["users"]},
+                                "body": {"mode": "raw", "raw": "{\"name\": \"{{username}}\", \"age\": {{age}}}"}
+                            },
+                            "event": [{
+                                "listen": "test",
+                                "script": {"exec": ["pm.environment.set(\"userId\", pm.response.json().id);"]}
+                            }]
+                        },
+                        {
+                            "name": "Get user",
+                            "request": {
+                                "method": "GET",
+                                "url": {
+                                    "raw": "{{baseUrl}}/users/:userId?verbose=true",
+                                    "path": ["users", ":userId"],
+                                    "query": [{"key": "verbose", "value": "true"}, {"key": "x", "value": "1", "disabled": true}],
+                                    "variable": [{"key": "userId", "value": "{{userId}}"}]
+                                }
+                            }
+                        }
+                    ]
+                },
+                {"name": "Health", "request": "https://api.example.com/health"}
+            ]
+        });
+
+        let flows = parse_collection(&collection).unwrap();
+        assert_eq!(flows.len(), 2);
+        assert_eq!(flows[0].name, "Users");
+        assert_eq!(flows[0].exchanges[0].path, "/health");
+
+        let lifecycle = &flows[1];
+        assert_eq!(lifecycle.name, "Lifecycle");
+        assert_eq!(lifecycle.variables[0].0, "username");
+
+        let create = &lifecycle.exchanges[0];
+        assert_eq!(create.method, "POST");
+        assert_eq!(create.path, "/users");
+        assert_eq!(
+            create.body.as_ref().unwrap().payload,
+            json!({"name": "{{username}}", "age": "{{age}}"})
+        );
+        assert_eq!(create.captures[0].variable, "userId");
+
+        let get = &lifecycle.exchanges[1];
+        assert_eq!(get.path, "/users/{{userId}}");
+        assert_eq!(get.query, vec![("verbose".to_string(), "true".to_string())]);
+    }
+
+    #[test]
+    fn test_rejects_non_collection() {
+        assert!(parse_collection(&json!({"log": {}})).is_err());
+    }
+}
@@ -2,10 +2,14 @@ pub mod cli;
 pub mod commands;
 pub mod converters;
 pub mod error;
+pub mod fuzz;
+pub mod generator;
 pub mod graph;
+pub mod importers;
 pub mod loader;
 pub mod lsp;
 pub mod models;
+pub mod payload;
 pub mod runner;
 pub mod server;
 pub mod telemetry;
@@ -236,6 +236,7 @@ workflows:
                 on_success: None,
                 on_failure: None,
                 outputs: None,
+                extensions: Default::default(),
             }],
             success_criteria: None,
             outputs: None,
@@ -253,6 +254,7 @@ workflows:
             source_descriptions: vec![],
             workflows: vec![workflow],
             components: None,
+            extensions: Default::default(),
         };
 
         // Save and check order
@@ -295,6 +297,7 @@ workflows:
             source_descriptions: vec![],
             workflows: vec![],
             components: None,
+            extensions: Default::default(),
         };
 
         let output_file = NamedTempFile::new().unwrap();
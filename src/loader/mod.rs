@@ -6,6 +6,6 @@ pub mod source_resolver;
 
 pub use arazzo::{load_arazzo, save_arazzo};
 pub use openapi::load_openapi;
-pub use openapi_resolver::{OpenApiResolver, OperationRef};
+pub use openapi_resolver::{OpenApiResolver, OperationRef, PathMatch};
 pub use project::{ProjectMetadata, ProjectScanner};
 pub use source_resolver::{SourceDescriptionResolver, SourceLoadError, SourceLoadResult};
@@ -164,6 +164,78 @@ impl OpenApiResolver {
         None
     }
 
+    /// 具体的なリクエストパスをテンプレートパスに照合して操作を検索
+    ///
+    /// サーバーURLのパス部分（例: `/v1`）が先頭にある場合は取り除いて照合する。
+    /// 複数のテンプレートに一致する場合はリテラルセグメントが最も多いものを選ぶ。
+    pub fn match_path(&self, method: &str, concrete_path: &str) -> Option<PathMatch> {
+        let method_upper = method.to_uppercase();
+        let concrete_path = concrete_path.split(['?', '#']).next().unwrap_or_default();
+        let mut best: Option<(usize, PathMatch)> = None;
+
+        for (source_name, spec) in &self.specs {
+            let Some(paths) = &spec.paths else {
+                continue;
+            };
+
+            // サーバーのベースパスを取り除いた候補
+            let mut candidates = vec![concrete_path.to_string()];
+            for server in &spec.servers {
+                let base = server_base_path(&server.url);
+                if !base.is_empty()
+                    && let Some(rest) = concrete_path.strip_prefix(base.as_str())
+                    && (rest.is_empty() || rest.starts_with('/'))
+                {
+                    candidates.push(if rest.is_empty() {
+                        "/".to_string()
+                    } else {
+                        rest.to_string()
+                    });
+                }
+            }
+
+            for (template, path_item) in paths.iter() {
+                let op_option = match method_upper.as_str() {
+                    "GET" => &path_item.get,
+                    "POST" => &path_item.post,
+                    "PUT" => &path_item.put,
+                    "DELETE" => &path_item.delete,
+                    "PATCH" => &path_item.patch,
+                    "OPTIONS" => &path_item.options,
+                    "HEAD" => &path_item.head,
+                    "TRACE" => &path_item.trace,
+                    _ => &None,
+                };
+                let Some(op) = op_option else {
+                    continue;
+                };
+
+                for candidate in &candidates {
+                    let Some((literals, path_params)) = match_template(template, candidate) else {
+                        continue;
+                    };
+                    if best.as_ref().is_some_and(|(score, _)| *score >= literals) {
+                        continue;
+                    }
+                    best = Some((
+                        literals,
+                        PathMatch {
+                            operation_ref: OperationRef {
+                                source_name: source_name.clone(),
+                                method: method_upper.clone(),
+                                path: template.clone(),
+                            },
+                            operation: op.clone(),
+                            path_params,
+                        },
+                    ));
+                }
+            }
+        }
+
+        best.map(|(_, m)| m)
+    }
+
     /// ファイル一覧を取得
     pub fn list_files(&self) -> Vec<String> {
         self.specs.keys().cloned().collect()
@@ -177,3 +249,114 @@ pub struct OperationRef {
     pub method: String,
     pub path: String,
 }
+
+/// テンプレートパスへの照合結果
+#[derive(Debug, Clone)]
+pub struct PathMatch {
+    pub operation_ref: OperationRef,
+    pub operation: Operation,
+    /// パスパラメータ名と具体的な値
+    pub path_params: Vec<(String, String)>,
+}
+
+/// サーバーURLからパス部分を取り出す（末尾の `/` は除く）
+fn server_base_path(url: &str) -> String {
+    let without_scheme = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let path = if url.contains("://") {
+        without_scheme
+            .find('/')
+            .map(|i| &without_scheme[i..])
+            .unwrap_or("")
+    } else {
+        without_scheme
+    };
+    path.trim_end_matches('/').to_string()
+}
+
+/// テンプレートパス（`/users/{id}`）と具体的なパスを照合する
+///
+/// 一致した場合はリテラルセグメント数とパスパラメータを返す。
+fn match_template(template: &str, path: &str) -> Option<(usize, Vec<(String, String)>)> {
+    let template_segments: Vec<&str> = template.trim_matches('/').split('/').collect();
+    let path_segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    if template_segments.len() != path_segments.len() {
+        return None;
+    }
+
+    let mut literals = 0;
+    let mut params = Vec::new();
+    for (template_segment, segment) in template_segments.iter().zip(&path_segments) {
+        if !template_segment.contains('{') {
+            if template_segment != segment {
+                return None;
+            }
+            literals += 1;
+            continue;
+        }
+
+        // `{id}.json` や `v{version}` のような部分テンプレートにも対応する
+        let mut pattern = String::from("^");
+        let mut names = Vec::new();
+        let mut rest = *template_segment;
+        while let Some(start) = rest.find('{') {
+            let end = start + rest[start..].find('}')?;
+            pattern.push_str(&regex::escape(&rest[..start]));
+            pattern.push_str("(.+?)");
+            names.push(rest[start + 1..end].to_string());
+            rest = &rest[end + 1..];
+        }
+        pattern.push_str(&regex::escape(rest));
+        pattern.push('$');
+
+        let captures = regex::Regex::new(&pattern).ok()?.captures(segment)?;
+        for (i, name) in names.into_iter().enumerate() {
+            params.push((name, captures.get(i + 1)?.as_str().to_string()));
+        }
+    }
+
+    Some((literals, params))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_template() {
+        let (literals, params) = match_template("/users/{id}/posts", "/users/42/posts").unwrap();
+        assert_eq!(literals, 2);
+        assert_eq!(params, vec![("id".to_string(), "42".to_string())]);
+
+        let (_, params) = match_template("/files/{name}.json", "/files/report.json").unwrap();
+        assert_eq!(params, vec![("name".to_string(), "report".to_string())]);
+
+        assert!(match_template("/users/{id}", "/users/42/posts").is_none());
+        assert!(match_template("/users/me", "/users/42").is_none());
+    }
+
+    #[test]
+    fn test_server_base_path() {
+        assert_eq!(server_base_path("https://api.example.com/v1/"), "/v1");
+        assert_eq!(server_base_path("http://localhost:8080"), "");
+        assert_eq!(server_base_path("/api"), "/api");
+    }
+
+    #[test]
+    fn test_match_path() {
+        let mut resolver = OpenApiResolver::new("tests/fixtures/multi_project/project2");
+        resolver
+            .load_spec(
+                "userAPI",
+                Path::new("tests/fixtures/multi_project/project2/openapi.yaml"),
+            )
+            .unwrap();
+
+        let matched = resolver.match_path("get", "/users/123").unwrap();
+        assert_eq!(matched.operation_ref.path, "/users/{userId}");
+        assert_eq!(
+            matched.path_params,
+            vec![("userId".to_string(), "123".to_string())]
+        );
+        assert!(resolver.match_path("GET", "/unknown").is_none());
+    }
+}
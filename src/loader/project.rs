@@ -33,66 +33,69 @@ impl ProjectScanner {
     pub fn scan_projects(&self) -> Result<Vec<ProjectMetadata>> {
         let mut projects = Vec::new();
 
-        // Check if the root directory itself contains arazzo.yaml (single-project mode)
-        let root_arazzo = self.root_dir.join("arazzo.yaml");
-        if root_arazzo.exists() {
-            match self.load_project_metadata(&self.root_dir) {
+        for path in self.project_dirs()? {
+            match self.load_project_metadata(&path) {
                 Ok(meta) => projects.push(meta),
                 Err(e) => {
+                    // ログ警告して続行
                     eprintln!(
-                        "Warning: Failed to load project at root {}: {}",
-                        self.root_dir.display(),
+                        "Warning: Failed to load project at {}: {}",
+                        path.display(),
                         e
                     );
                 }
             }
         }
 
+        Ok(projects)
+    }
+
+    /// arazzo.yaml を持つディレクトリ（ルート自身が先頭）
+    pub fn project_dirs(&self) -> Result<Vec<PathBuf>> {
+        let mut dirs = Vec::new();
+
+        // Check if the root directory itself contains arazzo.yaml (single-project mode)
+        if self.root_dir.join("arazzo.yaml").exists() {
+            dirs.push(self.root_dir.clone());
+        }
+
         // Scan subdirectories for additional projects (multi-project mode)
+        let mut subdirs = Vec::new();
         for entry in fs::read_dir(&self.root_dir).map_err(HornetError::IoError)? {
             let entry = entry.map_err(HornetError::IoError)?;
-
             let path = entry.path();
-
-            if path.is_dir() {
-                let arazzo_path = path.join("arazzo.yaml");
-
-                if arazzo_path.exists() {
-                    match self.load_project_metadata(&path) {
-                        Ok(meta) => projects.push(meta),
-                        Err(e) => {
-                            // ログ警告して続行
-                            eprintln!(
-                                "Warning: Failed to load project at {}: {}",
-                                path.display(),
-                                e
-                            );
-                        }
-                    }
-                }
+            if path.is_dir() && path.join("arazzo.yaml").exists() {
+                subdirs.push(path);
             }
         }
+        subdirs.sort();
+        dirs.extend(subdirs);
 
-        Ok(projects)
+        Ok(dirs)
     }
 
-    /// 単一プロジェクトのメタデータを読み込み
-    pub fn load_project_metadata(&self, project_dir: &Path) -> Result<ProjectMetadata> {
+    /// ディレクトリのプロジェクト名
+    pub fn project_name(&self, project_dir: &Path) -> Result<String> {
         // Get project name from directory name, or use canonical path's file_name for root dir
-        let project_name = if project_dir == self.root_dir {
+        if project_dir == self.root_dir {
             // For root directory, use the absolute path's last component
-            project_dir
+            Ok(project_dir
                 .canonicalize()
                 .ok()
                 .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
-                .unwrap_or_else(|| "default".to_string())
+                .unwrap_or_else(|| "default".to_string()))
         } else {
-            project_dir
+            Ok(project_dir
                 .file_name()
                 .and_then(|n| n.to_str())
                 .ok_or_else(|| HornetError::InvalidPath("Invalid project directory name".into()))?
-                .to_string()
-        };
+                .to_string())
+        }
+    }
+
+    /// 単一プロジェクトのメタデータを読み込み
+    pub fn load_project_metadata(&self, project_dir: &Path) -> Result<ProjectMetadata> {
+        let project_name = self.project_name(project_dir)?;
 
         let arazzo_path = project_dir.join("arazzo.yaml");
 
@@ -297,6 +300,7 @@ paths:
             source_descriptions: vec![],
             workflows: vec![],
             components: None,
+            extensions: Default::default(),
         };
 
         let scanner = ProjectScanner::new(project_dir);
@@ -327,6 +331,7 @@ paths:
             }],
             workflows: vec![],
             components: None,
+            extensions: Default::default(),
         };
 
         let scanner = ProjectScanner::new(project_dir);
@@ -358,6 +363,7 @@ paths:
             }],
             workflows: vec![],
             components: None,
+            extensions: Default::default(),
         };
 
         let scanner = ProjectScanner::new(project_dir);
@@ -418,6 +424,7 @@ paths: {}
             ],
             workflows: vec![],
             components: None,
+            extensions: Default::default(),
         };
 
         let scanner = ProjectScanner::new(project_dir);
@@ -462,6 +469,7 @@ paths: {}
             ],
             workflows: vec![],
             components: None,
+            extensions: Default::default(),
         };
 
         let scanner = ProjectScanner::new(project_dir);
@@ -511,6 +519,7 @@ paths: {}
             ],
             workflows: vec![],
             components: None,
+            extensions: Default::default(),
         };
 
         let scanner = ProjectScanner::new(project_dir);
@@ -557,6 +566,7 @@ paths: {}
             }],
             workflows: vec![],
             components: None,
+            extensions: Default::default(),
         };
 
         let scanner = ProjectScanner::new(project_dir);
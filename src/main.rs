@@ -1,8 +1,10 @@
 use clap::Parser;
 use hornet2::{
-    Result,
-    cli::{Cli, Commands, ExportFormat},
+    HornetError, Result,
+    cli::{BaselineFlags, Cli, Commands, ExportFormat, SampleModeArg},
     commands,
+    converters::common::parse_duration,
+    generator::sample::SampleMode,
 };
 
 #[tokio::main]
@@ -30,8 +32,11 @@ async fn main() -> Result<()> {
         } => {
             commands::execute_serve(&root_dir, port, lsp).await?;
         }
+        Commands::Convert { list: true, .. } => {
+            commands::execute_list_converters()?;
+        }
         Commands::Convert {
-            arazzo,
+            arazzo: Some(arazzo),
             to,
             output,
             workflow,
@@ -39,6 +44,8 @@ async fn main() -> Result<()> {
             vus,
             duration,
             iterations,
+            profile,
+            ..
         } => {
             commands::execute_convert(commands::ConvertCommandArgs {
                 arazzo_path: &arazzo,
@@ -49,8 +56,12 @@ async fn main() -> Result<()> {
                 vus,
                 duration: duration.as_deref(),
                 iterations,
+                profile_path: profile.as_deref(),
             })?;
         }
+        Commands::Convert { arazzo: None, .. } => {
+            unreachable!("clap requires --arazzo unless --list is given")
+        }
         Commands::Run {
             arazzo,
             engine,
@@ -59,6 +70,17 @@ async fn main() -> Result<()> {
             vus,
             duration,
             iterations,
+            profile,
+            inputs,
+            negative,
+            report,
+            record,
+            replay,
+            match_body,
+            redact,
+            baseline,
+            history,
+            environment,
         } => {
             commands::execute_run(commands::RunCommandArgs {
                 arazzo_path: &arazzo,
@@ -68,7 +90,164 @@ async fn main() -> Result<()> {
                 vus,
                 duration: duration.as_deref(),
                 iterations,
+                profile_path: profile.as_deref(),
+                inputs: inputs.as_deref(),
+                negative,
+                report_path: report.as_deref(),
+                record_path: record.as_deref(),
+                replay_path: replay.as_deref(),
+                match_body: match_body.as_deref(),
+                redact: &redact,
+                baseline: baseline_args(&baseline),
+                history,
+                environment: environment.as_deref(),
+            })
+            .await?;
+        }
+        Commands::Load {
+            arazzo,
+            workflow,
+            base_url,
+            vus,
+            duration,
+            iterations,
+            rate,
+            stages,
+            max_vus,
+            profile,
+            search,
+            max_rate,
+            rate_step,
+            agents,
+            listen,
+            timeout,
+            output,
+            baseline,
+        } => {
+            commands::execute_load(commands::LoadCommandArgs {
+                arazzo_path: &arazzo,
+                workflow_id: workflow.as_deref(),
+                base_url: base_url.as_deref(),
+                vus,
+                duration: duration.as_deref(),
+                iterations,
+                rate,
+                stages: stages.as_deref(),
+                max_vus,
+                profile_path: profile.as_deref(),
+                search: search.as_deref(),
+                max_rate,
+                rate_step,
+                agents,
+                listen: &listen,
+                timeout: timeout.as_deref(),
+                output_path: output.as_deref(),
+                baseline: baseline_args(&baseline),
+            })
+            .await?;
+        }
+        Commands::Agent { coordinator, name } => {
+            commands::execute_agent(commands::AgentCommandArgs {
+                coordinator: &coordinator,
+                name: name.as_deref(),
+            })
+            .await?;
+        }
+        Commands::Import {
+            input,
+            from,
+            openapi,
+            output,
+        } => {
+            commands::execute_import(commands::ImportCommandArgs {
+                input_path: &input,
+                from: &from,
+                openapi_paths: &openapi,
+                output_path: &output,
+            })?;
+        }
+        Commands::Record {
+            openapi,
+            output,
+            target,
+            port,
+            name,
+        } => {
+            commands::execute_record(commands::RecordCommandArgs {
+                openapi_paths: &openapi,
+                output_path: &output,
+                target: target.as_deref(),
+                port,
+                name: &name,
+            })
+            .await?;
+        }
+        Commands::Generate {
+            openapi,
+            output,
+            overwrite,
+            dry_run,
+        } => {
+            commands::execute_generate(commands::GenerateCommandArgs {
+                openapi_paths: &openapi,
+                output_path: &output,
+                overwrite,
+                dry_run,
+            })?;
+        }
+        Commands::Sample {
+            openapi,
+            operation,
+            mode,
+            seed,
+        } => {
+            commands::execute_sample(commands::SampleCommandArgs {
+                openapi_paths: &openapi,
+                operation_id: &operation,
+                mode: match mode {
+                    SampleModeArg::Minimal => SampleMode::Minimal,
+                    SampleModeArg::Maximal => SampleMode::Maximal,
+                    SampleModeArg::Random => SampleMode::Random,
+                },
+                seed,
+            })?;
+        }
+        Commands::Mock {
+            openapi,
+            arazzo,
+            port,
+            stateful,
+        } => {
+            commands::execute_mock(commands::MockCommandArgs {
+                openapi_paths: &openapi,
+                arazzo_path: arazzo.as_deref(),
+                port,
+                stateful,
+            })
+            .await?;
+        }
+        Commands::Fuzz {
+            arazzo,
+            workflow,
+            base_url,
+            variants,
+            seed,
+            timeout,
+            output,
+        } => {
+            let timeout = parse_duration(&timeout).ok_or_else(|| {
+                HornetError::ValidationError(format!("Invalid timeout: {}", timeout))
             })?;
+            commands::execute_fuzz(commands::FuzzCommandArgs {
+                arazzo_path: &arazzo,
+                workflow_id: workflow.as_deref(),
+                base_url: base_url.as_deref(),
+                variants,
+                seed,
+                timeout,
+                output_path: &output,
+            })
+            .await?;
         }
         Commands::ExportOpenapi { format, output } => {
             commands::execute_export_openapi(
@@ -92,3 +271,12 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+fn baseline_args(flags: &BaselineFlags) -> commands::BaselineArgs<'_> {
+    commands::BaselineArgs {
+        baseline_path: flags.baseline.as_deref(),
+        save_path: flags.save_baseline.as_deref(),
+        tolerance: flags.tolerance.as_deref(),
+        diff_path: flags.diff_output.as_deref(),
+    }
+}
@@ -21,6 +21,10 @@ pub struct ArazzoSpec {
     /// Additional components that can be referenced
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub components: Option<Components>,
+
+    /// Additional data for extensions (x-* fields)
+    #[serde(flatten)]
+    pub extensions: IndexMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -156,6 +160,10 @@ pub struct Step {
     /// Output values from the step
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub outputs: Option<serde_json::Value>,
+
+    /// Additional data for extensions (x-* fields)
+    #[serde(flatten)]
+    pub extensions: IndexMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -183,6 +191,19 @@ pub struct RequestBody {
 
     /// The payload (can be a runtime expression)
     pub payload: serde_json::Value,
+
+    /// Values to inject into the payload before it is sent
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub replacements: Vec<PayloadReplacement>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PayloadReplacement {
+    /// A JSON Pointer (structured payloads) or XPath (XML payloads) to the value to replace
+    pub target: String,
+
+    /// The value to set at the target (can be a runtime expression)
+    pub value: serde_json::Value,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,404 @@
+//! Arazzo runtime expressions
+//!
+//! https://spec.openapis.org/arazzo/latest.html#runtime-expressions
+//!
+//! Expressions appear either as a whole value (`$steps.login.outputs.token`)
+//! or embedded in a string (`Bearer $steps.login.outputs.token`, or with
+//! braces as in the specification: `Bearer {$steps.login.outputs.token}`).
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// A parsed runtime expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuntimeExpression {
+    /// `$url`
+    Url,
+    /// `$method`
+    Method,
+    /// `$statusCode`
+    StatusCode,
+    /// `$request.{source}`
+    Request(ExpressionSource),
+    /// `$response.{source}`
+    Response(ExpressionSource),
+    /// `$inputs.{name}[.path]`
+    Inputs(Vec<String>),
+    /// `$outputs.{name}[.path]` (workflow outputs)
+    Outputs(Vec<String>),
+    /// `$steps.{stepId}.outputs.{name}[.path]`
+    StepOutput {
+        step_id: String,
+        output: String,
+        path: Vec<String>,
+    },
+    /// `$workflows.{workflowId}.outputs.{name}[.path]`
+    WorkflowOutput {
+        workflow_id: String,
+        output: String,
+        path: Vec<String>,
+    },
+    /// Any other expression (`$components...`, `$sourceDescriptions...`)
+    Other(String),
+}
+
+/// Part of a request or response an expression refers to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpressionSource {
+    /// `header.{name}`
+    Header(String),
+    /// `query.{name}`
+    Query(String),
+    /// `path.{name}`
+    Path(String),
+    /// `body` followed by an optional path (`.a.b`, `.items[0]` or `#/a/b`)
+    Body(Vec<String>),
+}
+
+/// A piece of a string that may embed runtime expressions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateSegment {
+    Literal(String),
+    Expression(RuntimeExpression),
+}
+
+impl RuntimeExpression {
+    /// Parse a complete runtime expression (must start with `$`)
+    pub fn parse(expr: &str) -> Option<Self> {
+        let expr = expr.trim();
+        let expr = expr
+            .strip_prefix('{')
+            .and_then(|e| e.strip_suffix('}'))
+            .unwrap_or(expr);
+        let body = expr.strip_prefix('$')?;
+
+        match body {
+            "url" => return Some(Self::Url),
+            "method" => return Some(Self::Method),
+            "statusCode" => return Some(Self::StatusCode),
+            _ => {}
+        }
+
+        if let Some(rest) = body.strip_prefix("request.") {
+            return ExpressionSource::parse(rest).map(Self::Request);
+        }
+        if let Some(rest) = body.strip_prefix("response.") {
+            return ExpressionSource::parse(rest).map(Self::Response);
+        }
+        if let Some(rest) = body.strip_prefix("inputs.") {
+            return Some(Self::Inputs(split_path(rest)));
+        }
+        if let Some(rest) = body.strip_prefix("outputs.") {
+            return Some(Self::Outputs(split_path(rest)));
+        }
+        if let Some(rest) = body.strip_prefix("steps.") {
+            let (step_id, output, path) = split_outputs_ref(rest)?;
+            return Some(Self::StepOutput {
+                step_id,
+                output,
+                path,
+            });
+        }
+        if let Some(rest) = body.strip_prefix("workflows.") {
+            let (workflow_id, output, path) = split_outputs_ref(rest)?;
+            return Some(Self::WorkflowOutput {
+                workflow_id,
+                output,
+                path,
+            });
+        }
+
+        if body.is_empty() {
+            None
+        } else {
+            Some(Self::Other(expr.to_string()))
+        }
+    }
+
+    /// Whether evaluating this expression needs the current step's response
+    pub fn needs_response(&self) -> bool {
+        matches!(self, Self::StatusCode | Self::Response(_))
+    }
+}
+
+impl std::fmt::Display for RuntimeExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Url => write!(f, "$url"),
+            Self::Method => write!(f, "$method"),
+            Self::StatusCode => write!(f, "$statusCode"),
+            Self::Request(source) => write!(f, "$request.{}", source),
+            Self::Response(source) => write!(f, "$response.{}", source),
+            Self::Inputs(path) => write!(f, "$inputs.{}", path.join(".")),
+            Self::Outputs(path) => write!(f, "$outputs.{}", path.join(".")),
+            Self::StepOutput {
+                step_id,
+                output,
+                path,
+            } => write!(f, "$steps.{}.outputs.{}{}", step_id, output, dotted(path)),
+            Self::WorkflowOutput {
+                workflow_id,
+                output,
+                path,
+            } => write!(
+                f,
+                "$workflows.{}.outputs.{}{}",
+                workflow_id,
+                output,
+                dotted(path)
+            ),
+            Self::Other(expr) => write!(f, "{}", expr),
+        }
+    }
+}
+
+impl std::fmt::Display for ExpressionSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Header(name) => write!(f, "header.{}", name),
+            Self::Query(name) => write!(f, "query.{}", name),
+            Self::Path(name) => write!(f, "path.{}", name),
+            Self::Body(path) if path.is_empty() => write!(f, "body"),
+            Self::Body(path) if path.iter().all(|s| is_plain_segment(s)) => {
+                write!(f, "body{}", dotted_with_indexes(path))
+            }
+            Self::Body(path) => write!(f, "body#/{}", join_pointer(path)),
+        }
+    }
+}
+
+fn dotted(path: &[String]) -> String {
+    path.iter().map(|p| format!(".{}", p)).collect()
+}
+
+/// Whether a path segment can be written in dotted form
+fn is_plain_segment(segment: &str) -> bool {
+    !segment.is_empty()
+        && segment
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Render `["items", "0", "id"]` as `.items[0].id`
+fn dotted_with_indexes(path: &[String]) -> String {
+    path.iter()
+        .map(|p| {
+            if p.chars().all(|c| c.is_ascii_digit()) {
+                format!("[{}]", p)
+            } else {
+                format!(".{}", p)
+            }
+        })
+        .collect()
+}
+
+/// Join tokens into an escaped JSON Pointer (without the leading `/`)
+fn join_pointer(path: &[String]) -> String {
+    path.iter()
+        .map(|t| t.replace('~', "~0").replace('/', "~1"))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+impl ExpressionSource {
+    fn parse(rest: &str) -> Option<Self> {
+        if let Some(name) = rest.strip_prefix("header.") {
+            return Some(Self::Header(name.to_string()));
+        }
+        if let Some(name) = rest.strip_prefix("query.") {
+            return Some(Self::Query(name.to_string()));
+        }
+        if let Some(name) = rest.strip_prefix("path.") {
+            return Some(Self::Path(name.to_string()));
+        }
+        let path = rest.strip_prefix("body")?;
+        if path.is_empty() {
+            return Some(Self::Body(Vec::new()));
+        }
+        if let Some(pointer) = path.strip_prefix('#') {
+            return Some(Self::Body(split_pointer(pointer)));
+        }
+        path.strip_prefix('.').map(|p| Self::Body(split_path(p)))
+    }
+}
+
+/// Split `a.b[0].c` into `["a", "b", "0", "c"]`
+fn split_path(path: &str) -> Vec<String> {
+    path.split('.')
+        .flat_map(|segment| {
+            segment
+                .split(['[', ']'])
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Split a JSON Pointer (`/a/b`) into unescaped tokens
+fn split_pointer(pointer: &str) -> Vec<String> {
+    pointer
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(|t| t.replace("~1", "/").replace("~0", "~"))
+        .collect()
+}
+
+/// Split `{id}.outputs.{name}[.path]`
+fn split_outputs_ref(rest: &str) -> Option<(String, String, Vec<String>)> {
+    let (id, tail) = rest.split_once(".outputs.")?;
+    let mut path = split_path(tail);
+    if id.is_empty() || path.is_empty() {
+        return None;
+    }
+    let output = path.remove(0);
+    Some((id.to_string(), output, path))
+}
+
+fn embedded_expression_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"\{(\$[^{}\s]+)\}|(\$(?:url|method|statusCode|request\.|response\.|inputs\.|outputs\.|steps\.|workflows\.|components\.|sourceDescriptions\.)[A-Za-z0-9_.\-\[\]]*(?:#/[A-Za-z0-9_.~/\-]*)?)",
+        )
+        .unwrap()
+    })
+}
+
+/// Split a string into literal text and embedded runtime expressions
+///
+/// Unknown `$` sequences are kept as literal text.
+pub fn parse_template(input: &str) -> Vec<TemplateSegment> {
+    let mut segments = Vec::new();
+    let mut last = 0;
+
+    for caps in embedded_expression_regex().captures_iter(input) {
+        let whole = caps.get(0).unwrap();
+        let raw = caps.get(1).or_else(|| caps.get(2)).unwrap().as_str();
+
+        // A trailing '.' or '-' ends a sentence rather than the expression
+        let trimmed = if caps.get(2).is_some() {
+            raw.trim_end_matches(['.', '-'])
+        } else {
+            raw
+        };
+        let end = if caps.get(2).is_some() {
+            whole.start() + trimmed.len()
+        } else {
+            whole.end()
+        };
+
+        let Some(expr) = RuntimeExpression::parse(trimmed) else {
+            continue;
+        };
+
+        if whole.start() > last {
+            segments.push(TemplateSegment::Literal(
+                input[last..whole.start()].to_string(),
+            ));
+        }
+        segments.push(TemplateSegment::Expression(expr));
+        last = end;
+    }
+
+    if last < input.len() {
+        segments.push(TemplateSegment::Literal(input[last..].to_string()));
+    }
+
+    segments
+}
+
+/// Return the expression if the whole value is a single runtime expression
+pub fn as_single_expression(value: &serde_json::Value) -> Option<RuntimeExpression> {
+    let s = value.as_str()?;
+    match parse_template(s).as_slice() {
+        [TemplateSegment::Expression(expr)] => Some(expr.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_expressions() {
+        assert_eq!(
+            RuntimeExpression::parse("$statusCode"),
+            Some(RuntimeExpression::StatusCode)
+        );
+        assert_eq!(
+            RuntimeExpression::parse("$inputs.user.name"),
+            Some(RuntimeExpression::Inputs(vec![
+                "user".to_string(),
+                "name".to_string()
+            ]))
+        );
+        assert_eq!(
+            RuntimeExpression::parse("$steps.login.outputs.profile.email"),
+            Some(RuntimeExpression::StepOutput {
+                step_id: "login".to_string(),
+                output: "profile".to_string(),
+                path: vec!["email".to_string()],
+            })
+        );
+        assert_eq!(
+            RuntimeExpression::parse("$response.body#/items/0/id"),
+            Some(RuntimeExpression::Response(ExpressionSource::Body(vec![
+                "items".to_string(),
+                "0".to_string(),
+                "id".to_string()
+            ])))
+        );
+        assert_eq!(
+            RuntimeExpression::parse("$response.header.X-Request-Id"),
+            Some(RuntimeExpression::Response(ExpressionSource::Header(
+                "X-Request-Id".to_string()
+            )))
+        );
+        assert_eq!(RuntimeExpression::parse("$steps.login"), None);
+        assert_eq!(RuntimeExpression::parse("plain"), None);
+    }
+
+    #[test]
+    fn test_parse_template() {
+        let segments = parse_template("Bearer $steps.login.outputs.token");
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0], TemplateSegment::Literal("Bearer ".to_string()));
+
+        let segments = parse_template("id={$inputs.id}&price=$5.");
+        assert_eq!(
+            segments,
+            vec![
+                TemplateSegment::Literal("id=".to_string()),
+                TemplateSegment::Expression(RuntimeExpression::Inputs(vec!["id".to_string()])),
+                TemplateSegment::Literal("&price=$5.".to_string()),
+            ]
+        );
+
+        let segments = parse_template("Hello $inputs.name.");
+        assert_eq!(segments.last(), Some(&TemplateSegment::Literal(".".into())));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for expr in [
+            "$statusCode",
+            "$inputs.user.name",
+            "$steps.login.outputs.token",
+            "$response.body.items[0].id",
+            "$response.body#/a~1b",
+            "$request.header.Authorization",
+        ] {
+            let parsed = RuntimeExpression::parse(expr).unwrap();
+            assert_eq!(parsed.to_string(), expr);
+        }
+    }
+
+    #[test]
+    fn test_as_single_expression() {
+        assert!(as_single_expression(&serde_json::json!("$inputs.id")).is_some());
+        assert!(as_single_expression(&serde_json::json!("id: $inputs.id")).is_none());
+        assert!(as_single_expression(&serde_json::json!(42)).is_none());
+    }
+}
@@ -1,4 +1,7 @@
 pub mod arazzo;
+pub mod expression;
+pub mod profile;
+pub mod slo;
 
 // Re-export oas3 for OpenAPI
 pub use oas3::OpenApiV3Spec;
@@ -0,0 +1,391 @@
+//! Load profiles: a weighted mix of workflows with think time and pacing
+//!
+//! A profile is given either inline as the `x-load-profile` extension of an
+//! Arazzo document or as a separate YAML/JSON file:
+//!
+//! ```yaml
+//! x-load-profile:
+//!   workflows:
+//!     - workflowId: browse
+//!       weight: 70
+//!     - workflowId: search
+//!       weight: 20
+//!       thinkTime: { distribution: uniform, min: 500ms, max: 2s }
+//!     - workflowId: checkout
+//!       weight: 10
+//!   thinkTime: { distribution: constant, value: 1s }
+//!   pacing: { distribution: normal, mean: 5s, stddev: 1s }
+//! ```
+//!
+//! Think time is a pause between two steps of an iteration; pacing is the
+//! target time from the start of one iteration of a VU to the start of its
+//! next one. Both can be set for the whole profile and overridden per
+//! workflow.
+
+use crate::converters::common::parse_duration;
+use crate::error::{HornetError, Result};
+use crate::models::arazzo::ArazzoSpec;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::path::Path;
+use std::time::Duration;
+
+/// Name of the Arazzo extension holding a load profile
+pub const LOAD_PROFILE_EXTENSION: &str = "x-load-profile";
+
+/// Distribution of a duration
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "distribution", rename_all = "lowercase")]
+pub enum Distribution {
+    Constant {
+        #[serde(with = "duration_text")]
+        value: Duration,
+    },
+    Uniform {
+        #[serde(with = "duration_text")]
+        min: Duration,
+        #[serde(with = "duration_text")]
+        max: Duration,
+    },
+    /// Normal distribution (negative samples become zero)
+    Normal {
+        #[serde(with = "duration_text")]
+        mean: Duration,
+        #[serde(with = "duration_text")]
+        stddev: Duration,
+    },
+    Exponential {
+        #[serde(with = "duration_text")]
+        mean: Duration,
+    },
+}
+
+impl Distribution {
+    /// Draw a duration, given a source of uniform values in `[0, 1)`
+    pub fn sample(&self, mut unit: impl FnMut() -> f64) -> Duration {
+        let seconds = match *self {
+            Self::Constant { value } => value.as_secs_f64(),
+            Self::Uniform { min, max } => {
+                min.as_secs_f64() + (max.as_secs_f64() - min.as_secs_f64()) * unit()
+            }
+            Self::Normal { mean, stddev } => {
+                // Box-Muller
+                let radius = (-2.0 * (1.0 - unit()).ln()).sqrt();
+                let angle = 2.0 * std::f64::consts::PI * unit();
+                mean.as_secs_f64() + stddev.as_secs_f64() * radius * angle.cos()
+            }
+            Self::Exponential { mean } => -mean.as_secs_f64() * (1.0 - unit()).ln(),
+        };
+        Duration::from_secs_f64(seconds.max(0.0))
+    }
+
+    fn validate(&self) -> Result<()> {
+        match *self {
+            Self::Uniform { min, max } if min > max => Err(HornetError::ValidationError(format!(
+                "Uniform distribution has min {:?} above max {:?}",
+                min, max
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Share of a workflow in the mix
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowWeight {
+    pub workflow_id: String,
+    /// Relative weight (the weights do not need to add up to 100)
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+    /// Overrides the profile's think time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub think_time: Option<Distribution>,
+    /// Overrides the profile's pacing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pacing: Option<Distribution>,
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+/// Weighted workflow mix of a load test
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadProfile {
+    #[serde(default)]
+    pub workflows: Vec<WorkflowWeight>,
+    /// Pause between two steps
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub think_time: Option<Distribution>,
+    /// Target time between the starts of two iterations of a VU
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pacing: Option<Distribution>,
+}
+
+impl LoadProfile {
+    /// Profile of the `x-load-profile` extension, if any
+    pub fn from_arazzo(arazzo: &ArazzoSpec) -> Result<Option<Self>> {
+        arazzo
+            .extensions
+            .get(LOAD_PROFILE_EXTENSION)
+            .map(|value| {
+                serde_json::from_value(value.clone()).map_err(|e| {
+                    HornetError::ValidationError(format!(
+                        "Invalid {}: {}",
+                        LOAD_PROFILE_EXTENSION, e
+                    ))
+                })
+            })
+            .transpose()
+    }
+
+    /// Read a profile file (JSON for `.json`, YAML otherwise)
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let parsed = if path.extension().is_some_and(|e| e == "json") {
+            serde_json::from_str(&content).map_err(|e| e.to_string())
+        } else {
+            serde_yaml::from_str(&content).map_err(|e| e.to_string())
+        };
+        parsed.map_err(|e| {
+            HornetError::ValidationError(format!("Invalid load profile {}: {}", path.display(), e))
+        })
+    }
+
+    /// The profile file if given, else the document's extension
+    pub fn resolve(path: Option<&Path>, arazzo: &ArazzoSpec) -> Result<Option<Self>> {
+        let profile = match path {
+            Some(path) => Some(Self::load(path)?),
+            None => Self::from_arazzo(arazzo)?,
+        };
+        if let Some(ref profile) = profile {
+            profile.validate(arazzo)?;
+        }
+        Ok(profile)
+    }
+
+    /// Check weights, distributions and workflow ids
+    pub fn validate(&self, arazzo: &ArazzoSpec) -> Result<()> {
+        if self.workflows.is_empty() {
+            return Err(HornetError::ValidationError(
+                "Load profile lists no workflows".to_string(),
+            ));
+        }
+        for entry in &self.workflows {
+            if !arazzo
+                .workflows
+                .iter()
+                .any(|w| w.workflow_id == entry.workflow_id)
+            {
+                return Err(HornetError::ValidationError(format!(
+                    "Load profile refers to unknown workflow '{}'",
+                    entry.workflow_id
+                )));
+            }
+            if !entry.weight.is_finite() || entry.weight < 0.0 {
+                return Err(HornetError::ValidationError(format!(
+                    "Weight of workflow '{}' must not be negative",
+                    entry.workflow_id
+                )));
+            }
+        }
+        if self.workflows.iter().map(|w| w.weight).sum::<f64>() <= 0.0 {
+            return Err(HornetError::ValidationError(
+                "Load profile weights add up to zero".to_string(),
+            ));
+        }
+        let distributions = self
+            .workflows
+            .iter()
+            .flat_map(|w| [&w.think_time, &w.pacing])
+            .chain([&self.think_time, &self.pacing])
+            .flatten();
+        for distribution in distributions {
+            distribution.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Workflows with their share of iterations (adding up to 1)
+    pub fn shares(&self) -> Vec<(&str, f64)> {
+        let total: f64 = self.workflows.iter().map(|w| w.weight).sum();
+        self.workflows
+            .iter()
+            .map(|w| (w.workflow_id.as_str(), w.weight / total))
+            .collect()
+    }
+
+    fn entry(&self, workflow_id: &str) -> Option<&WorkflowWeight> {
+        self.workflows.iter().find(|w| w.workflow_id == workflow_id)
+    }
+
+    /// Think time of a workflow (its own, else the profile's)
+    pub fn think_time(&self, workflow_id: &str) -> Option<Distribution> {
+        self.entry(workflow_id)
+            .and_then(|w| w.think_time)
+            .or(self.think_time)
+    }
+
+    /// Pacing of a workflow (its own, else the profile's)
+    pub fn pacing(&self, workflow_id: &str) -> Option<Distribution> {
+        self.entry(workflow_id)
+            .and_then(|w| w.pacing)
+            .or(self.pacing)
+    }
+}
+
+/// Durations written as text (`1s`, `250ms`) or as a number of seconds
+pub(super) mod duration_text {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        duration: &Duration,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{}ms", duration.as_millis()))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Duration, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Text {
+            Seconds(f64),
+            Text(String),
+        }
+        match Text::deserialize(deserializer)? {
+            Text::Seconds(seconds) if seconds.is_finite() && seconds >= 0.0 => {
+                Ok(Duration::from_secs_f64(seconds))
+            }
+            Text::Seconds(seconds) => Err(serde::de::Error::custom(format!(
+                "invalid duration: {}",
+                seconds
+            ))),
+            Text::Text(text) => parse_duration(&text)
+                .ok_or_else(|| serde::de::Error::custom(format!("invalid duration: {}", text))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn arazzo() -> ArazzoSpec {
+        serde_yaml::from_str(
+            r#"
+arazzo: 1.0.0
+info: { title: Shop, version: 1.0.0 }
+workflows:
+  - workflowId: browse
+    steps: []
+  - workflowId: search
+    steps: []
+  - workflowId: checkout
+    steps: []
+x-load-profile:
+  workflows:
+    - workflowId: browse
+      weight: 70
+    - workflowId: search
+      weight: 20
+      thinkTime: { distribution: uniform, min: 500ms, max: 2s }
+    - workflowId: checkout
+      weight: 10
+      pacing: { distribution: exponential, mean: 3 }
+  thinkTime: { distribution: constant, value: 1s }
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_profile_from_extension() {
+        let arazzo = arazzo();
+        let profile = LoadProfile::resolve(None, &arazzo).unwrap().unwrap();
+        assert_eq!(
+            profile.shares(),
+            vec![("browse", 0.7), ("search", 0.2), ("checkout", 0.1)]
+        );
+        assert_eq!(
+            profile.think_time("browse"),
+            Some(Distribution::Constant {
+                value: Duration::from_secs(1)
+            })
+        );
+        assert_eq!(
+            profile.think_time("search"),
+            Some(Distribution::Uniform {
+                min: Duration::from_millis(500),
+                max: Duration::from_secs(2)
+            })
+        );
+        assert_eq!(profile.pacing("browse"), None);
+        assert_eq!(
+            profile.pacing("checkout"),
+            Some(Distribution::Exponential {
+                mean: Duration::from_secs(3)
+            })
+        );
+
+        // 保存しても拡張は残る
+        let saved = serde_yaml::to_string(&arazzo).unwrap();
+        let reloaded: ArazzoSpec = serde_yaml::from_str(&saved).unwrap();
+        assert_eq!(LoadProfile::from_arazzo(&reloaded).unwrap(), Some(profile));
+    }
+
+    #[test]
+    fn test_invalid_profiles() {
+        let arazzo = arazzo();
+        for invalid in [
+            json!({"workflows": []}),
+            json!({"workflows": [{"workflowId": "missing"}]}),
+            json!({"workflows": [{"workflowId": "browse", "weight": 0}]}),
+            json!({"workflows": [{"workflowId": "browse", "weight": -1}]}),
+            json!({
+                "workflows": [{"workflowId": "browse"}],
+                "pacing": {"distribution": "uniform", "min": "2s", "max": "1s"}
+            }),
+        ] {
+            let profile: LoadProfile = serde_json::from_value(invalid).unwrap();
+            assert!(profile.validate(&arazzo).is_err());
+        }
+        assert!(
+            serde_json::from_value::<Distribution>(
+                json!({"distribution": "constant", "value": "soon"})
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_sample() {
+        let half = || 0.5;
+        let second = Duration::from_secs(1);
+        assert_eq!(
+            Distribution::Constant { value: second }.sample(half),
+            second
+        );
+        assert_eq!(
+            Distribution::Uniform {
+                min: Duration::ZERO,
+                max: 2 * second
+            }
+            .sample(half),
+            second
+        );
+        // 負の値は 0 になる
+        let mut values = [0.999, 0.5].into_iter();
+        assert_eq!(
+            Distribution::Normal {
+                mean: second,
+                stddev: 10 * second
+            }
+            .sample(|| values.next().unwrap()),
+            Duration::ZERO
+        );
+    }
+}
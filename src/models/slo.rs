@@ -0,0 +1,143 @@
+//! Service level objectives of workflow steps
+//!
+//! A step declares its objectives with the `x-slo` extension. Latency limits
+//! apply to statistics of the step's response times, the error rate to the
+//! share of its requests that failed:
+//!
+//! ```yaml
+//! steps:
+//!   - stepId: search
+//!     operationId: searchItems
+//!     x-slo:
+//!       latency: { p95: 300ms, p99: 1s }
+//!       errorRate: 0.01
+//! ```
+
+use super::arazzo::ArazzoSpec;
+use super::profile::duration_text;
+use crate::error::{HornetError, Result};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Name of the step extension holding an SLO
+pub const SLO_EXTENSION: &str = "x-slo";
+
+/// Latency statistics an SLO can limit
+pub const LATENCY_STATISTICS: [&str; 7] = ["mean", "p50", "p90", "p95", "p99", "p99.9", "max"];
+
+/// Upper bound of a latency statistic
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LatencyLimit(#[serde(with = "duration_text")] pub Duration);
+
+/// Objectives of one step
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct StepSlo {
+    /// Limits keyed by statistic (`p95: 300ms`)
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub latency: IndexMap<String, LatencyLimit>,
+    /// Largest share of failed requests, in `[0, 1]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_rate: Option<f64>,
+}
+
+/// The SLO of a step of a workflow
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepObjective {
+    pub workflow_id: String,
+    pub step_id: String,
+    pub slo: StepSlo,
+}
+
+/// SLOs of every step declaring one, in document order
+pub fn step_objectives(arazzo: &ArazzoSpec) -> Result<Vec<StepObjective>> {
+    let mut objectives = Vec::new();
+    for workflow in &arazzo.workflows {
+        for step in &workflow.steps {
+            let Some(value) = step.extensions.get(SLO_EXTENSION) else {
+                continue;
+            };
+            let invalid = |message: String| {
+                HornetError::ValidationError(format!(
+                    "Invalid {} of step '{}' in workflow '{}': {}",
+                    SLO_EXTENSION, step.step_id, workflow.workflow_id, message
+                ))
+            };
+            let slo: StepSlo =
+                serde_json::from_value(value.clone()).map_err(|e| invalid(e.to_string()))?;
+            if let Some(statistic) = slo
+                .latency
+                .keys()
+                .find(|k| !LATENCY_STATISTICS.contains(&k.as_str()))
+            {
+                return Err(invalid(format!(
+                    "unknown latency statistic '{}' (expected one of {})",
+                    statistic,
+                    LATENCY_STATISTICS.join(", ")
+                )));
+            }
+            if slo.error_rate.is_some_and(|r| !(0.0..=1.0).contains(&r)) {
+                return Err(invalid("errorRate must be between 0 and 1".to_string()));
+            }
+            objectives.push(StepObjective {
+                workflow_id: workflow.workflow_id.clone(),
+                step_id: step.step_id.clone(),
+                slo,
+            });
+        }
+    }
+    Ok(objectives)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arazzo(slo: &str) -> ArazzoSpec {
+        serde_yaml::from_str(&format!(
+            r#"
+arazzo: 1.0.0
+info:
+  title: Shop
+  version: 1.0.0
+sourceDescriptions: []
+workflows:
+  - workflowId: browse
+    steps:
+      - stepId: list
+        operationId: listItems
+      - stepId: search
+        operationId: searchItems
+        x-slo: {}
+"#,
+            slo
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_step_objectives() {
+        let objectives = step_objectives(&arazzo(
+            "{ latency: { p95: 300ms, p99.9: 2 }, errorRate: 0.01 }",
+        ))
+        .unwrap();
+        assert_eq!(objectives.len(), 1);
+        assert_eq!(objectives[0].workflow_id, "browse");
+        assert_eq!(objectives[0].step_id, "search");
+        let slo = &objectives[0].slo;
+        assert_eq!(slo.latency["p95"], LatencyLimit(Duration::from_millis(300)));
+        assert_eq!(slo.latency["p99.9"], LatencyLimit(Duration::from_secs(2)));
+        assert_eq!(slo.error_rate, Some(0.01));
+
+        // 異常系: 未知の統計量、範囲外のエラー率、未知のキー
+        for invalid in [
+            "{ latency: { p42: 1s } }",
+            "{ errorRate: 1.5 }",
+            "{ latency: { p95: soon } }",
+            "{ throughput: 10 }",
+        ] {
+            assert!(step_objectives(&arazzo(invalid)).is_err(), "{invalid}");
+        }
+    }
+}
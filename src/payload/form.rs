@@ -0,0 +1,100 @@
+//! `application/x-www-form-urlencoded` encoding
+
+use super::{payload_error, text_value};
+use crate::error::Result;
+
+/// Flatten a payload object into form fields
+///
+/// Nested objects use bracket notation (`address[city]`), arrays of scalars
+/// repeat the key and arrays of objects are indexed (`items[0][sku]`).
+/// Field values are returned untouched so converters can still render
+/// runtime expressions inside them.
+pub fn flatten(payload: &serde_json::Value) -> Result<Vec<(String, serde_json::Value)>> {
+    let obj = payload.as_object().ok_or_else(|| {
+        payload_error("form-urlencoded payload must be an object or a pre-encoded string")
+    })?;
+
+    let mut fields = Vec::new();
+    for (key, value) in obj {
+        flatten_into(key.clone(), value, &mut fields);
+    }
+    Ok(fields)
+}
+
+fn flatten_into(
+    key: String,
+    value: &serde_json::Value,
+    fields: &mut Vec<(String, serde_json::Value)>,
+) {
+    match value {
+        serde_json::Value::Object(obj) => {
+            for (child, v) in obj {
+                flatten_into(format!("{}[{}]", key, child), v, fields);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                if item.is_object() || item.is_array() {
+                    flatten_into(format!("{}[{}]", key, i), item, fields);
+                } else {
+                    fields.push((key.clone(), item.clone()));
+                }
+            }
+        }
+        _ => fields.push((key, value.clone())),
+    }
+}
+
+/// Encode flattened fields as a form-urlencoded string
+pub fn encode(fields: &[(String, serde_json::Value)]) -> String {
+    fields
+        .iter()
+        .map(|(k, v)| format!("{}={}", escape(k), escape(&text_value(v))))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Percent-encode a form component (spaces become `+`)
+pub fn escape(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'*' => {
+                out.push(byte as char)
+            }
+            b' ' => out.push('+'),
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_flatten_nested() {
+        let fields = flatten(&json!({
+            "user": {"name": "a", "age": 3},
+            "items": [{"sku": "x"}]
+        }))
+        .unwrap();
+
+        let keys: Vec<&str> = fields.iter().map(|(k, _)| k.as_str()).collect();
+        assert!(keys.contains(&"user[name]"));
+        assert!(keys.contains(&"user[age]"));
+        assert!(keys.contains(&"items[0][sku]"));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a b&c=d/é"), "a+b%26c%3Dd%2F%C3%A9");
+    }
+
+    #[test]
+    fn test_flatten_rejects_scalars() {
+        assert!(flatten(&json!(42)).is_err());
+    }
+}
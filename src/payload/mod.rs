@@ -0,0 +1,255 @@
+//! Content-type-aware request body encoding
+//!
+//! This module decides how an Arazzo `requestBody` is put on the wire based on
+//! its `contentType`. Converters use [`BodyEncoding`] together with the helpers
+//! in the submodules to render payloads in their target language, while native
+//! HTTP execution calls [`encode_body`] once runtime expressions are resolved.
+
+pub mod form;
+pub mod multipart;
+pub mod replacements;
+pub mod xml;
+
+pub use replacements::{apply_replacements, set_pointer};
+
+use crate::error::{HornetError, Result};
+use std::path::Path;
+
+/// Key marking a payload object as a file reference (`{"$file": "./avatar.png"}`)
+pub const FILE_KEY: &str = "$file";
+
+/// Wire encoding of a request body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyEncoding {
+    /// `application/json` and `+json` media types
+    Json,
+    /// `application/x-www-form-urlencoded`
+    FormUrlEncoded,
+    /// `multipart/form-data`
+    Multipart,
+    /// `application/xml`, `text/xml` and `+xml` media types
+    Xml,
+    /// `text/plain` and any other media type, sent as-is
+    Raw,
+}
+
+impl BodyEncoding {
+    /// Determine the encoding from a content type (defaults to JSON)
+    pub fn from_content_type(content_type: Option<&str>) -> Self {
+        let essence = content_type
+            .and_then(|ct| ct.split(';').next())
+            .map(|ct| ct.trim().to_ascii_lowercase())
+            .unwrap_or_default();
+
+        match essence.as_str() {
+            "" | "application/json" => Self::Json,
+            "application/x-www-form-urlencoded" => Self::FormUrlEncoded,
+            "application/xml" | "text/xml" => Self::Xml,
+            ct if ct.starts_with("multipart/") => Self::Multipart,
+            ct if ct.ends_with("+json") => Self::Json,
+            ct if ct.ends_with("+xml") => Self::Xml,
+            _ => Self::Raw,
+        }
+    }
+
+    /// Content type used when the request body does not declare one
+    pub fn default_content_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::FormUrlEncoded => "application/x-www-form-urlencoded",
+            Self::Multipart => "multipart/form-data",
+            Self::Xml => "application/xml",
+            Self::Raw => "text/plain",
+        }
+    }
+}
+
+/// A file on disk referenced from a payload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileRef {
+    /// Path to the file, relative to the Arazzo document
+    pub path: String,
+    /// File name sent in the part's `Content-Disposition` (defaults to the path's file name)
+    pub filename: Option<String>,
+    /// Content type of the part (defaults to `application/octet-stream`)
+    pub content_type: Option<String>,
+}
+
+impl FileRef {
+    /// Interpret a payload value as a file reference
+    ///
+    /// File references are objects with a `$file` key and optional
+    /// `filename` and `contentType` keys.
+    pub fn from_value(value: &serde_json::Value) -> Option<Self> {
+        let obj = value.as_object()?;
+        let path = obj.get(FILE_KEY)?.as_str()?.to_string();
+
+        Some(Self {
+            path,
+            filename: obj
+                .get("filename")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            content_type: obj
+                .get("contentType")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+        })
+    }
+
+    /// File name to announce for this part
+    pub fn file_name(&self) -> String {
+        self.filename.clone().unwrap_or_else(|| {
+            Path::new(&self.path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| self.path.clone())
+        })
+    }
+
+    /// Content type to announce for this part
+    pub fn mime_type(&self) -> &str {
+        self.content_type
+            .as_deref()
+            .unwrap_or("application/octet-stream")
+    }
+}
+
+/// An encoded request body ready to be sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodedBody {
+    /// Value for the `Content-Type` header (includes the multipart boundary)
+    pub content_type: String,
+    /// Body bytes
+    pub bytes: Vec<u8>,
+}
+
+/// Encode a resolved payload according to its content type
+///
+/// `payload` must no longer contain runtime expressions. File references in
+/// multipart payloads are read relative to `base_dir`.
+pub fn encode_body(
+    content_type: Option<&str>,
+    payload: &serde_json::Value,
+    base_dir: &Path,
+) -> Result<EncodedBody> {
+    let encoding = BodyEncoding::from_content_type(content_type);
+    let declared = content_type
+        .unwrap_or_else(|| encoding.default_content_type())
+        .to_string();
+
+    match encoding {
+        BodyEncoding::Json => Ok(EncodedBody {
+            content_type: declared,
+            bytes: serde_json::to_vec(payload)?,
+        }),
+        BodyEncoding::FormUrlEncoded => {
+            let bytes = match payload {
+                serde_json::Value::String(s) => s.clone().into_bytes(),
+                _ => form::encode(&form::flatten(payload)?).into_bytes(),
+            };
+            Ok(EncodedBody {
+                content_type: declared,
+                bytes,
+            })
+        }
+        BodyEncoding::Multipart => {
+            let parts = multipart::collect_parts(payload)?;
+            let (boundary, bytes) = multipart::encode(&parts, base_dir)?;
+            Ok(EncodedBody {
+                content_type: format!("multipart/form-data; boundary={}", boundary),
+                bytes,
+            })
+        }
+        BodyEncoding::Xml => {
+            let xml = match payload {
+                serde_json::Value::String(s) => s.clone(),
+                _ => xml::to_xml(payload)?,
+            };
+            Ok(EncodedBody {
+                content_type: declared,
+                bytes: xml.into_bytes(),
+            })
+        }
+        BodyEncoding::Raw => Ok(EncodedBody {
+            content_type: declared,
+            bytes: text_value(payload).into_bytes(),
+        }),
+    }
+}
+
+/// Render a scalar payload value as text (strings are not quoted)
+pub fn text_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn payload_error(message: impl Into<String>) -> HornetError {
+    HornetError::PayloadError(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_encoding_from_content_type() {
+        assert_eq!(BodyEncoding::from_content_type(None), BodyEncoding::Json);
+        assert_eq!(
+            BodyEncoding::from_content_type(Some("application/vnd.api+json")),
+            BodyEncoding::Json
+        );
+        assert_eq!(
+            BodyEncoding::from_content_type(Some("application/x-www-form-urlencoded")),
+            BodyEncoding::FormUrlEncoded
+        );
+        assert_eq!(
+            BodyEncoding::from_content_type(Some("multipart/form-data; boundary=x")),
+            BodyEncoding::Multipart
+        );
+        assert_eq!(
+            BodyEncoding::from_content_type(Some("text/xml; charset=utf-8")),
+            BodyEncoding::Xml
+        );
+        assert_eq!(
+            BodyEncoding::from_content_type(Some("text/plain")),
+            BodyEncoding::Raw
+        );
+    }
+
+    #[test]
+    fn test_encode_body_form() {
+        let encoded = encode_body(
+            Some("application/x-www-form-urlencoded"),
+            &json!({"name": "John Doe", "tags": ["a", "b"]}),
+            Path::new("."),
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(encoded.bytes).unwrap(),
+            "name=John+Doe&tags=a&tags=b"
+        );
+    }
+
+    #[test]
+    fn test_encode_body_raw_and_xml() {
+        let text = encode_body(Some("text/plain"), &json!("hello"), Path::new(".")).unwrap();
+        assert_eq!(text.bytes, b"hello");
+
+        let xml = encode_body(
+            Some("application/xml"),
+            &json!({"user": {"name": "alice"}}),
+            Path::new("."),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(xml.bytes).unwrap(),
+            "<user><name>alice</name></user>"
+        );
+    }
+}
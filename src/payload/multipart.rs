@@ -0,0 +1,247 @@
+//! `multipart/form-data` encoding
+
+use super::{FileRef, payload_error, text_value};
+use crate::error::{HornetError, Result};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// A single part of a multipart body
+#[derive(Debug, Clone, PartialEq)]
+pub struct Part {
+    /// Form field name
+    pub name: String,
+    /// Part content
+    pub content: PartContent,
+}
+
+/// Content of a multipart part
+#[derive(Debug, Clone, PartialEq)]
+pub enum PartContent {
+    /// A plain field (may still hold a runtime expression)
+    Field(serde_json::Value),
+    /// A nested object or array sent as a JSON part
+    Json(serde_json::Value),
+    /// A file read from disk
+    File(FileRef),
+}
+
+/// Split a payload object into multipart parts
+///
+/// Array values produce one part per item with the same name.
+pub fn collect_parts(payload: &serde_json::Value) -> Result<Vec<Part>> {
+    let obj = payload
+        .as_object()
+        .ok_or_else(|| payload_error("multipart payload must be an object"))?;
+
+    let mut parts = Vec::new();
+    for (name, value) in obj {
+        match value {
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    parts.push(to_part(name, item));
+                }
+            }
+            _ => parts.push(to_part(name, value)),
+        }
+    }
+    Ok(parts)
+}
+
+fn to_part(name: &str, value: &serde_json::Value) -> Part {
+    let content = if let Some(file) = FileRef::from_value(value) {
+        PartContent::File(file)
+    } else if value.is_object() || value.is_array() {
+        PartContent::Json(value.clone())
+    } else {
+        PartContent::Field(value.clone())
+    };
+
+    Part {
+        name: name.to_string(),
+        content,
+    }
+}
+
+/// Encode parts into a multipart body, returning the boundary and the bytes
+///
+/// File parts are read relative to `base_dir` and must stay inside it.
+pub fn encode(parts: &[Part], base_dir: &Path) -> Result<(String, Vec<u8>)> {
+    let mut rendered = Vec::with_capacity(parts.len());
+    for part in parts {
+        let (headers, body) = match &part.content {
+            PartContent::Field(value) => (
+                format!(
+                    "Content-Disposition: form-data; name=\"{}\"",
+                    quote_param(&part.name)
+                ),
+                text_value(value).into_bytes(),
+            ),
+            PartContent::Json(value) => (
+                format!(
+                    "Content-Disposition: form-data; name=\"{}\"\r\nContent-Type: application/json",
+                    quote_param(&part.name)
+                ),
+                serde_json::to_vec(value)?,
+            ),
+            PartContent::File(file) => {
+                let path = resolve_file(base_dir, &part.name, &file.path)?;
+                let bytes = std::fs::read(&path).map_err(|e| {
+                    HornetError::PayloadError(format!(
+                        "Failed to read file part '{}' from {}: {}",
+                        part.name,
+                        path.display(),
+                        e
+                    ))
+                })?;
+                (
+                    format!(
+                        "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: {}",
+                        quote_param(&part.name),
+                        quote_param(&file.file_name()),
+                        file.mime_type()
+                    ),
+                    bytes,
+                )
+            }
+        };
+        rendered.push((headers, body));
+    }
+
+    let boundary = choose_boundary(&rendered);
+    let mut out = Vec::new();
+    for (headers, body) in &rendered {
+        out.extend_from_slice(format!("--{}\r\n{}\r\n\r\n", boundary, headers).as_bytes());
+        out.extend_from_slice(body);
+        out.extend_from_slice(b"\r\n");
+    }
+    out.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+
+    Ok((boundary, out))
+}
+
+/// Resolve a `$file` reference, rejecting paths that leave `base_dir`
+///
+/// Absolute paths, `..` segments and symlinks are checked on the canonical
+/// path, so a workflow cannot upload files from elsewhere on the machine.
+fn resolve_file(base_dir: &Path, name: &str, file: &str) -> Result<PathBuf> {
+    let unreadable = |path: &Path, e: std::io::Error| {
+        HornetError::PayloadError(format!(
+            "Failed to read file part '{}' from {}: {}",
+            name,
+            path.display(),
+            e
+        ))
+    };
+    let root = base_dir
+        .canonicalize()
+        .map_err(|e| unreadable(base_dir, e))?;
+    let joined = root.join(file);
+    let path = joined.canonicalize().map_err(|e| unreadable(&joined, e))?;
+    if !path.starts_with(&root) {
+        return Err(HornetError::PayloadError(format!(
+            "File part '{}' refers to '{}' outside {}",
+            name,
+            file,
+            root.display()
+        )));
+    }
+    Ok(path)
+}
+
+/// Escape a `Content-Disposition` parameter value (RFC 7578, section 4.2)
+///
+/// `"`, CR and LF are percent-encoded the way browsers do, so a field name
+/// cannot end the quoted string or inject headers.
+fn quote_param(value: &str) -> String {
+    value
+        .replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Derive a deterministic boundary that does not occur in any part
+fn choose_boundary(parts: &[(String, Vec<u8>)]) -> String {
+    let mut hasher = DefaultHasher::new();
+    for (headers, body) in parts {
+        headers.hash(&mut hasher);
+        body.hash(&mut hasher);
+    }
+
+    let mut seed = hasher.finish();
+    loop {
+        let boundary = format!("----hornet2-{:016x}", seed);
+        let collides = parts
+            .iter()
+            .any(|(_, body)| contains(body, boundary.as_bytes()));
+        if !collides {
+            return boundary;
+        }
+        seed = seed.wrapping_add(1);
+    }
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_encode_fields_and_files() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("avatar.png"), b"PNGDATA").unwrap();
+
+        let parts = collect_parts(&json!({
+            "name": "alice",
+            "avatar": {"$file": "avatar.png", "contentType": "image/png"}
+        }))
+        .unwrap();
+        assert_eq!(parts.len(), 2);
+
+        let (boundary, bytes) = encode(&parts, dir.path()).unwrap();
+        let body = String::from_utf8(bytes).unwrap();
+
+        assert!(body.starts_with(&format!("--{}\r\n", boundary)));
+        assert!(body.contains("name=\"name\"\r\n\r\nalice\r\n"));
+        assert!(body.contains("filename=\"avatar.png\"\r\nContent-Type: image/png\r\n\r\nPNGDATA"));
+        assert!(body.ends_with(&format!("--{}--\r\n", boundary)));
+    }
+
+    #[test]
+    fn test_escapes_part_names() {
+        let parts = collect_parts(&json!({"a\"b\r\nX-Injected: 1": "v"})).unwrap();
+        let (_, bytes) = encode(&parts, Path::new(".")).unwrap();
+        let body = String::from_utf8(bytes).unwrap();
+
+        assert!(body.contains("name=\"a%22b%0D%0AX-Injected: 1\"\r\n\r\nv\r\n"));
+        assert!(!body.contains("\r\nX-Injected"));
+    }
+
+    #[test]
+    fn test_files_outside_base_dir_are_rejected() {
+        // 異常系: 絶対パスや `..` でプロジェクトの外のファイルは読まない
+        let root = TempDir::new().unwrap();
+        let project = root.path().join("project");
+        std::fs::create_dir(&project).unwrap();
+        std::fs::write(root.path().join("secret.txt"), b"SECRET").unwrap();
+        let absolute = root.path().join("secret.txt");
+
+        for path in ["../secret.txt", absolute.to_str().unwrap()] {
+            let parts = collect_parts(&json!({"f": {"$file": path}})).unwrap();
+            let message = encode(&parts, &project).unwrap_err().to_string();
+            assert!(message.contains("outside"), "{path}: {message}");
+        }
+    }
+
+    #[test]
+    fn test_missing_file_is_an_error() {
+        let parts = collect_parts(&json!({"f": {"$file": "nope.bin"}})).unwrap();
+        let result = encode(&parts, Path::new("/nonexistent"));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,180 @@
+//! Arazzo payload `replacements`
+//!
+//! Replacements are applied to the templated payload, before runtime
+//! expressions are resolved, so the replaced values may themselves be
+//! runtime expressions.
+
+use super::{BodyEncoding, payload_error, text_value, xml};
+use crate::error::Result;
+use crate::models::arazzo::RequestBody;
+
+/// Return the request body payload with all `replacements` applied
+///
+/// Structured payloads are addressed with JSON Pointers (`/user/name`,
+/// optionally prefixed with `#`). XML documents given as strings are
+/// addressed with XPath (`/user/name/text()`, `/user/@id`).
+pub fn apply_replacements(body: &RequestBody) -> Result<serde_json::Value> {
+    let mut payload = body.payload.clone();
+    let encoding = BodyEncoding::from_content_type(body.content_type.as_deref());
+
+    for replacement in &body.replacements {
+        match &mut payload {
+            serde_json::Value::String(document) if encoding == BodyEncoding::Xml => {
+                *document = xml::replace_at(
+                    document,
+                    &replacement.target,
+                    &text_value(&replacement.value),
+                )?;
+            }
+            serde_json::Value::Object(_) | serde_json::Value::Array(_) => {
+                set_pointer(&mut payload, &replacement.target, replacement.value.clone())?;
+            }
+            _ => {
+                return Err(payload_error(format!(
+                    "Cannot apply replacement '{}' to a scalar payload",
+                    replacement.target
+                )));
+            }
+        }
+    }
+
+    Ok(payload)
+}
+
+/// Set the value addressed by a JSON Pointer, creating missing object members
+pub fn set_pointer(
+    root: &mut serde_json::Value,
+    pointer: &str,
+    value: serde_json::Value,
+) -> Result<()> {
+    let pointer = pointer.strip_prefix('#').unwrap_or(pointer);
+    if pointer.is_empty() {
+        *root = value;
+        return Ok(());
+    }
+    if !pointer.starts_with('/') {
+        return Err(payload_error(format!("Invalid JSON Pointer: {}", pointer)));
+    }
+
+    let tokens: Vec<String> = pointer[1..]
+        .split('/')
+        .map(|t| t.replace("~1", "/").replace("~0", "~"))
+        .collect();
+
+    let mut current = root;
+    for (i, token) in tokens.iter().enumerate() {
+        let is_last = i + 1 == tokens.len();
+        current = match current {
+            serde_json::Value::Object(map) => {
+                if is_last {
+                    map.insert(token.clone(), value);
+                    return Ok(());
+                }
+                map.entry(token.clone())
+                    .or_insert_with(|| serde_json::Value::Object(Default::default()))
+            }
+            serde_json::Value::Array(items) => {
+                if token == "-" {
+                    if is_last {
+                        items.push(value);
+                        return Ok(());
+                    }
+                    return Err(payload_error(format!("Invalid JSON Pointer: {}", pointer)));
+                }
+                let index: usize = token.parse().map_err(|_| {
+                    payload_error(format!("Invalid array index '{}' in {}", token, pointer))
+                })?;
+                let len = items.len();
+                let slot = items.get_mut(index).ok_or_else(|| {
+                    payload_error(format!(
+                        "Array index {} out of bounds (len {}) in {}",
+                        index, len, pointer
+                    ))
+                })?;
+                if is_last {
+                    *slot = value;
+                    return Ok(());
+                }
+                slot
+            }
+            _ => {
+                return Err(payload_error(format!(
+                    "JSON Pointer '{}' traverses a scalar value",
+                    pointer
+                )));
+            }
+        };
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::arazzo::PayloadReplacement;
+    use serde_json::json;
+
+    fn body(
+        content_type: &str,
+        payload: serde_json::Value,
+        replacements: &[(&str, serde_json::Value)],
+    ) -> RequestBody {
+        RequestBody {
+            content_type: Some(content_type.to_string()),
+            payload,
+            replacements: replacements
+                .iter()
+                .map(|(target, value)| PayloadReplacement {
+                    target: target.to_string(),
+                    value: value.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_json_pointer_replacements() {
+        let body = body(
+            "application/json",
+            json!({"user": {"name": "placeholder"}, "tags": ["a"]}),
+            &[
+                ("/user/name", json!("$inputs.name")),
+                ("#/tags/0", json!("b")),
+                ("/tags/-", json!("c")),
+                ("/meta/source", json!("hornet2")),
+            ],
+        );
+
+        let payload = apply_replacements(&body).unwrap();
+        assert_eq!(
+            payload,
+            json!({
+                "user": {"name": "$inputs.name"},
+                "tags": ["b", "c"],
+                "meta": {"source": "hornet2"}
+            })
+        );
+    }
+
+    #[test]
+    fn test_xpath_replacement() {
+        let body = body(
+            "application/xml",
+            json!("<user><id>0</id></user>"),
+            &[("/user/id", json!("$steps.create.outputs.id"))],
+        );
+
+        let payload = apply_replacements(&body).unwrap();
+        assert_eq!(
+            payload,
+            json!("<user><id>$steps.create.outputs.id</id></user>")
+        );
+    }
+
+    #[test]
+    fn test_out_of_bounds_index() {
+        let body = body("application/json", json!({"a": []}), &[("/a/3", json!(1))]);
+        assert!(apply_replacements(&body).is_err());
+    }
+}
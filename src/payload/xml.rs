@@ -0,0 +1,392 @@
+//! XML payload rendering and XPath replacements
+//!
+//! Only the subset of XPath needed for Arazzo `replacements` is supported:
+//! absolute element paths with optional 1-based positions, ending in either
+//! an element (`/order/items/item[2]`), its `text()` or an attribute
+//! (`/order/@id`).
+
+use super::{payload_error, text_value};
+use crate::error::Result;
+
+/// Render a JSON payload as XML
+///
+/// The object must have a single root key. Keys prefixed with `@` become
+/// attributes, `#text` sets the element text and arrays repeat the element.
+pub fn to_xml(value: &serde_json::Value) -> Result<String> {
+    let obj = value
+        .as_object()
+        .filter(|o| o.len() == 1)
+        .ok_or_else(|| payload_error("XML payload object must have exactly one root element"))?;
+
+    let mut out = String::new();
+    for (name, child) in obj {
+        write_element(name, child, &mut out);
+    }
+    Ok(out)
+}
+
+fn write_element(name: &str, value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Array(items) => {
+            for item in items {
+                write_element(name, item, out);
+            }
+        }
+        serde_json::Value::Object(obj) => {
+            out.push('<');
+            out.push_str(name);
+            for (key, v) in obj {
+                if let Some(attr) = key.strip_prefix('@') {
+                    out.push_str(&format!(" {}=\"{}\"", attr, escape_attr(&text_value(v))));
+                }
+            }
+            out.push('>');
+            for (key, v) in obj {
+                if key == "#text" {
+                    out.push_str(&escape_text(&text_value(v)));
+                } else if !key.starts_with('@') {
+                    write_element(key, v, out);
+                }
+            }
+            out.push_str(&format!("</{}>", name));
+        }
+        serde_json::Value::Null => out.push_str(&format!("<{}/>", name)),
+        scalar => out.push_str(&format!(
+            "<{}>{}</{}>",
+            name,
+            escape_text(&text_value(scalar)),
+            name
+        )),
+    }
+}
+
+/// Escape character data
+pub fn escape_text(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Escape an attribute value
+pub fn escape_attr(s: &str) -> String {
+    escape_text(s).replace('"', "&quot;")
+}
+
+/// A parsed XPath location
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct XPath {
+    /// Element steps (name, 1-based position)
+    steps: Vec<(String, usize)>,
+    /// Attribute to replace instead of the element text
+    attribute: Option<String>,
+}
+
+impl XPath {
+    fn parse(expr: &str) -> Result<Self> {
+        let rest = expr
+            .strip_prefix('/')
+            .ok_or_else(|| payload_error(format!("XPath must be absolute: {}", expr)))?;
+
+        let mut steps = Vec::new();
+        let mut attribute = None;
+        let segments: Vec<&str> = rest.split('/').collect();
+
+        for (i, segment) in segments.iter().enumerate() {
+            let is_last = i + 1 == segments.len();
+            if segment.is_empty() {
+                return Err(payload_error(format!("Unsupported XPath: {}", expr)));
+            }
+            if is_last && *segment == "text()" {
+                break;
+            }
+            if let Some(attr) = segment.strip_prefix('@') {
+                if !is_last {
+                    return Err(payload_error(format!("Unsupported XPath: {}", expr)));
+                }
+                attribute = Some(attr.to_string());
+                break;
+            }
+
+            let (name, position) = match segment.split_once('[') {
+                Some((name, index)) => {
+                    let index = index
+                        .strip_suffix(']')
+                        .and_then(|n| n.parse::<usize>().ok())
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| payload_error(format!("Unsupported XPath: {}", expr)))?;
+                    (name, index)
+                }
+                None => (*segment, 1),
+            };
+            steps.push((name.to_string(), position));
+        }
+
+        if steps.is_empty() {
+            return Err(payload_error(format!("XPath selects no element: {}", expr)));
+        }
+
+        Ok(Self { steps, attribute })
+    }
+}
+
+/// A start tag located in the document
+struct StartTag {
+    /// Byte offset of `<`
+    start: usize,
+    /// Byte offset just after `>`
+    end: usize,
+    self_closing: bool,
+}
+
+/// Replace the text or attribute selected by `xpath` with `value`
+pub fn replace_at(xml: &str, xpath: &str, value: &str) -> Result<String> {
+    let path = XPath::parse(xpath)?;
+    let tag = find_element(xml, &path.steps)
+        .ok_or_else(|| payload_error(format!("XPath '{}' did not match any element", xpath)))?;
+
+    let mut out = String::with_capacity(xml.len() + value.len());
+
+    if let Some(attr) = &path.attribute {
+        let open = &xml[tag.start..tag.end];
+        out.push_str(&xml[..tag.start]);
+        out.push_str(&set_attribute(
+            open,
+            attr,
+            &escape_attr(value),
+            tag.self_closing,
+        ));
+        out.push_str(&xml[tag.end..]);
+        return Ok(out);
+    }
+
+    if tag.self_closing {
+        let open = &xml[tag.start..tag.end];
+        let name = tag_name(open);
+        let attrs = open[1 + name.len()..open.len() - 2].trim_end();
+        out.push_str(&xml[..tag.start]);
+        out.push_str(&format!(
+            "<{}{}>{}</{}>",
+            name,
+            attrs,
+            escape_text(value),
+            name
+        ));
+        out.push_str(&xml[tag.end..]);
+        return Ok(out);
+    }
+
+    let close = find_closing(xml, tag.end)
+        .ok_or_else(|| payload_error(format!("Unclosed element selected by '{}'", xpath)))?;
+    out.push_str(&xml[..tag.end]);
+    out.push_str(&escape_text(value));
+    out.push_str(&xml[close..]);
+    Ok(out)
+}
+
+/// Walk the document and return the start tag matching `steps`
+fn find_element(xml: &str, steps: &[(String, usize)]) -> Option<StartTag> {
+    // Stack of open element names plus, per depth, how many siblings of each
+    // name have been seen so far.
+    let mut stack: Vec<(String, usize)> = Vec::new();
+    let mut seen: Vec<std::collections::HashMap<String, usize>> = vec![Default::default()];
+    let mut pos = 0;
+
+    while let Some(offset) = xml[pos..].find('<') {
+        let start = pos + offset;
+        let rest = &xml[start..];
+
+        if let Some(skip) = skip_markup(rest) {
+            pos = start + skip;
+            continue;
+        }
+
+        let end = start + rest.find('>')? + 1;
+        let tag = &xml[start..end];
+
+        if tag.starts_with("</") {
+            stack.pop();
+            seen.pop();
+        } else {
+            let name = tag_name(tag).to_string();
+            let self_closing = tag.ends_with("/>");
+            let depth = stack.len();
+
+            let count = seen
+                .get_mut(depth)
+                .map(|m| {
+                    let c = m.entry(name.clone()).or_insert(0);
+                    *c += 1;
+                    *c
+                })
+                .unwrap_or(1);
+
+            let matches_prefix = depth < steps.len()
+                && stack
+                    .iter()
+                    .zip(steps)
+                    .all(|((open, n), (step, i))| names_match(open, step) && n == i)
+                && names_match(&name, &steps[depth].0)
+                && count == steps[depth].1;
+
+            if matches_prefix && depth + 1 == steps.len() {
+                return Some(StartTag {
+                    start,
+                    end,
+                    self_closing,
+                });
+            }
+
+            if !self_closing {
+                stack.push((name, count));
+                seen.push(Default::default());
+            }
+        }
+
+        pos = end;
+    }
+
+    None
+}
+
+/// Find the offset of the closing tag for an element whose content starts at `from`
+fn find_closing(xml: &str, from: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut pos = from;
+
+    while let Some(offset) = xml[pos..].find('<') {
+        let start = pos + offset;
+        let rest = &xml[start..];
+
+        if let Some(skip) = skip_markup(rest) {
+            pos = start + skip;
+            continue;
+        }
+
+        let end = start + rest.find('>')? + 1;
+        let tag = &xml[start..end];
+
+        if tag.starts_with("</") {
+            if depth == 0 {
+                return Some(start);
+            }
+            depth -= 1;
+        } else if !tag.ends_with("/>") {
+            depth += 1;
+        }
+        pos = end;
+    }
+
+    None
+}
+
+/// Length of a comment, processing instruction, CDATA or doctype at the start of `rest`
+fn skip_markup(rest: &str) -> Option<usize> {
+    let terminator = if rest.starts_with("<!--") {
+        "-->"
+    } else if rest.starts_with("<![CDATA[") {
+        "]]>"
+    } else if rest.starts_with("<?") {
+        "?>"
+    } else if rest.starts_with("<!") {
+        ">"
+    } else {
+        return None;
+    };
+
+    rest.find(terminator)
+        .map(|i| i + terminator.len())
+        .or(Some(rest.len()))
+}
+
+fn tag_name(tag: &str) -> &str {
+    let inner = tag.trim_start_matches('<');
+    let end = inner
+        .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .unwrap_or(inner.len());
+    &inner[..end]
+}
+
+/// Compare an element name with an XPath step, ignoring the namespace prefix
+/// when the step does not specify one
+fn names_match(element: &str, step: &str) -> bool {
+    if step == "*" || element == step {
+        return true;
+    }
+    !step.contains(':') && element.rsplit(':').next() == Some(step)
+}
+
+fn set_attribute(open: &str, attr: &str, escaped: &str, self_closing: bool) -> String {
+    for quote in ['"', '\''] {
+        let needle = format!(" {}={}", attr, quote);
+        if let Some(idx) = open.find(&needle) {
+            let value_start = idx + needle.len();
+            if let Some(len) = open[value_start..].find(quote) {
+                return format!(
+                    "{}{}{}",
+                    &open[..value_start],
+                    escaped,
+                    &open[value_start + len..]
+                );
+            }
+        }
+    }
+
+    let tail = if self_closing { 2 } else { 1 };
+    let head = open[..open.len() - tail].trim_end();
+    format!(
+        "{} {}=\"{}\"{}",
+        head,
+        attr,
+        escaped,
+        &open[open.len() - tail..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_to_xml_attributes_and_arrays() {
+        let xml = to_xml(&json!({
+            "order": {
+                "@id": "42",
+                "item": [{"#text": "a"}, {"#text": "b & c"}],
+                "note": null
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            xml,
+            "<order id=\"42\"><item>a</item><item>b &amp; c</item><note/></order>"
+        );
+    }
+
+    #[test]
+    fn test_replace_text_with_position() {
+        let xml = "<?xml version=\"1.0\"?><order><item>a</item><item>b</item></order>";
+        let replaced = replace_at(xml, "/order/item[2]", "$inputs.sku").unwrap();
+        assert_eq!(
+            replaced,
+            "<?xml version=\"1.0\"?><order><item>a</item><item>$inputs.sku</item></order>"
+        );
+    }
+
+    #[test]
+    fn test_replace_attribute_and_self_closing() {
+        let xml = "<user id=\"1\"><name/></user>";
+        let with_attr = replace_at(xml, "/user/@id", "7").unwrap();
+        assert_eq!(with_attr, "<user id=\"7\"><name/></user>");
+
+        let with_text = replace_at(xml, "/user/name/text()", "bob").unwrap();
+        assert_eq!(with_text, "<user id=\"1\"><name>bob</name></user>");
+    }
+
+    #[test]
+    fn test_replace_unmatched_path() {
+        assert!(replace_at("<a><b/></a>", "/a/c", "x").is_err());
+        assert!(replace_at("<a/>", "a", "x").is_err());
+    }
+}
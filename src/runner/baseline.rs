@@ -0,0 +1,501 @@
+//! Comparing the metrics of a run against a stored baseline
+//!
+//! A baseline is the [`RunMetrics`] of an earlier run saved as JSON. A later
+//! run is compared step by step (plus one row for the whole run) on three
+//! numbers: the p95 latency, the throughput and the error rate. Latency and
+//! throughput tolerances are relative to the baseline, the error rate
+//! tolerance is an absolute difference. Steps only present on one side are
+//! listed but never count as a regression.
+
+use super::RunMetrics;
+use crate::error::{HornetError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::str::FromStr;
+
+/// How far a run may move from the baseline before it counts as a regression
+///
+/// `None` disables the check of that metric.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tolerance {
+    /// Allowed p95 increase, relative to the baseline (`0.1` = 10%)
+    pub p95: Option<f64>,
+    /// Allowed throughput decrease, relative to the baseline
+    pub throughput: Option<f64>,
+    /// Allowed error rate increase, as an absolute difference of rates
+    pub error_rate: Option<f64>,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            p95: Some(0.1),
+            throughput: Some(0.1),
+            error_rate: Some(0.01),
+        }
+    }
+}
+
+impl FromStr for Tolerance {
+    type Err = HornetError;
+
+    /// `p95=20%,throughput=off,errorRate=0.5%`; unlisted metrics keep their
+    /// defaults
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |part: &str| {
+            HornetError::ValidationError(format!(
+                "Invalid tolerance '{}': expected <metric>=<value> with metric p95, \
+                 throughput or errorRate and a value such as 10%, 0.1 or off",
+                part
+            ))
+        };
+        let mut tolerance = Self::default();
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (metric, value) = part.split_once('=').ok_or_else(|| invalid(part))?;
+            let value = value.trim();
+            let value = if value.eq_ignore_ascii_case("off") {
+                None
+            } else {
+                let number = match value.strip_suffix('%') {
+                    Some(percent) => percent.trim().parse::<f64>().map(|p| p / 100.0),
+                    None => value.parse::<f64>(),
+                }
+                .map_err(|_| invalid(part))?;
+                if !number.is_finite() || number < 0.0 {
+                    return Err(invalid(part));
+                }
+                Some(number)
+            };
+            match metric.trim() {
+                "p95" => tolerance.p95 = value,
+                "throughput" | "rps" => tolerance.throughput = value,
+                "errorRate" | "errors" => tolerance.error_rate = value,
+                _ => return Err(invalid(part)),
+            }
+        }
+        Ok(tolerance)
+    }
+}
+
+/// One metric of a row on both sides
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricDiff {
+    pub baseline: f64,
+    pub current: f64,
+    /// `current - baseline`
+    pub delta: f64,
+    /// Delta relative to the baseline, if the baseline is not zero
+    pub delta_ratio: Option<f64>,
+    pub regressed: bool,
+}
+
+impl MetricDiff {
+    fn new(baseline: f64, current: f64, regressed: bool) -> Self {
+        let delta = current - baseline;
+        Self {
+            baseline,
+            current,
+            delta,
+            delta_ratio: (baseline != 0.0).then(|| delta / baseline),
+            regressed,
+        }
+    }
+
+    /// Higher is worse; `tolerance` is relative
+    fn latency(baseline: f64, current: f64, tolerance: Option<f64>) -> Self {
+        let regressed = tolerance.is_some_and(|t| baseline > 0.0 && current > baseline * (1.0 + t));
+        Self::new(baseline, current, regressed)
+    }
+
+    /// Lower is worse; `tolerance` is relative
+    fn throughput(baseline: f64, current: f64, tolerance: Option<f64>) -> Self {
+        let regressed = tolerance.is_some_and(|t| baseline > 0.0 && current < baseline * (1.0 - t));
+        Self::new(baseline, current, regressed)
+    }
+
+    /// Higher is worse; `tolerance` is absolute
+    fn error_rate(baseline: f64, current: f64, tolerance: Option<f64>) -> Self {
+        let regressed = tolerance.is_some_and(|t| current - baseline > t);
+        Self::new(baseline, current, regressed)
+    }
+}
+
+/// Outcome of a row
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffStatus {
+    /// Within the tolerance
+    Ok,
+    /// At least one metric beyond the tolerance
+    Regressed,
+    /// Only in the current run
+    New,
+    /// Only in the baseline
+    Missing,
+}
+
+/// Comparison of one step, or of the whole run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffRow {
+    /// `None` for the row of the whole run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workflow_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step_id: Option<String>,
+    pub status: DiffStatus,
+    /// p95 latency in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p95_ms: Option<MetricDiff>,
+    /// Requests per second
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub throughput: Option<MetricDiff>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_rate: Option<MetricDiff>,
+}
+
+impl DiffRow {
+    /// `workflow/step`, or `(total)` for the whole run
+    pub fn name(&self) -> String {
+        match (&self.workflow_id, &self.step_id) {
+            (Some(workflow_id), Some(step_id)) => format!("{}/{}", workflow_id, step_id),
+            _ => "(total)".to_string(),
+        }
+    }
+
+    fn compare(
+        workflow_id: Option<&str>,
+        step_id: Option<&str>,
+        baseline: (f64, f64, f64),
+        current: (f64, f64, f64),
+        tolerance: &Tolerance,
+    ) -> Self {
+        let p95_ms = MetricDiff::latency(baseline.0, current.0, tolerance.p95);
+        let throughput = MetricDiff::throughput(baseline.1, current.1, tolerance.throughput);
+        let error_rate = MetricDiff::error_rate(baseline.2, current.2, tolerance.error_rate);
+        let regressed = p95_ms.regressed || throughput.regressed || error_rate.regressed;
+        Self {
+            workflow_id: workflow_id.map(str::to_string),
+            step_id: step_id.map(str::to_string),
+            status: if regressed {
+                DiffStatus::Regressed
+            } else {
+                DiffStatus::Ok
+            },
+            p95_ms: Some(p95_ms),
+            throughput: Some(throughput),
+            error_rate: Some(error_rate),
+        }
+    }
+
+    fn unmatched(workflow_id: &str, step_id: &str, status: DiffStatus) -> Self {
+        Self {
+            workflow_id: Some(workflow_id.to_string()),
+            step_id: Some(step_id.to_string()),
+            status,
+            p95_ms: None,
+            throughput: None,
+            error_rate: None,
+        }
+    }
+}
+
+/// Comparison of a run against a baseline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaselineDiff {
+    pub tolerance: Tolerance,
+    /// Steps in baseline order, then new steps, then the whole run
+    pub rows: Vec<DiffRow>,
+}
+
+impl BaselineDiff {
+    /// Compare `current` against `baseline`
+    pub fn compare(baseline: &RunMetrics, current: &RunMetrics, tolerance: Tolerance) -> Self {
+        let mut rows = Vec::new();
+        for step in &baseline.steps {
+            let row = match current.step(&step.workflow_id, &step.step_id) {
+                Some(now) => DiffRow::compare(
+                    Some(&step.workflow_id),
+                    Some(&step.step_id),
+                    (step.latency.p95, step.rps, step.error_rate),
+                    (now.latency.p95, now.rps, now.error_rate),
+                    &tolerance,
+                ),
+                None => DiffRow::unmatched(&step.workflow_id, &step.step_id, DiffStatus::Missing),
+            };
+            rows.push(row);
+        }
+        for step in &current.steps {
+            if baseline.step(&step.workflow_id, &step.step_id).is_none() {
+                rows.push(DiffRow::unmatched(
+                    &step.workflow_id,
+                    &step.step_id,
+                    DiffStatus::New,
+                ));
+            }
+        }
+        rows.push(DiffRow::compare(
+            None,
+            None,
+            (baseline.latency.p95, baseline.rps, baseline.error_rate),
+            (current.latency.p95, current.rps, current.error_rate),
+            &tolerance,
+        ));
+        Self { tolerance, rows }
+    }
+
+    pub fn regressions(&self) -> impl Iterator<Item = &DiffRow> {
+        self.rows
+            .iter()
+            .filter(|r| r.status == DiffStatus::Regressed)
+    }
+
+    pub fn regressed(&self) -> bool {
+        self.regressions().next().is_some()
+    }
+
+    /// Standalone HTML page with the diff table
+    pub fn to_html(&self) -> String {
+        let mut html = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>Baseline comparison</title>\n<style>\n\
+             body { font-family: sans-serif; margin: 2em; }\n\
+             table { border-collapse: collapse; }\n\
+             th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: right; }\n\
+             th:first-child, td:first-child { text-align: left; }\n\
+             .regressed { background: #fde2e2; }\n\
+             .new, .missing { color: #777; }\n\
+             .worse { color: #b00020; font-weight: bold; }\n\
+             </style>\n</head>\n<body>\n",
+        );
+        let status = if self.regressed() {
+            "Regressed"
+        } else {
+            "Within tolerance"
+        };
+        html.push_str(&format!(
+            "<h1>Baseline comparison: {}</h1>\n<p>Tolerance: {}</p>\n",
+            status,
+            escape(&describe_tolerance(&self.tolerance))
+        ));
+        html.push_str(
+            "<table>\n<tr><th>step</th><th>status</th>\
+             <th>p95 baseline (ms)</th><th>p95 current (ms)</th><th>p95 delta</th>\
+             <th>rps baseline</th><th>rps current</th><th>rps delta</th>\
+             <th>errors baseline</th><th>errors current</th><th>errors delta</th></tr>\n",
+        );
+        for row in &self.rows {
+            let status = match row.status {
+                DiffStatus::Ok => "ok",
+                DiffStatus::Regressed => "regressed",
+                DiffStatus::New => "new",
+                DiffStatus::Missing => "missing",
+            };
+            html.push_str(&format!(
+                "<tr class=\"{}\"><td>{}</td><td>{}</td>",
+                status,
+                escape(&row.name()),
+                status
+            ));
+            let cells = [
+                (row.p95_ms, Format::Millis),
+                (row.throughput, Format::Rate),
+                (row.error_rate, Format::Percent),
+            ];
+            for (diff, format) in cells {
+                match diff {
+                    Some(diff) => html.push_str(&format!(
+                        "<td>{}</td><td>{}</td><td{}>{}</td>",
+                        format.value(diff.baseline),
+                        format.value(diff.current),
+                        if diff.regressed {
+                            " class=\"worse\""
+                        } else {
+                            ""
+                        },
+                        format.delta(&diff)
+                    )),
+                    None => html.push_str("<td></td><td></td><td></td>"),
+                }
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</table>\n</body>\n</html>\n");
+        html
+    }
+
+    /// Write the diff (HTML for `.html` files, JSON otherwise)
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let content = if path
+            .extension()
+            .is_some_and(|ext| ext == "html" || ext == "htm")
+        {
+            self.to_html()
+        } else {
+            serde_json::to_string_pretty(self)?
+        };
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+/// How the numbers of a metric are printed
+#[derive(Debug, Clone, Copy)]
+pub enum Format {
+    Millis,
+    Rate,
+    Percent,
+}
+
+impl Format {
+    pub fn value(self, value: f64) -> String {
+        match self {
+            Self::Millis => format!("{:.2}", value),
+            Self::Rate => format!("{:.1}", value),
+            Self::Percent => format!("{:.2}%", value * 100.0),
+        }
+    }
+
+    /// Relative change for latencies and rates, percentage points for errors
+    pub fn delta(self, diff: &MetricDiff) -> String {
+        match (self, diff.delta_ratio) {
+            (Self::Percent, _) => format!("{:+.2}pt", diff.delta * 100.0),
+            (_, Some(ratio)) => format!("{:+.1}%", ratio * 100.0),
+            (_, None) => format!("{:+.2}", diff.delta),
+        }
+    }
+}
+
+/// `p95 +10%, throughput -10%, errorRate +1.00pt`
+pub fn describe_tolerance(tolerance: &Tolerance) -> String {
+    let describe = |value: Option<f64>, sign: &str, unit: &str, scale: f64| match value {
+        Some(value) => format!("{}{}{}", sign, (value * scale * 1e6).round() / 1e6, unit),
+        None => "off".to_string(),
+    };
+    format!(
+        "p95 {}, throughput {}, errorRate {}",
+        describe(tolerance.p95, "+", "%", 100.0),
+        describe(tolerance.throughput, "-", "%", 100.0),
+        describe(tolerance.error_rate, "+", "pt", 100.0)
+    )
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{LatencySummary, StepRunMetrics};
+
+    fn step(step_id: &str, p95: f64, rps: f64, error_rate: f64) -> StepRunMetrics {
+        StepRunMetrics {
+            workflow_id: "browse".to_string(),
+            step_id: step_id.to_string(),
+            requests: 100,
+            errors: (error_rate * 100.0) as u64,
+            rps,
+            error_rate,
+            latency: LatencySummary {
+                p95,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn metrics(steps: Vec<StepRunMetrics>) -> RunMetrics {
+        RunMetrics {
+            latency: LatencySummary {
+                p95: 100.0,
+                ..Default::default()
+            },
+            rps: 50.0,
+            steps,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_tolerance_from_str() {
+        assert_eq!("".parse::<Tolerance>().unwrap(), Tolerance::default());
+        let tolerance: Tolerance = "p95=25%, throughput=off, errorRate=0.005".parse().unwrap();
+        assert_eq!(tolerance.p95, Some(0.25));
+        assert_eq!(tolerance.throughput, None);
+        assert_eq!(tolerance.error_rate, Some(0.005));
+
+        // 異常系: 未知の指標、数値でない値、負の値
+        for invalid in ["p99=10%", "p95=fast", "throughput=-5%", "p95"] {
+            assert!(invalid.parse::<Tolerance>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = metrics(vec![
+            step("list", 100.0, 20.0, 0.0),
+            step("search", 200.0, 20.0, 0.0),
+            step("detail", 50.0, 10.0, 0.0),
+        ]);
+        let current = metrics(vec![
+            // 許容範囲内
+            step("list", 109.0, 18.5, 0.005),
+            // p95 が 10% を超えて悪化
+            step("search", 230.0, 20.0, 0.0),
+            step("cart", 10.0, 5.0, 0.0),
+        ]);
+        let diff = BaselineDiff::compare(&baseline, &current, Tolerance::default());
+        let statuses: Vec<(String, DiffStatus)> =
+            diff.rows.iter().map(|r| (r.name(), r.status)).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("browse/list".to_string(), DiffStatus::Ok),
+                ("browse/search".to_string(), DiffStatus::Regressed),
+                ("browse/detail".to_string(), DiffStatus::Missing),
+                ("browse/cart".to_string(), DiffStatus::New),
+                ("(total)".to_string(), DiffStatus::Ok),
+            ]
+        );
+        assert!(diff.regressed());
+        let search = diff.rows[1].p95_ms.unwrap();
+        assert_eq!(search.delta, 30.0);
+        assert!((search.delta_ratio.unwrap() - 0.15).abs() < 1e-9);
+
+        // スループットの低下とエラー率の上昇
+        let current = metrics(vec![
+            step("list", 100.0, 15.0, 0.0),
+            step("search", 200.0, 20.0, 0.03),
+            step("detail", 50.0, 10.0, 0.0),
+        ]);
+        let diff = BaselineDiff::compare(&baseline, &current, Tolerance::default());
+        let regressed: Vec<String> = diff.regressions().map(|r| r.name()).collect();
+        assert_eq!(regressed, vec!["browse/list", "browse/search"]);
+        assert!(diff.rows[0].throughput.unwrap().regressed);
+        assert!(diff.rows[1].error_rate.unwrap().regressed);
+
+        // 無効にした指標は見ない
+        let lenient = Tolerance {
+            throughput: None,
+            error_rate: None,
+            ..Default::default()
+        };
+        assert!(!BaselineDiff::compare(&baseline, &current, lenient).regressed());
+    }
+
+    #[test]
+    fn test_to_html() {
+        let baseline = metrics(vec![step("<search>", 200.0, 20.0, 0.0)]);
+        let current = metrics(vec![step("<search>", 300.0, 20.0, 0.0)]);
+        let html = BaselineDiff::compare(&baseline, &current, Tolerance::default()).to_html();
+        assert!(html.contains("<h1>Baseline comparison: Regressed</h1>"));
+        assert!(html.contains("<td>browse/&lt;search&gt;</td><td>regressed</td>"));
+        assert!(html.contains("<td class=\"worse\">+50.0%</td>"));
+    }
+}
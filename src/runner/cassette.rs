@@ -0,0 +1,493 @@
+//! Record/replay cassettes of native runs
+//!
+//! In record mode every exchange sent by the [`NativeExecutor`] is appended
+//! to a cassette, with secrets (credential headers, query parameters and
+//! body properties such as `password` or `token`) replaced by
+//! [`REDACTED`]. In replay mode the executor does not touch the network:
+//! each request is answered by the first unused interaction with the same
+//! method and templated path whose body satisfies the [`BodyMatcher`].
+//!
+//! Cassettes are YAML, or JSON when the file name ends with `.json`.
+//!
+//! [`NativeExecutor`]: super::NativeExecutor
+
+use super::native::{PreparedRequest, StepResponse};
+use crate::error::{HornetError, Result};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Placeholder written instead of a secret (matches anything on replay)
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Header, query parameter and property names redacted by default
+const SENSITIVE_NAMES: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-api-key",
+    "api-key",
+    "apikey",
+    "api_key",
+    "password",
+    "secret",
+    "client_secret",
+    "token",
+    "access_token",
+    "refresh_token",
+    "id_token",
+];
+
+/// Recorded exchanges of a run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Cassette {
+    #[serde(default)]
+    pub interactions: Vec<Interaction>,
+}
+
+/// A request and the response it received
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    /// HTTP method (upper case)
+    pub method: String,
+    /// OpenAPI path template (`/pets/{petId}`)
+    pub path: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub headers: IndexMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub headers: IndexMap<String, String>,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub body: Value,
+    #[serde(default)]
+    pub duration_ms: u64,
+}
+
+impl Cassette {
+    /// Read a cassette (JSON for `.json`, YAML otherwise)
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        if is_json(path) {
+            Ok(serde_json::from_str(&content)?)
+        } else {
+            Ok(serde_yaml::from_str(&content)?)
+        }
+    }
+
+    /// Write the cassette (JSON for `.json`, YAML otherwise)
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = if is_json(path) {
+            serde_json::to_string_pretty(self)?
+        } else {
+            serde_yaml::to_string(self)?
+        };
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+/// Replaces secrets before an exchange is written
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    /// Lower-case names of headers, query parameters and properties
+    names: Vec<String>,
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self {
+            names: SENSITIVE_NAMES.iter().map(|n| n.to_string()).collect(),
+        }
+    }
+}
+
+impl Redactor {
+    /// Redact `extra` names on top of the default ones
+    pub fn with_names(extra: &[String]) -> Self {
+        let mut redactor = Self::default();
+        redactor
+            .names
+            .extend(extra.iter().map(|n| n.to_lowercase()));
+        redactor
+    }
+
+    fn is_sensitive(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        self.names.contains(&name)
+    }
+
+    fn headers(&self, headers: &[(String, String)]) -> IndexMap<String, String> {
+        let mut map: IndexMap<String, String> = IndexMap::new();
+        for (name, value) in headers {
+            let value = if self.is_sensitive(name) {
+                REDACTED
+            } else {
+                value
+            };
+            map.entry(name.clone())
+                .and_modify(|existing| {
+                    existing.push_str(", ");
+                    existing.push_str(value);
+                })
+                .or_insert_with(|| value.to_string());
+        }
+        map
+    }
+
+    fn url(&self, url: &str) -> String {
+        let Ok(mut parsed) = reqwest::Url::parse(url) else {
+            return url.to_string();
+        };
+        if parsed.query().is_none() {
+            return url.to_string();
+        }
+        let pairs: Vec<(String, String)> = parsed
+            .query_pairs()
+            .map(|(k, v)| {
+                let v = if self.is_sensitive(&k) {
+                    REDACTED.to_string()
+                } else {
+                    v.into_owned()
+                };
+                (k.into_owned(), v)
+            })
+            .collect();
+        parsed.query_pairs_mut().clear().extend_pairs(pairs);
+        parsed.to_string()
+    }
+
+    fn value(&self, value: &Value) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| {
+                        let v = if self.is_sensitive(k) && !v.is_object() && !v.is_array() {
+                            Value::String(REDACTED.to_string())
+                        } else {
+                            self.value(v)
+                        };
+                        (k.clone(), v)
+                    })
+                    .collect(),
+            ),
+            Value::Array(items) => Value::Array(items.iter().map(|v| self.value(v)).collect()),
+            other => other.clone(),
+        }
+    }
+}
+
+/// How request bodies are compared on replay
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum BodyMatcher {
+    /// Bodies are not compared
+    #[default]
+    Ignore,
+    /// The whole JSON body must be equal
+    Exact,
+    /// Only the values at these JSON Pointers must be equal
+    Pointers(Vec<String>),
+}
+
+impl FromStr for BodyMatcher {
+    type Err = HornetError;
+
+    /// `none`, `exact`, or comma-separated JSON Pointers (`/name,/owner/id`)
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            "" | "none" => Ok(Self::Ignore),
+            "exact" => Ok(Self::Exact),
+            pointers => {
+                let pointers: Vec<String> = pointers
+                    .split(',')
+                    .map(|p| p.trim().trim_start_matches('#').to_string())
+                    .collect();
+                match pointers.iter().find(|p| !p.starts_with('/')) {
+                    Some(invalid) => Err(HornetError::ValidationError(format!(
+                        "Invalid body matcher '{}' (expected none, exact or JSON Pointers)",
+                        invalid
+                    ))),
+                    None => Ok(Self::Pointers(pointers)),
+                }
+            }
+        }
+    }
+}
+
+impl BodyMatcher {
+    fn matches(&self, recorded: Option<&Value>, actual: Option<&Value>) -> bool {
+        let null = Value::Null;
+        let recorded = recorded.unwrap_or(&null);
+        let actual = actual.unwrap_or(&null);
+        match self {
+            Self::Ignore => true,
+            Self::Exact => same(recorded, actual),
+            Self::Pointers(pointers) => pointers.iter().all(|p| {
+                same(
+                    recorded.pointer(p).unwrap_or(&null),
+                    actual.pointer(p).unwrap_or(&null),
+                )
+            }),
+        }
+    }
+}
+
+/// Equality where a redacted recorded value matches anything
+fn same(recorded: &Value, actual: &Value) -> bool {
+    match (recorded, actual) {
+        (Value::String(s), _) if s == REDACTED => true,
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(k, v)| b.get(k).is_some_and(|other| same(v, other)))
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(x, y)| same(x, y))
+        }
+        _ => recorded == actual,
+    }
+}
+
+/// Appends the exchanges of a run to a cassette
+#[derive(Debug, Default)]
+pub struct Recorder {
+    redactor: Redactor,
+    cassette: Mutex<Cassette>,
+}
+
+impl Recorder {
+    pub fn new(redactor: Redactor) -> Self {
+        Self {
+            redactor,
+            cassette: Mutex::default(),
+        }
+    }
+
+    pub fn record(&self, request: &PreparedRequest, response: &StepResponse) {
+        let interaction = Interaction {
+            request: RecordedRequest {
+                method: request.method.to_uppercase(),
+                path: request.path_template.clone(),
+                url: self.redactor.url(&request.url),
+                headers: self.redactor.headers(&request.headers),
+                body: request.body.as_ref().map(|b| self.redactor.value(b)),
+            },
+            response: RecordedResponse {
+                status: response.status,
+                headers: self.redactor.headers(&response.headers),
+                body: self.redactor.value(&response.body),
+                duration_ms: response.elapsed.as_millis() as u64,
+            },
+        };
+        if let Ok(mut cassette) = self.cassette.lock() {
+            cassette.interactions.push(interaction);
+        }
+    }
+
+    /// The interactions recorded so far
+    pub fn cassette(&self) -> Cassette {
+        self.cassette.lock().map(|c| c.clone()).unwrap_or_default()
+    }
+}
+
+/// Answers requests from a cassette
+#[derive(Debug)]
+pub struct Replayer {
+    cassette: Cassette,
+    matcher: BodyMatcher,
+    used: Mutex<Vec<bool>>,
+}
+
+impl Replayer {
+    pub fn new(cassette: Cassette, matcher: BodyMatcher) -> Self {
+        let used = vec![false; cassette.interactions.len()];
+        Self {
+            cassette,
+            matcher,
+            used: Mutex::new(used),
+        }
+    }
+
+    /// Response of the first unused matching interaction
+    ///
+    /// Once every matching interaction has been used, the last one is
+    /// served again so that retries and loops keep working.
+    pub fn respond(&self, request: &PreparedRequest) -> Option<StepResponse> {
+        let candidates: Vec<usize> = self
+            .cassette
+            .interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| {
+                i.request.method.eq_ignore_ascii_case(&request.method)
+                    && i.request.path == request.path_template
+                    && self
+                        .matcher
+                        .matches(i.request.body.as_ref(), request.body.as_ref())
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        let mut used = self.used.lock().ok()?;
+        let index = candidates
+            .iter()
+            .copied()
+            .find(|i| !used[*i])
+            .or(candidates.last().copied())?;
+        used[index] = true;
+
+        let response = &self.cassette.interactions[index].response;
+        Some(StepResponse {
+            status: response.status,
+            headers: response
+                .headers
+                .iter()
+                .map(|(k, v)| (k.to_lowercase(), v.clone()))
+                .collect(),
+            body: response.body.clone(),
+            elapsed: Duration::from_millis(response.duration_ms),
+        })
+    }
+}
+
+/// Cassette attached to an executor
+#[derive(Debug, Clone)]
+pub enum CassetteMode {
+    Record(Arc<Recorder>),
+    Replay(Arc<Replayer>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn request(path: &str, body: Option<Value>) -> PreparedRequest {
+        PreparedRequest {
+            method: "POST".to_string(),
+            url: "https://api.example.com/login?api_key=abc&page=1".to_string(),
+            path_template: path.to_string(),
+            path_params: vec![],
+            query: vec![],
+            headers: vec![
+                ("Authorization".to_string(), "Bearer xyz".to_string()),
+                ("Accept".to_string(), "application/json".to_string()),
+            ],
+            body,
+            encoded: None,
+        }
+    }
+
+    fn response(body: Value) -> StepResponse {
+        StepResponse {
+            status: 200,
+            headers: vec![("set-cookie".to_string(), "session=1".to_string())],
+            body,
+            elapsed: Duration::from_millis(5),
+        }
+    }
+
+    #[test]
+    fn test_record_redacts_secrets() {
+        let recorder = Recorder::new(Redactor::with_names(&["X-Tenant".to_string()]));
+        let mut req = request("/login", Some(json!({"user": "alice", "password": "p"})));
+        req.headers
+            .push(("X-Tenant".to_string(), "acme".to_string()));
+        recorder.record(
+            &req,
+            &response(json!({"token": "t", "profile": {"refresh_token": "r", "name": "A"}})),
+        );
+
+        let cassette = recorder.cassette();
+        let recorded = &cassette.interactions[0];
+        assert_eq!(recorded.request.headers["Authorization"], REDACTED);
+        assert_eq!(recorded.request.headers["Accept"], "application/json");
+        assert_eq!(recorded.request.headers["X-Tenant"], REDACTED);
+        assert_eq!(
+            recorded.request.url,
+            "https://api.example.com/login?api_key=%5BREDACTED%5D&page=1"
+        );
+        assert_eq!(
+            recorded.request.body,
+            Some(json!({"user": "alice", "password": REDACTED}))
+        );
+        assert_eq!(recorded.response.headers["set-cookie"], REDACTED);
+        assert_eq!(
+            recorded.response.body,
+            json!({"token": REDACTED, "profile": {"refresh_token": REDACTED, "name": "A"}})
+        );
+    }
+
+    #[test]
+    fn test_replay_matching() {
+        let recorder = Recorder::default();
+        recorder.record(
+            &request("/pets", Some(json!({"name": "Rex", "password": "p"}))),
+            &response(json!({"id": 1})),
+        );
+        recorder.record(
+            &request("/pets", Some(json!({"name": "Tama"}))),
+            &response(json!({"id": 2})),
+        );
+        let cassette = recorder.cassette();
+
+        // 本文を比較しなければ記録順に返し、使い切ったら最後を繰り返す
+        let replayer = Replayer::new(cassette.clone(), BodyMatcher::Ignore);
+        let ids: Vec<Value> = (0..3)
+            .map(|_| replayer.respond(&request("/pets", None)).unwrap().body["id"].clone())
+            .collect();
+        assert_eq!(ids, vec![json!(1), json!(2), json!(2)]);
+        assert!(replayer.respond(&request("/owners", None)).is_none());
+
+        // 秘匿した値は何にでも一致する
+        let replayer = Replayer::new(cassette.clone(), BodyMatcher::Exact);
+        let matched = replayer.respond(&request(
+            "/pets",
+            Some(json!({"name": "Rex", "password": "other"})),
+        ));
+        assert_eq!(matched.unwrap().body, json!({"id": 1}));
+
+        let replayer = Replayer::new(cassette, "/name".parse().unwrap());
+        let matched = replayer.respond(&request("/pets", Some(json!({"name": "Tama", "x": 1}))));
+        assert_eq!(matched.unwrap().body, json!({"id": 2}));
+        assert!(
+            replayer
+                .respond(&request("/pets", Some(json!({"name": "Pochi"}))))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_body_matcher_from_str() {
+        assert_eq!("none".parse::<BodyMatcher>().unwrap(), BodyMatcher::Ignore);
+        assert_eq!("exact".parse::<BodyMatcher>().unwrap(), BodyMatcher::Exact);
+        assert_eq!(
+            "/name, #/owner/id".parse::<BodyMatcher>().unwrap(),
+            BodyMatcher::Pointers(vec!["/name".to_string(), "/owner/id".to_string()])
+        );
+        assert!("name".parse::<BodyMatcher>().is_err());
+    }
+}
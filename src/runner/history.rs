@@ -0,0 +1,368 @@
+//! Run history of a project
+//!
+//! Every recorded run is one JSON file under `.hornet/runs/` in the project
+//! directory, named after its id. Ids start with the start time in
+//! milliseconds, so that sorting them sorts the runs. A record keeps the
+//! environment, the git commit of the project, the outcome of each executed
+//! step and the metrics of the run.
+//!
+//! Trends are built per step over the latest runs of a workflow: the
+//! latency and outcome of every run, the pass rate, and how often the
+//! outcome flipped. A step that both passed and failed in the window and
+//! flipped more than once is flagged as flaky.
+
+use super::RunMetrics;
+use super::load::LoadMetrics;
+use super::native::WorkflowRun;
+use crate::error::{HornetError, Result};
+use crate::generator::sample::Rng;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Directory of the history, relative to the project directory
+pub const HISTORY_DIR: &str = ".hornet/runs";
+
+/// Outcome of one executed step
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepRecord {
+    pub step_id: String,
+    pub passed: bool,
+    /// Response time, absent when no response was received
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    /// Execution error and failed criteria
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<String>,
+}
+
+/// A recorded run of a workflow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunRecord {
+    pub id: String,
+    /// Start time in milliseconds since the Unix epoch
+    pub started_at: u64,
+    pub duration_ms: f64,
+    pub workflow_id: String,
+    /// Environment name given by the caller (`staging`, `ci`, …)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// Commit checked out in the project directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_commit: Option<String>,
+    pub success: bool,
+    /// Reason the run stopped early, if it failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure: Option<String>,
+    pub steps: Vec<StepRecord>,
+    pub metrics: RunMetrics,
+}
+
+/// Milliseconds since the Unix epoch
+fn epoch_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// A new run id for a run that started at `started_at`
+///
+/// The start time comes first so that ids sort like the runs.
+pub fn new_run_id(started_at: SystemTime) -> String {
+    format!(
+        "{}-{:04x}",
+        epoch_millis(started_at),
+        Rng::from_time().next_u64() & 0xffff
+    )
+}
+
+impl RunRecord {
+    /// Record of a finished run that started at `started_at`, with the
+    /// metrics of that single run
+    pub fn new(run: &WorkflowRun, started_at: SystemTime, elapsed: Duration) -> Self {
+        let mut load = LoadMetrics::default();
+        load.record(run, elapsed);
+        let metrics = RunMetrics {
+            vus: 1,
+            ..RunMetrics::from(&load.summary(elapsed))
+        };
+        let id = new_run_id(started_at);
+        let started_at = epoch_millis(started_at);
+        let steps = run
+            .steps
+            .iter()
+            .map(|step| {
+                let mut failures: Vec<String> =
+                    step.error.iter().map(|e| e.message.clone()).collect();
+                failures.extend(step.failures.iter().cloned());
+                StepRecord {
+                    step_id: step.step_id.clone(),
+                    passed: step.passed(),
+                    latency_ms: step
+                        .response
+                        .as_ref()
+                        .map(|r| r.elapsed.as_secs_f64() * 1000.0),
+                    status_code: step.response.as_ref().map(|r| r.status),
+                    failures,
+                }
+            })
+            .collect();
+        Self {
+            id,
+            started_at,
+            duration_ms: elapsed.as_secs_f64() * 1000.0,
+            workflow_id: run.workflow_id.clone(),
+            environment: None,
+            base_url: None,
+            git_commit: None,
+            success: run.success(),
+            failure: run.failure.clone(),
+            steps,
+            metrics,
+        }
+    }
+
+    pub fn step(&self, step_id: &str) -> Option<&StepRecord> {
+        self.steps.iter().find(|s| s.step_id == step_id)
+    }
+}
+
+/// One run in the trend of a step
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendPoint {
+    pub run_id: String,
+    pub started_at: u64,
+    pub passed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<f64>,
+}
+
+/// Outcomes and latencies of a step over the latest runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepTrend {
+    pub step_id: String,
+    /// Runs that executed the step
+    pub runs: usize,
+    pub passed: usize,
+    pub pass_rate: f64,
+    /// Times the outcome changed from one run to the next
+    pub flips: usize,
+    pub flaky: bool,
+    /// Oldest first, for charting
+    pub points: Vec<TrendPoint>,
+}
+
+/// Per-step trends of `runs` (in any order), in the step order of the
+/// latest run
+pub fn step_trends(runs: &[RunRecord]) -> Vec<StepTrend> {
+    let mut runs: Vec<&RunRecord> = runs.iter().collect();
+    runs.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut step_ids: Vec<&str> = Vec::new();
+    for run in runs.iter().rev() {
+        for step in &run.steps {
+            if !step_ids.contains(&step.step_id.as_str()) {
+                step_ids.push(&step.step_id);
+            }
+        }
+    }
+
+    step_ids
+        .into_iter()
+        .map(|step_id| {
+            let points: Vec<TrendPoint> = runs
+                .iter()
+                .filter_map(|run| {
+                    run.step(step_id).map(|step| TrendPoint {
+                        run_id: run.id.clone(),
+                        started_at: run.started_at,
+                        passed: step.passed,
+                        latency_ms: step.latency_ms,
+                    })
+                })
+                .collect();
+            let passed = points.iter().filter(|p| p.passed).count();
+            let flips = points
+                .windows(2)
+                .filter(|w| w[0].passed != w[1].passed)
+                .count();
+            StepTrend {
+                step_id: step_id.to_string(),
+                runs: points.len(),
+                passed,
+                pass_rate: passed as f64 / points.len() as f64,
+                flips,
+                flaky: passed > 0 && passed < points.len() && flips > 1,
+                points,
+            }
+        })
+        .collect()
+}
+
+/// The run history of a project directory
+#[derive(Debug, Clone)]
+pub struct RunHistory {
+    dir: PathBuf,
+}
+
+impl RunHistory {
+    pub fn new(project_dir: &Path) -> Self {
+        Self {
+            dir: project_dir.join(HISTORY_DIR),
+        }
+    }
+
+    pub fn save(&self, record: &RunRecord) -> Result<PathBuf> {
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.path(&record.id)?;
+        std::fs::write(&path, serde_json::to_string_pretty(record)?)?;
+        Ok(path)
+    }
+
+    /// A run by id, `None` if it is not recorded
+    pub fn get(&self, id: &str) -> Result<Option<RunRecord>> {
+        let path = self.path(id)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    /// Runs, newest first, optionally of one workflow only
+    ///
+    /// Files that cannot be read are skipped.
+    pub fn list(&self, workflow_id: Option<&str>) -> Result<Vec<RunRecord>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut runs: Vec<RunRecord> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| std::fs::read_to_string(path).ok())
+            .filter_map(|content| serde_json::from_str::<RunRecord>(&content).ok())
+            .filter(|run| workflow_id.is_none_or(|id| run.workflow_id == id))
+            .collect();
+        runs.sort_by(|a, b| b.id.cmp(&a.id));
+        Ok(runs)
+    }
+
+    fn path(&self, id: &str) -> Result<PathBuf> {
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(HornetError::ValidationError(format!(
+                "Invalid run id '{}'",
+                id
+            )));
+        }
+        Ok(self.dir.join(format!("{}.json", id)))
+    }
+}
+
+/// Commit checked out in `dir`, if it is in a git work tree
+pub fn git_commit(dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|commit| !commit.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: &str, outcomes: &[(&str, bool)]) -> RunRecord {
+        RunRecord {
+            id: id.to_string(),
+            started_at: id.parse().unwrap(),
+            duration_ms: 10.0,
+            workflow_id: "checkout".to_string(),
+            environment: None,
+            base_url: None,
+            git_commit: None,
+            success: outcomes.iter().all(|(_, passed)| *passed),
+            failure: None,
+            steps: outcomes
+                .iter()
+                .map(|(step_id, passed)| StepRecord {
+                    step_id: step_id.to_string(),
+                    passed: *passed,
+                    latency_ms: Some(5.0),
+                    status_code: Some(200),
+                    failures: Vec::new(),
+                })
+                .collect(),
+            metrics: RunMetrics::default(),
+        }
+    }
+
+    #[test]
+    fn test_step_trends() {
+        let runs = vec![
+            record("3", &[("cart", true), ("pay", true)]),
+            record("1", &[("cart", true), ("pay", false)]),
+            record("2", &[("cart", true), ("pay", true)]),
+            record("4", &[("cart", false), ("pay", false)]),
+        ];
+        let trends = step_trends(&runs);
+        assert_eq!(trends.len(), 2);
+
+        let cart = &trends[0];
+        assert_eq!(cart.step_id, "cart");
+        assert_eq!(cart.passed, 3);
+        assert_eq!(cart.pass_rate, 0.75);
+        // 1回だけ落ちたのは劣化であって不安定ではない
+        assert_eq!(cart.flips, 1);
+        assert!(!cart.flaky);
+
+        let pay = &trends[1];
+        let ids: Vec<&str> = pay.points.iter().map(|p| p.run_id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2", "3", "4"]);
+        assert_eq!(pay.flips, 2);
+        assert!(pay.flaky);
+    }
+
+    #[test]
+    fn test_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = RunHistory::new(dir.path());
+        assert!(history.list(None).unwrap().is_empty());
+
+        history.save(&record("100", &[("cart", true)])).unwrap();
+        history.save(&record("200", &[("cart", false)])).unwrap();
+        let mut other = record("150", &[("search", true)]);
+        other.workflow_id = "browse".to_string();
+        history.save(&other).unwrap();
+
+        let ids: Vec<String> = history
+            .list(None)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(ids, vec!["200", "150", "100"]);
+        assert_eq!(history.list(Some("browse")).unwrap().len(), 1);
+        assert!(!history.get("200").unwrap().unwrap().success);
+        assert!(history.get("300").unwrap().is_none());
+
+        // 異常系: 履歴の外を指す ID
+        assert!(history.get("../arazzo").is_err());
+    }
+}
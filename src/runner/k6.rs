@@ -62,17 +62,36 @@ impl K6Runner {
             metrics.iterations = caps[1].parse().unwrap_or(0);
         }
 
-        // Parse http_req_duration avg
-        let duration_re = Regex::new(r"http_req_duration[.\s]+:.*avg=(\d+\.?\d*)(\w+)").ok()?;
+        // Parse http_req_duration statistics (avg, min, med, max, p(90), p(95))
+        let duration_re = Regex::new(r"http_req_duration[.\s]+:(.*)").ok()?;
+        let statistic_re = Regex::new(r"([\w()]+)=(\d+\.?\d*)([a-zµ]+)").ok()?;
         if let Some(caps) = duration_re.captures(stdout) {
-            let value: f64 = caps[1].parse().unwrap_or(0.0);
-            let unit = &caps[2];
-            metrics.avg_response_time_ms = match unit {
-                "s" => value * 1000.0,
-                "ms" => value,
-                "µs" | "us" => value / 1000.0,
-                _ => value,
-            };
+            for stat in statistic_re.captures_iter(&caps[1]) {
+                let value = to_millis(stat[2].parse().unwrap_or(0.0), &stat[3]);
+                match &stat[1] {
+                    "avg" => {
+                        metrics.avg_response_time_ms = value;
+                        metrics.latency.mean = value;
+                    }
+                    "min" => metrics.latency.min = value,
+                    "med" => metrics.latency.p50 = value,
+                    "max" => metrics.latency.max = value,
+                    "p(90)" => metrics.latency.p90 = value,
+                    "p(95)" => metrics.latency.p95 = value,
+                    "p(99)" => metrics.latency.p99 = value,
+                    _ => {}
+                }
+            }
+        }
+
+        // Parse the request rate and the share of failed requests
+        let rate_re = Regex::new(r"http_reqs[.\s]+:\s+\d+\s+(\d+\.?\d*)/s").ok()?;
+        if let Some(caps) = rate_re.captures(stdout) {
+            metrics.rps = caps[1].parse().unwrap_or(0.0);
+        }
+        let failed_re = Regex::new(r"http_req_failed[.\s]+:\s+(\d+\.?\d*)%").ok()?;
+        if let Some(caps) = failed_re.captures(stdout) {
+            metrics.error_rate = caps[1].parse::<f64>().unwrap_or(0.0) / 100.0;
         }
 
         // Parse checks
@@ -92,6 +111,17 @@ impl K6Runner {
     }
 }
 
+/// Convert a k6 duration value to milliseconds
+fn to_millis(value: f64, unit: &str) -> f64 {
+    match unit {
+        "m" => value * 60_000.0,
+        "s" => value * 1000.0,
+        "µs" | "us" => value / 1000.0,
+        "ns" => value / 1_000_000.0,
+        _ => value,
+    }
+}
+
 impl Runner for K6Runner {
     fn is_available(&self) -> bool {
         Command::new(&self.k6_path)
@@ -224,6 +254,11 @@ mod tests {
         assert_eq!(metrics.checks_passed, 8);
         assert_eq!(metrics.checks_failed, 0);
         assert_eq!(metrics.vus, 1);
+        assert_eq!(metrics.latency.p50, 120.0);
+        assert_eq!(metrics.latency.p95, 145.0);
+        assert_eq!(metrics.latency.max, 150.0);
+        assert!((metrics.rps - 0.123456).abs() < 1e-9);
+        assert_eq!(metrics.error_rate, 0.0);
     }
 
     #[test]
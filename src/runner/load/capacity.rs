@@ -0,0 +1,286 @@
+//! Capacity search: the highest arrival rate that still meets the SLOs
+//!
+//! Runs the mix at a series of constant arrival rates, each for the same
+//! duration, and checks the step SLOs after every probe. The step strategy
+//! raises the rate by a fixed increment until a probe fails; the binary
+//! strategy bisects between the highest passing and the lowest failing rate
+//! until they are closer than the resolution. A probe that drops iterations
+//! fails as well, since it did not actually sustain its rate.
+
+use super::{LoadModel, LoadSummary, LoadTest, Progress};
+use crate::error::{HornetError, Result};
+use crate::models::slo::StepObjective;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// How the next rate is chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchStrategy {
+    /// Raise the rate by a fixed increment until a probe fails
+    Step,
+    /// Bisect between the highest passing and the lowest failing rate
+    Binary,
+}
+
+impl FromStr for SearchStrategy {
+    type Err = HornetError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "step" => Ok(Self::Step),
+            "binary" => Ok(Self::Binary),
+            _ => Err(HornetError::ValidationError(format!(
+                "Unknown search strategy '{}' (expected step or binary)",
+                s
+            ))),
+        }
+    }
+}
+
+/// Settings of a capacity search
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapacitySearch {
+    pub strategy: SearchStrategy,
+    /// First rate probed (iterations per second)
+    pub start_rate: f64,
+    /// Highest rate probed
+    pub max_rate: f64,
+    /// Increment of the step strategy, resolution of the binary one
+    pub rate_step: f64,
+    /// Duration of each probe
+    pub duration: Duration,
+    /// Upper bound of concurrent iterations in a probe
+    pub max_vus: u32,
+}
+
+impl CapacitySearch {
+    pub fn validate(&self) -> Result<()> {
+        let rates = [self.start_rate, self.max_rate, self.rate_step];
+        if rates.iter().any(|r| !r.is_finite() || *r <= 0.0) {
+            return Err(HornetError::ValidationError(
+                "Capacity search rates must be positive".to_string(),
+            ));
+        }
+        if self.max_rate < self.start_rate {
+            return Err(HornetError::ValidationError(
+                "--max-rate must not be below --rate".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Rate of the next probe, or `None` once the search is over
+    pub fn next_rate(&self, probes: &[Probe]) -> Option<f64> {
+        if probes.is_empty() {
+            return Some(self.start_rate);
+        }
+        let passing = probes
+            .iter()
+            .filter(|p| p.passed)
+            .map(|p| p.rate)
+            .reduce(f64::max);
+        let failing = probes
+            .iter()
+            .filter(|p| !p.passed)
+            .map(|p| p.rate)
+            .reduce(f64::min);
+        let passing = passing?;
+        match (self.strategy, failing) {
+            (_, None) if passing >= self.max_rate => None,
+            (SearchStrategy::Step, None) => Some((passing + self.rate_step).min(self.max_rate)),
+            (SearchStrategy::Step, Some(_)) => None,
+            (SearchStrategy::Binary, None) => Some(self.max_rate),
+            (SearchStrategy::Binary, Some(failing)) if failing - passing <= self.rate_step => None,
+            (SearchStrategy::Binary, Some(failing)) => Some((passing + failing) / 2.0),
+        }
+    }
+}
+
+/// A statistic of a step above its SLO
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SloViolation {
+    pub workflow_id: String,
+    pub step_id: String,
+    /// `errorRate` or a latency statistic such as `p95`
+    pub metric: String,
+    /// Limit (milliseconds for latencies)
+    pub limit: f64,
+    pub actual: f64,
+}
+
+/// Check the step SLOs against a summary
+///
+/// Steps without requests in the summary are not checked.
+pub fn check_slos(objectives: &[StepObjective], summary: &LoadSummary) -> Vec<SloViolation> {
+    let mut violations = Vec::new();
+    for objective in objectives {
+        let Some(step) = summary
+            .workflow(&objective.workflow_id)
+            .and_then(|w| w.step(&objective.step_id))
+            .filter(|s| s.requests > 0)
+        else {
+            continue;
+        };
+        let mut violate = |metric: &str, limit: f64, actual: f64| {
+            if actual > limit {
+                violations.push(SloViolation {
+                    workflow_id: objective.workflow_id.clone(),
+                    step_id: objective.step_id.clone(),
+                    metric: metric.to_string(),
+                    limit,
+                    actual,
+                });
+            }
+        };
+        for (statistic, limit) in &objective.slo.latency {
+            if let Some(actual) = step.latency.statistic(statistic) {
+                violate(statistic, limit.0.as_secs_f64() * 1000.0, actual);
+            }
+        }
+        if let Some(limit) = objective.slo.error_rate {
+            violate("errorRate", limit, step.error_rate);
+        }
+    }
+    violations
+}
+
+/// Outcome of one rate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Probe {
+    /// Target arrival rate (iterations per second)
+    pub rate: f64,
+    pub passed: bool,
+    pub violations: Vec<SloViolation>,
+    pub summary: LoadSummary,
+}
+
+/// Result of a capacity search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapacityReport {
+    /// Highest rate meeting every SLO, if any did
+    pub max_passing_rate: Option<f64>,
+    /// Probes in the order they ran
+    pub probes: Vec<Probe>,
+}
+
+impl CapacityReport {
+    /// Probes ordered by rate: the latency curve
+    pub fn curve(&self) -> Vec<&Probe> {
+        let mut probes: Vec<&Probe> = self.probes.iter().collect();
+        probes.sort_by(|a, b| a.rate.total_cmp(&b.rate));
+        probes
+    }
+}
+
+impl LoadTest {
+    /// Search the highest arrival rate meeting `objectives`
+    ///
+    /// `on_progress` receives the progress of the running probe with its
+    /// rate, `on_probe` each finished probe.
+    pub async fn search_capacity(
+        &self,
+        search: &CapacitySearch,
+        objectives: &[StepObjective],
+        mut on_progress: impl FnMut(f64, &Progress),
+        mut on_probe: impl FnMut(&Probe),
+    ) -> Result<CapacityReport> {
+        search.validate()?;
+        let mut probes: Vec<Probe> = Vec::new();
+        while let Some(rate) = search.next_rate(&probes) {
+            let test = Self {
+                mix: self.mix.clone(),
+                model: LoadModel::constant_rate(rate, search.duration, search.max_vus),
+            };
+            let summary = test.run(|progress| on_progress(rate, progress)).await;
+            let violations = check_slos(objectives, &summary);
+            let probe = Probe {
+                rate,
+                passed: violations.is_empty() && summary.dropped_iterations == 0,
+                violations,
+                summary,
+            };
+            on_probe(&probe);
+            probes.push(probe);
+        }
+
+        let max_passing_rate = probes
+            .iter()
+            .filter(|p| p.passed)
+            .map(|p| p.rate)
+            .reduce(f64::max);
+        Ok(CapacityReport {
+            max_passing_rate,
+            probes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(strategy: SearchStrategy) -> CapacitySearch {
+        CapacitySearch {
+            strategy,
+            start_rate: 10.0,
+            max_rate: 100.0,
+            rate_step: 10.0,
+            duration: Duration::from_secs(1),
+            max_vus: 10,
+        }
+    }
+
+    /// 容量が `capacity` のサーバーに対して探索した順のレート
+    fn rates(search: &CapacitySearch, capacity: f64) -> Vec<f64> {
+        let mut probes = Vec::new();
+        while let Some(rate) = search.next_rate(&probes) {
+            probes.push(Probe {
+                rate,
+                passed: rate <= capacity,
+                violations: Vec::new(),
+                summary: LoadSummary {
+                    duration_ms: 0.0,
+                    iterations: 0,
+                    dropped_iterations: 0,
+                    requests: 0,
+                    errors: 0,
+                    rps: 0.0,
+                    error_rate: 0.0,
+                    latency: Default::default(),
+                    workflows: Vec::new(),
+                    agents: Vec::new(),
+                },
+            });
+        }
+        probes.iter().map(|p| p.rate).collect()
+    }
+
+    #[test]
+    fn test_next_rate() {
+        let step = search(SearchStrategy::Step);
+        assert_eq!(rates(&step, 35.0), vec![10.0, 20.0, 30.0, 40.0]);
+        assert_eq!(rates(&step, 500.0).last(), Some(&100.0));
+        // 最初のレートで失敗したら終わる
+        assert_eq!(rates(&step, 5.0), vec![10.0]);
+
+        let binary = search(SearchStrategy::Binary);
+        assert_eq!(
+            rates(&binary, 35.0),
+            vec![10.0, 100.0, 55.0, 32.5, 43.75, 38.125]
+        );
+        assert_eq!(rates(&binary, 500.0), vec![10.0, 100.0]);
+        assert_eq!(rates(&binary, 5.0), vec![10.0]);
+
+        // 異常系: 上限が開始レートより小さい
+        let invalid = CapacitySearch {
+            max_rate: 5.0,
+            ..step
+        };
+        assert!(invalid.validate().is_err());
+    }
+}
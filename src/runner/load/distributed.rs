@@ -0,0 +1,684 @@
+//! Distributed load generation
+//!
+//! A coordinator waits for agents to connect over TCP, hands each of them
+//! the resolved workflow plans and its slice of the load model, and merges
+//! the metrics they stream back into one report. Agents send the metrics of
+//! every progress interval (histogram deltas, not running totals), so an
+//! agent that disconnects or goes silent still contributes everything it
+//! reported up to then; the run carries on with the remaining agents and the
+//! lost one is flagged in the summary.
+//!
+//! Messages are JSON objects, one per line, tagged by `type`.
+
+use super::{LatencySummary, LoadMetrics, LoadModel, LoadSummary, LoadTest, Progress, Scenario};
+use crate::error::{HornetError, Result};
+use crate::runner::native::NativeOptions;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+/// Version both sides must speak
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Time an agent may stay silent before it is considered lost
+const AGENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Time an agent keeps retrying to reach the coordinator
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Interval between progress snapshots of the coordinator
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Everything an agent needs to run its slice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bundle {
+    pub base_url: String,
+    /// Timeout of a single request
+    pub timeout: Duration,
+    /// Resolved workflows of the mix
+    pub scenarios: Vec<Scenario>,
+}
+
+/// A protocol message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Message {
+    /// Agent → coordinator, right after connecting
+    Hello { name: String, version: u32 },
+    /// Coordinator → agent: run this slice now
+    Start {
+        bundle: Box<Bundle>,
+        model: LoadModel,
+    },
+    /// Agent → coordinator, every second: metrics since the previous message
+    Metrics {
+        metrics: Box<LoadMetrics>,
+        active_vus: u64,
+    },
+    /// Agent → coordinator: metrics after the last `Metrics`, run over
+    Done { metrics: Box<LoadMetrics> },
+    /// Either side: the session cannot go on
+    Error { message: String },
+}
+
+/// How an agent's part of the run ended
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum AgentStatus {
+    Finished,
+    /// Disconnected, silent for too long or failed
+    Lost {
+        reason: String,
+    },
+}
+
+/// What the coordinator reports about an agent during a run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AgentUpdate<'a> {
+    Joined {
+        name: &'a str,
+        address: &'a str,
+    },
+    /// The agent could not be started; the run goes on without its slice
+    Lost {
+        name: &'a str,
+        address: &'a str,
+        reason: &'a str,
+    },
+}
+
+/// Part of one agent in a distributed run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentSummary {
+    pub name: String,
+    pub address: String,
+    #[serde(flatten)]
+    pub status: AgentStatus,
+    /// Iterations the agent reported
+    pub iterations: u64,
+    pub requests: u64,
+}
+
+/// A JSON-lines connection
+struct Connection {
+    lines: Lines<BufReader<OwnedReadHalf>>,
+    writer: OwnedWriteHalf,
+}
+
+impl Connection {
+    fn new(stream: TcpStream) -> Self {
+        let (reader, writer) = stream.into_split();
+        Self {
+            lines: BufReader::new(reader).lines(),
+            writer,
+        }
+    }
+
+    async fn send(&mut self, message: &Message) -> Result<()> {
+        write_message(&mut self.writer, message).await
+    }
+
+    /// Next message; `None` once the peer closed the connection
+    async fn receive(&mut self) -> Result<Option<Message>> {
+        match self.lines.next_line().await? {
+            Some(line) => Ok(Some(serde_json::from_str(&line)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+async fn write_message(writer: &mut OwnedWriteHalf, message: &Message) -> Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    writer.write_all(&line).await?;
+    Ok(())
+}
+
+/// An agent that joined the coordinator
+struct Agent {
+    name: String,
+    address: String,
+    connection: Connection,
+}
+
+/// What an agent's reader task reports to the coordinator
+enum Event {
+    Metrics {
+        agent: usize,
+        metrics: LoadMetrics,
+        active_vus: u64,
+    },
+    Done {
+        agent: usize,
+        metrics: LoadMetrics,
+    },
+    Lost {
+        agent: usize,
+        reason: String,
+    },
+}
+
+/// Coordinator of a distributed run
+pub struct Coordinator {
+    listener: TcpListener,
+}
+
+impl Coordinator {
+    pub async fn bind(address: impl ToSocketAddrs) -> Result<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(address).await?,
+        })
+    }
+
+    pub fn local_addr(&self) -> Result<std::net::SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Wait up to `wait` for `agents` agents, run `model` split between
+    /// them and merge their metrics
+    ///
+    /// `on_agent` is called as agents join and when one cannot be started,
+    /// `on_progress` every second with the merged interval of all agents.
+    pub async fn run(
+        self,
+        bundle: Bundle,
+        model: &LoadModel,
+        agents: usize,
+        wait: Duration,
+        mut on_agent: impl FnMut(AgentUpdate<'_>),
+        mut on_progress: impl FnMut(&Progress),
+    ) -> Result<LoadSummary> {
+        let slices = model.split(agents)?;
+        let mut joined = self.accept(agents, wait, &mut on_agent).await?;
+
+        // An agent that drops before it gets its slice is lost like one
+        // that drops mid-run: its slice is not handed to the others
+        let bundle = Box::new(bundle);
+        let mut started_agents = Vec::new();
+        for (agent, slice) in joined.iter_mut().zip(slices) {
+            let sent = agent
+                .connection
+                .send(&Message::Start {
+                    bundle: bundle.clone(),
+                    model: slice,
+                })
+                .await;
+            let status = match sent {
+                Ok(()) => AgentStatus::Finished,
+                Err(e) => {
+                    let reason = format!("start failed: {}", e);
+                    on_agent(AgentUpdate::Lost {
+                        name: &agent.name,
+                        address: &agent.address,
+                        reason: &reason,
+                    });
+                    AgentStatus::Lost { reason }
+                }
+            };
+            started_agents.push(status);
+        }
+
+        let started = Instant::now();
+        let (events, mut received) = mpsc::unbounded_channel();
+        let mut summaries = Vec::new();
+        let mut running = 0;
+        for (index, (agent, status)) in joined.into_iter().zip(started_agents).enumerate() {
+            if status == AgentStatus::Finished {
+                tokio::spawn(follow(index, agent.connection, events.clone()));
+                running += 1;
+            }
+            summaries.push(AgentSummary {
+                name: agent.name,
+                address: agent.address,
+                status,
+                iterations: 0,
+                requests: 0,
+            });
+        }
+        drop(events);
+
+        let mut total = LoadMetrics::default();
+        let mut interval = LoadMetrics::default();
+        let mut active = vec![0; summaries.len()];
+        let mut ticker = tokio::time::interval_at(started + PROGRESS_INTERVAL, PROGRESS_INTERVAL);
+        let mut last_tick = started;
+        while running > 0 {
+            tokio::select! {
+                event = received.recv() => {
+                    let Some(event) = event else { break };
+                    let (agent, metrics) = match event {
+                        Event::Metrics { agent, metrics, active_vus } => {
+                            active[agent] = active_vus;
+                            (agent, metrics)
+                        }
+                        Event::Done { agent, metrics } => {
+                            active[agent] = 0;
+                            running -= 1;
+                            (agent, metrics)
+                        }
+                        Event::Lost { agent, reason } => {
+                            active[agent] = 0;
+                            running -= 1;
+                            summaries[agent].status = AgentStatus::Lost { reason };
+                            continue;
+                        }
+                    };
+                    summaries[agent].iterations += metrics.iterations();
+                    summaries[agent].requests += metrics.requests();
+                    total.merge(&metrics);
+                    interval.merge(&metrics);
+                }
+                now = ticker.tick() => {
+                    let interval = std::mem::take(&mut interval);
+                    let seconds = (now - last_tick).as_secs_f64().max(f64::EPSILON);
+                    last_tick = now;
+                    on_progress(&Progress {
+                        elapsed: now - started,
+                        active_vus: active.iter().sum(),
+                        iterations: total.iterations(),
+                        requests: total.requests(),
+                        errors: total.errors(),
+                        dropped_iterations: total.dropped_iterations,
+                        rps: interval.requests() as f64 / seconds,
+                        latency: LatencySummary::from(&interval.latency()),
+                        interval,
+                    });
+                }
+            }
+        }
+
+        let mut summary = total.summary(started.elapsed());
+        summary.agents = summaries;
+        Ok(summary)
+    }
+
+    async fn accept(
+        &self,
+        agents: usize,
+        wait: Duration,
+        on_agent: &mut impl FnMut(AgentUpdate<'_>),
+    ) -> Result<Vec<Agent>> {
+        let deadline = Instant::now() + wait;
+        let mut joined = Vec::new();
+        while joined.len() < agents {
+            let (stream, address) = tokio::time::timeout_at(deadline, self.listener.accept())
+                .await
+                .map_err(|_| {
+                    HornetError::ValidationError(format!(
+                        "Only {} of {} agent(s) connected within {:?}",
+                        joined.len(),
+                        agents,
+                        wait
+                    ))
+                })??;
+            let mut connection = Connection::new(stream);
+            // 名乗らない接続や版の違う agent は受け入れない
+            let hello = tokio::time::timeout(AGENT_TIMEOUT, connection.receive()).await;
+            let name = match hello {
+                Ok(Ok(Some(Message::Hello { name, version }))) if version == PROTOCOL_VERSION => {
+                    name
+                }
+                Ok(Ok(Some(Message::Hello { version, .. }))) => {
+                    let _ = connection
+                        .send(&Message::Error {
+                            message: format!(
+                                "Protocol version {} is not supported (coordinator speaks {})",
+                                version, PROTOCOL_VERSION
+                            ),
+                        })
+                        .await;
+                    continue;
+                }
+                _ => continue,
+            };
+            let address = address.to_string();
+            on_agent(AgentUpdate::Joined {
+                name: &name,
+                address: &address,
+            });
+            joined.push(Agent {
+                name,
+                address,
+                connection,
+            });
+        }
+        Ok(joined)
+    }
+}
+
+/// Forward the messages of one agent until it is done or lost
+async fn follow(agent: usize, mut connection: Connection, events: mpsc::UnboundedSender<Event>) {
+    let event = loop {
+        let message = tokio::time::timeout(AGENT_TIMEOUT, connection.receive()).await;
+        match message {
+            Ok(Ok(Some(Message::Metrics {
+                metrics,
+                active_vus,
+            }))) => {
+                let _ = events.send(Event::Metrics {
+                    agent,
+                    metrics: *metrics,
+                    active_vus,
+                });
+            }
+            Ok(Ok(Some(Message::Done { metrics }))) => {
+                break Event::Done {
+                    agent,
+                    metrics: *metrics,
+                };
+            }
+            Ok(Ok(Some(Message::Error { message }))) => {
+                break Event::Lost {
+                    agent,
+                    reason: message,
+                };
+            }
+            Ok(Ok(Some(_))) => {
+                break Event::Lost {
+                    agent,
+                    reason: "unexpected message".to_string(),
+                };
+            }
+            Ok(Ok(None)) => {
+                break Event::Lost {
+                    agent,
+                    reason: "disconnected".to_string(),
+                };
+            }
+            Ok(Err(e)) => {
+                break Event::Lost {
+                    agent,
+                    reason: e.to_string(),
+                };
+            }
+            Err(_) => {
+                break Event::Lost {
+                    agent,
+                    reason: format!("silent for {:?}", AGENT_TIMEOUT),
+                };
+            }
+        }
+    };
+    let _ = events.send(event);
+}
+
+/// Connect to a coordinator, run the slice it hands out and report back
+///
+/// File references in payloads are resolved against `base_dir`.
+/// `on_start` is called with the slice once the coordinator starts the run.
+pub async fn run_agent(
+    coordinator: &str,
+    name: &str,
+    base_dir: PathBuf,
+    mut on_start: impl FnMut(&LoadModel),
+) -> Result<LoadSummary> {
+    let mut connection = Connection::new(connect(coordinator).await?);
+    connection
+        .send(&Message::Hello {
+            name: name.to_string(),
+            version: PROTOCOL_VERSION,
+        })
+        .await?;
+
+    let (bundle, model) = match connection.receive().await? {
+        Some(Message::Start { bundle, model }) => (bundle, model),
+        Some(Message::Error { message }) => return Err(HornetError::ValidationError(message)),
+        Some(_) => {
+            return Err(HornetError::ValidationError(
+                "Unexpected message from the coordinator".to_string(),
+            ));
+        }
+        None => {
+            return Err(HornetError::ValidationError(
+                "The coordinator closed the connection".to_string(),
+            ));
+        }
+    };
+    on_start(&model);
+
+    let Connection { mut writer, .. } = connection;
+    let test = match LoadTest::new(
+        NativeOptions {
+            base_url: bundle.base_url,
+            timeout: bundle.timeout,
+            base_dir,
+        },
+        bundle.scenarios,
+        model,
+    ) {
+        Ok(test) => test,
+        Err(e) => {
+            let _ = write_message(
+                &mut writer,
+                &Message::Error {
+                    message: e.to_string(),
+                },
+            )
+            .await;
+            return Err(e);
+        }
+    };
+
+    // 進捗のたびに差分を送る（送信は別タスクで順に行う）
+    let (outgoing, mut queued) = mpsc::unbounded_channel::<Message>();
+    let sender = tokio::spawn(async move {
+        while let Some(message) = queued.recv().await {
+            write_message(&mut writer, &message).await?;
+        }
+        writer.shutdown().await?;
+        Ok::<_, HornetError>(())
+    });
+    let (summary, remainder) = test
+        .run_with_remainder(|progress| {
+            let _ = outgoing.send(Message::Metrics {
+                metrics: Box::new(progress.interval.clone()),
+                active_vus: progress.active_vus,
+            });
+        })
+        .await;
+    let _ = outgoing.send(Message::Done {
+        metrics: Box::new(remainder),
+    });
+    drop(outgoing);
+    sender
+        .await
+        .map_err(|e| HornetError::ValidationError(e.to_string()))??;
+    Ok(summary)
+}
+
+/// Connect, retrying while the coordinator is not listening yet
+async fn connect(coordinator: &str) -> Result<TcpStream> {
+    let deadline = Instant::now() + CONNECT_TIMEOUT;
+    loop {
+        match TcpStream::connect(coordinator).await {
+            Ok(stream) => return Ok(stream),
+            Err(e) if Instant::now() >= deadline => {
+                return Err(HornetError::ValidationError(format!(
+                    "Cannot reach coordinator {}: {}",
+                    coordinator, e
+                )));
+            }
+            Err(_) => tokio::time::sleep(Duration::from_millis(200)).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_agent_loss_keeps_reported_metrics() {
+        let coordinator = Coordinator::bind("127.0.0.1:0").await.unwrap();
+        let address = coordinator.local_addr().unwrap();
+        let bundle = Bundle {
+            base_url: "http://localhost".to_string(),
+            timeout: Duration::from_secs(1),
+            scenarios: Vec::new(),
+        };
+        let model = LoadModel::constant_rate(10.0, Duration::from_secs(1), 2);
+
+        // 1つ目は差分を1回送ってから切断し、2つ目は最後まで報告する
+        let fake_agent = move |name: &'static str, finish: bool| async move {
+            let mut connection = Connection::new(TcpStream::connect(address).await.unwrap());
+            connection
+                .send(&Message::Hello {
+                    name: name.to_string(),
+                    version: PROTOCOL_VERSION,
+                })
+                .await
+                .unwrap();
+            let Some(Message::Start { model, .. }) = connection.receive().await.unwrap() else {
+                panic!("expected start");
+            };
+            assert_eq!(
+                model,
+                LoadModel::constant_rate(5.0, Duration::from_secs(1), 1)
+            );
+            let metrics = LoadMetrics {
+                dropped_iterations: 3,
+                ..Default::default()
+            };
+            connection
+                .send(&Message::Metrics {
+                    metrics: Box::new(metrics.clone()),
+                    active_vus: 1,
+                })
+                .await
+                .unwrap();
+            if finish {
+                connection
+                    .send(&Message::Done {
+                        metrics: Box::new(metrics),
+                    })
+                    .await
+                    .unwrap();
+            }
+        };
+        let agents = tokio::spawn(async move {
+            tokio::join!(fake_agent("first", false), fake_agent("second", true));
+        });
+
+        let mut names = Vec::new();
+        let summary = coordinator
+            .run(
+                bundle,
+                &model,
+                2,
+                Duration::from_secs(5),
+                |update| {
+                    if let AgentUpdate::Joined { name, .. } = update {
+                        names.push(name.to_string());
+                    }
+                },
+                |_| {},
+            )
+            .await
+            .unwrap();
+        agents.await.unwrap();
+
+        names.sort();
+        assert_eq!(names, vec!["first", "second"]);
+        assert_eq!(summary.dropped_iterations, 9);
+        let status = |name: &str| {
+            let agent = summary.agents.iter().find(|a| a.name == name).unwrap();
+            agent.status.clone()
+        };
+        assert_eq!(
+            status("first"),
+            AgentStatus::Lost {
+                reason: "disconnected".to_string()
+            }
+        );
+        assert_eq!(status("second"), AgentStatus::Finished);
+    }
+
+    #[tokio::test]
+    async fn test_agent_lost_before_start() {
+        // 異常系: Start を送れない agent は失われたものとして扱い、残りで続ける
+        let coordinator = Coordinator::bind("127.0.0.1:0").await.unwrap();
+        let address = coordinator.local_addr().unwrap();
+        let hello = |name: &str| Message::Hello {
+            name: name.to_string(),
+            version: PROTOCOL_VERSION,
+        };
+        let agents = tokio::spawn(async move {
+            // 名乗ってすぐ切断する
+            let mut dropped = Connection::new(TcpStream::connect(address).await.unwrap());
+            dropped.send(&hello("dropped")).await.unwrap();
+            drop(dropped);
+            tokio::time::sleep(Duration::from_millis(100)).await;
+
+            let mut connection = Connection::new(TcpStream::connect(address).await.unwrap());
+            connection.send(&hello("steady")).await.unwrap();
+            let Some(Message::Start { .. }) = connection.receive().await.unwrap() else {
+                panic!("expected start");
+            };
+            connection
+                .send(&Message::Done {
+                    metrics: Box::default(),
+                })
+                .await
+                .unwrap();
+        });
+
+        // 送信バッファに収まらない大きさにして、切断された側への書き込みを失敗させる
+        let bundle = Bundle {
+            base_url: "x".repeat(8 << 20),
+            timeout: Duration::from_secs(1),
+            scenarios: Vec::new(),
+        };
+        let mut lost = Vec::new();
+        let summary = coordinator
+            .run(
+                bundle,
+                &LoadModel::constant_rate(10.0, Duration::from_secs(1), 2),
+                2,
+                Duration::from_secs(5),
+                |update| {
+                    if let AgentUpdate::Lost { name, .. } = update {
+                        lost.push(name.to_string());
+                    }
+                },
+                |_| {},
+            )
+            .await
+            .unwrap();
+        agents.await.unwrap();
+
+        assert_eq!(lost, vec!["dropped"]);
+        assert!(matches!(
+            &summary.agents[0].status,
+            AgentStatus::Lost { reason } if reason.starts_with("start failed")
+        ));
+        assert_eq!(summary.agents[1].status, AgentStatus::Finished);
+    }
+
+    #[tokio::test]
+    async fn test_coordinator_waits_for_agents() {
+        // 異常系: agent が揃わない
+        let coordinator = Coordinator::bind("127.0.0.1:0").await.unwrap();
+        let result = coordinator
+            .run(
+                Bundle {
+                    base_url: "http://localhost".to_string(),
+                    timeout: Duration::from_secs(1),
+                    scenarios: Vec::new(),
+                },
+                &LoadModel::constant_rate(10.0, Duration::from_secs(1), 2),
+                1,
+                Duration::from_millis(100),
+                |_| {},
+                |_| {},
+            )
+            .await;
+        assert!(result.is_err());
+    }
+}
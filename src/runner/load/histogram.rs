@@ -0,0 +1,283 @@
+//! Log-bucketed latency histogram
+//!
+//! Records values (microseconds) with a bounded relative error in the spirit
+//! of HDR histograms: values below `2 * SUB_BUCKETS` are counted exactly, and
+//! every larger power-of-two range is split into `SUB_BUCKETS` linear buckets,
+//! so a reported value is within 1/128 (< 0.8%) of the recorded one. Memory
+//! grows with the largest recorded value only, and histograms recorded on
+//! different tasks (or machines) can be merged without losing precision.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::time::Duration;
+
+/// Number of linear buckets per power of two (above the exact range)
+const SUB_BUCKETS: u64 = 128;
+/// `log2(SUB_BUCKETS)`
+const SUB_BUCKET_BITS: u32 = 7;
+
+/// Latency histogram with a bounded relative error
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Histogram {
+    counts: Vec<u64>,
+    count: u64,
+    sum: u128,
+    min: u64,
+    max: u64,
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a duration (microsecond resolution)
+    pub fn record(&mut self, duration: Duration) {
+        self.record_value(u64::try_from(duration.as_micros()).unwrap_or(u64::MAX));
+    }
+
+    /// Record a raw value
+    pub fn record_value(&mut self, value: u64) {
+        self.record_n(value, 1);
+    }
+
+    fn record_n(&mut self, value: u64, n: u64) {
+        let index = bucket_index(value);
+        if index >= self.counts.len() {
+            self.counts.resize(index + 1, 0);
+        }
+        self.counts[index] += n;
+        if self.count == 0 || value < self.min {
+            self.min = value;
+        }
+        self.max = self.max.max(value);
+        self.count += n;
+        self.sum += u128::from(value) * u128::from(n);
+    }
+
+    /// Add every value recorded in `other`
+    pub fn merge(&mut self, other: &Histogram) {
+        if other.count == 0 {
+            return;
+        }
+        if other.counts.len() > self.counts.len() {
+            self.counts.resize(other.counts.len(), 0);
+        }
+        for (target, count) in self.counts.iter_mut().zip(&other.counts) {
+            *target = target.saturating_add(*count);
+        }
+        if self.count == 0 || other.min < self.min {
+            self.min = other.min;
+        }
+        self.max = self.max.max(other.max);
+        self.count = self.count.saturating_add(other.count);
+        self.sum += other.sum;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Smallest recorded value (exact)
+    pub fn min(&self) -> u64 {
+        self.min
+    }
+
+    /// Largest recorded value (exact)
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+
+    /// Mean of the recorded values (exact)
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        self.sum as f64 / self.count as f64
+    }
+
+    /// Value at or below which `percentile` percent of the values fall
+    ///
+    /// Returns the highest value equivalent to the bucket the percentile
+    /// lands in, clamped to the recorded range (0 when empty).
+    pub fn value_at_percentile(&self, percentile: f64) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+        let percentile = percentile.clamp(0.0, 100.0);
+        let rank = ((percentile / 100.0) * self.count as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (index, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return highest_equivalent(index).clamp(self.min, self.max);
+            }
+        }
+        self.max
+    }
+
+    /// Non-empty buckets as `(index, count)` pairs
+    pub fn buckets(&self) -> impl Iterator<Item = (usize, u64)> + '_ {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(index, count)| (index, *count))
+    }
+}
+
+/// Serialized form: only the non-empty buckets
+#[derive(Serialize, Deserialize)]
+struct SparseHistogram {
+    /// `(bucket index, count)` pairs
+    buckets: Vec<(usize, u64)>,
+    /// Decimal text: tagged enums cannot carry `u128` numbers
+    sum: String,
+    min: u64,
+    max: u64,
+}
+
+impl Serialize for Histogram {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SparseHistogram {
+            buckets: self.buckets().collect(),
+            sum: self.sum.to_string(),
+            min: self.min,
+            max: self.max,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Histogram {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let sparse = SparseHistogram::deserialize(deserializer)?;
+        let mut histogram = Histogram::new();
+        // Indices come from other processes (agent reports), so bound them
+        // before they size the bucket vector.
+        let max_index = bucket_index(u64::MAX);
+        for (index, count) in sparse.buckets {
+            if index > max_index {
+                return Err(serde::de::Error::custom(format!(
+                    "bucket index {} is out of range (max {})",
+                    index, max_index
+                )));
+            }
+            if index >= histogram.counts.len() {
+                histogram.counts.resize(index + 1, 0);
+            }
+            histogram.counts[index] = histogram.counts[index].saturating_add(count);
+            histogram.count = histogram.count.saturating_add(count);
+        }
+        if histogram.count > 0 {
+            histogram.sum = sparse.sum.parse().map_err(serde::de::Error::custom)?;
+            histogram.min = sparse.min;
+            histogram.max = sparse.max;
+        }
+        Ok(histogram)
+    }
+}
+
+fn bucket_index(value: u64) -> usize {
+    if value < 2 * SUB_BUCKETS {
+        return value as usize;
+    }
+    // `value >> shift` lies in [SUB_BUCKETS, 2 * SUB_BUCKETS)
+    let shift = (63 - value.leading_zeros()) - SUB_BUCKET_BITS;
+    let sub = (value >> shift) - SUB_BUCKETS;
+    (2 * SUB_BUCKETS + u64::from(shift - 1) * SUB_BUCKETS + sub) as usize
+}
+
+fn highest_equivalent(index: usize) -> u64 {
+    let index = index as u64;
+    if index < 2 * SUB_BUCKETS {
+        return index;
+    }
+    let shift = (index - 2 * SUB_BUCKETS) / SUB_BUCKETS + 1;
+    let sub = (index - 2 * SUB_BUCKETS) % SUB_BUCKETS + SUB_BUCKETS;
+    (sub << shift).saturating_add((1 << shift) - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles_within_relative_error() {
+        let mut histogram = Histogram::new();
+        for value in 1..=100_000u64 {
+            histogram.record_value(value);
+        }
+        assert_eq!(histogram.count(), 100_000);
+        assert_eq!(histogram.min(), 1);
+        assert_eq!(histogram.max(), 100_000);
+        assert!((histogram.mean() - 50_000.5).abs() < 1e-6);
+
+        for (percentile, expected) in [(50.0, 50_000.0), (99.0, 99_000.0), (99.9, 99_900.0)] {
+            let actual = histogram.value_at_percentile(percentile) as f64;
+            assert!(
+                (actual - expected).abs() / expected < 1.0 / 128.0,
+                "p{percentile}: {actual} vs {expected}"
+            );
+        }
+        assert_eq!(histogram.value_at_percentile(100.0), 100_000);
+
+        // 小さい値は正確に数える
+        let mut small = Histogram::new();
+        for value in [3, 3, 7, 200] {
+            small.record_value(value);
+        }
+        assert_eq!(small.value_at_percentile(50.0), 3);
+        assert_eq!(small.value_at_percentile(75.0), 7);
+        assert_eq!(small.value_at_percentile(100.0), 200);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut left = Histogram::new();
+        let mut right = Histogram::new();
+        let mut both = Histogram::new();
+        for value in [5, 1_000, 70_000] {
+            left.record_value(value);
+            both.record_value(value);
+        }
+        for value in [2, 3_000_000] {
+            right.record(Duration::from_micros(value));
+            both.record_value(value);
+        }
+
+        left.merge(&right);
+        assert_eq!(left, both);
+
+        // 空でないバケットだけを書き出し、読み戻しても同じになる
+        let json = serde_json::to_value(&both).unwrap();
+        assert_eq!(json["buckets"].as_array().unwrap().len(), 5);
+        assert_eq!(serde_json::from_value::<Histogram>(json).unwrap(), both);
+        assert_eq!(left.min(), 2);
+        assert_eq!(left.max(), 3_000_000);
+
+        // 空のヒストグラムとのマージは何も変えない
+        left.merge(&Histogram::new());
+        assert_eq!(left, both);
+    }
+
+    #[test]
+    fn test_deserialize_rejects_out_of_range_index() {
+        // 異常系: 範囲外のバケット番号は巨大な確保をせずにエラーになる
+        let json = r#"{"buckets":[[18446744073709551615,1]],"sum":"1","min":1,"max":1}"#;
+        let err = serde_json::from_str::<Histogram>(json).unwrap_err();
+        assert!(err.to_string().contains("out of range"), "{err}");
+
+        // 件数の合計はあふれずに飽和する
+        let last = bucket_index(u64::MAX);
+        let json = format!(
+            r#"{{"buckets":[[{last},{max}],[{last},1]],"sum":"1","min":1,"max":1}}"#,
+            max = u64::MAX
+        );
+        let histogram = serde_json::from_str::<Histogram>(&json).unwrap();
+        assert_eq!(histogram.count(), u64::MAX);
+    }
+}
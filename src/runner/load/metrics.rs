@@ -0,0 +1,324 @@
+//! Metrics collected during a load test
+//!
+//! Every finished workflow iteration is folded into per-step and
+//! per-workflow histograms. A second set of counters covers only the time
+//! since the last progress tick, so that the live view shows current rates
+//! and latencies instead of averages over the whole run.
+
+use super::distributed::AgentSummary;
+use super::histogram::Histogram;
+use crate::runner::native::WorkflowRun;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Counters and latencies of one step
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StepMetrics {
+    /// Time until the whole response was received
+    pub latency: Histogram,
+    pub requests: u64,
+    /// Requests without response or with failed successCriteria
+    pub errors: u64,
+}
+
+/// Counters and latencies of one workflow
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkflowMetrics {
+    /// Duration of whole iterations
+    pub duration: Histogram,
+    pub iterations: u64,
+    /// Iterations that stopped with a failure
+    pub failed: u64,
+    /// Steps by id, in the order they were first executed
+    pub steps: IndexMap<String, StepMetrics>,
+}
+
+impl WorkflowMetrics {
+    fn record(&mut self, run: &WorkflowRun, elapsed: Duration) {
+        self.iterations += 1;
+        if !run.success() {
+            self.failed += 1;
+        }
+        self.duration.record(elapsed);
+        for step in &run.steps {
+            let metrics = self.steps.entry(step.step_id.clone()).or_default();
+            metrics.requests += 1;
+            if !step.passed() {
+                metrics.errors += 1;
+            }
+            if let Some(ref response) = step.response {
+                metrics.latency.record(response.elapsed);
+            }
+        }
+    }
+
+    fn merge(&mut self, other: &WorkflowMetrics) {
+        self.duration.merge(&other.duration);
+        self.iterations += other.iterations;
+        self.failed += other.failed;
+        for (step_id, step) in &other.steps {
+            let target = self.steps.entry(step_id.clone()).or_default();
+            target.latency.merge(&step.latency);
+            target.requests += step.requests;
+            target.errors += step.errors;
+        }
+    }
+
+    fn requests(&self) -> u64 {
+        self.steps.values().map(|s| s.requests).sum()
+    }
+
+    fn errors(&self) -> u64 {
+        self.steps.values().map(|s| s.errors).sum()
+    }
+}
+
+/// Metrics of a whole load test, by workflow id
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadMetrics {
+    pub workflows: IndexMap<String, WorkflowMetrics>,
+    /// Arrivals skipped because every VU was busy (open model)
+    pub dropped_iterations: u64,
+}
+
+impl LoadMetrics {
+    pub fn record(&mut self, run: &WorkflowRun, elapsed: Duration) {
+        self.workflows
+            .entry(run.workflow_id.clone())
+            .or_default()
+            .record(run, elapsed);
+    }
+
+    pub fn merge(&mut self, other: &LoadMetrics) {
+        for (workflow_id, workflow) in &other.workflows {
+            self.workflows
+                .entry(workflow_id.clone())
+                .or_default()
+                .merge(workflow);
+        }
+        self.dropped_iterations += other.dropped_iterations;
+    }
+
+    pub fn iterations(&self) -> u64 {
+        self.workflows.values().map(|w| w.iterations).sum()
+    }
+
+    pub fn requests(&self) -> u64 {
+        self.workflows.values().map(|w| w.requests()).sum()
+    }
+
+    pub fn errors(&self) -> u64 {
+        self.workflows.values().map(|w| w.errors()).sum()
+    }
+
+    /// Latencies of every step of every workflow
+    pub fn latency(&self) -> Histogram {
+        let mut merged = Histogram::new();
+        for step in self.workflows.values().flat_map(|w| w.steps.values()) {
+            merged.merge(&step.latency);
+        }
+        merged
+    }
+
+    /// Summary over `elapsed`
+    pub fn summary(&self, elapsed: Duration) -> LoadSummary {
+        let seconds = elapsed.as_secs_f64().max(f64::EPSILON);
+        let workflows = self
+            .workflows
+            .iter()
+            .map(|(workflow_id, workflow)| WorkflowSummary {
+                workflow_id: workflow_id.clone(),
+                iterations: workflow.iterations,
+                failed: workflow.failed,
+                iteration_rate: workflow.iterations as f64 / seconds,
+                duration: LatencySummary::from(&workflow.duration),
+                requests: workflow.requests(),
+                errors: workflow.errors(),
+                error_rate: ratio(workflow.errors(), workflow.requests()),
+                steps: workflow
+                    .steps
+                    .iter()
+                    .map(|(step_id, step)| StepSummary {
+                        step_id: step_id.clone(),
+                        requests: step.requests,
+                        errors: step.errors,
+                        rps: step.requests as f64 / seconds,
+                        error_rate: ratio(step.errors, step.requests),
+                        latency: LatencySummary::from(&step.latency),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        LoadSummary {
+            duration_ms: elapsed.as_secs_f64() * 1000.0,
+            iterations: self.iterations(),
+            dropped_iterations: self.dropped_iterations,
+            requests: self.requests(),
+            errors: self.errors(),
+            rps: self.requests() as f64 / seconds,
+            error_rate: ratio(self.errors(), self.requests()),
+            latency: LatencySummary::from(&self.latency()),
+            workflows,
+            agents: Vec::new(),
+        }
+    }
+}
+
+fn ratio(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+/// Metrics shared by the VUs of a running test
+#[derive(Debug, Default)]
+pub struct Collector {
+    total: Mutex<LoadMetrics>,
+    /// Metrics since the last progress tick
+    interval: Mutex<LoadMetrics>,
+}
+
+impl Collector {
+    pub fn record(&self, run: &WorkflowRun, elapsed: Duration) {
+        for metrics in [&self.total, &self.interval] {
+            if let Ok(mut metrics) = metrics.lock() {
+                metrics.record(run, elapsed);
+            }
+        }
+    }
+
+    pub fn drop_iteration(&self) {
+        for metrics in [&self.total, &self.interval] {
+            if let Ok(mut metrics) = metrics.lock() {
+                metrics.dropped_iterations += 1;
+            }
+        }
+    }
+
+    /// Metrics of the whole run so far
+    pub fn total(&self) -> LoadMetrics {
+        self.total.lock().map(|m| m.clone()).unwrap_or_default()
+    }
+
+    /// Metrics since the previous call, resetting the interval
+    pub fn take_interval(&self) -> LoadMetrics {
+        self.interval
+            .lock()
+            .map(|mut m| std::mem::take(&mut *m))
+            .unwrap_or_default()
+    }
+}
+
+/// Latency percentiles in milliseconds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencySummary {
+    pub min: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p95: f64,
+    pub p99: f64,
+    #[serde(rename = "p99.9")]
+    pub p999: f64,
+    pub max: f64,
+}
+
+impl LatencySummary {
+    /// Statistic by name (`mean`, `p50` … `p99.9`, `min`, `max`)
+    pub fn statistic(&self, name: &str) -> Option<f64> {
+        match name {
+            "min" => Some(self.min),
+            "mean" => Some(self.mean),
+            "p50" => Some(self.p50),
+            "p90" => Some(self.p90),
+            "p95" => Some(self.p95),
+            "p99" => Some(self.p99),
+            "p99.9" => Some(self.p999),
+            "max" => Some(self.max),
+            _ => None,
+        }
+    }
+}
+
+impl From<&Histogram> for LatencySummary {
+    fn from(histogram: &Histogram) -> Self {
+        let millis = |micros: u64| micros as f64 / 1000.0;
+        Self {
+            min: millis(histogram.min()),
+            mean: histogram.mean() / 1000.0,
+            p50: millis(histogram.value_at_percentile(50.0)),
+            p90: millis(histogram.value_at_percentile(90.0)),
+            p95: millis(histogram.value_at_percentile(95.0)),
+            p99: millis(histogram.value_at_percentile(99.0)),
+            p999: millis(histogram.value_at_percentile(99.9)),
+            max: millis(histogram.max()),
+        }
+    }
+}
+
+/// Final numbers of a step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepSummary {
+    pub step_id: String,
+    pub requests: u64,
+    pub errors: u64,
+    pub rps: f64,
+    pub error_rate: f64,
+    #[serde(rename = "latencyMs")]
+    pub latency: LatencySummary,
+}
+
+/// Final numbers of a workflow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowSummary {
+    pub workflow_id: String,
+    pub iterations: u64,
+    pub failed: u64,
+    pub iteration_rate: f64,
+    /// Duration of whole iterations
+    #[serde(rename = "durationMs")]
+    pub duration: LatencySummary,
+    pub requests: u64,
+    pub errors: u64,
+    pub error_rate: f64,
+    pub steps: Vec<StepSummary>,
+}
+
+/// Final numbers of a load test
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadSummary {
+    pub duration_ms: f64,
+    pub iterations: u64,
+    pub dropped_iterations: u64,
+    pub requests: u64,
+    pub errors: u64,
+    pub rps: f64,
+    pub error_rate: f64,
+    #[serde(rename = "latencyMs")]
+    pub latency: LatencySummary,
+    pub workflows: Vec<WorkflowSummary>,
+    /// Agents of a distributed run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agents: Vec<AgentSummary>,
+}
+
+impl LoadSummary {
+    pub fn workflow(&self, workflow_id: &str) -> Option<&WorkflowSummary> {
+        self.workflows.iter().find(|w| w.workflow_id == workflow_id)
+    }
+}
+
+impl WorkflowSummary {
+    pub fn step(&self, step_id: &str) -> Option<&StepSummary> {
+        self.steps.iter().find(|s| s.step_id == step_id)
+    }
+}
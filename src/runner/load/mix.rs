@@ -0,0 +1,173 @@
+//! Weighted choice between the workflows of a load test
+
+use crate::converters::common::find_workflow;
+use crate::error::{HornetError, Result};
+use crate::generator::sample::Rng;
+use crate::loader::OpenApiResolver;
+use crate::models::arazzo::ArazzoSpec;
+use crate::models::profile::{Distribution, LoadProfile};
+use crate::runner::native::{NativeExecutor, WorkflowPlan};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// A workflow of the mix with its weight, think time and pacing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    pub plan: WorkflowPlan,
+    /// Relative weight
+    pub weight: f64,
+    /// Pause between two steps
+    pub think_time: Option<Distribution>,
+    /// Target time between the starts of two iterations of a VU
+    pub pacing: Option<Distribution>,
+}
+
+impl Scenario {
+    /// The only workflow of a test, without pauses
+    pub fn new(plan: WorkflowPlan) -> Self {
+        Self {
+            plan,
+            weight: 1.0,
+            think_time: None,
+            pacing: None,
+        }
+    }
+
+    /// One scenario per workflow of a profile
+    pub fn from_profile(
+        profile: &LoadProfile,
+        arazzo: &ArazzoSpec,
+        resolver: &OpenApiResolver,
+    ) -> Result<Vec<Self>> {
+        profile.validate(arazzo)?;
+        profile
+            .workflows
+            .iter()
+            .filter(|entry| entry.weight > 0.0)
+            .map(|entry| {
+                let workflow = find_workflow(arazzo, &entry.workflow_id)?;
+                Ok(Self {
+                    plan: WorkflowPlan::new(workflow, resolver)?,
+                    weight: entry.weight,
+                    think_time: profile.think_time(&entry.workflow_id),
+                    pacing: profile.pacing(&entry.workflow_id),
+                })
+            })
+            .collect()
+    }
+}
+
+/// A scenario ready to run
+#[derive(Debug)]
+pub(super) struct Lane {
+    pub executor: NativeExecutor,
+    pub plan: WorkflowPlan,
+    pub pacing: Option<Distribution>,
+}
+
+/// Scenarios with cumulative shares for weighted picking
+#[derive(Debug)]
+pub(super) struct Mix {
+    lanes: Vec<Arc<Lane>>,
+    /// Upper bound of each lane's share in `[0, 1]`
+    cumulative: Vec<f64>,
+}
+
+impl Mix {
+    /// Give each scenario an executor sharing `executor`'s client
+    pub fn new(executor: &NativeExecutor, scenarios: Vec<Scenario>) -> Result<Self> {
+        let total: f64 = scenarios.iter().map(|s| s.weight).sum();
+        if scenarios.is_empty()
+            || !total.is_finite()
+            || total <= 0.0
+            || scenarios.iter().any(|s| s.weight < 0.0)
+        {
+            return Err(HornetError::ValidationError(
+                "A load test needs at least one workflow with a positive weight".to_string(),
+            ));
+        }
+
+        let mut share = 0.0;
+        let mut cumulative = Vec::new();
+        let mut lanes = Vec::new();
+        for scenario in scenarios {
+            share += scenario.weight / total;
+            cumulative.push(share);
+            let mut lane_executor = executor.clone();
+            if let Some(think_time) = scenario.think_time {
+                lane_executor = lane_executor.with_think_time(think_time);
+            }
+            lanes.push(Arc::new(Lane {
+                executor: lane_executor,
+                plan: scenario.plan,
+                pacing: scenario.pacing,
+            }));
+        }
+        Ok(Self { lanes, cumulative })
+    }
+
+    pub fn pick(&self, rng: &mut Rng) -> &Arc<Lane> {
+        let unit = rng.unit();
+        let index = self
+            .cumulative
+            .iter()
+            .position(|bound| unit < *bound)
+            .unwrap_or(self.lanes.len() - 1);
+        &self.lanes[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::native::NativeOptions;
+    use std::time::Duration;
+
+    fn scenario(workflow_id: &str, weight: f64) -> Scenario {
+        let workflow = serde_json::from_value(serde_json::json!({
+            "workflowId": workflow_id,
+            "steps": []
+        }))
+        .unwrap();
+        let plan = WorkflowPlan::new(&workflow, &OpenApiResolver::new(".")).unwrap();
+        Scenario {
+            weight,
+            ..Scenario::new(plan)
+        }
+    }
+
+    #[test]
+    fn test_pick_follows_weights() {
+        let executor = NativeExecutor::new(NativeOptions {
+            base_url: "http://localhost".to_string(),
+            timeout: Duration::from_secs(1),
+            base_dir: ".".into(),
+        })
+        .unwrap();
+        let mix = Mix::new(
+            &executor,
+            vec![
+                scenario("browse", 70.0),
+                scenario("search", 20.0),
+                scenario("checkout", 10.0),
+            ],
+        )
+        .unwrap();
+
+        let mut rng = Rng::new(42);
+        let mut counts = std::collections::HashMap::<String, i32>::new();
+        for _ in 0..10_000 {
+            *counts
+                .entry(mix.pick(&mut rng).plan.workflow_id.clone())
+                .or_insert(0) += 1;
+        }
+        for (workflow_id, expected) in [("browse", 7000), ("search", 2000), ("checkout", 1000)] {
+            let actual = counts[workflow_id];
+            assert!((actual - expected).abs() < 300, "{workflow_id}: {actual}");
+        }
+
+        // 異常系: 重みがすべて 0
+        assert!(Mix::new(&executor, vec![scenario("browse", 0.0)]).is_err());
+        assert!(Mix::new(&executor, vec![]).is_err());
+    }
+}
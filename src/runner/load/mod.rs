@@ -0,0 +1,511 @@
+//! Native load generation
+//!
+//! Drives workflow plans with the native engine under one of two load
+//! models:
+//!
+//! - closed model: a fixed number of VUs run iterations back to back, for a
+//!   duration or until a shared iteration budget is used up;
+//! - open model: iterations start at an arrival rate (constant, or ramped
+//!   linearly through stages) regardless of how fast earlier ones finish,
+//!   bounded by a maximum number of concurrent VUs.
+//!
+//! Each iteration runs one workflow of a weighted [`Scenario`] mix. All VUs
+//! share one HTTP client and thus one connection pool. Step latencies go
+//! into [`Histogram`]s per workflow and a progress snapshot is reported every
+//! second. A [`CapacitySearch`] repeats constant-rate runs to find the
+//! highest rate meeting the step SLOs, and a [`Coordinator`] spreads a test
+//! over agent processes.
+
+pub mod capacity;
+pub mod distributed;
+pub mod histogram;
+pub mod metrics;
+pub mod mix;
+
+pub use capacity::{CapacityReport, CapacitySearch, Probe, SearchStrategy, SloViolation};
+pub use distributed::{AgentStatus, AgentSummary, AgentUpdate, Bundle, Coordinator, run_agent};
+pub use histogram::Histogram;
+pub use metrics::{
+    Collector, LatencySummary, LoadMetrics, LoadSummary, StepSummary, WorkflowSummary,
+};
+pub use mix::Scenario;
+
+use super::native::{NativeExecutor, NativeOptions};
+use crate::converters::common::parse_duration;
+use crate::error::{HornetError, Result};
+use crate::generator::sample::Rng;
+use mix::{Lane, Mix};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+
+/// Interval between progress snapshots
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A ramp stage: move the arrival rate linearly to `target` within `duration`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Stage {
+    pub duration: Duration,
+    /// Iterations per second at the end of the stage
+    pub target: f64,
+}
+
+impl FromStr for Stage {
+    type Err = HornetError;
+
+    /// `30s:50` (duration and target rate)
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || {
+            HornetError::ValidationError(format!(
+                "Invalid stage '{}': expected <duration>:<rate> such as 30s:50",
+                s
+            ))
+        };
+        let (duration, target) = s.trim().split_once(':').ok_or_else(invalid)?;
+        let duration = parse_duration(duration).ok_or_else(invalid)?;
+        let target: f64 = target.trim().parse().map_err(|_| invalid())?;
+        if !target.is_finite() || target < 0.0 {
+            return Err(invalid());
+        }
+        Ok(Self { duration, target })
+    }
+}
+
+/// Parse comma-separated stages (`30s:10,1m:50,10s:0`)
+pub fn parse_stages(s: &str) -> Result<Vec<Stage>> {
+    s.split(',')
+        .filter(|part| !part.trim().is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// How load is generated
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LoadModel {
+    /// Closed model: `vus` VUs loop over the workflow
+    ///
+    /// Stops at `duration` or once `iterations` iterations (shared by all
+    /// VUs) have started, whichever comes first.
+    Vus {
+        vus: u32,
+        duration: Option<Duration>,
+        iterations: Option<u64>,
+    },
+    /// Open model: iterations start at a rate that moves from `start_rate`
+    /// through `stages`
+    ArrivalRate {
+        start_rate: f64,
+        stages: Vec<Stage>,
+        /// Upper bound of concurrent iterations; arrivals beyond it are dropped
+        max_vus: u32,
+    },
+}
+
+impl LoadModel {
+    /// Open model at a constant rate
+    pub fn constant_rate(rate: f64, duration: Duration, max_vus: u32) -> Self {
+        Self::ArrivalRate {
+            start_rate: rate,
+            stages: vec![Stage {
+                duration,
+                target: rate,
+            }],
+            max_vus,
+        }
+    }
+
+    /// Largest number of concurrent iterations
+    pub fn max_vus(&self) -> u32 {
+        match self {
+            Self::Vus { vus, .. } => *vus,
+            Self::ArrivalRate { max_vus, .. } => *max_vus,
+        }
+    }
+
+    /// Split the load into `parts` slices that add up to it
+    ///
+    /// VUs, iterations and rates are divided; durations stay the same.
+    pub fn split(&self, parts: usize) -> Result<Vec<Self>> {
+        let share = |total: u64, index: usize| {
+            let parts = parts as u64;
+            total / parts + u64::from((index as u64) < total % parts)
+        };
+        let slices: Vec<Self> = (0..parts)
+            .map(|index| match self {
+                Self::Vus {
+                    vus,
+                    duration,
+                    iterations,
+                } => Self::Vus {
+                    vus: share(u64::from(*vus), index) as u32,
+                    duration: *duration,
+                    iterations: iterations.map(|i| share(i, index)),
+                },
+                Self::ArrivalRate {
+                    start_rate,
+                    stages,
+                    max_vus,
+                } => Self::ArrivalRate {
+                    start_rate: start_rate / parts as f64,
+                    stages: stages
+                        .iter()
+                        .map(|stage| Stage {
+                            target: stage.target / parts as f64,
+                            ..*stage
+                        })
+                        .collect(),
+                    max_vus: u64::from(*max_vus).div_ceil(parts as u64) as u32,
+                },
+            })
+            .collect();
+        if parts == 0 || slices.iter().any(|slice| slice.validate().is_err()) {
+            return Err(HornetError::ValidationError(format!(
+                "Cannot split the load between {} agent(s) (fewer VUs than agents?)",
+                parts
+            )));
+        }
+        Ok(slices)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let invalid = |message: &str| Err(HornetError::ValidationError(message.to_string()));
+        match self {
+            Self::Vus { vus: 0, .. } => invalid("At least one VU is required"),
+            Self::Vus {
+                duration: None,
+                iterations: None,
+                ..
+            } => invalid("A closed model needs a duration or an iteration count"),
+            Self::ArrivalRate { max_vus: 0, .. } => invalid("--max-vus must be at least 1"),
+            Self::ArrivalRate { stages, .. } if stages.is_empty() => {
+                invalid("An arrival rate needs a duration or stages")
+            }
+            Self::ArrivalRate { start_rate, .. }
+                if !start_rate.is_finite() || *start_rate < 0.0 =>
+            {
+                invalid("The arrival rate must not be negative")
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Start time of the `n`-th iteration (from 0) when the arrival rate
+/// moves from `start_rate` through `stages`
+///
+/// The `n`-th iteration starts once the integral of the rate reaches `n`,
+/// so slow ramps from zero still start their first iterations on time.
+/// `None` when the stages end before that.
+pub fn arrival_time(start_rate: f64, stages: &[Stage], n: u64) -> Option<Duration> {
+    let mut remaining = n as f64;
+    let mut from = start_rate;
+    let mut offset = Duration::ZERO;
+    for stage in stages {
+        let seconds = stage.duration.as_secs_f64();
+        let arrivals = (from + stage.target) / 2.0 * seconds;
+        if remaining < arrivals {
+            // rate(u) = from + slope * u を積分した値が remaining になる u
+            let slope = (stage.target - from) / seconds;
+            let u = if slope.abs() < f64::EPSILON {
+                remaining / from
+            } else {
+                (-from + (from * from + 2.0 * slope * remaining).max(0.0).sqrt()) / slope
+            };
+            return Some(offset + Duration::from_secs_f64(u.clamp(0.0, seconds)));
+        }
+        remaining -= arrivals;
+        offset += stage.duration;
+        from = stage.target;
+    }
+    None
+}
+
+/// Snapshot reported while a test runs
+#[derive(Debug, Clone)]
+pub struct Progress {
+    pub elapsed: Duration,
+    /// Iterations in flight
+    pub active_vus: u64,
+    pub iterations: u64,
+    pub requests: u64,
+    pub errors: u64,
+    pub dropped_iterations: u64,
+    /// Requests per second since the previous snapshot
+    pub rps: f64,
+    /// Latencies since the previous snapshot
+    pub latency: LatencySummary,
+    /// Metrics since the previous snapshot
+    pub interval: LoadMetrics,
+}
+
+/// A load test of a weighted mix of workflows
+pub struct LoadTest {
+    mix: Arc<Mix>,
+    model: LoadModel,
+}
+
+impl LoadTest {
+    /// Build a test whose VUs share one pooled client
+    ///
+    /// Each iteration runs one of `scenarios`, picked by weight.
+    pub fn new(options: NativeOptions, scenarios: Vec<Scenario>, model: LoadModel) -> Result<Self> {
+        model.validate()?;
+        let client = reqwest::Client::builder()
+            .timeout(options.timeout)
+            .pool_max_idle_per_host(model.max_vus() as usize)
+            .build()
+            .map_err(|e| HornetError::ValidationError(format!("Failed to build client: {e}")))?;
+        let executor = NativeExecutor::with_client(client, options);
+        Ok(Self {
+            mix: Arc::new(Mix::new(&executor, scenarios)?),
+            model,
+        })
+    }
+
+    /// Run the test, calling `on_progress` every second
+    pub async fn run(&self, on_progress: impl FnMut(&Progress)) -> LoadSummary {
+        self.run_with_remainder(on_progress).await.0
+    }
+
+    /// Like [`LoadTest::run`], also returning the metrics recorded after the
+    /// last snapshot, so that the snapshots' intervals and the remainder add
+    /// up to the whole run
+    pub async fn run_with_remainder(
+        &self,
+        mut on_progress: impl FnMut(&Progress),
+    ) -> (LoadSummary, LoadMetrics) {
+        let collector = Arc::new(Collector::default());
+        let active = Arc::new(AtomicU64::new(0));
+        let started = Instant::now();
+
+        let workload = self.drive(collector.clone(), active.clone());
+        tokio::pin!(workload);
+        let mut ticker = tokio::time::interval_at(started + PROGRESS_INTERVAL, PROGRESS_INTERVAL);
+        let mut last_tick = started;
+        loop {
+            tokio::select! {
+                _ = &mut workload => break,
+                now = ticker.tick() => {
+                    let interval = collector.take_interval();
+                    let total = collector.total();
+                    let seconds = (now - last_tick).as_secs_f64().max(f64::EPSILON);
+                    last_tick = now;
+                    on_progress(&Progress {
+                        elapsed: now - started,
+                        active_vus: active.load(Ordering::Relaxed),
+                        iterations: total.iterations(),
+                        requests: total.requests(),
+                        errors: total.errors(),
+                        dropped_iterations: total.dropped_iterations,
+                        rps: interval.requests() as f64 / seconds,
+                        latency: LatencySummary::from(&interval.latency()),
+                        interval,
+                    });
+                }
+            }
+        }
+
+        (
+            collector.total().summary(started.elapsed()),
+            collector.take_interval(),
+        )
+    }
+
+    async fn drive(&self, collector: Arc<Collector>, active: Arc<AtomicU64>) {
+        let mut rng = Rng::from_time();
+        match self.model {
+            LoadModel::Vus {
+                vus,
+                duration,
+                iterations,
+            } => {
+                let deadline = duration.map(|d| Instant::now() + d);
+                let budget = Arc::new(AtomicU64::new(iterations.unwrap_or(u64::MAX)));
+                let mut tasks = JoinSet::new();
+                for _ in 0..vus {
+                    let mix = self.mix.clone();
+                    let collector = collector.clone();
+                    let active = active.clone();
+                    let budget = budget.clone();
+                    let mut rng = Rng::new(rng.next_u64());
+                    tasks.spawn(async move {
+                        active.fetch_add(1, Ordering::Relaxed);
+                        while deadline.is_none_or(|d| Instant::now() < d)
+                            && budget
+                                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |left| {
+                                    left.checked_sub(1)
+                                })
+                                .is_ok()
+                        {
+                            let lane = mix.pick(&mut rng);
+                            let started = Instant::now();
+                            iterate(lane, &collector).await;
+                            // ペーシングの残りを待つ（期限は越えない）
+                            if let Some(ref pacing) = lane.pacing {
+                                let mut next = started + pacing.sample(|| rng.unit());
+                                if let Some(deadline) = deadline {
+                                    next = next.min(deadline);
+                                }
+                                tokio::time::sleep_until(next).await;
+                            }
+                        }
+                        active.fetch_sub(1, Ordering::Relaxed);
+                    });
+                }
+                while tasks.join_next().await.is_some() {}
+            }
+            LoadModel::ArrivalRate {
+                start_rate,
+                ref stages,
+                max_vus,
+            } => {
+                let slots = Arc::new(Semaphore::new(max_vus as usize));
+                let started = Instant::now();
+                let mut tasks = JoinSet::new();
+                for at in (0..).map_while(|n| arrival_time(start_rate, stages, n)) {
+                    tokio::time::sleep_until(started + at).await;
+
+                    let Ok(permit) = slots.clone().try_acquire_owned() else {
+                        collector.drop_iteration();
+                        continue;
+                    };
+                    let lane = self.mix.pick(&mut rng).clone();
+                    let collector = collector.clone();
+                    let active = active.clone();
+                    tasks.spawn(async move {
+                        active.fetch_add(1, Ordering::Relaxed);
+                        iterate(&lane, &collector).await;
+                        active.fetch_sub(1, Ordering::Relaxed);
+                        drop(permit);
+                    });
+                    // 終わったタスクを溜め込まない
+                    while tasks.try_join_next().is_some() {}
+                }
+                while tasks.join_next().await.is_some() {}
+            }
+        }
+    }
+}
+
+async fn iterate(lane: &Lane, collector: &Collector) {
+    let started = Instant::now();
+    let run = lane.executor.run(&lane.plan, &Value::Null).await;
+    collector.record(&run, started.elapsed());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stages() {
+        let stages = parse_stages("30s:10, 1m:50,10s:0").unwrap();
+        assert_eq!(
+            stages,
+            vec![
+                Stage {
+                    duration: Duration::from_secs(30),
+                    target: 10.0
+                },
+                Stage {
+                    duration: Duration::from_secs(60),
+                    target: 50.0
+                },
+                Stage {
+                    duration: Duration::from_secs(10),
+                    target: 0.0
+                },
+            ]
+        );
+        assert!(parse_stages("30s").is_err());
+        assert!(parse_stages("30s:-1").is_err());
+        assert!(parse_stages("soon:5").is_err());
+    }
+
+    #[test]
+    fn test_arrival_time() {
+        let at = |model: &LoadModel, n: u64| {
+            let LoadModel::ArrivalRate {
+                start_rate, stages, ..
+            } = model
+            else {
+                unreachable!()
+            };
+            arrival_time(*start_rate, stages, n).map(|d| d.as_secs_f64())
+        };
+
+        let constant = LoadModel::constant_rate(20.0, Duration::from_secs(3), 5);
+        assert_eq!(at(&constant, 0), Some(0.0));
+        assert_eq!(at(&constant, 10), Some(0.5));
+        assert_eq!(at(&constant, 59), Some(2.95));
+        assert_eq!(at(&constant, 60), None);
+
+        // 0 から 100/s まで 10 秒で上げ、10 秒保ってから 10 秒で 0 に下げる
+        let ramp = LoadModel::ArrivalRate {
+            start_rate: 0.0,
+            stages: parse_stages("10s:100,10s:100,10s:0").unwrap(),
+            max_vus: 5,
+        };
+        let close = |actual: Option<f64>, expected: f64| {
+            assert!((actual.unwrap() - expected).abs() < 1e-9, "{actual:?}");
+        };
+        assert_eq!(at(&ramp, 0), Some(0.0));
+        // 5u² = 125 となる u
+        close(at(&ramp, 125), 5.0);
+        close(at(&ramp, 500), 10.0);
+        close(at(&ramp, 1000), 15.0);
+        // 下りでは 100u - 5u² = 375 となる u
+        close(at(&ramp, 1875), 25.0);
+        assert_eq!(at(&ramp, 2000), None);
+    }
+
+    #[test]
+    fn test_split() {
+        let closed = LoadModel::Vus {
+            vus: 5,
+            duration: None,
+            iterations: Some(11),
+        };
+        let slices = closed.split(2).unwrap();
+        assert_eq!(
+            slices,
+            vec![
+                LoadModel::Vus {
+                    vus: 3,
+                    duration: None,
+                    iterations: Some(6)
+                },
+                LoadModel::Vus {
+                    vus: 2,
+                    duration: None,
+                    iterations: Some(5)
+                },
+            ]
+        );
+
+        let open = LoadModel::ArrivalRate {
+            start_rate: 10.0,
+            stages: parse_stages("10s:100").unwrap(),
+            max_vus: 5,
+        };
+        assert_eq!(
+            open.split(4).unwrap()[3],
+            LoadModel::ArrivalRate {
+                start_rate: 2.5,
+                stages: parse_stages("10s:25").unwrap(),
+                max_vus: 2,
+            }
+        );
+
+        // 異常系: agent より VU が少ない
+        assert!(closed.split(6).is_err());
+        assert!(closed.split(0).is_err());
+    }
+}
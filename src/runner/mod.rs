@@ -3,9 +3,21 @@
 //! This module provides implementations for running test scripts
 //! using various engines (k6, etc.)
 
+pub mod baseline;
+pub mod cassette;
+pub mod history;
 pub mod k6;
+pub mod load;
+pub mod native;
+pub mod report;
 
+pub use baseline::{BaselineDiff, DiffRow, DiffStatus, MetricDiff, Tolerance};
+pub use cassette::{BodyMatcher, Cassette, CassetteMode, Recorder, Redactor, Replayer};
+pub use history::{RunHistory, RunRecord, StepTrend};
 pub use k6::K6Runner;
+pub use load::{LatencySummary, LoadModel, LoadSummary, LoadTest};
+pub use native::{NativeExecutor, NativeOptions, WorkflowPlan};
+pub use report::{CaseStatus, RunReport, TestCase};
 
 use crate::error::Result;
 use serde::{Deserialize, Serialize};
@@ -27,6 +39,9 @@ pub struct RunResult {
 }
 
 /// Metrics from a test run
+///
+/// The fields after `duration` were added for baselines; metrics saved by
+/// older versions read them as zero or empty.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct RunMetrics {
     /// Number of HTTP requests made
@@ -43,6 +58,86 @@ pub struct RunMetrics {
     pub vus: u32,
     /// Duration of the run
     pub duration: String,
+    /// Response time percentiles in milliseconds
+    #[serde(default)]
+    pub latency: LatencySummary,
+    /// HTTP requests per second
+    #[serde(default)]
+    pub rps: f64,
+    /// Share of failed requests, in `[0, 1]`
+    #[serde(default)]
+    pub error_rate: f64,
+    /// Per-step metrics (empty when the runner only reports totals)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<StepRunMetrics>,
+}
+
+/// Metrics of one step of a workflow
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepRunMetrics {
+    pub workflow_id: String,
+    pub step_id: String,
+    pub requests: u64,
+    pub errors: u64,
+    /// Requests per second
+    pub rps: f64,
+    /// Share of failed requests, in `[0, 1]`
+    pub error_rate: f64,
+    /// Response time percentiles in milliseconds
+    pub latency: LatencySummary,
+}
+
+impl RunMetrics {
+    /// Metrics of a step, if the run reported it
+    pub fn step(&self, workflow_id: &str, step_id: &str) -> Option<&StepRunMetrics> {
+        self.steps
+            .iter()
+            .find(|s| s.workflow_id == workflow_id && s.step_id == step_id)
+    }
+
+    /// Read metrics saved with [`RunMetrics::save`]
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Save the metrics as JSON, e.g. as a baseline for later runs
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+impl From<&LoadSummary> for RunMetrics {
+    fn from(summary: &LoadSummary) -> Self {
+        Self {
+            http_reqs: summary.requests,
+            iterations: summary.iterations,
+            avg_response_time_ms: summary.latency.mean,
+            checks_failed: summary.errors,
+            checks_passed: summary.requests - summary.errors,
+            vus: 0,
+            duration: format!("{:.3}s", summary.duration_ms / 1000.0),
+            latency: summary.latency,
+            rps: summary.rps,
+            error_rate: summary.error_rate,
+            steps: summary
+                .workflows
+                .iter()
+                .flat_map(|workflow| {
+                    workflow.steps.iter().map(|step| StepRunMetrics {
+                        workflow_id: workflow.workflow_id.clone(),
+                        step_id: step.step_id.clone(),
+                        requests: step.requests,
+                        errors: step.errors,
+                        rps: step.rps,
+                        error_rate: step.error_rate,
+                        latency: step.latency,
+                    })
+                })
+                .collect(),
+        }
+    }
 }
 
 /// Trait for test runners
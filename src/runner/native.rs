@@ -0,0 +1,1192 @@
+//! Native workflow execution
+//!
+//! Runs Arazzo workflows directly over HTTP instead of generating a script:
+//! runtime expressions are resolved against the workflow inputs, the outputs
+//! of earlier steps and the current request/response, bodies are encoded with
+//! [`crate::payload::encode_body`], and successCriteria and
+//! `onSuccess`/`onFailure` actions (`goto`, `end`, `retry`) behave like in the
+//! generated scripts.
+
+use super::cassette::CassetteMode;
+use crate::converters::common::{goto_target, input_defaults, resolve_operation};
+use crate::error::{HornetError, Result};
+use crate::generator::sample::Rng;
+use crate::loader::OpenApiResolver;
+use crate::models::arazzo::{Step, SuccessCriteria, Workflow};
+use crate::models::expression::{
+    ExpressionSource, RuntimeExpression, TemplateSegment, as_single_expression, parse_template,
+};
+use crate::models::profile::Distribution;
+use crate::payload::{self, EncodedBody, text_value};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Upper bound of executed steps per run (guards against `goto` loops)
+const MAX_STEP_EXECUTIONS: usize = 1000;
+
+/// Options of the native engine
+#[derive(Debug, Clone)]
+pub struct NativeOptions {
+    /// Base URL prepended to operation paths
+    pub base_url: String,
+    /// Timeout of a single request
+    pub timeout: Duration,
+    /// Directory file references in payloads are resolved against
+    pub base_dir: PathBuf,
+}
+
+/// A workflow step with its operation resolved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedStep {
+    pub step: Step,
+    /// HTTP method (upper case)
+    pub method: String,
+    /// OpenAPI path template (`/pets/{petId}`)
+    pub path: String,
+}
+
+/// A workflow ready to be executed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowPlan {
+    pub workflow_id: String,
+    pub steps: Vec<PlannedStep>,
+    /// Default inputs (`inputs.properties.*.default`)
+    pub inputs: Value,
+    outputs: Option<Value>,
+}
+
+impl WorkflowPlan {
+    /// Resolve the operations of every step
+    ///
+    /// Steps calling other workflows are not supported by the native engine.
+    pub fn new(workflow: &Workflow, resolver: &OpenApiResolver) -> Result<Self> {
+        let steps = workflow
+            .steps
+            .iter()
+            .map(|step| {
+                if let Some(ref workflow_id) = step.workflow_id {
+                    return Err(HornetError::ValidationError(format!(
+                        "Step '{}' calls workflow '{}'; nested workflows are not supported by the native engine",
+                        step.step_id, workflow_id
+                    )));
+                }
+                let (path, method) = resolve_operation(step, resolver)?;
+                Ok(PlannedStep {
+                    step: step.clone(),
+                    method,
+                    path,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            workflow_id: workflow.workflow_id.clone(),
+            steps,
+            inputs: Value::Object(input_defaults(workflow).into_iter().collect()),
+            outputs: workflow.outputs.clone(),
+        })
+    }
+
+    /// Index of a step by id
+    pub fn index_of(&self, step_id: &str) -> Option<usize> {
+        self.steps.iter().position(|s| s.step.step_id == step_id)
+    }
+}
+
+/// Values runtime expressions are resolved against
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    /// Workflow inputs
+    pub inputs: Value,
+    /// Outputs of the steps executed so far, by step id
+    pub steps: Map<String, Value>,
+}
+
+impl Context {
+    pub fn new(inputs: Value) -> Self {
+        Self {
+            inputs,
+            steps: Map::new(),
+        }
+    }
+
+    /// Evaluate an expression (`Null` when it cannot be resolved)
+    pub fn evaluate(
+        &self,
+        expr: &RuntimeExpression,
+        request: Option<&PreparedRequest>,
+        response: Option<&StepResponse>,
+    ) -> Value {
+        match expr {
+            RuntimeExpression::Inputs(path) => lookup(&self.inputs, path),
+            RuntimeExpression::StepOutput {
+                step_id,
+                output,
+                path,
+            } => self
+                .steps
+                .get(step_id)
+                .and_then(|outputs| outputs.get(output))
+                .map(|value| lookup(value, path))
+                .unwrap_or(Value::Null),
+            RuntimeExpression::Url => request
+                .map(|r| Value::String(r.url.clone()))
+                .unwrap_or(Value::Null),
+            RuntimeExpression::Method => request
+                .map(|r| Value::String(r.method.clone()))
+                .unwrap_or(Value::Null),
+            RuntimeExpression::StatusCode => response
+                .map(|r| Value::from(r.status))
+                .unwrap_or(Value::Null),
+            RuntimeExpression::Request(source) => {
+                request.map(|r| r.source(source)).unwrap_or(Value::Null)
+            }
+            RuntimeExpression::Response(source) => {
+                response.map(|r| r.source(source)).unwrap_or(Value::Null)
+            }
+            _ => Value::Null,
+        }
+    }
+
+    /// Resolve every runtime expression embedded in a value
+    ///
+    /// A string that is a single expression takes the expression's value
+    /// (keeping its JSON type); expressions embedded in text are rendered as
+    /// text.
+    pub fn render(
+        &self,
+        value: &Value,
+        request: Option<&PreparedRequest>,
+        response: Option<&StepResponse>,
+    ) -> Value {
+        match value {
+            Value::String(s) => {
+                if let Some(expr) = as_single_expression(value) {
+                    return self.evaluate(&expr, request, response);
+                }
+                let rendered: String = parse_template(s)
+                    .into_iter()
+                    .map(|segment| match segment {
+                        TemplateSegment::Literal(text) => text,
+                        TemplateSegment::Expression(expr) => {
+                            text_value(&self.evaluate(&expr, request, response))
+                        }
+                    })
+                    .collect();
+                Value::String(rendered)
+            }
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| self.render(item, request, response))
+                    .collect(),
+            ),
+            Value::Object(obj) => Value::Object(
+                obj.iter()
+                    .map(|(k, v)| (k.clone(), self.render(v, request, response)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+}
+
+/// A request with every runtime expression resolved
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreparedRequest {
+    pub method: String,
+    pub url: String,
+    /// OpenAPI path template the URL was built from
+    #[serde(skip)]
+    pub path_template: String,
+    pub path_params: Vec<(String, String)>,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    /// Resolved payload (before encoding)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+    #[serde(skip)]
+    pub encoded: Option<EncodedBody>,
+}
+
+impl PreparedRequest {
+    fn source(&self, source: &ExpressionSource) -> Value {
+        let find = |pairs: &[(String, String)], name: &str, ignore_case: bool| {
+            pairs
+                .iter()
+                .find(|(k, _)| {
+                    if ignore_case {
+                        k.eq_ignore_ascii_case(name)
+                    } else {
+                        k == name
+                    }
+                })
+                .map(|(_, v)| Value::String(v.clone()))
+                .unwrap_or(Value::Null)
+        };
+        match source {
+            ExpressionSource::Header(name) => find(&self.headers, name, true),
+            ExpressionSource::Query(name) => find(&self.query, name, false),
+            ExpressionSource::Path(name) => find(&self.path_params, name, false),
+            ExpressionSource::Body(path) => self
+                .body
+                .as_ref()
+                .map(|body| lookup(body, path))
+                .unwrap_or(Value::Null),
+        }
+    }
+}
+
+/// A received response
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepResponse {
+    pub status: u16,
+    /// Header names are lower case
+    pub headers: Vec<(String, String)>,
+    /// Body parsed as JSON, or the raw text when it is not JSON
+    pub body: Value,
+    /// Time until the whole body was received
+    #[serde(serialize_with = "serialize_millis")]
+    pub elapsed: Duration,
+}
+
+impl StepResponse {
+    /// Value of a header (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn source(&self, source: &ExpressionSource) -> Value {
+        match source {
+            ExpressionSource::Header(name) => self
+                .header(name)
+                .map(|v| Value::String(v.to_string()))
+                .unwrap_or(Value::Null),
+            ExpressionSource::Body(path) => lookup(&self.body, path),
+            _ => Value::Null,
+        }
+    }
+}
+
+/// Why a request got no response
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorKind {
+    /// The request could not be built (unresolvable body, invalid URL)
+    Request,
+    /// No response within the timeout
+    Timeout,
+    /// Connection or protocol error
+    Transport,
+}
+
+/// Error of a step without response
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutionError {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+/// Result of one step execution
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepResult {
+    pub step_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request: Option<PreparedRequest>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<StepResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ExecutionError>,
+    /// Failed successCriteria
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<String>,
+}
+
+impl StepResult {
+    /// Whether a response was received and every criterion passed
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.failures.is_empty()
+    }
+}
+
+/// Result of a workflow run
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowRun {
+    pub workflow_id: String,
+    pub steps: Vec<StepResult>,
+    /// Workflow outputs (resolved after the last step)
+    pub outputs: Value,
+    /// Reason the run stopped early, if it failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure: Option<String>,
+}
+
+impl WorkflowRun {
+    pub fn success(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+/// Outcome of one successCriteria entry
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CriterionResult {
+    /// `context condition value`
+    pub criterion: String,
+    pub passed: bool,
+    /// Actual (and expected) value, set when the criterion failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Progress of a run, reported to the sender given to
+/// [`NativeExecutor::with_events`] while the run executes
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum RunEvent {
+    #[serde(rename_all = "camelCase")]
+    StepStarted { step_id: String },
+    #[serde(rename_all = "camelCase")]
+    Request {
+        step_id: String,
+        request: PreparedRequest,
+    },
+    #[serde(rename_all = "camelCase")]
+    Response {
+        step_id: String,
+        response: StepResponse,
+    },
+    #[serde(rename_all = "camelCase")]
+    CriterionResult {
+        step_id: String,
+        #[serde(flatten)]
+        result: CriterionResult,
+    },
+    #[serde(rename_all = "camelCase")]
+    StepFinished {
+        step_id: String,
+        passed: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<ExecutionError>,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        failures: Vec<String>,
+    },
+    /// Sent by [`NativeExecutor::run`] after the last step
+    #[serde(rename_all = "camelCase")]
+    RunFinished {
+        success: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        failure: Option<String>,
+    },
+    /// The run was aborted before it finished (sent by whoever aborted it)
+    RunCancelled,
+}
+
+impl RunEvent {
+    /// Name of the event (the `type` tag)
+    pub fn name(&self) -> &'static str {
+        match self {
+            RunEvent::StepStarted { .. } => "step-started",
+            RunEvent::Request { .. } => "request",
+            RunEvent::Response { .. } => "response",
+            RunEvent::CriterionResult { .. } => "criterion-result",
+            RunEvent::StepFinished { .. } => "step-finished",
+            RunEvent::RunFinished { .. } => "run-finished",
+            RunEvent::RunCancelled => "run-cancelled",
+        }
+    }
+
+    /// Whether no event follows this one
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, RunEvent::RunFinished { .. } | RunEvent::RunCancelled)
+    }
+}
+
+/// Action chosen after a step
+enum Flow {
+    Next,
+    Goto(String),
+    End,
+    Retry { after: Duration, limit: u32 },
+}
+
+/// Pause drawn between two steps
+#[derive(Debug, Clone)]
+struct ThinkTime {
+    distribution: Distribution,
+    rng: Arc<Mutex<Rng>>,
+}
+
+impl ThinkTime {
+    async fn pause(&self) {
+        let pause = match self.rng.lock() {
+            Ok(mut rng) => self.distribution.sample(|| rng.unit()),
+            Err(_) => return,
+        };
+        tokio::time::sleep(pause).await;
+    }
+}
+
+/// Executes workflow plans over HTTP
+#[derive(Debug, Clone)]
+pub struct NativeExecutor {
+    client: reqwest::Client,
+    options: NativeOptions,
+    cassette: Option<CassetteMode>,
+    think_time: Option<ThinkTime>,
+    events: Option<UnboundedSender<RunEvent>>,
+}
+
+impl NativeExecutor {
+    pub fn new(options: NativeOptions) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(options.timeout)
+            .build()
+            .map_err(|e| HornetError::ValidationError(format!("Failed to build client: {e}")))?;
+        Ok(Self {
+            client,
+            options,
+            cassette: None,
+            think_time: None,
+            events: None,
+        })
+    }
+
+    /// Share an existing client (and its connection pool)
+    pub fn with_client(client: reqwest::Client, options: NativeOptions) -> Self {
+        Self {
+            client,
+            options,
+            cassette: None,
+            think_time: None,
+            events: None,
+        }
+    }
+
+    /// Record every exchange to, or answer every request from, a cassette
+    pub fn with_cassette(mut self, cassette: CassetteMode) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// Pause for a duration drawn from `distribution` between two steps
+    pub fn with_think_time(mut self, distribution: Distribution) -> Self {
+        self.think_time = Some(ThinkTime {
+            distribution,
+            rng: Arc::new(Mutex::new(Rng::from_time())),
+        });
+        self
+    }
+
+    /// Report the progress of every run to `sender`
+    ///
+    /// Sending never blocks; events are dropped once the receiver is gone.
+    pub fn with_events(mut self, sender: UnboundedSender<RunEvent>) -> Self {
+        self.events = Some(sender);
+        self
+    }
+
+    pub fn options(&self) -> &NativeOptions {
+        &self.options
+    }
+
+    /// Run a workflow from its first step
+    ///
+    /// `inputs` are merged over the plan's default inputs.
+    pub async fn run(&self, plan: &WorkflowPlan, inputs: &Value) -> WorkflowRun {
+        let mut merged = plan.inputs.clone();
+        if let (Value::Object(base), Value::Object(extra)) = (&mut merged, inputs) {
+            base.extend(extra.clone());
+        }
+        let mut context = Context::new(merged);
+        let mut steps = Vec::new();
+        let failure = self.run_from(plan, 0, &mut context, &mut steps).await;
+        self.emit(|| RunEvent::RunFinished {
+            success: failure.is_none(),
+            failure: failure.clone(),
+        });
+
+        WorkflowRun {
+            workflow_id: plan.workflow_id.clone(),
+            outputs: plan
+                .outputs
+                .as_ref()
+                .map(|outputs| context.render(outputs, None, None))
+                .unwrap_or(Value::Null),
+            steps,
+            failure,
+        }
+    }
+
+    /// Run the steps of `plan` from `start`, following actions
+    ///
+    /// Returns the reason of the failure, if any.
+    pub async fn run_from(
+        &self,
+        plan: &WorkflowPlan,
+        start: usize,
+        context: &mut Context,
+        results: &mut Vec<StepResult>,
+    ) -> Option<String> {
+        let mut index = start;
+        let mut executions = 0;
+        let mut retries: IndexMap<usize, u32> = IndexMap::new();
+
+        while let Some(planned) = plan.steps.get(index) {
+            executions += 1;
+            if executions > MAX_STEP_EXECUTIONS {
+                return Some(format!(
+                    "stopped after {MAX_STEP_EXECUTIONS} step executions (goto loop?)"
+                ));
+            }
+
+            let result = self.run_step(planned, context).await;
+            let passed = result.passed();
+            let flow = if passed {
+                self.choose_action(
+                    planned
+                        .step
+                        .on_success
+                        .iter()
+                        .flatten()
+                        .map(|a| (a.action_type.as_str(), &a.config)),
+                    context,
+                    &result,
+                )
+            } else {
+                self.choose_action(
+                    planned
+                        .step
+                        .on_failure
+                        .iter()
+                        .flatten()
+                        .map(|a| (a.action_type.as_str(), &a.config)),
+                    context,
+                    &result,
+                )
+            };
+            let summary = failure_summary(&result);
+            results.push(result);
+
+            match flow {
+                Flow::Next if passed => {
+                    index += 1;
+                    if index < plan.steps.len() {
+                        self.think().await;
+                    }
+                }
+                Flow::Next | Flow::End if !passed => {
+                    return Some(format!(
+                        "step '{}' failed: {}",
+                        planned.step.step_id, summary
+                    ));
+                }
+                Flow::Next | Flow::End => return None,
+                Flow::Goto(target) => match plan.index_of(&target) {
+                    Some(target) => {
+                        index = target;
+                        self.think().await;
+                    }
+                    None => return Some(format!("goto target '{}' not found", target)),
+                },
+                Flow::Retry { after, limit } => {
+                    let count = retries.entry(index).or_insert(0);
+                    if *count >= limit {
+                        return Some(format!(
+                            "step '{}' failed after {} retries: {}",
+                            planned.step.step_id, limit, summary
+                        ));
+                    }
+                    *count += 1;
+                    tokio::time::sleep(after).await;
+                }
+            }
+        }
+
+        None
+    }
+
+    fn emit(&self, event: impl FnOnce() -> RunEvent) {
+        if let Some(ref sender) = self.events {
+            let _ = sender.send(event());
+        }
+    }
+
+    async fn think(&self) {
+        if let Some(ref think_time) = self.think_time {
+            think_time.pause().await;
+        }
+    }
+
+    /// Execute a single step and store its outputs when it passes
+    pub async fn run_step(&self, planned: &PlannedStep, context: &mut Context) -> StepResult {
+        let step_id = &planned.step.step_id;
+        self.emit(|| RunEvent::StepStarted {
+            step_id: step_id.clone(),
+        });
+        let result = self.execute_step(planned, context).await;
+        self.emit(|| RunEvent::StepFinished {
+            step_id: step_id.clone(),
+            passed: result.passed(),
+            error: result.error.clone(),
+            failures: result.failures.clone(),
+        });
+        result
+    }
+
+    async fn execute_step(&self, planned: &PlannedStep, context: &mut Context) -> StepResult {
+        let step_id = &planned.step.step_id;
+        let request = match self.prepare(planned, context) {
+            Ok(request) => request,
+            Err(e) => {
+                return StepResult {
+                    step_id: step_id.clone(),
+                    request: None,
+                    response: None,
+                    error: Some(ExecutionError {
+                        kind: ErrorKind::Request,
+                        message: e.to_string(),
+                    }),
+                    failures: Vec::new(),
+                };
+            }
+        };
+        self.emit(|| RunEvent::Request {
+            step_id: step_id.clone(),
+            request: request.clone(),
+        });
+
+        let (response, error) = match self.send(&request).await {
+            Ok(response) => (Some(response), None),
+            Err(error) => (None, Some(error)),
+        };
+        let mut failures = Vec::new();
+        if let Some(ref response) = response {
+            self.emit(|| RunEvent::Response {
+                step_id: step_id.clone(),
+                response: response.clone(),
+            });
+            for result in evaluate_criteria(
+                planned.step.success_criteria.as_deref().unwrap_or(&[]),
+                context,
+                &request,
+                response,
+            ) {
+                if let Some(ref message) = result.message {
+                    failures.push(message.clone());
+                }
+                self.emit(|| RunEvent::CriterionResult {
+                    step_id: step_id.clone(),
+                    result,
+                });
+            }
+        }
+
+        if error.is_none()
+            && failures.is_empty()
+            && let Some(ref outputs) = planned.step.outputs
+        {
+            let values = context.render(outputs, Some(&request), response.as_ref());
+            context.steps.insert(step_id.clone(), values);
+        }
+
+        StepResult {
+            step_id: step_id.clone(),
+            request: Some(request),
+            response,
+            error,
+            failures,
+        }
+    }
+
+    /// Resolve the parameters and body of a step
+    pub fn prepare(&self, planned: &PlannedStep, context: &Context) -> Result<PreparedRequest> {
+        let step = &planned.step;
+        let mut path_params = Vec::new();
+        let mut query = Vec::new();
+        let mut headers = Vec::new();
+        let mut cookies = Vec::new();
+
+        for param in &step.parameters {
+            let value = context.render(&param.value, None, None);
+            let values = match value {
+                Value::Array(items) if param.location == "query" => {
+                    items.iter().map(text_value).collect()
+                }
+                other => vec![text_value(&other)],
+            };
+            for value in values {
+                match param.location.as_str() {
+                    "path" => path_params.push((param.name.clone(), value)),
+                    "query" => query.push((param.name.clone(), value)),
+                    "header" => headers.push((param.name.clone(), value)),
+                    "cookie" => cookies.push(format!("{}={}", param.name, value)),
+                    _ => {}
+                }
+            }
+        }
+        if !cookies.is_empty() {
+            headers.push(("Cookie".to_string(), cookies.join("; ")));
+        }
+
+        let mut path = String::new();
+        let mut rest = planned.path.as_str();
+        while let Some(start) = rest.find('{') {
+            let Some(end) = rest[start..].find('}').map(|e| start + e) else {
+                break;
+            };
+            path.push_str(&rest[..start]);
+            let name = &rest[start + 1..end];
+            match path_params.iter().find(|(k, _)| k == name) {
+                Some((_, value)) => path.push_str(&encode_segment(value)),
+                None => path.push_str(&rest[start..=end]),
+            }
+            rest = &rest[end + 1..];
+        }
+        path.push_str(rest);
+
+        let mut url = reqwest::Url::parse(&format!(
+            "{}{}",
+            self.options.base_url.trim_end_matches('/'),
+            path
+        ))
+        .map_err(|e| HornetError::ValidationError(format!("Invalid URL: {e}")))?;
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query.iter());
+        }
+
+        let (body, encoded) = match step.request_body {
+            Some(ref request_body) => {
+                let payload = payload::apply_replacements(request_body)?;
+                let payload = context.render(&payload, None, None);
+                let encoded = payload::encode_body(
+                    request_body.content_type.as_deref(),
+                    &payload,
+                    &self.options.base_dir,
+                )?;
+                (Some(payload), Some(encoded))
+            }
+            None => (None, None),
+        };
+
+        Ok(PreparedRequest {
+            method: planned.method.clone(),
+            url: url.to_string(),
+            path_template: planned.path.clone(),
+            path_params,
+            query,
+            headers,
+            body,
+            encoded,
+        })
+    }
+
+    /// Send a prepared request and read the whole response
+    ///
+    /// With a replay cassette the response comes from the cassette instead.
+    pub async fn send(
+        &self,
+        request: &PreparedRequest,
+    ) -> std::result::Result<StepResponse, ExecutionError> {
+        if let Some(CassetteMode::Replay(ref replayer)) = self.cassette {
+            return replayer.respond(request).ok_or_else(|| ExecutionError {
+                kind: ErrorKind::Transport,
+                message: format!(
+                    "no recorded interaction matches {} {}",
+                    request.method, request.path_template
+                ),
+            });
+        }
+
+        let response = self.fetch(request).await;
+        if let (Some(CassetteMode::Record(recorder)), Ok(response)) = (&self.cassette, &response) {
+            recorder.record(request, response);
+        }
+        response
+    }
+
+    async fn fetch(
+        &self,
+        request: &PreparedRequest,
+    ) -> std::result::Result<StepResponse, ExecutionError> {
+        let method =
+            reqwest::Method::from_bytes(request.method.as_bytes()).map_err(|e| ExecutionError {
+                kind: ErrorKind::Request,
+                message: e.to_string(),
+            })?;
+        let mut builder = self.client.request(method, &request.url);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(ref encoded) = request.encoded {
+            builder = builder
+                .header("Content-Type", &encoded.content_type)
+                .body(encoded.bytes.clone());
+        }
+
+        let started = Instant::now();
+        let response = builder.send().await.map_err(transport_error)?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .map(|(k, v)| {
+                (
+                    k.as_str().to_string(),
+                    String::from_utf8_lossy(v.as_bytes()).to_string(),
+                )
+            })
+            .collect();
+        let text = response.text().await.map_err(transport_error)?;
+        let elapsed = started.elapsed();
+        let body = if text.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_str(&text).unwrap_or(Value::String(text))
+        };
+
+        Ok(StepResponse {
+            status,
+            headers,
+            body,
+            elapsed,
+        })
+    }
+
+    fn choose_action<'a>(
+        &self,
+        actions: impl Iterator<Item = (&'a str, &'a IndexMap<String, Value>)>,
+        context: &Context,
+        result: &StepResult,
+    ) -> Flow {
+        for (action_type, config) in actions {
+            let criteria: Vec<SuccessCriteria> = config
+                .get("criteria")
+                .and_then(|c| serde_json::from_value(c.clone()).ok())
+                .unwrap_or_default();
+            let matches = match (&result.request, &result.response) {
+                (Some(request), Some(response)) => {
+                    check_criteria(&criteria, context, request, response).is_empty()
+                }
+                _ => criteria.is_empty(),
+            };
+            if !matches {
+                continue;
+            }
+
+            match action_type {
+                "end" => return Flow::End,
+                // 他のワークフローへの goto はネイティブエンジンでは扱わない
+                "goto" => {
+                    if let Some(target) = goto_target(config) {
+                        return Flow::Goto(target.to_string());
+                    }
+                }
+                "retry" => {
+                    let after = config
+                        .get("retryAfter")
+                        .and_then(|v| v.as_f64())
+                        .unwrap_or(0.0);
+                    let limit = config
+                        .get("retryLimit")
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(1);
+                    return Flow::Retry {
+                        after: Duration::from_secs_f64(after.max(0.0)),
+                        limit: limit as u32,
+                    };
+                }
+                _ => {}
+            }
+        }
+        Flow::Next
+    }
+}
+
+/// Evaluate successCriteria, returning a description of each failed criterion
+///
+/// `jsonpath` criteria are not supported and are skipped, as in the converters.
+pub fn check_criteria(
+    criteria: &[SuccessCriteria],
+    context: &Context,
+    request: &PreparedRequest,
+    response: &StepResponse,
+) -> Vec<String> {
+    evaluate_criteria(criteria, context, request, response)
+        .into_iter()
+        .filter_map(|result| result.message)
+        .collect()
+}
+
+/// Evaluate successCriteria, returning the outcome of each evaluated criterion
+///
+/// The message of a failed criterion is what [`check_criteria`] reports.
+pub fn evaluate_criteria(
+    criteria: &[SuccessCriteria],
+    context: &Context,
+    request: &PreparedRequest,
+    response: &StepResponse,
+) -> Vec<CriterionResult> {
+    let mut results = Vec::new();
+    for crit in criteria {
+        let actual = match RuntimeExpression::parse(&crit.context) {
+            Some(expr) => context.evaluate(&expr, Some(request), Some(response)),
+            None => Value::String(crit.context.clone()),
+        };
+        let description = match crit.value {
+            Some(ref value) => format!("{} {} {}", crit.context, crit.condition, text_value(value)),
+            None => format!("{} {}", crit.context, crit.condition),
+        };
+
+        let message = match crit.criteria_type.as_deref() {
+            Some("jsonpath") => continue,
+            Some("regex") => {
+                let matched = regex::Regex::new(&crit.condition)
+                    .map(|re| re.is_match(&text_value(&actual)))
+                    .unwrap_or(false);
+                (!matched).then(|| format!("{} (actual: {})", description, actual))
+            }
+            _ => {
+                let expected = match crit.value {
+                    Some(ref value) => context.render(value, Some(request), Some(response)),
+                    None => Value::Bool(true),
+                };
+                (!compare(&actual, operator(&crit.condition), &expected)).then(|| {
+                    format!(
+                        "{} (actual: {}, expected: {})",
+                        description, actual, expected
+                    )
+                })
+            }
+        };
+        results.push(CriterionResult {
+            criterion: description,
+            passed: message.is_none(),
+            message,
+        });
+    }
+    results
+}
+
+fn operator(condition: &str) -> &'static str {
+    match condition {
+        "!=" | "$ne" => "!=",
+        ">" | "$gt" => ">",
+        ">=" | "$gte" => ">=",
+        "<" | "$lt" => "<",
+        "<=" | "$lte" => "<=",
+        _ => "==",
+    }
+}
+
+/// Compare two values with an Arazzo operator
+fn compare(left: &Value, operator: &str, right: &Value) -> bool {
+    use std::cmp::Ordering;
+
+    let ordering = match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64().partial_cmp(&b.as_f64()),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    };
+    let equal = left == right || ordering == Some(Ordering::Equal);
+    match operator {
+        "==" => equal,
+        "!=" => !equal,
+        ">" => ordering == Some(Ordering::Greater),
+        ">=" => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+        "<" => ordering == Some(Ordering::Less),
+        "<=" => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        _ => false,
+    }
+}
+
+/// Value at a path of object keys and array indexes
+fn lookup(value: &Value, path: &[String]) -> Value {
+    let mut current = value;
+    for token in path {
+        let next = match current {
+            Value::Object(obj) => obj.get(token),
+            Value::Array(items) => token.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        };
+        match next {
+            Some(next) => current = next,
+            None => return Value::Null,
+        }
+    }
+    current.clone()
+}
+
+/// Percent-encode a path segment
+fn encode_segment(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn transport_error(e: reqwest::Error) -> ExecutionError {
+    ExecutionError {
+        kind: if e.is_timeout() {
+            ErrorKind::Timeout
+        } else {
+            ErrorKind::Transport
+        },
+        message: e.to_string(),
+    }
+}
+
+/// One-line description of why a step failed
+fn failure_summary(result: &StepResult) -> String {
+    match result.error {
+        Some(ref error) => error.message.clone(),
+        None => result.failures.join("; "),
+    }
+}
+
+fn serialize_millis<S: serde::Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::arazzo::{Parameter, RequestBody};
+    use serde_json::json;
+
+    fn planned(step: Step) -> PlannedStep {
+        PlannedStep {
+            step,
+            method: "POST".to_string(),
+            path: "/owners/{ownerId}/pets".to_string(),
+        }
+    }
+
+    fn step(parameters: Vec<Parameter>, body: Option<Value>) -> Step {
+        serde_json::from_value(json!({"stepId": "create", "operationId": "createPet"}))
+            .map(|mut step: Step| {
+                step.parameters = parameters;
+                step.request_body = body.map(|payload| RequestBody {
+                    content_type: None,
+                    payload,
+                    replacements: Vec::new(),
+                });
+                step
+            })
+            .unwrap()
+    }
+
+    fn executor() -> NativeExecutor {
+        NativeExecutor::new(NativeOptions {
+            base_url: "http://localhost:8080/api/".to_string(),
+            timeout: Duration::from_secs(5),
+            base_dir: PathBuf::from("."),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_prepare_resolves_expressions() {
+        let mut context = Context::new(json!({"owner": "o 1", "tags": ["a", "b"]}));
+        context
+            .steps
+            .insert("login".to_string(), json!({"token": "abc", "id": 7}));
+
+        let step = step(
+            vec![
+                Parameter {
+                    name: "ownerId".to_string(),
+                    location: "path".to_string(),
+                    value: json!("$inputs.owner"),
+                },
+                Parameter {
+                    name: "tag".to_string(),
+                    location: "query".to_string(),
+                    value: json!("$inputs.tags"),
+                },
+                Parameter {
+                    name: "Authorization".to_string(),
+                    location: "header".to_string(),
+                    value: json!("Bearer {$steps.login.outputs.token}"),
+                },
+            ],
+            Some(json!({"ownerRef": "$steps.login.outputs.id", "name": "rex"})),
+        );
+
+        let request = executor().prepare(&planned(step), &context).unwrap();
+        assert_eq!(
+            request.url,
+            "http://localhost:8080/api/owners/o%201/pets?tag=a&tag=b"
+        );
+        assert_eq!(
+            request.headers,
+            vec![("Authorization".to_string(), "Bearer abc".to_string())]
+        );
+        // 単独の式は JSON の型を保つ
+        assert_eq!(request.body, Some(json!({"ownerRef": 7, "name": "rex"})));
+        assert_eq!(
+            request.encoded.unwrap().content_type,
+            "application/json".to_string()
+        );
+    }
+
+    #[test]
+    fn test_check_criteria() {
+        let context = Context::default();
+        let request = PreparedRequest {
+            method: "GET".to_string(),
+            url: "http://localhost/pets".to_string(),
+            path_template: "/pets".to_string(),
+            path_params: Vec::new(),
+            query: Vec::new(),
+            headers: Vec::new(),
+            body: None,
+            encoded: None,
+        };
+        let response = StepResponse {
+            status: 201,
+            headers: vec![("location".to_string(), "/pets/1".to_string())],
+            body: json!({"items": [{"id": 3}]}),
+            elapsed: Duration::ZERO,
+        };
+        let criteria: Vec<SuccessCriteria> = serde_json::from_value(json!([
+            {"context": "$statusCode", "condition": "==", "value": 201},
+            {"context": "$response.body#/items/0/id", "condition": ">=", "value": 3},
+            {"context": "$response.header.Location", "condition": "^/pets/\\d+$", "type": "regex"},
+            {"context": "$statusCode", "condition": "<", "value": 200}
+        ]))
+        .unwrap();
+
+        let failures = check_criteria(&criteria, &context, &request, &response);
+        assert_eq!(
+            failures,
+            vec!["$statusCode < 200 (actual: 201, expected: 200)".to_string()]
+        );
+
+        // 条件ごとの結果（実行中のイベントに使う）
+        let results = evaluate_criteria(&criteria, &context, &request, &response);
+        let passed: Vec<bool> = results.iter().map(|r| r.passed).collect();
+        assert_eq!(passed, vec![true, true, true, false]);
+        assert_eq!(results[0].criterion, "$statusCode == 201");
+        assert_eq!(results[3].message.as_deref(), Some(failures[0].as_str()));
+    }
+}
@@ -0,0 +1,199 @@
+//! Test case reports of native runs
+//!
+//! A report is a flat list of test cases grouped by suite (the workflow
+//! id). It is written as JSON, or as JUnit XML when the file name ends with
+//! `.xml`, so that CI systems can display each case separately.
+
+use crate::error::Result;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::time::Duration;
+
+/// Outcome of a test case
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaseStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+/// A single test case
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestCase {
+    /// Suite the case belongs to (workflow id)
+    pub suite: String,
+    pub name: String,
+    pub status: CaseStatus,
+    #[serde(serialize_with = "serialize_millis", rename = "durationMs")]
+    pub duration: Duration,
+    /// Failure or skip reason
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl TestCase {
+    pub fn passed(suite: &str, name: impl Into<String>, duration: Duration) -> Self {
+        Self {
+            suite: suite.to_string(),
+            name: name.into(),
+            status: CaseStatus::Passed,
+            duration,
+            message: None,
+        }
+    }
+
+    pub fn failed(
+        suite: &str,
+        name: impl Into<String>,
+        duration: Duration,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            suite: suite.to_string(),
+            name: name.into(),
+            status: CaseStatus::Failed,
+            duration,
+            message: Some(message.into()),
+        }
+    }
+
+    pub fn skipped(suite: &str, name: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            suite: suite.to_string(),
+            name: name.into(),
+            status: CaseStatus::Skipped,
+            duration: Duration::ZERO,
+            message: Some(message.into()),
+        }
+    }
+}
+
+/// Test cases of a run
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RunReport {
+    pub cases: Vec<TestCase>,
+}
+
+impl RunReport {
+    pub fn count(&self, status: CaseStatus) -> usize {
+        self.cases.iter().filter(|c| c.status == status).count()
+    }
+
+    /// Whether no case failed
+    pub fn success(&self) -> bool {
+        self.count(CaseStatus::Failed) == 0
+    }
+
+    /// Render the report as JUnit XML (one `<testsuite>` per suite)
+    pub fn to_junit(&self) -> String {
+        let mut suites: Vec<&str> = Vec::new();
+        for case in &self.cases {
+            if !suites.contains(&case.suite.as_str()) {
+                suites.push(&case.suite);
+            }
+        }
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n");
+        for suite in suites {
+            let cases: Vec<&TestCase> = self.cases.iter().filter(|c| c.suite == suite).collect();
+            let count = |status| cases.iter().filter(|c| c.status == status).count();
+            let time: f64 = cases.iter().map(|c| c.duration.as_secs_f64()).sum();
+            let _ = writeln!(
+                xml,
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\">",
+                escape(suite),
+                cases.len(),
+                count(CaseStatus::Failed),
+                count(CaseStatus::Skipped),
+                time
+            );
+            for case in cases {
+                let _ = write!(
+                    xml,
+                    "    <testcase classname=\"{}\" name=\"{}\" time=\"{:.3}\"",
+                    escape(suite),
+                    escape(&case.name),
+                    case.duration.as_secs_f64()
+                );
+                let message = escape(case.message.as_deref().unwrap_or_default());
+                match case.status {
+                    CaseStatus::Passed => xml.push_str("/>\n"),
+                    CaseStatus::Failed => {
+                        let _ = writeln!(
+                            xml,
+                            ">\n      <failure message=\"{message}\"/>\n    </testcase>"
+                        );
+                    }
+                    CaseStatus::Skipped => {
+                        let _ = writeln!(
+                            xml,
+                            ">\n      <skipped message=\"{message}\"/>\n    </testcase>"
+                        );
+                    }
+                }
+            }
+            xml.push_str("  </testsuite>\n");
+        }
+        xml.push_str("</testsuites>\n");
+        xml
+    }
+
+    /// Write the report (JUnit XML for `.xml` files, JSON otherwise)
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let content = if path.extension().is_some_and(|ext| ext == "xml") {
+            self.to_junit()
+        } else {
+            serde_json::to_string_pretty(self)?
+        };
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn serialize_millis<S: serde::Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_junit() {
+        let report = RunReport {
+            cases: vec![
+                TestCase::passed("flow", "create", Duration::from_millis(12)),
+                TestCase::failed(
+                    "flow",
+                    "create [negative] missing query 'limit'",
+                    Duration::from_millis(3),
+                    "expected a declared 4xx, got 200 <ok>",
+                ),
+                TestCase::skipped("other", "get", "prerequisite step 'create' failed"),
+            ],
+        };
+        assert!(!report.success());
+        assert_eq!(report.count(CaseStatus::Passed), 1);
+
+        let xml = report.to_junit();
+        assert!(xml.contains(
+            "<testsuite name=\"flow\" tests=\"2\" failures=\"1\" skipped=\"0\" time=\"0.015\">"
+        ));
+        assert!(xml.contains("<testcase classname=\"flow\" name=\"create\" time=\"0.012\"/>"));
+        assert!(xml.contains("<failure message=\"expected a declared 4xx, got 200 &lt;ok&gt;\"/>"));
+        assert!(xml.contains("<skipped message=\"prerequisite step 'create' failed\"/>"));
+    }
+}
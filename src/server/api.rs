@@ -1,19 +1,22 @@
 use axum::{
     Json,
-    extract::{Path, State},
-    http::{StatusCode, header},
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode, header},
     response::{IntoResponse, Response},
 };
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    generator::sample::{OperationSample, SampleMode, SampleOptions, sample_operation},
     graph::{builder::build_flow_graph, exporter::export_json},
     loader,
     models::arazzo::{ArazzoSpec, Workflow},
+    server::etag::{check_if_match, etag, etag_headers},
     server::state::AppState,
 };
 use oas3::OpenApiV3Spec;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// RFC 9457 Problem Details for HTTP APIs
 /// https://datatracker.ietf.org/doc/html/rfc9457
@@ -31,10 +34,13 @@ pub struct ProblemDetails {
     /// A URI reference that identifies the specific occurrence of the problem
     #[serde(skip_serializing_if = "Option::is_none")]
     pub instance: Option<String>,
+    /// The current version (ETag) of the resource when a precondition failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_version: Option<String>,
 }
 
 impl ProblemDetails {
-    fn new(status: StatusCode, type_suffix: &str, title: &str, detail: String) -> Self {
+    pub(crate) fn new(status: StatusCode, type_suffix: &str, title: &str, detail: String) -> Self {
         tracing::warn!(
             http.status = status.as_u16(),
             problem.type = type_suffix,
@@ -48,31 +54,63 @@ impl ProblemDetails {
             status: status.as_u16(),
             detail,
             instance: None,
+            current_version: None,
         }
     }
 
-    #[allow(dead_code)]
-    fn with_instance(mut self, instance: String) -> Self {
+    pub(crate) fn with_instance(mut self, instance: String) -> Self {
         self.instance = Some(instance);
         self
     }
+
+    pub(crate) fn with_current_version(mut self, version: String) -> Self {
+        self.current_version = Some(version);
+        self
+    }
 }
 
 impl IntoResponse for ProblemDetails {
     fn into_response(self) -> Response {
         let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let etag = self.current_version.clone();
         let body = Json(self);
-        (
+        let mut response = (
             status,
             [(header::CONTENT_TYPE, "application/problem+json")],
             body,
         )
-            .into_response()
+            .into_response();
+        if let Some(value) = etag.and_then(|etag| header::HeaderValue::from_str(&etag).ok()) {
+            response.headers_mut().insert(header::ETAG, value);
+        }
+        response
     }
 }
 
-/// エラーレスポンスを作成するヘルパー
-type ApiResult<T> = Result<T, ProblemDetails>;
+/// エラーレスポンスを作成するヘルパー（ProblemDetails は大きいので Box に入れて返す）
+pub(crate) type ApiResult<T> = Result<T, Box<ProblemDetails>>;
+
+impl IntoResponse for Box<ProblemDetails> {
+    fn into_response(self) -> Response {
+        (*self).into_response()
+    }
+}
+
+/// ファイルの読み書きをブロッキングスレッドで行う
+pub(crate) async fn blocking<T, F>(task: F) -> ApiResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> ApiResult<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(task).await.map_err(|e| {
+        ProblemDetails::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "task-failed",
+            "Internal Error",
+            format!("Failed to complete the request: {}", e),
+        )
+    })?
+}
 
 /// GET /api/arazzo/workflows のレスポンス
 #[derive(Debug, Serialize, Deserialize)]
@@ -114,6 +152,9 @@ pub struct ProjectInfo {
     pub workflow_count: usize,
     pub arazzo_path: String,
     pub openapi_files: Vec<String>,
+    /// 最後の読み込みエラー（前の版を返している）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub load_error: Option<String>,
 }
 
 /// GET /api/projects/{project_name} のレスポンス
@@ -189,20 +230,14 @@ pub async fn get_project_operations(
             "invalid-project-name",
             "Invalid Request",
             "Project name contains invalid characters".into(),
-        ));
-    }
-
-    let mut cache = state.projects.write().map_err(|_| {
-        ProblemDetails::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "cache-lock-failed",
-            "Internal Error",
-            "Failed to acquire project cache lock".into(),
         )
-    })?;
+        .into());
+    }
 
-    let project = cache
+    let project = state
+        .projects
         .get_project(&project_name, &state.root_dir)
+        .await
         .map_err(|e| {
             ProblemDetails::new(
                 StatusCode::NOT_FOUND,
@@ -445,6 +480,70 @@ pub async fn get_project_operations(
     Ok(Json(OperationsResponse { operations }))
 }
 
+/// Query parameters of the operation sample endpoint
+#[derive(Debug, Deserialize)]
+pub struct SampleQuery {
+    /// `minimal` (default) or `maximal`
+    #[serde(default)]
+    pub mode: SampleMode,
+    /// Seed of the generator (default 0)
+    #[serde(default)]
+    pub seed: u64,
+}
+
+/// GET /api/projects/{project_name}/operations/{operation_id}/sample
+/// Synthesize a request (parameters and body) from the operation's schemas
+#[tracing::instrument(
+    name = "api.get_operation_sample",
+    skip(state, query),
+    fields(project_name = %project_name, operation_id = %operation_id)
+)]
+pub async fn get_operation_sample(
+    State(state): State<AppState>,
+    Path((project_name, operation_id)): Path<(String, String)>,
+    Query(query): Query<SampleQuery>,
+) -> ApiResult<Json<OperationSample>> {
+    if project_name.contains("..") || project_name.contains('/') || project_name.contains('\\') {
+        return Err(ProblemDetails::new(
+            StatusCode::BAD_REQUEST,
+            "invalid-project-name",
+            "Invalid Request",
+            "Project name contains invalid characters".into(),
+        )
+        .into());
+    }
+
+    let project = state
+        .projects
+        .get_project(&project_name, &state.root_dir)
+        .await
+        .map_err(|e| {
+            ProblemDetails::new(
+                StatusCode::NOT_FOUND,
+                "project-not-found",
+                "Project Not Found",
+                format!("Project '{}' not found: {}", project_name, e),
+            )
+        })?;
+
+    let options = SampleOptions {
+        mode: query.mode,
+        seed: query.seed,
+        ..Default::default()
+    };
+    let sample =
+        sample_operation(&project.openapi_resolver, &operation_id, &options).map_err(|_| {
+            ProblemDetails::new(
+                StatusCode::NOT_FOUND,
+                "operation-not-found",
+                "Operation Not Found",
+                format!("Operation '{}' not found", operation_id),
+            )
+        })?;
+
+    Ok(Json(sample))
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ValidateRequest {
     pub yaml: String,
@@ -571,23 +670,18 @@ fn extract_location_from_error(error: &str) -> (Option<usize>, Option<usize>) {
     )
 )]
 pub async fn list_projects(State(state): State<AppState>) -> ApiResult<Json<ProjectsResponse>> {
-    let mut cache = state.projects.write().map_err(|_| {
-        ProblemDetails::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "cache-lock-failed",
-            "Internal Error",
-            "Failed to acquire project cache lock".into(),
-        )
-    })?;
-
-    let projects = cache.list_projects(&state.root_dir).map_err(|e| {
-        ProblemDetails::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "scan-failed",
-            "Project Scan Failed",
-            format!("Failed to scan projects: {}", e),
-        )
-    })?;
+    let projects = state
+        .projects
+        .list_projects(&state.root_dir)
+        .await
+        .map_err(|e| {
+            ProblemDetails::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "scan-failed",
+                "Project Scan Failed",
+                format!("Failed to scan projects: {}", e),
+            )
+        })?;
 
     let project_infos: Vec<ProjectInfo> = projects
         .iter()
@@ -603,6 +697,7 @@ pub async fn list_projects(State(state): State<AppState>) -> ApiResult<Json<Proj
                 .iter()
                 .map(|name| format!("{}/{}.yaml", p.name, name))
                 .collect(),
+            load_error: state.projects.load_error(&p.name),
         })
         .collect();
 
@@ -630,20 +725,14 @@ pub async fn get_project(
             "invalid-project-name",
             "Invalid Request",
             "Project name contains invalid characters".into(),
-        ));
-    }
-
-    let mut cache = state.projects.write().map_err(|_| {
-        ProblemDetails::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "cache-lock-failed",
-            "Internal Error",
-            "Failed to acquire project cache lock".into(),
         )
-    })?;
+        .into());
+    }
 
-    let project = cache
+    let project = state
+        .projects
         .get_project(&project_name, &state.root_dir)
+        .await
         .map_err(|e| {
             ProblemDetails::new(
                 StatusCode::NOT_FOUND,
@@ -669,11 +758,11 @@ pub async fn get_project(
     Ok(Json(project_detail))
 }
 
-/// GET /api/projects/{project_name}/arazzo - Arazzo仕様を取得
+/// GET /api/projects/{project_name}/arazzo - Arazzo仕様を取得（ETag 付き）
 pub async fn get_project_arazzo(
     State(state): State<AppState>,
     Path(project_name): Path<String>,
-) -> ApiResult<Json<ArazzoSpec>> {
+) -> ApiResult<(HeaderMap, Json<ArazzoSpec>)> {
     // セキュリティチェック
     if project_name.contains("..") || project_name.contains('/') || project_name.contains('\\') {
         return Err(ProblemDetails::new(
@@ -681,20 +770,14 @@ pub async fn get_project_arazzo(
             "invalid-project-name",
             "Invalid Request",
             "Project name contains invalid characters".into(),
-        ));
-    }
-
-    let mut cache = state.projects.write().map_err(|_| {
-        ProblemDetails::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "cache-lock-failed",
-            "Internal Error",
-            "Failed to acquire project cache lock".into(),
         )
-    })?;
+        .into());
+    }
 
-    let project = cache
+    let project = state
+        .projects
         .get_project(&project_name, &state.root_dir)
+        .await
         .map_err(|e| {
             ProblemDetails::new(
                 StatusCode::NOT_FOUND,
@@ -704,15 +787,21 @@ pub async fn get_project_arazzo(
             )
         })?;
 
-    Ok(Json(project.arazzo_spec.clone()))
+    Ok((
+        etag_headers(&project.arazzo_spec),
+        Json(project.arazzo_spec.clone()),
+    ))
 }
 
 /// PUT /api/projects/{project_name}/arazzo - Arazzo仕様を更新
+///
+/// `If-Match` があれば今のファイルの版と合うときだけ上書きする。
 pub async fn update_project_arazzo(
     State(state): State<AppState>,
     Path(project_name): Path<String>,
+    headers: HeaderMap,
     Json(spec): Json<ArazzoSpec>,
-) -> ApiResult<Json<ArazzoSpec>> {
+) -> ApiResult<(HeaderMap, Json<ArazzoSpec>)> {
     // セキュリティチェック
     if project_name.contains("..") || project_name.contains('/') || project_name.contains('\\') {
         return Err(ProblemDetails::new(
@@ -720,20 +809,18 @@ pub async fn update_project_arazzo(
             "invalid-project-name",
             "Invalid Request",
             "Project name contains invalid characters".into(),
-        ));
+        )
+        .into());
     }
 
-    let mut cache = state.projects.write().map_err(|_| {
-        ProblemDetails::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "cache-lock-failed",
-            "Internal Error",
-            "Failed to acquire project cache lock".into(),
-        )
-    })?;
+    // 同じプロジェクトの書き換えは順に行う
+    let edit_lock = state.projects.edit_lock(&project_name);
+    let _editing = edit_lock.lock().await;
 
-    let project = cache
+    let project = state
+        .projects
         .get_project(&project_name, &state.root_dir)
+        .await
         .map_err(|e| {
             ProblemDetails::new(
                 StatusCode::NOT_FOUND,
@@ -743,28 +830,48 @@ pub async fn update_project_arazzo(
             )
         })?;
 
-    loader::save_arazzo(&project.arazzo_path, &spec).map_err(|e| {
-        ProblemDetails::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "save-failed",
-            "Failed to Save Resource",
-            format!("Failed to save Arazzo specification: {}", e),
-        )
-    })?;
+    // ファイルの読み書きと再読み込みはブロッキングスレッドで行う
+    let projects = Arc::clone(&state.projects);
+    let root_dir = state.root_dir.clone();
+    let spec = blocking(move || {
+        if headers.contains_key(header::IF_MATCH) {
+            let current = loader::load_arazzo(&project.arazzo_path).map_err(|e| {
+                ProblemDetails::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "load-failed",
+                    "Failed to Load Resource",
+                    format!("Failed to load Arazzo specification: {}", e),
+                )
+            })?;
+            check_if_match(&headers, &etag(&current))?;
+        }
 
-    // キャッシュを再読み込み
-    cache
-        .reload_project(&project_name, &state.root_dir)
-        .map_err(|e| {
+        loader::save_arazzo(&project.arazzo_path, &spec).map_err(|e| {
             ProblemDetails::new(
                 StatusCode::INTERNAL_SERVER_ERROR,
-                "cache-reload-failed",
-                "Cache Reload Failed",
-                format!("Failed to reload project cache: {}", e),
+                "save-failed",
+                "Failed to Save Resource",
+                format!("Failed to save Arazzo specification: {}", e),
             )
         })?;
 
-    Ok(Json(spec))
+        // キャッシュを再読み込み
+        projects
+            .reload_project(&project_name, &root_dir)
+            .map_err(|e| {
+                ProblemDetails::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "cache-reload-failed",
+                    "Cache Reload Failed",
+                    format!("Failed to reload project cache: {}", e),
+                )
+            })?;
+
+        Ok(spec)
+    })
+    .await?;
+
+    Ok((etag_headers(&spec), Json(spec)))
 }
 /// GET /api/projects/{project_name}/workflows - ワークフロー一覧を取得
 pub async fn get_project_workflows(
@@ -777,20 +884,14 @@ pub async fn get_project_workflows(
             "invalid-project-name",
             "Invalid Request",
             "Project name contains invalid characters".into(),
-        ));
-    }
-
-    let mut cache = state.projects.write().map_err(|_| {
-        ProblemDetails::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "cache-lock-failed",
-            "Internal Error",
-            "Failed to acquire project cache lock".into(),
         )
-    })?;
+        .into());
+    }
 
-    let project = cache
+    let project = state
+        .projects
         .get_project(&project_name, &state.root_dir)
+        .await
         .map_err(|e| {
             ProblemDetails::new(
                 StatusCode::NOT_FOUND,
@@ -835,20 +936,18 @@ pub async fn create_project_workflow(
             "invalid-project-name",
             "Invalid Request",
             "Project name contains invalid characters".into(),
-        ));
+        )
+        .into());
     }
 
-    let mut cache = state.projects.write().map_err(|_| {
-        ProblemDetails::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "cache-lock-failed",
-            "Internal Error",
-            "Failed to acquire project cache lock".into(),
-        )
-    })?;
+    // 同じプロジェクトの書き換えは順に行う
+    let edit_lock = state.projects.edit_lock(&project_name);
+    let _editing = edit_lock.lock().await;
 
-    let project = cache
+    let project = state
+        .projects
         .get_project(&project_name, &state.root_dir)
+        .await
         .map_err(|e| {
             ProblemDetails::new(
                 StatusCode::NOT_FOUND,
@@ -858,85 +957,88 @@ pub async fn create_project_workflow(
             )
         })?;
 
-    let mut arazzo = loader::load_arazzo(&project.arazzo_path).map_err(|e| {
-        ProblemDetails::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "load-failed",
-            "Failed to Load Resource",
-            format!("Failed to load Arazzo specification: {}", e),
-        )
-    })?;
-
-    // 重複チェック
-    if arazzo
-        .workflows
-        .iter()
-        .any(|w| w.workflow_id == req.workflow.workflow_id)
-    {
-        return Err(ProblemDetails::new(
-            StatusCode::CONFLICT,
-            "workflow-exists",
-            "Resource Already Exists",
-            format!(
-                "Workflow with ID '{}' already exists",
-                req.workflow.workflow_id
-            ),
-        ));
-    }
+    // ファイルの読み書きと再読み込みはブロッキングスレッドで行う
+    let projects = Arc::clone(&state.projects);
+    let root_dir = state.root_dir.clone();
+    let workflow = blocking(move || {
+        let mut arazzo = loader::load_arazzo(&project.arazzo_path).map_err(|e| {
+            ProblemDetails::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "load-failed",
+                "Failed to Load Resource",
+                format!("Failed to load Arazzo specification: {}", e),
+            )
+        })?;
 
-    // ワークフロー追加（既存ファイルに追記）
-    arazzo.workflows.push(req.workflow.clone());
+        // 重複チェック
+        if arazzo
+            .workflows
+            .iter()
+            .any(|w| w.workflow_id == req.workflow.workflow_id)
+        {
+            return Err(ProblemDetails::new(
+                StatusCode::CONFLICT,
+                "workflow-exists",
+                "Resource Already Exists",
+                format!(
+                    "Workflow with ID '{}' already exists",
+                    req.workflow.workflow_id
+                ),
+            )
+            .into());
+        }
 
-    // 保存
-    loader::save_arazzo(&project.arazzo_path, &arazzo).map_err(|e| {
-        ProblemDetails::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "save-failed",
-            "Failed to Save Resource",
-            format!("Failed to save Arazzo specification: {}", e),
-        )
-    })?;
+        // ワークフロー追加（既存ファイルに追記）
+        arazzo.workflows.push(req.workflow.clone());
 
-    // キャッシュを再読み込み
-    cache
-        .reload_project(&project_name, &state.root_dir)
-        .map_err(|e| {
+        // 保存
+        loader::save_arazzo(&project.arazzo_path, &arazzo).map_err(|e| {
             ProblemDetails::new(
                 StatusCode::INTERNAL_SERVER_ERROR,
-                "cache-reload-failed",
-                "Cache Reload Failed",
-                format!("Failed to reload project cache: {}", e),
+                "save-failed",
+                "Failed to Save Resource",
+                format!("Failed to save Arazzo specification: {}", e),
             )
         })?;
 
-    Ok((StatusCode::CREATED, Json(req.workflow)))
+        // キャッシュを再読み込み
+        projects
+            .reload_project(&project_name, &root_dir)
+            .map_err(|e| {
+                ProblemDetails::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "cache-reload-failed",
+                    "Cache Reload Failed",
+                    format!("Failed to reload project cache: {}", e),
+                )
+            })?;
+
+        Ok(req.workflow)
+    })
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(workflow)))
 }
 
-/// GET /api/projects/{project_name}/workflows/{workflow_id} - 特定のワークフローを取得
+/// GET /api/projects/{project_name}/workflows/{workflow_id} - 特定のワークフローを取得（ETag 付き）
 pub async fn get_project_workflow(
     State(state): State<AppState>,
     Path((project_name, workflow_id)): Path<(String, String)>,
-) -> ApiResult<Json<Workflow>> {
+) -> ApiResult<(HeaderMap, Json<Workflow>)> {
     if project_name.contains("..") || project_name.contains('/') || project_name.contains('\\') {
         return Err(ProblemDetails::new(
             StatusCode::BAD_REQUEST,
             "invalid-project-name",
             "Invalid Request",
             "Project name contains invalid characters".into(),
-        ));
-    }
-
-    let mut cache = state.projects.write().map_err(|_| {
-        ProblemDetails::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "cache-lock-failed",
-            "Internal Error",
-            "Failed to acquire project cache lock".into(),
         )
-    })?;
+        .into());
+    }
 
-    let project = cache
+    let project = state
+        .projects
         .get_project(&project_name, &state.root_dir)
+        .await
         .map_err(|e| {
             ProblemDetails::new(
                 StatusCode::NOT_FOUND,
@@ -960,7 +1062,7 @@ pub async fn get_project_workflow(
             )
         })?;
 
-    Ok(Json(workflow.clone()))
+    Ok((etag_headers(workflow), Json(workflow.clone())))
 }
 
 /// PUT /api/projects/{project_name}/workflows/{workflow_id} - ワークフローを更新
@@ -975,15 +1077,17 @@ pub async fn get_project_workflow(
 pub async fn update_project_workflow(
     State(state): State<AppState>,
     Path((project_name, workflow_id)): Path<(String, String)>,
+    headers: HeaderMap,
     Json(workflow): Json<Workflow>,
-) -> ApiResult<Json<Workflow>> {
+) -> ApiResult<(HeaderMap, Json<Workflow>)> {
     if project_name.contains("..") || project_name.contains('/') || project_name.contains('\\') {
         return Err(ProblemDetails::new(
             StatusCode::BAD_REQUEST,
             "invalid-project-name",
             "Invalid Request",
             "Project name contains invalid characters".into(),
-        ));
+        )
+        .into());
     }
 
     // Validate workflow ID matches path parameter
@@ -996,20 +1100,18 @@ pub async fn update_project_workflow(
                 "Workflow ID in path ({}) does not match workflow ID in body ({})",
                 workflow_id, workflow.workflow_id
             ),
-        ));
+        )
+        .into());
     }
 
-    let mut cache = state.projects.write().map_err(|_| {
-        ProblemDetails::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "cache-lock-failed",
-            "Internal Error",
-            "Failed to acquire project cache lock".into(),
-        )
-    })?;
+    // 同じプロジェクトの書き換えは順に行う
+    let edit_lock = state.projects.edit_lock(&project_name);
+    let _editing = edit_lock.lock().await;
 
-    let project = cache
+    let project = state
+        .projects
         .get_project(&project_name, &state.root_dir)
+        .await
         .map_err(|e| {
             ProblemDetails::new(
                 StatusCode::NOT_FOUND,
@@ -1019,59 +1121,71 @@ pub async fn update_project_workflow(
             )
         })?;
 
-    let mut arazzo = loader::load_arazzo(&project.arazzo_path).map_err(|e| {
-        ProblemDetails::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "load-failed",
-            "Failed to Load Resource",
-            format!("Failed to load Arazzo specification: {}", e),
-        )
-    })?;
-
-    // Find and update existing workflow
-    let pos = arazzo
-        .workflows
-        .iter()
-        .position(|w| w.workflow_id == workflow_id)
-        .ok_or_else(|| {
+    // ファイルの読み書きと再読み込みはブロッキングスレッドで行う
+    let projects = Arc::clone(&state.projects);
+    let root_dir = state.root_dir.clone();
+    let workflow = blocking(move || {
+        let mut arazzo = loader::load_arazzo(&project.arazzo_path).map_err(|e| {
             ProblemDetails::new(
-                StatusCode::NOT_FOUND,
-                "workflow-not-found",
-                "Resource Not Found",
-                format!("Workflow '{}' not found", workflow_id),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "load-failed",
+                "Failed to Load Resource",
+                format!("Failed to load Arazzo specification: {}", e),
             )
         })?;
 
-    arazzo.workflows[pos] = workflow.clone();
-
-    loader::save_arazzo(&project.arazzo_path, &arazzo).map_err(|e| {
-        ProblemDetails::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "save-failed",
-            "Failed to Save Resource",
-            format!("Failed to save Arazzo specification: {}", e),
-        )
-    })?;
-
-    // キャッシュを再読み込み
-    cache
-        .reload_project(&project_name, &state.root_dir)
-        .map_err(|e| {
+        // Find and update existing workflow
+        let pos = arazzo
+            .workflows
+            .iter()
+            .position(|w| w.workflow_id == workflow_id)
+            .ok_or_else(|| {
+                ProblemDetails::new(
+                    StatusCode::NOT_FOUND,
+                    "workflow-not-found",
+                    "Resource Not Found",
+                    format!("Workflow '{}' not found", workflow_id),
+                )
+            })?;
+
+        // 読んだ後に別の編集が入っていれば上書きしない
+        check_if_match(&headers, &etag(&arazzo.workflows[pos]))?;
+
+        arazzo.workflows[pos] = workflow.clone();
+
+        loader::save_arazzo(&project.arazzo_path, &arazzo).map_err(|e| {
             ProblemDetails::new(
                 StatusCode::INTERNAL_SERVER_ERROR,
-                "cache-reload-failed",
-                "Cache Reload Failed",
-                format!("Failed to reload project cache: {}", e),
+                "save-failed",
+                "Failed to Save Resource",
+                format!("Failed to save Arazzo specification: {}", e),
             )
         })?;
 
-    Ok(Json(workflow))
+        // キャッシュを再読み込み
+        projects
+            .reload_project(&project_name, &root_dir)
+            .map_err(|e| {
+                ProblemDetails::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "cache-reload-failed",
+                    "Cache Reload Failed",
+                    format!("Failed to reload project cache: {}", e),
+                )
+            })?;
+
+        Ok(workflow)
+    })
+    .await?;
+
+    Ok((etag_headers(&workflow), Json(workflow)))
 }
 
 /// DELETE /api/projects/{project_name}/workflows/{workflow_id} - ワークフローを削除
 pub async fn delete_project_workflow(
     State(state): State<AppState>,
     Path((project_name, workflow_id)): Path<(String, String)>,
+    headers: HeaderMap,
 ) -> ApiResult<StatusCode> {
     if project_name.contains("..") || project_name.contains('/') || project_name.contains('\\') {
         return Err(ProblemDetails::new(
@@ -1079,20 +1193,18 @@ pub async fn delete_project_workflow(
             "invalid-project-name",
             "Invalid Request",
             "Project name contains invalid characters".into(),
-        ));
+        )
+        .into());
     }
 
-    let mut cache = state.projects.write().map_err(|_| {
-        ProblemDetails::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "cache-lock-failed",
-            "Internal Error",
-            "Failed to acquire project cache lock".into(),
-        )
-    })?;
+    // 同じプロジェクトの書き換えは順に行う
+    let edit_lock = state.projects.edit_lock(&project_name);
+    let _editing = edit_lock.lock().await;
 
-    let project = cache
+    let project = state
+        .projects
         .get_project(&project_name, &state.root_dir)
+        .await
         .map_err(|e| {
             ProblemDetails::new(
                 StatusCode::NOT_FOUND,
@@ -1102,48 +1214,63 @@ pub async fn delete_project_workflow(
             )
         })?;
 
-    let mut arazzo = loader::load_arazzo(&project.arazzo_path).map_err(|e| {
-        ProblemDetails::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "load-failed",
-            "Failed to Load Resource",
-            format!("Failed to load Arazzo specification: {}", e),
-        )
-    })?;
-
-    let initial_len = arazzo.workflows.len();
-    arazzo.workflows.retain(|w| w.workflow_id != workflow_id);
-
-    if arazzo.workflows.len() == initial_len {
-        return Err(ProblemDetails::new(
-            StatusCode::NOT_FOUND,
-            "workflow-not-found",
-            "Resource Not Found",
-            format!("Workflow '{}' not found", workflow_id),
-        ));
-    }
-
-    loader::save_arazzo(&project.arazzo_path, &arazzo).map_err(|e| {
-        ProblemDetails::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "save-failed",
-            "Failed to Save Resource",
-            format!("Failed to save Arazzo specification: {}", e),
-        )
-    })?;
+    // ファイルの読み書きと再読み込みはブロッキングスレッドで行う
+    let projects = Arc::clone(&state.projects);
+    let root_dir = state.root_dir.clone();
+    blocking(move || {
+        let mut arazzo = loader::load_arazzo(&project.arazzo_path).map_err(|e| {
+            ProblemDetails::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "load-failed",
+                "Failed to Load Resource",
+                format!("Failed to load Arazzo specification: {}", e),
+            )
+        })?;
 
-    // キャッシュを再読み込み
-    cache
-        .reload_project(&project_name, &state.root_dir)
-        .map_err(|e| {
+        let pos = arazzo
+            .workflows
+            .iter()
+            .position(|w| w.workflow_id == workflow_id)
+            .ok_or_else(|| {
+                ProblemDetails::new(
+                    StatusCode::NOT_FOUND,
+                    "workflow-not-found",
+                    "Resource Not Found",
+                    format!("Workflow '{}' not found", workflow_id),
+                )
+            })?;
+
+        // 読んだ後に別の編集が入っていれば消さない
+        check_if_match(&headers, &etag(&arazzo.workflows[pos]))?;
+
+        // 重複した ID は読み込み時に弾かれるが、残さないよう一致するものはすべて消す
+        arazzo.workflows.retain(|w| w.workflow_id != workflow_id);
+
+        loader::save_arazzo(&project.arazzo_path, &arazzo).map_err(|e| {
             ProblemDetails::new(
                 StatusCode::INTERNAL_SERVER_ERROR,
-                "cache-reload-failed",
-                "Cache Reload Failed",
-                format!("Failed to reload project cache: {}", e),
+                "save-failed",
+                "Failed to Save Resource",
+                format!("Failed to save Arazzo specification: {}", e),
             )
         })?;
 
+        // キャッシュを再読み込み
+        projects
+            .reload_project(&project_name, &root_dir)
+            .map_err(|e| {
+                ProblemDetails::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "cache-reload-failed",
+                    "Cache Reload Failed",
+                    format!("Failed to reload project cache: {}", e),
+                )
+            })?;
+
+        Ok(())
+    })
+    .await?;
+
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -1166,20 +1293,14 @@ pub async fn get_project_graph(
             "invalid-project-name",
             "Invalid Request",
             "Project name contains invalid characters".into(),
-        ));
-    }
-
-    let mut cache = state.projects.write().map_err(|_| {
-        ProblemDetails::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "cache-lock-failed",
-            "Internal Error",
-            "Failed to acquire project cache lock".into(),
         )
-    })?;
+        .into());
+    }
 
-    let project = cache
+    let project = state
+        .projects
         .get_project(&project_name, &state.root_dir)
+        .await
         .map_err(|e| {
             ProblemDetails::new(
                 StatusCode::NOT_FOUND,
@@ -1299,22 +1420,17 @@ pub async fn get_project_openapi(
             "invalid-project-name",
             "Invalid Request",
             "Project name contains invalid characters".into(),
-        ));
+        )
+        .into());
     }
 
     // 2. Acquire cache lock
-    let mut cache = state.projects.write().map_err(|_| {
-        ProblemDetails::new(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "cache-lock-failed",
-            "Internal Error",
-            "Failed to acquire project cache lock".into(),
-        )
-    })?;
 
     // 3. Get project from cache
-    let project = cache
+    let project = state
+        .projects
         .get_project(&project_name, &state.root_dir)
+        .await
         .map_err(|e| {
             ProblemDetails::new(
                 StatusCode::NOT_FOUND,
@@ -1335,7 +1451,8 @@ pub async fn get_project_openapi(
             "openapi-not-found",
             "OpenAPI Not Found",
             format!("Project '{}' has no OpenAPI files", project_name),
-        ));
+        )
+        .into());
     }
 
     // 5. Merge or return single spec
@@ -1357,9 +1474,7 @@ pub async fn get_project_openapi(
 }
 
 /// Helper function to merge multiple OpenAPI specifications into one
-fn merge_openapi_specs(
-    specs: &HashMap<String, OpenApiV3Spec>,
-) -> Result<serde_json::Value, ProblemDetails> {
+fn merge_openapi_specs(specs: &HashMap<String, OpenApiV3Spec>) -> ApiResult<serde_json::Value> {
     // 1. Sort specs by name for deterministic order
     let mut sorted_names: Vec<&String> = specs.keys().collect();
     sorted_names.sort();
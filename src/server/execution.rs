@@ -0,0 +1,492 @@
+//! エディタからのワークフロー実行
+//!
+//! `POST /api/projects/{project_name}/workflows/{workflow_id}/runs` でネイティブ
+//! エンジンの実行を始め、実行 ID をすぐに返す。実行中のイベント（step-started、
+//! request、response、criterion-result、step-finished、最後に run-finished か
+//! run-cancelled）は `GET .../runs/{run_id}/events` の SSE で流す。途中から
+//! 購読しても、それまでのイベントを先に受け取れる。
+//!
+//! 終わった実行は `hornet2 run --history` と同じくプロジェクトの実行履歴に
+//! 同じ ID で残る。`POST .../runs/{run_id}/cancel` で実行中のリクエストごと
+//! 中断できる（中断した実行は履歴に残らない）。
+
+use super::api::{ApiResult, ProblemDetails};
+use super::state::AppState;
+use crate::converters::ConvertOptions;
+use crate::converters::common::resolve_base_url;
+use crate::runner::history::{RunHistory, RunRecord, git_commit, new_run_id};
+use crate::runner::native::{NativeExecutor, NativeOptions, RunEvent, WorkflowPlan};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::stream::{self, Stream};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{mpsc, watch};
+use tokio_util::sync::CancellationToken;
+
+/// 1リクエストのタイムアウト（`hornet2 run` と同じ）
+const RUN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// イベントを残しておく終了済みの実行の数
+const MAX_FINISHED_RUNS: usize = 32;
+
+/// 実行中（と直近に終わった）実行
+pub struct Execution {
+    project_name: String,
+    /// これまでのイベント（途中から購読したクライアントに先に送る）
+    events: Mutex<Vec<RunEvent>>,
+    /// イベントの数（増えたら購読者を起こす）
+    changes: watch::Sender<usize>,
+    cancel: CancellationToken,
+}
+
+impl Execution {
+    fn new(project_name: &str) -> Self {
+        Self {
+            project_name: project_name.to_string(),
+            events: Mutex::new(Vec::new()),
+            changes: watch::Sender::new(0),
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    fn publish(&self, event: RunEvent) {
+        let count = match self.events.lock() {
+            Ok(mut events) => {
+                events.push(event);
+                events.len()
+            }
+            Err(_) => return,
+        };
+        self.changes.send_replace(count);
+    }
+
+    fn event(&self, index: usize) -> Option<RunEvent> {
+        self.events.lock().ok()?.get(index).cloned()
+    }
+
+    /// run-finished か run-cancelled まで届いたか
+    pub fn is_finished(&self) -> bool {
+        self.events
+            .lock()
+            .map(|events| events.last().is_some_and(RunEvent::is_terminal))
+            .unwrap_or(true)
+    }
+
+    /// 最初のイベントからのストリーム（終了のイベントで閉じる）
+    pub fn subscribe(self: &Arc<Self>) -> impl Stream<Item = RunEvent> + use<> {
+        let changes = self.changes.subscribe();
+        stream::unfold(
+            (Arc::clone(self), changes, 0, false),
+            |(execution, mut changes, next, done)| async move {
+                if done {
+                    return None;
+                }
+                loop {
+                    if let Some(event) = execution.event(next) {
+                        let done = event.is_terminal();
+                        return Some((event, (execution, changes, next + 1, done)));
+                    }
+                    // 送信側（レジストリ）が消えたら閉じる
+                    changes.changed().await.ok()?;
+                }
+            },
+        )
+    }
+}
+
+/// 実行のレジストリ（実行 ID -> 実行）
+#[derive(Default)]
+pub struct ExecutionRegistry {
+    executions: Mutex<IndexMap<String, Arc<Execution>>>,
+}
+
+impl ExecutionRegistry {
+    pub fn get(&self, run_id: &str) -> Option<Arc<Execution>> {
+        self.executions.lock().ok()?.get(run_id).cloned()
+    }
+
+    /// 実行を登録し、古い終了済みの実行を捨てる
+    fn insert(&self, run_id: &str, execution: Arc<Execution>) {
+        let Ok(mut executions) = self.executions.lock() else {
+            return;
+        };
+        let finished: Vec<String> = executions
+            .iter()
+            .filter(|(_, e)| e.is_finished())
+            .map(|(id, _)| id.clone())
+            .collect();
+        let excess = finished.len().saturating_sub(MAX_FINISHED_RUNS - 1);
+        for id in finished.iter().take(excess) {
+            executions.shift_remove(id);
+        }
+        executions.insert(run_id.to_string(), execution);
+    }
+}
+
+/// POST /api/projects/{project_name}/workflows/{workflow_id}/runs のリクエスト
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StartRunRequest {
+    /// ワークフローの入力（既定値に上書きする）
+    pub inputs: Option<Value>,
+    /// 履歴に残す環境名（`staging` など）
+    pub environment: Option<String>,
+    /// OpenAPI の servers の代わりに使うベース URL
+    pub base_url: Option<String>,
+}
+
+/// 実行開始のレスポンス
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartRunResponse {
+    pub run_id: String,
+    pub workflow_id: String,
+    pub base_url: String,
+    /// イベントストリームの URL
+    pub events_url: String,
+}
+
+/// 中断のレスポンス
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelRunResponse {
+    pub run_id: String,
+    pub cancelled: bool,
+}
+
+/// POST /api/projects/{project_name}/workflows/{workflow_id}/runs - ワークフローを実行
+#[tracing::instrument(
+    name = "api.start_workflow_run",
+    skip(state, request),
+    fields(project_name = %project_name, workflow_id = %workflow_id)
+)]
+pub async fn start_workflow_run(
+    State(state): State<AppState>,
+    Path((project_name, workflow_id)): Path<(String, String)>,
+    request: Option<Json<StartRunRequest>>,
+) -> ApiResult<(StatusCode, Json<StartRunResponse>)> {
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+    let inputs = match request.inputs {
+        None | Some(Value::Null) => Value::Null,
+        Some(inputs @ Value::Object(_)) => inputs,
+        Some(_) => {
+            return Err(ProblemDetails::new(
+                StatusCode::BAD_REQUEST,
+                "invalid-inputs",
+                "Invalid Request",
+                "Workflow inputs must be an object".into(),
+            )
+            .into());
+        }
+    };
+
+    let (plan, base_url, project_dir) = prepare_run(
+        &state,
+        &project_name,
+        &workflow_id,
+        request.base_url.clone(),
+    )
+    .await?;
+
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let executor = NativeExecutor::new(NativeOptions {
+        base_url: base_url.clone(),
+        timeout: RUN_TIMEOUT,
+        base_dir: project_dir.clone(),
+    })
+    .map_err(|e| {
+        ProblemDetails::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "run-failed",
+            "Internal Error",
+            e.to_string(),
+        )
+    })?
+    .with_events(sender);
+
+    let started_at = SystemTime::now();
+    let run_id = new_run_id(started_at);
+    let execution = Arc::new(Execution::new(&project_name));
+    state.executions.insert(&run_id, Arc::clone(&execution));
+
+    let record = RecordOptions {
+        run_id: run_id.clone(),
+        started_at,
+        environment: request.environment,
+        base_url: base_url.clone(),
+        project_dir,
+    };
+    tokio::spawn(execute(execution, executor, receiver, plan, inputs, record));
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(StartRunResponse {
+            events_url: format!("/api/projects/{}/runs/{}/events", project_name, run_id),
+            run_id,
+            workflow_id,
+            base_url,
+        }),
+    ))
+}
+
+/// GET /api/projects/{project_name}/runs/{run_id}/events - 実行のイベントを SSE で購読
+#[tracing::instrument(
+    name = "api.stream_run_events",
+    skip(state),
+    fields(project_name = %project_name, run_id = %run_id)
+)]
+pub async fn stream_run_events(
+    State(state): State<AppState>,
+    Path((project_name, run_id)): Path<(String, String)>,
+) -> ApiResult<Sse<impl Stream<Item = Result<Event, axum::Error>>>> {
+    let execution = find_execution(&state, &project_name, &run_id)?;
+    let events = futures::StreamExt::map(execution.subscribe(), |event| {
+        Event::default().event(event.name()).json_data(&event)
+    });
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// POST /api/projects/{project_name}/runs/{run_id}/cancel - 実行を中断
+#[tracing::instrument(
+    name = "api.cancel_run",
+    skip(state),
+    fields(project_name = %project_name, run_id = %run_id)
+)]
+pub async fn cancel_run(
+    State(state): State<AppState>,
+    Path((project_name, run_id)): Path<(String, String)>,
+) -> ApiResult<(StatusCode, Json<CancelRunResponse>)> {
+    let execution = find_execution(&state, &project_name, &run_id)?;
+    if execution.is_finished() {
+        return Err(ProblemDetails::new(
+            StatusCode::CONFLICT,
+            "run-finished",
+            "Run Already Finished",
+            format!("Run '{}' has already finished", run_id),
+        )
+        .into());
+    }
+
+    execution.cancel.cancel();
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(CancelRunResponse {
+            run_id,
+            cancelled: true,
+        }),
+    ))
+}
+
+/// 実行の記録に使う情報
+struct RecordOptions {
+    run_id: String,
+    started_at: SystemTime,
+    environment: Option<String>,
+    base_url: String,
+    project_dir: PathBuf,
+}
+
+/// ワークフローを実行し、イベントを中継する（中断されたら実行を捨てる）
+async fn execute(
+    execution: Arc<Execution>,
+    executor: NativeExecutor,
+    mut events: mpsc::UnboundedReceiver<RunEvent>,
+    plan: WorkflowPlan,
+    inputs: Value,
+    record: RecordOptions,
+) {
+    let started = Instant::now();
+    let run = executor.run(&plan, &inputs);
+    tokio::pin!(run);
+
+    let run = loop {
+        tokio::select! {
+            Some(event) = events.recv() => execution.publish(event),
+            run = &mut run => break Some(run),
+            _ = execution.cancel.cancelled() => break None,
+        }
+    };
+
+    match run {
+        Some(run) => {
+            // 履歴に残してから run-finished を流す（git とファイルの書き込みはブロッキングスレッドで行う）
+            let run_record = RunRecord::new(&run, record.started_at, started.elapsed());
+            let run_id = record.run_id.clone();
+            let saved = tokio::task::spawn_blocking(move || {
+                RunHistory::new(&record.project_dir).save(&RunRecord {
+                    id: record.run_id,
+                    environment: record.environment,
+                    base_url: Some(record.base_url),
+                    git_commit: git_commit(&record.project_dir),
+                    ..run_record
+                })
+            })
+            .await;
+            match saved {
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => tracing::warn!(run_id = %run_id, "Failed to record run: {}", e),
+                Err(e) => tracing::warn!(run_id = %run_id, "Run recording task failed: {}", e),
+            }
+            while let Ok(event) = events.try_recv() {
+                execution.publish(event);
+            }
+        }
+        None => {
+            // 実行中のリクエストは future と一緒に捨てられる
+            while let Ok(event) = events.try_recv() {
+                execution.publish(event);
+            }
+            execution.publish(RunEvent::RunCancelled);
+        }
+    }
+}
+
+/// 実行計画とベース URL、プロジェクトのディレクトリ
+async fn prepare_run(
+    state: &AppState,
+    project_name: &str,
+    workflow_id: &str,
+    base_url: Option<String>,
+) -> ApiResult<(WorkflowPlan, String, PathBuf)> {
+    validate_project_name(project_name)?;
+
+    let project = state
+        .projects
+        .get_project(project_name, &state.root_dir)
+        .await
+        .map_err(|e| {
+            ProblemDetails::new(
+                StatusCode::NOT_FOUND,
+                "project-not-found",
+                "Project Not Found",
+                format!("Project '{}' not found: {}", project_name, e),
+            )
+        })?;
+
+    let workflow = project
+        .arazzo_spec
+        .workflows
+        .iter()
+        .find(|w| w.workflow_id == workflow_id)
+        .ok_or_else(|| {
+            ProblemDetails::new(
+                StatusCode::NOT_FOUND,
+                "workflow-not-found",
+                "Resource Not Found",
+                format!("Workflow '{}' not found", workflow_id),
+            )
+        })?;
+
+    let plan = WorkflowPlan::new(workflow, &project.openapi_resolver).map_err(|e| {
+        ProblemDetails::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "workflow-not-runnable",
+            "Workflow Cannot Be Run",
+            e.to_string(),
+        )
+    })?;
+    let base_url = resolve_base_url(
+        &project.openapi_resolver,
+        &ConvertOptions {
+            base_url,
+            ..Default::default()
+        },
+    );
+    let project_dir = project
+        .arazzo_path
+        .parent()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| state.root_dir.clone());
+    Ok((plan, base_url, project_dir))
+}
+
+/// プロジェクトの実行（別のプロジェクトの実行 ID なら見つからない扱い）
+fn find_execution(state: &AppState, project_name: &str, run_id: &str) -> ApiResult<Arc<Execution>> {
+    validate_project_name(project_name)?;
+    state
+        .executions
+        .get(run_id)
+        .filter(|e| e.project_name == project_name)
+        .ok_or_else(|| {
+            ProblemDetails::new(
+                StatusCode::NOT_FOUND,
+                "run-not-found",
+                "Resource Not Found",
+                format!("No active run '{}'", run_id),
+            )
+            .into()
+        })
+}
+
+fn validate_project_name(project_name: &str) -> ApiResult<()> {
+    // セキュリティ: パストラバーサル攻撃を防ぐ
+    if project_name.contains("..") || project_name.contains('/') || project_name.contains('\\') {
+        return Err(ProblemDetails::new(
+            StatusCode::BAD_REQUEST,
+            "invalid-project-name",
+            "Invalid Request",
+            "Project name contains invalid characters".into(),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_subscribe_replays_and_closes() {
+        let execution = Arc::new(Execution::new("shop"));
+        execution.publish(RunEvent::StepStarted {
+            step_id: "cart".to_string(),
+        });
+
+        // 途中から購読してもそれまでのイベントを受け取る
+        let late = execution.subscribe();
+        let publisher = Arc::clone(&execution);
+        tokio::spawn(async move {
+            publisher.publish(RunEvent::StepFinished {
+                step_id: "cart".to_string(),
+                passed: true,
+                error: None,
+                failures: Vec::new(),
+            });
+            publisher.publish(RunEvent::RunCancelled);
+        });
+
+        let names: Vec<&str> = late.map(|e| e.name()).collect().await;
+        assert_eq!(
+            names,
+            vec!["step-started", "step-finished", "run-cancelled"]
+        );
+        assert!(execution.is_finished());
+    }
+
+    #[test]
+    fn test_registry_drops_old_finished_runs() {
+        let registry = ExecutionRegistry::default();
+        for i in 0..MAX_FINISHED_RUNS + 5 {
+            let execution = Arc::new(Execution::new("shop"));
+            execution.publish(RunEvent::RunCancelled);
+            registry.insert(&i.to_string(), execution);
+        }
+        let running = Arc::new(Execution::new("shop"));
+        registry.insert("running", running);
+
+        assert!(registry.get("0").is_none());
+        assert!(registry.get(&(MAX_FINISHED_RUNS + 4).to_string()).is_some());
+        assert!(registry.get("running").is_some());
+    }
+}
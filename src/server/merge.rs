@@ -0,0 +1,416 @@
+//! ワークフローの三方向マージ
+//!
+//! 編集を始めたときの版（base）、手元で編集した版（ours）、今ファイルにある版
+//! （theirs）から、重ならない編集をまとめる。オブジェクトはキーごとに、ステップは
+//! `stepId` ごとに比べ、片方だけが変えた部分はその変更を取り、両方が違うように
+//! 変えた部分を衝突として返す。
+
+use super::api::{ApiResult, ProblemDetails, blocking};
+use super::etag::{etag, etag_headers};
+use super::state::AppState;
+use crate::error::Result;
+use crate::loader;
+use crate::models::arazzo::Workflow;
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::sync::Arc;
+
+/// キーで突き合わせる配列（ステップ）の要素のキー
+const STEP_KEY: &str = "stepId";
+
+/// マージの結果
+#[derive(Debug)]
+pub enum MergeOutcome {
+    Merged(Box<Workflow>),
+    /// 両方が違うように変えた場所（`/steps/{stepId}/...` の形）
+    Conflicts(Vec<String>),
+}
+
+/// 三つの版をマージする
+pub fn merge_workflows(
+    base: &Workflow,
+    ours: &Workflow,
+    theirs: &Workflow,
+) -> Result<MergeOutcome> {
+    let base = serde_json::to_value(base)?;
+    let ours = serde_json::to_value(ours)?;
+    let theirs = serde_json::to_value(theirs)?;
+
+    let mut conflicts = Vec::new();
+    let merged = merge_value(Some(&base), Some(&ours), Some(&theirs), "", &mut conflicts);
+    if !conflicts.is_empty() {
+        return Ok(MergeOutcome::Conflicts(conflicts));
+    }
+    let merged = merged.unwrap_or(Value::Null);
+    Ok(MergeOutcome::Merged(Box::new(serde_json::from_value(
+        merged,
+    )?)))
+}
+
+/// 値をマージする（`None` はその版にキーがないこと）
+fn merge_value(
+    base: Option<&Value>,
+    ours: Option<&Value>,
+    theirs: Option<&Value>,
+    path: &str,
+    conflicts: &mut Vec<String>,
+) -> Option<Value> {
+    if ours == theirs || theirs == base {
+        return ours.cloned();
+    }
+    if ours == base {
+        return theirs.cloned();
+    }
+
+    match (ours, theirs) {
+        (Some(Value::Object(o)), Some(Value::Object(t))) => {
+            let empty = Map::new();
+            let b = match base {
+                Some(Value::Object(b)) => b,
+                _ => &empty,
+            };
+            Some(Value::Object(merge_object(b, o, t, path, conflicts)))
+        }
+        (Some(Value::Array(o)), Some(Value::Array(t))) => {
+            let b = match base {
+                Some(Value::Array(b)) => b.as_slice(),
+                _ => &[],
+            };
+            if [b, o, t]
+                .iter()
+                .all(|items| items.iter().all(|v| key_of(v).is_some()))
+            {
+                Some(Value::Array(merge_keyed(b, o, t, path, conflicts)))
+            } else {
+                conflicts.push(path.to_string());
+                theirs.cloned()
+            }
+        }
+        _ => {
+            conflicts.push(path.to_string());
+            theirs.cloned()
+        }
+    }
+}
+
+/// キーごとにマージする
+fn merge_object(
+    base: &Map<String, Value>,
+    ours: &Map<String, Value>,
+    theirs: &Map<String, Value>,
+    path: &str,
+    conflicts: &mut Vec<String>,
+) -> Map<String, Value> {
+    let keys = theirs
+        .keys()
+        .chain(ours.keys().filter(|k| !theirs.contains_key(*k)))
+        .chain(
+            base.keys()
+                .filter(|k| !theirs.contains_key(*k) && !ours.contains_key(*k)),
+        );
+
+    let mut merged = Map::new();
+    for key in keys {
+        let child = format!("{}/{}", path, key);
+        if let Some(value) = merge_value(
+            base.get(key),
+            ours.get(key),
+            theirs.get(key),
+            &child,
+            conflicts,
+        ) {
+            merged.insert(key.clone(), value);
+        }
+    }
+    merged
+}
+
+/// `stepId` ごとにマージする
+///
+/// 並びは片方だけが変えていればその並び、両方が変えていれば theirs の並びに
+/// ours で増えた要素を ours での直前の要素の後ろへ入れる。
+fn merge_keyed(
+    base: &[Value],
+    ours: &[Value],
+    theirs: &[Value],
+    path: &str,
+    conflicts: &mut Vec<String>,
+) -> Vec<Value> {
+    let ids = |items: &[Value]| -> Vec<String> {
+        items
+            .iter()
+            .filter_map(|v| key_of(v).map(str::to_string))
+            .collect()
+    };
+    let (base_ids, our_ids, their_ids) = (ids(base), ids(ours), ids(theirs));
+
+    let order = if their_ids == base_ids {
+        our_ids.clone()
+    } else {
+        let mut order = their_ids.clone();
+        for (i, id) in our_ids.iter().enumerate() {
+            if order.contains(id) {
+                continue;
+            }
+            let at = our_ids[..i]
+                .iter()
+                .rev()
+                .find_map(|prev| order.iter().position(|o| o == prev))
+                .map_or(0, |pos| pos + 1);
+            order.insert(at, id.clone());
+        }
+        order
+    };
+    // どちらかで消えた要素もマージ（消すか衝突か）の対象にする
+    let removed = base_ids.iter().filter(|id| !order.contains(id));
+
+    let find = |items: &'_ [Value], id: &str| -> Option<Value> {
+        items.iter().find(|v| key_of(v) == Some(id)).cloned()
+    };
+    let mut merged = Vec::new();
+    for id in order.iter().chain(removed) {
+        let (b, o, t) = (find(base, id), find(ours, id), find(theirs, id));
+        let child = format!("{}/{}", path, id);
+        if let Some(value) = merge_value(b.as_ref(), o.as_ref(), t.as_ref(), &child, conflicts) {
+            merged.push(value);
+        }
+    }
+    merged
+}
+
+fn key_of(value: &Value) -> Option<&str> {
+    value.get(STEP_KEY).and_then(Value::as_str)
+}
+
+/// POST /api/projects/{project_name}/workflows/{workflow_id}/merge のリクエスト
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MergeWorkflowRequest {
+    /// 編集を始めたときの版
+    pub base: Workflow,
+    /// 編集した版
+    pub workflow: Workflow,
+}
+
+/// POST /api/projects/{project_name}/workflows/{workflow_id}/merge - 編集を今の版にマージして保存
+#[tracing::instrument(
+    name = "api.merge_project_workflow",
+    skip(state, req),
+    fields(
+        project_name = %project_name,
+        workflow_id = %workflow_id
+    )
+)]
+pub async fn merge_project_workflow(
+    State(state): State<AppState>,
+    Path((project_name, workflow_id)): Path<(String, String)>,
+    Json(req): Json<MergeWorkflowRequest>,
+) -> ApiResult<(HeaderMap, Json<Workflow>)> {
+    if project_name.contains("..") || project_name.contains('/') || project_name.contains('\\') {
+        return Err(ProblemDetails::new(
+            StatusCode::BAD_REQUEST,
+            "invalid-project-name",
+            "Invalid Request",
+            "Project name contains invalid characters".into(),
+        )
+        .into());
+    }
+
+    if req.base.workflow_id != workflow_id || req.workflow.workflow_id != workflow_id {
+        return Err(ProblemDetails::new(
+            StatusCode::BAD_REQUEST,
+            "workflow-id-mismatch",
+            "Invalid Request",
+            format!(
+                "Workflow IDs in the body must match the workflow ID in path ({})",
+                workflow_id
+            ),
+        )
+        .into());
+    }
+
+    // 同じプロジェクトの書き換えは順に行う
+    let edit_lock = state.projects.edit_lock(&project_name);
+    let _editing = edit_lock.lock().await;
+
+    let project = state
+        .projects
+        .get_project(&project_name, &state.root_dir)
+        .await
+        .map_err(|e| {
+            ProblemDetails::new(
+                StatusCode::NOT_FOUND,
+                "project-not-found",
+                "Project Not Found",
+                format!("Project '{}' not found: {}", project_name, e),
+            )
+        })?;
+
+    // ファイルの読み書きと再読み込みはブロッキングスレッドで行う
+    let projects = Arc::clone(&state.projects);
+    let root_dir = state.root_dir.clone();
+    let merged = blocking(move || {
+        let mut arazzo = loader::load_arazzo(&project.arazzo_path).map_err(|e| {
+            ProblemDetails::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "load-failed",
+                "Failed to Load Resource",
+                format!("Failed to load Arazzo specification: {}", e),
+            )
+        })?;
+
+        let pos = arazzo
+            .workflows
+            .iter()
+            .position(|w| w.workflow_id == workflow_id)
+            .ok_or_else(|| {
+                ProblemDetails::new(
+                    StatusCode::NOT_FOUND,
+                    "workflow-not-found",
+                    "Resource Not Found",
+                    format!("Workflow '{}' not found", workflow_id),
+                )
+            })?;
+        let current = &arazzo.workflows[pos];
+
+        let outcome = merge_workflows(&req.base, &req.workflow, current).map_err(|e| {
+            ProblemDetails::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "merge-failed",
+                "Merge Failed",
+                format!("Failed to merge workflow: {}", e),
+            )
+        })?;
+        let merged = match outcome {
+            MergeOutcome::Merged(merged) => *merged,
+            MergeOutcome::Conflicts(conflicts) => {
+                return Err(ProblemDetails::new(
+                    StatusCode::CONFLICT,
+                    "merge-conflict",
+                    "Merge Conflict",
+                    format!("Conflicting edits at {}", conflicts.join(", ")),
+                )
+                .with_current_version(etag(current))
+                .into());
+            }
+        };
+
+        // 今の版から変わったときだけ保存する
+        if etag(&merged) != etag(current) {
+            arazzo.workflows[pos] = merged.clone();
+            loader::save_arazzo(&project.arazzo_path, &arazzo).map_err(|e| {
+                ProblemDetails::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "save-failed",
+                    "Failed to Save Resource",
+                    format!("Failed to save merged workflow: {}", e),
+                )
+            })?;
+
+            // キャッシュを再読み込み
+            projects
+                .reload_project(&project_name, &root_dir)
+                .map_err(|e| {
+                    ProblemDetails::new(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "cache-reload-failed",
+                        "Cache Reload Failed",
+                        format!("Failed to reload project cache: {}", e),
+                    )
+                })?;
+        }
+
+        Ok(merged)
+    })
+    .await?;
+
+    Ok((etag_headers(&merged), Json(merged)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn workflow(value: Value) -> Workflow {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn step(id: &str, operation: &str) -> Value {
+        json!({"stepId": id, "operationId": operation})
+    }
+
+    fn merged(outcome: MergeOutcome) -> Value {
+        match outcome {
+            MergeOutcome::Merged(workflow) => serde_json::to_value(workflow).unwrap(),
+            MergeOutcome::Conflicts(conflicts) => panic!("unexpected conflicts: {:?}", conflicts),
+        }
+    }
+
+    #[test]
+    fn test_merge_non_overlapping_edits() {
+        let base = workflow(json!({
+            "workflowId": "checkout",
+            "steps": [step("login", "login"), step("cart", "addToCart")]
+        }));
+        // ours: 概要を足し、cart を書き換え、最後に pay を足す
+        let ours = workflow(json!({
+            "workflowId": "checkout",
+            "summary": "Buy a box",
+            "steps": [step("login", "login"), step("cart", "addItem"), step("pay", "pay")]
+        }));
+        // theirs: login を消し、先頭に browse を足す
+        let theirs = workflow(json!({
+            "workflowId": "checkout",
+            "steps": [step("browse", "listItems"), step("cart", "addToCart")]
+        }));
+
+        let result = merged(merge_workflows(&base, &ours, &theirs).unwrap());
+        assert_eq!(
+            result,
+            json!({
+                "workflowId": "checkout",
+                "steps": [step("browse", "listItems"), step("cart", "addItem"), step("pay", "pay")],
+                "summary": "Buy a box"
+            })
+        );
+
+        // 片方だけが並べ替えたときはその並び
+        let reordered = workflow(json!({
+            "workflowId": "checkout",
+            "steps": [step("cart", "addToCart"), step("login", "login")]
+        }));
+        let result = merged(merge_workflows(&base, &base, &reordered).unwrap());
+        assert_eq!(result["steps"][0]["stepId"], json!("cart"));
+    }
+
+    #[test]
+    fn test_merge_conflicts() {
+        let base = workflow(json!({
+            "workflowId": "checkout",
+            "summary": "Checkout",
+            "steps": [step("login", "login"), step("cart", "addToCart")]
+        }));
+        let ours = workflow(json!({
+            "workflowId": "checkout",
+            "summary": "Buy",
+            "steps": [step("login", "signIn"), step("cart", "addToCart")]
+        }));
+        // 異常系: 同じ場所を違うように変えた、相手が消したステップを書き換えた
+        let theirs = workflow(json!({
+            "workflowId": "checkout",
+            "summary": "Purchase",
+            "steps": [step("cart", "addToCart")]
+        }));
+
+        let MergeOutcome::Conflicts(conflicts) = merge_workflows(&base, &ours, &theirs).unwrap()
+        else {
+            panic!("expected conflicts");
+        };
+        assert_eq!(conflicts, vec!["/steps/login", "/summary"]);
+    }
+}
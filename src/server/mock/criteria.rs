@@ -0,0 +1,293 @@
+//! ワークフローの successCriteria から導くモックの応答
+//!
+//! 各ステップが呼ぶ操作について、`$statusCode` の条件を満たす宣言済みの
+//! ステータスを選び、`$response.body` と `$response.header` の条件を満たす値で
+//! レスポンスを書き換える。値が実行時式の条件は実行時にしか決まらないので扱わない。
+
+use crate::converters::common::resolve_operation;
+use crate::generator::schema::OperationSchemas;
+use crate::loader::OpenApiResolver;
+use crate::models::arazzo::{ArazzoSpec, SuccessCriteria};
+use crate::models::expression::{ExpressionSource, RuntimeExpression, as_single_expression};
+use crate::payload::{set_pointer, text_value};
+use serde_json::Value;
+
+/// ステップ1つ分の期待
+#[derive(Debug, Clone, Default)]
+struct Expectation {
+    method: String,
+    path: String,
+    /// `$statusCode` の条件（演算子と値）
+    status: Vec<(&'static str, f64)>,
+    /// レスポンスヘッダーの名前と値
+    headers: Vec<(String, String)>,
+    /// ボディの JSON Pointer と値
+    body: Vec<(String, Value)>,
+}
+
+impl Expectation {
+    fn matches(&self, schemas: &OperationSchemas) -> bool {
+        self.method.eq_ignore_ascii_case(&schemas.method) && self.path == schemas.path
+    }
+
+    fn accepts(&self, status: u16) -> bool {
+        self.status
+            .iter()
+            .all(|(op, expected)| satisfies(f64::from(status), op, *expected))
+    }
+}
+
+/// 操作ごとの応答の期待（ステップの順）
+#[derive(Debug, Clone, Default)]
+pub struct Expectations {
+    entries: Vec<Expectation>,
+}
+
+impl Expectations {
+    /// Arazzo のすべてのワークフローのステップから期待を集める
+    pub fn from_arazzo(arazzo: &ArazzoSpec, resolver: &OpenApiResolver) -> Self {
+        let mut entries = Vec::new();
+        for step in arazzo.workflows.iter().flat_map(|w| &w.steps) {
+            let Some(ref criteria) = step.success_criteria else {
+                continue;
+            };
+            let Ok((path, method)) = resolve_operation(step, resolver) else {
+                continue;
+            };
+            let mut expectation = Expectation {
+                method,
+                path,
+                ..Default::default()
+            };
+            for criterion in criteria {
+                add_criterion(&mut expectation, criterion);
+            }
+            entries.push(expectation);
+        }
+        Self { entries }
+    }
+
+    /// 期待を導いたステップの数
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 最初に条件を満たせるステップの、宣言済みのステータス
+    pub fn status(&self, schemas: &OperationSchemas) -> Option<u16> {
+        let mut candidates = schemas.status_codes();
+        candidates.sort_by_key(|code| (!(200..300).contains(code), *code));
+
+        self.entries
+            .iter()
+            .filter(|e| e.matches(schemas) && !e.status.is_empty())
+            .find_map(|e| {
+                // `2XX` や `default` でしか宣言されていなければ条件の値そのものを使う
+                let exact = e
+                    .status
+                    .iter()
+                    .filter(|(op, _)| *op == "==")
+                    .map(|(_, value)| *value as u16);
+                candidates
+                    .iter()
+                    .copied()
+                    .chain(exact)
+                    .find(|code| e.accepts(*code) && schemas.response(*code).is_some())
+            })
+    }
+
+    /// ステータスを受け入れるステップの条件でボディを書き換え、返すヘッダーを集める
+    pub fn apply(
+        &self,
+        schemas: &OperationSchemas,
+        status: u16,
+        body: &mut Option<Value>,
+    ) -> Vec<(String, String)> {
+        let mut headers = Vec::new();
+        for entry in self
+            .entries
+            .iter()
+            .filter(|e| e.matches(schemas) && e.accepts(status))
+        {
+            for (pointer, value) in &entry.body {
+                let target = body.get_or_insert_with(|| Value::Object(Default::default()));
+                let _ = set_pointer(target, pointer, value.clone());
+            }
+            headers.extend(entry.headers.iter().cloned());
+        }
+        headers
+    }
+}
+
+fn add_criterion(expectation: &mut Expectation, criterion: &SuccessCriteria) {
+    if criterion
+        .criteria_type
+        .as_deref()
+        .is_some_and(|t| t != "simple")
+    {
+        return;
+    }
+    let Some(ref value) = criterion.value else {
+        return;
+    };
+    if as_single_expression(value).is_some() {
+        return;
+    }
+    let op = operator(&criterion.condition);
+
+    match RuntimeExpression::parse(&criterion.context) {
+        Some(RuntimeExpression::StatusCode) => {
+            let expected = match value {
+                Value::Number(n) => n.as_f64(),
+                Value::String(s) => s.parse().ok(),
+                _ => None,
+            };
+            if let (Some(op), Some(expected)) = (op, expected) {
+                expectation.status.push((op, expected));
+            }
+        }
+        Some(RuntimeExpression::Response(ExpressionSource::Body(path))) => {
+            if let Some(value) = op.and_then(|op| satisfying_value(op, value)) {
+                let pointer: String = path
+                    .iter()
+                    .map(|token| format!("/{}", token.replace('~', "~0").replace('/', "~1")))
+                    .collect();
+                expectation.body.push((pointer, value));
+            }
+        }
+        Some(RuntimeExpression::Response(ExpressionSource::Header(name))) if op == Some("==") => {
+            expectation.headers.push((name, text_value(value)));
+        }
+        _ => {}
+    }
+}
+
+/// 条件の演算子（`!=` は満たす値を一意に決められないので扱わない）
+fn operator(condition: &str) -> Option<&'static str> {
+    match condition {
+        "==" | "$eq" => Some("=="),
+        ">" | "$gt" => Some(">"),
+        ">=" | "$gte" => Some(">="),
+        "<" | "$lt" => Some("<"),
+        "<=" | "$lte" => Some("<="),
+        _ => None,
+    }
+}
+
+/// 条件を満たす値（大小の条件は数値のみ）
+fn satisfying_value(op: &str, expected: &Value) -> Option<Value> {
+    if op == "==" {
+        return Some(expected.clone());
+    }
+    let number = expected.as_f64()?;
+    let value = match op {
+        ">" => number + 1.0,
+        "<" => number - 1.0,
+        _ => number,
+    };
+    Some(if expected.is_i64() || expected.is_u64() {
+        Value::from(value as i64)
+    } else {
+        Value::from(value)
+    })
+}
+
+fn satisfies(actual: f64, op: &str, expected: f64) -> bool {
+    match op {
+        ">" => actual > expected,
+        ">=" => actual >= expected,
+        "<" => actual < expected,
+        "<=" => actual <= expected,
+        _ => actual == expected,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::schema::ResponseSchema;
+    use serde_json::json;
+
+    fn schemas() -> OperationSchemas {
+        OperationSchemas {
+            operation_id: Some("getPet".to_string()),
+            method: "GET".to_string(),
+            path: "/pets/{petId}".to_string(),
+            parameters: vec![],
+            request_body: None,
+            responses: ["200", "404"]
+                .iter()
+                .map(|status| ResponseSchema {
+                    status: status.to_string(),
+                    body: None,
+                })
+                .collect(),
+        }
+    }
+
+    fn expectation(criteria: Value) -> Expectation {
+        let criteria: Vec<SuccessCriteria> = serde_json::from_value(criteria).unwrap();
+        let mut expectation = Expectation {
+            method: "GET".to_string(),
+            path: "/pets/{petId}".to_string(),
+            ..Default::default()
+        };
+        for criterion in &criteria {
+            add_criterion(&mut expectation, criterion);
+        }
+        expectation
+    }
+
+    #[test]
+    fn test_status_and_body_from_criteria() {
+        let expectations = Expectations {
+            entries: vec![expectation(json!([
+                {"context": "$statusCode", "condition": "==", "value": 200},
+                {"context": "$response.body#/status", "condition": "==", "value": "available"},
+                {"context": "$response.body.age", "condition": ">", "value": 3},
+                {"context": "$response.header.X-Rate-Limit", "condition": "==", "value": 10},
+                {"context": "$response.body#/name", "condition": "==", "value": "$inputs.name"},
+                {"context": "$response.body#/tag", "condition": "!=", "value": "cat"}
+            ]))],
+        };
+        let schemas = schemas();
+        assert_eq!(expectations.status(&schemas), Some(200));
+
+        let mut body = Some(json!({"status": "pending", "name": "Rex"}));
+        let headers = expectations.apply(&schemas, 200, &mut body);
+        assert_eq!(
+            body,
+            Some(json!({"status": "available", "name": "Rex", "age": 4}))
+        );
+        assert_eq!(
+            headers,
+            vec![("X-Rate-Limit".to_string(), "10".to_string())]
+        );
+
+        // 別のステータスの応答には適用しない
+        let mut body = None;
+        assert!(expectations.apply(&schemas, 404, &mut body).is_empty());
+        assert_eq!(body, None);
+    }
+
+    #[test]
+    fn test_status_ranges() {
+        let expectations = Expectations {
+            entries: vec![expectation(json!([
+                {"context": "$statusCode", "condition": ">=", "value": 400}
+            ]))],
+        };
+        assert_eq!(expectations.status(&schemas()), Some(404));
+
+        // 宣言されたステータスで満たせなければ選ばない
+        let expectations = Expectations {
+            entries: vec![expectation(json!([
+                {"context": "$statusCode", "condition": "==", "value": 500}
+            ]))],
+        };
+        assert_eq!(expectations.status(&schemas()), None);
+    }
+}
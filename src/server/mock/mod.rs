@@ -0,0 +1,616 @@
+//! OpenAPI からのモックサーバー
+//!
+//! プロジェクトの OpenAPI に宣言されたすべての操作に応答する。リクエストは
+//! パステンプレートで照合してスキーマで検証し、宣言された例（なければスキーマ
+//! から合成した値）を返す。`Prefer: code=404` で返すレスポンスを選べる。
+//!
+//! Arazzo のワークフローを与えると、各ステップの successCriteria を満たす
+//! ステータスと値で応答する（[`criteria`]）。ステートフルモードでは CRUD 操作から
+//! 推定したリソースをメモリに保持し、POST で作成した値を GET で返し、DELETE で
+//! 取り除く（[`store`]）。
+//!
+//! 単体で起動する（`hornet2 mock`）ほか、`start_server` のルーターに
+//! `/mock/{project_name}/...` としてマウントされる。
+
+pub mod criteria;
+pub mod store;
+
+pub use criteria::Expectations;
+pub use store::{Outcome, Resource, ResourceStore, StoreRequest};
+
+use super::api::ProblemDetails;
+use super::state::AppState;
+use crate::generator::examples::media_example;
+use crate::generator::sample::{SampleDirection, SampleMode, SampleOptions, synthesize};
+use crate::generator::schema::{BodySchema, OperationSchemas, validate_instance};
+use crate::importers::{parse_query, percent_decode};
+use crate::loader::OpenApiResolver;
+use crate::models::arazzo::ArazzoSpec;
+use crate::payload::text_value;
+use axum::Router;
+use axum::body::{Body, Bytes};
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, Method, StatusCode, Uri, header};
+use axum::response::{IntoResponse, Response};
+use oas3::Spec;
+use serde_json::Value;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+/// モックが受け取ったリクエスト
+pub struct MockRequest<'a> {
+    pub method: &'a str,
+    /// マウント先を除いたパス（`/pets/1`）
+    pub path: &'a str,
+    pub query: Option<&'a str>,
+    pub headers: &'a HeaderMap,
+    pub body: &'a [u8],
+}
+
+/// モックの OpenAPI、応答の期待とリソースストア
+pub struct Mock {
+    resolver: OpenApiResolver,
+    expectations: Expectations,
+    store: Option<Mutex<ResourceStore>>,
+}
+
+impl Mock {
+    pub fn new(resolver: OpenApiResolver) -> Self {
+        Self {
+            resolver,
+            expectations: Expectations::default(),
+            store: None,
+        }
+    }
+
+    /// ワークフローの successCriteria を満たすように応答する
+    pub fn with_workflows(mut self, arazzo: &ArazzoSpec) -> Self {
+        self.expectations = Expectations::from_arazzo(arazzo, &self.resolver);
+        self
+    }
+
+    /// CRUD 操作から推定したリソースを保持するステートフルモードにする
+    pub fn stateful(mut self) -> Self {
+        self.store = Some(Mutex::new(ResourceStore::infer(&self.resolver)));
+        self
+    }
+
+    pub fn expectations(&self) -> &Expectations {
+        &self.expectations
+    }
+
+    /// ステートフルモードで保持するリソース
+    pub fn resources(&self) -> Vec<Resource> {
+        self.store
+            .as_ref()
+            .and_then(|store| store.lock().ok().map(|s| s.resources().to_vec()))
+            .unwrap_or_default()
+    }
+
+    /// リクエストに対するモックのレスポンスを作る
+    pub fn respond(&self, request: &MockRequest<'_>) -> Response {
+        handle(
+            &self.resolver,
+            Some(&self.expectations),
+            self.store.as_ref(),
+            request,
+        )
+    }
+
+    /// すべてのパスをモックが処理するルーター
+    pub fn into_router(self) -> Router {
+        Router::new()
+            .fallback(standalone)
+            .with_state(Arc::new(self))
+    }
+}
+
+/// 単体のモックサーバーのルーター（ステートレス）
+pub fn router(resolver: OpenApiResolver) -> Router {
+    Mock::new(resolver).into_router()
+}
+
+/// モックサーバーを単体で起動する
+pub async fn start_mock_server(addr: SocketAddr, mock: Mock) -> crate::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, mock.into_router())
+        .with_graceful_shutdown(super::shutdown_signal())
+        .await?;
+    Ok(())
+}
+
+async fn standalone(
+    State(mock): State<Arc<Mock>>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    mock.respond(&MockRequest {
+        method: method.as_str(),
+        path: uri.path(),
+        query: uri.query(),
+        headers: &headers,
+        body: &body,
+    })
+}
+
+/// ANY /mock/{project_name}/{*path} - プロジェクトのモック
+pub async fn project_mock(
+    State(state): State<AppState>,
+    Path((project_name, path)): Path<(String, String)>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let project = match state
+        .projects
+        .get_project(&project_name, &state.root_dir)
+        .await
+    {
+        Ok(project) => project,
+        Err(e) => {
+            return ProblemDetails::new(
+                StatusCode::NOT_FOUND,
+                "project-not-found",
+                "Project Not Found",
+                format!("Project '{}' not found: {}", project_name, e),
+            )
+            .into_response();
+        }
+    };
+
+    // プロジェクトのワークフローの successCriteria を満たすように応答する
+    let expectations = Expectations::from_arazzo(&project.arazzo_spec, &project.openapi_resolver);
+    let path = format!("/{}", path);
+    handle(
+        &project.openapi_resolver,
+        Some(&expectations),
+        None,
+        &MockRequest {
+            method: method.as_str(),
+            path: &path,
+            query: uri.query(),
+            headers: &headers,
+            body: &body,
+        },
+    )
+}
+
+/// リクエストに対するモックのレスポンスを作る（ステートレス、期待なし）
+pub fn respond(resolver: &OpenApiResolver, request: &MockRequest<'_>) -> Response {
+    handle(resolver, None, None, request)
+}
+
+fn handle(
+    resolver: &OpenApiResolver,
+    expectations: Option<&Expectations>,
+    store: Option<&Mutex<ResourceStore>>,
+    request: &MockRequest<'_>,
+) -> Response {
+    let Some(matched) = resolver.match_path(request.method, request.path) else {
+        // 別のメソッドなら一致する場合は 405
+        let allowed = [
+            "GET", "PUT", "POST", "DELETE", "PATCH", "OPTIONS", "HEAD", "TRACE",
+        ]
+        .iter()
+        .any(|m| resolver.match_path(m, request.path).is_some());
+        let (status, type_suffix, title) = if allowed {
+            (
+                StatusCode::METHOD_NOT_ALLOWED,
+                "mock-method-not-allowed",
+                "Method Not Allowed",
+            )
+        } else {
+            (
+                StatusCode::NOT_FOUND,
+                "mock-route-not-found",
+                "Route Not Found",
+            )
+        };
+        return ProblemDetails::new(
+            status,
+            type_suffix,
+            title,
+            format!(
+                "No operation matches {} {}",
+                request.method.to_uppercase(),
+                request.path
+            ),
+        )
+        .with_instance(request.path.to_string())
+        .into_response();
+    };
+
+    let Some(spec) = resolver.get_spec(&matched.operation_ref.source_name) else {
+        return ProblemDetails::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal-error",
+            "Internal Error",
+            format!(
+                "OpenAPI source '{}' is not loaded",
+                matched.operation_ref.source_name
+            ),
+        )
+        .into_response();
+    };
+    let schemas = OperationSchemas::new(
+        spec,
+        matched.operation_ref.method.clone(),
+        matched.operation_ref.path.clone(),
+        &matched.operation,
+    );
+
+    let path_params: Vec<(String, String)> = matched
+        .path_params
+        .iter()
+        .map(|(name, value)| (name.clone(), percent_decode(value)))
+        .collect();
+    let errors = validate_request(&schemas, request, &path_params);
+    if !errors.is_empty() {
+        return ProblemDetails::new(
+            StatusCode::BAD_REQUEST,
+            "mock-request-invalid",
+            "Invalid Request",
+            format!(
+                "{} {} does not match the OpenAPI document: {}",
+                schemas.method,
+                schemas.path,
+                errors.join("; ")
+            ),
+        )
+        .with_instance(request.path.to_string())
+        .into_response();
+    }
+
+    let preferred = preferred_code(request.headers);
+    let expected = expectations.and_then(|e| e.status(&schemas));
+
+    // ステートフルモードではリソースの操作をストアで処理する（Prefer の指定が優先）
+    if preferred.is_none()
+        && let Some(store) = store
+    {
+        let success = expected.filter(|code| (200..300).contains(code));
+        let declared = select_response(&schemas, success);
+        let sample = declared
+            .and_then(|(_, body)| body)
+            .and_then(|body| sample_body(body, spec));
+        let outcome = match store.lock() {
+            Ok(mut store) => store.handle(StoreRequest {
+                method: request.method,
+                path: &schemas.path,
+                path_params: &path_params,
+                body: serde_json::from_slice(request.body).ok(),
+                sample: sample.clone(),
+            }),
+            Err(_) => None,
+        };
+        if let Some(outcome) = outcome {
+            return stored_response(&schemas, spec, expectations, declared, sample, outcome);
+        }
+    }
+
+    let code = preferred.or(expected);
+    let Some((status, declared)) = select_response(&schemas, code) else {
+        return ProblemDetails::new(
+            StatusCode::BAD_REQUEST,
+            "mock-response-not-declared",
+            "Response Not Declared",
+            format!(
+                "{} {} declares no response with status {}",
+                schemas.method,
+                schemas.path,
+                code.unwrap_or_default()
+            ),
+        )
+        .with_instance(request.path.to_string())
+        .into_response();
+    };
+
+    let value = declared.and_then(|body| sample_body(body, spec));
+    build_response(&schemas, expectations, status, declared, value)
+}
+
+/// ストアでの処理結果をレスポンスにする
+fn stored_response(
+    schemas: &OperationSchemas,
+    spec: &Spec,
+    expectations: Option<&Expectations>,
+    declared: Option<(u16, Option<&BodySchema>)>,
+    sample: Option<Value>,
+    outcome: Outcome,
+) -> Response {
+    let (status, body) = declared.unwrap_or((200, None));
+    match outcome {
+        Outcome::Stored(value) => build_response(schemas, expectations, status, body, Some(value)),
+        Outcome::Listed(values) => {
+            // 一覧のスキーマがオブジェクトなら最初の配列のプロパティに入れる
+            let value = match sample {
+                Some(Value::Object(mut map)) => {
+                    match map.values_mut().find(|v| v.is_array()) {
+                        Some(items) => *items = Value::Array(values),
+                        None => {
+                            return build_response(
+                                schemas,
+                                expectations,
+                                status,
+                                body,
+                                Some(Value::Object(map)),
+                            );
+                        }
+                    }
+                    Value::Object(map)
+                }
+                _ => Value::Array(values),
+            };
+            build_response(schemas, expectations, status, body, Some(value))
+        }
+        Outcome::Deleted => {
+            let value = body.and_then(|body| sample_body(body, spec));
+            build_response(schemas, expectations, status, body, value)
+        }
+        Outcome::NotFound => match schemas.response(404) {
+            Some(not_found) => {
+                let body = not_found.body.as_ref();
+                let value = body.and_then(|body| sample_body(body, spec));
+                build_response(schemas, expectations, 404, body, value)
+            }
+            None => ProblemDetails::new(
+                StatusCode::NOT_FOUND,
+                "mock-resource-not-found",
+                "Resource Not Found",
+                format!("{} {} has no stored resource", schemas.method, schemas.path),
+            )
+            .into_response(),
+        },
+    }
+}
+
+/// パラメータとボディをスキーマで検証する
+fn validate_request(
+    schemas: &OperationSchemas,
+    request: &MockRequest<'_>,
+    path_params: &[(String, String)],
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    let query = parse_query(request.query.unwrap_or_default());
+    let cookies: Vec<(String, String)> = request
+        .headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+    for param in &schemas.parameters {
+        let values: Vec<String> = match param.location {
+            "path" => path_params
+                .iter()
+                .filter(|(name, _)| *name == param.name)
+                .map(|(_, v)| v.clone())
+                .collect(),
+            "query" => query
+                .iter()
+                .filter(|(name, _)| *name == param.name)
+                .map(|(_, v)| v.clone())
+                .collect(),
+            "header" => request
+                .headers
+                .get_all(param.name.as_str())
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .map(str::to_string)
+                .collect(),
+            _ => cookies
+                .iter()
+                .filter(|(name, _)| *name == param.name)
+                .map(|(_, v)| v.clone())
+                .collect(),
+        };
+
+        if values.is_empty() {
+            if param.required {
+                errors.push(format!(
+                    "missing required {} parameter '{}'",
+                    param.location, param.name
+                ));
+            }
+            continue;
+        }
+        let value = coerce(&values, &param.schema);
+        for error in validate_instance(&param.schema, &value) {
+            errors.push(format!(
+                "{} parameter '{}': {}",
+                param.location, param.name, error
+            ));
+        }
+    }
+
+    if let Some(ref declared) = schemas.request_body {
+        if request.body.is_empty() {
+            if declared.required {
+                errors.push("missing required request body".to_string());
+            }
+        } else if is_json(request.headers, declared)
+            && let Some(ref schema) = declared.schema
+        {
+            match serde_json::from_slice::<Value>(request.body) {
+                Ok(body) => {
+                    for error in validate_instance(schema, &body) {
+                        errors.push(format!("body {}", error));
+                    }
+                }
+                Err(e) => errors.push(format!("body is not valid JSON: {}", e)),
+            }
+        }
+    }
+
+    errors
+}
+
+/// リクエストの Content-Type（なければ宣言）が JSON か
+fn is_json(headers: &HeaderMap, declared: &BodySchema) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or(&declared.content_type)
+        .contains("json")
+}
+
+/// テキストのパラメータ値をスキーマの型に合わせる（変換できなければ文字列のまま）
+fn coerce(values: &[String], schema: &Value) -> Value {
+    let ty = schema.get("type").and_then(|t| t.as_str());
+    if ty == Some("array") {
+        let items = schema.get("items").cloned().unwrap_or(Value::Null);
+        let parts: Vec<String> = if values.len() == 1 {
+            values[0].split(',').map(str::to_string).collect()
+        } else {
+            values.to_vec()
+        };
+        return Value::Array(
+            parts
+                .iter()
+                .map(|part| coerce(std::slice::from_ref(part), &items))
+                .collect(),
+        );
+    }
+
+    let text = &values[0];
+    let parsed = match ty {
+        Some("integer") => text.parse::<i64>().ok().map(Value::from),
+        Some("number") => text.parse::<f64>().ok().map(Value::from),
+        Some("boolean") => text.parse::<bool>().ok().map(Value::from),
+        _ => None,
+    };
+    parsed.unwrap_or_else(|| Value::String(text.clone()))
+}
+
+/// `Prefer: code=404` の値
+fn preferred_code(headers: &HeaderMap) -> Option<u16> {
+    headers
+        .get_all("prefer")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split([',', ';']))
+        .filter_map(|pref| pref.trim().strip_prefix("code="))
+        .find_map(|code| code.trim_matches('"').parse().ok())
+}
+
+/// 返すステータスとボディの宣言を選ぶ
+///
+/// 指定がなければ最小の 2xx、次に `2XX`、`default`、最初の宣言の順。
+fn select_response(
+    schemas: &OperationSchemas,
+    preferred: Option<u16>,
+) -> Option<(u16, Option<&BodySchema>)> {
+    if let Some(code) = preferred {
+        return schemas.response(code).map(|r| (code, r.body.as_ref()));
+    }
+
+    if let Some(code) = schemas
+        .status_codes()
+        .into_iter()
+        .find(|c| (200..300).contains(c))
+    {
+        return schemas.response(code).map(|r| (code, r.body.as_ref()));
+    }
+    schemas
+        .responses
+        .iter()
+        .find(|r| r.status.eq_ignore_ascii_case("2XX") || r.status == "default")
+        .or(schemas.responses.first())
+        .map(|r| {
+            let code = r.status.parse().unwrap_or(200);
+            (code, r.body.as_ref())
+        })
+}
+
+/// 宣言された例、なければスキーマから合成した値
+fn sample_body(declared: &BodySchema, spec: &Spec) -> Option<Value> {
+    media_example(&declared.media, spec).or_else(|| {
+        declared.schema.as_ref().map(|schema| {
+            synthesize(
+                schema,
+                &SampleOptions {
+                    mode: SampleMode::Maximal,
+                    direction: SampleDirection::Response,
+                    ..Default::default()
+                },
+            )
+        })
+    })
+}
+
+/// 期待で書き換えた値をボディにしてレスポンスを作る
+fn build_response(
+    schemas: &OperationSchemas,
+    expectations: Option<&Expectations>,
+    status: u16,
+    declared: Option<&BodySchema>,
+    mut value: Option<Value>,
+) -> Response {
+    let headers = expectations
+        .map(|e| e.apply(schemas, status, &mut value))
+        .unwrap_or_default();
+    let status = StatusCode::from_u16(status).unwrap_or(StatusCode::OK);
+
+    let mut builder = Response::builder().status(status);
+    for (name, value) in &headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+    let body = match value {
+        Some(value) => {
+            let content_type = declared
+                .map(|d| d.content_type.as_str())
+                .unwrap_or("application/json");
+            builder = builder.header(header::CONTENT_TYPE, content_type);
+            if content_type.contains("json") {
+                Body::from(value.to_string())
+            } else {
+                Body::from(text_value(&value))
+            }
+        }
+        None => Body::empty(),
+    };
+    builder
+        .body(body)
+        .unwrap_or_else(|_| status.into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_coerce() {
+        let one = |s: &str| vec![s.to_string()];
+        assert_eq!(coerce(&one("42"), &json!({"type": "integer"})), json!(42));
+        assert_eq!(
+            coerce(&one("abc"), &json!({"type": "integer"})),
+            json!("abc")
+        );
+        assert_eq!(
+            coerce(&one("true"), &json!({"type": "boolean"})),
+            json!(true)
+        );
+        assert_eq!(
+            coerce(
+                &one("1,2"),
+                &json!({"type": "array", "items": {"type": "integer"}})
+            ),
+            json!([1, 2])
+        );
+    }
+
+    #[test]
+    fn test_preferred_code() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(preferred_code(&headers), None);
+        headers.insert("prefer", "respond-async, code=404".parse().unwrap());
+        assert_eq!(preferred_code(&headers), Some(404));
+    }
+}
@@ -0,0 +1,363 @@
+//! ステートフルモックのリソースストア
+//!
+//! `POST /pets` と `/pets/{petId}` のように、コレクションへの POST とその直下の
+//! アイテムパスを持つ操作の組をリソースとみなし、作成された値をメモリに保持する。
+//! 値はパスパラメータを埋めた具体的なコレクションパス（`/owners/1/pets`）ごとに
+//! 分けて持つので、ネストしたリソースも親ごとに独立する。
+
+use crate::generator::schema::{OperationSchemas, property_names};
+use crate::loader::OpenApiResolver;
+use indexmap::IndexMap;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// CRUD 操作から推定したリソース
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resource {
+    /// コレクションのテンプレートパス（`/pets`）
+    pub collection: String,
+    /// アイテムのテンプレートパス（`/pets/{petId}`）
+    pub item: String,
+    /// アイテムを識別するパスパラメータ
+    pub id_param: String,
+    /// ID を持つボディのプロパティ
+    pub id_field: String,
+    /// ID が数値か（パスパラメータのスキーマによる）
+    integer_id: bool,
+}
+
+/// リソースに対する操作の結果
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    /// 作成、取得、更新した値
+    Stored(Value),
+    /// コレクションの一覧
+    Listed(Vec<Value>),
+    /// 削除した
+    Deleted,
+    /// アイテムが存在しない
+    NotFound,
+}
+
+/// ストアに渡す、検証済みのリクエスト
+pub struct StoreRequest<'a> {
+    pub method: &'a str,
+    /// 照合したテンプレートパス
+    pub path: &'a str,
+    pub path_params: &'a [(String, String)],
+    /// JSON のリクエストボディ
+    pub body: Option<Value>,
+    /// 宣言された成功レスポンスの例（作成時の値の土台）
+    pub sample: Option<Value>,
+}
+
+/// 作成されたリソースを保持するストア
+#[derive(Debug, Default)]
+pub struct ResourceStore {
+    resources: Vec<Resource>,
+    /// 具体的なコレクションパスごとの ID と値
+    items: HashMap<String, IndexMap<String, Value>>,
+    next_id: u64,
+}
+
+impl ResourceStore {
+    /// OpenAPI の操作からリソースを推定する
+    pub fn infer(resolver: &OpenApiResolver) -> Self {
+        let mut templates: Vec<(String, Vec<String>)> = Vec::new();
+        for spec in resolver.get_all_specs().values() {
+            for (path, item) in spec.paths.iter().flatten() {
+                let methods = item
+                    .methods()
+                    .into_iter()
+                    .map(|(method, _)| method.as_str().to_uppercase())
+                    .collect();
+                templates.push((path.clone(), methods));
+            }
+        }
+
+        let mut resources = Vec::new();
+        for (collection, methods) in &templates {
+            let collection = collection.trim_end_matches('/');
+            if !methods.iter().any(|m| m == "POST")
+                || collection
+                    .rsplit('/')
+                    .next()
+                    .is_none_or(|last| last.is_empty() || path_param(last).is_some())
+            {
+                continue;
+            }
+            let item = templates.iter().find_map(|(path, _)| {
+                let rest = path.strip_prefix(collection)?.strip_prefix('/')?;
+                path_param(rest)
+                    .filter(|_| !rest.contains('/'))
+                    .map(|name| (path.clone(), name.to_string()))
+            });
+            let Some((item, id_param)) = item else {
+                continue;
+            };
+
+            // 作成レスポンスに ID らしいプロパティがあればそれを使う
+            let properties = OperationSchemas::by_path(resolver, "POST", collection)
+                .and_then(|schemas| {
+                    let code = schemas
+                        .status_codes()
+                        .into_iter()
+                        .find(|c| (200..300).contains(c))?;
+                    schemas.response(code)?.body.as_ref()?.schema.clone()
+                })
+                .map(|schema| property_names(&schema))
+                .unwrap_or_default();
+            let id_field = [id_param.as_str(), "id"]
+                .into_iter()
+                .find(|candidate| properties.iter().any(|p| p == candidate))
+                .unwrap_or("id")
+                .to_string();
+
+            let integer_id = ["GET", "PUT", "PATCH", "DELETE"]
+                .iter()
+                .filter_map(|method| OperationSchemas::by_path(resolver, method, &item))
+                .flat_map(|schemas| schemas.parameters)
+                .find(|p| p.location == "path" && p.name == id_param)
+                .and_then(|p| {
+                    p.schema
+                        .get("type")
+                        .and_then(|t| t.as_str())
+                        .map(|t| t == "integer" || t == "number")
+                })
+                .unwrap_or(false);
+
+            resources.push(Resource {
+                collection: collection.to_string(),
+                item,
+                id_param,
+                id_field,
+                integer_id,
+            });
+        }
+
+        Self {
+            resources,
+            ..Default::default()
+        }
+    }
+
+    /// 推定したリソース
+    pub fn resources(&self) -> &[Resource] {
+        &self.resources
+    }
+
+    /// リソースの操作なら処理する（リソースに属さない操作は `None`）
+    pub fn handle(&mut self, request: StoreRequest<'_>) -> Option<Outcome> {
+        let method = request.method.to_uppercase();
+        let path = request.path.trim_end_matches('/');
+        let (resource, on_item) = self.resources.iter().find_map(|r| {
+            if r.collection == path {
+                Some((r.clone(), false))
+            } else if r.item == path {
+                Some((r.clone(), true))
+            } else {
+                None
+            }
+        })?;
+        let key = fill(&resource.collection, request.path_params);
+
+        if !on_item {
+            return match method.as_str() {
+                "POST" => Some(Outcome::Stored(self.create(&resource, key, request))),
+                "GET" => Some(Outcome::Listed(
+                    self.items
+                        .get(&key)
+                        .map(|items| items.values().cloned().collect())
+                        .unwrap_or_default(),
+                )),
+                _ => None,
+            };
+        }
+
+        let id = request
+            .path_params
+            .iter()
+            .find(|(name, _)| *name == resource.id_param)
+            .map(|(_, value)| value.clone())?;
+        let items = self.items.entry(key).or_default();
+        let outcome = match method.as_str() {
+            "GET" => items.get(&id).cloned().map(Outcome::Stored),
+            "PUT" | "PATCH" => items.get_mut(&id).map(|stored| {
+                let mut value = if method == "PATCH" {
+                    stored.clone()
+                } else {
+                    Value::Object(Default::default())
+                };
+                if let Some(body) = request.body {
+                    merge(&mut value, body);
+                }
+                // ID は変えない
+                if let (Value::Object(map), Some(current)) =
+                    (&mut value, stored.get(&resource.id_field))
+                {
+                    map.insert(resource.id_field.clone(), current.clone());
+                }
+                *stored = value.clone();
+                Outcome::Stored(value)
+            }),
+            "DELETE" => items.shift_remove(&id).map(|_| Outcome::Deleted),
+            _ => return None,
+        };
+        Some(outcome.unwrap_or(Outcome::NotFound))
+    }
+
+    /// 例とリクエストボディから値を作り、ID を割り当てて保存する
+    fn create(&mut self, resource: &Resource, key: String, request: StoreRequest<'_>) -> Value {
+        let mut value = request
+            .sample
+            .filter(Value::is_object)
+            .unwrap_or_else(|| Value::Object(Default::default()));
+        let given = request
+            .body
+            .as_ref()
+            .and_then(|body| body.get(&resource.id_field))
+            .filter(|id| !id.is_null())
+            .cloned();
+        if let Some(body) = request.body {
+            merge(&mut value, body);
+        }
+
+        let id = given.unwrap_or_else(|| {
+            self.next_id += 1;
+            if resource.integer_id {
+                Value::from(self.next_id)
+            } else {
+                Value::String(self.next_id.to_string())
+            }
+        });
+        if let Value::Object(ref mut map) = value {
+            map.insert(resource.id_field.clone(), id.clone());
+        }
+
+        let id = match id {
+            Value::String(s) => s,
+            other => other.to_string(),
+        };
+        self.items.entry(key).or_default().insert(id, value.clone());
+        value
+    }
+}
+
+fn path_param(segment: &str) -> Option<&str> {
+    segment.strip_prefix('{')?.strip_suffix('}')
+}
+
+/// テンプレートパスにパスパラメータを埋める
+fn fill(template: &str, params: &[(String, String)]) -> String {
+    params
+        .iter()
+        .fold(template.to_string(), |path, (name, value)| {
+            path.replace(&format!("{{{}}}", name), value)
+        })
+}
+
+/// オブジェクト同士ならプロパティごとに上書きし、それ以外は置き換える
+fn merge(target: &mut Value, patch: Value) {
+    match (target, patch) {
+        (Value::Object(target), Value::Object(patch)) => {
+            for (key, value) in patch {
+                target.insert(key, value);
+            }
+        }
+        (target, patch) => *target = patch,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn store() -> ResourceStore {
+        ResourceStore {
+            resources: vec![Resource {
+                collection: "/owners/{ownerId}/pets".to_string(),
+                item: "/owners/{ownerId}/pets/{petId}".to_string(),
+                id_param: "petId".to_string(),
+                id_field: "id".to_string(),
+                integer_id: true,
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn request<'a>(
+        method: &'a str,
+        path: &'a str,
+        path_params: &'a [(String, String)],
+        body: Option<Value>,
+    ) -> StoreRequest<'a> {
+        StoreRequest {
+            method,
+            path,
+            path_params,
+            body,
+            sample: Some(json!({"id": 99, "name": "sample", "tag": "dog"})),
+        }
+    }
+
+    #[test]
+    fn test_crud_lifecycle() {
+        let mut store = store();
+        let owner = [("ownerId".to_string(), "1".to_string())];
+        let collection = "/owners/{ownerId}/pets";
+        let item = "/owners/{ownerId}/pets/{petId}";
+
+        // 例を土台にリクエストの値で上書きし、新しい ID を割り当てる
+        let created = store.handle(request(
+            "POST",
+            collection,
+            &owner,
+            Some(json!({"name": "Rex"})),
+        ));
+        assert_eq!(
+            created,
+            Some(Outcome::Stored(
+                json!({"id": 1, "name": "Rex", "tag": "dog"})
+            ))
+        );
+
+        let pet = [
+            ("ownerId".to_string(), "1".to_string()),
+            ("petId".to_string(), "1".to_string()),
+        ];
+        let fetched = store.handle(request("GET", item, &pet, None));
+        assert_eq!(fetched, created);
+
+        // 別の親のコレクションには現れない
+        let other = [("ownerId".to_string(), "2".to_string())];
+        assert_eq!(
+            store.handle(request("GET", collection, &other, None)),
+            Some(Outcome::Listed(vec![]))
+        );
+
+        let patched = store.handle(request("PATCH", item, &pet, Some(json!({"tag": "cat"}))));
+        assert_eq!(
+            patched,
+            Some(Outcome::Stored(
+                json!({"id": 1, "name": "Rex", "tag": "cat"})
+            ))
+        );
+
+        assert_eq!(
+            store.handle(request("DELETE", item, &pet, None)),
+            Some(Outcome::Deleted)
+        );
+        assert_eq!(
+            store.handle(request("GET", item, &pet, None)),
+            Some(Outcome::NotFound)
+        );
+        assert_eq!(
+            store.handle(request("GET", collection, &owner, None)),
+            Some(Outcome::Listed(vec![]))
+        );
+
+        // リソースに属さない操作は扱わない
+        assert_eq!(store.handle(request("GET", "/health", &[], None)), None);
+    }
+}
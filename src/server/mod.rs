@@ -1,6 +1,13 @@
 pub mod api;
+pub mod etag;
+pub mod execution;
 pub mod lsp;
+pub mod merge;
+pub mod mock;
+pub mod proxy;
+pub mod runs;
 pub mod state;
+pub mod watcher;
 
 use axum::{Router, http::StatusCode, routing::get};
 use std::net::SocketAddr;
@@ -22,10 +29,14 @@ pub async fn start_server(addr: SocketAddr, root_dir: PathBuf) -> crate::Result<
     // 共有状態を作成
     let state = AppState::new(root_dir)?;
 
+    // プロジェクトファイルの監視を開始
+    watcher::spawn(state.clone(), watcher::POLL_INTERVAL);
+
     // ルーターを構築
     let app = Router::new()
         // Multi-Project API
         .route("/api/projects", get(api::list_projects))
+        .route("/api/events", get(watcher::stream_project_events))
         .route("/api/projects/{project_name}", get(api::get_project))
         .route(
             "/api/projects/{project_name}/arazzo",
@@ -41,20 +52,59 @@ pub async fn start_server(addr: SocketAddr, root_dir: PathBuf) -> crate::Result<
                 .put(api::update_project_workflow)
                 .delete(api::delete_project_workflow),
         )
+        .route(
+            "/api/projects/{project_name}/workflows/{workflow_id}/merge",
+            axum::routing::post(merge::merge_project_workflow),
+        )
         .route(
             "/api/projects/{project_name}/graph/{workflow_id}",
             get(api::get_project_graph),
         )
+        // Workflow execution
+        .route(
+            "/api/projects/{project_name}/workflows/{workflow_id}/runs",
+            axum::routing::post(execution::start_workflow_run),
+        )
+        .route(
+            "/api/projects/{project_name}/runs/{run_id}/events",
+            get(execution::stream_run_events),
+        )
+        .route(
+            "/api/projects/{project_name}/runs/{run_id}/cancel",
+            axum::routing::post(execution::cancel_run),
+        )
+        // Run history
+        .route(
+            "/api/projects/{project_name}/runs",
+            get(runs::list_project_runs),
+        )
+        .route(
+            "/api/projects/{project_name}/runs/{run_id}",
+            get(runs::get_project_run),
+        )
+        .route(
+            "/api/projects/{project_name}/workflows/{workflow_id}/trends",
+            get(runs::get_workflow_trends),
+        )
         // Editor API
         .route(
             "/api/projects/{project_name}/operations",
             get(api::get_project_operations),
         )
+        .route(
+            "/api/projects/{project_name}/operations/{operation_id}/sample",
+            get(api::get_operation_sample),
+        )
         .route(
             "/api/projects/{project_name}/openapi",
             get(api::get_project_openapi),
         )
         .route("/api/validate", axum::routing::post(api::validate_arazzo))
+        // Mock server
+        .route(
+            "/mock/{project_name}/{*path}",
+            axum::routing::any(mock::project_mock),
+        )
         .route("/api/openapi.json", get(api::get_openapi_spec))
         .route("/api/arazzo.json", get(api::get_arazzo_spec))
         // LSP
@@ -219,6 +269,7 @@ cargo run -- serve --arazzo tests/fixtures/arazzo.yaml --openapi tests/fixtures/
         <h3>Multi-Project API</h3>
         <ul>
             <li><code>GET /api/projects</code> - List all projects</li>
+            <li><code>GET /api/events</code> - Stream project changes (SSE)</li>
             <li><code>GET /api/projects/{project_name}</code> - Get project details</li>
             <li><code>GET /api/projects/{project_name}/arazzo</code> - Get Arazzo specification</li>
             <li><code>PUT /api/projects/{project_name}/arazzo</code> - Update Arazzo specification</li>
@@ -227,7 +278,14 @@ cargo run -- serve --arazzo tests/fixtures/arazzo.yaml --openapi tests/fixtures/
             <li><code>GET /api/projects/{project_name}/workflows/{id}</code> - Get specific workflow</li>
             <li><code>PUT /api/projects/{project_name}/workflows/{id}</code> - Update specific workflow</li>
             <li><code>DELETE /api/projects/{project_name}/workflows/{id}</code> - Delete specific workflow</li>
+            <li><code>POST /api/projects/{project_name}/workflows/{id}/merge</code> - Merge an edit into the current workflow</li>
             <li><code>GET /api/projects/{project_name}/graph/{id}</code> - Get workflow graph visualization</li>
+            <li><code>POST /api/projects/{project_name}/workflows/{id}/runs</code> - Run a workflow</li>
+            <li><code>GET /api/projects/{project_name}/runs/{run_id}/events</code> - Stream the events of a run (SSE)</li>
+            <li><code>POST /api/projects/{project_name}/runs/{run_id}/cancel</code> - Cancel a run</li>
+            <li><code>GET /api/projects/{project_name}/runs</code> - List recorded runs</li>
+            <li><code>GET /api/projects/{project_name}/runs/{run_id}</code> - Get a recorded run</li>
+            <li><code>GET /api/projects/{project_name}/workflows/{id}/trends</code> - Get per-step trends of a workflow</li>
             <li><code>GET /api/openapi.json</code> - Get API specification (OpenAPI 3.0.3)</li>
             <li><code>GET /api/arazzo.json</code> - Get Arazzo workflow specification (Arazzo 1.0.0)</li>
         </ul>
@@ -0,0 +1,275 @@
+//! 通信を記録する HTTP プロキシ
+//!
+//! フォワードプロキシ（`http_proxy=http://127.0.0.1:8888`、絶対 URL のリクエスト）
+//! とリバースプロキシ（`--target` を前置したパス）のどちらとしても動き、転送した
+//! リクエストとレスポンスを [`RecordedExchange`] として記録順に保持する。
+//! HTTPS の CONNECT トンネルは中身を読めないので扱わない。
+
+use super::api::ProblemDetails;
+use crate::error::{HornetError, Result};
+use crate::importers::{RecordedBody, RecordedExchange, RecordedResponse, parse_body, parse_query};
+use crate::payload::BodyEncoding;
+use axum::Router;
+use axum::body::{Body, Bytes};
+use axum::extract::State;
+use axum::http::{HeaderMap, Method, StatusCode, Uri, header};
+use axum::response::{IntoResponse, Response};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// 転送しないホップバイホップのヘッダー
+const HOP_BY_HOP: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-connection",
+    "proxy-authorization",
+    "proxy-authenticate",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+    "host",
+    "content-length",
+];
+
+/// 上流へのリクエストに許す時間
+const UPSTREAM_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 通信を転送して記録するプロキシ
+pub struct RecordingProxy {
+    /// リバースプロキシの転送先（`http://localhost:8080`）
+    target: Option<String>,
+    client: reqwest::Client,
+    exchanges: Mutex<Vec<RecordedExchange>>,
+}
+
+impl RecordingProxy {
+    pub fn new(target: Option<&str>) -> Result<Self> {
+        // リダイレクトはブラウザに任せて、そのまま記録する
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .timeout(UPSTREAM_TIMEOUT)
+            .build()
+            .map_err(|e| HornetError::ValidationError(format!("Failed to build client: {e}")))?;
+        Ok(Self {
+            target: target.map(|t| t.trim_end_matches('/').to_string()),
+            client,
+            exchanges: Mutex::default(),
+        })
+    }
+
+    /// これまでに記録した通信（記録順）
+    pub fn exchanges(&self) -> Vec<RecordedExchange> {
+        self.exchanges.lock().map(|e| e.clone()).unwrap_or_default()
+    }
+
+    /// 転送先の URL（絶対 URL のリクエストはそのまま）
+    fn upstream_url(&self, uri: &Uri) -> Option<String> {
+        if uri.scheme().is_some() && uri.authority().is_some() {
+            return Some(uri.to_string());
+        }
+        let path = uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+        self.target.as_ref().map(|target| format!("{target}{path}"))
+    }
+
+    async fn forward(&self, method: Method, uri: Uri, headers: HeaderMap, body: Bytes) -> Response {
+        let Some(url) = self.upstream_url(&uri) else {
+            return ProblemDetails::new(
+                StatusCode::BAD_REQUEST,
+                "proxy-target-missing",
+                "Proxy Target Missing",
+                format!(
+                    "Request for {} has no absolute URL and no --target is configured",
+                    uri
+                ),
+            )
+            .into_response();
+        };
+
+        let forwarded: Vec<(String, String)> = headers
+            .iter()
+            .filter(|(name, _)| {
+                let name = name.as_str();
+                // 圧縮されると本文を記録できないので Accept-Encoding は送らない
+                !HOP_BY_HOP.contains(&name) && name != "accept-encoding"
+            })
+            .filter_map(|(name, value)| {
+                Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+            })
+            .collect();
+
+        let mut request = self.client.request(method.clone(), &url);
+        for (name, value) in &forwarded {
+            request = request.header(name, value);
+        }
+        if !body.is_empty() {
+            request = request.body(body.clone());
+        }
+        let upstream = match request.send().await {
+            Ok(upstream) => upstream,
+            Err(e) => {
+                return ProblemDetails::new(
+                    StatusCode::BAD_GATEWAY,
+                    "proxy-upstream-error",
+                    "Upstream Error",
+                    format!("Failed to forward {} {}: {}", method, url, e),
+                )
+                .into_response();
+            }
+        };
+
+        let status = upstream.status();
+        let response_headers: Vec<(String, String)> = upstream
+            .headers()
+            .iter()
+            .filter(|(name, _)| !HOP_BY_HOP.contains(&name.as_str()))
+            .filter_map(|(name, value)| {
+                Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+            })
+            .collect();
+        let response_body = match upstream.bytes().await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                return ProblemDetails::new(
+                    StatusCode::BAD_GATEWAY,
+                    "proxy-upstream-error",
+                    "Upstream Error",
+                    format!("Failed to read the response of {} {}: {}", method, url, e),
+                )
+                .into_response();
+            }
+        };
+
+        // JSON 以外のレスポンス（HTML、画像など）は本文を残さない
+        let response_type = content_type(&response_headers);
+        let recorded_body = std::str::from_utf8(&response_body)
+            .ok()
+            .filter(|text| !text.is_empty())
+            .filter(|_| {
+                BodyEncoding::from_content_type(response_type.as_deref()) == BodyEncoding::Json
+            })
+            .map(|text| parse_body(response_type.as_deref(), text));
+        self.record(
+            &method,
+            &url,
+            forwarded,
+            &body,
+            RecordedResponse {
+                status: status.as_u16(),
+                headers: response_headers.clone(),
+                body: recorded_body,
+            },
+        );
+
+        let mut builder = Response::builder().status(status);
+        for (name, value) in &response_headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        builder
+            .body(Body::from(response_body))
+            .unwrap_or_else(|_| StatusCode::BAD_GATEWAY.into_response())
+    }
+
+    fn record(
+        &self,
+        method: &Method,
+        url: &str,
+        headers: Vec<(String, String)>,
+        body: &[u8],
+        response: RecordedResponse,
+    ) {
+        let (path, query) = match reqwest::Url::parse(url) {
+            Ok(parsed) => (
+                parsed.path().to_string(),
+                parse_query(parsed.query().unwrap_or_default()),
+            ),
+            Err(_) => (url.to_string(), Vec::new()),
+        };
+
+        let request_type = content_type(&headers);
+        let body = std::str::from_utf8(body)
+            .ok()
+            .filter(|text| !text.is_empty())
+            .map(|text| RecordedBody {
+                payload: parse_body(request_type.as_deref(), text),
+                content_type: request_type.clone(),
+            });
+
+        let exchange = RecordedExchange {
+            name: None,
+            method: method.as_str().to_uppercase(),
+            path,
+            query,
+            headers,
+            body,
+            response: Some(response),
+            captures: Vec::new(),
+        };
+        if let Ok(mut exchanges) = self.exchanges.lock() {
+            exchanges.push(exchange);
+        }
+    }
+}
+
+fn content_type(headers: &[(String, String)]) -> Option<String> {
+    headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(header::CONTENT_TYPE.as_str()))
+        .map(|(_, value)| value.clone())
+}
+
+/// すべてのリクエストを転送して記録するルーター
+pub fn router(proxy: Arc<RecordingProxy>) -> Router {
+    Router::new().fallback(handle).with_state(proxy)
+}
+
+/// Ctrl-C を受けるまでプロキシを動かす
+pub async fn start_recording_proxy(addr: SocketAddr, proxy: Arc<RecordingProxy>) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, router(proxy))
+        .with_graceful_shutdown(super::shutdown_signal())
+        .await?;
+    Ok(())
+}
+
+async fn handle(
+    State(proxy): State<Arc<RecordingProxy>>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if method == Method::CONNECT {
+        return ProblemDetails::new(
+            StatusCode::NOT_IMPLEMENTED,
+            "proxy-connect-unsupported",
+            "CONNECT Not Supported",
+            "HTTPS tunnels cannot be recorded; use --target as a reverse proxy instead".into(),
+        )
+        .into_response();
+    }
+    proxy.forward(method, uri, headers, body).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upstream_url() {
+        let reverse = RecordingProxy::new(Some("http://localhost:8080/")).unwrap();
+        assert_eq!(
+            reverse.upstream_url(&"/pets?limit=1".parse().unwrap()),
+            Some("http://localhost:8080/pets?limit=1".to_string())
+        );
+        // 絶対 URL（フォワードプロキシ）はそのまま転送する
+        assert_eq!(
+            reverse.upstream_url(&"http://api.example.com/pets".parse().unwrap()),
+            Some("http://api.example.com/pets".to_string())
+        );
+
+        let forward = RecordingProxy::new(None).unwrap();
+        assert_eq!(forward.upstream_url(&"/pets".parse().unwrap()), None);
+    }
+}
@@ -0,0 +1,231 @@
+//! 実行履歴の API
+//!
+//! `hornet2 run --history` がプロジェクトの `.hornet/runs` に残した実行を一覧し、
+//! 個別に返し、ワークフローのステップごとの推移（レイテンシと成否、不安定な
+//! ステップ）を返す。
+
+use super::api::{ApiResult, ProblemDetails, blocking};
+use super::state::AppState;
+use crate::runner::history::{RunHistory, RunRecord, StepTrend, step_trends};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// 一覧で返す実行の既定の数
+const DEFAULT_RUN_LIMIT: usize = 50;
+
+/// 推移に使う実行の既定の数
+const DEFAULT_TREND_LIMIT: usize = 20;
+
+/// GET /api/projects/{project_name}/runs のクエリ
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunsQuery {
+    /// このワークフローの実行だけを返す
+    pub workflow_id: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// 実行の概要（一覧表示用）
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunSummary {
+    pub id: String,
+    pub started_at: u64,
+    pub duration_ms: f64,
+    pub workflow_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_commit: Option<String>,
+    pub success: bool,
+    pub passed_steps: usize,
+    pub failed_steps: usize,
+}
+
+impl From<&RunRecord> for RunSummary {
+    fn from(run: &RunRecord) -> Self {
+        let passed_steps = run.steps.iter().filter(|s| s.passed).count();
+        Self {
+            id: run.id.clone(),
+            started_at: run.started_at,
+            duration_ms: run.duration_ms,
+            workflow_id: run.workflow_id.clone(),
+            environment: run.environment.clone(),
+            git_commit: run.git_commit.clone(),
+            success: run.success,
+            passed_steps,
+            failed_steps: run.steps.len() - passed_steps,
+        }
+    }
+}
+
+/// GET /api/projects/{project_name}/runs のレスポンス
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunsResponse {
+    pub runs: Vec<RunSummary>,
+}
+
+/// GET /api/projects/{project_name}/workflows/{workflow_id}/trends のクエリ
+#[derive(Debug, Deserialize)]
+pub struct TrendsQuery {
+    /// 推移に使う直近の実行の数
+    pub limit: Option<usize>,
+}
+
+/// GET /api/projects/{project_name}/workflows/{workflow_id}/trends のレスポンス
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendsResponse {
+    pub workflow_id: String,
+    /// 推移に使った実行の数
+    pub runs: usize,
+    /// 不安定なステップの ID
+    pub flaky_steps: Vec<String>,
+    pub steps: Vec<StepTrend>,
+}
+
+/// GET /api/projects/{project_name}/runs - 実行履歴を新しい順に取得
+#[tracing::instrument(
+    name = "api.list_project_runs",
+    skip(state, query),
+    fields(project_name = %project_name)
+)]
+pub async fn list_project_runs(
+    State(state): State<AppState>,
+    Path(project_name): Path<String>,
+    Query(query): Query<RunsQuery>,
+) -> ApiResult<Json<RunsResponse>> {
+    let history = project_history(&state, &project_name).await?;
+    let workflow_id = query.workflow_id;
+    let runs = blocking(move || {
+        history
+            .list(workflow_id.as_deref())
+            .map_err(|e| history_failed(e).into())
+    })
+    .await?;
+    Ok(Json(RunsResponse {
+        runs: runs
+            .iter()
+            .take(query.limit.unwrap_or(DEFAULT_RUN_LIMIT))
+            .map(RunSummary::from)
+            .collect(),
+    }))
+}
+
+/// GET /api/projects/{project_name}/runs/{run_id} - 実行の詳細を取得
+#[tracing::instrument(
+    name = "api.get_project_run",
+    skip(state),
+    fields(project_name = %project_name, run_id = %run_id)
+)]
+pub async fn get_project_run(
+    State(state): State<AppState>,
+    Path((project_name, run_id)): Path<(String, String)>,
+) -> ApiResult<Json<RunRecord>> {
+    let history = project_history(&state, &project_name).await?;
+    let id = run_id.clone();
+    let run = blocking(move || {
+        history.get(&id).map_err(|e| {
+            ProblemDetails::new(
+                StatusCode::BAD_REQUEST,
+                "invalid-run-id",
+                "Invalid Request",
+                e.to_string(),
+            )
+            .into()
+        })
+    })
+    .await?;
+    run.map(Json).ok_or_else(|| {
+        ProblemDetails::new(
+            StatusCode::NOT_FOUND,
+            "run-not-found",
+            "Resource Not Found",
+            format!("Run '{}' not found", run_id),
+        )
+        .into()
+    })
+}
+
+/// GET /api/projects/{project_name}/workflows/{workflow_id}/trends
+/// ステップごとのレイテンシと成否の推移を取得
+#[tracing::instrument(
+    name = "api.get_workflow_trends",
+    skip(state, query),
+    fields(project_name = %project_name, workflow_id = %workflow_id)
+)]
+pub async fn get_workflow_trends(
+    State(state): State<AppState>,
+    Path((project_name, workflow_id)): Path<(String, String)>,
+    Query(query): Query<TrendsQuery>,
+) -> ApiResult<Json<TrendsResponse>> {
+    let history = project_history(&state, &project_name).await?;
+    let id = workflow_id.clone();
+    let mut runs = blocking(move || {
+        history
+            .list(Some(&id))
+            .map_err(|e| history_failed(e).into())
+    })
+    .await?;
+    runs.truncate(query.limit.unwrap_or(DEFAULT_TREND_LIMIT));
+
+    let steps = step_trends(&runs);
+    Ok(Json(TrendsResponse {
+        workflow_id,
+        runs: runs.len(),
+        flaky_steps: steps
+            .iter()
+            .filter(|s| s.flaky)
+            .map(|s| s.step_id.clone())
+            .collect(),
+        steps,
+    }))
+}
+
+/// プロジェクトの履歴
+async fn project_history(state: &AppState, project_name: &str) -> ApiResult<RunHistory> {
+    // セキュリティ: パストラバーサル攻撃を防ぐ
+    if project_name.contains("..") || project_name.contains('/') || project_name.contains('\\') {
+        return Err(ProblemDetails::new(
+            StatusCode::BAD_REQUEST,
+            "invalid-project-name",
+            "Invalid Request",
+            "Project name contains invalid characters".into(),
+        )
+        .into());
+    }
+
+    let project = state
+        .projects
+        .get_project(project_name, &state.root_dir)
+        .await
+        .map_err(|e| {
+            ProblemDetails::new(
+                StatusCode::NOT_FOUND,
+                "project-not-found",
+                "Project Not Found",
+                format!("Project '{}' not found: {}", project_name, e),
+            )
+        })?;
+
+    let project_dir = project
+        .arazzo_path
+        .parent()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| state.root_dir.clone());
+    Ok(RunHistory::new(&project_dir))
+}
+
+fn history_failed(e: crate::error::HornetError) -> ProblemDetails {
+    ProblemDetails::new(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "history-read-failed",
+        "Failed to Load Resource",
+        format!("Failed to read the run history: {}", e),
+    )
+}
@@ -1,92 +1,176 @@
+use super::execution::ExecutionRegistry;
+use super::watcher::ProjectEvents;
 use crate::error::{HornetError, Result};
 use crate::loader::{OpenApiResolver, ProjectMetadata, ProjectScanner};
 use crate::models::arazzo::ArazzoSpec;
-use std::collections::HashMap;
+use dashmap::DashMap;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, RwLock};
-use std::time::{Duration, Instant};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 /// アプリケーション全体の状態
 #[derive(Clone)]
 pub struct AppState {
-    /// プロジェクトキャッシュ（読み取り多め、更新少なめ）
-    pub projects: Arc<RwLock<ProjectCache>>,
+    /// プロジェクトキャッシュ（読み取りはロックを待たない）
+    pub projects: Arc<ProjectCache>,
 
     /// ルートディレクトリパス
     pub root_dir: PathBuf,
+
+    /// エディタから始めた実行
+    pub executions: Arc<ExecutionRegistry>,
+
+    /// プロジェクトの変化の通知先（ファイル監視が送る）
+    pub project_events: ProjectEvents,
 }
 
 impl AppState {
+    /// ファイル監視（[`super::watcher::spawn`]）で更新する前提の状態
     pub fn new(root_dir: PathBuf) -> Result<Self> {
-        let cache = ProjectCache::new(Duration::from_secs(60));
-
         Ok(Self {
-            projects: Arc::new(RwLock::new(cache)),
+            projects: Arc::new(ProjectCache::new()),
             root_dir,
+            executions: Arc::new(ExecutionRegistry::default()),
+            project_events: ProjectEvents::default(),
         })
     }
 }
 
 /// プロジェクトのキャッシュ
+///
+/// 読み取りは読み込み済みのスナップショット（`Arc<ProjectData>`）を受け取るだけで、
+/// 再読み込みを待たない。再読み込みはロックの外でファイルを読み、出来上がった
+/// スナップショットをプロジェクト単位で差し替える。同じプロジェクトの再読み込みは
+/// 順に行い（最後に読んだものが最新になる）、別のプロジェクトは互いに待たない。
+#[derive(Default)]
 pub struct ProjectCache {
     /// プロジェクト名 -> プロジェクトデータ
-    projects: HashMap<String, ProjectData>,
+    projects: DashMap<String, Arc<ProjectData>>,
+
+    /// プロジェクト名 -> 最後の読み込みエラー（前の版は projects に残す）
+    errors: DashMap<String, String>,
 
-    /// 最終スキャン時刻
-    last_scan: Option<Instant>,
+    /// プロジェクト名 -> 再読み込みを順に行うためのロック
+    reload_locks: DashMap<String, Arc<Mutex<()>>>,
 
-    /// キャッシュ有効期限
-    cache_ttl: Duration,
+    /// プロジェクト名 -> ファイルの書き換えを順に行うためのロック（保存を待つ間も持つので非同期）
+    edit_locks: DashMap<String, Arc<tokio::sync::Mutex<()>>>,
+
+    /// 最初のスキャンが終わったか
+    scanned: AtomicBool,
+
+    /// 最初のスキャンは1つだけ（ほかの参照は終わるのを待つ）
+    scanning: Mutex<()>,
 }
 
 impl ProjectCache {
-    pub fn new(cache_ttl: Duration) -> Self {
-        Self {
-            projects: HashMap::new(),
-            last_scan: None,
-            cache_ttl,
-        }
+    /// 最初の参照でスキャンし、以後は監視と書き換えの後の再読み込みで更新するキャッシュ
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    /// プロジェクトを取得（キャッシュから or ファイルシステムから再読み込み）
-    pub fn get_project(&mut self, name: &str, root_dir: &Path) -> Result<&ProjectData> {
-        // キャッシュが期限切れかチェック
-        if self.is_expired() {
-            self.refresh(root_dir)?;
-        }
+    /// プロジェクトを取得（最初の参照ならスキャンを待つ）
+    pub async fn get_project(
+        self: &Arc<Self>,
+        name: &str,
+        root_dir: &Path,
+    ) -> Result<Arc<ProjectData>> {
+        self.scan(root_dir).await?;
+
+        self.cached(name)
+            .ok_or_else(|| HornetError::ProjectNotFound(name.to_string()))
+    }
+
+    /// すべてのプロジェクトをリスト（名前順）
+    pub async fn list_projects(self: &Arc<Self>, root_dir: &Path) -> Result<Vec<Arc<ProjectData>>> {
+        self.scan(root_dir).await?;
 
-        // プロジェクトを取得
+        let mut projects: Vec<Arc<ProjectData>> = self
+            .projects
+            .iter()
+            .map(|entry| Arc::clone(entry.value()))
+            .collect();
+        projects.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(projects)
+    }
+
+    /// キャッシュにあるプロジェクト（スキャンしない）
+    pub fn cached(&self, name: &str) -> Option<Arc<ProjectData>> {
         self.projects
             .get(name)
-            .ok_or_else(|| HornetError::ProjectNotFound(name.to_string()))
+            .map(|entry| Arc::clone(entry.value()))
+    }
+
+    /// 最後の読み込みが失敗していればそのエラー
+    pub fn load_error(&self, name: &str) -> Option<String> {
+        self.errors.get(name).map(|entry| entry.value().clone())
+    }
+
+    /// プロジェクトのファイルを書き換える間に持つロック
+    ///
+    /// 読み込み、書き換え、[`Self::reload_project`] までを同じプロジェクトの
+    /// ほかの書き換えと重ならないようにする。
+    pub fn edit_lock(&self, name: &str) -> Arc<tokio::sync::Mutex<()>> {
+        Arc::clone(self.edit_locks.entry(name.to_string()).or_default().value())
     }
 
-    /// すべてのプロジェクトをリスト
-    pub fn list_projects(&mut self, root_dir: &Path) -> Result<Vec<&ProjectData>> {
-        if self.is_expired() {
-            self.refresh(root_dir)?;
+    /// 最初の参照のときにブロッキングスレッドでスキャンする
+    pub async fn scan(self: &Arc<Self>, root_dir: &Path) -> Result<()> {
+        if self.scanned.load(Ordering::Acquire) {
+            return Ok(());
+        }
+
+        let cache = Arc::clone(self);
+        let root_dir = root_dir.to_path_buf();
+        tokio::task::spawn_blocking(move || cache.ensure_scanned(&root_dir))
+            .await
+            .map_err(|e| HornetError::ValidationError(format!("Project scan failed: {}", e)))?
+    }
+
+    /// 最初の参照のときにスキャンする（ブロッキングスレッドから呼ぶ）
+    pub fn ensure_scanned(&self, root_dir: &Path) -> Result<()> {
+        if self.scanned.load(Ordering::Acquire) {
+            return Ok(());
+        }
+
+        let _scanning = self
+            .scanning
+            .lock()
+            .map_err(|_| HornetError::ValidationError("Project cache is poisoned".into()))?;
+        if self.scanned.load(Ordering::Acquire) {
+            // 待っている間に終わった
+            return Ok(());
         }
-        Ok(self.projects.values().collect())
+        self.refresh(root_dir)
     }
 
     /// キャッシュをリフレッシュ
-    fn refresh(&mut self, root_dir: &Path) -> Result<()> {
+    ///
+    /// 読み込めないプロジェクトは前の版を残し、エラーを記録する。
+    fn refresh(&self, root_dir: &Path) -> Result<()> {
         let scanner = ProjectScanner::new(root_dir);
-        let projects = scanner.scan_projects()?;
+        let mut names = Vec::new();
 
-        self.projects.clear();
-        for meta in projects {
-            let project_data = ProjectData::from_metadata(meta)?;
-            self.projects
-                .insert(project_data.name.clone(), project_data);
+        for dir in scanner.project_dirs()? {
+            let Ok(name) = scanner.project_name(&dir) else {
+                continue;
+            };
+            if let Err(e) = self.load(&scanner, &name, &dir) {
+                tracing::warn!("Failed to load project at {}: {}", dir.display(), e);
+            }
+            names.push(name);
         }
+        self.projects.retain(|name, _| names.contains(name));
+        self.errors.retain(|name, _| names.contains(name));
 
-        self.last_scan = Some(Instant::now());
+        self.scanned.store(true, Ordering::Release);
         Ok(())
     }
 
     /// プロジェクトを個別に再読み込み（ワークフロー作成/更新後）
-    pub fn reload_project(&mut self, name: &str, root_dir: &Path) -> Result<()> {
+    ///
+    /// 失敗しても前の版は残る。ファイルを読むのでブロッキングスレッドから呼ぶ。
+    pub fn reload_project(&self, name: &str, root_dir: &Path) -> Result<()> {
         let scanner = ProjectScanner::new(root_dir);
 
         // Check if this is single-project mode (root dir contains arazzo.yaml)
@@ -109,17 +193,41 @@ impl ProjectCache {
             root_dir.join(name)
         };
 
-        let meta = scanner.load_project_metadata(&project_dir)?;
-        let project_data = ProjectData::from_metadata(meta)?;
+        self.load(&scanner, name, &project_dir)
+    }
 
-        self.projects.insert(name.to_string(), project_data);
-        Ok(())
+    /// プロジェクトをキャッシュから外す（ディレクトリが消えたとき）
+    pub fn remove_project(&self, name: &str) -> bool {
+        self.errors.remove(name);
+        self.projects.remove(name).is_some()
     }
 
-    fn is_expired(&self) -> bool {
-        match self.last_scan {
-            None => true,
-            Some(last) => last.elapsed() > self.cache_ttl,
+    /// プロジェクトを読み込んで差し替える（同じプロジェクトの読み込みは順に行う）
+    fn load(&self, scanner: &ProjectScanner, name: &str, project_dir: &Path) -> Result<()> {
+        let lock = Arc::clone(
+            self.reload_locks
+                .entry(name.to_string())
+                .or_default()
+                .value(),
+        );
+        let _reloading = lock
+            .lock()
+            .map_err(|_| HornetError::ValidationError("Project cache is poisoned".into()))?;
+
+        let loaded = scanner
+            .load_project_metadata(project_dir)
+            .and_then(ProjectData::from_metadata);
+        match loaded {
+            Ok(project_data) => {
+                self.errors.remove(name);
+                self.projects
+                    .insert(name.to_string(), Arc::new(project_data));
+                Ok(())
+            }
+            Err(e) => {
+                self.errors.insert(name.to_string(), e.to_string());
+                Err(e)
+            }
         }
     }
 }
@@ -131,6 +239,8 @@ pub struct ProjectData {
     pub arazzo_path: PathBuf,
     pub arazzo_spec: ArazzoSpec,
     pub openapi_resolver: OpenApiResolver,
+    /// 読み込んだ OpenAPI ファイル（監視の対象）
+    pub openapi_paths: Vec<PathBuf>,
 }
 
 impl ProjectData {
@@ -158,6 +268,84 @@ impl ProjectData {
             arazzo_path: meta.arazzo_path,
             arazzo_spec: meta.arazzo_spec,
             openapi_resolver: resolver,
+            openapi_paths: meta.openapi_paths,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPENAPI: &str =
+        "openapi: 3.0.0\ninfo:\n  title: Shop\n  version: 1.0.0\npaths:\n  /cart: {}\n";
+
+    fn write_project(root: &Path, name: &str, workflows: &[&str]) {
+        let dir = root.join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("openapi.yaml"), OPENAPI).unwrap();
+        let mut arazzo = String::from(
+            "arazzo: 1.0.0\ninfo:\n  title: Shop\n  version: 1.0.0\nsourceDescriptions:\n  - name: shop\n    url: openapi.yaml\n    type: openapi\nworkflows:\n",
+        );
+        for id in workflows {
+            arazzo.push_str(&format!("  - workflowId: {}\n    steps: []\n", id));
+        }
+        std::fs::write(dir.join("arazzo.yaml"), arazzo).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reload_swaps_snapshot() {
+        let root = tempfile::tempdir().unwrap();
+        write_project(root.path(), "shop", &["checkout"]);
+        write_project(root.path(), "blog", &["post"]);
+        let cache = Arc::new(ProjectCache::new());
+
+        let shop = cache.get_project("shop", root.path()).await.unwrap();
+        let blog = cache.get_project("blog", root.path()).await.unwrap();
+
+        // 再読み込みしても手元のスナップショットは変わらず、新しい参照は新しい版になる
+        write_project(root.path(), "shop", &["checkout", "refund"]);
+        cache.reload_project("shop", root.path()).unwrap();
+        assert_eq!(shop.arazzo_spec.workflows.len(), 1);
+        let reloaded = cache.get_project("shop", root.path()).await.unwrap();
+        assert_eq!(reloaded.arazzo_spec.workflows.len(), 2);
+
+        // 別のプロジェクトはそのまま
+        let unchanged = cache.get_project("blog", root.path()).await.unwrap();
+        assert!(Arc::ptr_eq(&blog, &unchanged));
+
+        // 異常系: 壊れたファイルは前の版を残す
+        std::fs::write(root.path().join("shop/arazzo.yaml"), "workflows: [").unwrap();
+        assert!(cache.reload_project("shop", root.path()).is_err());
+        assert!(cache.load_error("shop").is_some());
+        let kept = cache.get_project("shop", root.path()).await.unwrap();
+        assert!(Arc::ptr_eq(&reloaded, &kept));
+    }
+
+    #[test]
+    fn test_reads_during_reloads() {
+        let root = tempfile::tempdir().unwrap();
+        write_project(root.path(), "shop", &["checkout"]);
+        let cache = ProjectCache::new();
+        cache.ensure_scanned(root.path()).unwrap();
+
+        // 再読み込みが続いていても読み取りはいつも揃った版を受け取る
+        std::thread::scope(|scope| {
+            for _ in 0..2 {
+                scope.spawn(|| {
+                    for _ in 0..10 {
+                        cache.reload_project("shop", root.path()).unwrap();
+                    }
+                });
+            }
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..50 {
+                        let project = cache.cached("shop").unwrap();
+                        assert_eq!(project.arazzo_spec.workflows.len(), 1);
+                    }
+                });
+            }
+        });
+    }
+}
@@ -0,0 +1,417 @@
+//! プロジェクトファイルの監視
+//!
+//! ルート以下のファイルシステムのイベント（`notify`）を受けたら、プロジェクト
+//! （arazzo.yaml と、読み込んだ OpenAPI ファイル）の更新時刻とサイズを前回と比べ、
+//! 変わったプロジェクトだけを読み込み直す。イベントを受け取れない環境では一定間隔で
+//! 同じ比較をする。読み込みに失敗したときは前の版をキャッシュに残したままエラーを
+//! 記録する。
+//!
+//! 変化は `GET /api/events` の SSE で接続中の UI に知らせる（project-added、
+//! project-changed、project-removed、project-error）。通知が追いつかず
+//! 取りこぼしたクライアントには resync を送るので、すべて取り直す。
+
+use super::state::{AppState, ProjectCache, ProjectData};
+use crate::loader::ProjectScanner;
+use axum::{
+    extract::State,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::stream::{self, Stream};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::{broadcast, mpsc};
+
+/// イベントを受け取れないときに調べる間隔
+pub const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 続けて届くイベント（エディタの保存など）をまとめる待ち時間
+const DEBOUNCE: Duration = Duration::from_millis(50);
+
+/// 購読者ごとに溜めておける通知の数
+const EVENT_CAPACITY: usize = 64;
+
+/// プロジェクトの変化
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ProjectEvent {
+    ProjectAdded {
+        project: String,
+    },
+    ProjectChanged {
+        project: String,
+    },
+    ProjectRemoved {
+        project: String,
+    },
+    /// 読み込みに失敗した（前の版があればそれを返し続ける）
+    #[serde(rename_all = "camelCase")]
+    ProjectError {
+        project: String,
+        message: String,
+        kept_previous: bool,
+    },
+    /// 通知を取りこぼした（すべて取り直す）
+    Resync,
+}
+
+impl ProjectEvent {
+    /// イベント名（`type` と同じ）
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProjectEvent::ProjectAdded { .. } => "project-added",
+            ProjectEvent::ProjectChanged { .. } => "project-changed",
+            ProjectEvent::ProjectRemoved { .. } => "project-removed",
+            ProjectEvent::ProjectError { .. } => "project-error",
+            ProjectEvent::Resync => "resync",
+        }
+    }
+}
+
+/// プロジェクトの変化の通知先
+#[derive(Clone)]
+pub struct ProjectEvents {
+    sender: broadcast::Sender<ProjectEvent>,
+}
+
+impl Default for ProjectEvents {
+    fn default() -> Self {
+        Self {
+            sender: broadcast::channel(EVENT_CAPACITY).0,
+        }
+    }
+}
+
+impl ProjectEvents {
+    /// 購読者がいなければ捨てる
+    pub fn send(&self, event: ProjectEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ProjectEvent> {
+        self.sender.subscribe()
+    }
+}
+
+/// 監視するファイルの更新時刻とサイズ（消えたファイルは None）
+type Fingerprint = Vec<(PathBuf, Option<(SystemTime, u64)>)>;
+
+/// ルート以下のプロジェクトの監視
+pub struct ProjectWatcher {
+    root_dir: PathBuf,
+    /// プロジェクト名 -> 前回の状態
+    fingerprints: HashMap<String, Fingerprint>,
+    started: bool,
+}
+
+impl ProjectWatcher {
+    pub fn new(root_dir: &Path) -> Self {
+        Self {
+            root_dir: root_dir.to_path_buf(),
+            fingerprints: HashMap::new(),
+            started: false,
+        }
+    }
+
+    /// 1回分の監視（変わったプロジェクトを読み込み直し、その変化を返す）
+    ///
+    /// 最初の呼び出しは今の状態を覚えるだけで、変化は返さない。
+    pub fn poll(&mut self, cache: &ProjectCache) -> Vec<ProjectEvent> {
+        let scanner = ProjectScanner::new(&self.root_dir);
+        let dirs = match scanner.project_dirs() {
+            Ok(dirs) => dirs,
+            Err(e) => {
+                tracing::warn!("Failed to scan {}: {}", self.root_dir.display(), e);
+                return Vec::new();
+            }
+        };
+        if !self.started {
+            // 最初のスキャン（読み込めないプロジェクトはエラーとして残る）
+            let _ = cache.ensure_scanned(&self.root_dir);
+        }
+
+        let mut events = Vec::new();
+        let mut seen = HashSet::new();
+        for dir in dirs {
+            let Ok(name) = scanner.project_name(&dir) else {
+                continue;
+            };
+            seen.insert(name.clone());
+
+            let current = fingerprint(&dir, cache.cached(&name).as_deref());
+            let known = self.fingerprints.contains_key(&name);
+            if !self.started || self.fingerprints.get(&name) == Some(&current) {
+                self.fingerprints.insert(name, current);
+                continue;
+            }
+
+            let event = match cache.reload_project(&name, &self.root_dir) {
+                Ok(()) if known => ProjectEvent::ProjectChanged {
+                    project: name.clone(),
+                },
+                Ok(()) => ProjectEvent::ProjectAdded {
+                    project: name.clone(),
+                },
+                Err(e) => ProjectEvent::ProjectError {
+                    project: name.clone(),
+                    message: e.to_string(),
+                    kept_previous: cache.cached(&name).is_some(),
+                },
+            };
+            // 参照する OpenAPI ファイルが変わっているかもしれないので取り直す
+            let current = fingerprint(&dir, cache.cached(&name).as_deref());
+            self.fingerprints.insert(name, current);
+            events.push(event);
+        }
+
+        let removed: Vec<String> = self
+            .fingerprints
+            .keys()
+            .filter(|name| !seen.contains(*name))
+            .cloned()
+            .collect();
+        for name in removed {
+            self.fingerprints.remove(&name);
+            cache.remove_project(&name);
+            events.push(ProjectEvent::ProjectRemoved { project: name });
+        }
+
+        self.started = true;
+        events
+    }
+}
+
+/// プロジェクトの監視対象の状態
+///
+/// 読み込めていないプロジェクトは参照先がわからないので、ディレクトリ直下の
+/// YAML と JSON をすべて見る。
+fn fingerprint(project_dir: &Path, project: Option<&ProjectData>) -> Fingerprint {
+    let mut paths = vec![project_dir.join("arazzo.yaml")];
+    match project {
+        Some(project) => paths.extend(project.openapi_paths.iter().cloned()),
+        None => {
+            if let Ok(entries) = std::fs::read_dir(project_dir) {
+                paths.extend(
+                    entries
+                        .filter_map(|entry| entry.ok())
+                        .map(|entry| entry.path())
+                        .filter(|path| {
+                            path.extension()
+                                .is_some_and(|ext| ext == "yaml" || ext == "yml" || ext == "json")
+                        }),
+                );
+            }
+        }
+    }
+    paths.sort();
+    paths.dedup();
+    paths
+        .into_iter()
+        .map(|path| {
+            let stamp = std::fs::metadata(&path)
+                .ok()
+                .and_then(|meta| Some((meta.modified().ok()?, meta.len())));
+            (path, stamp)
+        })
+        .collect()
+}
+
+/// 監視を始める（ファイルの読み込みはブロッキングスレッドで行う）
+///
+/// ファイルシステムのイベントを受け取れなければ `fallback_interval` ごとに調べる。
+pub fn spawn(state: AppState, fallback_interval: Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        // 最初の状態を覚える前に購読し、その間の変更も取りこぼさない
+        let (sender, mut changes) = mpsc::unbounded_channel();
+        let subscription = match watch_events(&state.root_dir, sender) {
+            Ok(subscription) => Some(subscription),
+            Err(e) => {
+                tracing::warn!(
+                    "File system events are unavailable, polling every {:?}: {}",
+                    fallback_interval,
+                    e
+                );
+                None
+            }
+        };
+        let mut ticker = subscription
+            .is_none()
+            .then(|| tokio::time::interval(fallback_interval));
+
+        let mut watcher = ProjectWatcher::new(&state.root_dir);
+        loop {
+            let projects = Arc::clone(&state.projects);
+            let polled = tokio::task::spawn_blocking(move || {
+                let events = watcher.poll(&projects);
+                (watcher, events)
+            })
+            .await;
+            let events = match polled {
+                Ok((returned, events)) => {
+                    watcher = returned;
+                    events
+                }
+                Err(e) => {
+                    tracing::error!("Project watcher stopped: {}", e);
+                    return;
+                }
+            };
+            for event in events {
+                tracing::info!(event = event.name(), "{:?}", event);
+                state.project_events.send(event);
+            }
+
+            match ticker {
+                Some(ref mut ticker) => {
+                    ticker.tick().await;
+                }
+                None => {
+                    if changes.recv().await.is_none() {
+                        return;
+                    }
+                    tokio::time::sleep(DEBOUNCE).await;
+                    while changes.try_recv().is_ok() {}
+                }
+            }
+        }
+    })
+}
+
+/// ルート以下の変更を `sender` に知らせる（返した監視を捨てると止まる）
+fn watch_events(
+    root_dir: &Path,
+    sender: mpsc::UnboundedSender<()>,
+) -> notify::Result<RecommendedWatcher> {
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        // 読み取りだけのイベントは無視する（エラーは取りこぼしかもしれないので調べ直す）
+        if event.is_ok_and(|event| event.kind.is_access()) {
+            return;
+        }
+        let _ = sender.send(());
+    })?;
+    watcher.watch(root_dir, RecursiveMode::Recursive)?;
+    Ok(watcher)
+}
+
+/// GET /api/events - プロジェクトの変化を SSE で購読
+#[tracing::instrument(name = "api.stream_project_events", skip(state))]
+pub async fn stream_project_events(
+    State(state): State<AppState>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let receiver = state.project_events.subscribe();
+    let events = stream::unfold(receiver, |mut receiver| async move {
+        let event = match receiver.recv().await {
+            Ok(event) => event,
+            Err(broadcast::error::RecvError::Lagged(_)) => ProjectEvent::Resync,
+            Err(broadcast::error::RecvError::Closed) => return None,
+        };
+        Some((event, receiver))
+    });
+    let events = futures::StreamExt::map(events, |event| {
+        Event::default().event(event.name()).json_data(&event)
+    });
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPENAPI: &str =
+        "openapi: 3.0.0\ninfo:\n  title: Shop\n  version: 1.0.0\npaths:\n  /cart: {}\n";
+
+    fn arazzo(workflows: &[&str]) -> String {
+        let mut arazzo = String::from(
+            "arazzo: 1.0.0\ninfo:\n  title: Shop\n  version: 1.0.0\nsourceDescriptions:\n  - name: shop\n    url: openapi.yaml\n    type: openapi\nworkflows:\n",
+        );
+        for id in workflows {
+            arazzo.push_str(&format!("  - workflowId: {}\n    steps: []\n", id));
+        }
+        arazzo
+    }
+
+    fn write_project(root: &Path, name: &str, workflows: &[&str]) {
+        let dir = root.join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("openapi.yaml"), OPENAPI).unwrap();
+        std::fs::write(dir.join("arazzo.yaml"), arazzo(workflows)).unwrap();
+    }
+
+    fn workflow_count(cache: &ProjectCache, name: &str) -> usize {
+        cache.cached(name).unwrap().arazzo_spec.workflows.len()
+    }
+
+    #[test]
+    fn test_poll_reloads_changed_projects() {
+        let root = tempfile::tempdir().unwrap();
+        write_project(root.path(), "shop", &["checkout"]);
+        write_project(root.path(), "blog", &["post"]);
+        let cache = ProjectCache::new();
+        let mut watcher = ProjectWatcher::new(root.path());
+
+        // 最初は今の状態を覚えるだけ
+        assert!(watcher.poll(&cache).is_empty());
+        assert_eq!(workflow_count(&cache, "shop"), 1);
+        assert!(watcher.poll(&cache).is_empty());
+
+        // 変わったプロジェクトだけを読み込み直す
+        std::fs::write(
+            root.path().join("shop/arazzo.yaml"),
+            arazzo(&["checkout", "refund"]),
+        )
+        .unwrap();
+        assert_eq!(
+            watcher.poll(&cache),
+            vec![ProjectEvent::ProjectChanged {
+                project: "shop".to_string()
+            }]
+        );
+        assert_eq!(workflow_count(&cache, "shop"), 2);
+
+        // 異常系: 壊れた YAML は報告し、前の版を残す
+        std::fs::write(root.path().join("shop/arazzo.yaml"), "workflows: [").unwrap();
+        let events = watcher.poll(&cache);
+        assert!(
+            matches!(
+                &events[..],
+                [ProjectEvent::ProjectError { project, kept_previous: true, .. }] if project == "shop"
+            ),
+            "{events:?}"
+        );
+        assert_eq!(workflow_count(&cache, "shop"), 2);
+        assert!(cache.load_error("shop").is_some());
+        // 直ればエラーは消える
+        std::fs::write(root.path().join("shop/arazzo.yaml"), arazzo(&["checkout"])).unwrap();
+        assert_eq!(watcher.poll(&cache).len(), 1);
+        assert!(cache.load_error("shop").is_none());
+
+        // OpenAPI ファイルの変更も拾う
+        std::fs::write(
+            root.path().join("blog/openapi.yaml"),
+            OPENAPI.replace("/cart", "/posts"),
+        )
+        .unwrap();
+        assert_eq!(
+            watcher.poll(&cache),
+            vec![ProjectEvent::ProjectChanged {
+                project: "blog".to_string()
+            }]
+        );
+
+        // プロジェクトの追加と削除
+        write_project(root.path(), "wiki", &["edit"]);
+        std::fs::remove_dir_all(root.path().join("blog")).unwrap();
+        let events = watcher.poll(&cache);
+        assert!(events.contains(&ProjectEvent::ProjectAdded {
+            project: "wiki".to_string()
+        }));
+        assert!(events.contains(&ProjectEvent::ProjectRemoved {
+            project: "blog".to_string()
+        }));
+        assert!(cache.cached("blog").is_none());
+        assert_eq!(workflow_count(&cache, "wiki"), 1);
+    }
+}
@@ -0,0 +1,208 @@
+use axum::{
+    Router,
+    body::Body,
+    http::{HeaderMap, Request, StatusCode, header},
+    routing::{get, post},
+};
+use hornet2::server::{api, merge, state::AppState};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tower::util::ServiceExt;
+
+const OPENAPI: &str = r#"
+openapi: 3.0.0
+info:
+  title: Shop API
+  version: 1.0.0
+paths:
+  /cart:
+    post:
+      operationId: addToCart
+      responses:
+        '201':
+          description: Created
+"#;
+
+const ARAZZO: &str = r#"
+arazzo: 1.0.0
+info:
+  title: Shop
+  version: 1.0.0
+sourceDescriptions:
+  - name: shop
+    url: openapi.yaml
+    type: openapi
+workflows:
+  - workflowId: checkout
+    summary: Checkout
+    steps:
+      - stepId: login
+        operationId: addToCart
+      - stepId: cart
+        operationId: addToCart
+  - workflowId: refund
+    steps:
+      - stepId: cart
+        operationId: addToCart
+"#;
+
+async fn send(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    if_match: Option<&str>,
+    body: Option<Value>,
+) -> (StatusCode, HeaderMap, Value) {
+    let mut request = Request::builder().method(method).uri(uri);
+    if let Some(etag) = if_match {
+        request = request.header(header::IF_MATCH, etag);
+    }
+    let request = match body {
+        Some(body) => request
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    };
+    let response = app.clone().oneshot(request.unwrap()).await.unwrap();
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    (status, headers, body)
+}
+
+fn etag_of(headers: &HeaderMap) -> String {
+    headers[header::ETAG].to_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_optimistic_concurrency() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_dir = temp_dir.path().join("shop");
+    std::fs::create_dir(&project_dir).unwrap();
+    std::fs::write(project_dir.join("openapi.yaml"), OPENAPI).unwrap();
+    std::fs::write(project_dir.join("arazzo.yaml"), ARAZZO).unwrap();
+
+    let state = AppState {
+        root_dir: temp_dir.path().to_path_buf(),
+        projects: Arc::new(hornet2::server::state::ProjectCache::new()),
+        executions: Default::default(),
+        project_events: Default::default(),
+    };
+    // Replicating routes from src/server/mod.rs
+    let app = Router::new()
+        .route(
+            "/api/projects/{project_name}/arazzo",
+            get(api::get_project_arazzo).put(api::update_project_arazzo),
+        )
+        .route(
+            "/api/projects/{project_name}/workflows/{workflow_id}",
+            get(api::get_project_workflow)
+                .put(api::update_project_workflow)
+                .delete(api::delete_project_workflow),
+        )
+        .route(
+            "/api/projects/{project_name}/workflows/{workflow_id}/merge",
+            post(merge::merge_project_workflow),
+        )
+        .with_state(state);
+    let checkout_uri = "/api/projects/shop/workflows/checkout";
+
+    // 1. GET は ETag を返し、同じ版を指定した更新は通って新しい版を返す
+    let (status, headers, base) = send(&app, "GET", checkout_uri, None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let base_etag = etag_of(&headers);
+    let mut first = base.clone();
+    first["summary"] = json!("Buy a box");
+    let (status, headers, _) = send(&app, "PUT", checkout_uri, Some(&base_etag), Some(first)).await;
+    assert_eq!(status, StatusCode::OK);
+    let first_etag = etag_of(&headers);
+    assert_ne!(first_etag, base_etag);
+    let (_, headers, _) = send(&app, "GET", checkout_uri, None, None).await;
+    assert_eq!(etag_of(&headers), first_etag);
+
+    // 異常系: 古い版での更新と削除は 412 で、今の版が返る
+    let mut second = base.clone();
+    second["steps"][1]["operationId"] = json!("addToCartV2");
+    let (status, headers, problem) = send(
+        &app,
+        "PUT",
+        checkout_uri,
+        Some(&base_etag),
+        Some(second.clone()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+    assert_eq!(problem["current_version"], json!(first_etag));
+    assert_eq!(etag_of(&headers), first_etag);
+    let (status, _, _) = send(&app, "DELETE", checkout_uri, Some(&base_etag), None).await;
+    assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+
+    // 2. 重ならない編集はマージされる
+    let (status, headers, merged) = send(
+        &app,
+        "POST",
+        "/api/projects/shop/workflows/checkout/merge",
+        None,
+        Some(json!({"base": base, "workflow": second})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(merged["summary"], json!("Buy a box"));
+    assert_eq!(merged["steps"][1]["operationId"], json!("addToCartV2"));
+    let merged_etag = etag_of(&headers);
+    let (_, headers, current) = send(&app, "GET", checkout_uri, None, None).await;
+    assert_eq!(current, merged);
+    assert_eq!(etag_of(&headers), merged_etag);
+
+    // 異常系: 同じ場所を違うように変えた編集は衝突になる
+    let mut third = base.clone();
+    third["summary"] = json!("Purchase");
+    let (status, _, problem) = send(
+        &app,
+        "POST",
+        "/api/projects/shop/workflows/checkout/merge",
+        None,
+        Some(json!({"base": base, "workflow": third})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert!(problem["detail"].as_str().unwrap().contains("/summary"));
+    assert_eq!(problem["current_version"], json!(merged_etag));
+
+    // 3. ドキュメント全体の更新も版を確かめる
+    let (_, headers, spec) = send(&app, "GET", "/api/projects/shop/arazzo", None, None).await;
+    let spec_etag = etag_of(&headers);
+    let (status, _, _) = send(
+        &app,
+        "PUT",
+        "/api/projects/shop/arazzo",
+        Some("\"0000000000000000\""),
+        Some(spec.clone()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+    let (status, _, _) = send(
+        &app,
+        "PUT",
+        "/api/projects/shop/arazzo",
+        Some(&spec_etag),
+        Some(spec),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // 4. If-Match のない削除はこれまでどおり通る
+    let (status, _, _) = send(
+        &app,
+        "DELETE",
+        "/api/projects/shop/workflows/refund",
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+}
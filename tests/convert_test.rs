@@ -13,6 +13,7 @@ fn convert_returns_error_for_unsupported_target() {
         vus: None,
         duration: None,
         iterations: None,
+        profile_path: None,
     };
 
     let result = execute_convert(args);
@@ -37,6 +38,7 @@ fn convert_returns_error_for_missing_workflow() {
         vus: None,
         duration: None,
         iterations: None,
+        profile_path: None,
     };
 
     let result = execute_convert(args);
@@ -5,8 +5,7 @@ use axum::{
     routing::get,
 };
 use hornet2::server::{api, state::AppState};
-use std::sync::{Arc, RwLock};
-use std::time::Duration;
+use std::sync::Arc;
 use tempfile::TempDir;
 use tower::util::ServiceExt;
 
@@ -57,9 +56,9 @@ workflows:
     // Setup state
     let state = AppState {
         root_dir: root_path.clone(),
-        projects: Arc::new(RwLock::new(hornet2::server::state::ProjectCache::new(
-            Duration::from_secs(60),
-        ))),
+        projects: Arc::new(hornet2::server::state::ProjectCache::new()),
+        executions: Default::default(),
+        project_events: Default::default(),
     };
 
     // Build app with new multi-project API structure
@@ -350,9 +349,9 @@ workflows:
     // Setup state and app
     let state = AppState {
         root_dir: root_path.clone(),
-        projects: Arc::new(RwLock::new(hornet2::server::state::ProjectCache::new(
-            Duration::from_secs(60),
-        ))),
+        projects: Arc::new(hornet2::server::state::ProjectCache::new()),
+        executions: Default::default(),
+        project_events: Default::default(),
     };
 
     let app = Router::new()
@@ -410,9 +409,9 @@ async fn test_get_project_openapi_not_found() {
 
     let state = AppState {
         root_dir: root_path,
-        projects: Arc::new(RwLock::new(hornet2::server::state::ProjectCache::new(
-            Duration::from_secs(60),
-        ))),
+        projects: Arc::new(hornet2::server::state::ProjectCache::new()),
+        executions: Default::default(),
+        project_events: Default::default(),
     };
 
     let app = Router::new()
@@ -452,9 +451,9 @@ async fn test_get_project_openapi_invalid_name() {
 
     let state = AppState {
         root_dir: root_path,
-        projects: Arc::new(RwLock::new(hornet2::server::state::ProjectCache::new(
-            Duration::from_secs(60),
-        ))),
+        projects: Arc::new(hornet2::server::state::ProjectCache::new()),
+        executions: Default::default(),
+        project_events: Default::default(),
     };
 
     let app = Router::new()
@@ -481,3 +480,106 @@ async fn test_get_project_openapi_invalid_name() {
     // Axum normalizes paths, so this results in 404 from router (no JSON body)
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
+
+#[tokio::test]
+async fn test_get_operation_sample() {
+    let temp_dir = TempDir::new().unwrap();
+    let root_path = temp_dir.path().to_path_buf();
+
+    let project_dir = root_path.join("project1");
+    std::fs::create_dir(&project_dir).unwrap();
+    let openapi_yaml = r#"
+openapi: 3.0.0
+info:
+  title: Test API
+  version: 1.0.0
+paths:
+  /items:
+    post:
+      operationId: createItem
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema:
+              type: object
+              required: [sku]
+              properties:
+                sku:
+                  type: string
+                  pattern: '^[A-Z]{2}[0-9]{4}$'
+                quantity:
+                  type: integer
+                  minimum: 1
+                  maximum: 99
+      responses:
+        '201':
+          description: Created
+"#;
+    std::fs::write(project_dir.join("openapi.yaml"), openapi_yaml).unwrap();
+    std::fs::write(
+        project_dir.join("arazzo.yaml"),
+        "arazzo: 1.0.0\ninfo:\n  title: Test\n  version: 1.0.0\nsourceDescriptions:\n  - name: api\n    url: openapi.yaml\n    type: openapi\nworkflows: []\n",
+    )
+    .unwrap();
+
+    let state = AppState {
+        root_dir: root_path,
+        projects: Arc::new(hornet2::server::state::ProjectCache::new()),
+        executions: Default::default(),
+        project_events: Default::default(),
+    };
+
+    let app = Router::new()
+        .route(
+            "/api/projects/{project_name}/operations/{operation_id}/sample",
+            get(api::get_operation_sample),
+        )
+        .with_state(state);
+
+    // Test: maximal sample contains all properties within the bounds
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/projects/project1/operations/createItem/sample?mode=maximal&seed=5")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let sample: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(sample["method"], "POST");
+    assert_eq!(sample["body"]["quantity"], 99);
+    let sku = sample["body"]["sku"].as_str().unwrap();
+    assert!(
+        regex::Regex::new("^[A-Z]{2}[0-9]{4}$")
+            .unwrap()
+            .is_match(sku),
+        "unexpected sku: {sku}"
+    );
+
+    // Test: unknown operation
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/projects/project1/operations/missing/sample")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let error: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(
+        error["type"],
+        "https://hornet2.dev/problems/operation-not-found"
+    );
+}
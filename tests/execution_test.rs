@@ -0,0 +1,284 @@
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+    routing::{get, post},
+};
+use hornet2::server::{execution, runs, state::AppState};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+use tower::util::ServiceExt;
+
+const OPENAPI: &str = r#"
+openapi: 3.0.0
+info:
+  title: Shop API
+  version: 1.0.0
+paths:
+  /cart:
+    post:
+      operationId: addToCart
+      responses:
+        '201':
+          description: Created
+  /slow:
+    get:
+      operationId: slow
+      responses:
+        '200':
+          description: OK
+"#;
+
+const ARAZZO: &str = r#"
+arazzo: 1.0.0
+info:
+  title: Shop
+  version: 1.0.0
+sourceDescriptions:
+  - name: shop
+    url: openapi.yaml
+    type: openapi
+workflows:
+  - workflowId: checkout
+    inputs:
+      type: object
+      properties:
+        sku:
+          type: string
+          default: default-sku
+    steps:
+      - stepId: cart
+        operationId: addToCart
+        requestBody:
+          contentType: application/json
+          payload:
+            sku: $inputs.sku
+        successCriteria:
+          - context: $statusCode
+            condition: "=="
+            value: 201
+          - context: $response.body#/sku
+            condition: "=="
+            value: box
+  - workflowId: wait
+    steps:
+      - stepId: slow
+        operationId: slow
+"#;
+
+/// カートに入れた SKU を返し、`/slow` はなかなか応答しない代替サーバー
+async fn start_stand_in() -> String {
+    let app = Router::new()
+        .route(
+            "/cart",
+            post(|body: axum::Json<Value>| async move {
+                (StatusCode::CREATED, axum::Json(json!({"sku": body["sku"]})))
+            }),
+        )
+        .route(
+            "/slow",
+            get(|| async {
+                tokio::time::sleep(Duration::from_secs(20)).await;
+                StatusCode::OK
+            }),
+        );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+async fn send(app: &Router, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, String) {
+    let request = Request::builder().method(method).uri(uri);
+    let request = match body {
+        Some(body) => request
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    };
+    let response = app.clone().oneshot(request.unwrap()).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+/// SSE の本文からイベント名とデータを取り出す
+fn parse_events(body: &str) -> Vec<(String, Value)> {
+    body.split("\n\n")
+        .filter_map(|chunk| {
+            let mut name = None;
+            let mut data = None;
+            for line in chunk.lines() {
+                if let Some(value) = line.strip_prefix("event: ") {
+                    name = Some(value.to_string());
+                } else if let Some(value) = line.strip_prefix("data: ") {
+                    data = Some(serde_json::from_str(value).unwrap());
+                }
+            }
+            Some((name?, data?))
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_workflow_run_api() {
+    let base_url = start_stand_in().await;
+    let temp_dir = TempDir::new().unwrap();
+    let project_dir = temp_dir.path().join("shop");
+    std::fs::create_dir(&project_dir).unwrap();
+    std::fs::write(project_dir.join("openapi.yaml"), OPENAPI).unwrap();
+    std::fs::write(project_dir.join("arazzo.yaml"), ARAZZO).unwrap();
+
+    let state = AppState {
+        root_dir: temp_dir.path().to_path_buf(),
+        projects: Arc::new(hornet2::server::state::ProjectCache::new()),
+        executions: Default::default(),
+        project_events: Default::default(),
+    };
+    // Replicating routes from src/server/mod.rs
+    let app = Router::new()
+        .route(
+            "/api/projects/{project_name}/workflows/{workflow_id}/runs",
+            post(execution::start_workflow_run),
+        )
+        .route(
+            "/api/projects/{project_name}/runs/{run_id}/events",
+            get(execution::stream_run_events),
+        )
+        .route(
+            "/api/projects/{project_name}/runs/{run_id}/cancel",
+            post(execution::cancel_run),
+        )
+        .route(
+            "/api/projects/{project_name}/runs/{run_id}",
+            get(runs::get_project_run),
+        )
+        .with_state(state);
+
+    // 1. 実行を始めると実行 ID が返る
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/api/projects/shop/workflows/checkout/runs",
+        Some(json!({"inputs": {"sku": "box"}, "environment": "editor", "baseUrl": base_url})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    let started: Value = serde_json::from_str(&body).unwrap();
+    let run_id = started["runId"].as_str().unwrap().to_string();
+    assert_eq!(
+        started["eventsUrl"],
+        json!(format!("/api/projects/shop/runs/{}/events", run_id))
+    );
+
+    // 2. イベントが順に流れ、実行が終わるとストリームが閉じる
+    let (status, body) = send(&app, "GET", started["eventsUrl"].as_str().unwrap(), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let events = parse_events(&body);
+    let names: Vec<&str> = events.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "step-started",
+            "request",
+            "response",
+            "criterion-result",
+            "criterion-result",
+            "step-finished",
+            "run-finished"
+        ]
+    );
+    assert_eq!(events[1].1["request"]["body"], json!({"sku": "box"}));
+    assert_eq!(events[2].1["response"]["status"], json!(201));
+    assert_eq!(events[3].1["criterion"], json!("$statusCode == 201"));
+    assert_eq!(events[3].1["passed"], json!(true));
+    assert_eq!(events[5].1["stepId"], json!("cart"));
+    assert_eq!(events[6].1["success"], json!(true));
+
+    // 3. 終わった実行は同じ ID で履歴に残る
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!("/api/projects/shop/runs/{}", run_id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let record: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(record["environment"], json!("editor"));
+    assert_eq!(record["steps"][0]["passed"], json!(true));
+
+    // 異常系: 終わった実行の中断
+    let cancel_uri = format!("/api/projects/shop/runs/{}/cancel", run_id);
+    let (status, _) = send(&app, "POST", &cancel_uri, None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    // 4. 応答待ちの実行を中断する
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/api/projects/shop/workflows/wait/runs",
+        Some(json!({"baseUrl": base_url})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    let started: Value = serde_json::from_str(&body).unwrap();
+    let run_id = started["runId"].as_str().unwrap();
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/api/projects/shop/runs/{}/cancel", run_id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    let (_, body) = tokio::time::timeout(
+        Duration::from_secs(5),
+        send(&app, "GET", started["eventsUrl"].as_str().unwrap(), None),
+    )
+    .await
+    .unwrap();
+    let events = parse_events(&body);
+    assert_eq!(events.last().unwrap().0, "run-cancelled");
+    // 中断した実行は履歴に残らない
+    let (status, _) = send(
+        &app,
+        "GET",
+        &format!("/api/projects/shop/runs/{}", run_id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // 異常系: 入力がオブジェクトでない、存在しないワークフロー、知らない実行、別のプロジェクト
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/projects/shop/workflows/checkout/runs",
+        Some(json!({"inputs": [1]})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/projects/shop/workflows/unknown/runs",
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(&app, "GET", "/api/projects/shop/runs/0-0000/events", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(
+        &app,
+        "GET",
+        &format!("/api/projects/other/runs/{}/events", run_id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,247 @@
+use axum::extract::Path as UrlPath;
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use hornet2::commands::{FuzzCommandArgs, execute_fuzz};
+use hornet2::fuzz::{FindingKind, FuzzOptions, Fuzzer};
+use hornet2::loader::{SourceDescriptionResolver, load_arazzo};
+use hornet2::runner::native::{NativeExecutor, NativeOptions};
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const OPENAPI: &str = r#"
+openapi: 3.0.3
+info:
+  title: Items
+  version: 1.0.0
+paths:
+  /items:
+    post:
+      operationId: createItem
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema:
+              type: object
+              required: [name]
+              properties:
+                name:
+                  type: string
+                  minLength: 1
+                  maxLength: 20
+                qty:
+                  type: integer
+                  minimum: 0
+                  maximum: 100
+      responses:
+        '201':
+          description: Created
+          content:
+            application/json:
+              schema:
+                type: object
+                required: [id]
+                properties:
+                  id:
+                    type: integer
+        '400':
+          description: Bad request
+  /items/{id}:
+    get:
+      operationId: getItem
+      parameters:
+        - name: id
+          in: path
+          required: true
+          schema:
+            type: integer
+      responses:
+        '200':
+          description: Item
+          content:
+            application/json:
+              schema:
+                type: object
+                required: [id]
+                properties:
+                  id:
+                    type: integer
+        '404':
+          description: Not found
+"#;
+
+const ARAZZO: &str = r#"
+arazzo: 1.0.0
+info:
+  title: Items
+  version: 1.0.0
+sourceDescriptions:
+  - name: items
+    url: ./openapi.yaml
+    type: openapi
+workflows:
+  - workflowId: item-lifecycle
+    steps:
+      - stepId: create
+        operationId: createItem
+        requestBody:
+          contentType: application/json
+          payload:
+            name: box
+            qty: 1
+        successCriteria:
+          - context: $statusCode
+            condition: "=="
+            value: 201
+        outputs:
+          id: $response.body#/id
+      - stepId: get
+        operationId: getItem
+        parameters:
+          - name: id
+            in: path
+            value: $steps.create.outputs.id
+        successCriteria:
+          - context: $statusCode
+            condition: "=="
+            value: 200
+"#;
+
+/// 不具合を仕込んだ代替サーバーを起動し、ベースURLを返す
+async fn start_stand_in() -> String {
+    let app = Router::new()
+        .route(
+            "/items",
+            post(|Json(body): Json<Value>| async move {
+                // qty が大きいと落ちる
+                if body["qty"].as_i64().unwrap_or(0) > 50 {
+                    return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({})));
+                }
+                (StatusCode::CREATED, Json(json!({"id": 7})))
+            }),
+        )
+        .route(
+            "/items/{id}",
+            // id を文字列で返してしまう
+            get(|UrlPath(id): UrlPath<String>| async move { Json(json!({"id": id})) }),
+        );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+fn write_project(dir: &Path) -> PathBuf {
+    std::fs::write(dir.join("openapi.yaml"), OPENAPI).unwrap();
+    let arazzo = dir.join("arazzo.yaml");
+    std::fs::write(&arazzo, ARAZZO).unwrap();
+    arazzo
+}
+
+#[tokio::test]
+async fn fuzz_finds_and_shrinks_failures() {
+    // 正常系: 5xx とスキーマ違反を検出し、5xx のリクエストを最小化することを確認する
+    let base_url = start_stand_in().await;
+    let dir = tempfile::tempdir().unwrap();
+    let arazzo_path = write_project(dir.path());
+
+    let arazzo = load_arazzo(&arazzo_path).unwrap();
+    let resolver = SourceDescriptionResolver::new(&arazzo_path)
+        .unwrap()
+        .load_sources(&arazzo.source_descriptions)
+        .resolver;
+    let executor = NativeExecutor::new(NativeOptions {
+        base_url,
+        timeout: Duration::from_secs(5),
+        base_dir: dir.path().to_path_buf(),
+    })
+    .unwrap();
+    let fuzzer = Fuzzer::new(
+        &executor,
+        &resolver,
+        FuzzOptions {
+            variants: 8,
+            ..Default::default()
+        },
+    );
+
+    let reports = fuzzer.fuzz_workflow(&arazzo.workflows[0]).await.unwrap();
+    assert_eq!(reports.len(), 2);
+
+    let create = &reports[0];
+    assert_eq!(create.variants, 8);
+    assert_eq!(create.findings.len(), 1);
+    let finding = &create.findings[0];
+    assert_eq!(finding.kind, FindingKind::ServerError);
+    assert_eq!(finding.status, Some(500));
+    assert!(finding.shrink_steps > 0);
+
+    // 最小化後も qty は 50 を超え、name は短くなっている
+    let reproducer = &finding.reproducer;
+    assert_eq!(reproducer.steps.len(), 1);
+    let payload = &reproducer.steps[0].request_body.as_ref().unwrap().payload;
+    assert!(payload["qty"].as_i64().unwrap() > 50);
+    assert!(payload["name"].as_str().unwrap().len() < 20);
+    assert_eq!(
+        reproducer.steps[0].success_criteria.as_ref().unwrap()[0].value,
+        Some(json!(500))
+    );
+
+    // 前提ステップ（create）の出力を使って get を検査する
+    let get = &reports[1];
+    assert!(get.skipped.is_none());
+    assert_eq!(get.findings.len(), 1);
+    assert_eq!(get.findings[0].kind, FindingKind::SchemaViolation);
+    assert!(get.findings[0].message.contains("#/id"));
+    let steps: Vec<&str> = get.findings[0]
+        .reproducer
+        .steps
+        .iter()
+        .map(|s| s.step_id.as_str())
+        .collect();
+    assert_eq!(steps, vec!["create", "get"]);
+    assert_eq!(
+        get.findings[0].reproducer.steps[1].parameters[0].value,
+        json!("$steps.create.outputs.id")
+    );
+}
+
+#[tokio::test]
+async fn fuzz_command_writes_reproducers() {
+    // 異常系: 検出があればエラーを返し、再現用の Arazzo ファイルを書き出すことを確認する
+    let base_url = start_stand_in().await;
+    let dir = tempfile::tempdir().unwrap();
+    let arazzo_path = write_project(dir.path());
+    let output = dir.path().join("findings.arazzo.yaml");
+
+    let result = execute_fuzz(FuzzCommandArgs {
+        arazzo_path: &arazzo_path,
+        workflow_id: Some("item-lifecycle"),
+        base_url: Some(&base_url),
+        variants: 4,
+        seed: 1,
+        timeout: Duration::from_secs(5),
+        output_path: &output,
+    })
+    .await;
+    assert!(result.is_err());
+
+    let findings = load_arazzo(&output).unwrap();
+    assert_eq!(findings.source_descriptions[0].url, "./openapi.yaml");
+    let ids: Vec<&str> = findings
+        .workflows
+        .iter()
+        .map(|w| w.workflow_id.as_str())
+        .collect();
+    assert_eq!(
+        ids,
+        vec![
+            "fuzz-item-lifecycle-create-server-error-500",
+            "fuzz-item-lifecycle-get-schema-violation-200"
+        ]
+    );
+    assert!(findings.workflows[0].extensions.contains_key("x-fuzz"));
+}
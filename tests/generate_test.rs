@@ -0,0 +1,63 @@
+use hornet2::commands::{GenerateCommandArgs, execute_generate};
+use hornet2::loader::load_arazzo;
+use std::path::PathBuf;
+
+#[test]
+fn generate_writes_and_merges_workflows() {
+    // 正常系: 新規ファイルを生成し、再実行では既存のワークフローを保持することを確認する
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("arazzo.yaml");
+    let openapi = vec![PathBuf::from("tests/fixtures/generate/openapi.yaml")];
+
+    execute_generate(GenerateCommandArgs {
+        openapi_paths: &openapi,
+        output_path: &output,
+        overwrite: false,
+        dry_run: false,
+    })
+    .unwrap();
+
+    let mut spec = load_arazzo(&output).unwrap();
+    assert_eq!(spec.source_descriptions.len(), 1);
+    let ids: Vec<&str> = spec
+        .workflows
+        .iter()
+        .map(|w| w.workflow_id.as_str())
+        .collect();
+    assert_eq!(ids, vec!["owners-pets-crud", "pets-crud", "login-links"]);
+
+    // 手で編集したワークフローは再生成で上書きされない
+    spec.workflows[1].summary = Some("edited".to_string());
+    hornet2::loader::save_arazzo(&output, &spec).unwrap();
+
+    execute_generate(GenerateCommandArgs {
+        openapi_paths: &[],
+        output_path: &output,
+        overwrite: false,
+        dry_run: false,
+    })
+    .unwrap();
+
+    let spec = load_arazzo(&output).unwrap();
+    assert_eq!(spec.workflows.len(), 3);
+    assert_eq!(spec.workflows[1].summary.as_deref(), Some("edited"));
+}
+
+#[test]
+fn generate_requires_openapi_for_new_file() {
+    // 異常系: 出力先が存在せず --openapi もない場合はエラーになることを確認する
+    let dir = tempfile::tempdir().unwrap();
+
+    let result = execute_generate(GenerateCommandArgs {
+        openapi_paths: &[],
+        output_path: &dir.path().join("arazzo.yaml"),
+        overwrite: false,
+        dry_run: false,
+    });
+
+    let message = format!("{}", result.unwrap_err());
+    assert!(
+        message.contains("--openapi"),
+        "unexpected message: {message}"
+    );
+}
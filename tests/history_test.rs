@@ -0,0 +1,156 @@
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+    routing::get,
+};
+use hornet2::runner::history::{RunHistory, RunRecord};
+use hornet2::server::{runs, state::AppState};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tower::util::ServiceExt;
+
+const OPENAPI: &str = r#"
+openapi: 3.0.0
+info:
+  title: Shop API
+  version: 1.0.0
+paths:
+  /cart:
+    post:
+      operationId: addToCart
+      responses:
+        '201':
+          description: Created
+"#;
+
+const ARAZZO: &str = r#"
+arazzo: 1.0.0
+info:
+  title: Shop
+  version: 1.0.0
+sourceDescriptions:
+  - name: shop
+    url: openapi.yaml
+    type: openapi
+workflows:
+  - workflowId: checkout
+    steps:
+      - stepId: cart
+        operationId: addToCart
+"#;
+
+/// `pay` の成否が交互に変わる実行
+fn record(started_at: u64, pay_passed: bool) -> RunRecord {
+    serde_json::from_value(json!({
+        "id": format!("{}-0000", started_at),
+        "startedAt": started_at,
+        "durationMs": 12.5,
+        "workflowId": "checkout",
+        "environment": "staging",
+        "gitCommit": "0123abc",
+        "success": pay_passed,
+        "steps": [
+            { "stepId": "cart", "passed": true, "latencyMs": 4.0, "statusCode": 201 },
+            { "stepId": "pay", "passed": pay_passed, "latencyMs": started_at as f64 / 100.0 }
+        ],
+        "metrics": { "http_reqs": 2, "iterations": 1, "avg_response_time_ms": 5.0,
+                     "checks_failed": 0, "checks_passed": 2, "vus": 1, "duration": "0.013s" }
+    }))
+    .unwrap()
+}
+
+async fn get_json(app: &Router, uri: &str) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn test_run_history_api() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_dir = temp_dir.path().join("shop");
+    std::fs::create_dir(&project_dir).unwrap();
+    std::fs::write(project_dir.join("openapi.yaml"), OPENAPI).unwrap();
+    std::fs::write(project_dir.join("arazzo.yaml"), ARAZZO).unwrap();
+
+    let history = RunHistory::new(&project_dir);
+    for (started_at, pay_passed) in [(1000, true), (2000, false), (3000, true), (4000, true)] {
+        history.save(&record(started_at, pay_passed)).unwrap();
+    }
+
+    let state = AppState {
+        root_dir: temp_dir.path().to_path_buf(),
+        projects: Arc::new(hornet2::server::state::ProjectCache::new()),
+        executions: Default::default(),
+        project_events: Default::default(),
+    };
+    // Replicating routes from src/server/mod.rs
+    let app = Router::new()
+        .route(
+            "/api/projects/{project_name}/runs",
+            get(runs::list_project_runs),
+        )
+        .route(
+            "/api/projects/{project_name}/runs/{run_id}",
+            get(runs::get_project_run),
+        )
+        .route(
+            "/api/projects/{project_name}/workflows/{workflow_id}/trends",
+            get(runs::get_workflow_trends),
+        )
+        .with_state(state);
+
+    // 1. 新しい順の一覧
+    let (status, body) = get_json(&app, "/api/projects/shop/runs?limit=3").await;
+    assert_eq!(status, StatusCode::OK);
+    let runs = body["runs"].as_array().unwrap();
+    assert_eq!(runs.len(), 3);
+    assert_eq!(runs[0]["id"], json!("4000-0000"));
+    assert_eq!(runs[2]["id"], json!("2000-0000"));
+    assert_eq!(runs[2]["failedSteps"], json!(1));
+    assert_eq!(runs[0]["environment"], json!("staging"));
+    assert_eq!(runs[0]["gitCommit"], json!("0123abc"));
+
+    // 2. 実行の詳細
+    let (status, body) = get_json(&app, "/api/projects/shop/runs/2000-0000").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["steps"][1]["stepId"], json!("pay"));
+    assert_eq!(body["steps"][1]["passed"], json!(false));
+
+    // 3. ステップごとの推移（古い順）と不安定なステップ
+    let (status, body) = get_json(&app, "/api/projects/shop/workflows/checkout/trends").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["runs"], json!(4));
+    assert_eq!(body["flakySteps"], json!(["pay"]));
+    let pay = &body["steps"][1];
+    assert_eq!(pay["passRate"], json!(0.75));
+    assert_eq!(pay["flips"], json!(2));
+    let latencies: Vec<f64> = pay["points"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["latencyMs"].as_f64().unwrap())
+        .collect();
+    assert_eq!(latencies, vec![10.0, 20.0, 30.0, 40.0]);
+
+    // 直近2回だけなら安定している
+    let (_, body) = get_json(&app, "/api/projects/shop/workflows/checkout/trends?limit=2").await;
+    assert_eq!(body["flakySteps"], json!([]));
+
+    // 異常系: 記録のない実行、不正な ID、存在しないプロジェクト
+    let (status, _) = get_json(&app, "/api/projects/shop/runs/9999-0000").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = get_json(&app, "/api/projects/shop/runs/..%2Farazzo").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = get_json(&app, "/api/projects/unknown/runs").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
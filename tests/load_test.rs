@@ -0,0 +1,528 @@
+use axum::extract::Path as UrlPath;
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use hornet2::commands::{BaselineArgs, LoadCommandArgs, execute_load};
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+
+const OPENAPI: &str = r#"
+openapi: 3.0.3
+info:
+  title: Items
+  version: 1.0.0
+paths:
+  /items:
+    post:
+      operationId: createItem
+      responses:
+        '201':
+          description: Created
+  /items/{id}:
+    get:
+      operationId: getItem
+      parameters:
+        - name: id
+          in: path
+          required: true
+          schema:
+            type: integer
+      responses:
+        '200':
+          description: OK
+        '404':
+          description: Not found
+"#;
+
+const ARAZZO: &str = r#"
+arazzo: 1.0.0
+info:
+  title: Items
+  version: 1.0.0
+sourceDescriptions:
+  - name: items
+    url: ./openapi.yaml
+    type: openapi
+workflows:
+  - workflowId: create-and-get
+    steps:
+      - stepId: create
+        operationId: createItem
+        requestBody:
+          contentType: application/json
+          payload:
+            name: box
+        successCriteria:
+          - context: $statusCode
+            condition: "=="
+            value: 201
+        outputs:
+          id: $response.body#/id
+      - stepId: get
+        operationId: getItem
+        parameters:
+          - name: id
+            in: path
+            value: $steps.create.outputs.id
+        successCriteria:
+          - context: $statusCode
+            condition: "=="
+            value: 200
+  - workflowId: create-only
+    steps:
+      - stepId: create
+        operationId: createItem
+        requestBody:
+          contentType: application/json
+          payload:
+            name: box
+"#;
+
+const PROFILE: &str = r#"
+workflows:
+  - workflowId: create-and-get
+    weight: 3
+  - workflowId: create-only
+    weight: 1
+    thinkTime: { distribution: constant, value: 1ms }
+"#;
+
+/// 奇数の ID だけを返す代替サーバー
+async fn start_stand_in() -> String {
+    let next = std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0));
+    let app = Router::new()
+        .route(
+            "/items",
+            post(move || {
+                let id = next.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
+                async move { (StatusCode::CREATED, Json(json!({"id": id}))) }
+            }),
+        )
+        .route(
+            "/items/{id}",
+            get(|UrlPath(id): UrlPath<u64>| async move {
+                if id % 2 == 1 {
+                    StatusCode::OK
+                } else {
+                    StatusCode::NOT_FOUND
+                }
+            }),
+        );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+fn write_project(dir: &Path) -> PathBuf {
+    std::fs::write(dir.join("openapi.yaml"), OPENAPI).unwrap();
+    let arazzo = dir.join("arazzo.yaml");
+    std::fs::write(&arazzo, ARAZZO).unwrap();
+    arazzo
+}
+
+fn args<'a>(arazzo_path: &'a Path, base_url: &'a str, output: &'a Path) -> LoadCommandArgs<'a> {
+    LoadCommandArgs {
+        arazzo_path,
+        workflow_id: None,
+        base_url: Some(base_url),
+        vus: None,
+        duration: None,
+        iterations: None,
+        rate: None,
+        stages: None,
+        max_vus: None,
+        profile_path: None,
+        search: None,
+        max_rate: None,
+        rate_step: None,
+        agents: None,
+        listen: "127.0.0.1:0",
+        timeout: None,
+        output_path: Some(output),
+        baseline: Default::default(),
+    }
+}
+
+fn read_summary(path: &Path) -> Value {
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn load_closed_model_reports_steps() {
+    // 全 VU で共有したイテレーション数だけ実行し、ステップごとに集計することを確認する
+    let base_url = start_stand_in().await;
+    let dir = tempfile::tempdir().unwrap();
+    let arazzo_path = write_project(dir.path());
+    let output = dir.path().join("summary.json");
+
+    execute_load(LoadCommandArgs {
+        vus: Some(4),
+        iterations: Some(20),
+        ..args(&arazzo_path, &base_url, &output)
+    })
+    .await
+    .unwrap();
+
+    let summary = read_summary(&output);
+    assert_eq!(summary["iterations"], json!(20));
+    assert_eq!(summary["requests"], json!(40));
+
+    let workflow = &summary["workflows"][0];
+    assert_eq!(workflow["workflowId"], json!("create-and-get"));
+    // 偶数の ID の取得は失敗する
+    assert_eq!(workflow["failed"], json!(10));
+
+    let steps = workflow["steps"].as_array().unwrap();
+    assert_eq!(steps[0]["stepId"], json!("create"));
+    assert_eq!(steps[0]["errors"], json!(0));
+    assert_eq!(steps[1]["stepId"], json!("get"));
+    assert_eq!(steps[1]["errorRate"], json!(0.5));
+
+    let latency = &steps[0]["latencyMs"];
+    for key in ["p50", "p90", "p95", "p99", "p99.9"] {
+        assert!(latency[key].as_f64().unwrap() > 0.0, "{key}");
+    }
+    assert!(latency["p50"].as_f64() <= latency["p99.9"].as_f64());
+    assert!(latency["p99.9"].as_f64() <= latency["max"].as_f64());
+}
+
+#[tokio::test]
+async fn load_open_model_keeps_arrival_rate() {
+    // 一定の到着レートでイテレーションを開始することを確認する
+    let base_url = start_stand_in().await;
+    let dir = tempfile::tempdir().unwrap();
+    let arazzo_path = write_project(dir.path());
+    let output = dir.path().join("summary.json");
+
+    execute_load(LoadCommandArgs {
+        rate: Some(40.0),
+        duration: Some("1s"),
+        ..args(&arazzo_path, &base_url, &output)
+    })
+    .await
+    .unwrap();
+
+    let summary = read_summary(&output);
+    let iterations = summary["iterations"].as_u64().unwrap();
+    assert!((36..=44).contains(&iterations), "{iterations} iterations");
+    assert_eq!(summary["droppedIterations"], json!(0));
+
+    // ステージで 0 から上げていく
+    execute_load(LoadCommandArgs {
+        stages: Some("1s:40"),
+        ..args(&arazzo_path, &base_url, &output)
+    })
+    .await
+    .unwrap();
+    let iterations = read_summary(&output)["iterations"].as_u64().unwrap();
+    assert!((16..=24).contains(&iterations), "{iterations} iterations");
+}
+
+#[tokio::test]
+async fn load_profile_mixes_workflows() {
+    // プロファイルの重みに従ってワークフローを選び、ワークフローごとに集計することを確認する
+    let base_url = start_stand_in().await;
+    let dir = tempfile::tempdir().unwrap();
+    let arazzo_path = write_project(dir.path());
+    let profile = dir.path().join("profile.yaml");
+    std::fs::write(&profile, PROFILE).unwrap();
+    let output = dir.path().join("summary.json");
+
+    execute_load(LoadCommandArgs {
+        vus: Some(4),
+        iterations: Some(400),
+        profile_path: Some(&profile),
+        ..args(&arazzo_path, &base_url, &output)
+    })
+    .await
+    .unwrap();
+
+    let summary = read_summary(&output);
+    assert_eq!(summary["iterations"], json!(400));
+    let workflows = summary["workflows"].as_array().unwrap();
+    let iterations = |id: &str| {
+        workflows
+            .iter()
+            .find(|w| w["workflowId"] == json!(id))
+            .map(|w| w["iterations"].as_u64().unwrap())
+            .unwrap_or(0)
+    };
+    let mixed = iterations("create-and-get");
+    assert!((260..=340).contains(&mixed), "{mixed} iterations");
+    assert_eq!(iterations("create-only"), 400 - mixed);
+
+    // 異常系: プロファイルにないワークフロー
+    std::fs::write(&profile, "workflows:\n  - workflowId: missing\n").unwrap();
+    let result = execute_load(LoadCommandArgs {
+        profile_path: Some(&profile),
+        ..args(&arazzo_path, &base_url, &output)
+    })
+    .await;
+    assert!(result.is_err());
+}
+
+const SLOW_OPENAPI: &str = r#"
+openapi: 3.0.3
+info:
+  title: Slow
+  version: 1.0.0
+paths:
+  /slow:
+    get:
+      operationId: getSlow
+      responses:
+        '200':
+          description: OK
+"#;
+
+const SLOW_ARAZZO: &str = r#"
+arazzo: 1.0.0
+info:
+  title: Slow
+  version: 1.0.0
+sourceDescriptions:
+  - name: slow
+    url: ./openapi.yaml
+    type: openapi
+workflows:
+  - workflowId: slow
+    steps:
+      - stepId: get
+        operationId: getSlow
+        successCriteria:
+          - context: $statusCode
+            condition: "=="
+            value: 200
+        x-slo:
+          latency:
+            p95: 100ms
+          errorRate: 0.01
+"#;
+
+#[tokio::test]
+async fn load_compares_against_baseline() {
+    // 保存したベースラインとステップごとに比べ、悪化したら失敗することを確認する
+    let base_url = start_stand_in().await;
+    let dir = tempfile::tempdir().unwrap();
+    let arazzo_path = write_project(dir.path());
+    let output = dir.path().join("summary.json");
+    let baseline = dir.path().join("baseline.json");
+    let diff = dir.path().join("diff.html");
+    let closed = |baseline| LoadCommandArgs {
+        vus: Some(2),
+        iterations: Some(10),
+        baseline,
+        ..args(&arazzo_path, &base_url, &output)
+    };
+
+    execute_load(closed(BaselineArgs {
+        save_path: Some(&baseline),
+        ..Default::default()
+    }))
+    .await
+    .unwrap();
+    let saved = read_summary(&baseline);
+    let steps = saved["steps"].as_array().unwrap();
+    assert_eq!(steps.len(), 2);
+    assert_eq!(steps[1]["step_id"], json!("get"));
+    assert_eq!(steps[1]["error_rate"], json!(0.5));
+
+    // エラー率は決まっているので、時間に左右される指標を外せば通る
+    let compare = BaselineArgs {
+        baseline_path: Some(&baseline),
+        tolerance: Some("p95=off,throughput=off"),
+        ..Default::default()
+    };
+    execute_load(closed(compare)).await.unwrap();
+
+    // 異常系: ベースラインではエラーがなかった
+    let mut edited = saved.clone();
+    edited["steps"][1]["error_rate"] = json!(0.0);
+    edited["error_rate"] = json!(0.0);
+    std::fs::write(&baseline, edited.to_string()).unwrap();
+    let result = execute_load(closed(BaselineArgs {
+        diff_path: Some(&diff),
+        ..compare
+    }))
+    .await;
+    let error = result.unwrap_err().to_string();
+    assert!(error.contains("create-and-get/get"), "{error}");
+    assert!(!error.contains("create-and-get/create"), "{error}");
+    let html = std::fs::read_to_string(&diff).unwrap();
+    assert!(html.contains("<h1>Baseline comparison: Regressed</h1>"));
+    assert!(html.contains("<td class=\"worse\">+50.00pt</td>"));
+}
+
+/// 1件ずつ 20ms かけて処理する（毎秒 50 件で飽和する）代替サーバー
+async fn start_throttled_stand_in() -> String {
+    let worker = std::sync::Arc::new(tokio::sync::Mutex::new(()));
+    let app = Router::new().route(
+        "/slow",
+        get(move || {
+            let worker = worker.clone();
+            async move {
+                let _busy = worker.lock().await;
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                StatusCode::OK
+            }
+        }),
+    );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn load_search_finds_capacity() {
+    // 飽和するレートの手前で止まり、レートごとの結果を報告することを確認する
+    let base_url = start_throttled_stand_in().await;
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("openapi.yaml"), SLOW_OPENAPI).unwrap();
+    let arazzo_path = dir.path().join("arazzo.yaml");
+    std::fs::write(&arazzo_path, SLOW_ARAZZO).unwrap();
+    let output = dir.path().join("capacity.json");
+
+    execute_load(LoadCommandArgs {
+        search: Some("step"),
+        rate: Some(20.0),
+        max_rate: Some(100.0),
+        rate_step: Some(20.0),
+        duration: Some("1s"),
+        ..args(&arazzo_path, &base_url, &output)
+    })
+    .await
+    .unwrap();
+
+    let report = read_summary(&output);
+    assert_eq!(report["maxPassingRate"], json!(40.0));
+    let probes = report["probes"].as_array().unwrap();
+    let rates: Vec<f64> = probes.iter().map(|p| p["rate"].as_f64().unwrap()).collect();
+    assert_eq!(rates, vec![20.0, 40.0, 60.0]);
+    let failed = &probes[2];
+    assert_eq!(failed["passed"], json!(false));
+    assert_eq!(failed["violations"][0]["stepId"], json!("get"));
+    assert_eq!(failed["violations"][0]["metric"], json!("p95"));
+    // レイテンシの推移はレートとともに増える
+    let p95 = |probe: &Value| probe["summary"]["latencyMs"]["p95"].as_f64().unwrap();
+    assert!(p95(&probes[0]) < p95(&probes[2]));
+
+    // 二分探索も同じ範囲に収まる
+    execute_load(LoadCommandArgs {
+        search: Some("binary"),
+        rate: Some(20.0),
+        max_rate: Some(100.0),
+        rate_step: Some(10.0),
+        duration: Some("1s"),
+        ..args(&arazzo_path, &base_url, &output)
+    })
+    .await
+    .unwrap();
+    let rate = read_summary(&output)["maxPassingRate"].as_f64().unwrap();
+    assert!((35.0..=60.0).contains(&rate), "{rate}/s");
+
+    // 異常系: SLO のないワークフロー
+    let arazzo_path = write_project(dir.path());
+    let result = execute_load(LoadCommandArgs {
+        search: Some("step"),
+        rate: Some(20.0),
+        max_rate: Some(100.0),
+        duration: Some("1s"),
+        ..args(&arazzo_path, &base_url, &output)
+    })
+    .await;
+    assert!(result.is_err());
+}
+
+/// 空いているポートのアドレス
+fn free_address() -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.local_addr().unwrap().to_string()
+}
+
+fn spawn_agent(coordinator: &str, name: &str) -> std::process::Child {
+    std::process::Command::new(env!("CARGO_BIN_EXE_hornet2"))
+        .args(["agent", "--coordinator", coordinator, "--name", name])
+        .stdout(std::process::Stdio::null())
+        .spawn()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn load_distributes_over_agents() {
+    // 2つの agent プロセスに負荷を分け、結果を1つにまとめることを確認する
+    let base_url = start_stand_in().await;
+    let dir = tempfile::tempdir().unwrap();
+    let arazzo_path = write_project(dir.path());
+    let output = dir.path().join("summary.json");
+    let listen = free_address();
+    let agents = [spawn_agent(&listen, "left"), spawn_agent(&listen, "right")];
+
+    execute_load(LoadCommandArgs {
+        vus: Some(4),
+        iterations: Some(40),
+        agents: Some(2),
+        listen: &listen,
+        ..args(&arazzo_path, &base_url, &output)
+    })
+    .await
+    .unwrap();
+    for mut agent in agents {
+        assert!(agent.wait().unwrap().success());
+    }
+
+    let summary = read_summary(&output);
+    assert_eq!(summary["iterations"], json!(40));
+    assert_eq!(summary["requests"], json!(80));
+    assert_eq!(summary["workflows"][0]["failed"], json!(20));
+    let agents = summary["agents"].as_array().unwrap();
+    assert_eq!(agents.len(), 2);
+    for agent in agents {
+        assert_eq!(agent["status"], json!("finished"));
+        assert_eq!(agent["iterations"], json!(20));
+    }
+}
+
+#[tokio::test]
+async fn load_survives_agent_loss() {
+    // 途中で落ちた agent の報告済みの結果を残して最後まで実行することを確認する
+    let base_url = start_stand_in().await;
+    let dir = tempfile::tempdir().unwrap();
+    let arazzo_path = write_project(dir.path());
+    let output = dir.path().join("summary.json");
+    let listen = free_address();
+    let mut steady = spawn_agent(&listen, "steady");
+    let mut doomed = spawn_agent(&listen, "doomed");
+    let killer = tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(2500)).await;
+        doomed.kill().unwrap();
+        doomed.wait().unwrap();
+    });
+
+    execute_load(LoadCommandArgs {
+        rate: Some(20.0),
+        duration: Some("4s"),
+        agents: Some(2),
+        listen: &listen,
+        ..args(&arazzo_path, &base_url, &output)
+    })
+    .await
+    .unwrap();
+    killer.await.unwrap();
+    assert!(steady.wait().unwrap().success());
+
+    let summary = read_summary(&output);
+    let agents = summary["agents"].as_array().unwrap();
+    let agent = |name: &str| agents.iter().find(|a| a["name"] == json!(name)).unwrap();
+    assert_eq!(agent("steady")["status"], json!("finished"));
+    assert_eq!(agent("doomed")["status"], json!("lost"));
+    assert_eq!(agent("doomed")["reason"], json!("disconnected"));
+    // 落ちるまでに送った差分は残る
+    let lost = agent("doomed")["iterations"].as_u64().unwrap();
+    assert!(lost > 0, "{lost} iterations");
+    let iterations = summary["iterations"].as_u64().unwrap();
+    assert!((40..80).contains(&iterations), "{iterations} iterations");
+}
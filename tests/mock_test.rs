@@ -0,0 +1,464 @@
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+    routing::any,
+};
+use hornet2::loader::OpenApiResolver;
+use hornet2::server::{mock, state::AppState};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tower::util::ServiceExt;
+
+const OPENAPI: &str = r#"
+openapi: 3.0.3
+info:
+  title: Pets
+  version: 1.0.0
+servers:
+  - url: http://localhost:8080/v1
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      parameters:
+        - name: limit
+          in: query
+          schema:
+            type: integer
+            maximum: 100
+      responses:
+        '200':
+          description: Pets
+          content:
+            application/json:
+              schema:
+                type: array
+                items:
+                  $ref: '#/components/schemas/Pet'
+    post:
+      operationId: createPet
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema:
+              $ref: '#/components/schemas/Pet'
+      responses:
+        '201':
+          description: Created
+          content:
+            application/json:
+              schema:
+                $ref: '#/components/schemas/Pet'
+              example:
+                id: 1
+                name: Rex
+  /pets/{petId}:
+    get:
+      operationId: getPet
+      parameters:
+        - name: petId
+          in: path
+          required: true
+          schema:
+            type: integer
+      responses:
+        '200':
+          description: Pet
+          content:
+            application/json:
+              schema:
+                $ref: '#/components/schemas/Pet'
+        '404':
+          description: Not found
+          content:
+            application/json:
+              schema:
+                type: object
+                properties:
+                  message:
+                    type: string
+                    example: pet not found
+    delete:
+      operationId: deletePet
+      parameters:
+        - name: petId
+          in: path
+          required: true
+          schema:
+            type: integer
+      responses:
+        '204':
+          description: Deleted
+        '404':
+          description: Not found
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [name]
+      properties:
+        id:
+          type: integer
+          readOnly: true
+        name:
+          type: string
+          minLength: 1
+        status:
+          type: string
+"#;
+
+fn resolver(dir: &std::path::Path) -> OpenApiResolver {
+    std::fs::write(dir.join("openapi.yaml"), OPENAPI).unwrap();
+    let mut resolver = OpenApiResolver::new(dir);
+    resolver
+        .load_spec("pets", &dir.join("openapi.yaml"))
+        .unwrap();
+    resolver
+}
+
+async fn send(app: &Router, request: Request<Body>) -> (StatusCode, Value) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    (status, body)
+}
+
+fn get(uri: &str) -> Request<Body> {
+    Request::builder().uri(uri).body(Body::empty()).unwrap()
+}
+
+#[tokio::test]
+async fn test_mock_serves_examples_and_synthesized_bodies() {
+    let temp_dir = TempDir::new().unwrap();
+    let app = mock::router(resolver(temp_dir.path()));
+
+    // 宣言された例を返す
+    let (status, body) = send(
+        &app,
+        Request::builder()
+            .method("POST")
+            .uri("/pets")
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"name": "Rex"}"#))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body, json!({"id": 1, "name": "Rex"}));
+
+    // 例がなければスキーマから合成する（テンプレートパス、サーバーのベースパスも照合）
+    let (status, body) = send(&app, get("/v1/pets/12")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["id"].is_i64());
+    assert!(body["name"].is_string());
+
+    let (status, body) = send(&app, get("/pets?limit=5")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.is_array());
+}
+
+#[tokio::test]
+async fn test_mock_validates_requests() {
+    let temp_dir = TempDir::new().unwrap();
+    let app = mock::router(resolver(temp_dir.path()));
+
+    let (status, body) = send(&app, get("/pets/abc")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        body["type"],
+        json!("https://hornet2.dev/problems/mock-request-invalid")
+    );
+    assert!(body["detail"].as_str().unwrap().contains("petId"));
+
+    let (status, _) = send(&app, get("/pets?limit=500")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = send(
+        &app,
+        Request::builder()
+            .method("POST")
+            .uri("/pets")
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"name": ""}"#))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["detail"].as_str().unwrap().contains("#/name"));
+
+    let (status, _) = send(
+        &app,
+        Request::builder()
+            .method("POST")
+            .uri("/pets")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // 未定義のパスとメソッド
+    let (status, _) = send(&app, get("/owners")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(
+        &app,
+        Request::builder()
+            .method("DELETE")
+            .uri("/pets")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+}
+
+#[tokio::test]
+async fn test_mock_prefer_code() {
+    let temp_dir = TempDir::new().unwrap();
+    let app = mock::router(resolver(temp_dir.path()));
+
+    let (status, body) = send(
+        &app,
+        Request::builder()
+            .uri("/pets/1")
+            .header("prefer", "code=404")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body, json!({"message": "pet not found"}));
+
+    // 宣言されていないステータス
+    let (status, body) = send(
+        &app,
+        Request::builder()
+            .uri("/pets/1")
+            .header("prefer", "code=500")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        body["type"],
+        json!("https://hornet2.dev/problems/mock-response-not-declared")
+    );
+}
+
+#[tokio::test]
+async fn test_mock_mounted_per_project() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_dir = temp_dir.path().join("project1");
+    std::fs::create_dir(&project_dir).unwrap();
+    std::fs::write(project_dir.join("openapi.yaml"), OPENAPI).unwrap();
+    std::fs::write(
+        project_dir.join("arazzo.yaml"),
+        r#"
+arazzo: 1.0.0
+info:
+  title: Pets
+  version: 1.0.0
+sourceDescriptions:
+  - name: pets
+    url: openapi.yaml
+    type: openapi
+workflows: []
+"#,
+    )
+    .unwrap();
+
+    let state = AppState {
+        root_dir: temp_dir.path().to_path_buf(),
+        projects: Arc::new(hornet2::server::state::ProjectCache::new()),
+        executions: Default::default(),
+        project_events: Default::default(),
+    };
+    let app = Router::new()
+        .route("/mock/{project_name}/{*path}", any(mock::project_mock))
+        .with_state(state);
+
+    let (status, body) = send(&app, get("/mock/project1/pets/3")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["name"].is_string());
+
+    let (status, _) = send(&app, get("/mock/unknown/pets/3")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+const WORKFLOW: &str = r#"
+arazzo: 1.0.0
+info:
+  title: Pets
+  version: 1.0.0
+sourceDescriptions:
+  - name: pets
+    url: ./openapi.yaml
+    type: openapi
+workflows:
+  - workflowId: pet-lifecycle
+    steps:
+      - stepId: create
+        operationId: createPet
+        requestBody:
+          contentType: application/json
+          payload:
+            name: Pochi
+        successCriteria:
+          - context: $statusCode
+            condition: "=="
+            value: 201
+        outputs:
+          id: $response.body#/id
+      - stepId: get
+        operationId: getPet
+        parameters:
+          - name: petId
+            in: path
+            value: $steps.create.outputs.id
+        successCriteria:
+          - context: $statusCode
+            condition: "=="
+            value: 200
+          - context: $response.body#/name
+            condition: "=="
+            value: Pochi
+          - context: $response.body#/status
+            condition: "=="
+            value: available
+      - stepId: delete
+        operationId: deletePet
+        parameters:
+          - name: petId
+            in: path
+            value: $steps.create.outputs.id
+        successCriteria:
+          - context: $statusCode
+            condition: "=="
+            value: 204
+      - stepId: get-deleted
+        operationId: getPet
+        parameters:
+          - name: petId
+            in: path
+            value: $steps.create.outputs.id
+        successCriteria:
+          - context: $statusCode
+            condition: "=="
+            value: 404
+"#;
+
+fn post_pet(name: &str) -> Request<Body> {
+    Request::builder()
+        .method("POST")
+        .uri("/pets")
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "name": name }).to_string()))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_stateful_mock_keeps_created_resources() {
+    let temp_dir = TempDir::new().unwrap();
+    let mock = mock::Mock::new(resolver(temp_dir.path())).stateful();
+    assert_eq!(mock.resources()[0].item, "/pets/{petId}");
+    let app = mock.into_router();
+
+    // 作成ごとに新しい ID を割り当て、リクエストの値を保持する
+    let (status, first) = send(&app, post_pet("Pochi")).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(first, json!({"id": 1, "name": "Pochi"}));
+    let (_, second) = send(&app, post_pet("Tama")).await;
+    assert_eq!(second["id"], json!(2));
+
+    let (status, body) = send(&app, get("/v1/pets/2")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, second);
+    let (_, body) = send(&app, get("/pets")).await;
+    assert_eq!(body, json!([first, second]));
+
+    let (status, _) = send(
+        &app,
+        Request::builder()
+            .method("DELETE")
+            .uri("/pets/1")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    // 削除済み、未作成のリソースは宣言された 404 を返す
+    let (status, body) = send(&app, get("/pets/1")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body, json!({"message": "pet not found"}));
+    let (status, _) = send(&app, get("/pets/42")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (_, body) = send(&app, get("/pets")).await;
+    assert_eq!(body, json!([second]));
+}
+
+#[tokio::test]
+async fn test_native_run_passes_against_stateful_mock() {
+    // 正常系: successCriteria から導いた値とストアで、ワークフローが最後まで成功することを確認する
+    let temp_dir = TempDir::new().unwrap();
+    let resolver = resolver(temp_dir.path());
+    let arazzo_path = temp_dir.path().join("arazzo.yaml");
+    std::fs::write(&arazzo_path, WORKFLOW).unwrap();
+    let spec = hornet2::loader::load_arazzo(&arazzo_path).unwrap();
+    let app = mock::Mock::new(resolver)
+        .with_workflows(&spec)
+        .stateful()
+        .into_router();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    hornet2::commands::execute_run(hornet2::commands::RunCommandArgs {
+        arazzo_path: &arazzo_path,
+        engine: "native",
+        workflow_id: Some("pet-lifecycle"),
+        base_url: Some(&base_url),
+        vus: None,
+        duration: None,
+        iterations: None,
+        profile_path: None,
+        inputs: None,
+        negative: false,
+        report_path: None,
+        record_path: None,
+        replay_path: None,
+        match_body: None,
+        redact: &[],
+        baseline: Default::default(),
+        history: false,
+        environment: None,
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_stateless_mock_follows_success_criteria() {
+    // ステートレスでもステータスとボディの条件は満たす（削除後の 404 は満たせない）
+    let temp_dir = TempDir::new().unwrap();
+    let resolver = resolver(temp_dir.path());
+    let arazzo_path = temp_dir.path().join("arazzo.yaml");
+    std::fs::write(&arazzo_path, WORKFLOW).unwrap();
+    let spec = hornet2::loader::load_arazzo(&arazzo_path).unwrap();
+    let app = mock::Mock::new(resolver)
+        .with_workflows(&spec)
+        .into_router();
+
+    let (status, body) = send(&app, get("/pets/7")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["name"], json!("Pochi"));
+    assert_eq!(body["status"], json!("available"));
+}
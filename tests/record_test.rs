@@ -0,0 +1,181 @@
+use axum::extract::Path as UrlPath;
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use hornet2::commands::RecordCommandArgs;
+use hornet2::commands::record::save_recording;
+use hornet2::loader::load_arazzo;
+use hornet2::server::proxy::{self, RecordingProxy};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+const OPENAPI: &str = r#"
+openapi: 3.0.3
+info:
+  title: Pets
+  version: 1.0.0
+paths:
+  /pets:
+    post:
+      operationId: createPet
+      requestBody:
+        content:
+          application/json:
+            schema:
+              type: object
+      responses:
+        '201':
+          description: Created
+  /pets/{petId}:
+    get:
+      operationId: getPet
+      parameters:
+        - name: petId
+          in: path
+          required: true
+          schema:
+            type: integer
+      responses:
+        '200':
+          description: Pet
+"#;
+
+async fn serve(app: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+/// 記録対象の代替サーバー
+async fn start_stand_in() -> String {
+    serve(
+        Router::new()
+            .route(
+                "/pets",
+                post(|Json(body): Json<Value>| async move {
+                    (
+                        StatusCode::CREATED,
+                        Json(json!({"id": 4242, "name": body["name"]})),
+                    )
+                }),
+            )
+            .route(
+                "/pets/{pet_id}",
+                get(|UrlPath(pet_id): UrlPath<u64>| async move {
+                    Json(json!({"id": pet_id, "name": "Rex"}))
+                }),
+            ),
+    )
+    .await
+}
+
+async fn click_through(client: &reqwest::Client, base_url: &str) {
+    let created = client
+        .post(format!("{base_url}/pets"))
+        .header("content-type", "application/json")
+        .body(r#"{"name": "Rex"}"#)
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    let created: Value = serde_json::from_str(&created).unwrap();
+    let response = client
+        .get(format!("{base_url}/pets/{}", created["id"]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    // OpenAPI にないリクエストは記録しても取り込まない
+    let response = client
+        .get(format!("{base_url}/favicon.ico"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+}
+
+fn assert_recorded_workflow(output: &std::path::Path) {
+    let spec = load_arazzo(output).unwrap();
+    let workflow = &spec.workflows[0];
+    assert_eq!(workflow.workflow_id, "checkout");
+    assert_eq!(workflow.steps.len(), 2);
+
+    let create = &workflow.steps[0];
+    assert_eq!(create.operation_id.as_deref(), Some("createPet"));
+    let criteria = create.success_criteria.as_ref().unwrap();
+    assert_eq!(criteria[0].context, "$statusCode");
+    assert_eq!(criteria[0].value, Some(json!(201)));
+
+    // 作成した ID はステップの出力として後続のリクエストに渡る
+    let get = &workflow.steps[1];
+    let value = get.parameters[0].value.as_str().unwrap();
+    assert!(value.starts_with("$steps.createPet.outputs."), "{value}");
+    assert!(create.outputs.is_some());
+}
+
+#[tokio::test]
+async fn record_as_reverse_proxy() {
+    let upstream = start_stand_in().await;
+    let recorder = Arc::new(RecordingProxy::new(Some(&upstream)).unwrap());
+    let proxy_url = serve(proxy::router(recorder.clone())).await;
+
+    let client = reqwest::Client::builder().no_proxy().build().unwrap();
+    click_through(&client, &proxy_url).await;
+
+    let exchanges = recorder.exchanges();
+    assert_eq!(exchanges.len(), 3);
+    assert_eq!(exchanges[0].method, "POST");
+    assert_eq!(exchanges[0].path, "/pets");
+
+    let dir = tempfile::tempdir().unwrap();
+    let openapi = dir.path().join("openapi.yaml");
+    std::fs::write(&openapi, OPENAPI).unwrap();
+    let output = dir.path().join("arazzo.yaml");
+    let openapi_paths = [openapi];
+    save_recording(
+        &RecordCommandArgs {
+            openapi_paths: &openapi_paths,
+            output_path: &output,
+            target: Some(&upstream),
+            port: 0,
+            name: "checkout",
+        },
+        exchanges,
+    )
+    .unwrap();
+    assert_recorded_workflow(&output);
+}
+
+#[tokio::test]
+async fn record_as_forward_proxy() {
+    let upstream = start_stand_in().await;
+    let recorder = Arc::new(RecordingProxy::new(None).unwrap());
+    let proxy_url = serve(proxy::router(recorder.clone())).await;
+
+    let client = reqwest::Client::builder()
+        .proxy(reqwest::Proxy::http(&proxy_url).unwrap())
+        .build()
+        .unwrap();
+    click_through(&client, &upstream).await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let openapi = dir.path().join("openapi.yaml");
+    std::fs::write(&openapi, OPENAPI).unwrap();
+    let output = dir.path().join("arazzo.yaml");
+    let openapi_paths = [openapi];
+    let args = RecordCommandArgs {
+        openapi_paths: &openapi_paths,
+        output_path: &output,
+        target: None,
+        port: 0,
+        name: "checkout",
+    };
+    save_recording(&args, recorder.exchanges()).unwrap();
+    assert_recorded_workflow(&output);
+
+    // 何も記録していなければ書き出さない
+    assert!(save_recording(&args, Vec::new()).is_err());
+}
@@ -0,0 +1,539 @@
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::{Json, Router};
+use hornet2::commands::{BaselineArgs, RunCommandArgs, execute_run};
+use serde_json::{Value, json};
+use std::path::Path;
+
+const OPENAPI: &str = r#"
+openapi: 3.0.3
+info:
+  title: Items
+  version: 1.0.0
+paths:
+  /items:
+    post:
+      operationId: createItem
+      parameters:
+        - name: mode
+          in: query
+          required: true
+          schema:
+            type: string
+            enum: [fast, safe]
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema:
+              type: object
+              required: [name]
+              properties:
+                name:
+                  type: string
+                  maxLength: 8
+                qty:
+                  type: integer
+                  maximum: 10
+      responses:
+        '201':
+          description: Created
+        '400':
+          description: Bad request
+"#;
+
+const ARAZZO: &str = r#"
+arazzo: 1.0.0
+info:
+  title: Items
+  version: 1.0.0
+sourceDescriptions:
+  - name: items
+    url: ./openapi.yaml
+    type: openapi
+workflows:
+  - workflowId: create-item
+    steps:
+      - stepId: create
+        operationId: createItem
+        parameters:
+          - name: mode
+            in: query
+            value: fast
+        requestBody:
+          contentType: application/json
+          payload:
+            name: box
+            qty: 1
+        successCriteria:
+          - context: $statusCode
+            condition: "=="
+            value: 201
+  - workflowId: create-named
+    inputs:
+      type: object
+      required: [name]
+      properties:
+        name:
+          type: string
+    steps:
+      - stepId: create
+        operationId: createItem
+        parameters:
+          - name: mode
+            in: query
+            value: fast
+        requestBody:
+          contentType: application/json
+          payload:
+            name: $inputs.name
+        successCriteria:
+          - context: $statusCode
+            condition: "=="
+            value: 201
+"#;
+
+/// リクエストを検証する代替サーバー（qty の上限だけ検証し忘れている）
+async fn start_stand_in() -> String {
+    let app = Router::new().route(
+        "/items",
+        post(
+            |query: axum::extract::RawQuery, body: Option<Json<Value>>| async move {
+                let mode_ok = matches!(query.0.as_deref(), Some("mode=fast") | Some("mode=safe"));
+                let valid = body.is_some_and(|Json(body)| {
+                    body["name"].as_str().is_some_and(|n| n.len() <= 8)
+                        && (body.get("qty").is_none() || body["qty"].is_i64())
+                });
+                if mode_ok && valid {
+                    StatusCode::CREATED
+                } else {
+                    StatusCode::BAD_REQUEST
+                }
+            },
+        ),
+    );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+fn write_project(dir: &Path) -> std::path::PathBuf {
+    std::fs::write(dir.join("openapi.yaml"), OPENAPI).unwrap();
+    let arazzo = dir.join("arazzo.yaml");
+    std::fs::write(&arazzo, ARAZZO).unwrap();
+    arazzo
+}
+
+#[tokio::test]
+async fn native_run_reports_negative_cases() {
+    // 異常系: 検証漏れ（qty の上限）の否定テストだけが失敗し、レポートに個別のケースとして残ることを確認する
+    let base_url = start_stand_in().await;
+    let dir = tempfile::tempdir().unwrap();
+    let arazzo_path = write_project(dir.path());
+    let report_path = dir.path().join("report.json");
+
+    let result = execute_run(RunCommandArgs {
+        arazzo_path: &arazzo_path,
+        engine: "native",
+        workflow_id: None,
+        base_url: Some(&base_url),
+        vus: None,
+        duration: None,
+        iterations: None,
+        profile_path: None,
+        inputs: None,
+        negative: true,
+        report_path: Some(&report_path),
+        record_path: None,
+        replay_path: None,
+        match_body: None,
+        redact: &[],
+        baseline: Default::default(),
+        history: false,
+        environment: None,
+    })
+    .await;
+    assert!(result.is_err());
+
+    let report: Value =
+        serde_json::from_str(&std::fs::read_to_string(&report_path).unwrap()).unwrap();
+    let cases = report["cases"].as_array().unwrap();
+    assert_eq!(cases[0]["name"], json!("create"));
+    assert_eq!(cases[0]["status"], json!("passed"));
+
+    let failed: Vec<&str> = cases
+        .iter()
+        .filter(|c| c["status"] == json!("failed"))
+        .map(|c| c["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        failed,
+        vec!["create [negative] body property '/qty': above maximum (10)"]
+    );
+    assert!(cases.iter().any(|c| c["name"]
+        == json!("create [negative] missing required query parameter 'mode'")
+        && c["status"] == json!("passed")));
+    assert!(cases.len() > 5);
+}
+
+#[tokio::test]
+async fn native_run_writes_junit() {
+    // 正常系: 否定テストなしでは成功し、.xml なら JUnit 形式で書き出すことを確認する
+    let base_url = start_stand_in().await;
+    let dir = tempfile::tempdir().unwrap();
+    let arazzo_path = write_project(dir.path());
+    let report_path = dir.path().join("report.xml");
+
+    execute_run(RunCommandArgs {
+        arazzo_path: &arazzo_path,
+        engine: "native",
+        workflow_id: Some("create-item"),
+        base_url: Some(&base_url),
+        vus: None,
+        duration: None,
+        iterations: None,
+        profile_path: None,
+        inputs: None,
+        negative: false,
+        report_path: Some(&report_path),
+        record_path: None,
+        replay_path: None,
+        match_body: None,
+        redact: &[],
+        baseline: Default::default(),
+        history: false,
+        environment: None,
+    })
+    .await
+    .unwrap();
+
+    let xml = std::fs::read_to_string(&report_path).unwrap();
+    assert!(xml.contains("<testsuite name=\"create-item\" tests=\"1\" failures=\"0\""));
+}
+
+#[tokio::test]
+async fn native_run_records_and_replays_cassette() {
+    // 正常系: 記録したカセットから、サーバーなしで同じワークフローを再実行できることを確認する
+    let base_url = start_stand_in().await;
+    let dir = tempfile::tempdir().unwrap();
+    let arazzo_path = write_project(dir.path());
+    let cassette_path = dir.path().join("cassette.yaml");
+
+    let run = |base_url: String, record: bool, match_body: &'static str| {
+        let arazzo_path = arazzo_path.clone();
+        let cassette_path = cassette_path.clone();
+        async move {
+            execute_run(RunCommandArgs {
+                arazzo_path: &arazzo_path,
+                engine: "native",
+                workflow_id: None,
+                base_url: Some(&base_url),
+                vus: None,
+                duration: None,
+                iterations: None,
+                profile_path: None,
+                inputs: None,
+                negative: false,
+                report_path: None,
+                record_path: record.then_some(cassette_path.as_path()),
+                replay_path: (!record).then_some(cassette_path.as_path()),
+                match_body: (!record).then_some(match_body),
+                redact: &[],
+                baseline: Default::default(),
+                history: false,
+                environment: None,
+            })
+            .await
+        }
+    };
+
+    run(base_url, true, "none").await.unwrap();
+    let cassette: Value =
+        serde_yaml::from_str(&std::fs::read_to_string(&cassette_path).unwrap()).unwrap();
+    let interactions = cassette["interactions"].as_array().unwrap();
+    assert_eq!(interactions.len(), 1);
+    assert_eq!(interactions[0]["request"]["path"], json!("/items"));
+    assert_eq!(interactions[0]["response"]["status"], json!(201));
+
+    // 接続できないアドレスでも再生なら成功する
+    let offline = "http://127.0.0.1:9".to_string();
+    run(offline.clone(), false, "exact").await.unwrap();
+    run(offline.clone(), false, "/name,/qty").await.unwrap();
+
+    // ボディが一致しなければ再生できない
+    let yaml = std::fs::read_to_string(&cassette_path)
+        .unwrap()
+        .replace("name: box", "name: crate");
+    std::fs::write(&cassette_path, yaml).unwrap();
+    assert!(run(offline.clone(), false, "/name").await.is_err());
+    run(offline, false, "none").await.unwrap();
+}
+
+#[tokio::test]
+async fn native_run_compares_against_baseline() {
+    // 異常系: ベースラインより p95 が悪化したステップがあれば失敗し、JSON の差分を書き出すことを確認する
+    let base_url = start_stand_in().await;
+    let dir = tempfile::tempdir().unwrap();
+    let arazzo_path = write_project(dir.path());
+    let baseline = dir.path().join("baseline.json");
+    let diff = dir.path().join("diff.json");
+    let run = |baseline| RunCommandArgs {
+        arazzo_path: &arazzo_path,
+        engine: "native",
+        workflow_id: Some("create-item"),
+        base_url: Some(&base_url),
+        vus: None,
+        duration: None,
+        iterations: None,
+        profile_path: None,
+        inputs: None,
+        negative: false,
+        report_path: None,
+        record_path: None,
+        replay_path: None,
+        match_body: None,
+        redact: &[],
+        baseline,
+        history: false,
+        environment: None,
+    };
+
+    execute_run(run(BaselineArgs {
+        save_path: Some(&baseline),
+        ..Default::default()
+    }))
+    .await
+    .unwrap();
+    let mut saved: Value =
+        serde_json::from_str(&std::fs::read_to_string(&baseline).unwrap()).unwrap();
+    assert_eq!(saved["steps"][0]["step_id"], json!("create"));
+    assert_eq!(saved["vus"], json!(1));
+
+    // ベースラインの p95 を極端に速くしておく
+    saved["steps"][0]["latency"]["p95"] = json!(0.001);
+    std::fs::write(&baseline, saved.to_string()).unwrap();
+    let result = execute_run(run(BaselineArgs {
+        baseline_path: Some(&baseline),
+        tolerance: Some("throughput=off"),
+        diff_path: Some(&diff),
+        ..Default::default()
+    }))
+    .await;
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("create-item/create")
+    );
+
+    let diff: Value = serde_json::from_str(&std::fs::read_to_string(&diff).unwrap()).unwrap();
+    let row = &diff["rows"][0];
+    assert_eq!(row["stepId"], json!("create"));
+    assert_eq!(row["status"], json!("regressed"));
+    assert_eq!(row["p95Ms"]["regressed"], json!(true));
+    assert_eq!(row["errorRate"]["regressed"], json!(false));
+
+    // 1回だけの run ではスループットを比べないので、req/s が大きく落ちても失敗しない
+    saved["steps"][0]["latency"]["p95"] = json!(60_000.0);
+    saved["latency"]["p95"] = json!(60_000.0);
+    saved["steps"][0]["rps"] = json!(1e9);
+    saved["rps"] = json!(1e9);
+    std::fs::write(&baseline, saved.to_string()).unwrap();
+    execute_run(run(BaselineArgs {
+        baseline_path: Some(&baseline),
+        ..Default::default()
+    }))
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn native_run_adds_to_history() {
+    // 正常系: --history で実行がプロジェクトの .hornet/runs に残ることを確認する
+    let base_url = start_stand_in().await;
+    let dir = tempfile::tempdir().unwrap();
+    let arazzo_path = write_project(dir.path());
+
+    execute_run(RunCommandArgs {
+        arazzo_path: &arazzo_path,
+        engine: "native",
+        workflow_id: Some("create-item"),
+        base_url: Some(&base_url),
+        vus: None,
+        duration: None,
+        iterations: None,
+        profile_path: None,
+        inputs: None,
+        negative: false,
+        report_path: None,
+        record_path: None,
+        replay_path: None,
+        match_body: None,
+        redact: &[],
+        baseline: Default::default(),
+        history: true,
+        environment: Some("local"),
+    })
+    .await
+    .unwrap();
+
+    let runs = hornet2::runner::RunHistory::new(dir.path())
+        .list(None)
+        .unwrap();
+    assert_eq!(runs.len(), 1);
+    let run = &runs[0];
+    assert_eq!(run.workflow_id, "create-item");
+    assert_eq!(run.environment.as_deref(), Some("local"));
+    assert_eq!(run.base_url.as_deref(), Some(base_url.as_str()));
+    assert!(run.success);
+    assert_eq!(run.steps[0].step_id, "create");
+    assert_eq!(run.steps[0].status_code, Some(201));
+    assert_eq!(run.metrics.http_reqs, 1);
+}
+
+#[tokio::test]
+async fn run_checks_profile() {
+    // 異常系: --profile は k6 の起動前に検証され、native エンジンでは使えないことを確認する
+    let dir = tempfile::tempdir().unwrap();
+    let arazzo_path = write_project(dir.path());
+    let profile_path = dir.path().join("profile.yaml");
+    let run = |engine| RunCommandArgs {
+        arazzo_path: &arazzo_path,
+        engine,
+        workflow_id: None,
+        base_url: Some("http://127.0.0.1:9"),
+        vus: None,
+        duration: None,
+        iterations: None,
+        profile_path: Some(&profile_path),
+        inputs: None,
+        negative: false,
+        report_path: None,
+        record_path: None,
+        replay_path: None,
+        match_body: None,
+        redact: &[],
+        baseline: Default::default(),
+        history: false,
+        environment: None,
+    };
+
+    std::fs::write(
+        &profile_path,
+        "workflows:\n  - workflowId: browse\n    weight: 1\n",
+    )
+    .unwrap();
+    let message = execute_run(run("k6")).await.unwrap_err().to_string();
+    assert!(
+        message.contains("unknown workflow 'browse'"),
+        "unexpected message: {message}"
+    );
+
+    std::fs::write(
+        &profile_path,
+        "workflows:\n  - workflowId: create-item\n    weight: 1\n",
+    )
+    .unwrap();
+    let message = execute_run(run("native")).await.unwrap_err().to_string();
+    assert!(
+        message.contains("--profile requires --engine k6"),
+        "unexpected message: {message}"
+    );
+}
+
+#[tokio::test]
+async fn native_run_passes_inputs() {
+    // 正常系: --inputs の値（JSON と @ファイル）がワークフローの入力として使われることを確認する
+    let base_url = start_stand_in().await;
+    let dir = tempfile::tempdir().unwrap();
+    let arazzo_path = write_project(dir.path());
+    let inputs_path = dir.path().join("inputs.yaml");
+    std::fs::write(&inputs_path, "name: crate\n").unwrap();
+    let inputs_file = format!("@{}", inputs_path.display());
+    let run = |inputs| RunCommandArgs {
+        arazzo_path: &arazzo_path,
+        engine: "native",
+        workflow_id: Some("create-named"),
+        base_url: Some(&base_url),
+        vus: None,
+        duration: None,
+        iterations: None,
+        profile_path: None,
+        inputs,
+        negative: false,
+        report_path: None,
+        record_path: None,
+        replay_path: None,
+        match_body: None,
+        redact: &[],
+        baseline: Default::default(),
+        history: false,
+        environment: None,
+    };
+
+    execute_run(run(Some(r#"{"name":"box"}"#))).await.unwrap();
+    execute_run(run(Some(&inputs_file))).await.unwrap();
+
+    // 異常系: 値がサーバーに届くので、長すぎる名前は 400 で失敗する
+    assert!(
+        execute_run(run(Some(r#"{"name":"far-too-long"}"#)))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn native_run_checks_inputs() {
+    // 異常系: 既定値のない必須入力の欠落と、オブジェクト以外の --inputs は実行前に拒否されることを確認する
+    let dir = tempfile::tempdir().unwrap();
+    let arazzo_path = write_project(dir.path());
+    let run = |engine, inputs| RunCommandArgs {
+        arazzo_path: &arazzo_path,
+        engine,
+        workflow_id: Some("create-named"),
+        base_url: Some("http://127.0.0.1:9"),
+        vus: None,
+        duration: None,
+        iterations: None,
+        profile_path: None,
+        inputs,
+        negative: false,
+        report_path: None,
+        record_path: None,
+        replay_path: None,
+        match_body: None,
+        redact: &[],
+        baseline: Default::default(),
+        history: false,
+        environment: None,
+    };
+
+    let message = execute_run(run("native", None))
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(
+        message.contains("requires input(s) name"),
+        "unexpected message: {message}"
+    );
+
+    let message = execute_run(run("native", Some("[1, 2]")))
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(
+        message.contains("--inputs must be a JSON object"),
+        "unexpected message: {message}"
+    );
+
+    let message = execute_run(run("k6", Some("{}")))
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(
+        message.contains("require --engine native"),
+        "unexpected message: {message}"
+    );
+}
@@ -0,0 +1,119 @@
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+    routing::get,
+};
+use futures::StreamExt;
+use hornet2::server::{api, state::AppState, watcher};
+use serde_json::{Value, json};
+use std::time::Duration;
+use tempfile::TempDir;
+use tower::util::ServiceExt;
+
+const OPENAPI: &str = r#"
+openapi: 3.0.0
+info:
+  title: Shop API
+  version: 1.0.0
+paths:
+  /cart: {}
+"#;
+
+fn arazzo(workflows: &[&str]) -> String {
+    let mut arazzo = String::from(
+        "arazzo: 1.0.0\ninfo:\n  title: Shop\n  version: 1.0.0\nsourceDescriptions:\n  - name: shop\n    url: openapi.yaml\n    type: openapi\nworkflows:\n",
+    );
+    for id in workflows {
+        arazzo.push_str(&format!("  - workflowId: {}\n    steps: []\n", id));
+    }
+    arazzo
+}
+
+async fn get_json(app: &Router, uri: &str) -> Value {
+    let response = app
+        .clone()
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+/// SSE の本文から次のイベントのデータを読む（キープアライブは飛ばす）
+async fn next_event(body: &mut axum::body::BodyDataStream) -> Value {
+    loop {
+        let chunk = tokio::time::timeout(Duration::from_secs(10), body.next())
+            .await
+            .expect("no event within 10s")
+            .unwrap()
+            .unwrap();
+        let text = String::from_utf8(chunk.to_vec()).unwrap();
+        if let Some(data) = text.lines().find_map(|line| line.strip_prefix("data: ")) {
+            return serde_json::from_str(data).unwrap();
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_project_events() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_dir = temp_dir.path().join("shop");
+    std::fs::create_dir(&project_dir).unwrap();
+    std::fs::write(project_dir.join("openapi.yaml"), OPENAPI).unwrap();
+    std::fs::write(project_dir.join("arazzo.yaml"), arazzo(&["checkout"])).unwrap();
+
+    let state = AppState::new(temp_dir.path().to_path_buf()).unwrap();
+    // Replicating routes from src/server/mod.rs
+    let app = Router::new()
+        .route("/api/projects", get(api::list_projects))
+        .route("/api/events", get(watcher::stream_project_events))
+        .with_state(state.clone());
+    // ポーリングに頼らず、ファイルシステムのイベントで反映されることを確かめる
+    watcher::spawn(state, Duration::from_secs(3600));
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/events")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut events = response.into_body().into_data_stream();
+    // 監視が最初の状態を覚えるのを待つ
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // 1. IDE での編集がすぐに反映される
+    std::fs::write(
+        project_dir.join("arazzo.yaml"),
+        arazzo(&["checkout", "refund"]),
+    )
+    .unwrap();
+    let event = next_event(&mut events).await;
+    assert_eq!(event, json!({"type": "project-changed", "project": "shop"}));
+    let body = get_json(&app, "/api/projects").await;
+    assert_eq!(body["projects"][0]["workflow_count"], json!(2));
+
+    // 異常系: 壊れた YAML は報告され、前の版が返り続ける
+    std::fs::write(project_dir.join("arazzo.yaml"), "workflows: [").unwrap();
+    let event = next_event(&mut events).await;
+    assert_eq!(event["type"], json!("project-error"));
+    assert_eq!(event["keptPrevious"], json!(true));
+    let body = get_json(&app, "/api/projects").await;
+    assert_eq!(body["projects"][0]["workflow_count"], json!(2));
+    assert!(body["projects"][0]["load_error"].is_string());
+
+    // 2. 新しいプロジェクト
+    let blog_dir = temp_dir.path().join("blog");
+    std::fs::create_dir(&blog_dir).unwrap();
+    std::fs::write(blog_dir.join("openapi.yaml"), OPENAPI).unwrap();
+    std::fs::write(blog_dir.join("arazzo.yaml"), arazzo(&["post"])).unwrap();
+    let event = next_event(&mut events).await;
+    assert_eq!(event, json!({"type": "project-added", "project": "blog"}));
+}